@@ -27,6 +27,7 @@ use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::common::exec_err;
 use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::CatalogSnapshot;
 use datafusion::prelude::SessionContext;
 use std::fs::File;
 use std::io::BufReader;
@@ -43,6 +44,8 @@ pub enum Command {
     DescribeTableStmt(String),
     ListFunctions,
     Include(Option<String>),
+    SaveCatalog(Option<String>),
+    LoadCatalog(Option<String>),
     SearchFunctions(String),
     QuietMode(Option<bool>),
     OutputFormat(Option<String>),
@@ -85,6 +88,35 @@ impl Command {
                     exec_err!("Required filename argument is missing")
                 }
             }
+            Self::SaveCatalog(filename) => {
+                let Some(filename) = filename else {
+                    return exec_err!("Required filename argument is missing");
+                };
+                let snapshot = ctx.export_catalog().await?;
+                std::fs::write(filename, snapshot.to_string()).map_err(|e| {
+                    DataFusionError::Execution(format!(
+                        "Error writing {:?} {}",
+                        filename, e
+                    ))
+                })?;
+                for table in &snapshot.skipped {
+                    println!("Table {table} has no definition and was not saved");
+                }
+                Ok(())
+            }
+            Self::LoadCatalog(filename) => {
+                let Some(filename) = filename else {
+                    return exec_err!("Required filename argument is missing");
+                };
+                let snapshot = std::fs::read_to_string(filename).map_err(|e| {
+                    DataFusionError::Execution(format!(
+                        "Error opening {:?} {}",
+                        filename, e
+                    ))
+                })?;
+                ctx.import_catalog(&snapshot.parse::<CatalogSnapshot>()?)
+                    .await
+            }
             Self::QuietMode(quiet) => {
                 if let Some(quiet) = quiet {
                     print_options.quiet = *quiet;
@@ -126,6 +158,14 @@ impl Command {
             Self::Include(_) => {
                 ("\\i filename", "reads input from the specified filename")
             }
+            Self::SaveCatalog(_) => (
+                "\\save filename",
+                "saves the tables, views and settings\nof the session to the specified filename",
+            ),
+            Self::LoadCatalog(_) => (
+                "\\load filename",
+                "restores the tables, views and settings\nsaved to the specified filename",
+            ),
             Self::ListFunctions => ("\\h", "function list"),
             Self::SearchFunctions(_) => ("\\h function", "search function"),
             Self::QuietMode(_) => ("\\quiet (true|false)?", "print or set quiet mode"),
@@ -136,12 +176,14 @@ impl Command {
     }
 }
 
-const ALL_COMMANDS: [Command; 9] = [
+const ALL_COMMANDS: [Command; 11] = [
     Command::ListTables,
     Command::DescribeTableStmt(String::new()),
     Command::Quit,
     Command::Help,
     Command::Include(Some(String::new())),
+    Command::SaveCatalog(Some(String::new())),
+    Command::LoadCatalog(Some(String::new())),
     Command::ListFunctions,
     Command::SearchFunctions(String::new()),
     Command::QuietMode(None),
@@ -185,6 +227,10 @@ impl FromStr for Command {
             ("h", Some(function)) => Self::SearchFunctions(function.into()),
             ("i", None) => Self::Include(None),
             ("i", Some(filename)) => Self::Include(Some(filename.to_owned())),
+            ("save", None) => Self::SaveCatalog(None),
+            ("save", Some(filename)) => Self::SaveCatalog(Some(filename.to_owned())),
+            ("load", None) => Self::LoadCatalog(None),
+            ("load", Some(filename)) => Self::LoadCatalog(Some(filename.to_owned())),
             ("quiet", Some("true" | "t" | "yes" | "y" | "on")) => {
                 Self::QuietMode(Some(true))
             }
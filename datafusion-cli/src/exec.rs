@@ -219,6 +219,10 @@ async fn exec_and_print(
     for statement in statements {
         let mut plan = ctx.state().statement_to_plan(statement).await?;
 
+        // Render numbers with the options set by the previous statements
+        let mut print_options = print_options.clone();
+        print_options.numeric_format = ctx.state().config_options().format.clone();
+
         // For plans like `Explain` ignore `MaxRows` option and always display all rows
         let should_ignore_maxrows = matches!(
             plan,
@@ -235,13 +239,17 @@ async fn exec_and_print(
         }
 
         let df = ctx.execute_logical_plan(plan).await?;
-        let physical_plan = df.create_physical_plan().await?;
+        let (physical_plan, warnings) = df.create_physical_plan_with_warnings().await?;
+        if !print_options.quiet {
+            for warning in warnings {
+                eprintln!("Warning: {warning}");
+            }
+        }
 
         if is_plan_streaming(&physical_plan)? {
             let stream = execute_stream(physical_plan, task_ctx.clone())?;
             print_options.print_stream(stream, now).await?;
         } else {
-            let mut print_options = print_options.clone();
             if should_ignore_maxrows {
                 print_options.maxrows = MaxRows::Unlimited;
             }
@@ -23,7 +23,9 @@ use std::sync::{Arc, OnceLock};
 
 use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::SessionConfig;
-use datafusion::execution::memory_pool::{FairSpillPool, GreedyMemoryPool};
+use datafusion::execution::memory_pool::{
+    FairSpillPool, GreedyMemoryPool, SpillLargestPool,
+};
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use datafusion::prelude::SessionContext;
 use datafusion_cli::catalog::DynamicFileCatalog;
@@ -45,6 +47,7 @@ static GLOBAL: MiMalloc = MiMalloc;
 enum PoolType {
     Greedy,
     Fair,
+    SpillLargest,
 }
 
 impl FromStr for PoolType {
@@ -54,6 +57,7 @@ impl FromStr for PoolType {
         match s {
             "Greedy" | "greedy" => Ok(PoolType::Greedy),
             "Fair" | "fair" => Ok(PoolType::Fair),
+            "SpillLargest" | "spill-largest" => Ok(PoolType::SpillLargest),
             _ => Err(format!("Invalid memory pool type '{}'", s)),
         }
     }
@@ -125,7 +129,7 @@ struct Args {
 
     #[clap(
         long,
-        help = "Specify the memory pool type 'greedy' or 'fair', default to 'greedy'"
+        help = "Specify the memory pool type 'greedy', 'fair' or 'spill-largest', default to 'greedy'"
     )]
     mem_pool_type: Option<PoolType>,
 
@@ -169,6 +173,8 @@ pub async fn main() -> Result<()> {
                         .with_memory_pool(Arc::new(GreedyMemoryPool::new(memory_limit))),
                     PoolType::Fair => rn_config
                         .with_memory_pool(Arc::new(FairSpillPool::new(memory_limit))),
+                    PoolType::SpillLargest => rn_config
+                        .with_memory_pool(Arc::new(SpillLargestPool::new(memory_limit))),
                 }
             } else {
                 rn_config
@@ -195,6 +201,7 @@ pub async fn main() -> Result<()> {
         format: args.format,
         quiet: args.quiet,
         maxrows: args.maxrows,
+        numeric_format: ctx.state().config_options().format.clone(),
     };
 
     let commands = args.command;
@@ -24,6 +24,8 @@ use std::time::Instant;
 use crate::print_format::PrintFormat;
 
 use arrow::record_batch::RecordBatch;
+use datafusion::common::config::NumericFormatOptions;
+use datafusion::common::format::format_numeric_columns;
 use datafusion::common::DataFusionError;
 use datafusion::error::Result;
 use datafusion::physical_plan::RecordBatchStream;
@@ -70,6 +72,8 @@ pub struct PrintOptions {
     pub format: PrintFormat,
     pub quiet: bool,
     pub maxrows: MaxRows,
+    /// How numbers are rendered, from the `datafusion.format` options
+    pub numeric_format: NumericFormatOptions,
 }
 
 fn get_timing_info_str(
@@ -102,8 +106,12 @@ impl PrintOptions {
         let stdout = std::io::stdout();
         let mut writer = stdout.lock();
 
+        let batches = batches
+            .iter()
+            .map(|batch| format_numeric_columns(batch, &self.numeric_format))
+            .collect::<Result<Vec<_>>>()?;
         self.format
-            .print_batches(&mut writer, batches, self.maxrows, true)?;
+            .print_batches(&mut writer, &batches, self.maxrows, true)?;
 
         let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
         let timing_info = get_timing_info_str(
@@ -142,7 +150,7 @@ impl PrintOptions {
         let mut with_header = true;
 
         while let Some(maybe_batch) = stream.next().await {
-            let batch = maybe_batch?;
+            let batch = format_numeric_columns(&maybe_batch?, &self.numeric_format)?;
             row_count += batch.num_rows();
             self.format.print_batches(
                 &mut writer,
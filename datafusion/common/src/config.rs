@@ -17,7 +17,9 @@
 
 //! Runtime configuration, via [`ConfigOptions`]
 use crate::error::_internal_err;
+use crate::utils::estimate_row_width;
 use crate::{DataFusionError, Result};
+use arrow::datatypes::Schema;
 use std::any::Any;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
@@ -163,6 +165,11 @@ config_namespace! {
         /// The default schema name - this impacts what SQL queries use if not specified
         pub default_schema: String, default = "public".to_string()
 
+        /// Comma separated list of schemas searched, in order, for the tables
+        /// referenced without schema, before the default schema. Each schema is
+        /// either `schema`, in the default catalog, or `catalog.schema`
+        pub search_path: Option<String>, default = None
+
         /// Should DataFusion provide access to `information_schema`
         /// virtual tables for displaying schema information
         pub information_schema: bool, default = false
@@ -220,7 +227,11 @@ config_namespace! {
         /// The default time zone
         ///
         /// Some functions, e.g. `EXTRACT(HOUR from SOME_TIME)`, shift the underlying datetime
-        /// according to this time zone, and then extract the hour
+        /// according to this time zone, and then extract the hour. `current_date()` and
+        /// `current_time()` return the date and time in this time zone, `date_trunc`
+        /// truncates timestamps in UTC, such as the result of `now()`, in this time zone,
+        /// and strings without time zone offset cast to timestamps in UTC are interpreted
+        /// in this time zone
         pub time_zone: Option<String>, default = Some("+00:00".into())
 
         /// Parquet options
@@ -252,6 +263,17 @@ config_namespace! {
         /// batches and merged.
         pub sort_in_place_threshold_bytes: usize, default = 1024 * 1024
 
+        /// The maximum number of sorted runs that a sort merges at once after
+        /// spilling. When a sort spilled more runs, they are first merged into
+        /// fewer, longer runs on disk, in as many passes as needed, so that the
+        /// number of open spill files and their read buffers stay bounded.
+        /// Values below 2 are treated as 2.
+        pub sort_spill_merge_fan_in: usize, default = 64
+
+        /// The number of batches read ahead from each spilled run while a sort
+        /// merges its runs.
+        pub sort_spill_read_buffer_batches: usize, default = 2
+
         /// Number of files to read in parallel when inferring schema and statistics
         pub meta_fetch_concurrency: usize, default = 32
 
@@ -278,6 +300,50 @@ config_namespace! {
         /// Hive. Note that this setting does not affect reading partitioned
         /// tables (e.g. `/table/year=2021/month=01/data.parquet`).
         pub listing_table_ignore_subdirectory: bool, default = true
+
+        /// When set to true, all NaN values are equal to each other and -0.0
+        /// is equal to +0.0 when grouping, joining and computing DISTINCT on
+        /// floating point keys. Set to false to compare the keys bitwise,
+        /// as in previous versions
+        pub normalize_float_keys: bool, default = true
+
+        /// Maximum time in milliseconds a query may run. Queries running longer
+        /// are stopped with a cancellation error. When not set, queries are not
+        /// timed out
+        pub query_timeout: Option<usize>, default = None
+
+        /// Maximum number of times the recursive term of a recursive CTE
+        /// (`WITH RECURSIVE`) is evaluated. Queries needing more iterations
+        /// fail, rather than running forever on cyclic data
+        pub max_recursive_iterations: usize, default = 1000
+
+        /// When set to true, projections and filters record the time spent
+        /// evaluating each of their expressions, and `EXPLAIN ANALYZE` lists
+        /// the most expensive ones. Timing each expression adds some overhead
+        pub expression_metrics: bool, default = false
+
+        /// When set to true, each operator opens a `tracing` span for every
+        /// partition it executes, recording the rows and bytes it produced.
+        /// Requires DataFusion to be built with the `tracing` feature
+        pub tracing_spans: bool, default = false
+
+        /// Target size in bytes of the batches read by scans and coalesced by
+        /// `CoalesceBatchesExec`. When set, their number of rows is computed
+        /// from the estimated width of a row of their schema instead of using
+        /// `batch_size`, so that wide rows get smaller batches and narrow rows
+        /// larger ones
+        pub target_batch_bytes: Option<usize>, default = None
+    }
+}
+
+impl ExecutionOptions {
+    /// Returns the number of rows of the batches of `schema`: as many rows
+    /// as fit in `target_batch_bytes` if set, and `batch_size` otherwise
+    pub fn batch_size_for_schema(&self, schema: &Schema) -> usize {
+        match self.target_batch_bytes {
+            Some(bytes) => (bytes / estimate_row_width(schema)).max(1),
+            None => self.batch_size,
+        }
     }
 }
 
@@ -306,7 +372,9 @@ config_namespace! {
         pub metadata_size_hint: Option<usize>, default = None
 
         /// If true, filter expressions are be applied during the parquet decoding operation to
-        /// reduce the number of rows decoded
+        /// reduce the number of rows decoded. The other columns are only decoded for the rows
+        /// passing the filters, and their pages without such rows are skipped when the page
+        /// index is enabled
         pub pushdown_filters: bool, default = false
 
         /// If true, filter expressions evaluated during the parquet decoding operation
@@ -441,6 +509,13 @@ config_namespace! {
         /// repartitioning to increase parallelism to leverage more CPU cores
         pub enable_round_robin_repartition: bool, default = true
 
+        /// When set to true, the output partitions of the round robin
+        /// repartitioning added by the physical plan optimizer pull batches
+        /// from a queue shared by all of them, rather than each receiving
+        /// every nth batch. This keeps all CPU cores busy when some batches
+        /// take much longer to process than others
+        pub repartition_work_stealing: bool, default = false
+
         /// When set to true, the optimizer will attempt to perform limit operations
         /// during aggregations, if possible
         pub enable_topk_aggregation: bool, default = true
@@ -518,10 +593,21 @@ config_namespace! {
         /// Number of times that the optimizer will attempt to optimize the plan
         pub max_passes: usize, default = 3
 
+        /// The maximum time in milliseconds that the logical plan optimizer
+        /// should spend on a plan. Once it is exceeded, the remaining optional
+        /// rules are skipped and no further passes are attempted. 0 means no limit
+        pub max_optimization_time_ms: usize, default = 0
+
         /// When set to true, the physical plan optimizer will run a top down
         /// process to reorder the join keys
         pub top_down_join_key_reordering: bool, default = true
 
+        /// The semi join of an `IN` or `EXISTS` subquery first removes the duplicate
+        /// join keys of the subquery with an aggregation when the statistics estimate
+        /// that the keys have at most this fraction as many distinct values as the
+        /// subquery has rows. Set to 0 to disable
+        pub semi_join_deduplication_ratio: f64, default = 0.1
+
         /// When set to true, the physical plan optimizer will prefer HashJoin over SortMergeJoin.
         /// HashJoin can work more efficiently than SortMergeJoin but consumes more memory
         pub prefer_hash_join: bool, default = true
@@ -530,10 +616,96 @@ config_namespace! {
         /// will be collected into a single partition
         pub hash_join_single_partition_threshold: usize, default = 1024 * 1024
 
+        /// The maximum estimated number of rows for one input side of a HashJoin
+        /// will be collected into a single partition. Only used when the
+        /// estimated size in bytes of that input is unknown
+        pub hash_join_single_partition_threshold_rows: usize, default = 1024 * 128
+
+        /// When set to true, joins without equality conditions whose filter
+        /// bounds an expression of one input both from below and from above with
+        /// expressions of the other input, such as `a.ts BETWEEN b.start AND b.end`,
+        /// are planned as range joins rather than nested loop joins
+        pub enable_range_join: bool, default = true
+
         /// The default filter selectivity used by Filter Statistics
         /// when an exact selectivity cannot be determined. Valid values are
         /// between 0 (no selectivity) and 100 (all rows are selected).
         pub default_filter_selectivity: u8, default = 20
+
+        /// When set to true, the physical optimizer will push the bounds of the
+        /// join keys on the build side of a `CollectLeft` hash join into the
+        /// parquet scan on its probe side, so that row groups which cannot
+        /// match are skipped at runtime
+        pub enable_dynamic_join_filter: bool, default = true
+
+        /// When greater than zero, the physical optimizer will cache the results
+        /// of non-volatile scalar functions in projections and filters for up to
+        /// this many distinct arguments per function call within a query, so that
+        /// expensive functions such as regular expressions are not recomputed for
+        /// repeated inputs. Set to 0 to disable caching
+        pub scalar_function_cache_size: usize, default = 0
+
+        /// Comma separated names of tables that inner joins should join in this
+        /// order, as far as their join predicates allow, rather than the order
+        /// they appear in the query. Usually set for a single query with a
+        /// `/*+ JOIN_ORDER(t1, t2, ...) */` hint
+        pub join_order_hint: Option<String>, default = None
+
+        /// Comma separated names of tables that hash joins should collect into a
+        /// single partition and broadcast to every partition of their other input,
+        /// regardless of statistics. Usually set for a single query with a
+        /// `/*+ BROADCAST(t) */` hint
+        pub broadcast_join_hint: Option<String>, default = None
+
+        /// When set to true, the inputs of joins that end in an aggregation or a
+        /// sort, or that are collected by the join, are executed first, and the
+        /// rest of the physical plan is re-optimized using the actual number of
+        /// rows and bytes they produced, e.g. to swap the inputs of a hash join or
+        /// to collect a small input rather than repartitioning both. The results
+        /// of these inputs are buffered in memory
+        pub enable_adaptive_execution: bool, default = false
+
+        /// When set to true, a HashJoin that collects its left input into a
+        /// single partition because of its estimated size falls back to
+        /// repartitioning both inputs on the join keys if the collected input
+        /// does not fit in the memory available to the join
+        pub enable_hash_join_partitioned_fallback: bool, default = false
+
+        /// When set to true, the physical optimizer lowers the number of
+        /// partitions of repartitions whose input is estimated to be small, so
+        /// that each partition receives at least `dynamic_partitioning_min_bytes`
+        /// bytes, or `dynamic_partitioning_min_rows` rows when the estimated size
+        /// in bytes of the input is unknown. At runtime, round robin repartitions
+        /// also only send batches to another partition once each partition in
+        /// use received `dynamic_partitioning_min_rows` rows
+        pub enable_dynamic_partitioning: bool, default = false
+
+        /// The minimum estimated size in bytes of each partition of a
+        /// repartition when `enable_dynamic_partitioning` is set
+        pub dynamic_partitioning_min_bytes: usize, default = 16 * 1024 * 1024
+
+        /// The minimum number of rows of each partition of a repartition when
+        /// `enable_dynamic_partitioning` is set. Used by the optimizer only when
+        /// the estimated size in bytes of the input is unknown, and by round
+        /// robin repartitions at runtime
+        pub dynamic_partitioning_min_rows: usize, default = 128 * 1024
+
+        /// When set to true, aggregations and hash joins whose inputs are known
+        /// from exact statistics to have at most `batch_size` rows are planned as
+        /// single-stage operators over a single partition, without the partial
+        /// aggregation and the repartitioning of their inputs
+        pub enable_small_input_fast_path: bool, default = false
+
+        /// When set to true, the scans of files appearing several times in a
+        /// physical plan with the same files, projection and filters, such as
+        /// the scans of a self join, read the files once and send the batches
+        /// to each of their consumers. The batches are buffered in memory until
+        /// all consumers have read them
+        pub enable_shared_scans: bool, default = false
+
+        /// Comma separated names of physical optimizer rules to skip, as shown
+        /// by `EXPLAIN VERBOSE`, e.g. `TopKSort,coalesce_batches`
+        pub disabled_physical_optimizer_rules: Option<String>, default = None
     }
 }
 
@@ -549,6 +721,56 @@ config_namespace! {
         /// When set to true, the explain statement will print operator statistics
         /// for physical plans
         pub show_statistics: bool, default = false
+
+        /// When set to true, the verbose explain statement will print the time
+        /// spent in, and the number of rewrites applied by each logical optimizer rule
+        pub show_optimizer_metrics: bool, default = false
+    }
+}
+
+config_namespace! {
+    /// Options controlling how numbers are rendered as text by the CLI,
+    /// `DataFrame::show`, and the CSV and JSON writers
+    pub struct NumericFormatOptions {
+        /// Number of digits printed after the decimal point of floating point
+        /// values. When not set, floats are printed with the fewest digits that
+        /// represent them exactly
+        pub float_precision: Option<usize>, default = None
+
+        /// Notation of floating point values: `auto` uses an exponent for very
+        /// large and very small values only, `fixed` never uses one, and
+        /// `scientific` always does
+        pub float_notation: String, default = "auto".to_string()
+
+        /// When set to true, decimal values are printed exactly, with all the
+        /// digits of their scale. When set to false, decimal values are printed
+        /// like floating point values
+        pub exact_decimals: bool, default = true
+    }
+}
+
+config_namespace! {
+    /// Limits on the complexity of the queries that are planned, checked
+    /// when a query is analyzed. Queries exceeding a limit fail with a
+    /// "query too complex" error
+    pub struct PlanLimitsOptions {
+        /// Maximum number of nested operators in a logical plan, counted
+        /// separately within each subquery. When not set, plans may be
+        /// arbitrarily deep
+        pub max_plan_depth: Option<usize>, default = None
+
+        /// Maximum depth of an expression tree, such as a chain of `AND`s or
+        /// nested function calls. When not set, expressions may be arbitrarily
+        /// deep
+        pub max_expr_depth: Option<usize>, default = None
+
+        /// Maximum number of joins in a query, including the joins of its
+        /// subqueries. When not set, queries may join any number of relations
+        pub max_joins: Option<usize>, default = None
+
+        /// Maximum number of subqueries nested within each other. When not
+        /// set, subqueries may be nested arbitrarily
+        pub max_subquery_depth: Option<usize>, default = None
     }
 }
 
@@ -579,6 +801,10 @@ pub struct ConfigOptions {
     pub sql_parser: SqlParserOptions,
     /// Explain options
     pub explain: ExplainOptions,
+    /// Numeric format options
+    pub format: NumericFormatOptions,
+    /// Query complexity limits
+    pub limits: PlanLimitsOptions,
     /// Optional extensions registered using [`Extensions::insert`]
     pub extensions: Extensions,
 }
@@ -593,6 +819,8 @@ impl ConfigField for ConfigOptions {
             "optimizer" => self.optimizer.set(rem, value),
             "explain" => self.explain.set(rem, value),
             "sql_parser" => self.sql_parser.set(rem, value),
+            "format" => self.format.set(rem, value),
+            "limits" => self.limits.set(rem, value),
             _ => _internal_err!("Config value \"{key}\" not found on ConfigOptions"),
         }
     }
@@ -603,6 +831,8 @@ impl ConfigField for ConfigOptions {
         self.optimizer.visit(v, "datafusion.optimizer", "");
         self.explain.visit(v, "datafusion.explain", "");
         self.sql_parser.visit(v, "datafusion.sql_parser", "");
+        self.format.visit(v, "datafusion.format", "");
+        self.limits.visit(v, "datafusion.limits", "");
     }
 }
 
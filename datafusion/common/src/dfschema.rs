@@ -25,8 +25,8 @@ use std::hash::Hash;
 use std::sync::Arc;
 
 use crate::error::{
-    unqualified_field_not_found, DataFusionError, Result, SchemaError, _plan_err,
-    _schema_err,
+    _plan_err, _schema_err, unqualified_field_not_found, DataFusionError, Result,
+    SchemaError,
 };
 use crate::{
     field_not_found, Column, FunctionalDependencies, OwnedTableReference, TableReference,
@@ -308,7 +308,34 @@ impl DFSchema {
                 (None, Some(_)) | (None, None) => field.name() == name,
             })
             .map(|(idx, _)| idx);
-        Ok(matches.next())
+        let Some(first) = matches.next() else {
+            return Ok(None);
+        };
+
+        // a qualifier matching the fields of several relations, e.g. `t` for
+        // both `s1.t` and `s2.t`, is ambiguous
+        if let (Some(qualifier), Some(first_qualifier)) =
+            (qualifier, &self.fields[first].qualifier)
+        {
+            let others = matches
+                .filter_map(|idx| self.fields[idx].qualifier.as_ref())
+                .filter(|other| *other != first_qualifier)
+                .collect::<Vec<_>>();
+            if !others.is_empty() {
+                let qualifiers = std::iter::once(first_qualifier)
+                    .chain(others)
+                    .cloned()
+                    .collect();
+                return _schema_err!(SchemaError::AmbiguousQualifiedReference {
+                    field: Box::new(Column::new(
+                        Some(qualifier.to_owned_reference()),
+                        name
+                    )),
+                    qualifiers,
+                });
+            }
+        }
+        Ok(Some(first))
     }
 
     /// Find the index of the column with the given qualifier and name
@@ -1024,6 +1051,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn ambiguous_qualifier() -> Result<()> {
+        let s1 = DFSchema::try_from_qualified_schema("s1.t", &test_schema_1())?;
+        let s2 = DFSchema::try_from_qualified_schema("s2.t", &test_schema_1())?;
+        let schema = s1.join(&s2)?;
+
+        let col = Column::from_qualified_name("s2.t.c0");
+        assert_eq!(schema.index_of_column(&col)?, 2);
+
+        let col = Column::from_qualified_name("t.c0");
+        let err = schema.index_of_column(&col).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Schema error: Ambiguous reference to field t.c0, which could refer to s1.t.c0, s2.t.c0"
+        );
+        Ok(())
+    }
+
     #[test]
     fn from_unqualified_field() {
         let field = Field::new("c0", DataType::Boolean, true);
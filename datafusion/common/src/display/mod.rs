@@ -43,6 +43,9 @@ pub enum PlanType {
         /// The name of the optimizer which produced this plan
         optimizer_name: String,
     },
+    /// The time spent in, and the number of rewrites applied by each
+    /// optimizer rule
+    LogicalOptimizerMetrics,
     /// The final, fully optimized LogicalPlan that was converted to a physical plan
     FinalLogicalPlan,
     /// The initial physical plan, prepared for execution
@@ -71,6 +74,7 @@ impl Display for PlanType {
             PlanType::OptimizedLogicalPlan { optimizer_name } => {
                 write!(f, "logical_plan after {optimizer_name}")
             }
+            PlanType::LogicalOptimizerMetrics => write!(f, "logical_optimizer_metrics"),
             PlanType::FinalLogicalPlan => write!(f, "logical_plan"),
             PlanType::InitialPhysicalPlan => write!(f, "initial_physical_plan"),
             PlanType::InitialPhysicalPlanWithStats => {
@@ -91,6 +91,12 @@ pub enum DataFusionError {
     /// This error is thrown when a consumer cannot acquire memory from the Memory Manager
     /// we can just cancel the execution of the partition.
     ResourcesExhausted(String),
+    /// This error happens when the execution of a query is cancelled, either
+    /// explicitly or because it ran longer than its timeout
+    Cancelled(String),
+    /// This error happens when a query exceeds one of the limits of
+    /// `datafusion.limits`, such as the maximum number of joins
+    QueryTooComplex(String),
     /// Errors originating from outside DataFusion's core codebase.
     /// For example, a custom S3Error from the crate datafusion-objectstore-s3
     External(GenericError),
@@ -113,6 +119,12 @@ macro_rules! context {
 pub enum SchemaError {
     /// Schema contains a (possibly) qualified and unqualified field with same unqualified name
     AmbiguousReference { field: Column },
+    /// A qualified field name matches fields of several relations, e.g. `t.a`
+    /// when both `s1.t` and `s2.t` are joined
+    AmbiguousQualifiedReference {
+        field: Box<Column>,
+        qualifiers: Vec<OwnedTableReference>,
+    },
     /// Schema contains duplicate qualified field name
     DuplicateQualifiedField {
         qualifier: Box<OwnedTableReference>,
@@ -179,6 +191,22 @@ impl Display for SchemaError {
                     )
                 }
             }
+            Self::AmbiguousQualifiedReference { field, qualifiers } => {
+                write!(
+                    f,
+                    "Ambiguous reference to field {}, which could refer to {}",
+                    field.quoted_flat_name(),
+                    qualifiers
+                        .iter()
+                        .map(|qualifier| format!(
+                            "{}.{}",
+                            qualifier.to_quoted_string(),
+                            quote_identifier(&field.name)
+                        ))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
         }
     }
 }
@@ -299,6 +327,12 @@ impl Display for DataFusionError {
             DataFusionError::ResourcesExhausted(ref desc) => {
                 write!(f, "Resources exhausted: {desc}")
             }
+            DataFusionError::Cancelled(ref desc) => {
+                write!(f, "Query cancelled: {desc}")
+            }
+            DataFusionError::QueryTooComplex(ref desc) => {
+                write!(f, "Query too complex: {desc}")
+            }
             DataFusionError::External(ref desc) => {
                 write!(f, "External error: {desc}")
             }
@@ -335,6 +369,8 @@ impl Error for DataFusionError {
             DataFusionError::SchemaError(e, _) => Some(e),
             DataFusionError::Execution(_) => None,
             DataFusionError::ResourcesExhausted(_) => None,
+            DataFusionError::Cancelled(_) => None,
+            DataFusionError::QueryTooComplex(_) => None,
             DataFusionError::External(e) => Some(e.as_ref()),
             DataFusionError::Context(_, e) => Some(e.as_ref()),
             DataFusionError::Substrait(_) => None,
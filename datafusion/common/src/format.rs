@@ -15,8 +15,190 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
 use arrow::util::display::{DurationFormat, FormatOptions};
 
+use crate::cast::as_float64_array;
+use crate::config::NumericFormatOptions;
+use crate::{DataFusionError, Result};
+
 /// The default [`FormatOptions`] to use within DataFusion
 pub const DEFAULT_FORMAT_OPTIONS: FormatOptions<'static> =
     FormatOptions::new().with_duration_format(DurationFormat::Pretty);
+
+/// Notation of floating point values, see [`NumericFormatOptions::float_notation`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FloatNotation {
+    Auto,
+    Fixed,
+    Scientific,
+}
+
+impl FloatNotation {
+    fn parse(notation: &str) -> Result<Self> {
+        match notation.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "fixed" => Ok(Self::Fixed),
+            "scientific" => Ok(Self::Scientific),
+            _ => Err(DataFusionError::Configuration(format!(
+                "Invalid float notation '{notation}', expected one of auto, fixed or scientific"
+            ))),
+        }
+    }
+}
+
+/// Formats a floating point value according to `options`
+pub fn format_float(value: f64, options: &NumericFormatOptions) -> Result<String> {
+    let notation = FloatNotation::parse(&options.float_notation)?;
+    Ok(format_float_with(value, options.float_precision, notation))
+}
+
+fn format_float_with(
+    value: f64,
+    precision: Option<usize>,
+    notation: FloatNotation,
+) -> String {
+    let scientific = match notation {
+        FloatNotation::Auto => {
+            let magnitude = value.abs();
+            value.is_finite() && magnitude != 0.0 && !(1e-4..1e16).contains(&magnitude)
+        }
+        FloatNotation::Fixed => false,
+        FloatNotation::Scientific => true,
+    };
+    match (scientific, precision) {
+        (false, None) => format!("{value}"),
+        (false, Some(precision)) => format!("{value:.precision$}"),
+        (true, None) => format!("{value:e}"),
+        (true, Some(precision)) => format!("{value:.precision$e}"),
+    }
+}
+
+/// Returns `batch` with its floating point columns, and its decimal columns
+/// unless [`NumericFormatOptions::exact_decimals`] is set, replaced by their
+/// text according to `options`.
+///
+/// `batch` is returned unchanged when `options` are the default ones, so
+/// that the output of the writers using it only differs when asked to.
+pub fn format_numeric_columns(
+    batch: &RecordBatch,
+    options: &NumericFormatOptions,
+) -> Result<RecordBatch> {
+    let notation = FloatNotation::parse(&options.float_notation)?;
+    if options.float_precision.is_none()
+        && notation == FloatNotation::Auto
+        && options.exact_decimals
+    {
+        return Ok(batch.clone());
+    }
+
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(schema.fields().len());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        let formatted = match field.data_type() {
+            DataType::Float16 | DataType::Float32 | DataType::Float64 => true,
+            DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => {
+                !options.exact_decimals
+            }
+            _ => false,
+        };
+        if !formatted {
+            fields.push(field.clone());
+            columns.push(column.clone());
+            continue;
+        }
+
+        let values = cast(column, &DataType::Float64)?;
+        let values = as_float64_array(&values)?;
+        let strings = values
+            .iter()
+            .map(|v| v.map(|v| format_float_with(v, options.float_precision, notation)))
+            .collect::<StringArray>();
+        fields.push(Arc::new(
+            field.as_ref().clone().with_data_type(DataType::Utf8),
+        ));
+        columns.push(Arc::new(strings) as ArrayRef);
+    }
+
+    let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Array, AsArray, Decimal128Array, Float64Array};
+    use arrow::datatypes::Field;
+
+    fn options(precision: Option<usize>, notation: &str) -> NumericFormatOptions {
+        NumericFormatOptions {
+            float_precision: precision,
+            float_notation: notation.to_string(),
+            exact_decimals: true,
+        }
+    }
+
+    #[test]
+    fn test_format_float() -> Result<()> {
+        let cases = [
+            (1.5, None, "fixed", "1.5"),
+            (1.5, Some(3), "fixed", "1.500"),
+            (1e20, None, "fixed", "100000000000000000000"),
+            (1234.5, None, "scientific", "1.2345e3"),
+            (1234.5, Some(2), "scientific", "1.23e3"),
+            (0.26, Some(1), "auto", "0.3"),
+            (1e20, Some(1), "auto", "1.0e20"),
+            (0.00001, None, "auto", "1e-5"),
+            (f64::NAN, Some(2), "scientific", "NaN"),
+        ];
+        for (value, precision, notation, expected) in cases {
+            let formatted = format_float(value, &options(precision, notation))?;
+            assert_eq!(formatted, expected, "{value} {precision:?} {notation}");
+        }
+
+        let err = format_float(1.0, &options(None, "engineering")).unwrap_err();
+        assert!(err.to_string().contains("Invalid float notation"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_numeric_columns() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("f", DataType::Float64, true),
+            Field::new("d", DataType::Decimal128(5, 2), true),
+        ]));
+        let floats = Float64Array::from(vec![Some(1.0 / 3.0), None]);
+        let decimals = Decimal128Array::from(vec![Some(12345), Some(-5)])
+            .with_precision_and_scale(5, 2)?;
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(floats), Arc::new(decimals)])?;
+
+        // default options leave the batch unchanged
+        let formatted = format_numeric_columns(&batch, &Default::default())?;
+        assert_eq!(formatted, batch);
+
+        let formatted = format_numeric_columns(&batch, &options(Some(2), "fixed"))?;
+        assert_eq!(formatted.schema().field(0).data_type(), &DataType::Utf8);
+        assert_eq!(formatted.column(1), batch.column(1));
+        let floats = formatted.column(0).as_string::<i32>();
+        assert_eq!(floats.value(0), "0.33");
+        assert!(floats.is_null(1));
+
+        let options = NumericFormatOptions {
+            exact_decimals: false,
+            ..options(Some(1), "fixed")
+        };
+        let formatted = format_numeric_columns(&batch, &options)?;
+        let decimals = formatted.column(1).as_string::<i32>();
+        assert_eq!(decimals.value(0), "123.5");
+        assert_eq!(decimals.value(1), "-0.1");
+        Ok(())
+    }
+}
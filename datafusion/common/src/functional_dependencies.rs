@@ -526,6 +526,19 @@ pub fn aggregate_functional_dependencies(
             )
             .with_mode(Dependency::Single),
         );
+    } else if group_by_expr_names.len() > 1 {
+        // Otherwise, the GROUP BY keys together are unique after aggregation:
+        let n_group = group_by_expr_names.len();
+        aggregate_func_dependencies.push(
+            FunctionalDependence::new(
+                (0..n_group).collect(),
+                target_indices,
+                aggr_fields[..n_group]
+                    .iter()
+                    .any(|field| field.is_nullable()),
+            )
+            .with_mode(Dependency::Single),
+        );
     }
     FunctionalDependencies::new(aggregate_func_dependencies)
 }
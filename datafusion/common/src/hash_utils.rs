@@ -30,7 +30,7 @@ use crate::cast::{
     as_boolean_array, as_generic_binary_array, as_large_list_array, as_list_array,
     as_primitive_array, as_string_array, as_struct_array,
 };
-use crate::error::{DataFusionError, Result, _internal_err};
+use crate::error::{_internal_err, DataFusionError, Result};
 
 // Combines two hashes into one hash
 #[inline]
@@ -75,15 +75,28 @@ hash_value!(i8, i16, i32, i64, i128, i256, u8, u16, u32, u64);
 hash_value!(bool, str, [u8]);
 
 macro_rules! hash_float_value {
-    ($(($t:ty, $i:ty)),+) => {
+    ($(($t:ty, $i:ty, $zero:expr)),+) => {
         $(impl HashValue for $t {
             fn hash_one(&self, state: &RandomState) -> u64 {
-                state.hash_one(<$i>::from_ne_bytes(self.to_ne_bytes()))
+                // All NaNs and both zeros hash the same, so that keys that are
+                // normalized to be equal also end up in the same partition
+                let value = if self.is_nan() {
+                    <$t>::NAN
+                } else if *self == $zero {
+                    $zero
+                } else {
+                    *self
+                };
+                state.hash_one(<$i>::from_ne_bytes(value.to_ne_bytes()))
             }
         })+
     };
 }
-hash_float_value!((half::f16, u16), (f32, u32), (f64, u64));
+hash_float_value!(
+    (half::f16, u16, half::f16::ZERO),
+    (f32, u32, 0.0),
+    (f64, u64, 0.0)
+);
 
 /// Builds hash values of PrimitiveArray and writes them into `hashes_buffer`
 /// If `rehash==true` this combines the previous hash value in the buffer
@@ -113,18 +126,19 @@ fn hash_array_primitive<T>(
                 *hash = value.hash_one(random_state);
             }
         }
-    } else if rehash {
-        for (i, hash) in hashes_buffer.iter_mut().enumerate() {
-            if !array.is_null(i) {
-                let value = unsafe { array.value_unchecked(i) };
-                *hash = combine_hashes(value.hash_one(random_state), *hash);
-            }
-        }
     } else {
-        for (i, hash) in hashes_buffer.iter_mut().enumerate() {
-            if !array.is_null(i) {
-                let value = unsafe { array.value_unchecked(i) };
-                *hash = value.hash_one(random_state);
+        // Only visit the valid rows, finding them a word of the null bitmap
+        // at a time, rather than checking each row for nulls
+        let values = array.values();
+        let valid_indices = array.nulls().unwrap().valid_indices();
+        if rehash {
+            for i in valid_indices {
+                let hash = &mut hashes_buffer[i];
+                *hash = combine_hashes(values[i].hash_one(random_state), *hash);
+            }
+        } else {
+            for i in valid_indices {
+                hashes_buffer[i] = values[i].hash_one(random_state);
             }
         }
     }
@@ -160,18 +174,20 @@ fn hash_array<T>(
                 *hash = value.hash_one(random_state);
             }
         }
-    } else if rehash {
-        for (i, hash) in hashes_buffer.iter_mut().enumerate() {
-            if !array.is_null(i) {
+    } else {
+        // Only visit the valid rows, finding them a word of the null bitmap
+        // at a time, rather than checking each row for nulls
+        let valid_indices = array.nulls().unwrap().valid_indices();
+        if rehash {
+            for i in valid_indices {
                 let value = unsafe { array.value_unchecked(i) };
+                let hash = &mut hashes_buffer[i];
                 *hash = combine_hashes(value.hash_one(random_state), *hash);
             }
-        }
-    } else {
-        for (i, hash) in hashes_buffer.iter_mut().enumerate() {
-            if !array.is_null(i) {
+        } else {
+            for i in valid_indices {
                 let value = unsafe { array.value_unchecked(i) };
-                *hash = value.hash_one(random_state);
+                hashes_buffer[i] = value.hash_one(random_state);
             }
         }
     }
@@ -191,18 +207,30 @@ fn hash_dictionary<K: ArrowDictionaryKeyType>(
     let mut dict_hashes = vec![0; values.len()];
     create_hashes(&[values], random_state, &mut dict_hashes)?;
 
-    // combine hash for each index in values
-    if multi_col {
-        for (hash, key) in hashes_buffer.iter_mut().zip(array.keys().iter()) {
-            if let Some(key) = key {
+    // combine hash for each index in values, with no update for Null keys,
+    // consistent with other hashes
+    let keys = array.keys().values();
+    match (array.keys().nulls(), multi_col) {
+        (None, true) => {
+            for (hash, key) in hashes_buffer.iter_mut().zip(keys.iter()) {
                 *hash = combine_hashes(dict_hashes[key.as_usize()], *hash)
-            } // no update for Null, consistent with other hashes
+            }
         }
-    } else {
-        for (hash, key) in hashes_buffer.iter_mut().zip(array.keys().iter()) {
-            if let Some(key) = key {
+        (None, false) => {
+            for (hash, key) in hashes_buffer.iter_mut().zip(keys.iter()) {
                 *hash = dict_hashes[key.as_usize()]
-            } // no update for Null, consistent with other hashes
+            }
+        }
+        (Some(nulls), true) => {
+            for i in nulls.valid_indices() {
+                let hash = &mut hashes_buffer[i];
+                *hash = combine_hashes(dict_hashes[keys[i].as_usize()], *hash)
+            }
+        }
+        (Some(nulls), false) => {
+            for i in nulls.valid_indices() {
+                hashes_buffer[i] = dict_hashes[keys[i].as_usize()]
+            }
         }
     }
     Ok(())
@@ -449,6 +477,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn create_hashes_for_nan_and_signed_zero() -> Result<()> {
+        let array: ArrayRef = Arc::new(Float64Array::from(vec![
+            f64::NAN,
+            -f64::NAN,
+            f64::from_bits(f64::NAN.to_bits() | 1),
+            0.0,
+            -0.0,
+        ]));
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let hashes_buff = &mut vec![0; array.len()];
+        let hashes = create_hashes(&[array], &random_state, hashes_buff)?;
+        assert_eq!(hashes[0], hashes[1]);
+        assert_eq!(hashes[0], hashes[2]);
+        assert_eq!(hashes[3], hashes[4]);
+        assert_ne!(hashes[0], hashes[3]);
+
+        Ok(())
+    }
+
     #[test]
     fn create_hashes_binary() -> Result<()> {
         let byte_array = Arc::new(BinaryArray::from_vec(vec![
@@ -664,4 +713,47 @@ mod tests {
 
         assert_ne!(one_col_hashes, two_col_hashes);
     }
+
+    #[test]
+    // Tests actual values of hashes, which are different if forcing collisions
+    #[cfg(not(feature = "force_hash_collisions"))]
+    fn create_multi_column_hash_with_nulls() {
+        let ints: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            None,
+            Some(3),
+            Some(1),
+            None,
+        ]));
+        let strings: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("foo"),
+            Some("bar"),
+            None,
+            Some("foo"),
+            None,
+        ]));
+        let dict: ArrayRef = Arc::new(
+            [None, Some("a"), Some("b"), None, Some("a")]
+                .into_iter()
+                .collect::<DictionaryArray<Int8Type>>(),
+        );
+        let columns = [ints, strings, dict];
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let mut hashes = vec![0; 5];
+        create_hashes(&columns, &random_state, &mut hashes).unwrap();
+        assert_eq!(hashes[0], hashes[3]);
+
+        // Each row hashes the same when hashed alone, where each of its
+        // values is either in an array without nulls or only nulls
+        for (i, hash) in hashes.iter().enumerate() {
+            let row = columns
+                .iter()
+                .map(|column| column.slice(i, 1))
+                .collect::<Vec<_>>();
+            let mut row_hash = vec![0; 1];
+            create_hashes(&row, &random_state, &mut row_hash).unwrap();
+            assert_eq!(row_hash[0], *hash, "row {i}");
+        }
+    }
 }
@@ -64,7 +64,7 @@ pub use join_type::{JoinConstraint, JoinSide, JoinType};
 pub use param_value::ParamValues;
 pub use scalar::{ScalarType, ScalarValue};
 pub use schema_reference::{OwnedSchemaReference, SchemaReference};
-pub use stats::{ColumnStatistics, Statistics};
+pub use stats::{ColumnStatistics, Histogram, HistogramBucket, Statistics};
 pub use table_reference::{OwnedTableReference, ResolvedTableReference, TableReference};
 pub use unnest::UnnestOptions;
 pub use utils::project_schema;
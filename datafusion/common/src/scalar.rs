@@ -5196,6 +5196,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn temporal_op_matches_kernels() {
+        // ScalarValue arithmetic must behave like the array kernels used for
+        // columns, returning the same values and failing for the same pairs
+        let ops: [(&str, ScalarOp, ArrayKernel); 4] = [
+            ("+", |l, r| l.add(r), add_wrapping),
+            ("-", |l, r| l.sub(r), sub_wrapping),
+            ("+", |l, r| l.add_checked(r), add),
+            ("-", |l, r| l.sub_checked(r), sub),
+        ];
+        let values = get_random_temporals();
+        for lhs in &values {
+            for rhs in &values {
+                for (op, scalar_op, kernel) in ops {
+                    check_scalar_op_matches_kernel(lhs, rhs, op, scalar_op, kernel);
+                }
+            }
+        }
+    }
+
+    type ScalarOp = fn(&ScalarValue, &ScalarValue) -> Result<ScalarValue>;
+    type ArrayKernel =
+        fn(&dyn Datum, &dyn Datum) -> Result<ArrayRef, arrow::error::ArrowError>;
+
+    // Verifies that `scalar_op` returns the same value as `kernel` applied to
+    // arrays of `lhs` and `rhs`, or fails like it.
+    fn check_scalar_op_matches_kernel(
+        lhs: &ScalarValue,
+        rhs: &ScalarValue,
+        op: &str,
+        scalar_op: ScalarOp,
+        kernel: ArrayKernel,
+    ) {
+        let scalar_result = scalar_op(lhs, rhs);
+        let lhs_array = lhs.to_array_of_size(2).unwrap();
+        let rhs_array = rhs.to_array_of_size(2).unwrap();
+        let array_result = kernel(&lhs_array, &rhs_array);
+        match (scalar_result, array_result) {
+            (Ok(scalar), Ok(array)) => {
+                let expected = ScalarValue::try_from_array(&array, 1).unwrap();
+                assert_eq!(scalar, expected, "{lhs:?} {op} {rhs:?}");
+            }
+            (Err(_), Err(_)) => {}
+            (scalar, array) => panic!(
+                "{lhs:?} {op} {rhs:?}: scalar result {scalar:?}, array result {array:?}"
+            ),
+        }
+    }
+
     #[test]
     fn test_struct_nulls() {
         let fields_b = Fields::from(vec![
@@ -5402,4 +5451,40 @@ mod tests {
         }
         intervals
     }
+
+    /// A random value, and a null, of each temporal type
+    fn get_random_temporals() -> Vec<ScalarValue> {
+        let mut rng = rand::thread_rng();
+        // less than a day, so that times are valid
+        let millis = rng.gen_range(0..86_400_000);
+        let nanos = millis as i64 * 1_000_000 + rng.gen_range(0..1_000_000);
+        let days = rng.gen_range(-10_000..10_000);
+        let tz = Some("+05:00".into());
+        let mut values = vec![
+            ScalarValue::Date32(Some(days)),
+            ScalarValue::Date64(Some(days as i64 * 86_400_000)),
+            ScalarValue::Time32Second(Some(millis / 1000)),
+            ScalarValue::Time32Millisecond(Some(millis)),
+            ScalarValue::Time64Microsecond(Some(nanos / 1000)),
+            ScalarValue::Time64Nanosecond(Some(nanos)),
+            ScalarValue::TimestampSecond(Some(nanos / 1_000_000_000), None),
+            ScalarValue::TimestampMillisecond(Some(nanos / 1_000_000), None),
+            ScalarValue::TimestampMicrosecond(Some(nanos / 1000), None),
+            ScalarValue::TimestampNanosecond(Some(nanos), None),
+            ScalarValue::TimestampNanosecond(Some(nanos), tz),
+            ScalarValue::DurationSecond(Some(nanos / 1_000_000_000)),
+            ScalarValue::DurationMillisecond(Some(nanos / 1_000_000)),
+            ScalarValue::DurationMicrosecond(Some(nanos / 1000)),
+            ScalarValue::DurationNanosecond(Some(nanos)),
+            ScalarValue::new_interval_ym(0, rng.gen_range(-120..120)),
+            ScalarValue::new_interval_dt(rng.gen_range(-1000..1000), millis),
+            ScalarValue::new_interval_mdn(rng.gen_range(-120..120), days, nanos),
+        ];
+        let nulls = values
+            .iter()
+            .map(|value| ScalarValue::try_from(value.data_type()).unwrap())
+            .collect::<Vec<_>>();
+        values.extend(nulls);
+        values
+    }
 }
@@ -18,6 +18,7 @@
 //! This module provides data structures to represent statistics
 
 use std::fmt::{self, Debug, Display};
+use std::sync::Arc;
 
 use crate::ScalarValue;
 
@@ -258,6 +259,7 @@ impl Statistics {
                     max_value: cs.max_value.to_inexact(),
                     min_value: cs.min_value.to_inexact(),
                     distinct_count: cs.distinct_count.to_inexact(),
+                    histogram: cs.histogram,
                 })
                 .collect::<Vec<_>>(),
         }
@@ -320,6 +322,8 @@ pub struct ColumnStatistics {
     pub min_value: Precision<ScalarValue>,
     /// Number of distinct values
     pub distinct_count: Precision<usize>,
+    /// Distribution of the non null values of column, if known
+    pub histogram: Option<Arc<Histogram>>,
 }
 
 impl ColumnStatistics {
@@ -341,10 +345,44 @@ impl ColumnStatistics {
             max_value: Precision::Absent,
             min_value: Precision::Absent,
             distinct_count: Precision::Absent,
+            histogram: None,
         }
     }
 }
 
+/// An equi-depth histogram of the non null values of a column, used to
+/// estimate the selectivity of predicates on the column.
+///
+/// Bucket `i` holds the values greater than the upper bound of bucket `i - 1`
+/// and less than or equal to its own upper bound, so buckets are sorted by
+/// their upper bounds.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Histogram {
+    /// The buckets of the histogram, in ascending order of upper bound
+    pub buckets: Vec<HistogramBucket>,
+}
+
+/// A bucket of a [`Histogram`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistogramBucket {
+    /// The largest value in the bucket
+    pub upper_bound: ScalarValue,
+    /// The number of values in the bucket
+    pub count: usize,
+}
+
+impl Histogram {
+    /// Creates a histogram from buckets sorted by upper bound
+    pub fn new(buckets: Vec<HistogramBucket>) -> Self {
+        Self { buckets }
+    }
+
+    /// Returns the number of values in the histogram
+    pub fn total_count(&self) -> usize {
+        self.buckets.iter().map(|bucket| bucket.count).sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -60,10 +60,28 @@
 ///      └─────────┘ └─────┘                └─────────┘ └─────┘
 ///        c1         c2                        c1        c2
 /// ```
+///
+/// ## `Unnest(c1)`, preserve_nulls: false, ordinality: `ord`
+/// ```text
+///      ┌─────────┐ ┌─────┐                ┌─────────┐ ┌─────┐ ┌─────┐
+///      │ {1, 2}  │ │  A  │   Unnest       │    1    │ │  A  │ │  1  │
+///      ├─────────┤ ├─────┤                ├─────────┤ ├─────┤ ├─────┤
+///      │  null   │ │  B  │                │    2    │ │  A  │ │  2  │
+///      ├─────────┤ ├─────┤ ────────────▶  ├─────────┤ ├─────┤ ├─────┤
+///      │   {}    │ │  D  │                │    3    │ │  E  │ │  1  │
+///      ├─────────┤ ├─────┤                └─────────┘ └─────┘ └─────┘
+///      │   {3}   │ │  E  │                    c1        c2      ord
+///      └─────────┘ └─────┘
+///        c1         c2
+/// ```
 #[derive(Debug, Clone, PartialEq, PartialOrd, Hash, Eq)]
 pub struct UnnestOptions {
     /// Should nulls in the input be preserved? Defaults to true
     pub preserve_nulls: bool,
+    /// If set, the name of an additional `Int64` column, after all the
+    /// others, holding the 1-based position of each value in its list.
+    /// Defaults to `None`
+    pub ordinality: Option<String>,
 }
 
 impl Default for UnnestOptions {
@@ -71,6 +89,7 @@ impl Default for UnnestOptions {
         Self {
             // default to true to maintain backwards compatible behavior
             preserve_nulls: true,
+            ordinality: None,
         }
     }
 }
@@ -87,4 +106,11 @@ impl UnnestOptions {
         self.preserve_nulls = preserve_nulls;
         self
     }
+
+    /// Add a column named `name` with the position of each value in its
+    /// list, as described on [`Self`]
+    pub fn with_ordinality(mut self, name: impl Into<String>) -> Self {
+        self.ordinality = Some(name.into());
+        self
+    }
 }
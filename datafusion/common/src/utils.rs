@@ -23,17 +23,18 @@ use arrow::array::{ArrayRef, PrimitiveArray};
 use arrow::buffer::OffsetBuffer;
 use arrow::compute;
 use arrow::compute::{partition, SortColumn, SortOptions};
-use arrow::datatypes::{Field, SchemaRef, UInt32Type};
+use arrow::datatypes::{Field, Schema, SchemaRef, UInt32Type};
 use arrow::record_batch::RecordBatch;
 use arrow_array::{Array, LargeListArray, ListArray, RecordBatchOptions};
 use arrow_schema::DataType;
+use chrono::Months;
 use sqlparser::ast::Ident;
 use sqlparser::dialect::GenericDialect;
 use sqlparser::parser::Parser;
 use std::borrow::{Borrow, Cow};
 use std::cmp::Ordering;
 use std::collections::HashSet;
-use std::ops::Range;
+use std::ops::{Add, Range, Sub};
 use std::sync::Arc;
 
 /// Applies an optional projection to a [`SchemaRef`], returning the
@@ -492,6 +493,49 @@ pub fn list_ndims(data_type: &DataType) -> u64 {
     }
 }
 
+/// Width in bytes assumed for the values of variable width types, such as
+/// strings, by [`estimate_row_width`]
+const ESTIMATED_VARIABLE_WIDTH: usize = 32;
+
+/// Estimates the number of bytes a row of `schema` takes in memory, from the
+/// types of its fields.
+///
+/// Values of variable width types, such as strings and lists, are assumed to
+/// take 32 bytes plus their offset. The estimate is at least 1, even for
+/// empty schemas.
+pub fn estimate_row_width(schema: &Schema) -> usize {
+    schema
+        .fields()
+        .iter()
+        .map(|field| estimate_value_width(field.data_type()))
+        .sum::<usize>()
+        .max(1)
+}
+
+/// Estimates the number of bytes a value of `data_type` takes in memory
+fn estimate_value_width(data_type: &DataType) -> usize {
+    match data_type {
+        DataType::Null => 0,
+        DataType::Boolean => 1,
+        DataType::Utf8 | DataType::Binary => 4 + ESTIMATED_VARIABLE_WIDTH,
+        DataType::LargeUtf8 | DataType::LargeBinary => 8 + ESTIMATED_VARIABLE_WIDTH,
+        DataType::FixedSizeBinary(size) => *size as usize,
+        DataType::List(_) | DataType::Map(_, _) => 4 + ESTIMATED_VARIABLE_WIDTH,
+        DataType::LargeList(_) => 8 + ESTIMATED_VARIABLE_WIDTH,
+        DataType::FixedSizeList(field, size) => {
+            *size as usize * estimate_value_width(field.data_type())
+        }
+        DataType::Struct(fields) => fields
+            .iter()
+            .map(|field| estimate_value_width(field.data_type()))
+            .sum(),
+        DataType::Dictionary(key_type, _) => estimate_value_width(key_type),
+        data_type => data_type
+            .primitive_width()
+            .unwrap_or(ESTIMATED_VARIABLE_WIDTH),
+    }
+}
+
 /// An extension trait for smart pointers. Provides an interface to get a
 /// raw pointer to the data (with metadata stripped away).
 ///
@@ -588,6 +632,22 @@ pub mod datafusion_strsim {
     }
 }
 
+/// Shifts `date` by the given number of `months`, which may be negative.
+///
+/// When the day of month does not exist in the target month it is clamped
+/// to the last day of that month, e.g. `2023-01-31` shifted by one month
+/// yields `2023-02-28`.
+pub fn shift_months<D>(date: D, months: i32) -> D
+where
+    D: Add<Months, Output = D> + Sub<Months, Output = D>,
+{
+    match months.cmp(&0) {
+        Ordering::Equal => date,
+        Ordering::Greater => date + Months::new(months as u32),
+        Ordering::Less => date - Months::new(months.unsigned_abs()),
+    }
+}
+
 /// Merges collections `first` and `second`, removes duplicates and sorts the
 /// result, returning it as a [`Vec`].
 pub fn merge_and_order_indices<T: Borrow<usize>, S: Borrow<usize>>(
@@ -1009,4 +1069,34 @@ mod tests {
         assert!(find_indices(&[0, 3, 4], [0, 2]).is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_shift_months() {
+        let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(shift_months(date(2023, 5, 15), 0), date(2023, 5, 15));
+        assert_eq!(shift_months(date(2023, 5, 15), 3), date(2023, 8, 15));
+        assert_eq!(shift_months(date(2023, 11, 30), 3), date(2024, 2, 29));
+        assert_eq!(shift_months(date(2023, 1, 31), 1), date(2023, 2, 28));
+        assert_eq!(shift_months(date(2023, 3, 31), -1), date(2023, 2, 28));
+        assert_eq!(shift_months(date(2023, 1, 15), -13), date(2021, 12, 15));
+    }
+
+    #[test]
+    fn test_estimate_row_width() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Float64, true),
+            Field::new("c", DataType::Boolean, true),
+            Field::new("d", DataType::Utf8, true),
+        ]);
+        assert_eq!(estimate_row_width(&schema), 4 + 8 + 1 + 36);
+        assert_eq!(estimate_row_width(&Schema::empty()), 1);
+
+        let mut options = crate::config::ExecutionOptions::default();
+        assert_eq!(options.batch_size_for_schema(&schema), options.batch_size);
+        options.target_batch_bytes = Some(49 * 1000);
+        assert_eq!(options.batch_size_for_schema(&schema), 1000);
+        options.target_batch_bytes = Some(1);
+        assert_eq!(options.batch_size_for_schema(&schema), 1);
+    }
 }
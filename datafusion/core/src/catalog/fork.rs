@@ -0,0 +1,344 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Catalogs and schemas layered over the ones of another session, used by
+//! [`SessionContext::fork`](crate::execution::context::SessionContext::fork)
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use datafusion_common::{exec_err, DataFusionError, Result};
+
+use crate::catalog::schema::SchemaProvider;
+use crate::catalog::{CatalogList, CatalogProvider};
+use crate::datasource::TableProvider;
+
+/// A [`CatalogList`] sharing the catalogs of a parent list copy-on-write.
+///
+/// The catalogs of the parent are visible through the fork, but the
+/// catalogs, schemas and tables registered or deregistered through the fork
+/// are not visible to the parent. The changes made to the parent after the
+/// fork remain visible unless the fork has overridden them.
+pub struct ForkedCatalogList {
+    parent: Arc<dyn CatalogList>,
+    catalogs: DashMap<String, Arc<dyn CatalogProvider>>,
+}
+
+impl ForkedCatalogList {
+    /// Creates a new fork of `parent`
+    pub fn new(parent: Arc<dyn CatalogList>) -> Self {
+        Self {
+            parent,
+            catalogs: DashMap::new(),
+        }
+    }
+
+    /// Returns the catalog list this one was forked from
+    pub fn parent(&self) -> &Arc<dyn CatalogList> {
+        &self.parent
+    }
+}
+
+impl CatalogList for ForkedCatalogList {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn register_catalog(
+        &self,
+        name: String,
+        catalog: Arc<dyn CatalogProvider>,
+    ) -> Option<Arc<dyn CatalogProvider>> {
+        let previous = self.catalog(&name);
+        self.catalogs.insert(name, catalog);
+        previous
+    }
+
+    fn catalog_names(&self) -> Vec<String> {
+        let mut names = self.parent.catalog_names();
+        for catalog in self.catalogs.iter() {
+            if !names.contains(catalog.key()) {
+                names.push(catalog.key().clone());
+            }
+        }
+        names
+    }
+
+    fn catalog(&self, name: &str) -> Option<Arc<dyn CatalogProvider>> {
+        if let Some(catalog) = self.catalogs.get(name) {
+            return Some(catalog.value().clone());
+        }
+        // Fork the catalog of the parent the first time it is used, so that
+        // the schemas registered in it only affect this list
+        let parent = self.parent.catalog(name)?;
+        let catalog = self
+            .catalogs
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(ForkedCatalogProvider::new(parent)));
+        Some(catalog.value().clone())
+    }
+}
+
+/// A [`CatalogProvider`] sharing the schemas of a parent catalog
+/// copy-on-write, see [`ForkedCatalogList`]
+pub struct ForkedCatalogProvider {
+    parent: Arc<dyn CatalogProvider>,
+    /// The schemas of this fork, `None` for the schemas of the parent that
+    /// were dropped from it
+    schemas: DashMap<String, Option<Arc<dyn SchemaProvider>>>,
+}
+
+impl ForkedCatalogProvider {
+    /// Creates a new fork of `parent`
+    pub fn new(parent: Arc<dyn CatalogProvider>) -> Self {
+        Self {
+            parent,
+            schemas: DashMap::new(),
+        }
+    }
+}
+
+impl CatalogProvider for ForkedCatalogProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema_names(&self) -> Vec<String> {
+        let mut names = self
+            .parent
+            .schema_names()
+            .into_iter()
+            .filter(|name| !self.schemas.contains_key(name))
+            .collect::<Vec<_>>();
+        names.extend(
+            self.schemas
+                .iter()
+                .filter(|schema| schema.value().is_some())
+                .map(|schema| schema.key().clone()),
+        );
+        names
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        if let Some(schema) = self.schemas.get(name) {
+            return schema.value().clone();
+        }
+        // Fork the schema of the parent the first time it is used, so that
+        // the tables registered in it only affect this catalog
+        let parent = self.parent.schema(name)?;
+        let schema = self
+            .schemas
+            .entry(name.to_string())
+            .or_insert_with(|| Some(Arc::new(ForkedSchemaProvider::new(parent))));
+        schema.value().clone()
+    }
+
+    fn register_schema(
+        &self,
+        name: &str,
+        schema: Arc<dyn SchemaProvider>,
+    ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+        let previous = self.schema(name);
+        self.schemas.insert(name.into(), Some(schema));
+        Ok(previous)
+    }
+
+    fn deregister_schema(
+        &self,
+        name: &str,
+        cascade: bool,
+    ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+        let Some(schema) = self.schema(name) else {
+            return Ok(None);
+        };
+        let table_names = schema.table_names();
+        if !table_names.is_empty() && !cascade {
+            return exec_err!(
+                "Cannot drop schema {} because other tables depend on it: {}",
+                name,
+                itertools::join(table_names.iter(), ", ")
+            );
+        }
+        if self.parent.schema(name).is_some() {
+            // Hide the schema of the parent rather than dropping it
+            self.schemas.insert(name.into(), None);
+        } else {
+            self.schemas.remove(name);
+        }
+        Ok(Some(schema))
+    }
+}
+
+/// A [`SchemaProvider`] sharing the tables of a parent schema
+/// copy-on-write, see [`ForkedCatalogList`]
+pub struct ForkedSchemaProvider {
+    parent: Arc<dyn SchemaProvider>,
+    /// The tables of this fork, `None` for the tables of the parent that
+    /// were dropped from it
+    tables: DashMap<String, Option<Arc<dyn TableProvider>>>,
+}
+
+impl ForkedSchemaProvider {
+    /// Creates a new fork of `parent`
+    pub fn new(parent: Arc<dyn SchemaProvider>) -> Self {
+        Self {
+            parent,
+            tables: DashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl SchemaProvider for ForkedSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        let mut names = self
+            .parent
+            .table_names()
+            .into_iter()
+            .filter(|name| !self.tables.contains_key(name))
+            .collect::<Vec<_>>();
+        names.extend(
+            self.tables
+                .iter()
+                .filter(|table| table.value().is_some())
+                .map(|table| table.key().clone()),
+        );
+        names
+    }
+
+    async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+        // Do not hold the lock of the map over the await point
+        let table = self.tables.get(name).map(|table| table.value().clone());
+        match table {
+            Some(table) => table,
+            None => self.parent.table(name).await,
+        }
+    }
+
+    fn register_table(
+        &self,
+        name: String,
+        table: Arc<dyn TableProvider>,
+    ) -> Result<Option<Arc<dyn TableProvider>>> {
+        if self.table_exist(name.as_str()) {
+            return exec_err!("The table {name} already exists");
+        }
+        Ok(self.tables.insert(name, Some(table)).flatten())
+    }
+
+    /// The tables of the parent are hidden from this schema rather than
+    /// dropped, and `None` is returned for them as they are not owned by it
+    fn deregister_table(&self, name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        if self.parent.table_exist(name) {
+            Ok(self.tables.insert(name.to_string(), None).flatten())
+        } else {
+            Ok(self.tables.remove(name).and_then(|(_, table)| table))
+        }
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        match self.tables.get(name) {
+            Some(table) => table.value().is_some(),
+            None => self.parent.table_exist(name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::schema::MemorySchemaProvider;
+    use crate::catalog::{MemoryCatalogList, MemoryCatalogProvider};
+    use crate::datasource::empty::EmptyTable;
+    use arrow::datatypes::Schema;
+
+    fn empty_table() -> Arc<dyn TableProvider> {
+        Arc::new(EmptyTable::new(Arc::new(Schema::empty())))
+    }
+
+    fn sorted(mut names: Vec<String>) -> Vec<String> {
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn forked_schema() -> Result<()> {
+        let parent = Arc::new(MemorySchemaProvider::new());
+        parent.register_table("a".to_string(), empty_table())?;
+        parent.register_table("b".to_string(), empty_table())?;
+        let fork = ForkedSchemaProvider::new(parent.clone());
+
+        assert!(fork.table("a").await.is_some());
+        assert!(fork.register_table("a".to_string(), empty_table()).is_err());
+
+        fork.register_table("c".to_string(), empty_table())?;
+        assert!(fork.deregister_table("b")?.is_none());
+        assert!(!fork.table_exist("b"));
+        assert!(fork.table("b").await.is_none());
+        assert_eq!(sorted(fork.table_names()), vec!["a", "c"]);
+
+        // the parent is unchanged
+        assert_eq!(sorted(parent.table_names()), vec!["a", "b"]);
+
+        // a dropped table of the parent can be registered again
+        fork.register_table("b".to_string(), empty_table())?;
+        assert!(fork.table_exist("b"));
+        assert!(fork.deregister_table("c")?.is_some());
+        assert_eq!(sorted(fork.table_names()), vec!["a", "b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn forked_catalog_list() -> Result<()> {
+        let schema = Arc::new(MemorySchemaProvider::new());
+        schema.register_table("t".to_string(), empty_table())?;
+        let catalog = Arc::new(MemoryCatalogProvider::new());
+        catalog.register_schema("s", schema.clone())?;
+        let parent = Arc::new(MemoryCatalogList::new());
+        parent.register_catalog("c".to_string(), catalog.clone());
+        let fork = ForkedCatalogList::new(parent.clone());
+
+        let forked_catalog = fork.catalog("c").unwrap();
+        let forked_schema = forked_catalog.schema("s").unwrap();
+        forked_schema.register_table("u".to_string(), empty_table())?;
+        forked_catalog.register_schema("s2", Arc::new(MemorySchemaProvider::new()))?;
+        fork.register_catalog("c2".to_string(), Arc::new(MemoryCatalogProvider::new()));
+
+        assert_eq!(sorted(fork.catalog_names()), vec!["c", "c2"]);
+        assert_eq!(sorted(forked_catalog.schema_names()), vec!["s", "s2"]);
+        assert_eq!(sorted(forked_schema.table_names()), vec!["t", "u"]);
+
+        // the parent is unchanged
+        assert_eq!(parent.catalog_names(), vec!["c"]);
+        assert_eq!(catalog.schema_names(), vec!["s"]);
+        assert_eq!(schema.table_names(), vec!["t"]);
+
+        // dropping a schema of the parent hides it from the fork only
+        assert!(forked_catalog.deregister_schema("s", false).is_err());
+        assert!(forked_catalog.deregister_schema("s", true)?.is_some());
+        assert!(forked_catalog.schema("s").is_none());
+        assert_eq!(forked_catalog.schema_names(), vec!["s2"]);
+        assert!(catalog.schema("s").is_some());
+        Ok(())
+    }
+}
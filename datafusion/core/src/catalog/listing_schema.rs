@@ -150,6 +150,7 @@ impl ListingSchemaProvider {
                             options: Default::default(),
                             constraints: Constraints::empty(),
                             column_defaults: Default::default(),
+                            schema_location: None,
                         },
                     )
                     .await?;
@@ -30,6 +30,7 @@ use crate::datasource::{provider_as_source, MemTable, TableProvider};
 use crate::error::Result;
 use crate::execution::{
     context::{SessionState, TaskContext},
+    warnings::PlanWarning,
     FunctionRegistry,
 };
 use crate::logical_expr::utils::find_window_exprs;
@@ -38,7 +39,8 @@ use crate::logical_expr::{
 };
 use crate::physical_plan::{
     collect, collect_partitioned, execute_stream, execute_stream_partitioned,
-    ExecutionPlan, SendableRecordBatchStream,
+    execute_stream_with_progress, ExecutionPlan, QueryProgress,
+    SendableRecordBatchStream,
 };
 use crate::prelude::SessionContext;
 
@@ -46,8 +48,10 @@ use arrow::array::{Array, ArrayRef, Int64Array, StringArray};
 use arrow::compute::{cast, concat};
 use arrow::csv::WriterBuilder;
 use arrow::datatypes::{DataType, Field};
+use datafusion_common::config::NumericFormatOptions;
 use datafusion_common::file_options::csv_writer::CsvWriterOptions;
 use datafusion_common::file_options::json_writer::JsonWriterOptions;
+use datafusion_common::format::format_numeric_columns;
 use datafusion_common::parsers::CompressionTypeVariant;
 use datafusion_common::{
     Column, DFSchema, DataFusionError, FileType, FileTypeWriterOptions, ParamValues,
@@ -154,6 +158,31 @@ impl DataFrame {
         self.session_state.create_physical_plan(&self.plan).await
     }
 
+    /// Create a physical plan, along with the [`PlanWarning`]s about hazards
+    /// found while planning it
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.sql("SELECT * FROM (VALUES (1)) a, (VALUES (2)) b").await?;
+    /// let (plan, warnings) = df.create_physical_plan_with_warnings().await?;
+    /// for warning in warnings {
+    ///     println!("{warning}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_physical_plan_with_warnings(
+        self,
+    ) -> Result<(Arc<dyn ExecutionPlan>, Vec<PlanWarning>)> {
+        self.session_state
+            .create_physical_plan_with_warnings(&self.plan)
+            .await
+    }
+
     /// Filter the DataFrame by column. Returns a new DataFrame only containing the
     /// specified columns.
     ///
@@ -686,6 +715,59 @@ impl DataFrame {
         Ok(DataFrame::new(self.session_state, plan))
     }
 
+    /// Join each row of this `DataFrame` with the row of another `DataFrame`
+    /// with equal `left_cols` and `right_cols` whose time is the closest to,
+    /// and not later than, the time of the row.
+    ///
+    /// `left_time` and `right_time` are the time columns, and `tolerance`
+    /// the maximum difference of the times of the joined rows: an integer
+    /// for integer time columns, and an interval for date and timestamp
+    /// columns. Only [`JoinType::Inner`] and [`JoinType::Left`] joins are
+    /// supported, the latter joining the rows without a match with nulls.
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let left = ctx.read_csv("tests/data/example.csv", CsvReadOptions::new()).await?;
+    /// let right = ctx.read_csv("tests/data/example.csv", CsvReadOptions::new()).await?
+    ///   .select(vec![
+    ///     col("a").alias("a2"),
+    ///     col("b").alias("b2"),
+    ///     col("c").alias("c2")])?;
+    /// // join each row with the row of `right` with the same `a` and the
+    /// // greatest `b2` not greater than its `b`
+    /// let join = left.join_asof(right, JoinType::Inner, &["a"], &["a2"], "b", "b2", None)?;
+    /// let batches = join.collect().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn join_asof(
+        self,
+        right: DataFrame,
+        join_type: JoinType,
+        left_cols: &[&str],
+        right_cols: &[&str],
+        left_time: &str,
+        right_time: &str,
+        tolerance: Option<Expr>,
+    ) -> Result<DataFrame> {
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .join_asof(
+                right.plan,
+                join_type,
+                (left_cols.to_vec(), right_cols.to_vec()),
+                (left_time, right_time),
+                tolerance,
+            )?
+            .build()?;
+        Ok(DataFrame::new(self.session_state, plan))
+    }
+
     /// Repartition a DataFrame based on a logical partitioning scheme.
     ///
     /// ```
@@ -773,8 +855,9 @@ impl DataFrame {
     /// # }
     /// ```
     pub async fn show(self) -> Result<()> {
+        let options = self.session_state.config_options().format.clone();
         let results = self.collect().await?;
-        Ok(pretty::print_batches(&results)?)
+        print_formatted_batches(&results, &options)
     }
 
     /// Print results and limit rows.
@@ -791,8 +874,9 @@ impl DataFrame {
     /// # }
     /// ```
     pub async fn show_limit(self, num: usize) -> Result<()> {
+        let options = self.session_state.config_options().format.clone();
         let results = self.limit(0, Some(num))?.collect().await?;
-        Ok(pretty::print_batches(&results)?)
+        print_formatted_batches(&results, &options)
     }
 
     /// Get a new TaskContext to run in this session
@@ -825,6 +909,34 @@ impl DataFrame {
         execute_stream(plan, task_ctx)
     }
 
+    /// Executes this DataFrame like [`Self::execute_stream`], also returning a
+    /// [`QueryProgress`] reporting the progress of the execution
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/data/example.csv", CsvReadOptions::new()).await?;
+    /// let (mut stream, progress) = df.execute_stream_with_progress().await?;
+    /// while let Some(batch) = stream.next().await {
+    ///     batch?;
+    ///     println!("{:.0}% done", progress.fraction().unwrap_or_default() * 100.0);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_stream_with_progress(
+        self,
+    ) -> Result<(SendableRecordBatchStream, QueryProgress)> {
+        let task_ctx = Arc::new(self.task_ctx());
+        let plan = self.create_physical_plan().await?;
+        execute_stream_with_progress(plan, task_ctx)
+    }
+
     /// Executes this DataFrame and collects all results into a vector of vector of RecordBatch
     /// maintaining the input partitioning.
     ///
@@ -1297,6 +1409,18 @@ impl DataFrame {
     }
 }
 
+/// Prints `batches` to stdout, with their numbers formatted according to `options`
+fn print_formatted_batches(
+    batches: &[RecordBatch],
+    options: &NumericFormatOptions,
+) -> Result<()> {
+    let batches = batches
+        .iter()
+        .map(|batch| format_numeric_columns(batch, options))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(pretty::print_batches(&batches)?)
+}
+
 struct DataFrameTableProvider {
     plan: LogicalPlan,
 }
@@ -1361,6 +1485,7 @@ mod tests {
 
     use super::*;
     use crate::execution::context::SessionConfig;
+    use crate::execution::warnings::PlanWarningKind;
     use crate::physical_plan::{ColumnarValue, Partitioning, PhysicalExpr};
     use crate::test_util::{register_aggregate_csv, test_table, test_table_with_name};
     use crate::{assert_batches_sorted_eq, execution::context::SessionContext};
@@ -1929,6 +2054,93 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn join_asof() -> Result<()> {
+        let ctx = SessionContext::new();
+        let trades = RecordBatch::try_from_iter(vec![
+            (
+                "ts",
+                Arc::new(Int32Array::from(vec![1, 5, 10, 15])) as ArrayRef,
+            ),
+            (
+                "stock",
+                Arc::new(Int32Array::from(vec![1, 2, 1, 1])) as ArrayRef,
+            ),
+        ])?;
+        let quotes = RecordBatch::try_from_iter(vec![
+            (
+                "ts2",
+                Arc::new(Int32Array::from(vec![0, 4, 9, 12])) as ArrayRef,
+            ),
+            (
+                "stock2",
+                Arc::new(Int32Array::from(vec![1, 2, 1, 2])) as ArrayRef,
+            ),
+            (
+                "price",
+                Arc::new(Int32Array::from(vec![100, 200, 101, 201])) as ArrayRef,
+            ),
+        ])?;
+        let join = ctx.read_batch(trades)?.join_asof(
+            ctx.read_batch(quotes)?,
+            JoinType::Left,
+            &["stock"],
+            &["stock2"],
+            "ts",
+            "ts2",
+            Some(lit(5)),
+        )?;
+
+        let expected = [
+            "+----+-------+-----+--------+-------+",
+            "| ts | stock | ts2 | stock2 | price |",
+            "+----+-------+-----+--------+-------+",
+            "| 1  | 1     | 0   | 1      | 100   |",
+            "| 10 | 1     | 9   | 1      | 101   |",
+            "| 15 | 1     |     |        |       |",
+            "| 5  | 2     | 4   | 2      | 200   |",
+            "+----+-------+-----+--------+-------+",
+        ];
+        assert_batches_sorted_eq!(expected, &join.collect().await?);
+
+        Ok(())
+    }
+
+    async fn plan_warning_kinds(
+        ctx: &SessionContext,
+        sql: &str,
+    ) -> Result<Vec<PlanWarningKind>> {
+        let (_, warnings) = ctx
+            .sql(sql)
+            .await?
+            .create_physical_plan_with_warnings()
+            .await?;
+        Ok(warnings.into_iter().map(|warning| warning.kind).collect())
+    }
+
+    #[tokio::test]
+    async fn plan_warnings() -> Result<()> {
+        let mut ctx = SessionContext::new();
+        register_aggregate_csv(&mut ctx, "a").await?;
+        register_aggregate_csv(&mut ctx, "b").await?;
+
+        let kinds = plan_warning_kinds(&ctx, "SELECT a.c1, b.c1 FROM a, b").await?;
+        assert_eq!(kinds, [PlanWarningKind::CrossJoin]);
+
+        // `c6` is an Int64, cast to Float64 to be compared
+        let kinds = plan_warning_kinds(&ctx, "SELECT c1 FROM a WHERE c6 = 1.5").await?;
+        assert_eq!(kinds, [PlanWarningKind::LossyImplicitCast]);
+
+        let kinds =
+            plan_warning_kinds(&ctx, "SELECT c1 FROM a WHERE abs(c12) > 0.5").await?;
+        assert_eq!(kinds, [PlanWarningKind::DefaultSelectivity]);
+
+        // explicit casts are not reported
+        let kinds = plan_warning_kinds(&ctx, "SELECT CAST(c6 AS DOUBLE) FROM a").await?;
+        assert!(kinds.is_empty());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_ambiguous_filter() -> Result<()> {
         let left = test_table_with_name("a")
@@ -2832,4 +3044,24 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_execute_stream_with_progress() -> Result<()> {
+        let df = test_table().await?;
+        let (mut stream, progress) = df.execute_stream_with_progress().await?;
+        while let Some(batch) = futures::StreamExt::next(&mut stream).await {
+            batch?;
+        }
+
+        let scan = progress
+            .operators()
+            .into_iter()
+            .find(|op| op.files_total.is_some())
+            .unwrap();
+        assert_eq!(scan.operator, "CsvExec");
+        assert!(scan.files_scanned > 0);
+        assert_eq!(Some(scan.files_scanned), scan.files_total);
+        assert_eq!(progress.fraction(), Some(1.0));
+        Ok(())
+    }
 }
@@ -0,0 +1,417 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Reading and writing the JSON format of the [Arrow integration tests],
+//! and of schema files in that format
+//!
+//! [Arrow integration tests]: https://arrow.apache.org/docs/format/Integration.html#json-test-data-format
+
+use std::fmt::Display;
+use std::io::{Cursor, Read, Write};
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, GenericBinaryArray, GenericListArray, GenericStringArray,
+    OffsetSizeTrait,
+};
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, Date32Type, Date64Type, Decimal128Type,
+    DurationMicrosecondType, DurationMillisecondType, DurationNanosecondType,
+    DurationSecondType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    Int8Type, Schema, SchemaRef, Time32MillisecondType, Time32SecondType,
+    Time64MicrosecondType, Time64NanosecondType, TimeUnit, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::ipc::reader::FileReader;
+use arrow::record_batch::RecordBatch;
+use arrow_integration_test::{schema_from_json, schema_to_json, ArrowJson};
+use datafusion_common::{not_impl_err, DataFusionError, Result};
+use serde_json::{json, Map, Value};
+
+/// The magic bytes starting Arrow IPC files
+const ARROW_MAGIC: &[u8] = b"ARROW1";
+
+/// Reads the schema and record batches of a file in the JSON format of the
+/// Arrow integration tests
+pub fn read_arrow_json(reader: impl Read) -> Result<(SchemaRef, Vec<RecordBatch>)> {
+    let value: Value = serde_json::from_reader(reader).map_err(json_err)?;
+    let Some(schema) = value.get("schema") else {
+        return Err(DataFusionError::Execution(
+            "Arrow JSON file does not contain a schema".to_string(),
+        ));
+    };
+    let schema = Arc::new(schema_from_json(schema)?);
+    let json: ArrowJson = serde_json::from_value(value).map_err(json_err)?;
+    let batches = json.get_record_batches()?;
+    Ok((schema, batches))
+}
+
+/// Writes `batches`, whose schema is `schema`, in the JSON format of the
+/// Arrow integration tests.
+///
+/// Dictionary, union, map and view types are not supported.
+pub fn write_arrow_json(
+    writer: impl Write,
+    schema: &Schema,
+    batches: &[RecordBatch],
+) -> Result<()> {
+    let batches = batches
+        .iter()
+        .map(|batch| {
+            let columns = batch
+                .columns()
+                .iter()
+                .zip(schema.fields())
+                .map(|(column, field)| column_to_json(field.name(), column))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(json!({ "count": batch.num_rows(), "columns": columns }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let value = json!({ "schema": schema_to_json(schema), "batches": batches });
+    serde_json::to_writer_pretty(writer, &value).map_err(json_err)
+}
+
+/// Parses a schema in the JSON format of the Arrow integration tests, either
+/// on its own or as part of a complete file
+pub fn schema_from_arrow_json(json: &str) -> Result<Schema> {
+    let value: Value = serde_json::from_str(json).map_err(json_err)?;
+    let schema = value.get("schema").unwrap_or(&value);
+    Ok(schema_from_json(schema)?)
+}
+
+/// Formats `schema` in the JSON format of the Arrow integration tests
+pub fn schema_to_arrow_json(schema: &Schema) -> Result<String> {
+    serde_json::to_string_pretty(&schema_to_json(schema)).map_err(json_err)
+}
+
+/// Reads the schema stored in `contents`, either an Arrow IPC file, or a
+/// schema in the JSON format of the Arrow integration tests.
+///
+/// Used by the `SCHEMA` clause of `CREATE EXTERNAL TABLE`.
+pub fn read_schema_file(contents: &[u8]) -> Result<Schema> {
+    if contents.starts_with(ARROW_MAGIC) {
+        let reader = FileReader::try_new(Cursor::new(contents), None)?;
+        return Ok(reader.schema().as_ref().clone());
+    }
+    let json = std::str::from_utf8(contents).map_err(|e| {
+        DataFusionError::Execution(format!("Schema file is not valid UTF-8: {e}"))
+    })?;
+    schema_from_arrow_json(json)
+}
+
+fn json_err(e: serde_json::Error) -> DataFusionError {
+    DataFusionError::External(Box::new(e))
+}
+
+/// Returns the JSON column of `array`, named `name`
+fn column_to_json(name: &str, array: &ArrayRef) -> Result<Value> {
+    let mut column = Map::new();
+    column.insert("name".to_string(), json!(name));
+    column.insert("count".to_string(), json!(array.len()));
+    if *array.data_type() != DataType::Null {
+        let validity: Vec<u8> =
+            (0..array.len()).map(|i| array.is_valid(i) as u8).collect();
+        column.insert("VALIDITY".to_string(), json!(validity));
+    }
+
+    match array.data_type() {
+        DataType::Null => {}
+        DataType::Boolean => {
+            let data: Vec<_> = array
+                .as_boolean()
+                .iter()
+                .map(|v| json!(v.unwrap_or_default()))
+                .collect();
+            column.insert("DATA".to_string(), Value::Array(data));
+        }
+        DataType::Int8 => primitive_data::<Int8Type>(&mut column, array),
+        DataType::Int16 => primitive_data::<Int16Type>(&mut column, array),
+        DataType::Int32 => primitive_data::<Int32Type>(&mut column, array),
+        DataType::Int64 => wide_primitive_data::<Int64Type>(&mut column, array),
+        DataType::UInt8 => primitive_data::<UInt8Type>(&mut column, array),
+        DataType::UInt16 => primitive_data::<UInt16Type>(&mut column, array),
+        DataType::UInt32 => primitive_data::<UInt32Type>(&mut column, array),
+        DataType::UInt64 => wide_primitive_data::<UInt64Type>(&mut column, array),
+        DataType::Float32 => primitive_data::<Float32Type>(&mut column, array),
+        DataType::Float64 => primitive_data::<Float64Type>(&mut column, array),
+        DataType::Date32 => primitive_data::<Date32Type>(&mut column, array),
+        DataType::Date64 => wide_primitive_data::<Date64Type>(&mut column, array),
+        DataType::Time32(TimeUnit::Second) => {
+            primitive_data::<Time32SecondType>(&mut column, array)
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            primitive_data::<Time32MillisecondType>(&mut column, array)
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            wide_primitive_data::<Time64MicrosecondType>(&mut column, array)
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            wide_primitive_data::<Time64NanosecondType>(&mut column, array)
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            wide_primitive_data::<TimestampSecondType>(&mut column, array)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            wide_primitive_data::<TimestampMillisecondType>(&mut column, array)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            wide_primitive_data::<TimestampMicrosecondType>(&mut column, array)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            wide_primitive_data::<TimestampNanosecondType>(&mut column, array)
+        }
+        DataType::Duration(TimeUnit::Second) => {
+            wide_primitive_data::<DurationSecondType>(&mut column, array)
+        }
+        DataType::Duration(TimeUnit::Millisecond) => {
+            wide_primitive_data::<DurationMillisecondType>(&mut column, array)
+        }
+        DataType::Duration(TimeUnit::Microsecond) => {
+            wide_primitive_data::<DurationMicrosecondType>(&mut column, array)
+        }
+        DataType::Duration(TimeUnit::Nanosecond) => {
+            wide_primitive_data::<DurationNanosecondType>(&mut column, array)
+        }
+        DataType::Decimal128(_, _) => {
+            wide_primitive_data::<Decimal128Type>(&mut column, array)
+        }
+        DataType::Utf8 => string_data(&mut column, array.as_string::<i32>()),
+        DataType::LargeUtf8 => string_data(&mut column, array.as_string::<i64>()),
+        DataType::Binary => binary_data(&mut column, array.as_binary::<i32>()),
+        DataType::LargeBinary => binary_data(&mut column, array.as_binary::<i64>()),
+        DataType::List(field) => {
+            list_data(&mut column, field.name(), array.as_list::<i32>())?
+        }
+        DataType::LargeList(field) => {
+            list_data(&mut column, field.name(), array.as_list::<i64>())?
+        }
+        DataType::Struct(fields) => {
+            let children = array
+                .as_struct()
+                .columns()
+                .iter()
+                .zip(fields)
+                .map(|(child, field)| column_to_json(field.name(), child))
+                .collect::<Result<Vec<_>>>()?;
+            column.insert("children".to_string(), Value::Array(children));
+        }
+        other => {
+            return not_impl_err!(
+                "Writing {other} columns in the Arrow JSON format is not supported"
+            )
+        }
+    }
+    Ok(Value::Object(column))
+}
+
+/// Adds the values of the primitive `array` to `column`
+fn primitive_data<T: ArrowPrimitiveType>(
+    column: &mut Map<String, Value>,
+    array: &ArrayRef,
+) where
+    T::Native: Into<Value>,
+{
+    let data = array
+        .as_primitive::<T>()
+        .values()
+        .iter()
+        .map(|v| (*v).into());
+    column.insert("DATA".to_string(), data.collect());
+}
+
+/// Adds the values of the primitive `array` to `column` as strings, as the
+/// integration format represents 64 bit and wider values, which do not fit
+/// in JSON numbers
+fn wide_primitive_data<T: ArrowPrimitiveType>(
+    column: &mut Map<String, Value>,
+    array: &ArrayRef,
+) where
+    T::Native: Display,
+{
+    let data = array
+        .as_primitive::<T>()
+        .values()
+        .iter()
+        .map(|v| json!(v.to_string()));
+    column.insert("DATA".to_string(), data.collect());
+}
+
+/// Returns the offsets of a variable length array with `lengths`, starting
+/// at zero. The integration format represents 64 bit offsets as strings.
+fn offsets<O: OffsetSizeTrait>(lengths: impl Iterator<Item = usize>) -> Value {
+    let mut offset = 0;
+    let mut offsets = vec![offset];
+    offsets.extend(lengths.map(|len| {
+        offset += len;
+        offset
+    }));
+    let large = O::IS_LARGE;
+    Value::Array(
+        offsets
+            .into_iter()
+            .map(|o| {
+                if large {
+                    json!(o.to_string())
+                } else {
+                    json!(o)
+                }
+            })
+            .collect(),
+    )
+}
+
+fn string_data<O: OffsetSizeTrait>(
+    column: &mut Map<String, Value>,
+    array: &GenericStringArray<O>,
+) {
+    let values = (0..array.len()).map(|i| array.value(i));
+    column.insert(
+        "OFFSET".to_string(),
+        offsets::<O>(values.clone().map(str::len)),
+    );
+    column.insert("DATA".to_string(), values.map(|v| json!(v)).collect());
+}
+
+fn binary_data<O: OffsetSizeTrait>(
+    column: &mut Map<String, Value>,
+    array: &GenericBinaryArray<O>,
+) {
+    let values = (0..array.len()).map(|i| array.value(i));
+    column.insert(
+        "OFFSET".to_string(),
+        offsets::<O>(values.clone().map(<[u8]>::len)),
+    );
+    let data = values
+        .map(|v| json!(v.iter().map(|b| format!("{b:02X}")).collect::<String>()))
+        .collect();
+    column.insert("DATA".to_string(), data);
+}
+
+fn list_data<O: OffsetSizeTrait>(
+    column: &mut Map<String, Value>,
+    child_name: &str,
+    array: &GenericListArray<O>,
+) -> Result<()> {
+    let value_offsets = array.value_offsets();
+    let start = value_offsets[0].as_usize();
+    let end = value_offsets[array.len()].as_usize();
+    column.insert(
+        "OFFSET".to_string(),
+        offsets::<O>(value_offsets.windows(2).map(|w| (w[1] - w[0]).as_usize())),
+    );
+    let child = column_to_json(child_name, &array.values().slice(start, end - start))?;
+    column.insert("children".to_string(), json!([child]));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{
+        BooleanArray, Int32Array, Int64Array, ListArray, StringArray, StructArray,
+    };
+    use arrow::datatypes::{Field, Fields};
+    use arrow::ipc::writer::FileWriter;
+
+    fn test_batch() -> RecordBatch {
+        let list = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), None]),
+            None,
+            Some(vec![]),
+        ]);
+        let point = StructArray::from(vec![
+            (
+                Arc::new(Field::new("x", DataType::Int64, false)),
+                Arc::new(Int64Array::from(vec![1, i64::MAX, -3])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("valid", DataType::Boolean, true)),
+                Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)])) as _,
+            ),
+        ]);
+        RecordBatch::try_from_iter(vec![
+            ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            (
+                "name",
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("ccc")])) as _,
+            ),
+            ("list", Arc::new(list) as _),
+            ("point", Arc::new(point) as _),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn arrow_json_round_trip() -> Result<()> {
+        let batch = test_batch();
+        let batches = vec![batch.slice(1, 2), batch];
+
+        let mut buffer = vec![];
+        write_arrow_json(&mut buffer, &batches[1].schema(), &batches)?;
+        let (schema, read) = read_arrow_json(buffer.as_slice())?;
+
+        assert_eq!(schema, batches[1].schema());
+        assert_eq!(read, batches);
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_type() {
+        let dict = arrow::array::DictionaryArray::<Int32Type>::from_iter(["a", "b"]);
+        let batch =
+            RecordBatch::try_from_iter(vec![("dict", Arc::new(dict) as ArrayRef)])
+                .unwrap();
+        let err = write_arrow_json(vec![], &batch.schema(), &[batch]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Writing Dictionary(Int32, Utf8) columns in the Arrow JSON format is not supported"));
+    }
+
+    #[test]
+    fn schema_files() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new(
+                "b",
+                DataType::Struct(Fields::from(vec![Field::new(
+                    "c",
+                    DataType::Utf8,
+                    true,
+                )])),
+                true,
+            ),
+        ]);
+
+        let json = schema_to_arrow_json(&schema)?;
+        assert_eq!(read_schema_file(json.as_bytes())?, schema);
+
+        // the schema of a complete Arrow JSON file
+        let mut file = vec![];
+        write_arrow_json(&mut file, &schema, &[])?;
+        assert_eq!(read_schema_file(&file)?, schema);
+
+        // an Arrow IPC file
+        let mut file = vec![];
+        FileWriter::try_new(&mut file, &schema)?.finish()?;
+        assert_eq!(read_schema_file(&file)?, schema);
+
+        assert!(read_schema_file(b"not a schema").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CteWorkTable`] implementing the work table of recursive CTEs
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+
+use crate::datasource::{TableProvider, TableType};
+use crate::error::Result;
+use crate::execution::context::SessionState;
+use crate::logical_expr::Expr;
+use crate::physical_plan::work_table::WorkTableExec;
+use crate::physical_plan::ExecutionPlan;
+
+/// The temporary working table where the previous iteration of a recursive
+/// query is stored, which its recursive term reads.
+///
+/// Scanning it produces a [`WorkTableExec`], to which the `RecursiveQueryExec`
+/// of the query gives the rows of each iteration.
+pub struct CteWorkTable {
+    /// The name of the CTE work table
+    name: String,
+    /// This schema must be shared across both the static and recursive terms
+    /// of a recursive query
+    table_schema: SchemaRef,
+}
+
+impl CteWorkTable {
+    /// Construct a new CteWorkTable with the given name and schema
+    pub fn new(name: &str, table_schema: SchemaRef) -> Self {
+        Self {
+            name: name.to_owned(),
+            table_schema,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for CteWorkTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.table_schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(WorkTableExec::try_new(
+            self.name.clone(),
+            self.table_schema.clone(),
+            projection.cloned(),
+        )?))
+    }
+}
@@ -70,6 +70,48 @@ impl TableSource for DefaultTableSource {
         self.table_provider.supports_filters_pushdown(filter)
     }
 
+    fn supports_aggregate_pushdown(
+        &self,
+        group_expr: &[Expr],
+        aggr_expr: &[Expr],
+        filters: &[Expr],
+    ) -> datafusion_common::Result<bool> {
+        self.table_provider
+            .supports_aggregate_pushdown(group_expr, aggr_expr, filters)
+    }
+
+    fn aggregate_pushdown(
+        &self,
+        group_expr: &[Expr],
+        aggr_expr: &[Expr],
+        filters: &[Expr],
+    ) -> datafusion_common::Result<Arc<dyn TableSource>> {
+        let table_provider = self
+            .table_provider
+            .aggregate_pushdown(group_expr, aggr_expr, filters)?;
+        Ok(provider_as_source(table_provider))
+    }
+
+    fn supports_topk_pushdown(
+        &self,
+        sort_exprs: &[Expr],
+        k: usize,
+        filters: &[Expr],
+    ) -> datafusion_common::Result<bool> {
+        self.table_provider
+            .supports_topk_pushdown(sort_exprs, k, filters)
+    }
+
+    fn topk_pushdown(
+        &self,
+        sort_exprs: &[Expr],
+        k: usize,
+        filters: &[Expr],
+    ) -> datafusion_common::Result<Arc<dyn TableSource>> {
+        let table_provider = self.table_provider.topk_pushdown(sort_exprs, k, filters)?;
+        Ok(provider_as_source(table_provider))
+    }
+
     fn get_logical_plan(&self) -> Option<&datafusion_expr::LogicalPlan> {
         self.table_provider.get_logical_plan()
     }
@@ -25,9 +25,7 @@ use std::fmt::{self, Debug};
 use std::sync::Arc;
 
 use crate::datasource::file_format::FileFormat;
-use crate::datasource::physical_plan::{
-    ArrowExec, FileGroupDisplay, FileScanConfig, FileSinkConfig,
-};
+use crate::datasource::physical_plan::{ArrowExec, FileScanConfig, FileSinkConfig};
 use crate::error::Result;
 use crate::execution::context::SessionState;
 use crate::physical_plan::ExecutionPlan;
@@ -192,8 +190,8 @@ impl DisplayAs for ArrowFileSink {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match t {
             DisplayFormatType::Default | DisplayFormatType::Verbose => {
-                write!(f, "ArrowFileSink(file_groups=",)?;
-                FileGroupDisplay(&self.config.file_groups).fmt_as(t, f)?;
+                write!(f, "ArrowFileSink(")?;
+                self.config.fmt_as(t, f)?;
                 write!(f, ")")
             }
         }
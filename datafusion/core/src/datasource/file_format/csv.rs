@@ -26,9 +26,7 @@ use super::write::orchestration::stateless_multipart_put;
 use super::{FileFormat, DEFAULT_SCHEMA_INFER_MAX_RECORD};
 use crate::datasource::file_format::file_compression_type::FileCompressionType;
 use crate::datasource::file_format::write::BatchSerializer;
-use crate::datasource::physical_plan::{
-    CsvExec, FileGroupDisplay, FileScanConfig, FileSinkConfig,
-};
+use crate::datasource::physical_plan::{CsvExec, FileScanConfig, FileSinkConfig};
 use crate::error::Result;
 use crate::execution::context::SessionState;
 use crate::physical_plan::insert::{DataSink, FileSinkExec};
@@ -39,6 +37,8 @@ use arrow::array::RecordBatch;
 use arrow::csv::WriterBuilder;
 use arrow::datatypes::{DataType, Field, Fields, Schema};
 use arrow::{self, datatypes::SchemaRef};
+use datafusion_common::config::NumericFormatOptions;
+use datafusion_common::format::format_numeric_columns;
 use datafusion_common::{exec_err, not_impl_err, DataFusionError, FileType};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::{PhysicalExpr, PhysicalSortRequirement};
@@ -399,6 +399,8 @@ pub struct CsvSerializer {
     builder: WriterBuilder,
     // Flag to indicate whether there will be a header
     header: bool,
+    // How numbers are rendered
+    numeric_format: NumericFormatOptions,
 }
 
 impl CsvSerializer {
@@ -407,6 +409,7 @@ impl CsvSerializer {
         Self {
             builder: WriterBuilder::new(),
             header: true,
+            numeric_format: NumericFormatOptions::default(),
         }
     }
 
@@ -421,6 +424,12 @@ impl CsvSerializer {
         self.header = header;
         self
     }
+
+    /// Method for setting how numbers are rendered
+    pub fn with_numeric_format(mut self, numeric_format: NumericFormatOptions) -> Self {
+        self.numeric_format = numeric_format;
+        self
+    }
 }
 
 #[async_trait]
@@ -429,6 +438,7 @@ impl BatchSerializer for CsvSerializer {
         let mut buffer = Vec::with_capacity(4096);
         let builder = self.builder.clone();
         let header = self.header && initial;
+        let batch = format_numeric_columns(&batch, &self.numeric_format)?;
         let mut writer = builder.with_header(header).build(&mut buffer);
         writer.write(&batch)?;
         drop(writer);
@@ -452,8 +462,8 @@ impl DisplayAs for CsvSink {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match t {
             DisplayFormatType::Default | DisplayFormatType::Verbose => {
-                write!(f, "CsvSink(file_groups=",)?;
-                FileGroupDisplay(&self.config.file_groups).fmt_as(t, f)?;
+                write!(f, "CsvSink(")?;
+                self.config.fmt_as(t, f)?;
                 write!(f, ")")
             }
         }
@@ -481,11 +491,13 @@ impl CsvSink {
 
         let builder_clone = builder.clone();
         let options_clone = writer_options.clone();
+        let numeric_format = context.session_config().options().format.clone();
         let get_serializer = move || {
             Arc::new(
                 CsvSerializer::new()
                     .with_builder(builder_clone.clone())
-                    .with_header(options_clone.writer_options.header()),
+                    .with_header(options_clone.writer_options.header())
+                    .with_numeric_format(numeric_format.clone()),
             ) as _
         };
 
@@ -861,6 +873,28 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_csv_serializer_numeric_format() -> Result<()> {
+        let ctx = SessionContext::new();
+        let batches = ctx
+            .sql("SELECT 1.0 / 3 AS f, 2 AS i")
+            .await?
+            .collect()
+            .await?;
+        let numeric_format = NumericFormatOptions {
+            float_precision: Some(3),
+            float_notation: "scientific".to_string(),
+            exact_decimals: true,
+        };
+        let serializer = CsvSerializer::new().with_numeric_format(numeric_format);
+        let bytes = serializer.serialize(batches[0].clone(), true).await?;
+        assert_eq!(
+            "f,i\n3.333e-1,2\n",
+            String::from_utf8(bytes.into()).unwrap()
+        );
+        Ok(())
+    }
+
     /// Explain the `sql` query under `ctx` to make sure the underlying csv scan is parallelized
     /// e.g. "CsvExec: file_groups={2 groups:" in plan means 2 CsvExec runs concurrently
     async fn count_query_csv_partitions(
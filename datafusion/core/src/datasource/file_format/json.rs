@@ -28,7 +28,6 @@ use super::{FileFormat, FileScanConfig};
 use crate::datasource::file_format::file_compression_type::FileCompressionType;
 use crate::datasource::file_format::write::BatchSerializer;
 use crate::datasource::file_format::DEFAULT_SCHEMA_INFER_MAX_RECORD;
-use crate::datasource::physical_plan::FileGroupDisplay;
 use crate::datasource::physical_plan::{FileSinkConfig, NdJsonExec};
 use crate::error::Result;
 use crate::execution::context::SessionState;
@@ -42,6 +41,8 @@ use arrow::datatypes::SchemaRef;
 use arrow::json;
 use arrow::json::reader::{infer_json_schema_from_iterator, ValueIter};
 use arrow_array::RecordBatch;
+use datafusion_common::config::NumericFormatOptions;
+use datafusion_common::format::format_numeric_columns;
 use datafusion_common::{not_impl_err, DataFusionError, FileType};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::{PhysicalExpr, PhysicalSortRequirement};
@@ -195,12 +196,24 @@ impl Default for JsonSerializer {
 }
 
 /// Define a struct for serializing Json records to a stream
-pub struct JsonSerializer {}
+pub struct JsonSerializer {
+    // How numbers are rendered
+    numeric_format: NumericFormatOptions,
+}
 
 impl JsonSerializer {
     /// Constructor for the JsonSerializer object
     pub fn new() -> Self {
-        Self {}
+        Self {
+            numeric_format: NumericFormatOptions::default(),
+        }
+    }
+
+    /// Method for setting how numbers are rendered. Numbers formatted
+    /// differently than by default are written as JSON strings
+    pub fn with_numeric_format(mut self, numeric_format: NumericFormatOptions) -> Self {
+        self.numeric_format = numeric_format;
+        self
     }
 }
 
@@ -208,6 +221,7 @@ impl JsonSerializer {
 impl BatchSerializer for JsonSerializer {
     async fn serialize(&self, batch: RecordBatch, _initial: bool) -> Result<Bytes> {
         let mut buffer = Vec::with_capacity(4096);
+        let batch = format_numeric_columns(&batch, &self.numeric_format)?;
         let mut writer = json::LineDelimitedWriter::new(&mut buffer);
         writer.write(&batch)?;
         Ok(Bytes::from(buffer))
@@ -230,8 +244,8 @@ impl DisplayAs for JsonSink {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match t {
             DisplayFormatType::Default | DisplayFormatType::Verbose => {
-                write!(f, "JsonSink(file_groups=",)?;
-                FileGroupDisplay(&self.config.file_groups).fmt_as(t, f)?;
+                write!(f, "JsonSink(")?;
+                self.config.fmt_as(t, f)?;
                 write!(f, ")")
             }
         }
@@ -257,7 +271,11 @@ impl JsonSink {
         let writer_options = self.config.file_type_writer_options.try_into_json()?;
         let compression = &writer_options.compression;
 
-        let get_serializer = move || Arc::new(JsonSerializer::new()) as _;
+        let numeric_format = context.session_config().options().format.clone();
+        let get_serializer = move || {
+            Arc::new(JsonSerializer::new().with_numeric_format(numeric_format.clone()))
+                as _
+        };
 
         stateless_multipart_put(
             data,
@@ -84,6 +84,12 @@ pub trait FileFormat: Send + Sync + fmt::Debug {
         object: &ObjectMeta,
     ) -> Result<Statistics>;
 
+    /// Returns true if [`Self::infer_stats`] reports the exact number of rows
+    /// of a file, for example from the file metadata.
+    fn infers_exact_row_count(&self) -> bool {
+        false
+    }
+
     /// Take a list of files and convert it to the appropriate executor
     /// according to this file format.
     async fn create_physical_plan(
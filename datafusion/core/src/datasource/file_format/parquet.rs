@@ -63,9 +63,7 @@ use crate::arrow::array::{
 use crate::arrow::datatypes::DataType;
 use crate::config::ConfigOptions;
 
-use crate::datasource::physical_plan::{
-    FileGroupDisplay, FileSinkConfig, ParquetExec, SchemaAdapter,
-};
+use crate::datasource::physical_plan::{FileSinkConfig, ParquetExec, SchemaAdapter};
 use crate::error::Result;
 use crate::execution::context::SessionState;
 use crate::physical_plan::expressions::{MaxAccumulator, MinAccumulator};
@@ -248,6 +246,10 @@ impl FileFormat for ParquetFormat {
         Ok(stats)
     }
 
+    fn infers_exact_row_count(&self) -> bool {
+        true
+    }
+
     async fn create_physical_plan(
         &self,
         state: &SessionState,
@@ -636,8 +638,8 @@ impl DisplayAs for ParquetSink {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match t {
             DisplayFormatType::Default | DisplayFormatType::Verbose => {
-                write!(f, "ParquetSink(file_groups=",)?;
-                FileGroupDisplay(&self.config.file_groups).fmt_as(t, f)?;
+                write!(f, "ParquetSink(")?;
+                self.config.fmt_as(t, f)?;
                 write!(f, ")")
             }
         }
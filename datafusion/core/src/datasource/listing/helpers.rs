@@ -39,7 +39,7 @@ use crate::datasource::listing::ListingTableUrl;
 use crate::execution::context::SessionState;
 use datafusion_common::tree_node::{TreeNode, VisitRecursion};
 use datafusion_common::{internal_err, Column, DFField, DFSchema, DataFusionError};
-use datafusion_expr::{Expr, ScalarFunctionDefinition, Volatility};
+use datafusion_expr::{BinaryExpr, Expr, Operator, ScalarFunctionDefinition, Volatility};
 use datafusion_physical_expr::create_physical_expr;
 use datafusion_physical_expr::execution_props::ExecutionProps;
 use object_store::path::Path;
@@ -50,6 +50,9 @@ use object_store::{ObjectMeta, ObjectStore};
 /// - the table provider can filter the table partition values with this expression
 /// - the expression can be marked as `TableProviderFilterPushDown::Exact` once this filtering
 /// was performed
+///
+/// Stable functions such as `now()` are applicable, as the partitions are filtered with the
+/// [`ExecutionProps`] of the query.
 pub fn expr_applicable_for_cols(col_names: &[String], expr: &Expr) -> bool {
     let mut is_applicable = true;
     expr.apply(&mut |expr| {
@@ -90,33 +93,29 @@ pub fn expr_applicable_for_cols(col_names: &[String], expr: &Expr) -> bool {
             | Expr::GroupingSet(_)
             | Expr::Case { .. } => Ok(VisitRecursion::Continue),
 
-            Expr::ScalarFunction(scalar_function) => {
-                match &scalar_function.func_def {
-                    ScalarFunctionDefinition::BuiltIn(fun) => {
-                        match fun.volatility() {
-                            Volatility::Immutable => Ok(VisitRecursion::Continue),
-                            // TODO: Stable functions could be `applicable`, but that would require access to the context
-                            Volatility::Stable | Volatility::Volatile => {
-                                is_applicable = false;
-                                Ok(VisitRecursion::Stop)
-                            }
-                        }
+            Expr::ScalarFunction(scalar_function) => match &scalar_function.func_def {
+                ScalarFunctionDefinition::BuiltIn(fun) => match fun.volatility() {
+                    Volatility::Immutable | Volatility::Stable => {
+                        Ok(VisitRecursion::Continue)
                     }
-                    ScalarFunctionDefinition::UDF(fun) => {
-                        match fun.signature().volatility {
-                            Volatility::Immutable => Ok(VisitRecursion::Continue),
-                            // TODO: Stable functions could be `applicable`, but that would require access to the context
-                            Volatility::Stable | Volatility::Volatile => {
-                                is_applicable = false;
-                                Ok(VisitRecursion::Stop)
-                            }
-                        }
+                    Volatility::Volatile => {
+                        is_applicable = false;
+                        Ok(VisitRecursion::Stop)
                     }
-                    ScalarFunctionDefinition::Name(_) => {
-                        internal_err!("Function `Expr` with name should be resolved.")
+                },
+                ScalarFunctionDefinition::UDF(fun) => match fun.signature().volatility {
+                    Volatility::Immutable | Volatility::Stable => {
+                        Ok(VisitRecursion::Continue)
+                    }
+                    Volatility::Volatile => {
+                        is_applicable = false;
+                        Ok(VisitRecursion::Stop)
                     }
+                },
+                ScalarFunctionDefinition::Name(_) => {
+                    internal_err!("Function `Expr` with name should be resolved.")
                 }
-            }
+            },
 
             // TODO other expressions are not handled yet:
             // - AGGREGATE, WINDOW and SORT should not end up in filter conditions, except maybe in some edge cases
@@ -136,6 +135,40 @@ pub fn expr_applicable_for_cols(col_names: &[String], expr: &Expr) -> bool {
     is_applicable
 }
 
+/// Returns a predicate on the columns `col_names` implied by `expr`, if any.
+///
+/// Parts of a conjunction that reference other columns are dropped, e.g. the
+/// partition predicate of `(part = 'a' AND x > 1) OR part = 'b'` is
+/// `part = 'a' OR part = 'b'`. A disjunction only implies a predicate if all of
+/// its parts do.
+fn partition_filter(col_names: &[String], expr: &Expr) -> Option<Expr> {
+    if expr_applicable_for_cols(col_names, expr) {
+        return Some(expr.clone());
+    }
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => match (
+            partition_filter(col_names, left),
+            partition_filter(col_names, right),
+        ) {
+            (Some(left), Some(right)) => Some(left.and(right)),
+            (Some(filter), None) | (None, Some(filter)) => Some(filter),
+            (None, None) => None,
+        },
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Or,
+            right,
+        }) => Some(
+            partition_filter(col_names, left)?.or(partition_filter(col_names, right)?),
+        ),
+        _ => None,
+    }
+}
+
 /// The maximum number of concurrent listing requests
 const CONCURRENCY_LIMIT: usize = 100;
 
@@ -234,7 +267,16 @@ async fn prune_partitions(
     partitions: Vec<Partition>,
     filters: &[Expr],
     partition_cols: &[(String, DataType)],
+    props: &ExecutionProps,
 ) -> Result<Vec<Partition>> {
+    let col_names = partition_cols
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    let filters = filters
+        .iter()
+        .filter_map(|filter| partition_filter(&col_names, filter))
+        .collect::<Vec<_>>();
     if filters.is_empty() {
         return Ok(partitions);
     }
@@ -280,12 +322,9 @@ async fn prune_partitions(
 
     let batch = RecordBatch::try_new(schema.clone(), arrays)?;
 
-    // TODO: Plumb this down
-    let props = ExecutionProps::new();
-
     // Applies `filter` to `batch` returning `None` on error
     let do_filter = |filter| -> Option<ArrayRef> {
-        let expr = create_physical_expr(filter, &df_schema, &schema, &props).ok()?;
+        let expr = create_physical_expr(filter, &df_schema, &schema, props).ok()?;
         expr.evaluate(&batch)
             .ok()?
             .into_array(partitions.len())
@@ -349,8 +388,14 @@ pub async fn pruned_partition_list<'a>(
     let partitions = list_partitions(store, table_path, partition_cols.len()).await?;
     debug!("Listed {} partitions", partitions.len());
 
-    let pruned =
-        prune_partitions(table_path, partitions, filters, partition_cols).await?;
+    let pruned = prune_partitions(
+        table_path,
+        partitions,
+        filters,
+        partition_cols,
+        ctx.execution_props(),
+    )
+    .await?;
 
     debug!("Pruning yielded {} partitions", pruned.len());
 
@@ -436,7 +481,8 @@ mod tests {
 
     use futures::StreamExt;
 
-    use crate::logical_expr::{case, col, lit};
+    use crate::logical_expr::expr::ScalarFunction;
+    use crate::logical_expr::{case, col, lit, random, BuiltinScalarFunction};
     use crate::test::object_store::make_test_store_and_state;
 
     use super::*;
@@ -592,6 +638,73 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_pruned_partition_list_exprs() {
+        let (store, state) = make_test_store_and_state(&[
+            ("tablepath/year=2021/file.parquet", 100),
+            ("tablepath/year=2022/file.parquet", 100),
+            ("tablepath/year=2023/file.parquet", 100),
+            ("tablepath/year=2024/file.parquet", 100),
+        ]);
+        let prune = |filter: Expr| {
+            let (store, state) = (store.clone(), &state);
+            async move {
+                pruned_partition_list(
+                    state,
+                    store.as_ref(),
+                    &ListingTableUrl::parse("file:///tablepath/").unwrap(),
+                    &[filter],
+                    ".parquet",
+                    &[(String::from("year"), DataType::Int32)],
+                )
+                .await
+                .expect("partition pruning failed")
+                .map_ok(|f| f.partition_values[0].clone())
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap()
+            }
+        };
+        let years = |years: &[i32]| {
+            years
+                .iter()
+                .map(|y| ScalarValue::from(*y))
+                .collect::<Vec<_>>()
+        };
+
+        let filter = col("year").in_list(vec![lit(2021), lit(2023)], false);
+        assert_eq!(prune(filter).await, years(&[2021, 2023]));
+
+        let filter = col("year").between(lit(2022), lit(2023));
+        assert_eq!(prune(filter).await, years(&[2022, 2023]));
+
+        // the partition predicate `year = 2021 OR year = 2024` is implied
+        let filter = (col("year").eq(lit(2021)).and(col("other").gt(lit(1))))
+            .or(col("year").eq(lit(2024)));
+        assert_eq!(prune(filter).await, years(&[2021, 2024]));
+
+        // no partition predicate is implied
+        let filter = col("year").eq(lit(2021)).or(col("other").gt(lit(1)));
+        assert_eq!(prune(filter).await, years(&[2021, 2022, 2023, 2024]));
+    }
+
+    #[test]
+    fn test_partition_filter() {
+        let cols = [String::from("c1")];
+        let filter = col("c1").eq(lit(1)).and(col("c2").eq(lit(2)));
+        assert_eq!(partition_filter(&cols, &filter), Some(col("c1").eq(lit(1))));
+
+        let filter = (col("c1").eq(lit(1)).and(col("c2").eq(lit(2))))
+            .or(col("c1").eq(lit(3)).and(col("c2").eq(lit(4))));
+        assert_eq!(
+            partition_filter(&cols, &filter),
+            Some(col("c1").eq(lit(1)).or(col("c1").eq(lit(3))))
+        );
+
+        let filter = col("c1").eq(lit(1)).or(col("c2").eq(lit(2)));
+        assert_eq!(partition_filter(&cols, &filter), None);
+    }
+
     #[test]
     fn test_parse_partitions_for_path() {
         assert_eq!(
@@ -690,6 +803,21 @@ mod tests {
                 .otherwise(lit(false))
                 .expect("valid case expr"))
         ));
+        // stable functions are evaluated with the props of the query
+        assert!(expr_applicable_for_cols(
+            &[String::from("c1")],
+            &Expr::lt(
+                col("c1"),
+                Expr::ScalarFunction(ScalarFunction::new(
+                    BuiltinScalarFunction::Now,
+                    vec![]
+                ))
+            )
+        ));
+        assert!(!expr_applicable_for_cols(
+            &[String::from("c1")],
+            &Expr::lt(col("c1"), random())
+        ));
         // static expression not relvant in this context but we
         // test it as an edge case anyway in case we want to generalize
         // this helper function
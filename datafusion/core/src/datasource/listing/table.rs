@@ -38,7 +38,7 @@ use crate::datasource::{
     },
     get_statistics_with_limit,
     listing::ListingTableUrl,
-    physical_plan::{FileScanConfig, FileSinkConfig},
+    physical_plan::{FileScanConfig, FileSinkConfig, PartitionValuesExec},
     TableProvider, TableType,
 };
 use crate::{
@@ -50,6 +50,7 @@ use crate::{
 
 use arrow::datatypes::{DataType, Field, SchemaBuilder, SchemaRef};
 use arrow_schema::Schema;
+use datafusion_common::stats::Precision;
 use datafusion_common::{
     internal_err, plan_err, project_schema, Constraints, FileType, FileTypeWriterOptions,
     SchemaExt, ToDFSchema,
@@ -62,6 +63,7 @@ use datafusion_physical_expr::{
 
 use async_trait::async_trait;
 use futures::{future, stream, StreamExt, TryStreamExt};
+use object_store::path::Path;
 
 /// Configuration for creating a [`ListingTable`]
 #[derive(Debug, Clone)]
@@ -641,8 +643,24 @@ impl TableProvider for ListingTable {
         filters: &[Expr],
         limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let (partitioned_file_lists, statistics) =
-            self.list_files_for_scan(state, filters, limit).await?;
+        // a scan that only counts rows, such as for `count(*)`, or that only
+        // reads partition columns collects the file statistics so that it can
+        // be answered without reading any data
+        let exact_row_count = limit.is_none()
+            && self.supports_exact_row_count(&filters.iter().collect::<Vec<_>>())?;
+        let partition_values_only = exact_row_count
+            && projection
+                .map(|p| {
+                    !p.is_empty()
+                        && p.iter().all(|i| *i >= self.file_schema.fields().len())
+                })
+                .unwrap_or(false);
+        let collect_stat = self.options.collect_stat
+            || partition_values_only
+            || (exact_row_count && projection.map(|p| p.is_empty()).unwrap_or(false));
+        let (partitioned_file_lists, statistics) = self
+            .list_files_for_scan(state, filters, limit, collect_stat)
+            .await?;
 
         // if no files need to be read, return an `EmptyExec`
         if partitioned_file_lists.is_empty() {
@@ -678,23 +696,34 @@ impl TableProvider for ListingTable {
         } else {
             return Ok(Arc::new(EmptyExec::new(Arc::new(Schema::empty()))));
         };
+        let file_scan_config = FileScanConfig {
+            object_store_url,
+            file_schema: Arc::clone(&self.file_schema),
+            file_groups: partitioned_file_lists,
+            statistics,
+            projection: projection.cloned(),
+            limit,
+            output_ordering: self.try_create_output_ordering()?,
+            table_partition_cols,
+        };
+
+        // the partition values and row counts of the files are known
+        // without reading them
+        if partition_values_only {
+            if let Some(row_counts) =
+                self.cached_row_counts(&file_scan_config.file_groups)
+            {
+                return Ok(Arc::new(PartitionValuesExec::try_new(
+                    file_scan_config,
+                    row_counts,
+                )?));
+            }
+        }
+
         // create the execution plan
         self.options
             .format
-            .create_physical_plan(
-                state,
-                FileScanConfig {
-                    object_store_url,
-                    file_schema: Arc::clone(&self.file_schema),
-                    file_groups: partitioned_file_lists,
-                    statistics,
-                    projection: projection.cloned(),
-                    limit,
-                    output_ordering: self.try_create_output_ordering()?,
-                    table_partition_cols,
-                },
-                filters.as_ref(),
-            )
+            .create_physical_plan(state, file_scan_config, filters.as_ref())
             .await
     }
 
@@ -720,6 +749,21 @@ impl TableProvider for ListingTable {
         }
     }
 
+    /// The row count is exact if the file format reads it from the file
+    /// metadata and all `filters` are handled by partition pruning.
+    fn supports_exact_row_count(&self, filters: &[&Expr]) -> Result<bool> {
+        let partition_cols = self
+            .options
+            .table_partition_cols
+            .iter()
+            .map(|x| x.0.clone())
+            .collect::<Vec<_>>();
+        Ok(self.options.format.infers_exact_row_count()
+            && filters
+                .iter()
+                .all(|filter| expr_applicable_for_cols(&partition_cols, filter)))
+    }
+
     fn get_table_definition(&self) -> Option<&str> {
         self.definition.as_deref()
     }
@@ -826,6 +870,7 @@ impl ListingTable {
         ctx: &'a SessionState,
         filters: &'a [Expr],
         limit: Option<usize>,
+        collect_stat: bool,
     ) -> Result<(Vec<Vec<PartitionedFile>>, Statistics)> {
         let store = if let Some(url) = self.table_paths.first() {
             ctx.runtime_env().object_store(url)?
@@ -845,12 +890,12 @@ impl ListingTable {
         }))
         .await?;
         let file_list = stream::iter(file_list).flatten();
-        // collect the statistics if required by the config
+        // collect the statistics if required by the config or the scan
         let files = file_list
             .map(|part_file| async {
                 let part_file = part_file?;
                 let mut statistics_result = Statistics::new_unknown(&self.file_schema);
-                if self.options.collect_stat {
+                if collect_stat {
                     let statistics_cache = self.collected_statistics.clone();
                     match statistics_cache.get_with_extra(
                         &part_file.object_meta.location,
@@ -893,6 +938,29 @@ impl ListingTable {
             statistics,
         ))
     }
+
+    /// Get the exact number of rows of each file from the collected
+    /// statistics, or `None` if it is unknown for any of them
+    fn cached_row_counts(
+        &self,
+        file_groups: &[Vec<PartitionedFile>],
+    ) -> Option<HashMap<Path, usize>> {
+        file_groups
+            .iter()
+            .flatten()
+            .map(|file| {
+                let statistics = self
+                    .collected_statistics
+                    .get_with_extra(&file.object_meta.location, &file.object_meta)?;
+                match statistics.num_rows {
+                    Precision::Exact(num_rows) => {
+                        Some((file.object_meta.location.clone(), num_rows))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -992,6 +1060,38 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "parquet")]
+    #[tokio::test]
+    async fn load_table_stats_for_row_count() -> Result<()> {
+        let testdata = crate::test_util::parquet_test_data();
+        let filename = format!("{}/{}", testdata, "alltypes_plain.parquet");
+        let table_path = ListingTableUrl::parse(filename).unwrap();
+
+        let ctx = SessionContext::new();
+        let state = ctx.state();
+
+        let opt = ListingOptions::new(Arc::new(ParquetFormat::default()))
+            .with_collect_stat(false);
+        let schema = opt.infer_schema(&state, &table_path).await?;
+        let config = ListingTableConfig::new(table_path)
+            .with_listing_options(opt)
+            .with_schema(schema);
+        let table = ListingTable::try_new(config)?;
+        assert!(table.supports_exact_row_count(&[])?);
+        assert!(!table.supports_exact_row_count(&[&col("id").gt(lit(1))])?);
+
+        // only the row count is needed, so the statistics are collected
+        let exec = table.scan(&state, Some(&vec![]), &[], None).await?;
+        assert_eq!(exec.statistics()?.num_rows, Precision::Exact(8));
+
+        let exec = table
+            .scan(&state, Some(&vec![]), &[col("id").gt(lit(1))], None)
+            .await?;
+        assert_eq!(exec.statistics()?.num_rows, Precision::Absent);
+
+        Ok(())
+    }
+
     #[cfg(feature = "parquet")]
     #[tokio::test]
     async fn test_try_create_output_ordering() {
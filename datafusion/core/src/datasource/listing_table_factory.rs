@@ -21,6 +21,7 @@ use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use crate::datasource::arrow_json::read_schema_file;
 #[cfg(feature = "parquet")]
 use crate::datasource::file_format::parquet::ParquetFormat;
 use crate::datasource::file_format::{
@@ -34,7 +35,7 @@ use crate::datasource::provider::TableProviderFactory;
 use crate::datasource::TableProvider;
 use crate::execution::context::SessionState;
 
-use arrow::datatypes::{DataType, SchemaRef};
+use arrow::datatypes::{DataType, Schema, SchemaRef};
 use datafusion_common::file_options::{FileTypeWriterOptions, StatementOptions};
 use datafusion_common::{arrow_datafusion_err, plan_err, DataFusionError, FileType};
 use datafusion_expr::CreateExternalTable;
@@ -90,8 +91,16 @@ impl TableProviderFactory for ListingTableFactory {
             FileType::ARROW => Arc::new(ArrowFormat),
         };
 
-        let (provided_schema, table_partition_cols) = if cmd.schema.fields().is_empty() {
-            (
+        let declared_schema: Option<SchemaRef> = match &cmd.schema_location {
+            Some(location) => {
+                Some(Arc::new(read_schema_location(state, location).await?))
+            }
+            None if cmd.schema.fields().is_empty() => None,
+            None => Some(Arc::new(cmd.schema.as_ref().to_owned().into())),
+        };
+
+        let (provided_schema, table_partition_cols) = match declared_schema {
+            None => (
                 None,
                 cmd.table_partition_cols
                     .iter()
@@ -105,32 +114,32 @@ impl TableProviderFactory for ListingTableFactory {
                         )
                     })
                     .collect::<Vec<_>>(),
-            )
-        } else {
-            let schema: SchemaRef = Arc::new(cmd.schema.as_ref().to_owned().into());
-            let table_partition_cols = cmd
-                .table_partition_cols
-                .iter()
-                .map(|col| {
-                    schema
-                        .field_with_name(col)
-                        .map_err(|e| arrow_datafusion_err!(e))
-                })
-                .collect::<datafusion_common::Result<Vec<_>>>()?
-                .into_iter()
-                .map(|f| (f.name().to_owned(), f.data_type().to_owned()))
-                .collect();
-            // exclude partition columns to support creating partitioned external table
-            // with a specified column definition like
-            // `create external table a(c0 int, c1 int) stored as csv partitioned by (c1)...`
-            let mut project_idx = Vec::new();
-            for i in 0..schema.fields().len() {
-                if !cmd.table_partition_cols.contains(schema.field(i).name()) {
-                    project_idx.push(i);
+            ),
+            Some(schema) => {
+                let table_partition_cols = cmd
+                    .table_partition_cols
+                    .iter()
+                    .map(|col| {
+                        schema
+                            .field_with_name(col)
+                            .map_err(|e| arrow_datafusion_err!(e))
+                    })
+                    .collect::<datafusion_common::Result<Vec<_>>>()?
+                    .into_iter()
+                    .map(|f| (f.name().to_owned(), f.data_type().to_owned()))
+                    .collect();
+                // exclude partition columns to support creating partitioned external table
+                // with a specified column definition like
+                // `create external table a(c0 int, c1 int) stored as csv partitioned by (c1)...`
+                let mut project_idx = Vec::new();
+                for i in 0..schema.fields().len() {
+                    if !cmd.table_partition_cols.contains(schema.field(i).name()) {
+                        project_idx.push(i);
+                    }
                 }
+                let schema = Arc::new(schema.project(&project_idx)?);
+                (Some(schema), table_partition_cols)
             }
-            let schema = Arc::new(schema.project(&project_idx)?);
-            (Some(schema), table_partition_cols)
         };
 
         let mut statement_options = StatementOptions::from(&cmd.options);
@@ -210,6 +219,17 @@ impl TableProviderFactory for ListingTableFactory {
     }
 }
 
+/// Reads the schema file at `location`, given by the `SCHEMA` clause
+async fn read_schema_location(
+    state: &SessionState,
+    location: &str,
+) -> datafusion_common::Result<Schema> {
+    let url = ListingTableUrl::parse(location)?;
+    let store = state.runtime_env().object_store(&url)?;
+    let contents = store.get(url.prefix()).await?.bytes().await?;
+    read_schema_file(&contents)
+}
+
 // Get file extension from path
 fn get_extension(path: &str) -> String {
     let res = Path::new(path).extension().and_then(|ext| ext.to_str());
@@ -257,6 +277,7 @@ mod tests {
             options: HashMap::new(),
             constraints: Constraints::empty(),
             column_defaults: HashMap::new(),
+            schema_location: None,
         };
         let table_provider = factory.create(&state, &cmd).await.unwrap();
         let listing_table = table_provider
@@ -24,15 +24,20 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::{self, Debug};
 use std::sync::Arc;
+use std::time::Duration;
 
-use arrow::datatypes::SchemaRef;
+use arrow::array::BooleanArray;
+use arrow::compute::{cast, concat_batches, filter_record_batch};
+use arrow::datatypes::{DataType, SchemaRef, TimeUnit};
 use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, SortField};
 use async_trait::async_trait;
+use datafusion_common::cast::as_int64_array;
 use datafusion_common::{
     not_impl_err, plan_err, Constraints, DataFusionError, SchemaExt,
 };
 use datafusion_execution::TaskContext;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tokio::task::JoinSet;
 
 use crate::datasource::{TableProvider, TableType};
@@ -48,16 +53,125 @@ use crate::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan};
 /// Type alias for partition data
 pub type PartitionData = Arc<RwLock<Vec<RecordBatch>>>;
 
+/// The number of batches of each partition of a [`MemTable`] at each of
+/// its versions. Inserts only append batches, so the data of a version is
+/// a prefix of each partition.
+type VersionData = Arc<parking_lot::RwLock<Vec<Vec<usize>>>>;
+
 /// In-memory data source for presenting a `Vec<RecordBatch>` as a
 /// data source that can be queried by DataFusion. This allows data to
 /// be pre-loaded into memory and then repeatedly queried without
 /// incurring additional file I/O overhead.
+///
+/// The initial data of the table is version 0, and each `INSERT` creates
+/// a new version. A scan reads the latest version at the time it is
+/// planned, so it is not affected by concurrent inserts, and
+/// [`MemTable::at_version`] reads an earlier version.
+///
+/// Tables written by long running, or unbounded, queries can keep only
+/// their latest rows with [`MemTable::with_retention`].
 #[derive(Debug)]
 pub struct MemTable {
     schema: SchemaRef,
     pub(crate) batches: Vec<PartitionData>,
     constraints: Constraints,
     column_defaults: HashMap<String, Expr>,
+    versions: VersionData,
+    /// Serializes inserts, so that each version appends to all the
+    /// partitions at once
+    write_lock: Arc<Mutex<()>>,
+    /// The version read by this table, or `None` for the latest version
+    version: Option<usize>,
+    /// The rows kept by inserts, or `None` to keep all the rows
+    retention: Option<Arc<TableRetention>>,
+}
+
+/// The rows of each key kept by a [`MemTable`], see
+/// [`MemTable::with_retention`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Retention {
+    /// Keep the last `n` rows inserted of each key
+    LastRows(usize),
+    /// Keep the rows of each key whose time, in the timestamp column
+    /// `time_column`, is within `interval` of the latest time of the key.
+    /// Rows with a null time are not kept.
+    LastInterval {
+        /// The name of the timestamp column
+        time_column: String,
+        /// How much earlier than the latest time of their key rows are kept
+        interval: Duration,
+    },
+}
+
+/// A [`Retention`] resolved against the schema of a [`MemTable`]
+#[derive(Debug)]
+struct TableRetention {
+    /// The indices of the key columns
+    key: Vec<usize>,
+    /// The rows kept of each key
+    retained: RetainedRows,
+}
+
+#[derive(Debug)]
+enum RetainedRows {
+    /// The last `n` rows
+    LastRows(usize),
+    /// The rows within `interval`, in the unit of the time column, of the
+    /// latest time
+    LastInterval { time: usize, interval: i64 },
+}
+
+impl TableRetention {
+    /// Returns the rows of `batch` to keep, in their order
+    fn retain(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        let rows = if self.key.is_empty() {
+            None
+        } else {
+            let keys = self
+                .key
+                .iter()
+                .map(|i| batch.column(*i).clone())
+                .collect::<Vec<_>>();
+            let fields = keys
+                .iter()
+                .map(|key| SortField::new(key.data_type().clone()))
+                .collect();
+            Some(RowConverter::new(fields)?.convert_columns(&keys)?)
+        };
+        let key = |i: usize| rows.as_ref().map(|rows| rows.row(i));
+
+        let mut keep = vec![false; num_rows];
+        match &self.retained {
+            RetainedRows::LastRows(n) => {
+                let mut counts = HashMap::new();
+                for i in (0..num_rows).rev() {
+                    let count = counts.entry(key(i)).or_insert(0);
+                    if *count < *n {
+                        keep[i] = true;
+                        *count += 1;
+                    }
+                }
+            }
+            RetainedRows::LastInterval { time, interval } => {
+                let times = cast(batch.column(*time), &DataType::Int64)?;
+                let times = as_int64_array(&times)?;
+                let mut latest = HashMap::new();
+                for (i, time) in times.iter().enumerate() {
+                    if let Some(time) = time {
+                        let latest = latest.entry(key(i)).or_insert(time);
+                        *latest = time.max(*latest);
+                    }
+                }
+                for (i, time) in times.iter().enumerate() {
+                    keep[i] = time.is_some_and(|time| {
+                        time >= latest[&key(i)].saturating_sub(*interval)
+                    });
+                }
+            }
+        }
+        Ok(filter_record_batch(batch, &BooleanArray::from(keep))?)
+    }
 }
 
 impl MemTable {
@@ -74,6 +188,7 @@ impl MemTable {
             }
         }
 
+        let batch_counts = partitions.iter().map(|batches| batches.len()).collect();
         Ok(Self {
             schema,
             batches: partitions
@@ -82,6 +197,39 @@ impl MemTable {
                 .collect::<Vec<_>>(),
             constraints: Constraints::empty(),
             column_defaults: HashMap::new(),
+            versions: Arc::new(parking_lot::RwLock::new(vec![batch_counts])),
+            write_lock: Arc::new(Mutex::new(())),
+            version: None,
+            retention: None,
+        })
+    }
+
+    /// Returns the latest version of this table
+    pub fn version(&self) -> usize {
+        self.versions.read().len() - 1
+    }
+
+    /// Returns a read-only table of the data of this table at `version`,
+    /// which later inserts into this table do not change
+    pub fn at_version(&self, version: usize) -> Result<Self> {
+        if self.retention.is_some() {
+            return plan_err!("Tables with a retention only keep their latest version");
+        }
+        let latest = self.version();
+        if version > latest {
+            return plan_err!(
+                "Version {version} of the table does not exist, the latest version is {latest}"
+            );
+        }
+        Ok(Self {
+            schema: self.schema.clone(),
+            batches: self.batches.clone(),
+            constraints: self.constraints.clone(),
+            column_defaults: self.column_defaults.clone(),
+            versions: self.versions.clone(),
+            write_lock: self.write_lock.clone(),
+            version: Some(version),
+            retention: None,
         })
     }
 
@@ -100,6 +248,45 @@ impl MemTable {
         self
     }
 
+    /// Keep only the `retention` rows of each value of the `key` columns,
+    /// or of the whole table if `key` is empty.
+    ///
+    /// Inserts into the table then write their rows as they arrive, rather
+    /// than once their input ends, so that the table can be written by
+    /// unbounded queries without growing without bound. Each insert
+    /// removes the rows that are not retained any longer, and the table
+    /// keeps only its latest version.
+    pub fn with_retention(mut self, key: &[&str], retention: Retention) -> Result<Self> {
+        let key = key
+            .iter()
+            .map(|name| Ok(self.schema.index_of(name)?))
+            .collect::<Result<Vec<_>>>()?;
+        let retained = match &retention {
+            Retention::LastRows(n) => RetainedRows::LastRows(*n),
+            Retention::LastInterval {
+                time_column,
+                interval,
+            } => {
+                let time = self.schema.index_of(time_column)?;
+                let nanos = i64::try_from(interval.as_nanos()).unwrap_or(i64::MAX);
+                let interval = match self.schema.field(time).data_type() {
+                    DataType::Timestamp(TimeUnit::Second, _) => nanos / 1_000_000_000,
+                    DataType::Timestamp(TimeUnit::Millisecond, _) => nanos / 1_000_000,
+                    DataType::Timestamp(TimeUnit::Microsecond, _) => nanos / 1_000,
+                    DataType::Timestamp(TimeUnit::Nanosecond, _) => nanos,
+                    other => {
+                        return plan_err!(
+                            "Retention expects a timestamp column, {time_column} is of type {other}"
+                        )
+                    }
+                };
+                RetainedRows::LastInterval { time, interval }
+            }
+        };
+        self.retention = Some(Arc::new(TableRetention { key, retained }));
+        Ok(self)
+    }
+
     /// Create a mem table by reading from another data source
     pub async fn load(
         t: Arc<dyn TableProvider>,
@@ -187,12 +374,30 @@ impl TableProvider for MemTable {
         _state: &SessionState,
         projection: Option<&Vec<usize>>,
         _filters: &[Expr],
-        _limit: Option<usize>,
+        limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
+        let read = |batches: &[RecordBatch]| match limit {
+            Some(limit) => limit_batches(batches, limit),
+            None => batches.to_vec(),
+        };
         let mut partitions = vec![];
-        for arc_inner_vec in self.batches.iter() {
-            let inner_vec = arc_inner_vec.read().await;
-            partitions.push(inner_vec.clone())
+        if self.retention.is_some() {
+            // inserts rewrite all the partitions at once, so they are all
+            // locked before being read
+            let mut guards = vec![];
+            for arc_inner_vec in &self.batches {
+                guards.push(arc_inner_vec.read().await);
+            }
+            partitions.extend(guards.iter().map(|inner_vec| read(&inner_vec[..])));
+        } else {
+            let batch_counts = {
+                let versions = self.versions.read();
+                versions[self.version.unwrap_or(versions.len() - 1)].clone()
+            };
+            for (arc_inner_vec, batch_count) in self.batches.iter().zip(batch_counts) {
+                let inner_vec = arc_inner_vec.read().await;
+                partitions.push(read(&inner_vec[..batch_count]))
+            }
         }
         Ok(Arc::new(MemoryExec::try_new(
             &partitions,
@@ -232,7 +437,16 @@ impl TableProvider for MemTable {
         if overwrite {
             return not_impl_err!("Overwrite not implemented for MemoryTable yet");
         }
-        let sink = Arc::new(MemSink::new(self.batches.clone()));
+        if let Some(version) = self.version {
+            return plan_err!("Cannot insert into version {version} of a table");
+        }
+        let sink = Arc::new(MemSink::new(
+            self.schema.clone(),
+            self.batches.clone(),
+            self.versions.clone(),
+            self.write_lock.clone(),
+            self.retention.clone(),
+        ));
         Ok(Arc::new(FileSinkExec::new(
             input,
             sink,
@@ -246,10 +460,32 @@ impl TableProvider for MemTable {
     }
 }
 
+/// Returns the first `limit` rows of `batches`
+fn limit_batches(batches: &[RecordBatch], limit: usize) -> Vec<RecordBatch> {
+    let mut remaining = limit;
+    let mut limited = vec![];
+    for batch in batches {
+        if remaining == 0 {
+            break;
+        }
+        let num_rows = batch.num_rows().min(remaining);
+        limited.push(batch.slice(0, num_rows));
+        remaining -= num_rows;
+    }
+    limited
+}
+
 /// Implements for writing to a [`MemTable`]
 struct MemSink {
+    /// The schema of the table
+    schema: SchemaRef,
     /// Target locations for writing data
     batches: Vec<PartitionData>,
+    /// The versions of the table, extended by each write
+    versions: VersionData,
+    write_lock: Arc<Mutex<()>>,
+    /// The rows kept by each write, or `None` to keep all the rows
+    retention: Option<Arc<TableRetention>>,
 }
 
 impl Debug for MemSink {
@@ -272,8 +508,66 @@ impl DisplayAs for MemSink {
 }
 
 impl MemSink {
-    fn new(batches: Vec<PartitionData>) -> Self {
-        Self { batches }
+    fn new(
+        schema: SchemaRef,
+        batches: Vec<PartitionData>,
+        versions: VersionData,
+        write_lock: Arc<Mutex<()>>,
+        retention: Option<Arc<TableRetention>>,
+    ) -> Self {
+        Self {
+            schema,
+            batches,
+            versions,
+            write_lock,
+            retention,
+        }
+    }
+
+    /// Writes each batch of `data` as it arrives, keeping the rows of the
+    /// table retained by `retention`.
+    ///
+    /// The retained rows are in the order of the partitions, so that each
+    /// write keeps the latest rows, and are split evenly over them. The
+    /// latest version of the table is rewritten rather than extended.
+    async fn write_retained(
+        &self,
+        mut data: SendableRecordBatchStream,
+        retention: &TableRetention,
+    ) -> Result<u64> {
+        let num_partitions = self.batches.len();
+        let mut row_count = 0;
+        while let Some(batch) = data.next().await.transpose()? {
+            row_count += batch.num_rows();
+
+            let _write_guard = self.write_lock.lock().await;
+            let mut targets = vec![];
+            for target in &self.batches {
+                targets.push(target.write().await);
+            }
+            let batches = targets
+                .iter()
+                .flat_map(|target| target.iter())
+                .chain([&batch]);
+            let retained = retention.retain(&concat_batches(&self.schema, batches)?)?;
+
+            let num_rows = retained.num_rows();
+            let chunk_size = (num_rows + num_partitions - 1) / num_partitions;
+            let mut batch_counts = Vec::with_capacity(num_partitions);
+            for (i, target) in targets.iter_mut().enumerate() {
+                let offset = (i * chunk_size).min(num_rows);
+                let len = chunk_size.min(num_rows - offset);
+                **target = match len {
+                    0 => vec![],
+                    _ => vec![retained.slice(offset, len)],
+                };
+                batch_counts.push(target.len());
+            }
+            if let Some(latest) = self.versions.write().last_mut() {
+                *latest = batch_counts;
+            }
+        }
+        Ok(row_count as u64)
     }
 }
 
@@ -292,6 +586,9 @@ impl DataSink for MemSink {
         mut data: SendableRecordBatchStream,
         _context: &Arc<TaskContext>,
     ) -> Result<u64> {
+        if let Some(retention) = &self.retention {
+            return self.write_retained(data, retention).await;
+        }
         let num_partitions = self.batches.len();
 
         // buffer up the data round robin style into num_partitions
@@ -305,11 +602,17 @@ impl DataSink for MemSink {
             i = (i + 1) % num_partitions;
         }
 
-        // write the outputs into the batches
+        // write the outputs into the batches, and then make them visible to
+        // new scans as a new version
+        let _write_guard = self.write_lock.lock().await;
+        let mut batch_counts = Vec::with_capacity(num_partitions);
         for (target, mut batches) in self.batches.iter().zip(new_batches.into_iter()) {
             // Append all the new batches in one go to minimize locking overhead
-            target.write().await.append(&mut batches);
+            let mut target = target.write().await;
+            target.append(&mut batches);
+            batch_counts.push(target.len());
         }
+        self.versions.write().push(batch_counts);
 
         Ok(row_count as u64)
     }
@@ -322,7 +625,7 @@ mod tests {
     use crate::physical_plan::collect;
     use crate::prelude::SessionContext;
     use arrow::array::{AsArray, Int32Array};
-    use arrow::datatypes::{DataType, Field, Schema, UInt64Type};
+    use arrow::datatypes::{DataType, Field, Schema, TimeUnit, UInt64Type};
     use arrow::error::ArrowError;
     use datafusion_expr::LogicalPlanBuilder;
     use futures::StreamExt;
@@ -396,6 +699,33 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_with_limit() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+
+        let provider = MemTable::try_new(
+            schema,
+            vec![vec![batch.clone(), batch.clone()], vec![batch]],
+        )?;
+
+        let exec = provider
+            .scan(&session_ctx.state(), None, &[], Some(4))
+            .await?;
+        let batches = collect(exec, task_ctx).await?;
+        let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        // each partition returns at most 4 rows
+        assert_eq!(num_rows, 7);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_invalid_projection() -> Result<()> {
         let session_ctx = SessionContext::new();
@@ -716,4 +1046,149 @@ mod tests {
         assert_eq!(resulting_data_in_table[0].len(), 2);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_insert_with_retention() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("k", DataType::Int32, false),
+            Field::new("v", DataType::Int32, false),
+        ]));
+        let table = MemTable::try_new(schema.clone(), vec![vec![], vec![]])?
+            .with_retention(&["k"], Retention::LastRows(2))?;
+        let table = Arc::new(table);
+        let session_ctx = SessionContext::new();
+        session_ctx.register_table("t", table.clone())?;
+
+        for values in ["(1, 1), (2, 2), (1, 3)", "(1, 4), (2, 5), (2, 6), (2, 7)"] {
+            session_ctx
+                .sql(&format!("INSERT INTO t VALUES {values}"))
+                .await?
+                .collect()
+                .await?;
+        }
+        let batches = session_ctx
+            .sql("SELECT * FROM t ORDER BY v")
+            .await?
+            .collect()
+            .await?;
+        let expected = [
+            "+---+---+",
+            "| k | v |",
+            "+---+---+",
+            "| 1 | 3 |",
+            "| 1 | 4 |",
+            "| 2 | 6 |",
+            "| 2 | 7 |",
+            "+---+---+",
+        ];
+        crate::assert_batches_eq!(expected, &batches);
+
+        // the table keeps only its latest version
+        assert_eq!(table.version(), 0);
+        let err = table.at_version(0).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Tables with a retention only keep their latest version"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_with_interval_retention() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ts", DataType::Timestamp(TimeUnit::Second, None), true),
+            Field::new("v", DataType::Int32, false),
+        ]));
+        let retention = Retention::LastInterval {
+            time_column: "ts".to_string(),
+            interval: Duration::from_secs(60),
+        };
+        let table = MemTable::try_new(schema.clone(), vec![vec![]])?
+            .with_retention(&[], retention)?;
+        let session_ctx = SessionContext::new();
+        session_ctx.register_table("t", Arc::new(table))?;
+
+        session_ctx
+            .sql(
+                "INSERT INTO t VALUES \
+                 ('2024-01-01T00:00:00', 1), ('2024-01-01T00:01:00', 2), \
+                 (NULL, 3), ('2024-01-01T00:01:30', 4)",
+            )
+            .await?
+            .collect()
+            .await?;
+        let batches = session_ctx
+            .sql("SELECT v FROM t ORDER BY v")
+            .await?
+            .collect()
+            .await?;
+        let expected = ["+---+", "| v |", "+---+", "| 2 |", "| 4 |", "+---+"];
+        crate::assert_batches_eq!(expected, &batches);
+
+        let err = MemTable::try_new(schema, vec![vec![]])?
+            .with_retention(
+                &[],
+                Retention::LastInterval {
+                    time_column: "v".to_string(),
+                    interval: Duration::from_secs(60),
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Retention expects a timestamp column, v is of type Int32"
+        );
+        Ok(())
+    }
+
+    async fn num_rows(table: &MemTable, session_ctx: &SessionContext) -> Result<usize> {
+        let exec = table.scan(&session_ctx.state(), None, &[], None).await?;
+        let batches = collect(exec, session_ctx.task_ctx()).await?;
+        Ok(batches.iter().map(|batch| batch.num_rows()).sum())
+    }
+
+    #[tokio::test]
+    async fn test_read_previous_version() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let table = Arc::new(MemTable::try_new(schema, vec![vec![batch]])?);
+        let session_ctx = SessionContext::new();
+        session_ctx.register_table("t", table.clone())?;
+        assert_eq!(table.version(), 0);
+
+        session_ctx
+            .sql("INSERT INTO t VALUES (4), (5)")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(table.version(), 1);
+
+        assert_eq!(num_rows(&table, &session_ctx).await?, 5);
+        assert_eq!(num_rows(&table.at_version(0)?, &session_ctx).await?, 3);
+        assert_eq!(num_rows(&table.at_version(1)?, &session_ctx).await?, 5);
+
+        let err = table.at_version(2).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Version 2 of the table does not exist, the latest version is 1"
+        );
+
+        // earlier versions are read-only
+        session_ctx.register_table("t0", Arc::new(table.at_version(0)?))?;
+        let err = session_ctx
+            .sql("INSERT INTO t0 VALUES (6)")
+            .await?
+            .collect()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Cannot insert into version 0 of a table"
+        );
+        assert_eq!(table.version(), 1);
+        Ok(())
+    }
 }
@@ -19,7 +19,9 @@
 //!
 //! [`ListingTable`]: crate::datasource::listing::ListingTable
 
+pub mod arrow_json;
 pub mod avro_to_arrow;
+pub mod cte_worktable;
 pub mod default_table_source;
 pub mod empty;
 pub mod file_format;
@@ -40,7 +42,7 @@ pub use datafusion_execution::object_store;
 pub use self::default_table_source::{
     provider_as_source, source_as_provider, DefaultTableSource,
 };
-pub use self::memory::MemTable;
+pub use self::memory::{MemTable, Retention};
 pub use self::provider::TableProvider;
 pub use self::view::ViewTable;
 pub use crate::logical_expr::TableType;
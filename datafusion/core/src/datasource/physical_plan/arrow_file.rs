@@ -132,7 +132,8 @@ impl ExecutionPlan for ArrowExec {
             projection: self.base_config.projection.clone(),
         };
         let stream =
-            FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
+            FileStream::new(&self.base_config, partition, opener, &self.metrics)?
+                .with_cancellation_token(context.cancellation_token().clone());
         Ok(Box::pin(stream))
     }
 
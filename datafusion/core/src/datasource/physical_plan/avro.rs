@@ -137,14 +137,17 @@ impl ExecutionPlan for AvroExec {
 
         let config = Arc::new(private::AvroConfig {
             schema: Arc::clone(&self.base_config.file_schema),
-            batch_size: context.session_config().batch_size(),
+            batch_size: context
+                .session_config()
+                .batch_size_for_schema(&self.schema()),
             projection: self.base_config.projected_file_column_names(),
             object_store,
         });
         let opener = private::AvroOpener { config };
 
         let stream =
-            FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
+            FileStream::new(&self.base_config, partition, opener, &self.metrics)?
+                .with_cancellation_token(context.cancellation_token().clone());
         Ok(Box::pin(stream))
     }
 
@@ -209,8 +209,17 @@ impl ExecutionPlan for CsvExec {
             .runtime_env()
             .object_store(&self.base_config.object_store_url)?;
 
+        // read no more rows than the limit in one batch, so that the
+        // file stream can stop as soon as it has produced enough rows
+        let batch_size = context
+            .session_config()
+            .batch_size_for_schema(&self.schema());
+        let batch_size = match self.base_config.limit {
+            Some(limit) => batch_size.min(limit.max(1)),
+            None => batch_size,
+        };
         let config = Arc::new(CsvConfig {
-            batch_size: context.session_config().batch_size(),
+            batch_size,
             file_schema: Arc::clone(&self.base_config.file_schema),
             file_projection: self.base_config.file_column_projection_indices(),
             has_header: self.has_header,
@@ -225,7 +234,8 @@ impl ExecutionPlan for CsvExec {
             file_compression_type: self.file_compression_type.to_owned(),
         };
         let stream =
-            FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
+            FileStream::new(&self.base_config, partition, opener, &self.metrics)?
+                .with_cancellation_token(context.cancellation_token().clone());
         Ok(Box::pin(stream) as SendableRecordBatchStream)
     }
 
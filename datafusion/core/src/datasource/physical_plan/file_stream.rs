@@ -41,6 +41,7 @@ use arrow::datatypes::SchemaRef;
 use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::ScalarValue;
+use datafusion_execution::CancellationToken;
 
 use futures::future::BoxFuture;
 use futures::stream::BoxStream;
@@ -98,6 +99,8 @@ pub struct FileStream<F: FileOpener> {
     baseline_metrics: BaselineMetrics,
     /// Describes the behavior of the `FileStream` if file opening or scanning fails
     on_error: OnError,
+    /// Stops the scan when the query is cancelled
+    cancellation: CancellationToken,
 }
 
 /// Represents the state of the next `FileOpenFuture`. Since we need to poll
@@ -200,6 +203,11 @@ struct FileStreamMetrics {
     /// If using `OnError::Skip` this will provide a count of the number of files
     /// which were skipped and will not be included in the scan results.
     pub file_scan_errors: Count,
+    /// Count of files (or file ranges) of the partition, reported as the
+    /// total of [`QueryProgress`](crate::physical_plan::QueryProgress)
+    pub files_total: Count,
+    /// Count of files (or file ranges) read to the end
+    pub files_scanned: Count,
 }
 
 impl FileStreamMetrics {
@@ -234,6 +242,11 @@ impl FileStreamMetrics {
         let file_scan_errors =
             MetricBuilder::new(metrics).counter("file_scan_errors", partition);
 
+        let files_total = MetricBuilder::new(metrics).counter("files_total", partition);
+
+        let files_scanned =
+            MetricBuilder::new(metrics).counter("files_scanned", partition);
+
         Self {
             time_opening,
             time_scanning_until_data,
@@ -241,6 +254,8 @@ impl FileStreamMetrics {
             time_processing,
             file_open_errors,
             file_scan_errors,
+            files_total,
+            files_scanned,
         }
     }
 }
@@ -264,6 +279,8 @@ impl<F: FileOpener> FileStream<F> {
         );
 
         let files = config.file_groups[partition].clone();
+        let file_stream_metrics = FileStreamMetrics::new(metrics, partition);
+        file_stream_metrics.files_total.add(files.len());
 
         Ok(Self {
             file_iter: files.into(),
@@ -272,9 +289,10 @@ impl<F: FileOpener> FileStream<F> {
             file_reader,
             pc_projector,
             state: FileStreamState::Idle,
-            file_stream_metrics: FileStreamMetrics::new(metrics, partition),
+            file_stream_metrics,
             baseline_metrics: BaselineMetrics::new(metrics, partition),
             on_error: OnError::Fail,
+            cancellation: CancellationToken::new(),
         })
     }
 
@@ -287,6 +305,12 @@ impl<F: FileOpener> FileStream<F> {
         self
     }
 
+    /// Specify the [`CancellationToken`] checked before reading each batch
+    pub fn with_cancellation_token(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
     /// Begin opening the next file in parallel while decoding the current file in FileStream.
     ///
     /// Since file opening is mostly IO (and may involve a
@@ -308,6 +332,12 @@ impl<F: FileOpener> FileStream<F> {
     }
 
     fn poll_inner(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<RecordBatch>>> {
+        if !matches!(self.state, FileStreamState::Error | FileStreamState::Limit) {
+            if let Err(e) = self.cancellation.check() {
+                self.state = FileStreamState::Error;
+                return Poll::Ready(Some(Err(e)));
+            }
+        }
         loop {
             match &mut self.state {
                 FileStreamState::Idle => {
@@ -461,6 +491,7 @@ impl<F: FileOpener> FileStream<F> {
                         None => {
                             self.file_stream_metrics.time_scanning_until_data.stop();
                             self.file_stream_metrics.time_scanning_total.stop();
+                            self.file_stream_metrics.files_scanned.add(1);
 
                             match mem::take(next) {
                                 Some((future, partition_values)) => {
@@ -84,6 +84,11 @@ impl NdJsonExec {
     pub fn base_config(&self) -> &FileScanConfig {
         &self.base_config
     }
+
+    /// The compression type of the files being read
+    pub fn file_compression_type(&self) -> FileCompressionType {
+        self.file_compression_type
+    }
 }
 
 impl DisplayAs for NdJsonExec {
@@ -163,7 +168,15 @@ impl ExecutionPlan for NdJsonExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let batch_size = context.session_config().batch_size();
+        // read no more rows than the limit in one batch, so that the
+        // file stream can stop as soon as it has produced enough rows
+        let batch_size = context
+            .session_config()
+            .batch_size_for_schema(&self.schema());
+        let batch_size = match self.base_config.limit {
+            Some(limit) => batch_size.min(limit.max(1)),
+            None => batch_size,
+        };
         let (projected_schema, ..) = self.base_config.project();
 
         let object_store = context
@@ -177,7 +190,8 @@ impl ExecutionPlan for NdJsonExec {
         };
 
         let stream =
-            FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
+            FileStream::new(&self.base_config, partition, opener, &self.metrics)?
+                .with_cancellation_token(context.cancellation_token().clone());
 
         Ok(Box::pin(stream) as SendableRecordBatchStream)
     }
@@ -26,6 +26,7 @@ mod file_stream;
 mod json;
 #[cfg(feature = "parquet")]
 pub mod parquet;
+mod partition_values;
 pub use file_groups::FileGroupPartitioner;
 use futures::StreamExt;
 
@@ -43,6 +44,7 @@ pub use file_scan_config::{
 };
 pub use file_stream::{FileOpenFuture, FileOpener, FileStream, OnError};
 pub use json::{JsonOpener, NdJsonExec};
+pub use partition_values::PartitionValuesExec;
 
 use std::{
     fmt::{Debug, Formatter, Result as FmtResult},
@@ -68,6 +70,8 @@ use arrow::{
     datatypes::{DataType, Schema, SchemaRef},
     record_batch::{RecordBatch, RecordBatchOptions},
 };
+use datafusion_common::file_options::csv_writer::CsvWriterOptions;
+use datafusion_common::file_options::json_writer::JsonWriterOptions;
 use datafusion_common::{file_options::FileTypeWriterOptions, plan_err};
 use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::PhysicalSortExpr;
@@ -76,6 +80,8 @@ use datafusion_physical_plan::ExecutionPlan;
 use log::debug;
 use object_store::ObjectMeta;
 use object_store::{path::Path, GetOptions, ObjectStore};
+#[cfg(feature = "parquet")]
+use parquet::schema::types::ColumnPath;
 
 /// The base configurations to provide when creating a physical plan for
 /// writing to any given file format.
@@ -108,6 +114,57 @@ impl FileSinkConfig {
     }
 }
 
+/// Displays the files the sink writes to and how, e.g. for `EXPLAIN` of
+/// `COPY` and `INSERT INTO` statements
+impl DisplayAs for FileSinkConfig {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> FmtResult {
+        write!(f, "file_groups=")?;
+        FileGroupDisplay(&self.file_groups).fmt_as(t, f)?;
+        let output = self
+            .table_paths
+            .iter()
+            .map(|path| path.prefix().as_ref())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            ", output={output}, single_file_output={}",
+            self.single_file_output
+        )?;
+        if !self.table_partition_cols.is_empty() {
+            let columns = self
+                .table_partition_cols
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, ", partition_by=[{columns}]")?;
+        }
+        if self.overwrite {
+            write!(f, ", overwrite=true")?;
+        }
+        match &self.file_type_writer_options {
+            #[cfg(feature = "parquet")]
+            FileTypeWriterOptions::Parquet(options) => {
+                let properties = &options.writer_options;
+                write!(
+                    f,
+                    ", compression={:?}, max_row_group_size={}",
+                    properties.compression(&ColumnPath::new(vec![])),
+                    properties.max_row_group_size()
+                )
+            }
+            FileTypeWriterOptions::CSV(CsvWriterOptions { compression, .. })
+            | FileTypeWriterOptions::JSON(JsonWriterOptions { compression })
+                if compression.is_compressed() =>
+            {
+                write!(f, ", compression={}", compression.to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 impl Debug for FileScanConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(f, "object_store_url={:?}, ", self.object_store_url)?;
@@ -324,7 +381,10 @@ impl SchemaAdapter {
             if let Some((table_idx, table_field)) =
                 self.table_schema.fields().find(file_field.name())
             {
-                match can_cast_types(file_field.data_type(), table_field.data_type()) {
+                let table_type = table_field.data_type();
+                match can_cast_types(file_field.data_type(), table_type)
+                    || is_pruned_struct(file_field.data_type(), table_type)
+                {
                     true => {
                         field_mappings[table_idx] = Some(projection.len());
                         projection.push(file_idx);
@@ -351,6 +411,28 @@ impl SchemaAdapter {
     }
 }
 
+/// Returns true if `table_type` is `file_type` with some fields of (nested)
+/// structs removed, which file readers can skip instead of casting
+fn is_pruned_struct(file_type: &DataType, table_type: &DataType) -> bool {
+    match (file_type, table_type) {
+        (DataType::Struct(file_fields), DataType::Struct(table_fields)) => {
+            table_fields.iter().all(|table_field| {
+                file_fields
+                    .find(table_field.name())
+                    .map(|(_, file_field)| {
+                        file_field.data_type() == table_field.data_type()
+                            || is_pruned_struct(
+                                file_field.data_type(),
+                                table_field.data_type(),
+                            )
+                    })
+                    .unwrap_or(false)
+            })
+        }
+        _ => false,
+    }
+}
+
 /// The SchemaMapping struct holds a mapping from the file schema to the table schema
 /// and any necessary type conversions that need to be applied.
 #[derive(Debug)]
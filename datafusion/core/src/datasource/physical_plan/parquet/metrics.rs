@@ -31,14 +31,32 @@ pub struct ParquetFileMetrics {
     pub predicate_evaluation_errors: Count,
     /// Number of row groups pruned using
     pub row_groups_pruned: Count,
+    /// Number of row groups whose statistics matched the predicate
+    pub row_groups_matched_statistics: Count,
+    /// Number of row groups pruned by their statistics
+    pub row_groups_pruned_statistics: Count,
+    /// Number of row groups whose bloom filters matched the predicate
+    pub row_groups_matched_bloom_filter: Count,
+    /// Number of row groups pruned by their bloom filters
+    pub row_groups_pruned_bloom_filter: Count,
+    /// Number of row groups with rows selected by the parquet page index
+    pub row_groups_matched_page_index: Count,
+    /// Number of row groups with all their rows filtered out by the parquet
+    /// page index
+    pub row_groups_pruned_page_index: Count,
     /// Total number of bytes scanned
     pub bytes_scanned: Count,
-    /// Total rows filtered out by predicates pushed into parquet scan
+    /// Total rows filtered out by predicates pushed into parquet scan. The
+    /// other projected columns are not decoded for these rows
     pub pushdown_rows_filtered: Count,
+    /// Total rows passing all the predicates pushed into parquet scan
+    pub pushdown_rows_matched: Count,
     /// Total time spent evaluating pushdown filters
     pub pushdown_eval_time: Time,
     /// Total rows filtered out by parquet page index
     pub page_index_rows_filtered: Count,
+    /// Total rows selected by parquet page index
+    pub page_index_rows_matched: Count,
     /// Total time spent evaluating parquet page index filters
     pub page_index_eval_time: Time,
 }
@@ -58,6 +76,30 @@ impl ParquetFileMetrics {
             .with_new_label("filename", filename.to_string())
             .counter("row_groups_pruned", partition);
 
+        let row_groups_matched_statistics = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("row_groups_matched_statistics", partition);
+
+        let row_groups_pruned_statistics = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("row_groups_pruned_statistics", partition);
+
+        let row_groups_matched_bloom_filter = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("row_groups_matched_bloom_filter", partition);
+
+        let row_groups_pruned_bloom_filter = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("row_groups_pruned_bloom_filter", partition);
+
+        let row_groups_matched_page_index = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("row_groups_matched_page_index", partition);
+
+        let row_groups_pruned_page_index = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("row_groups_pruned_page_index", partition);
+
         let bytes_scanned = MetricBuilder::new(metrics)
             .with_new_label("filename", filename.to_string())
             .counter("bytes_scanned", partition);
@@ -66,6 +108,10 @@ impl ParquetFileMetrics {
             .with_new_label("filename", filename.to_string())
             .counter("pushdown_rows_filtered", partition);
 
+        let pushdown_rows_matched = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("pushdown_rows_matched", partition);
+
         let pushdown_eval_time = MetricBuilder::new(metrics)
             .with_new_label("filename", filename.to_string())
             .subset_time("pushdown_eval_time", partition);
@@ -73,6 +119,10 @@ impl ParquetFileMetrics {
             .with_new_label("filename", filename.to_string())
             .counter("page_index_rows_filtered", partition);
 
+        let page_index_rows_matched = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("page_index_rows_matched", partition);
+
         let page_index_eval_time = MetricBuilder::new(metrics)
             .with_new_label("filename", filename.to_string())
             .subset_time("page_index_eval_time", partition);
@@ -80,10 +130,18 @@ impl ParquetFileMetrics {
         Self {
             predicate_evaluation_errors,
             row_groups_pruned,
+            row_groups_matched_statistics,
+            row_groups_pruned_statistics,
+            row_groups_matched_bloom_filter,
+            row_groups_pruned_bloom_filter,
+            row_groups_matched_page_index,
+            row_groups_pruned_page_index,
             bytes_scanned,
             pushdown_rows_filtered,
+            pushdown_rows_matched,
             pushdown_eval_time,
             page_index_rows_filtered,
+            page_index_rows_matched,
             page_index_eval_time,
         }
     }
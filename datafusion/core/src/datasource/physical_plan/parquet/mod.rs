@@ -18,6 +18,7 @@
 //! Execution plan for reading Parquet files
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::Range;
 use std::sync::Arc;
@@ -36,14 +37,17 @@ use crate::{
     execution::context::TaskContext,
     physical_optimizer::pruning::PruningPredicate,
     physical_plan::{
+        joins::DynamicFilter,
         metrics::{ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
         DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
         Statistics,
     },
 };
 
-use arrow::datatypes::{DataType, SchemaRef};
+use arrow::datatypes::{DataType, Schema, SchemaRef};
 use arrow::error::ArrowError;
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column};
 use datafusion_physical_expr::{
     EquivalenceProperties, LexOrdering, PhysicalExpr, PhysicalSortExpr,
 };
@@ -59,7 +63,7 @@ use parquet::arrow::async_reader::{AsyncFileReader, ParquetObjectReader};
 use parquet::arrow::{AsyncArrowWriter, ParquetRecordBatchStreamBuilder, ProjectionMask};
 use parquet::basic::{ConvertedType, LogicalType};
 use parquet::file::{metadata::ParquetMetaData, properties::WriterProperties};
-use parquet::schema::types::ColumnDescriptor;
+use parquet::schema::types::{ColumnDescriptor, SchemaDescriptor};
 use tokio::task::JoinSet;
 
 mod metrics;
@@ -102,6 +106,9 @@ pub struct ParquetExec {
     metadata_size_hint: Option<usize>,
     /// Optional user defined parquet file reader factory
     parquet_file_reader_factory: Option<Arc<dyn ParquetFileReaderFactory>>,
+    /// Optional runtime filter from a hash join, along with the file schema
+    /// columns it applies to
+    dynamic_filter: Option<(Arc<DynamicFilter>, Vec<Column>)>,
 }
 
 impl ParquetExec {
@@ -165,6 +172,7 @@ impl ParquetExec {
             page_pruning_predicate,
             metadata_size_hint,
             parquet_file_reader_factory: None,
+            dynamic_filter: None,
         }
     }
 
@@ -183,6 +191,54 @@ impl ParquetExec {
         self.pruning_predicate.as_ref()
     }
 
+    /// Prune row groups using the join key bounds published to
+    /// `dynamic_filter` by a [`HashJoinExec`] at runtime.
+    ///
+    /// `columns` refer to the file schema and match the build side join keys
+    /// in order. The filter is only consulted when a file is opened, so files
+    /// opened before the build side completes are not pruned.
+    ///
+    /// [`HashJoinExec`]: crate::physical_plan::joins::HashJoinExec
+    pub fn with_dynamic_filter(
+        mut self,
+        dynamic_filter: Arc<DynamicFilter>,
+        columns: Vec<Column>,
+    ) -> Self {
+        self.dynamic_filter = Some((dynamic_filter, columns));
+        self
+    }
+
+    /// Optional runtime filter from a hash join
+    pub fn dynamic_filter(&self) -> Option<&(Arc<DynamicFilter>, Vec<Column>)> {
+        self.dynamic_filter.as_ref()
+    }
+
+    /// Only read some of the fields of struct columns.
+    ///
+    /// Each of `struct_columns` is the index of a column in the file schema
+    /// and its data type with the unused fields of (nested) structs removed.
+    pub fn with_pruned_struct_columns(
+        mut self,
+        struct_columns: Vec<(usize, DataType)>,
+    ) -> Self {
+        let file_schema = &self.base_config.file_schema;
+        let mut fields = file_schema.fields().to_vec();
+        for (index, data_type) in struct_columns {
+            fields[index] =
+                Arc::new(fields[index].as_ref().clone().with_data_type(data_type));
+        }
+        self.base_config.file_schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            file_schema.metadata().clone(),
+        ));
+        (
+            self.projected_schema,
+            self.projected_statistics,
+            self.projected_output_ordering,
+        ) = self.base_config.project();
+        self
+    }
+
     /// Optional user defined parquet file reader factory.
     ///
     /// `ParquetFileReaderFactory` complements `TableProvider`, It enables users to provide custom
@@ -281,9 +337,26 @@ impl DisplayAs for ParquetExec {
                     .map(|pre| format!(", pruning_predicate={}", pre.predicate_expr()))
                     .unwrap_or_default();
 
+                let dynamic_filter_string = self
+                    .dynamic_filter
+                    .as_ref()
+                    .map(|(_, columns)| {
+                        let columns = columns
+                            .iter()
+                            .map(|c| c.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        format!(", dynamic_filter=[{columns}]")
+                    })
+                    .unwrap_or_default();
+
                 write!(f, "ParquetExec: ")?;
                 self.base_config.fmt_as(t, f)?;
-                write!(f, "{}{}", predicate_string, pruning_predicate_string,)
+                write!(
+                    f,
+                    "{}{}{}",
+                    predicate_string, pruning_predicate_string, dynamic_filter_string
+                )
             }
         }
     }
@@ -378,7 +451,7 @@ impl ExecutionPlan for ParquetExec {
         let opener = ParquetOpener {
             partition_index,
             projection: Arc::from(projection),
-            batch_size: ctx.session_config().batch_size(),
+            batch_size: ctx.session_config().batch_size_for_schema(&self.schema()),
             limit: self.base_config.limit,
             predicate: self.predicate.clone(),
             pruning_predicate: self.pruning_predicate.clone(),
@@ -391,10 +464,12 @@ impl ExecutionPlan for ParquetExec {
             reorder_filters: self.reorder_filters(config_options),
             enable_page_index: self.enable_page_index(config_options),
             enable_bloom_filter: self.enable_bloom_filter(config_options),
+            dynamic_filter: self.dynamic_filter.clone(),
         };
 
         let stream =
-            FileStream::new(&self.base_config, partition_index, opener, &self.metrics)?;
+            FileStream::new(&self.base_config, partition_index, opener, &self.metrics)?
+                .with_cancellation_token(ctx.cancellation_token().clone());
 
         Ok(Box::pin(stream))
     }
@@ -425,6 +500,43 @@ struct ParquetOpener {
     reorder_filters: bool,
     enable_page_index: bool,
     enable_bloom_filter: bool,
+    dynamic_filter: Option<(Arc<DynamicFilter>, Vec<Column>)>,
+}
+
+impl ParquetOpener {
+    /// Returns the predicate used to prune row groups of the next file,
+    /// combining the static predicate with the current bounds of the dynamic
+    /// filter, and whether the dynamic filter rules out every row
+    fn current_pruning_predicate(&self) -> (Option<Arc<PruningPredicate>>, bool) {
+        let Some((dynamic_filter, columns)) = &self.dynamic_filter else {
+            return (self.pruning_predicate.clone(), false);
+        };
+        if dynamic_filter.matches_none() {
+            return (self.pruning_predicate.clone(), true);
+        }
+        let Some(dynamic_predicate) = dynamic_filter.predicate(columns) else {
+            return (self.pruning_predicate.clone(), false);
+        };
+
+        let predicate = match &self.predicate {
+            Some(predicate) => Arc::new(BinaryExpr::new(
+                predicate.clone(),
+                Operator::And,
+                dynamic_predicate,
+            )),
+            None => dynamic_predicate,
+        };
+        let pruning_predicate =
+            match PruningPredicate::try_new(predicate, self.table_schema.clone()) {
+                Ok(p) if !p.allways_true() => Some(Arc::new(p)),
+                Ok(_) => self.pruning_predicate.clone(),
+                Err(e) => {
+                    debug!("Could not create dynamic pruning predicate: {e}");
+                    self.pruning_predicate.clone()
+                }
+            };
+        (pruning_predicate, false)
+    }
 }
 
 impl FileOpener for ParquetOpener {
@@ -448,9 +560,9 @@ impl FileOpener for ParquetOpener {
         let batch_size = self.batch_size;
         let projection = self.projection.clone();
         let projected_schema = SchemaRef::from(self.table_schema.project(&projection)?);
-        let schema_adapter = SchemaAdapter::new(projected_schema);
+        let schema_adapter = SchemaAdapter::new(projected_schema.clone());
         let predicate = self.predicate.clone();
-        let pruning_predicate = self.pruning_predicate.clone();
+        let (pruning_predicate, matches_none) = self.current_pruning_predicate();
         let page_pruning_predicate = self.page_pruning_predicate.clone();
         let table_schema = self.table_schema.clone();
         let reorder_predicates = self.reorder_filters;
@@ -458,6 +570,7 @@ impl FileOpener for ParquetOpener {
         let enable_page_index = should_enable_page_index(
             self.enable_page_index,
             &self.page_pruning_predicate,
+            pushdown_filters && predicate.is_some(),
         );
         let enable_bloom_filter = self.enable_bloom_filter;
         let limit = self.limit;
@@ -474,9 +587,11 @@ impl FileOpener for ParquetOpener {
                 schema_adapter.map_schema(&file_schema)?;
             // let predicate = predicate.map(|p| reassign_predicate_columns(p, builder.schema(), true)).transpose()?;
 
-            let mask = ProjectionMask::roots(
+            let mask = projection_mask(
                 builder.parquet_schema(),
-                adapted_projections.iter().cloned(),
+                &file_schema,
+                &projected_schema,
+                &adapted_projections,
             );
 
             // Filter pushdown: evaluate predicates during scan
@@ -517,6 +632,13 @@ impl FileOpener for ParquetOpener {
                 &file_metrics,
             );
 
+            // Dynamic filter pruning: the join build side has no keys, so no
+            // row of this file can produce a match
+            if matches_none {
+                file_metrics.row_groups_pruned.add(row_groups.len());
+                row_groups.clear();
+            }
+
             // Bloom filter pruning: if bloom filters are enabled and then attempt to skip entire row_groups
             // using bloom filters on the row groups
             if enable_bloom_filter && !row_groups.is_empty() {
@@ -568,16 +690,77 @@ impl FileOpener for ParquetOpener {
     }
 }
 
+/// Returns the [`ProjectionMask`] reading the `projection` columns of
+/// `file_schema`.
+///
+/// Only the fields of struct columns present in the type of the matching
+/// column of `table_schema` are read, see
+/// [`ParquetExec::with_pruned_struct_columns`].
+fn projection_mask(
+    parquet_schema: &SchemaDescriptor,
+    file_schema: &Schema,
+    table_schema: &Schema,
+    projection: &[usize],
+) -> ProjectionMask {
+    // the table types of the projected struct columns that have pruned fields
+    let pruned_types = projection
+        .iter()
+        .filter_map(|file_idx| {
+            let file_field = file_schema.field(*file_idx);
+            let (_, table_field) = table_schema.fields().find(file_field.name())?;
+            let is_pruned = matches!(table_field.data_type(), DataType::Struct(_))
+                && table_field.data_type() != file_field.data_type();
+            is_pruned.then(|| (*file_idx, table_field.data_type()))
+        })
+        .collect::<HashMap<_, _>>();
+    if pruned_types.is_empty() {
+        return ProjectionMask::roots(parquet_schema, projection.iter().cloned());
+    }
+
+    let leaves = (0..parquet_schema.num_columns()).filter(|leaf_idx| {
+        let root_idx = parquet_schema.get_column_root_idx(*leaf_idx);
+        if !projection.contains(&root_idx) {
+            return false;
+        }
+        match pruned_types.get(&root_idx) {
+            Some(data_type) => {
+                let path = parquet_schema.column(*leaf_idx).path().parts();
+                is_projected_path(data_type, &path[1..])
+            }
+            None => true,
+        }
+    });
+    ProjectionMask::leaves(parquet_schema, leaves)
+}
+
+/// Returns true if the leaf column at the nested field `path` of a
+/// column of type `data_type` is read.
+fn is_projected_path(data_type: &DataType, path: &[String]) -> bool {
+    match (data_type, path.split_first()) {
+        (DataType::Struct(fields), Some((name, path))) => fields
+            .find(name)
+            .map(|(_, field)| is_projected_path(field.data_type(), path))
+            .unwrap_or(false),
+        // lists and maps are read entirely
+        _ => true,
+    }
+}
+
+/// Returns true if the page index should be read: it is used to prune the
+/// pages with the `page_pruning_predicate`, and, when a row filter is
+/// pushed down, to skip the pages of the other columns whose rows all fail
+/// the filter rather than decoding them.
 fn should_enable_page_index(
     enable_page_index: bool,
     page_pruning_predicate: &Option<Arc<PagePruningPredicate>>,
+    pushdown_row_filter: bool,
 ) -> bool {
     enable_page_index
-        && page_pruning_predicate.is_some()
-        && page_pruning_predicate
-            .as_ref()
-            .map(|p| p.filter_number() > 0)
-            .unwrap_or(false)
+        && (pushdown_row_filter
+            || page_pruning_predicate
+                .as_ref()
+                .map(|p| p.filter_number() > 0)
+                .unwrap_or(false))
 }
 
 /// Factory of parquet file readers.
@@ -804,6 +987,7 @@ mod tests {
         predicate: Option<Expr>,
         pushdown_predicate: bool,
         page_index_predicate: bool,
+        dynamic_filter: Option<(Arc<DynamicFilter>, Vec<Column>)>,
     }
 
     impl RoundTrip {
@@ -836,6 +1020,15 @@ mod tests {
             self
         }
 
+        fn with_dynamic_filter(
+            mut self,
+            dynamic_filter: Arc<DynamicFilter>,
+            columns: Vec<Column>,
+        ) -> Self {
+            self.dynamic_filter = Some((dynamic_filter, columns));
+            self
+        }
+
         /// run the test, returning only the resulting RecordBatches
         async fn round_trip_to_batches(
             self,
@@ -852,6 +1045,7 @@ mod tests {
                 predicate,
                 pushdown_predicate,
                 page_index_predicate,
+                dynamic_filter,
             } = self;
 
             let file_schema = match schema {
@@ -898,6 +1092,10 @@ mod tests {
                 parquet_exec = parquet_exec.with_enable_page_index(true);
             }
 
+            if let Some((dynamic_filter, columns)) = dynamic_filter {
+                parquet_exec = parquet_exec.with_dynamic_filter(dynamic_filter, columns);
+            }
+
             let session_ctx = SessionContext::new();
             let task_ctx = session_ctx.task_ctx();
             let parquet_exec = Arc::new(parquet_exec);
@@ -1139,6 +1337,59 @@ mod tests {
         let metrics = rt.parquet_exec.metrics().unwrap();
         // Note there are were 6 rows in total (across three batches)
         assert_eq!(get_value(&metrics, "pushdown_rows_filtered"), 4);
+        assert_eq!(get_value(&metrics, "pushdown_rows_matched"), 2);
+    }
+
+    #[tokio::test]
+    async fn dynamic_filter_prunes_row_groups() {
+        let batch1 = create_batch(vec![(
+            "c1",
+            Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef,
+        )]);
+        let batch2 = create_batch(vec![(
+            "c1",
+            Arc::new(Int32Array::from(vec![10, 11, 12])) as ArrayRef,
+        )]);
+
+        // bounds published by the build side of a join
+        let dynamic_filter = Arc::new(DynamicFilter::new());
+        dynamic_filter.update(vec![(
+            ScalarValue::Int32(Some(9)),
+            ScalarValue::Int32(Some(20)),
+        )]);
+
+        let rt = RoundTrip::new()
+            .with_dynamic_filter(dynamic_filter, vec![Column::new("c1", 0)])
+            .round_trip(vec![batch1, batch2])
+            .await;
+
+        let expected = [
+            "+----+", "| c1 |", "+----+", "| 10 |", "| 11 |", "| 12 |", "+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &rt.batches.unwrap());
+        let metrics = rt.parquet_exec.metrics().unwrap();
+        assert_eq!(get_value(&metrics, "row_groups_pruned"), 1);
+    }
+
+    #[tokio::test]
+    async fn dynamic_filter_matches_none() {
+        let batch = create_batch(vec![(
+            "c1",
+            Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef,
+        )]);
+
+        // the build side of the join had no non-null keys
+        let dynamic_filter = Arc::new(DynamicFilter::new());
+        dynamic_filter.update(vec![(ScalarValue::Int32(None), ScalarValue::Int32(None))]);
+
+        let rt = RoundTrip::new()
+            .with_dynamic_filter(dynamic_filter, vec![Column::new("c1", 0)])
+            .round_trip(vec![batch])
+            .await;
+
+        assert!(rt.batches.unwrap().is_empty());
+        let metrics = rt.parquet_exec.metrics().unwrap();
+        assert_eq!(get_value(&metrics, "row_groups_pruned"), 1);
     }
 
     #[tokio::test]
@@ -1365,6 +1616,11 @@ mod tests {
         // batch4 for a total of 12. batch1 had no pruning as c2 was
         // filled in as null
         assert_eq!(get_value(&metrics, "page_index_rows_filtered"), 12);
+        // 2 rows are selected in each of batch2, batch3 and batch4, and
+        // all 6 rows of batch1, so no row group is pruned
+        assert_eq!(get_value(&metrics, "page_index_rows_matched"), 12);
+        assert_eq!(get_value(&metrics, "row_groups_matched_page_index"), 4);
+        assert_eq!(get_value(&metrics, "row_groups_pruned_page_index"), 0);
     }
 
     #[tokio::test]
@@ -221,7 +221,20 @@ impl PagePruningPredicate {
                     }
                 },
             );
+        let total_select = final_selection
+            .iter()
+            .filter(|x| !x.skip)
+            .map(|x| x.row_count)
+            .sum();
         file_metrics.page_index_rows_filtered.add(total_skip);
+        file_metrics.page_index_rows_matched.add(total_select);
+
+        let (matched, pruned) = count_selected_row_groups(
+            &final_selection,
+            row_groups.iter().map(|r| groups[*r].num_rows() as usize),
+        );
+        file_metrics.row_groups_matched_page_index.add(matched);
+        file_metrics.row_groups_pruned_page_index.add(pruned);
         Ok(Some(final_selection))
     }
 
@@ -306,6 +319,41 @@ fn combine_multi_col_selection(row_selections: Vec<Vec<RowSelector>>) -> RowSele
         .unwrap()
 }
 
+/// Returns the number of row groups, of `row_counts` rows each, that have
+/// rows selected by `selection`, and the number that have none.
+fn count_selected_row_groups(
+    selection: &RowSelection,
+    row_counts: impl IntoIterator<Item = usize>,
+) -> (usize, usize) {
+    let mut selectors = selection.iter().cloned();
+    // the part of a selector left over from the previous row group
+    let mut current: Option<RowSelector> = None;
+    let (mut matched, mut pruned) = (0, 0);
+    for mut remaining in row_counts {
+        let mut selected = false;
+        while remaining > 0 {
+            let Some(selector) = current.take().or_else(|| selectors.next()) else {
+                break;
+            };
+            let taken = selector.row_count.min(remaining);
+            selected |= !selector.skip && taken > 0;
+            remaining -= taken;
+            if taken < selector.row_count {
+                current = Some(RowSelector {
+                    row_count: selector.row_count - taken,
+                    skip: selector.skip,
+                });
+            }
+        }
+        if selected {
+            matched += 1;
+        } else {
+            pruned += 1;
+        }
+    }
+    (matched, pruned)
+}
+
 fn prune_pages_in_one_row_group(
     group: &RowGroupMetaData,
     predicate: &PruningPredicate,
@@ -74,6 +74,8 @@ pub(crate) struct DatafusionArrowPredicate {
     projection: Vec<usize>,
     /// how many rows were filtered out by this predicate
     rows_filtered: metrics::Count,
+    /// how many rows passed this predicate, if it is the last one
+    rows_matched: Option<metrics::Count>,
     /// how long was spent evaluating this predicate
     time: metrics::Time,
 }
@@ -105,6 +107,7 @@ impl DatafusionArrowPredicate {
                 candidate.projection,
             ),
             rows_filtered,
+            rows_matched: None,
             time,
         })
     }
@@ -130,8 +133,11 @@ impl ArrowPredicate for DatafusionArrowPredicate {
         {
             Ok(array) => {
                 let bool_arr = as_boolean_array(&array)?.clone();
-                let num_filtered = bool_arr.len() - bool_arr.true_count();
-                self.rows_filtered.add(num_filtered);
+                let num_matched = bool_arr.true_count();
+                self.rows_filtered.add(bool_arr.len() - num_matched);
+                if let Some(rows_matched) = &self.rows_matched {
+                    rows_matched.add(num_matched);
+                }
                 timer.stop();
                 Ok(bool_arr)
             }
@@ -337,56 +343,41 @@ pub fn build_row_filter(
         .collect();
 
     if candidates.is_empty() {
-        Ok(None)
-    } else if reorder_predicates {
+        return Ok(None);
+    }
+
+    if reorder_predicates {
         candidates.sort_by_key(|c| c.required_bytes);
 
         let (indexed_candidates, other_candidates): (Vec<_>, Vec<_>) =
             candidates.into_iter().partition(|c| c.can_use_index);
+        candidates = indexed_candidates;
+        candidates.extend(other_candidates);
+    }
 
-        let mut filters: Vec<Box<dyn ArrowPredicate>> = vec![];
-
-        for candidate in indexed_candidates {
-            let filter = DatafusionArrowPredicate::try_new(
-                candidate,
-                file_schema,
-                metadata,
-                rows_filtered.clone(),
-                time.clone(),
-            )?;
-
-            filters.push(Box::new(filter));
-        }
-
-        for candidate in other_candidates {
-            let filter = DatafusionArrowPredicate::try_new(
-                candidate,
-                file_schema,
-                metadata,
-                rows_filtered.clone(),
-                time.clone(),
-            )?;
-
-            filters.push(Box::new(filter));
-        }
-
-        Ok(Some(RowFilter::new(filters)))
-    } else {
-        let mut filters: Vec<Box<dyn ArrowPredicate>> = vec![];
-        for candidate in candidates {
-            let filter = DatafusionArrowPredicate::try_new(
+    let mut filters = candidates
+        .into_iter()
+        .map(|candidate| {
+            DatafusionArrowPredicate::try_new(
                 candidate,
                 file_schema,
                 metadata,
                 rows_filtered.clone(),
                 time.clone(),
-            )?;
-
-            filters.push(Box::new(filter));
-        }
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-        Ok(Some(RowFilter::new(filters)))
+    // The last predicate only sees the rows passing all the previous ones
+    if let Some(last) = filters.last_mut() {
+        last.rows_matched = Some(file_metrics.pushdown_rows_matched.clone());
     }
+
+    let filters = filters
+        .into_iter()
+        .map(|filter| Box::new(filter) as Box<dyn ArrowPredicate>)
+        .collect();
+    Ok(Some(RowFilter::new(filters)))
 }
 
 #[cfg(test)]
@@ -82,8 +82,10 @@ pub(crate) fn prune_row_groups_by_statistics(
                     // NB: false means don't scan row group
                     if !values[0] {
                         metrics.row_groups_pruned.add(1);
+                        metrics.row_groups_pruned_statistics.add(1);
                         continue;
                     }
+                    metrics.row_groups_matched_statistics.add(1);
                 }
                 // stats filter array could not be built
                 // return a closure which will not filter out any row groups
@@ -160,7 +162,9 @@ pub(crate) async fn prune_row_groups_by_bloom_filters<
 
         if prune_group {
             metrics.row_groups_pruned.add(1);
+            metrics.row_groups_pruned_bloom_filter.add(1);
         } else {
+            metrics.row_groups_matched_bloom_filter.add(1);
             filtered.push(*idx);
         }
     }
@@ -0,0 +1,305 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan producing the partition values of files from their
+//! metadata, without reading them
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::datasource::physical_plan::{
+    FileMeta, FileOpenFuture, FileOpener, FileScanConfig, FileStream,
+};
+use crate::error::Result;
+use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+};
+
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use arrow_schema::{Schema, SchemaRef};
+use datafusion_common::stats::Precision;
+use datafusion_common::{internal_err, DataFusionError, Statistics};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::PhysicalSortExpr;
+
+use futures::{stream, StreamExt};
+use object_store::path::Path;
+
+/// Execution plan for scans that only project partition columns, such as
+/// `SELECT DISTINCT part FROM t` or `SELECT part, count(*) FROM t GROUP BY
+/// part` over a partitioned listing table.
+///
+/// Each file produces as many rows as it contains, as given by the file
+/// metadata, with the partition values of the file. The files themselves
+/// are never read.
+///
+/// In distinct mode, set by [`Self::with_distinct`], each non-empty file
+/// produces a single row instead, which is enough for the plans only
+/// depending on the distinct values of their input.
+#[derive(Debug, Clone)]
+pub struct PartitionValuesExec {
+    base_config: FileScanConfig,
+    /// The number of rows of each file
+    row_counts: Arc<HashMap<Path, usize>>,
+    /// Whether a single row is produced for each non-empty file
+    distinct: bool,
+    projected_statistics: Statistics,
+    projected_schema: SchemaRef,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl PartitionValuesExec {
+    /// Create a new plan producing the partition values of the files of
+    /// `base_config`, whose number of rows are given by `row_counts`.
+    ///
+    /// Returns an error if `base_config` projects columns of the files.
+    pub fn try_new(
+        base_config: FileScanConfig,
+        row_counts: HashMap<Path, usize>,
+    ) -> Result<Self> {
+        if base_config
+            .file_column_projection_indices()
+            .map_or(true, |indices| !indices.is_empty())
+        {
+            return internal_err!(
+                "PartitionValuesExec can only project partition columns"
+            );
+        }
+        for file in base_config.file_groups.iter().flatten() {
+            if !row_counts.contains_key(&file.object_meta.location) {
+                return internal_err!(
+                    "Unknown number of rows for file {}",
+                    file.object_meta.location
+                );
+            }
+        }
+        let (projected_schema, projected_statistics, _) = base_config.project();
+
+        Ok(Self {
+            base_config,
+            row_counts: Arc::new(row_counts),
+            distinct: false,
+            projected_statistics,
+            projected_schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// Produce a single row for each non-empty file rather than one row for
+    /// each row of the file
+    pub fn with_distinct(mut self, distinct: bool) -> Self {
+        self.distinct = distinct;
+        if distinct {
+            let non_empty_files = self
+                .base_config
+                .file_groups
+                .iter()
+                .flatten()
+                .filter(|file| self.row_counts[&file.object_meta.location] > 0)
+                .count();
+            self.projected_statistics.num_rows = Precision::Exact(non_empty_files);
+        } else {
+            self.projected_statistics.num_rows =
+                self.base_config.statistics.num_rows.clone();
+        }
+        self
+    }
+
+    /// Ref to the base configs
+    pub fn base_config(&self) -> &FileScanConfig {
+        &self.base_config
+    }
+
+    /// Whether a single row is produced for each non-empty file
+    pub fn distinct(&self) -> bool {
+        self.distinct
+    }
+}
+
+impl DisplayAs for PartitionValuesExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        write!(f, "PartitionValuesExec: ")?;
+        if self.distinct {
+            write!(f, "distinct=true, ")?;
+        }
+        self.base_config.fmt_as(t, f)
+    }
+}
+
+impl ExecutionPlan for PartitionValuesExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.base_config.file_groups.len())
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        Vec::new()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let opener = PartitionValuesOpener {
+            row_counts: self.row_counts.clone(),
+            distinct: self.distinct,
+            batch_size: context
+                .session_config()
+                .batch_size_for_schema(&self.schema()),
+        };
+        let stream =
+            FileStream::new(&self.base_config, partition, opener, &self.metrics)?
+                .with_cancellation_token(context.cancellation_token().clone());
+        Ok(Box::pin(stream))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(self.projected_statistics.clone())
+    }
+}
+
+/// A [`FileOpener`] producing batches without columns with the number of
+/// rows of the files, to which [`FileStream`] adds the partition columns
+struct PartitionValuesOpener {
+    row_counts: Arc<HashMap<Path, usize>>,
+    distinct: bool,
+    batch_size: usize,
+}
+
+impl FileOpener for PartitionValuesOpener {
+    fn open(&self, file_meta: FileMeta) -> Result<FileOpenFuture> {
+        let Some(&row_count) = self.row_counts.get(file_meta.location()) else {
+            return internal_err!(
+                "Unknown number of rows for file {}",
+                file_meta.location()
+            );
+        };
+        let row_count = if self.distinct {
+            row_count.min(1)
+        } else {
+            row_count
+        };
+        let batch_size = self.batch_size.max(1);
+        let schema = Arc::new(Schema::empty());
+        let batches = (0..row_count).step_by(batch_size).map(move |start| {
+            let num_rows = batch_size.min(row_count - start);
+            RecordBatch::try_new_with_options(
+                schema.clone(),
+                vec![],
+                &RecordBatchOptions::new().with_row_count(Some(num_rows)),
+            )
+        });
+        Ok(Box::pin(futures::future::ready(Ok(
+            stream::iter(batches).boxed()
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::listing::PartitionedFile;
+    use crate::datasource::physical_plan::{
+        wrap_partition_type_in_dict, wrap_partition_value_in_dict,
+    };
+    use crate::execution::context::SessionContext;
+    use crate::physical_plan::collect;
+    use arrow_schema::{DataType, Field};
+    use datafusion_common::ScalarValue;
+    use datafusion_execution::object_store::ObjectStoreUrl;
+
+    fn partitioned_file(name: &str, part: &str) -> PartitionedFile {
+        let mut file = PartitionedFile::new(name, 100);
+        file.partition_values =
+            vec![wrap_partition_value_in_dict(ScalarValue::from(part))];
+        file
+    }
+
+    #[tokio::test]
+    async fn partition_values_from_row_counts() -> Result<()> {
+        let files = vec![
+            partitioned_file("a.parquet", "x"),
+            partitioned_file("b.parquet", "y"),
+            partitioned_file("c.parquet", "z"),
+        ];
+        let row_counts = files
+            .iter()
+            .zip([3, 0, 2])
+            .map(|(file, rows)| (file.object_meta.location.clone(), rows))
+            .collect();
+        let part_type = wrap_partition_type_in_dict(DataType::Utf8);
+        let config = FileScanConfig {
+            object_store_url: ObjectStoreUrl::local_filesystem(),
+            file_schema: Arc::new(Schema::new(vec![Field::new(
+                "a",
+                DataType::Int32,
+                true,
+            )])),
+            file_groups: vec![files],
+            statistics: Statistics::new_unknown(&Schema::empty()),
+            projection: Some(vec![1]),
+            limit: None,
+            table_partition_cols: vec![Field::new("part", part_type, false)],
+            output_ordering: vec![],
+        };
+        let exec = PartitionValuesExec::try_new(config, row_counts)?;
+
+        let ctx = SessionContext::new();
+        let batches = collect(Arc::new(exec.clone()), ctx.task_ctx()).await?;
+        let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(rows, 5);
+
+        let exec = exec.with_distinct(true);
+        assert_eq!(exec.statistics()?.num_rows, Precision::Exact(2));
+        let batches = collect(Arc::new(exec), ctx.task_ctx()).await?;
+        crate::assert_batches_eq!(
+            ["+------+", "| part |", "+------+", "| x    |", "| z    |", "+------+"],
+            &batches
+        );
+        Ok(())
+    }
+}
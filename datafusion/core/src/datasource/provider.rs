@@ -146,6 +146,14 @@ pub trait TableProvider: Sync + Send {
     /// because inexact filters do not guarentee that every filtered row is
     /// removed, so applying the limit could lead to too few rows being available
     /// to return as a final result.
+    ///
+    /// # Ordering
+    ///
+    /// If the table is stored in a known order, for example a time ordered
+    /// dataset, the returned `ExecutionPlan` can advertise it in
+    /// [`ExecutionPlan::output_ordering`], or return its rows in that order
+    /// when asked with [`ExecutionPlan::try_pushdown_sort`]. Sorts on that
+    /// order above the scan are then removed ("Sort Pushdown").
     async fn scan(
         &self,
         state: &SessionState,
@@ -177,6 +185,80 @@ pub trait TableProvider: Sync + Send {
             .collect()
     }
 
+    /// Tests whether the table provider can compute the aggregate expressions
+    /// `aggr_expr`, grouped by `group_expr`, over the rows matching `filters`
+    /// without DataFusion scanning the table, for example using metadata such
+    /// as row counts and min/max statistics, or by delegating the aggregation
+    /// to a remote database.
+    ///
+    /// If this returns true, [`Self::aggregate_pushdown`] is used to create a
+    /// table containing the result of the aggregation.
+    fn supports_aggregate_pushdown(
+        &self,
+        _group_expr: &[Expr],
+        _aggr_expr: &[Expr],
+        _filters: &[Expr],
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Return a table containing the result of an aggregation accepted by
+    /// [`Self::supports_aggregate_pushdown`].
+    ///
+    /// The schema of the returned table must have one field for each of
+    /// `group_expr` followed by one field for each of `aggr_expr`, with the
+    /// data types of those expressions.
+    fn aggregate_pushdown(
+        &self,
+        _group_expr: &[Expr],
+        _aggr_expr: &[Expr],
+        _filters: &[Expr],
+    ) -> Result<Arc<dyn TableProvider>> {
+        not_impl_err!("Aggregate pushdown not implemented for this table")
+    }
+
+    /// Tests whether the table provider can return the first `k` of the rows
+    /// matching `filters` when ordered by `sort_exprs`, without DataFusion
+    /// scanning and sorting the whole table, for example using an index or
+    /// because the table is stored in that order.
+    ///
+    /// `sort_exprs` are [`Expr::Sort`]s over the columns of the table. If
+    /// this returns true, the table is replaced in the plan with the table
+    /// returned by [`Self::topk_pushdown`]. The `ORDER BY ... LIMIT` is still
+    /// applied to its rows, so it may return more than `k` rows, in any order.
+    fn supports_topk_pushdown(
+        &self,
+        _sort_exprs: &[Expr],
+        _k: usize,
+        _filters: &[Expr],
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Return a table containing the top rows accepted by
+    /// [`Self::supports_topk_pushdown`].
+    ///
+    /// The returned table must have the schema of this table. `filters` are
+    /// still passed to its [`Self::scan`].
+    fn topk_pushdown(
+        &self,
+        _sort_exprs: &[Expr],
+        _k: usize,
+        _filters: &[Expr],
+    ) -> Result<Arc<dyn TableProvider>> {
+        not_impl_err!("Top-k pushdown not implemented for this table")
+    }
+
+    /// Tests whether the plan returned by [`Self::scan`] with `filters` and
+    /// an empty projection reports the exact number of rows matching
+    /// `filters` in its statistics, for example from file metadata.
+    ///
+    /// If so, queries such as `SELECT count(*) FROM t` are answered from
+    /// the statistics of the scan, and no data is read.
+    fn supports_exact_row_count(&self, _filters: &[&Expr]) -> Result<bool> {
+        Ok(false)
+    }
+
     /// Get statistics for this table, if available
     fn statistics(&self) -> Option<Statistics> {
         None
@@ -0,0 +1,351 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`AdmissionControl`] deciding whether, and when, the queries of a session
+//! run, see [`SessionState::with_admission_control`]
+//!
+//! [`SessionState::with_admission_control`]: crate::execution::context::SessionState::with_admission_control
+
+use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::execution::TaskContext;
+use crate::physical_plan::stream::RecordBatchStreamAdapter;
+use crate::physical_plan::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream,
+};
+
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+use datafusion_common::stats::Precision;
+use datafusion_common::{DataFusionError, Statistics};
+use datafusion_physical_expr::{PhysicalSortExpr, PhysicalSortRequirement};
+use futures::StreamExt;
+use tokio::sync::Semaphore;
+
+/// The estimated size of a query, from the statistics of its physical plan
+#[derive(Debug, Clone)]
+pub struct QueryEstimate {
+    /// The estimated number of output rows
+    pub num_rows: Precision<usize>,
+    /// The estimated size of the output, in bytes
+    pub total_byte_size: Precision<usize>,
+    /// The number of output partitions, run concurrently
+    pub output_partitions: usize,
+}
+
+impl QueryEstimate {
+    /// Estimates the size of the query running `plan`
+    pub fn new(plan: &Arc<dyn ExecutionPlan>) -> Self {
+        let (num_rows, total_byte_size) = match plan.statistics() {
+            Ok(stats) => (stats.num_rows, stats.total_byte_size),
+            Err(_) => (Precision::Absent, Precision::Absent),
+        };
+        Self {
+            num_rows,
+            total_byte_size,
+            output_partitions: plan.output_partitioning().partition_count(),
+        }
+    }
+}
+
+/// Decides whether, and when, the queries of a session run.
+///
+/// [`Self::admit`] is called with the physical plan of every query before it
+/// runs, and can:
+///
+/// * run the query, returning its plan unchanged
+/// * queue the query, waiting before returning
+/// * reject the query, returning an error, usually
+///   [`DataFusionError::ResourcesExhausted`]
+/// * downgrade the query, returning a plan using less resources
+///
+/// The resources held while the query runs can be released when it ends by
+/// wrapping its plan in an [`AdmittedExec`].
+#[async_trait]
+pub trait AdmissionControl: Send + Sync {
+    /// Admits the query running `plan`, returning the plan to run
+    async fn admit(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        estimate: &QueryEstimate,
+    ) -> Result<Arc<dyn ExecutionPlan>>;
+}
+
+/// [`AdmissionControl`] running at most a number of queries concurrently,
+/// queueing the others until running queries end
+#[derive(Debug)]
+pub struct ConcurrencyLimit {
+    /// The maximum number of running queries
+    max_running: usize,
+    /// The permits of the running queries
+    semaphore: Arc<Semaphore>,
+    /// The maximum number of queued queries, unbounded if `None`
+    max_queued: Option<usize>,
+    /// The number of queued queries
+    queued: AtomicUsize,
+}
+
+impl ConcurrencyLimit {
+    /// Create a new [`ConcurrencyLimit`] running at most `max_running`
+    /// queries concurrently
+    pub fn new(max_running: usize) -> Self {
+        Self {
+            max_running,
+            semaphore: Arc::new(Semaphore::new(max_running)),
+            max_queued: None,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reject the queries arriving while `max_queued` queries are already
+    /// queued
+    pub fn with_max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = Some(max_queued);
+        self
+    }
+
+    /// The number of queries that can start without being queued
+    pub fn available(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    /// The number of queued queries
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+}
+
+/// Counts a queued query until dropped, including when the waiting query is
+/// cancelled
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl AdmissionControl for ConcurrencyLimit {
+    async fn admit(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _estimate: &QueryEstimate,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let semaphore = self.semaphore.clone();
+        let permit = match semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let queued = self.queued.fetch_add(1, Ordering::Relaxed);
+                let _guard = QueuedGuard(&self.queued);
+                if self.max_queued.is_some_and(|max| queued >= max) {
+                    return Err(DataFusionError::ResourcesExhausted(format!(
+                        "Too many queued queries: {queued} queries are waiting for one of the {} running queries to end",
+                        self.max_running
+                    )));
+                }
+                semaphore.acquire_owned().await.map_err(|e| {
+                    DataFusionError::Internal(format!(
+                        "Failed to acquire the permit of a query: {e}"
+                    ))
+                })?
+            }
+        };
+        Ok(Arc::new(AdmittedExec::new(plan, Arc::new(permit))))
+    }
+}
+
+/// Execution plan running its input, and holding resources given by an
+/// [`AdmissionControl`] until the plan and its streams are dropped
+#[derive(Debug)]
+pub struct AdmittedExec {
+    input: Arc<dyn ExecutionPlan>,
+    /// Released when the plan and its streams are dropped
+    permit: Arc<dyn Any + Send + Sync>,
+}
+
+impl AdmittedExec {
+    /// Create a new [`AdmittedExec`] running `input`, holding `permit`
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        permit: Arc<dyn Any + Send + Sync>,
+    ) -> Self {
+        Self { input, permit }
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+}
+
+impl DisplayAs for AdmittedExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "AdmittedExec")
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for AdmittedExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        Ok(children[0])
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self::new(
+            children[0].clone(),
+            self.permit.clone(),
+        )))
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::UnspecifiedDistribution]
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        vec![None]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let stream = self.input.execute(partition, context)?;
+        let permit = self.permit.clone();
+        let schema = stream.schema();
+        // the stream keeps the permit until it is dropped
+        let stream = stream.map(move |batch| {
+            let _ = &permit;
+            batch
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.input.statistics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::empty::EmptyExec;
+    use crate::physical_plan::{collect, execute_stream};
+    use crate::prelude::SessionContext;
+
+    use crate::execution::context::SessionState;
+    use arrow::datatypes::Schema;
+
+    fn empty_plan() -> Arc<dyn ExecutionPlan> {
+        Arc::new(EmptyExec::new(Arc::new(Schema::empty())))
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit() -> Result<()> {
+        let limit = ConcurrencyLimit::new(1).with_max_queued(1);
+        let estimate = QueryEstimate::new(&empty_plan());
+
+        let first = limit.admit(empty_plan(), &estimate).await?;
+        let stream = execute_stream(first.clone(), Arc::new(TaskContext::default()))?;
+        assert_eq!(limit.available(), 0);
+
+        // the second query is queued, and the third rejected
+        let mut second = limit.admit(empty_plan(), &estimate);
+        assert!(futures::poll!(&mut second).is_pending());
+        assert_eq!(limit.queued(), 1);
+        let err = limit.admit(empty_plan(), &estimate).await.unwrap_err();
+        assert!(err.to_string().contains("Too many queued queries"), "{err}");
+
+        // the second query runs once the plan and the stream of the first
+        // are dropped
+        drop(first);
+        assert!(futures::poll!(&mut second).is_pending());
+        drop(stream);
+        let second = second.await?;
+        assert_eq!(limit.queued(), 0);
+        assert_eq!(limit.available(), 0);
+        drop(second);
+        assert_eq!(limit.available(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn session_admission_control() -> Result<()> {
+        let limit = Arc::new(ConcurrencyLimit::new(1).with_max_queued(0));
+        let ctx = SessionContext::new();
+        let state =
+            SessionState::new_with_config_rt(ctx.copied_config(), ctx.runtime_env())
+                .with_admission_control(limit.clone());
+        let ctx = SessionContext::new_with_state(state);
+
+        let df = ctx.sql("SELECT 1").await?;
+        let plan = df.clone().create_physical_plan().await?;
+        assert!(plan.as_any().is::<AdmittedExec>());
+        assert_eq!(limit.available(), 0);
+
+        let err = df.clone().collect().await.unwrap_err();
+        assert!(err.to_string().contains("Too many queued queries"), "{err}");
+
+        assert_eq!(collect(plan, ctx.task_ctx()).await?.len(), 1);
+        assert_eq!(limit.available(), 1);
+        assert_eq!(df.collect().await?.len(), 1);
+        Ok(())
+    }
+}
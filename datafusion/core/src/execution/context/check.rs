@@ -0,0 +1,232 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Validation of SQL statements without running them
+
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use datafusion_common::plan_datafusion_err;
+use datafusion_expr::{EmptyRelation, LogicalPlan};
+use datafusion_sql::parser::DFParser;
+use sqlparser::dialect::dialect_from_str;
+
+use super::{
+    CreateMemoryTable, DataFusionError, DdlStatement, Result, SessionContext,
+    SessionState, Statement,
+};
+use crate::execution::warnings::{cross_join_warnings, lossy_cast_warnings, PlanWarning};
+
+/// The outcome of checking one SQL statement with
+/// [`SessionContext::check_sql`]
+#[derive(Debug)]
+pub struct SqlCheck {
+    /// The statement, as parsed
+    pub statement: String,
+    /// The schema of the output of the statement, if it could be planned
+    pub schema: Option<SchemaRef>,
+    /// The error that prevents the statement from running, if any
+    pub error: Option<DataFusionError>,
+    /// The hazards found in the statement that do not prevent it from
+    /// running, such as cross joins without join condition
+    pub warnings: Vec<PlanWarning>,
+}
+
+impl SqlCheck {
+    /// Returns `true` if the statement can be run
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl SessionContext {
+    /// Checks the SQL statements of `sql`, separated by `;`, without running
+    /// them: each statement is planned, resolving the names of its tables,
+    /// columns and functions, and type checked, and a [`SqlCheck`] is
+    /// returned for it.
+    ///
+    /// The statements are checked in order, against a fork of this context
+    /// (see [`Self::fork`]), so that the tables and views created and the
+    /// options set by one statement are visible to the following ones, but
+    /// not to this context. Tables created with `CREATE TABLE ... AS` are
+    /// created empty, and `INSERT` and `COPY` statements are not run.
+    ///
+    /// Returns an error if `sql` can not be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let checks = ctx
+    ///     .check_sql("CREATE VIEW v AS SELECT 1 AS a; SELECT b FROM v")
+    ///     .await?;
+    /// assert!(checks[0].is_valid());
+    /// assert!(!checks[1].is_valid());
+    /// // the view was only created to check the statements
+    /// assert!(!ctx.table_exist("v")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check_sql(&self, sql: &str) -> Result<Vec<SqlCheck>> {
+        let dialect = self
+            .state
+            .read()
+            .config_options()
+            .sql_parser
+            .dialect
+            .clone();
+        let dialect = dialect_from_str(&dialect)
+            .ok_or_else(|| plan_datafusion_err!("Unsupported SQL dialect: {dialect}"))?;
+        let statements = DFParser::parse_sql_with_dialect(sql, dialect.as_ref())?;
+
+        let fork = self.fork();
+        let mut checks = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let mut check = SqlCheck {
+                statement: statement.to_string(),
+                schema: None,
+                error: None,
+                warnings: vec![],
+            };
+            let state = fork.state();
+            match state.statement_to_plan(statement).await {
+                Ok(plan) => {
+                    check.schema = Some(Arc::new(plan.schema().as_ref().into()));
+                    match check_plan(&fork, &state, plan).await {
+                        Ok(warnings) => check.warnings = warnings,
+                        Err(e) => check.error = Some(e),
+                    }
+                }
+                Err(e) => check.error = Some(e),
+            }
+            checks.push(check);
+        }
+        Ok(checks)
+    }
+}
+
+/// Type checks `plan`, returning the warnings about it, and applies its
+/// effects on the catalogs and the configuration to `fork` without
+/// reading or writing any data
+async fn check_plan(
+    fork: &SessionContext,
+    state: &SessionState,
+    plan: LogicalPlan,
+) -> Result<Vec<PlanWarning>> {
+    match plan {
+        LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(cmd)) => {
+            let warnings = plan_warnings(state, &cmd.input)?;
+            let input = LogicalPlan::EmptyRelation(EmptyRelation {
+                produce_one_row: false,
+                schema: cmd.input.schema().clone(),
+            });
+            let cmd = CreateMemoryTable {
+                input: Arc::new(input),
+                ..cmd
+            };
+            fork.execute_logical_plan(LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(
+                cmd,
+            )))
+            .await?;
+            Ok(warnings)
+        }
+        LogicalPlan::Ddl(DdlStatement::CreateView(ref cmd)) => {
+            let warnings = plan_warnings(state, &cmd.input)?;
+            fork.execute_logical_plan(plan).await?;
+            Ok(warnings)
+        }
+        LogicalPlan::Ddl(_) | LogicalPlan::Statement(Statement::SetVariable(_)) => {
+            fork.execute_logical_plan(plan).await?;
+            Ok(vec![])
+        }
+        LogicalPlan::Statement(_) | LogicalPlan::Prepare(_) => Ok(vec![]),
+        LogicalPlan::Explain(explain) => plan_warnings(state, &explain.plan),
+        LogicalPlan::Analyze(analyze) => plan_warnings(state, &analyze.input),
+        plan => plan_warnings(state, &plan),
+    }
+}
+
+/// Analyzes and optimizes `plan`, returning the warnings about it
+fn plan_warnings(state: &SessionState, plan: &LogicalPlan) -> Result<Vec<PlanWarning>> {
+    let analyzed_plan =
+        state
+            .analyzer
+            .execute_and_check(plan, state.config_options(), |_, _| {})?;
+    let mut warnings = lossy_cast_warnings(plan, &analyzed_plan)?;
+    let optimized_plan = state.optimizer.optimize(&analyzed_plan, state, |_, _| {})?;
+    warnings.extend(cross_join_warnings(&optimized_plan)?);
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::warnings::PlanWarningKind;
+
+    #[tokio::test]
+    async fn check_sql() -> Result<()> {
+        let ctx = SessionContext::new();
+        let checks = ctx
+            .check_sql(
+                "CREATE TABLE t AS VALUES (1, 'a'), (2, 'b');
+                 CREATE VIEW v AS SELECT column1 AS a, column2 AS b FROM t;
+                 SELECT a FROM v WHERE b = 'a';
+                 SELECT c FROM v;
+                 SELECT a + b FROM v;
+                 SELECT * FROM v AS x, v AS y;
+                 INSERT INTO t VALUES (3, 'c');
+                 SELECT * FROM missing",
+            )
+            .await?;
+
+        let errors: Vec<_> = checks
+            .iter()
+            .map(|check| check.error.as_ref().map(|e| e.strip_backtrace()))
+            .collect();
+        assert!(errors[..3].iter().all(Option::is_none));
+        assert!(errors[3].as_ref().unwrap().contains("No field named c"));
+        assert!(errors[4]
+            .as_ref()
+            .unwrap()
+            .contains("Cannot coerce arithmetic expression Int64 + Utf8"));
+        assert!(errors[5..7].iter().all(Option::is_none));
+        assert!(errors[7].as_ref().unwrap().contains("missing"));
+
+        let schema = checks[2].schema.as_ref().unwrap();
+        assert_eq!(schema.fields().len(), 1);
+        assert_eq!(schema.field(0).name(), "a");
+        assert!(checks[2].warnings.is_empty());
+        assert_eq!(checks[5].warnings.len(), 1);
+        assert_eq!(checks[5].warnings[0].kind, PlanWarningKind::CrossJoin);
+
+        // the statements were not run against the context
+        assert!(!ctx.table_exist("t")?);
+        assert!(!ctx.table_exist("v")?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn check_sql_syntax_error() {
+        let ctx = SessionContext::new();
+        let err = ctx.check_sql("SELECT 1; SELEC 2").await.unwrap_err();
+        assert!(err.strip_backtrace().starts_with("SQL error: ParserError"));
+    }
+}
@@ -18,10 +18,15 @@
 //! [`SessionContext`] contains methods for registering data sources and executing queries
 
 mod avro;
+mod check;
 mod csv;
 mod json;
 #[cfg(feature = "parquet")]
 mod parquet;
+mod snapshot;
+
+pub use check::SqlCheck;
+pub use snapshot::CatalogSnapshot;
 
 use crate::{
     catalog::{CatalogList, MemoryCatalogList},
@@ -33,7 +38,9 @@ use crate::{
     datasource::{MemTable, ViewTable},
     logical_expr::{PlanType, ToStringifiedPlan},
     optimizer::optimizer::Optimizer,
-    physical_optimizer::optimizer::{PhysicalOptimizer, PhysicalOptimizerRule},
+    physical_optimizer::optimizer::{
+        PhysicalOptimizer, PhysicalOptimizerRule, RulePosition,
+    },
 };
 use datafusion_common::{
     alias::AliasGenerator,
@@ -61,11 +68,13 @@ use arrow::datatypes::{DataType, SchemaRef};
 use arrow::record_batch::RecordBatch;
 
 use crate::catalog::{
+    fork::ForkedCatalogList,
     schema::{MemorySchemaProvider, SchemaProvider},
     {CatalogProvider, MemoryCatalogProvider},
 };
 use crate::dataframe::DataFrame;
 use crate::datasource::{
+    cte_worktable::CteWorkTable,
     listing::{ListingTableConfig, ListingTableUrl},
     provider_as_source, TableProvider,
 };
@@ -73,7 +82,8 @@ use crate::error::{DataFusionError, Result};
 use crate::logical_expr::{
     CreateCatalog, CreateCatalogSchema, CreateExternalTable, CreateMemoryTable,
     CreateView, DropCatalogSchema, DropTable, DropView, Explain, LogicalPlan,
-    LogicalPlanBuilder, SetVariable, TableSource, TableType, UNNAMED_TABLE,
+    LogicalPlanBuilder, SetVariable, TableSource, TableType, TransactionEnd,
+    UNNAMED_TABLE,
 };
 use crate::optimizer::OptimizerRule;
 use datafusion_sql::{
@@ -84,6 +94,10 @@ use datafusion_sql::{
 use sqlparser::dialect::dialect_from_str;
 
 use crate::config::ConfigOptions;
+use crate::execution::admission::{AdmissionControl, QueryEstimate};
+use crate::execution::warnings::{
+    cross_join_warnings, default_selectivity_warnings, lossy_cast_warnings, PlanWarning,
+};
 use crate::execution::{runtime_env::RuntimeEnv, FunctionRegistry};
 use crate::physical_plan::udaf::AggregateUDF;
 use crate::physical_plan::udf::ScalarUDF;
@@ -251,6 +265,8 @@ pub struct SessionContext {
     session_start_time: DateTime<Utc>,
     /// Shared session state for the session
     state: Arc<RwLock<SessionState>>,
+    /// Configuration to restore when the current transaction ends, if any
+    transaction: Arc<RwLock<Option<TransactionConfig>>>,
 }
 
 impl Default for SessionContext {
@@ -333,6 +349,7 @@ impl SessionContext {
             session_id: state.session_id.clone(),
             session_start_time: Utc::now(),
             state: Arc::new(RwLock::new(state)),
+            transaction: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -452,15 +469,87 @@ impl SessionContext {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// # Example: Overriding Configuration for a Single Query
+    ///
+    /// Configuration options can be set for a single query, without
+    /// changing the configuration of the session, with a
+    /// `/*+ CONFIG(key = value, ...) */` hint at the start of the query:
+    ///
+    /// ```
+    /// use datafusion::prelude::*;
+    /// # use datafusion::{error::Result};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx
+    ///   .sql("/*+ CONFIG(datafusion.execution.batch_size = 2) */ SELECT 1")
+    ///   .await?;
+    /// df.collect().await?;
+    /// assert_ne!(ctx.copied_config().batch_size(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Similarly, `BROADCAST(table, ...)` and `JOIN_ORDER(table, ...)` hints
+    /// set the `datafusion.optimizer.broadcast_join_hint` and
+    /// `datafusion.optimizer.join_order_hint` options for the query.
     pub async fn sql_with_options(
         &self,
         sql: &str,
         options: SQLOptions,
     ) -> Result<DataFrame> {
-        let plan = self.state().create_logical_plan(sql).await?;
+        let hints = parse_hints(sql)?;
+        if hints.is_empty() {
+            let plan = self.state().create_logical_plan(sql).await?;
+            options.verify_plan(&plan)?;
+            return self.execute_logical_plan(plan).await;
+        }
+
+        let mut state = self.state();
+        apply_hints(state.config.options_mut(), hints)?;
+        state.update_time_zone();
+        let plan = state.create_logical_plan(sql).await?;
         options.verify_plan(&plan)?;
+        match plan {
+            LogicalPlan::Ddl(_) | LogicalPlan::Statement(_) => {
+                plan_err!("Hints are only supported for queries")
+            }
+            plan => Ok(DataFrame::new(state, plan)),
+        }
+    }
 
-        self.execute_logical_plan(plan).await
+    /// Plans SQL query text without running it, returning the schema of its
+    /// output and the data types of its parameters, such as `$1`.
+    ///
+    /// This can be used to describe the columns of a query or a prepared
+    /// statement without the cost of executing it. Parameters whose data
+    /// type can not be determined are not included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datafusion::prelude::*;
+    /// # use datafusion::{error::Result};
+    /// # use datafusion::arrow::datatypes::DataType;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let (schema, parameter_types) = ctx
+    ///   .sql_to_schema("PREPARE q(INT) AS SELECT $1 + 1 AS x")
+    ///   .await?;
+    /// assert_eq!(schema.field(0).name(), "x");
+    /// assert_eq!(parameter_types.get("$1"), Some(&Some(DataType::Int32)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sql_to_schema(
+        &self,
+        sql: &str,
+    ) -> Result<(SchemaRef, HashMap<String, Option<DataType>>)> {
+        let plan = self.state().create_logical_plan(sql).await?;
+        let parameter_types = plan.get_parameter_types()?;
+        Ok((Arc::new(plan.schema().as_ref().into()), parameter_types))
     }
 
     /// Execute the [`LogicalPlan`], return a [`DataFrame`]. This API
@@ -488,7 +577,12 @@ impl SessionContext {
                 DdlStatement::DropView(cmd) => self.drop_view(cmd).await,
                 DdlStatement::DropCatalogSchema(cmd) => self.drop_schema(cmd).await,
             },
-            // TODO what about the other statements (like TransactionStart and TransactionEnd)
+            LogicalPlan::Statement(Statement::TransactionStart(_)) => {
+                self.start_transaction()
+            }
+            LogicalPlan::Statement(Statement::TransactionEnd(stmt)) => {
+                self.end_transaction(stmt)
+            }
             LogicalPlan::Statement(Statement::SetVariable(stmt)) => {
                 self.set_variable(stmt).await
             }
@@ -734,12 +828,64 @@ impl SessionContext {
 
     async fn set_variable(&self, stmt: SetVariable) -> Result<DataFrame> {
         let SetVariable {
-            variable, value, ..
+            variable,
+            value,
+            local,
+            ..
         } = stmt;
 
+        let mut transaction = self.transaction.write();
+        match transaction.as_mut() {
+            Some(transaction) if !local => {
+                // remember the values set for the session, to keep them when
+                // the options set for the transaction are restored
+                transaction.settings.push((variable.clone(), value.clone()));
+            }
+            Some(_) => {}
+            None if local => {
+                return exec_err!("SET LOCAL can only be used in a transaction");
+            }
+            None => {}
+        }
+
         let mut state = self.state.write();
         state.config.options_mut().set(&variable, &value)?;
         drop(state);
+        drop(transaction);
+
+        self.return_empty_dataframe()
+    }
+
+    fn start_transaction(&self) -> Result<DataFrame> {
+        let mut transaction = self.transaction.write();
+        if transaction.is_some() {
+            return exec_err!("There is already a transaction in progress");
+        }
+        *transaction = Some(TransactionConfig {
+            options: self.state.read().config_options().clone(),
+            settings: vec![],
+        });
+        drop(transaction);
+
+        self.return_empty_dataframe()
+    }
+
+    fn end_transaction(&self, stmt: TransactionEnd) -> Result<DataFrame> {
+        let mut transaction = self.transaction.write();
+        if let Some(TransactionConfig { options, settings }) = transaction.take() {
+            let mut state = self.state.write();
+            *state.config.options_mut() = options;
+            for (variable, value) in settings {
+                state.config.options_mut().set(&variable, &value)?;
+            }
+            if stmt.chain {
+                *transaction = Some(TransactionConfig {
+                    options: state.config_options().clone(),
+                    settings: vec![],
+                });
+            }
+        }
+        drop(transaction);
 
         self.return_empty_dataframe()
     }
@@ -1104,11 +1250,25 @@ impl SessionContext {
     ) -> Result<Arc<dyn TableProvider>> {
         let table_ref = table_ref.into();
         let table = table_ref.table().to_string();
-        let schema = self.state.read().schema_for_ref(table_ref)?;
-        match schema.table(&table).await {
-            Some(ref provider) => Ok(Arc::clone(provider)),
-            _ => plan_err!("No table named '{table}'"),
+        let schemas = {
+            let state = self.state.read();
+            let schemas = state
+                .schemas_for_ref(table_ref.clone())
+                .into_iter()
+                .map(|(_, schema)| schema)
+                .collect::<Vec<_>>();
+            if schemas.is_empty() {
+                // report why the default schema could not be resolved
+                state.schema_for_ref(table_ref)?;
+            }
+            schemas
+        };
+        for schema in schemas {
+            if let Some(provider) = schema.table(&table).await {
+                return Ok(provider);
+            }
         }
+        plan_err!("No table named '{table}'")
     }
 
     /// Returns the set of available tables in the default catalog and
@@ -1164,6 +1324,7 @@ impl SessionContext {
     pub fn state(&self) -> SessionState {
         let mut state = self.state.read().clone();
         state.execution_props.start_execution();
+        state.update_time_zone();
         state
     }
 
@@ -1176,6 +1337,45 @@ impl SessionContext {
     pub fn register_catalog_list(&mut self, catalog_list: Arc<dyn CatalogList>) {
         self.state.write().catalog_list = catalog_list;
     }
+
+    /// Creates a new [`SessionContext`] starting from the state of this one
+    /// and sharing its [`RuntimeEnv`], see [`Self::fork_with_runtime`]
+    pub fn fork(&self) -> Self {
+        self.fork_with_runtime(self.runtime_env())
+    }
+
+    /// Creates a new [`SessionContext`] starting from the state of this one,
+    /// running its queries with `runtime`.
+    ///
+    /// The forked context shares the catalogs of this one copy-on-write:
+    /// the tables, schemas and catalogs of this context are visible to the
+    /// fork without being copied, while the ones created or dropped in the
+    /// fork are only visible to it. Its configuration, variables and
+    /// functions start as copies of the ones of this context and are changed
+    /// independently.
+    ///
+    /// Forking with a [`RuntimeEnv`] that has its own memory pool, for
+    /// example, gives the fork its own memory limit:
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use datafusion::prelude::SessionContext;
+    /// # use datafusion::execution::memory_pool::GreedyMemoryPool;
+    /// # use datafusion::execution::runtime_env::RuntimeEnv;
+    /// let ctx = SessionContext::new();
+    /// let runtime = RuntimeEnv {
+    ///     memory_pool: Arc::new(GreedyMemoryPool::new(64 * 1024 * 1024)),
+    ///     ..ctx.runtime_env().as_ref().clone()
+    /// };
+    /// let tenant = ctx.fork_with_runtime(Arc::new(runtime));
+    /// ```
+    pub fn fork_with_runtime(&self, runtime: Arc<RuntimeEnv>) -> Self {
+        let mut state = self.state.read().clone();
+        state.session_id = Uuid::new_v4().to_string();
+        state.catalog_list = Arc::new(ForkedCatalogList::new(state.catalog_list));
+        state.runtime_env = runtime;
+        Self::new_with_state(state)
+    }
 }
 
 impl FunctionRegistry for SessionContext {
@@ -1196,6 +1396,89 @@ impl FunctionRegistry for SessionContext {
     }
 }
 
+/// Configuration of a [`SessionContext`] saved at the start of a transaction
+struct TransactionConfig {
+    /// The options to restore when the transaction ends
+    options: ConfigOptions,
+    /// The variables set with `SET` (rather than `SET LOCAL`) during the
+    /// transaction, which are kept after it ends
+    settings: Vec<(String, String)>,
+}
+
+/// Returns the names, in upper case, and arguments of the hints in a
+/// `/*+ NAME(arg, ...) ... */` comment at the start of `sql`
+fn parse_hints(sql: &str) -> Result<Vec<(String, Vec<String>)>> {
+    let Some(comment) = sql.trim_start().strip_prefix("/*+") else {
+        return Ok(vec![]);
+    };
+    let Some((mut rest, _)) = comment.split_once("*/") else {
+        return Ok(vec![]);
+    };
+
+    let mut hints = vec![];
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if rest.is_empty() {
+            return Ok(hints);
+        }
+        let Some((name, (args, remaining))) = rest
+            .split_once('(')
+            .and_then(|(name, args)| Some((name.trim(), args.split_once(')')?)))
+        else {
+            return plan_err!("Invalid hint: {}", rest.trim());
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return plan_err!("Invalid hint: {}", rest.trim());
+        }
+        let args = args
+            .split(',')
+            .map(str::trim)
+            .filter(|arg| !arg.is_empty())
+            .map(String::from)
+            .collect();
+        hints.push((name.to_uppercase(), args));
+        rest = remaining;
+    }
+}
+
+/// Applies `CONFIG(key = value, ...)`, `BROADCAST(table, ...)` and
+/// `JOIN_ORDER(table, ...)` hints to `options`. Other hints are ignored.
+fn apply_hints(
+    options: &mut ConfigOptions,
+    hints: Vec<(String, Vec<String>)>,
+) -> Result<()> {
+    for (name, args) in hints {
+        match name.as_str() {
+            "CONFIG" => {
+                for arg in args {
+                    let Some((key, value)) = arg.split_once('=') else {
+                        return plan_err!("Invalid configuration hint: {arg}");
+                    };
+                    let value = value.trim();
+                    let value = value
+                        .strip_prefix('\'')
+                        .and_then(|value| value.strip_suffix('\''))
+                        .unwrap_or(value);
+                    options.set(&key.trim().to_lowercase(), value)?;
+                }
+            }
+            "BROADCAST" if !args.is_empty() => {
+                let tables = args.join(",").to_lowercase();
+                let broadcast_join_hint = &mut options.optimizer.broadcast_join_hint;
+                *broadcast_join_hint = Some(match broadcast_join_hint.take() {
+                    Some(hint) => format!("{hint},{tables}"),
+                    None => tables,
+                });
+            }
+            "JOIN_ORDER" if !args.is_empty() => {
+                options.optimizer.join_order_hint = Some(args.join(",").to_lowercase());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 /// A planner used to add extensions to DataFusion logical and physical plans.
 #[async_trait]
 pub trait QueryPlanner {
@@ -1255,6 +1538,8 @@ pub struct SessionState {
     window_functions: HashMap<String, Arc<WindowUDF>>,
     /// Deserializer registry for extensions.
     serializer_registry: Arc<dyn SerializerRegistry>,
+    /// Decides whether, and when, the queries of the session run
+    admission_control: Option<Arc<dyn AdmissionControl>>,
     /// Session configuration
     config: SessionConfig,
     /// Execution properties
@@ -1338,6 +1623,7 @@ impl SessionState {
             );
         }
 
+        let time_zone = config.options().execution.time_zone.clone().map(Into::into);
         SessionState {
             session_id,
             analyzer: Analyzer::new(),
@@ -1350,8 +1636,9 @@ impl SessionState {
             aggregate_functions: HashMap::new(),
             window_functions: HashMap::new(),
             serializer_registry: Arc::new(EmptySerializerRegistry),
+            admission_control: None,
             config,
-            execution_props: ExecutionProps::new(),
+            execution_props: ExecutionProps::new().with_time_zone(time_zone),
             runtime_env: runtime,
             table_factories,
         }
@@ -1450,6 +1737,46 @@ impl SessionState {
             })
     }
 
+    /// Returns the schemas the table `table_ref` may belong to, with its
+    /// resolved reference in each of them, in the order they are searched.
+    ///
+    /// A table without schema is looked up in the schemas of the
+    /// `datafusion.catalog.search_path` option before the default schema.
+    /// Schemas which do not exist are skipped.
+    pub(crate) fn schemas_for_ref<'a>(
+        &'a self,
+        table_ref: impl Into<TableReference<'a>>,
+    ) -> Vec<(ResolvedTableReference<'a>, Arc<dyn SchemaProvider>)> {
+        let table_ref = table_ref.into();
+        let catalog = &self.config_options().catalog;
+        let mut candidates = vec![];
+        if let TableReference::Bare { table } = &table_ref {
+            let search_path = catalog.search_path.as_deref().unwrap_or_default();
+            for entry in search_path.split(',').map(str::trim) {
+                if entry.is_empty() {
+                    continue;
+                }
+                let (catalog_name, schema_name) = entry
+                    .split_once('.')
+                    .unwrap_or((catalog.default_catalog.as_str(), entry));
+                candidates.push(ResolvedTableReference {
+                    catalog: catalog_name.into(),
+                    schema: schema_name.into(),
+                    table: table.clone(),
+                });
+            }
+        }
+        candidates.push(self.resolve_table_ref(table_ref));
+
+        candidates
+            .into_iter()
+            .filter_map(|resolved| {
+                let schema = self.schema_for_ref(resolved.clone()).ok()?;
+                Some((resolved, schema))
+            })
+            .collect()
+    }
+
     /// Replace the random session id.
     pub fn with_session_id(mut self, session_id: String) -> Self {
         self.session_id = session_id;
@@ -1519,6 +1846,20 @@ impl SessionState {
         self
     }
 
+    /// Adds a new [`PhysicalOptimizerRule`] at `position` among the registered
+    /// rules, e.g. after `EnforceDistribution`.
+    ///
+    /// Returns an error if `position` refers to a rule that is not registered.
+    pub fn add_physical_optimizer_rule_at(
+        mut self,
+        optimizer_rule: Arc<dyn PhysicalOptimizerRule + Send + Sync>,
+        position: RulePosition,
+    ) -> Result<Self> {
+        self.physical_optimizers
+            .add_rule(optimizer_rule, position)?;
+        Ok(self)
+    }
+
     /// Replace the extension [`SerializerRegistry`]
     pub fn with_serializer_registry(
         mut self,
@@ -1528,6 +1869,16 @@ impl SessionState {
         self
     }
 
+    /// Replace the [`AdmissionControl`] deciding whether, and when, the
+    /// queries of this session run
+    pub fn with_admission_control(
+        mut self,
+        admission_control: Arc<dyn AdmissionControl>,
+    ) -> Self {
+        self.admission_control = Some(admission_control);
+        self
+    }
+
     /// Get the table factories
     pub fn table_factories(&self) -> &HashMap<String, Arc<dyn TableProviderFactory>> {
         &self.table_factories
@@ -1669,6 +2020,7 @@ impl SessionState {
         let mut provider = SessionContextProvider {
             state: self,
             tables: HashMap::with_capacity(references.len()),
+            search_path_tables: HashMap::new(),
         };
 
         let enable_ident_normalization =
@@ -1677,12 +2029,25 @@ impl SessionState {
             self.config.options().sql_parser.parse_float_as_decimal;
         for reference in references {
             let table = reference.table();
-            let resolved = self.resolve_table_ref(&reference);
-            if let Entry::Vacant(v) = provider.tables.entry(resolved.to_string()) {
-                if let Ok(schema) = self.schema_for_ref(resolved) {
-                    if let Some(table) = schema.table(table).await {
-                        v.insert(provider_as_source(table));
+            for (resolved, schema) in self.schemas_for_ref(&reference) {
+                let resolved = resolved.to_string();
+                let found = match provider.tables.entry(resolved.clone()) {
+                    Entry::Occupied(_) => true,
+                    Entry::Vacant(v) => match schema.table(table).await {
+                        Some(table) => {
+                            v.insert(provider_as_source(table));
+                            true
+                        }
+                        None => false,
+                    },
+                };
+                if found {
+                    if let TableReference::Bare { table } = &reference {
+                        provider
+                            .search_path_tables
+                            .insert(table.to_string(), resolved);
                     }
+                    break;
                 }
             }
         }
@@ -1751,7 +2116,7 @@ impl SessionState {
                 .push(analyzed_plan.to_stringified(PlanType::FinalAnalyzedLogicalPlan));
 
             // optimize the child plan, capturing the output of each optimizer
-            let optimized_plan = self.optimizer.optimize(
+            let optimized_plan = self.optimizer.optimize_with_metrics(
                 &analyzed_plan,
                 self,
                 |optimized_plan, optimizer| {
@@ -1761,7 +2126,20 @@ impl SessionState {
                 },
             );
             let (plan, logical_optimization_succeeded) = match optimized_plan {
-                Ok(plan) => (Arc::new(plan), true),
+                Ok((plan, metrics)) => {
+                    if self.config_options().explain.show_optimizer_metrics {
+                        let metrics = metrics
+                            .iter()
+                            .map(|m| m.to_string())
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        stringified_plans.push(StringifiedPlan::new(
+                            PlanType::LogicalOptimizerMetrics,
+                            metrics,
+                        ));
+                    }
+                    (Arc::new(plan), true)
+                }
                 Err(DataFusionError::Context(optimizer_name, err)) => {
                     let plan_type = PlanType::OptimizedLogicalPlan { optimizer_name };
                     stringified_plans
@@ -1793,14 +2171,97 @@ impl SessionState {
     ///
     /// This function will error for [`LogicalPlan`]s such as catalog
     /// DDL `CREATE TABLE` must be handled by another layer.
+    ///
+    /// The plans of queries are admitted by the [`AdmissionControl`] of the
+    /// session, if any, which may wait or fail.
     pub async fn create_physical_plan(
         &self,
         logical_plan: &LogicalPlan,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let logical_plan = self.optimize(logical_plan)?;
-        self.query_planner
-            .create_physical_plan(&logical_plan, self)
-            .await
+        let optimized_plan = self.optimize(logical_plan)?;
+        let physical_plan = self
+            .query_planner
+            .create_physical_plan(&optimized_plan, self)
+            .await?;
+        let physical_plan = self.instrument(logical_plan, physical_plan)?;
+        self.admit(logical_plan, physical_plan).await
+    }
+
+    /// Wraps each operator of `physical_plan` to open a `tracing` span per
+    /// partition if `datafusion.execution.tracing_spans` is enabled, unless
+    /// `logical_plan` only explains or analyzes the query
+    fn instrument(
+        &self,
+        logical_plan: &LogicalPlan,
+        physical_plan: Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !self.config_options().execution.tracing_spans
+            || matches!(
+                logical_plan,
+                LogicalPlan::Explain(_) | LogicalPlan::Analyze(_)
+            )
+        {
+            return Ok(physical_plan);
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            datafusion_physical_plan::instrument::instrument_plan(physical_plan)
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            not_impl_err!(
+                "datafusion.execution.tracing_spans requires the tracing feature"
+            )
+        }
+    }
+
+    /// Admits `physical_plan` with the [`AdmissionControl`] of the session,
+    /// unless it only explains `logical_plan` without running it
+    async fn admit(
+        &self,
+        logical_plan: &LogicalPlan,
+        physical_plan: Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match &self.admission_control {
+            Some(admission_control)
+                if !matches!(logical_plan, LogicalPlan::Explain(_)) =>
+            {
+                let estimate = QueryEstimate::new(&physical_plan);
+                admission_control.admit(physical_plan, &estimate).await
+            }
+            _ => Ok(physical_plan),
+        }
+    }
+
+    /// Creates a physical plan from a logical plan, like
+    /// [`Self::create_physical_plan`], along with [`PlanWarning`]s about the
+    /// hazards found while planning it, such as cross joins without join
+    /// condition or implicit casts that may lose information.
+    ///
+    /// No warnings are returned for `EXPLAIN` plans.
+    pub async fn create_physical_plan_with_warnings(
+        &self,
+        logical_plan: &LogicalPlan,
+    ) -> Result<(Arc<dyn ExecutionPlan>, Vec<PlanWarning>)> {
+        if let LogicalPlan::Explain(_) = logical_plan {
+            let physical_plan = self.create_physical_plan(logical_plan).await?;
+            return Ok((physical_plan, vec![]));
+        }
+        let analyzed_plan =
+            self.analyzer
+                .execute_and_check(logical_plan, self.options(), |_, _| {})?;
+        let mut warnings = lossy_cast_warnings(logical_plan, &analyzed_plan)?;
+        let optimized_plan = self.optimizer.optimize(&analyzed_plan, self, |_, _| {})?;
+        warnings.extend(cross_join_warnings(&optimized_plan)?);
+        let physical_plan = self
+            .query_planner
+            .create_physical_plan(&optimized_plan, self)
+            .await?;
+        warnings.extend(default_selectivity_warnings(&physical_plan)?);
+        let physical_plan = self.instrument(logical_plan, physical_plan)?;
+        let physical_plan = self.admit(logical_plan, physical_plan).await?;
+        Ok((physical_plan, warnings))
     }
 
     /// Return the session ID
@@ -1818,6 +2279,17 @@ impl SessionState {
         &self.execution_props
     }
 
+    /// Sets the time zone of the execution properties to the
+    /// `datafusion.execution.time_zone` option
+    fn update_time_zone(&mut self) {
+        self.execution_props.time_zone = self
+            .config_options()
+            .execution
+            .time_zone
+            .clone()
+            .map(Into::into);
+    }
+
     /// Return the [`SessionConfig`]
     pub fn config(&self) -> &SessionConfig {
         &self.config
@@ -1872,11 +2344,21 @@ impl SessionState {
 struct SessionContextProvider<'a> {
     state: &'a SessionState,
     tables: HashMap<String, Arc<dyn TableSource>>,
+    /// The resolved references of the tables without schema found in the
+    /// `search_path`, or in the default schema
+    search_path_tables: HashMap<String, String>,
 }
 
 impl<'a> ContextProvider for SessionContextProvider<'a> {
     fn get_table_source(&self, name: TableReference) -> Result<Arc<dyn TableSource>> {
-        let name = self.state.resolve_table_ref(name).to_string();
+        let search_path_table = match &name {
+            TableReference::Bare { table } => self.search_path_tables.get(table.as_ref()),
+            _ => None,
+        };
+        let name = match search_path_table {
+            Some(resolved) => resolved.clone(),
+            None => self.state.resolve_table_ref(name).to_string(),
+        };
         self.tables
             .get(&name)
             .cloned()
@@ -1899,6 +2381,15 @@ impl<'a> ContextProvider for SessionContextProvider<'a> {
         Ok(provider_as_source(provider))
     }
 
+    fn create_cte_work_table(
+        &self,
+        name: &str,
+        schema: SchemaRef,
+    ) -> Result<Arc<dyn TableSource>> {
+        let table = Arc::new(CteWorkTable::new(name, schema));
+        Ok(provider_as_source(table))
+    }
+
     fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
         self.state.scalar_functions().get(name).cloned()
     }
@@ -2119,19 +2610,80 @@ mod tests {
     use super::*;
     use crate::assert_batches_eq;
     use crate::execution::context::QueryPlanner;
-    use crate::execution::memory_pool::MemoryConsumer;
+    use crate::execution::memory_pool::{GreedyMemoryPool, MemoryConsumer};
     use crate::execution::runtime_env::RuntimeConfig;
     use crate::test;
     use crate::test_util::{plan_and_collect, populate_csv_partitions};
     use crate::variable::VarType;
-    use arrow_schema::Schema;
+    use arrow::array::{ArrayRef, Int32Array};
+    use arrow_schema::{Field, Schema};
     use async_trait::async_trait;
+    use datafusion_common::assert_contains;
     use datafusion_expr::Expr;
     use std::env;
     use std::path::PathBuf;
     use std::sync::Weak;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn query_timeout() -> Result<()> {
+        let config =
+            SessionConfig::new().set_usize("datafusion.execution.query_timeout", 0);
+        let ctx = SessionContext::new_with_config(config);
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int32Array::from(vec![1, 2, 2])) as ArrayRef,
+        )])?;
+        ctx.register_batch("t", batch)?;
+
+        let err = ctx
+            .sql("SELECT a, count(*) FROM t GROUP BY a")
+            .await?
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err.find_root(), DataFusionError::Cancelled(_)),
+            "{err}"
+        );
+        assert_contains!(
+            err.to_string(),
+            "Query cancelled: the query ran longer than its timeout of 0ms"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tracing_spans() -> Result<()> {
+        let config =
+            SessionConfig::new().set_bool("datafusion.execution.tracing_spans", true);
+        let ctx = SessionContext::new_with_config(config);
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int32Array::from(vec![1, 2, 2])) as ArrayRef,
+        )])?;
+        ctx.register_batch("t", batch)?;
+
+        let df = ctx.sql("SELECT a, count(*) FROM t GROUP BY a").await?;
+        let plan = df.create_physical_plan().await;
+
+        #[cfg(feature = "tracing")]
+        {
+            let plan = plan?;
+            assert!(plan
+                .as_any()
+                .is::<datafusion_physical_plan::instrument::InstrumentedExec>());
+            let batches = crate::physical_plan::collect(plan, ctx.task_ctx()).await?;
+            assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+        }
+        #[cfg(not(feature = "tracing"))]
+        assert_contains!(
+            plan.unwrap_err().to_string(),
+            "datafusion.execution.tracing_spans requires the tracing feature"
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn shared_memory_and_disk_manager() {
         // Demonstrate the ability to share DiskManager and
@@ -2197,6 +2749,63 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn sql_to_schema() -> Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_table("dual", test::create_table_dual())?;
+
+        let (schema, parameter_types) = ctx
+            .sql_to_schema("SELECT id, name AS n FROM dual WHERE id = $1")
+            .await?;
+        let expected = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("n", DataType::Utf8, false),
+        ]);
+        assert_eq!(schema.as_ref(), &expected);
+        assert_eq!(
+            parameter_types,
+            HashMap::from([("$1".to_string(), Some(DataType::Int32))])
+        );
+
+        // planning the statement does not run it
+        let (schema, _) = ctx.sql_to_schema("CREATE TABLE t (x INT)").await?;
+        assert!(schema.fields().is_empty());
+        assert!(!ctx.table_exist("t")?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sql_with_config_hints() -> Result<()> {
+        let ctx = SessionContext::new();
+        let batch_size = ctx.copied_config().batch_size();
+
+        let df = ctx
+            .sql(
+                "/*+ config(datafusion.execution.batch_size = '3', \
+                 datafusion.execution.target_partitions = 2) */ SELECT 1",
+            )
+            .await?;
+        let config = df.task_ctx().session_config().clone();
+        assert_eq!(config.batch_size(), 3);
+        assert_eq!(config.target_partitions(), 2);
+        assert_eq!(ctx.copied_config().batch_size(), batch_size);
+
+        // other hints are ignored
+        ctx.sql("/*+ INDEX(t) */ SELECT 1").await?;
+
+        let err = ctx
+            .sql("/*+ CONFIG(datafusion.execution.batch_size) */ SELECT 1")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Invalid configuration hint: datafusion.execution.batch_size"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn create_variable_err() -> Result<()> {
         let ctx = SessionContext::new();
@@ -2224,6 +2833,50 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn fork() -> Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_table("dual", test::create_table_dual())?;
+        ctx.sql("CREATE TABLE t (x INT)").await?.collect().await?;
+
+        let fork = ctx.fork();
+        assert_ne!(fork.session_id(), ctx.session_id());
+        assert!(Arc::ptr_eq(&fork.runtime_env(), &ctx.runtime_env()));
+
+        fork.sql("CREATE TABLE u (x INT)").await?.collect().await?;
+        fork.sql("DROP TABLE t").await?.collect().await?;
+        fork.sql("SET datafusion.execution.batch_size = 3")
+            .await?
+            .collect()
+            .await?;
+        assert!(fork.table_exist("dual")?);
+        assert!(fork.table_exist("u")?);
+        assert!(!fork.table_exist("t")?);
+        assert_eq!(fork.copied_config().batch_size(), 3);
+        let results = plan_and_collect(&fork, "SELECT id FROM dual").await?;
+        assert_eq!(results[0].num_rows(), 1);
+
+        // the parent is unchanged
+        assert!(ctx.table_exist("t")?);
+        assert!(!ctx.table_exist("u")?);
+        assert_ne!(ctx.copied_config().batch_size(), 3);
+
+        // with its own memory limit
+        let pool = Arc::new(GreedyMemoryPool::new(1024));
+        let runtime = RuntimeEnv {
+            memory_pool: pool.clone(),
+            ..ctx.runtime_env().as_ref().clone()
+        };
+        let fork = ctx.fork_with_runtime(Arc::new(runtime));
+        let mut reservation =
+            MemoryConsumer::new("test").register(&fork.runtime_env().memory_pool);
+        reservation.try_grow(512)?;
+        assert_eq!(pool.reserved(), 512);
+        assert_eq!(ctx.runtime_env().memory_pool.reserved(), 0);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn send_context_to_threads() -> Result<()> {
         // ensure SessionContexts can be used in a multi-threaded
@@ -2488,6 +3141,72 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn add_physical_optimizer_rule_at_position() -> Result<()> {
+        struct NoopRule(&'static str);
+
+        impl PhysicalOptimizerRule for NoopRule {
+            fn optimize(
+                &self,
+                plan: Arc<dyn ExecutionPlan>,
+                _config: &ConfigOptions,
+            ) -> Result<Arc<dyn ExecutionPlan>> {
+                Ok(plan)
+            }
+
+            fn name(&self) -> &str {
+                self.0
+            }
+
+            fn schema_check(&self) -> bool {
+                true
+            }
+        }
+
+        let runtime = Arc::new(RuntimeEnv::default());
+        let state = SessionState::new_with_config_rt(SessionConfig::new(), runtime)
+            .add_physical_optimizer_rule_at(
+                Arc::new(NoopRule("after_distribution")),
+                RulePosition::After("EnforceDistribution".to_string()),
+            )?
+            .add_physical_optimizer_rule_at(
+                Arc::new(NoopRule("before_coalesce")),
+                RulePosition::Before("coalesce_batches".to_string()),
+            )?
+            .add_physical_optimizer_rule_at(
+                Arc::new(NoopRule("first")),
+                RulePosition::First,
+            )?;
+
+        let names = state
+            .physical_optimizers()
+            .iter()
+            .map(|rule| rule.name())
+            .collect::<Vec<_>>();
+        let position = |name| names.iter().position(|n| *n == name).unwrap();
+        assert_eq!(position("first"), 0);
+        assert_eq!(
+            position("after_distribution"),
+            position("EnforceDistribution") + 1
+        );
+        assert_eq!(
+            position("before_coalesce") + 1,
+            position("coalesce_batches")
+        );
+
+        let err = state
+            .add_physical_optimizer_rule_at(
+                Arc::new(NoopRule("unknown")),
+                RulePosition::After("no_such_rule".to_string()),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Physical optimizer rule no_such_rule is not registered"
+        );
+        Ok(())
+    }
+
     struct MyPhysicalPlanner {}
 
     #[async_trait]
@@ -0,0 +1,407 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Export and import of the environment of a [`SessionContext`]
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use datafusion_common::utils::quote_identifier;
+use datafusion_common::{plan_err, DataFusionError};
+use datafusion_sql::parser::{DFParser, Statement as DFStatement};
+use sqlparser::ast::{Expr as SQLExpr, Statement as SQLStatement, Value};
+
+use super::{ConfigOptions, Result, SessionContext, TableType};
+use crate::catalog::information_schema::INFORMATION_SCHEMA;
+
+/// Prefix of the comment lines listing [`CatalogSnapshot::functions`]
+const FUNCTION_PREFIX: &str = "-- requires function: ";
+
+/// Prefix of the comment lines listing [`CatalogSnapshot::skipped`]
+const SKIPPED_PREFIX: &str = "-- skipped table: ";
+
+/// A description of the environment of a [`SessionContext`]: its
+/// configuration, catalogs, schemas, external tables and views, created by
+/// [`SessionContext::export_catalog`] and restored by
+/// [`SessionContext::import_catalog`].
+///
+/// A snapshot is serialized by its [`Display`](fmt::Display) implementation
+/// as a SQL script, and parsed back with [`FromStr`]:
+///
+/// ```text
+/// -- requires function: my_udf
+/// SET datafusion.execution.batch_size = '1024';
+/// CREATE SCHEMA IF NOT EXISTS datafusion.staging;
+/// CREATE EXTERNAL TABLE staging.t STORED AS CSV WITH HEADER ROW LOCATION 'data.csv';
+/// CREATE VIEW v AS SELECT my_udf(a) FROM staging.t;
+/// ```
+///
+/// Only tables whose provider has a SQL definition, such as the ones created
+/// by `CREATE EXTERNAL TABLE` and `CREATE VIEW`, are part of the snapshot:
+/// the data of in-memory tables and the implementation of user defined
+/// functions are not serialized.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CatalogSnapshot {
+    /// The configuration options whose value differs from their default, as
+    /// `(key, value)` pairs
+    pub settings: Vec<(String, String)>,
+    /// `CREATE DATABASE` and `CREATE SCHEMA` statements of the catalogs and
+    /// schemas besides the default ones
+    pub schemas: Vec<String>,
+    /// The definitions of the tables, e.g. `CREATE EXTERNAL TABLE`
+    /// statements
+    pub tables: Vec<String>,
+    /// The definitions of the views, as `CREATE VIEW` statements
+    pub views: Vec<String>,
+    /// The names of the user defined functions registered in the session,
+    /// which must be registered again before the snapshot is imported
+    pub functions: Vec<String>,
+    /// The qualified names of the tables that could not be exported because
+    /// they have no SQL definition
+    pub skipped: Vec<String>,
+}
+
+impl fmt::Display for CatalogSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for name in &self.functions {
+            writeln!(f, "{FUNCTION_PREFIX}{name}")?;
+        }
+        for name in &self.skipped {
+            writeln!(f, "{SKIPPED_PREFIX}{name}")?;
+        }
+        for (key, value) in &self.settings {
+            let value = Value::SingleQuotedString(value.clone());
+            writeln!(f, "SET {key} = {value};")?;
+        }
+        for statement in self.schemas.iter().chain(&self.tables).chain(&self.views) {
+            writeln!(f, "{statement};")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CatalogSnapshot {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut snapshot = Self::default();
+        for line in s.lines().map(str::trim) {
+            if let Some(name) = line.strip_prefix(FUNCTION_PREFIX) {
+                snapshot.functions.push(name.to_string());
+            } else if let Some(name) = line.strip_prefix(SKIPPED_PREFIX) {
+                snapshot.skipped.push(name.to_string());
+            }
+        }
+
+        for statement in DFParser::parse_sql(s)? {
+            match statement {
+                DFStatement::CreateExternalTable(create) => {
+                    snapshot.tables.push(create.to_string())
+                }
+                DFStatement::Statement(statement) => match *statement {
+                    SQLStatement::SetVariable {
+                        variable, value, ..
+                    } => {
+                        let value = match value.as_slice() {
+                            [SQLExpr::Value(Value::SingleQuotedString(value))] => {
+                                value.clone()
+                            }
+                            _ => {
+                                return plan_err!(
+                                    "Unsupported value for {variable} in catalog snapshot"
+                                )
+                            }
+                        };
+                        snapshot.settings.push((variable.to_string(), value));
+                    }
+                    statement @ (SQLStatement::CreateDatabase { .. }
+                    | SQLStatement::CreateSchema { .. }) => {
+                        snapshot.schemas.push(statement.to_string())
+                    }
+                    statement @ SQLStatement::CreateView { .. } => {
+                        snapshot.views.push(statement.to_string())
+                    }
+                    statement => {
+                        return plan_err!(
+                            "Unexpected statement in catalog snapshot: {statement}"
+                        )
+                    }
+                },
+                statement => {
+                    return plan_err!(
+                        "Unexpected statement in catalog snapshot: {statement}"
+                    )
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+impl SessionContext {
+    /// Exports the configuration, catalogs, schemas, external tables and
+    /// views of this context as a [`CatalogSnapshot`], so that they can be
+    /// persisted and restored with [`Self::import_catalog`].
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::execution::context::CatalogSnapshot;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// ctx.sql("CREATE EXTERNAL TABLE example STORED AS CSV WITH HEADER ROW LOCATION 'tests/data/example.csv'").await?;
+    /// let saved = ctx.export_catalog().await?.to_string();
+    ///
+    /// let restored = SessionContext::new();
+    /// restored.import_catalog(&saved.parse::<CatalogSnapshot>()?).await?;
+    /// restored.table("example").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn export_catalog(&self) -> Result<CatalogSnapshot> {
+        let state = self.state();
+        let mut snapshot = CatalogSnapshot::default();
+
+        let defaults: HashMap<_, _> = ConfigOptions::new()
+            .entries()
+            .into_iter()
+            .map(|entry| (entry.key, entry.value))
+            .collect();
+        for entry in state.config_options().entries() {
+            let Some(value) = entry.value else {
+                continue;
+            };
+            if defaults.get(&entry.key) != Some(&Some(value.clone())) {
+                snapshot.settings.push((entry.key, value));
+            }
+        }
+
+        let default_catalog = &state.config_options().catalog.default_catalog;
+        let default_schema = &state.config_options().catalog.default_schema;
+        let catalog_list = state.catalog_list();
+        let mut catalog_names = catalog_list.catalog_names();
+        catalog_names.sort();
+        for catalog_name in catalog_names {
+            let Some(catalog) = catalog_list.catalog(&catalog_name) else {
+                continue;
+            };
+            let quoted_catalog = quote_identifier(&catalog_name);
+            if &catalog_name != default_catalog {
+                snapshot
+                    .schemas
+                    .push(format!("CREATE DATABASE IF NOT EXISTS {quoted_catalog}"));
+            }
+
+            let mut schema_names = catalog.schema_names();
+            schema_names.sort();
+            for schema_name in schema_names {
+                if schema_name == INFORMATION_SCHEMA {
+                    continue;
+                }
+                let Some(schema) = catalog.schema(&schema_name) else {
+                    continue;
+                };
+                let quoted_schema = quote_identifier(&schema_name);
+                if &catalog_name != default_catalog || &schema_name != default_schema {
+                    snapshot.schemas.push(format!(
+                        "CREATE SCHEMA IF NOT EXISTS {quoted_catalog}.{quoted_schema}"
+                    ));
+                }
+
+                let mut table_names = schema.table_names();
+                table_names.sort();
+                for table_name in table_names {
+                    let Some(table) = schema.table(&table_name).await else {
+                        continue;
+                    };
+                    match (table.get_table_definition(), table.table_type()) {
+                        (Some(definition), TableType::View) => {
+                            snapshot.views.push(definition.to_string())
+                        }
+                        (Some(definition), _) => {
+                            snapshot.tables.push(definition.to_string())
+                        }
+                        (None, _) => snapshot.skipped.push(format!(
+                            "{quoted_catalog}.{quoted_schema}.{}",
+                            quote_identifier(&table_name)
+                        )),
+                    }
+                }
+            }
+        }
+
+        let mut functions: Vec<_> = state
+            .scalar_functions()
+            .keys()
+            .chain(state.aggregate_functions().keys())
+            .chain(state.window_functions().keys())
+            .chain(state.table_functions.keys())
+            .cloned()
+            .collect();
+        functions.sort();
+        functions.dedup();
+        snapshot.functions = functions;
+
+        Ok(snapshot)
+    }
+
+    /// Restores a [`CatalogSnapshot`] created by [`Self::export_catalog`]
+    /// into this context.
+    ///
+    /// The user defined functions listed in the snapshot must already be
+    /// registered. Views are created after the tables, in an order that
+    /// satisfies the dependencies between them.
+    pub async fn import_catalog(&self, snapshot: &CatalogSnapshot) -> Result<()> {
+        let missing: Vec<_> = {
+            let state = self.state.read();
+            snapshot
+                .functions
+                .iter()
+                .filter(|name| {
+                    !state.scalar_functions().contains_key(*name)
+                        && !state.aggregate_functions().contains_key(*name)
+                        && !state.window_functions().contains_key(*name)
+                        && !state.table_functions.contains_key(*name)
+                })
+                .cloned()
+                .collect()
+        };
+        if !missing.is_empty() {
+            return plan_err!(
+                "Catalog snapshot requires functions that are not registered: {}",
+                missing.join(", ")
+            );
+        }
+
+        for (key, value) in &snapshot.settings {
+            let value = Value::SingleQuotedString(value.clone());
+            self.sql(&format!("SET {key} = {value}")).await?;
+        }
+        for statement in snapshot.schemas.iter().chain(&snapshot.tables) {
+            self.sql(statement).await?;
+        }
+
+        // a view can only be created once the views it selects from exist,
+        // so retry the failed ones for as long as others succeed
+        let mut pending: Vec<&String> = snapshot.views.iter().collect();
+        while !pending.is_empty() {
+            let mut failed = vec![];
+            let mut first_error = None;
+            for statement in &pending {
+                if let Err(e) = self.sql(statement).await {
+                    failed.push(*statement);
+                    first_error.get_or_insert(e);
+                }
+            }
+            if failed.len() == pending.len() {
+                return Err(first_error.unwrap());
+            }
+            pending = failed;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::context::SessionConfig;
+    use crate::test_util::populate_csv_partitions;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn export_and_import_catalog() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        populate_csv_partitions(&tmp_dir, 2, ".csv")?;
+        let path = tmp_dir.path().to_str().unwrap();
+
+        let ctx = SessionContext::new_with_config(
+            SessionConfig::new().with_information_schema(true),
+        );
+        ctx.sql("SET datafusion.execution.batch_size = 1024")
+            .await?;
+        ctx.sql("CREATE SCHEMA staging").await?;
+        ctx.sql(&format!(
+            "CREATE EXTERNAL TABLE staging.t (c1 INT, c2 BIGINT, c3 BOOLEAN) \
+             STORED AS CSV LOCATION '{path}'"
+        ))
+        .await?;
+        // `a` selects from `b`, which sorts after it
+        ctx.sql("CREATE VIEW b AS SELECT c1, c2 FROM staging.t WHERE c3")
+            .await?;
+        ctx.sql("CREATE VIEW a AS SELECT sum(c2) AS s FROM b")
+            .await?;
+        ctx.sql("CREATE TABLE m AS VALUES (1)").await?;
+
+        let snapshot = ctx.export_catalog().await?;
+        assert_eq!(
+            snapshot.settings,
+            vec![
+                (
+                    "datafusion.catalog.information_schema".to_string(),
+                    "true".to_string()
+                ),
+                (
+                    "datafusion.execution.batch_size".to_string(),
+                    "1024".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            snapshot.schemas,
+            vec!["CREATE SCHEMA IF NOT EXISTS datafusion.staging"]
+        );
+        assert_eq!(snapshot.tables.len(), 1);
+        assert_eq!(snapshot.views.len(), 2);
+        assert_eq!(snapshot.skipped, vec!["datafusion.public.m"]);
+        assert!(snapshot.functions.is_empty());
+
+        let parsed: CatalogSnapshot = snapshot.to_string().parse()?;
+        assert_eq!(parsed, snapshot);
+
+        let restored = SessionContext::new();
+        restored.import_catalog(&parsed).await?;
+        assert_eq!(restored.export_catalog().await?.views, snapshot.views);
+        assert_eq!(
+            restored.copied_config().options().execution.batch_size,
+            1024
+        );
+
+        let expected = ctx.sql("SELECT * FROM a").await?.collect().await?;
+        let actual = restored.sql("SELECT * FROM a").await?.collect().await?;
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn import_catalog_missing_function() {
+        let snapshot = CatalogSnapshot {
+            functions: vec!["my_udf".to_string()],
+            ..Default::default()
+        };
+        let err = SessionContext::new()
+            .import_catalog(&snapshot)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Catalog snapshot requires functions that are not registered: my_udf"
+        );
+    }
+}
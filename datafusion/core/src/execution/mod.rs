@@ -17,7 +17,9 @@
 
 //! Shared state for query planning and execution.
 
+pub mod admission;
 pub mod context;
+pub mod warnings;
 // backwards compatibility
 pub use crate::datasource::file_format::options;
 
@@ -0,0 +1,257 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`PlanWarning`]s about hazards in query plans that do not prevent them
+//! from running, but that users likely want to know about
+
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::ExecutionPlan;
+
+use arrow::datatypes::{DataType, TimeUnit};
+use datafusion_common::tree_node::{TreeNode, VisitRecursion};
+use datafusion_common::DFSchema;
+use datafusion_expr::expr::Cast;
+use datafusion_expr::{Expr, ExprSchemable, LogicalPlan};
+
+/// The kind of a [`PlanWarning`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlanWarningKind {
+    /// Two inputs are joined without a join condition, so that every row
+    /// of one is combined with every row of the other
+    CrossJoin,
+    /// A cast added to make the types of an expression match may lose
+    /// information, such as the fraction of a float cast to an integer
+    LossyImplicitCast,
+    /// The statistics are not sufficient to estimate the selectivity of a
+    /// filter, so a default selectivity is assumed when planning
+    DefaultSelectivity,
+}
+
+/// A hazard found while planning a query, returned by
+/// [`SessionState::create_physical_plan_with_warnings`]
+///
+/// [`SessionState::create_physical_plan_with_warnings`]: crate::execution::context::SessionState::create_physical_plan_with_warnings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanWarning {
+    /// The kind of the warning
+    pub kind: PlanWarningKind,
+    /// A description of the warning
+    pub message: String,
+}
+
+impl PlanWarning {
+    /// Creates a new warning of `kind`
+    pub fn new(kind: PlanWarningKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for PlanWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Returns warnings for the casts of `analyzed`, the result of analyzing
+/// `plan`, that may lose information and that are not part of `plan`
+pub(crate) fn lossy_cast_warnings(
+    plan: &LogicalPlan,
+    analyzed: &LogicalPlan,
+) -> Result<Vec<PlanWarning>> {
+    let mut explicit = HashSet::new();
+    visit_casts(plan, &mut |cast, _| {
+        explicit.insert(cast.to_string());
+    })?;
+
+    let mut warnings = vec![];
+    let mut reported = HashSet::new();
+    visit_casts(analyzed, &mut |cast, schema| {
+        let Expr::Cast(Cast { expr, data_type }) = cast else {
+            return;
+        };
+        let Ok(from) = expr.get_type(schema) else {
+            return;
+        };
+        let name = cast.to_string();
+        if is_lossy_cast(&from, data_type)
+            && !explicit.contains(&name)
+            && reported.insert(name.clone())
+        {
+            warnings.push(PlanWarning::new(
+                PlanWarningKind::LossyImplicitCast,
+                format!(
+                    "Implicit cast {name} from {from} to {data_type} may lose information"
+                ),
+            ));
+        }
+    })?;
+    Ok(warnings)
+}
+
+/// Calls `f` with every [`Expr::Cast`] of `plan`, and the schema its input
+/// is evaluated against
+fn visit_casts(plan: &LogicalPlan, f: &mut impl FnMut(&Expr, &DFSchema)) -> Result<()> {
+    plan.apply(&mut |plan| {
+        let inputs = plan.inputs();
+        // the expressions of joins refer to the columns of both inputs
+        let schema = match inputs.as_slice() {
+            [input] => input.schema(),
+            _ => plan.schema(),
+        };
+        for expr in plan.expressions() {
+            expr.apply(&mut |expr| {
+                if matches!(expr, Expr::Cast(_)) {
+                    f(expr, schema.as_ref());
+                }
+                Ok(VisitRecursion::Continue)
+            })?;
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(())
+}
+
+/// Returns true if casting values of type `from` to `to` may lose
+/// information, other than values that can not be cast at all
+fn is_lossy_cast(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+    match (from, to) {
+        (Float16 | Float32 | Float64 | Decimal128(_, _) | Decimal256(_, _), to)
+            if to.is_integer() =>
+        {
+            true
+        }
+        (Float64, Float32 | Float16) | (Float32, Float16) => true,
+        (Int64 | UInt64, Float32 | Float64) | (Int32 | UInt32, Float32) => true,
+        (Decimal128(_, from_scale) | Decimal256(_, from_scale), Float32 | Float64) => {
+            *from_scale > 0
+        }
+        (
+            Decimal128(_, from_scale) | Decimal256(_, from_scale),
+            Decimal128(_, to_scale) | Decimal256(_, to_scale),
+        ) => to_scale < from_scale,
+        (Timestamp(from_unit, _), Timestamp(to_unit, _)) => {
+            time_unit_rank(to_unit) < time_unit_rank(from_unit)
+        }
+        (Timestamp(_, _) | Date64, Date32) => true,
+        (from, to) => match (integer_bits(from), integer_bits(to)) {
+            (Some((from_signed, from_bits)), Some((to_signed, to_bits))) => {
+                match (from_signed, to_signed) {
+                    (false, true) => to_bits <= from_bits,
+                    (true, false) => true,
+                    _ => to_bits < from_bits,
+                }
+            }
+            _ => false,
+        },
+    }
+}
+
+/// Returns whether an integer type is signed, and its number of bits
+fn integer_bits(data_type: &DataType) -> Option<(bool, u8)> {
+    match data_type {
+        DataType::Int8 => Some((true, 8)),
+        DataType::Int16 => Some((true, 16)),
+        DataType::Int32 => Some((true, 32)),
+        DataType::Int64 => Some((true, 64)),
+        DataType::UInt8 => Some((false, 8)),
+        DataType::UInt16 => Some((false, 16)),
+        DataType::UInt32 => Some((false, 32)),
+        DataType::UInt64 => Some((false, 64)),
+        _ => None,
+    }
+}
+
+fn time_unit_rank(unit: &TimeUnit) -> u8 {
+    match unit {
+        TimeUnit::Second => 0,
+        TimeUnit::Millisecond => 1,
+        TimeUnit::Microsecond => 2,
+        TimeUnit::Nanosecond => 3,
+    }
+}
+
+/// Returns warnings for the cross joins of the optimized `plan`
+pub(crate) fn cross_join_warnings(plan: &LogicalPlan) -> Result<Vec<PlanWarning>> {
+    let mut warnings = vec![];
+    plan.apply(&mut |plan| {
+        if let LogicalPlan::CrossJoin(cross_join) = plan {
+            warnings.push(PlanWarning::new(
+                PlanWarningKind::CrossJoin,
+                format!(
+                    "Cross join of {} and {} without a join condition combines \
+                    every row of one with every row of the other",
+                    input_name(&cross_join.left),
+                    input_name(&cross_join.right),
+                ),
+            ));
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(warnings)
+}
+
+/// Returns the names of the relations of the columns of `plan`
+fn input_name(plan: &LogicalPlan) -> String {
+    let mut qualifiers = vec![];
+    for field in plan.schema().fields() {
+        if let Some(qualifier) = field.qualifier() {
+            let qualifier = qualifier.to_string();
+            if !qualifiers.contains(&qualifier) {
+                qualifiers.push(qualifier);
+            }
+        }
+    }
+    if qualifiers.is_empty() {
+        "an unnamed input".to_string()
+    } else {
+        qualifiers.join(", ")
+    }
+}
+
+/// Returns warnings for the filters of `plan` whose selectivity can not be
+/// estimated from the statistics of their input
+pub(crate) fn default_selectivity_warnings(
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Result<Vec<PlanWarning>> {
+    let mut warnings = vec![];
+    plan.apply(&mut |plan| {
+        if let Some(filter) = plan.as_any().downcast_ref::<FilterExec>() {
+            if filter.uses_default_selectivity()? {
+                warnings.push(PlanWarning::new(
+                    PlanWarningKind::DefaultSelectivity,
+                    format!(
+                        "Statistics are not sufficient to estimate the selectivity \
+                        of filter {}, assuming {}%",
+                        filter.predicate(),
+                        filter.default_selectivity()
+                    ),
+                ));
+            }
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(warnings)
+}
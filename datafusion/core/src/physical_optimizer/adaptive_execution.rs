@@ -0,0 +1,508 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`AdaptiveExecution`] re-optimizes the joins of a physical plan during
+//! execution, once the actual sizes of their inputs are known
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::execution::context::TaskContext;
+use crate::physical_optimizer::dynamic_join_filter::DynamicJoinFilter;
+use crate::physical_optimizer::enforce_distribution::EnforceDistribution;
+use crate::physical_optimizer::enforce_sorting::EnforceSorting;
+use crate::physical_optimizer::join_selection::JoinSelection;
+use crate::physical_optimizer::output_requirements::OutputRequirements;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::aggregates::{AggregateExec, AggregateMode};
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::joins::{
+    CrossJoinExec, HashJoinExec, NestedLoopJoinExec, PartitionMode, RangeJoinExec,
+    SortMergeJoinExec,
+};
+use crate::physical_plan::memory::MemoryExec;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::stream::RecordBatchStreamAdapter;
+use crate::physical_plan::{
+    collect_partitioned, displayable, unbounded_output, with_new_children_if_necessary,
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+};
+
+use arrow::datatypes::SchemaRef;
+use datafusion_common::{internal_err, Statistics};
+use datafusion_physical_expr::PhysicalSortExpr;
+
+use futures::future::{self, BoxFuture};
+use futures::{FutureExt, TryStreamExt};
+use log::debug;
+
+/// Optimizer rule that wraps a physical plan in an [`AdaptiveExec`] when
+/// `datafusion.optimizer.enable_adaptive_execution` is set and the plan
+/// contains a join with an input that ends in a pipeline breaker.
+///
+/// The pipeline breakers below joins, i.e. aggregations, sorts and the
+/// inputs collected by `CollectLeft` hash joins and cross joins, have to
+/// consume their whole input before the join produces its first row anyway.
+/// [`AdaptiveExec`] executes them first, and re-optimizes the rest of the
+/// plan using the actual number of rows and bytes they produced, rather than
+/// the estimates available during planning. For example, a hash join whose
+/// inputs were both aggregations of unknown size may turn out to have a
+/// small input, and collect it into a single partition instead of
+/// repartitioning both of them.
+#[derive(Default)]
+pub struct AdaptiveExecution {}
+
+impl AdaptiveExecution {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for AdaptiveExecution {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_adaptive_execution
+            || plan.as_any().is::<AdaptiveExec>()
+            || unbounded_output(&plan)
+            || !contains_stage(&plan)
+        {
+            return Ok(plan);
+        }
+        Ok(Arc::new(AdaptiveExec::new(plan)))
+    }
+
+    fn name(&self) -> &str {
+        "adaptive_execution"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Executes the pipeline breakers below the joins of its input first, then
+/// re-optimizes the joins using the actual sizes of their results, and
+/// executes the re-optimized plan. See [`AdaptiveExecution`] for details.
+///
+/// The results of the pipeline breakers are buffered in memory until the
+/// rest of the plan consumes them. The output of the re-optimized plan is
+/// merged into a single partition.
+#[derive(Debug)]
+pub struct AdaptiveExec {
+    input: Arc<dyn ExecutionPlan>,
+}
+
+impl AdaptiveExec {
+    /// Create a new `AdaptiveExec` re-optimizing `input` during execution
+    pub fn new(input: Arc<dyn ExecutionPlan>) -> Self {
+        Self { input }
+    }
+
+    /// The plan to re-optimize
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+}
+
+impl DisplayAs for AdaptiveExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "AdaptiveExec")
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for AdaptiveExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        // the re-optimized plan keeps the ordering of a single partition input
+        if self.input.output_partitioning().partition_count() == 1 {
+            self.input.output_ordering()
+        } else {
+            None
+        }
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        Ok(children[0])
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self::new(children.swap_remove(0))))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return internal_err!("AdaptiveExec invalid partition {partition}");
+        }
+
+        let input = self.input.clone();
+        let ordering = self.output_ordering().map(|ordering| ordering.to_vec());
+        let stream = futures::stream::once(async move {
+            let plan = materialize_stages(input, context.clone()).await?;
+            let plan = reoptimize(plan, context.session_config().options())?;
+            debug!(
+                "Re-optimized physical plan:\n{}",
+                displayable(plan.as_ref()).indent(false)
+            );
+            let plan: Arc<dyn ExecutionPlan> =
+                match (plan.output_partitioning().partition_count(), ordering) {
+                    (1, _) => plan,
+                    (_, Some(ordering)) => {
+                        Arc::new(SortPreservingMergeExec::new(ordering, plan))
+                    }
+                    (_, None) => Arc::new(CoalescePartitionsExec::new(plan)),
+                };
+            plan.execute(0, context)
+        })
+        .try_flatten();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.input.statistics()
+    }
+}
+
+/// Returns true if `plan` contains a join with an input that
+/// [`materialize_stages`] would execute
+fn contains_stage(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    let children = plan.children();
+    let has_stage_input = is_join(plan)
+        && children.iter().enumerate().any(|(index, child)| {
+            collects_input(plan, index) || contains_pipeline_breaker(child)
+        });
+    has_stage_input || children.iter().any(contains_stage)
+}
+
+fn is_join(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    let plan_any = plan.as_any();
+    plan_any.is::<HashJoinExec>()
+        || plan_any.is::<SortMergeJoinExec>()
+        || plan_any.is::<NestedLoopJoinExec>()
+        || plan_any.is::<RangeJoinExec>()
+        || plan_any.is::<CrossJoinExec>()
+}
+
+/// Returns true if the join `plan` collects its `index`th input into memory
+/// before producing any row
+fn collects_input(plan: &Arc<dyn ExecutionPlan>, index: usize) -> bool {
+    let plan_any = plan.as_any();
+    let collects_left = plan_any.is::<CrossJoinExec>()
+        || plan_any
+            .downcast_ref::<HashJoinExec>()
+            .map_or(false, |join| {
+                *join.partition_mode() == PartitionMode::CollectLeft
+            });
+    index == 0 && collects_left
+}
+
+/// Returns true if `plan` consumes its whole input before producing its
+/// first row
+fn is_pipeline_breaker(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    let plan_any = plan.as_any();
+    plan_any.is::<SortExec>()
+        || plan_any
+            .downcast_ref::<AggregateExec>()
+            .map_or(false, |aggregate| {
+                matches!(
+                    aggregate.mode(),
+                    AggregateMode::Final
+                        | AggregateMode::FinalPartitioned
+                        | AggregateMode::Single
+                        | AggregateMode::SinglePartitioned
+                )
+            })
+}
+
+/// Returns true if rows flow from `plan`'s only input to its output without
+/// being held back or filtered
+fn is_pass_through(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    let plan_any = plan.as_any();
+    plan_any.is::<ProjectionExec>()
+        || plan_any.is::<CoalesceBatchesExec>()
+        || plan_any.is::<CoalescePartitionsExec>()
+        || plan_any.is::<RepartitionExec>()
+}
+
+/// Returns true if `plan` is a pipeline breaker, possibly below operators
+/// passing its rows through
+fn contains_pipeline_breaker(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    is_pipeline_breaker(plan)
+        || (is_pass_through(plan) && contains_pipeline_breaker(&plan.children()[0]))
+}
+
+/// Executes the pipeline breakers at the top of the inputs of the joins in
+/// `plan`, and the inputs collected by joins, replacing them with
+/// [`MemoryExec`]s holding their results, whose statistics are exact.
+///
+/// Hash joins with an executed input are reset to [`PartitionMode::Auto`],
+/// so that [`JoinSelection`] chooses their mode again.
+fn materialize_stages(
+    plan: Arc<dyn ExecutionPlan>,
+    context: Arc<TaskContext>,
+) -> BoxFuture<'static, Result<Arc<dyn ExecutionPlan>>> {
+    async move {
+        let children = future::try_join_all(
+            plan.children()
+                .into_iter()
+                .map(|child| materialize_stages(child, context.clone())),
+        )
+        .await?;
+        if !is_join(&plan) {
+            return Ok(with_new_children_if_necessary(plan, children)?.into());
+        }
+
+        let mut materialized_any = false;
+        let mut new_children = Vec::with_capacity(children.len());
+        for (index, child) in children.into_iter().enumerate() {
+            let materialized =
+                match materialize_pipeline_breaker(child.clone(), context.clone()).await?
+                {
+                    Some(materialized) => Some(materialized),
+                    None if collects_input(&plan, index) => {
+                        Some(materialize(child.clone(), context.clone()).await?)
+                    }
+                    None => None,
+                };
+            materialized_any |= materialized.is_some();
+            new_children.push(materialized.unwrap_or(child));
+        }
+        let plan = with_new_children_if_necessary(plan, new_children)?.into();
+        if materialized_any {
+            reset_partition_mode(plan, context.session_config().options())
+        } else {
+            Ok(plan)
+        }
+    }
+    .boxed()
+}
+
+/// Executes the pipeline breaker at the top of `plan`, if any, returning
+/// `plan` with the breaker replaced by its results
+fn materialize_pipeline_breaker(
+    plan: Arc<dyn ExecutionPlan>,
+    context: Arc<TaskContext>,
+) -> BoxFuture<'static, Result<Option<Arc<dyn ExecutionPlan>>>> {
+    async move {
+        if is_pipeline_breaker(&plan) {
+            return materialize(plan, context).await.map(Some);
+        }
+        if !is_pass_through(&plan) {
+            return Ok(None);
+        }
+        let input = plan.children().swap_remove(0);
+        match materialize_pipeline_breaker(input, context).await? {
+            Some(input) => Ok(Some(plan.with_new_children(vec![input])?)),
+            None => Ok(None),
+        }
+    }
+    .boxed()
+}
+
+/// Executes `plan`, returning a [`MemoryExec`] with its results
+async fn materialize(
+    plan: Arc<dyn ExecutionPlan>,
+    context: Arc<TaskContext>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let partitions = collect_partitioned(plan.clone(), context).await?;
+    let mut memory = MemoryExec::try_new(&partitions, plan.schema(), None)?;
+    if let Some(ordering) = plan.output_ordering() {
+        memory = memory.with_sort_information(vec![ordering.to_vec()]);
+    }
+    Ok(Arc::new(memory))
+}
+
+/// Resets the mode of the hash join `plan` to [`PartitionMode::Auto`], if
+/// the planner would have let [`JoinSelection`] choose it and it was not
+/// chosen by a `BROADCAST` hint
+fn reset_partition_mode(
+    plan: Arc<dyn ExecutionPlan>,
+    config: &ConfigOptions,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let Some(join) = plan.as_any().downcast_ref::<HashJoinExec>() else {
+        return Ok(plan);
+    };
+    if *join.partition_mode() == PartitionMode::Auto
+        || join.broadcast_hint().is_some()
+        || config.execution.target_partitions <= 1
+        || !config.optimizer.repartition_joins
+    {
+        return Ok(plan);
+    }
+    Ok(Arc::new(HashJoinExec::try_new(
+        join.left().clone(),
+        join.right().clone(),
+        join.on().to_vec(),
+        join.filter().cloned(),
+        join.join_type(),
+        PartitionMode::Auto,
+        join.null_equals_null(),
+    )?))
+}
+
+/// Re-optimizes the joins of `plan`, once their inputs have been executed
+fn reoptimize(
+    plan: Arc<dyn ExecutionPlan>,
+    config: &ConfigOptions,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let rules: Vec<Arc<dyn PhysicalOptimizerRule + Send + Sync>> = vec![
+        Arc::new(OutputRequirements::new_add_mode()),
+        Arc::new(JoinSelection::new()),
+        Arc::new(EnforceDistribution::new()),
+        Arc::new(EnforceSorting::new()),
+        Arc::new(OutputRequirements::new_remove_mode()),
+        Arc::new(DynamicJoinFilter::new()),
+    ];
+    rules
+        .iter()
+        .try_fold(plan, |plan, rule| rule.optimize(plan, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_sorted_eq;
+    use crate::datasource::MemTable;
+    use crate::prelude::{SessionConfig, SessionContext};
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+
+    fn session_context() -> Result<SessionContext> {
+        let mut config = SessionConfig::new().with_target_partitions(4);
+        config.options_mut().optimizer.enable_adaptive_execution = true;
+        let ctx = SessionContext::new_with_config(config);
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        for (name, modulus) in [("t1", 10), ("t2", 5)] {
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from_iter_values(0..100)),
+                    Arc::new(Int32Array::from_iter_values((0..100).map(|v| v % modulus))),
+                ],
+            )?;
+            let table = MemTable::try_new(schema.clone(), vec![vec![batch]])?;
+            ctx.register_table(name, Arc::new(table))?;
+        }
+        Ok(ctx)
+    }
+
+    const SQL: &str = "SELECT x.b, x.c AS c1, y.c AS c2 FROM \
+        (SELECT b, count(*) AS c FROM t1 GROUP BY b) x \
+        JOIN (SELECT b, count(*) AS c FROM t2 GROUP BY b) y ON x.b = y.b";
+
+    #[tokio::test]
+    async fn reoptimize_join_of_aggregations() -> Result<()> {
+        let ctx = session_context()?;
+        let plan = ctx.sql(SQL).await?.create_physical_plan().await?;
+        let adaptive = plan
+            .as_any()
+            .downcast_ref::<AdaptiveExec>()
+            .expect("AdaptiveExec at the root of the plan");
+
+        // without statistics, the planner repartitions both aggregations
+        let formatted = displayable(plan.as_ref()).indent(true).to_string();
+        assert!(
+            formatted.contains("HashJoinExec: mode=Partitioned"),
+            "{formatted}"
+        );
+
+        // once executed, they are small enough to be collected
+        let task_ctx = ctx.task_ctx();
+        let materialized =
+            materialize_stages(adaptive.input().clone(), task_ctx.clone()).await?;
+        let reoptimized = reoptimize(materialized, task_ctx.session_config().options())?;
+        let formatted = displayable(reoptimized.as_ref()).indent(true).to_string();
+        assert!(
+            formatted.contains("HashJoinExec: mode=CollectLeft"),
+            "{formatted}"
+        );
+        assert!(formatted.contains("MemoryExec"), "{formatted}");
+        assert!(!formatted.contains("AggregateExec"), "{formatted}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn execute_adaptive_plan() -> Result<()> {
+        let ctx = session_context()?;
+        let batches = ctx.sql(SQL).await?.collect().await?;
+        let expected = [
+            "+---+----+----+",
+            "| b | c1 | c2 |",
+            "+---+----+----+",
+            "| 0 | 10 | 20 |",
+            "| 1 | 10 | 20 |",
+            "| 2 | 10 | 20 |",
+            "| 3 | 10 | 20 |",
+            "| 4 | 10 | 20 |",
+            "+---+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+}
@@ -51,7 +51,6 @@ impl PhysicalOptimizerRule for CoalesceBatches {
             return Ok(plan);
         }
 
-        let target_batch_size = config.execution.batch_size;
         plan.transform_up(&|plan| {
             let plan_any = plan.as_any();
             // The goal here is to detect operators that could produce small batches and only
@@ -71,6 +70,8 @@ impl PhysicalOptimizerRule for CoalesceBatches {
                     })
                     .unwrap_or(false);
             if wrap_in_coalesce {
+                let target_batch_size =
+                    config.execution.batch_size_for_schema(&plan.schema());
                 Ok(Transformed::Yes(Arc::new(CoalesceBatchesExec::new(
                     plan,
                     target_batch_size,
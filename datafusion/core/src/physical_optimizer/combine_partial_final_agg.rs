@@ -125,7 +125,7 @@ impl PhysicalOptimizerRule for CombinePartialFinalAggregate {
     }
 }
 
-type GroupExprsRef<'a> = (
+pub(crate) type GroupExprsRef<'a> = (
     &'a PhysicalGroupBy,
     &'a [Arc<dyn AggregateExpr>],
     &'a [Option<Arc<dyn PhysicalExpr>>],
@@ -137,7 +137,7 @@ type GroupExprs = (
     Vec<Option<Arc<dyn PhysicalExpr>>>,
 );
 
-fn can_combine(final_agg: GroupExprsRef, partial_agg: GroupExprsRef) -> bool {
+pub(crate) fn can_combine(final_agg: GroupExprsRef, partial_agg: GroupExprsRef) -> bool {
     let (final_group_by, final_aggr_expr, final_filter_expr) =
         normalize_group_exprs(final_agg);
     let (input_group_by, input_aggr_expr, input_filter_expr) =
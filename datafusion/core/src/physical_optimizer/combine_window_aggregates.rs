@@ -0,0 +1,258 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CombineWindowAggregates`] merges adjacent window aggregations whose
+//! window specifications are compatible
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::windows::{BoundedWindowAggExec, WindowAggExec, WindowExpr};
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_physical_expr::utils::collect_columns;
+use datafusion_physical_expr::{physical_exprs_equal, PhysicalExpr};
+
+/// Optimizer rule that merges a window aggregation directly above another
+/// one into a single operator, when both partition their input the same way
+/// and the input of the lower one is already ordered as the upper one
+/// requires:
+///
+/// ```text
+/// BoundedWindowAggExec: wdw=[SUM(c2) ...], mode=[Sorted]
+///   BoundedWindowAggExec: wdw=[COUNT(c2) ...], mode=[Sorted]
+///     SortExec: expr=[c1@0 ASC, c2@1 ASC]
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// BoundedWindowAggExec: wdw=[COUNT(c2) ..., SUM(c2) ...], mode=[Sorted]
+///   SortExec: expr=[c1@0 ASC, c2@1 ASC]
+/// ```
+///
+/// The planner creates one window aggregation per window specification, and
+/// `EnforceSorting` removes the sorts between those whose orderings are
+/// compatible (such as `ORDER BY c1` and `ORDER BY c1, c2`), so this saves
+/// the partitioning and buffering of the input of all but one of them.
+///
+/// The upper aggregation can only be merged if it does not use the results
+/// of the lower one, which would not be computed yet.
+#[derive(Default)]
+pub struct CombineWindowAggregates {}
+
+impl CombineWindowAggregates {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for CombineWindowAggregates {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            if let Some(window) = plan.as_any().downcast_ref::<BoundedWindowAggExec>() {
+                let Some(child) = window
+                    .input()
+                    .as_any()
+                    .downcast_ref::<BoundedWindowAggExec>()
+                else {
+                    return Ok(Transformed::No(plan));
+                };
+                if window.input_order_mode != child.input_order_mode
+                    || !can_combine(
+                        &plan,
+                        window.window_expr(),
+                        &window.partition_keys,
+                        window.input(),
+                        child.window_expr(),
+                        &child.partition_keys,
+                        child.input(),
+                    )
+                {
+                    return Ok(Transformed::No(plan));
+                }
+                let window_expr = [child.window_expr(), window.window_expr()].concat();
+                Ok(Transformed::Yes(Arc::new(BoundedWindowAggExec::try_new(
+                    window_expr,
+                    child.input().clone(),
+                    child.partition_keys.clone(),
+                    child.input_order_mode.clone(),
+                )?)))
+            } else if let Some(window) = plan.as_any().downcast_ref::<WindowAggExec>() {
+                let Some(child) = window.input().as_any().downcast_ref::<WindowAggExec>()
+                else {
+                    return Ok(Transformed::No(plan));
+                };
+                if !can_combine(
+                    &plan,
+                    window.window_expr(),
+                    &window.partition_keys,
+                    window.input(),
+                    child.window_expr(),
+                    &child.partition_keys,
+                    child.input(),
+                ) {
+                    return Ok(Transformed::No(plan));
+                }
+                let window_expr = [child.window_expr(), window.window_expr()].concat();
+                Ok(Transformed::Yes(Arc::new(WindowAggExec::try_new(
+                    window_expr,
+                    child.input().clone(),
+                    child.partition_keys.clone(),
+                )?)))
+            } else {
+                Ok(Transformed::No(plan))
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "combine_window_aggregates"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns true if the window expressions of `plan`, whose input is a window
+/// aggregation with `child_window_expr` of `child_input`, can be evaluated
+/// together with the latter
+fn can_combine(
+    plan: &Arc<dyn ExecutionPlan>,
+    window_expr: &[Arc<dyn WindowExpr>],
+    partition_keys: &[Arc<dyn PhysicalExpr>],
+    input: &Arc<dyn ExecutionPlan>,
+    child_window_expr: &[Arc<dyn WindowExpr>],
+    child_partition_keys: &[Arc<dyn PhysicalExpr>],
+    child_input: &Arc<dyn ExecutionPlan>,
+) -> bool {
+    if input.output_partitioning().partition_count()
+        != child_input.output_partitioning().partition_count()
+        || !physical_exprs_equal(partition_keys, child_partition_keys)
+        || !physical_exprs_equal(
+            window_expr[0].partition_by(),
+            child_window_expr[0].partition_by(),
+        )
+    {
+        return false;
+    }
+    // the columns of the window expressions of `plan` keep their indices
+    // only if they come from the input of the child
+    let num_input_columns = child_input.schema().fields().len();
+    let uses_child_results = window_expr.iter().any(|expr| {
+        expr.expressions()
+            .iter()
+            .chain(expr.partition_by())
+            .chain(expr.order_by().iter().map(|sort| &sort.expr))
+            .flat_map(collect_columns)
+            .any(|column| column.index() >= num_input_columns)
+    });
+    if uses_child_results {
+        return false;
+    }
+    match &plan.required_input_ordering()[0] {
+        Some(requirement) => child_input
+            .equivalence_properties()
+            .ordering_satisfy_requirement(requirement),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_optimizer::test_utils::{
+        bounded_window_exec, memory_exec, sort_exec, sort_expr,
+    };
+    use crate::physical_plan::displayable;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]))
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let optimized = CombineWindowAggregates::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap();
+        displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn combine_windows_with_same_ordering() {
+        let schema = schema();
+        let sort = sort_exec(vec![sort_expr("a", &schema)], memory_exec(&schema));
+        let lower = bounded_window_exec("a", vec![sort_expr("a", &schema)], sort);
+        let plan = bounded_window_exec("b", vec![sort_expr("a", &lower.schema())], lower);
+
+        let expected = [
+            "BoundedWindowAggExec: wdw=[count: Ok(Field { name: \"count\", data_type: Int64, nullable: true, dict_id: 0, dict_is_ordered: false, metadata: {} }), frame: WindowFrame { units: Range, start_bound: Preceding(NULL), end_bound: CurrentRow }, count: Ok(Field { name: \"count\", data_type: Int64, nullable: true, dict_id: 0, dict_is_ordered: false, metadata: {} }), frame: WindowFrame { units: Range, start_bound: Preceding(NULL), end_bound: CurrentRow }], mode=[Sorted]",
+            "  SortExec: expr=[a@0 ASC]",
+            "    MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn keep_windows_using_lower_results() {
+        let schema = schema();
+        let sort = sort_exec(vec![sort_expr("a", &schema)], memory_exec(&schema));
+        let lower = bounded_window_exec("a", vec![sort_expr("a", &schema)], sort);
+        // the upper window counts the results of the lower one
+        let plan =
+            bounded_window_exec("count", vec![sort_expr("a", &lower.schema())], lower);
+
+        let expected = [
+            "BoundedWindowAggExec: wdw=[count: Ok(Field { name: \"count\", data_type: Int64, nullable: true, dict_id: 0, dict_is_ordered: false, metadata: {} }), frame: WindowFrame { units: Range, start_bound: Preceding(NULL), end_bound: CurrentRow }], mode=[Sorted]",
+            "  BoundedWindowAggExec: wdw=[count: Ok(Field { name: \"count\", data_type: Int64, nullable: true, dict_id: 0, dict_is_ordered: false, metadata: {} }), frame: WindowFrame { units: Range, start_bound: Preceding(NULL), end_bound: CurrentRow }], mode=[Sorted]",
+            "    SortExec: expr=[a@0 ASC]",
+            "      MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn keep_windows_with_unsatisfied_ordering() {
+        let schema = schema();
+        let sort = sort_exec(vec![sort_expr("a", &schema)], memory_exec(&schema));
+        let lower = bounded_window_exec("a", vec![sort_expr("a", &schema)], sort);
+        let plan = bounded_window_exec("a", vec![sort_expr("b", &lower.schema())], lower);
+
+        let optimized = optimize(plan);
+        assert_eq!(optimized.len(), 4);
+        assert!(optimized[1].starts_with("  BoundedWindowAggExec"));
+    }
+}
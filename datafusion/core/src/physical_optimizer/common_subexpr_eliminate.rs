@@ -0,0 +1,394 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CommonSubexprEliminate`] evaluates function calls that are repeated
+//! within a projection, or between a projection and the filter below it,
+//! only once.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_expr::{BuiltinScalarFunction, Volatility};
+use datafusion_physical_expr::expressions::{CaseExpr, Column};
+use datafusion_physical_expr::{PhysicalExpr, ScalarFunctionExpr};
+
+/// Optimizer rule that factors function calls shared by the expressions of a
+/// [`ProjectionExec`] and the predicate of a [`FilterExec`] directly below it
+/// (possibly separated by a [`CoalesceBatchesExec`]) into an intermediate
+/// [`ProjectionExec`], e.g.
+///
+/// ```text
+/// ProjectionExec: expr=[regexp_match(a@0, x) as m]
+///   FilterExec: regexp_match(a@0, x) IS NOT NULL
+///     ...
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// ProjectionExec: expr=[__common_expr_0@1 as m]
+///   FilterExec: __common_expr_0@1 IS NOT NULL
+///     ProjectionExec: expr=[a@0 as a, regexp_match(a@0, x) as __common_expr_0]
+///       ...
+/// ```
+///
+/// Calls that only the projection shares are computed above the filter, so
+/// that they are not evaluated for rows that are filtered out. Calls to
+/// volatile and user defined functions, and calls within `CASE` expressions,
+/// which may only be evaluated for some rows, are left unchanged.
+#[derive(Default)]
+pub struct CommonSubexprEliminate {}
+
+impl CommonSubexprEliminate {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for CommonSubexprEliminate {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            Ok(match eliminate_common_subexprs(projection)? {
+                Some(new_plan) => Transformed::Yes(new_plan),
+                None => Transformed::No(plan),
+            })
+        })
+    }
+
+    fn name(&self) -> &str {
+        "common_subexpr_eliminate"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Rewrites `projection` and the filter below it to evaluate their common
+/// subexpressions once, returning `None` if there are none
+fn eliminate_common_subexprs(
+    projection: &ProjectionExec,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let input = projection.input();
+    let (coalesce, filter) = match input.as_any().downcast_ref::<CoalesceBatchesExec>() {
+        Some(coalesce) => (
+            Some(coalesce),
+            coalesce.input().as_any().downcast_ref::<FilterExec>(),
+        ),
+        None => (None, input.as_any().downcast_ref::<FilterExec>()),
+    };
+
+    let mut roots = projection
+        .expr()
+        .iter()
+        .map(|(expr, _)| expr.clone())
+        .collect::<Vec<_>>();
+    if let Some(filter) = filter {
+        roots.push(filter.predicate().clone());
+    }
+    let common = find_common_subexprs(&roots);
+    if common.is_empty() {
+        return Ok(None);
+    }
+
+    // Split the common subexpressions into those that are needed by the
+    // filter, and those only used by the projection
+    let (below_filter, above_filter): (Vec<_>, Vec<_>) =
+        common.into_iter().partition(|expr| {
+            filter
+                .map(|filter| contains_subexpr(filter.predicate(), expr))
+                .unwrap_or(false)
+        });
+
+    let mut replacements = vec![];
+    let mut plan = input.clone();
+    if let Some(filter) = filter.filter(|_| !below_filter.is_empty()) {
+        let filter_input = project_common_subexprs(
+            filter.input().clone(),
+            below_filter,
+            &mut replacements,
+        )?;
+        let predicate =
+            replace_common_subexprs(filter.predicate().clone(), &replacements)?;
+        plan = Arc::new(
+            FilterExec::try_new(predicate, filter_input)?
+                .with_default_selectivity(filter.default_selectivity())?,
+        );
+        if let Some(coalesce) = coalesce {
+            plan = Arc::new(CoalesceBatchesExec::new(plan, coalesce.target_batch_size()));
+        }
+    }
+    if !above_filter.is_empty() {
+        plan = project_common_subexprs(plan, above_filter, &mut replacements)?;
+    }
+
+    let exprs = projection
+        .expr()
+        .iter()
+        .map(|(expr, name)| {
+            Ok((
+                replace_common_subexprs(expr.clone(), &replacements)?,
+                name.clone(),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(Arc::new(ProjectionExec::try_new(exprs, plan)?)))
+}
+
+/// Returns the outermost subexpressions of `roots` that occur more than once
+fn find_common_subexprs(roots: &[Arc<dyn PhysicalExpr>]) -> Vec<Arc<dyn PhysicalExpr>> {
+    let mut counts: Vec<(Arc<dyn PhysicalExpr>, usize)> = vec![];
+    for root in roots {
+        visit_candidates(root, &mut |expr| {
+            match counts.iter_mut().find(|(e, _)| e.eq(expr)) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((expr.clone(), 1)),
+            }
+            true
+        });
+    }
+
+    let mut common: Vec<Arc<dyn PhysicalExpr>> = vec![];
+    for root in roots {
+        visit_candidates(root, &mut |expr| {
+            let is_common = counts.iter().any(|(e, count)| *count > 1 && e.eq(expr));
+            if is_common && !common.iter().any(|e| e.eq(expr)) {
+                common.push(expr.clone());
+            }
+            // only keep the outermost common subexpression
+            !is_common
+        });
+    }
+    common
+}
+
+/// Calls `f` for the subexpressions of `expr` that may be eliminated, from
+/// the outermost to the innermost, descending only while `f` returns true
+fn visit_candidates(
+    expr: &Arc<dyn PhysicalExpr>,
+    f: &mut impl FnMut(&Arc<dyn PhysicalExpr>) -> bool,
+) {
+    // The branches of a CASE expression are only evaluated for some rows
+    if expr.as_any().is::<CaseExpr>() {
+        return;
+    }
+    if is_candidate(expr) && !f(expr) {
+        return;
+    }
+    for child in expr.children() {
+        visit_candidates(&child, f);
+    }
+}
+
+/// Returns true if `expr` is a call to a built-in function that is not
+/// volatile and has no volatile arguments
+fn is_candidate(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    expr.as_any().is::<ScalarFunctionExpr>() && is_stable(expr)
+}
+
+fn is_stable(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    if let Some(func) = expr.as_any().downcast_ref::<ScalarFunctionExpr>() {
+        let stable = BuiltinScalarFunction::from_str(func.name())
+            .map(|fun| fun.volatility() != Volatility::Volatile)
+            .unwrap_or(false);
+        if !stable {
+            return false;
+        }
+    }
+    expr.children().iter().all(is_stable)
+}
+
+fn contains_subexpr(
+    expr: &Arc<dyn PhysicalExpr>,
+    subexpr: &Arc<dyn PhysicalExpr>,
+) -> bool {
+    expr.eq(subexpr) || expr.children().iter().any(|e| contains_subexpr(e, subexpr))
+}
+
+/// Appends `exprs` as columns named `__common_expr_<n>` to the output of
+/// `input`, recording the column replacing each expression in `replacements`
+fn project_common_subexprs(
+    input: Arc<dyn ExecutionPlan>,
+    exprs: Vec<Arc<dyn PhysicalExpr>>,
+    replacements: &mut Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let schema = input.schema();
+    let mut projection = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let column = Arc::new(Column::new(field.name(), index)) as _;
+            (column, field.name().clone())
+        })
+        .collect::<Vec<_>>();
+    for expr in exprs {
+        let name = format!("__common_expr_{}", replacements.len());
+        let column = Arc::new(Column::new(&name, projection.len()));
+        replacements.push((expr.clone(), column));
+        projection.push((expr, name));
+    }
+    Ok(Arc::new(ProjectionExec::try_new(projection, input)?))
+}
+
+fn replace_common_subexprs(
+    expr: Arc<dyn PhysicalExpr>,
+    replacements: &[(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)],
+) -> Result<Arc<dyn PhysicalExpr>> {
+    expr.transform_down(&|expr| {
+        Ok(match replacements.iter().find(|(e, _)| e.eq(&expr)) {
+            Some((_, column)) => Transformed::Yes(column.clone()),
+            None => Transformed::No(expr),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_optimizer::test_utils::memory_exec;
+    use crate::physical_plan::displayable;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_expr::Operator;
+    use datafusion_physical_expr::execution_props::ExecutionProps;
+    use datafusion_physical_expr::expressions::{binary, col, is_not_null, lit};
+    use datafusion_physical_expr::functions::create_physical_expr;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]))
+    }
+
+    fn func(
+        fun: BuiltinScalarFunction,
+        args: &[Arc<dyn PhysicalExpr>],
+        schema: &SchemaRef,
+    ) -> Arc<dyn PhysicalExpr> {
+        create_physical_expr(&fun, args, schema, &ExecutionProps::new()).unwrap()
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let optimized = CommonSubexprEliminate::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap();
+        displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn shared_with_filter() -> Result<()> {
+        let schema = schema();
+        let abs_a = func(BuiltinScalarFunction::Abs, &[col("a", &schema)?], &schema);
+        let filter = Arc::new(CoalesceBatchesExec::new(
+            Arc::new(FilterExec::try_new(
+                binary(abs_a.clone(), Operator::Gt, lit(1i64), &schema)?,
+                memory_exec(&schema),
+            )?),
+            8192,
+        ));
+        let plan = Arc::new(ProjectionExec::try_new(
+            vec![
+                (abs_a, "x".to_string()),
+                (col("b", &schema)?, "b".to_string()),
+            ],
+            filter,
+        )?);
+
+        let expected = [
+            "ProjectionExec: expr=[__common_expr_0@2 as x, b@1 as b]",
+            "  CoalesceBatchesExec: target_batch_size=8192",
+            "    FilterExec: __common_expr_0@2 > 1",
+            "      ProjectionExec: expr=[a@0 as a, b@1 as b, abs(a@0) as __common_expr_0]",
+            "        MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn shared_within_projection() -> Result<()> {
+        let schema = schema();
+        let abs_a = func(BuiltinScalarFunction::Abs, &[col("a", &schema)?], &schema);
+        let abs_b = func(BuiltinScalarFunction::Abs, &[col("b", &schema)?], &schema);
+        let filter = Arc::new(FilterExec::try_new(
+            is_not_null(abs_b)?,
+            memory_exec(&schema),
+        )?);
+        let plan = Arc::new(ProjectionExec::try_new(
+            vec![
+                (abs_a.clone(), "x".to_string()),
+                (
+                    binary(abs_a, Operator::Plus, lit(1i64), &schema)?,
+                    "y".to_string(),
+                ),
+            ],
+            filter,
+        )?);
+
+        // abs(a) is only needed after filtering, abs(b) is not shared
+        let expected = [
+            "ProjectionExec: expr=[__common_expr_0@2 as x, __common_expr_0@2 + 1 as y]",
+            "  ProjectionExec: expr=[a@0 as a, b@1 as b, abs(a@0) as __common_expr_0]",
+            "    FilterExec: abs(b@1) IS NOT NULL",
+            "      MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn volatile_not_eliminated() -> Result<()> {
+        let schema = schema();
+        let random = func(BuiltinScalarFunction::Random, &[], &schema);
+        let plan = Arc::new(ProjectionExec::try_new(
+            vec![(random.clone(), "x".to_string()), (random, "y".to_string())],
+            memory_exec(&schema),
+        )?);
+
+        let expected = [
+            "ProjectionExec: expr=[random() as x, random() as y]",
+            "  MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+        Ok(())
+    }
+}
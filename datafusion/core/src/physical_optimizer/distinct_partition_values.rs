@@ -0,0 +1,130 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`DistinctPartitionValues`] makes the [`PartitionValuesExec`] below
+//! aggregations without aggregate expressions produce a single row per file
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::datasource::physical_plan::PartitionValuesExec;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::aggregates::AggregateExec;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+
+/// Optimizer rule that answers queries such as `SELECT DISTINCT part FROM t`,
+/// where `part` is a partition column of a listing table, with a single row
+/// per file:
+///
+/// ```text
+/// AggregateExec: mode=FinalPartitioned, gby=[part@0 as part], aggr=[]
+///   ...
+///     AggregateExec: mode=Partial, gby=[part@0 as part], aggr=[]
+///       PartitionValuesExec: file_groups={...}, projection=[part]
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// AggregateExec: mode=FinalPartitioned, gby=[part@0 as part], aggr=[]
+///   ...
+///     AggregateExec: mode=Partial, gby=[part@0 as part], aggr=[]
+///       PartitionValuesExec: distinct=true, file_groups={...}, projection=[part]
+/// ```
+///
+/// The output of an aggregation without aggregate expressions only depends
+/// on the distinct values of its input, so the rows of a file beyond the
+/// first only repeat its partition values.
+#[derive(Default)]
+pub struct DistinctPartitionValues {}
+
+impl DistinctPartitionValues {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for DistinctPartitionValues {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let Some(aggregate) = plan.as_any().downcast_ref::<AggregateExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            if !aggregate.aggr_expr().is_empty() {
+                return Ok(Transformed::No(plan));
+            }
+            match distinct_input(aggregate.input())? {
+                Some(input) => Ok(Transformed::Yes(plan.with_new_children(vec![input])?)),
+                None => Ok(Transformed::No(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "distinct_partition_values"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns `plan` with its [`PartitionValuesExec`] in distinct mode, if it
+/// only goes through operators whose output does not depend on the number
+/// of duplicates of their input rows to reach it
+fn distinct_input(
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let any = plan.as_any();
+    if let Some(scan) = any.downcast_ref::<PartitionValuesExec>() {
+        if scan.distinct() {
+            return Ok(None);
+        }
+        return Ok(Some(Arc::new(scan.clone().with_distinct(true))));
+    }
+    let passes_through = any.is::<RepartitionExec>()
+        || any.is::<CoalesceBatchesExec>()
+        || any.is::<CoalescePartitionsExec>()
+        || any.is::<ProjectionExec>()
+        || any.is::<FilterExec>()
+        || any
+            .downcast_ref::<AggregateExec>()
+            .map_or(false, |aggregate| aggregate.aggr_expr().is_empty());
+    if !passes_through {
+        return Ok(None);
+    }
+    let children = plan.children();
+    let [child] = children.as_slice() else {
+        return Ok(None);
+    };
+    distinct_input(child)?
+        .map(|child| plan.clone().with_new_children(vec![child]))
+        .transpose()
+}
@@ -0,0 +1,342 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`DynamicJoinFilter`] connects the build side of hash joins with the
+//! parquet scans feeding their probe side, so that row groups which cannot
+//! produce any join matches are skipped at runtime.
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::datasource::physical_plan::ParquetExec;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::joins::{DynamicFilter, HashJoinExec, PartitionMode};
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::JoinType;
+use datafusion_physical_expr::expressions::Column;
+
+/// Optimizer rule that attaches a [`DynamicFilter`] to [`HashJoinExec`]s
+/// running in [`PartitionMode::CollectLeft`] mode and to the [`ParquetExec`]
+/// on their probe side.
+///
+/// Once the build side has been collected, the join publishes the min/max
+/// bounds of its join keys, which the scan then uses to prune row groups of
+/// the files it opens afterwards. This is most effective for selective
+/// star-schema joins, where a small filtered dimension table is joined with
+/// a large fact table.
+///
+/// The filter is only pushed through operators that neither change the join
+/// key values nor start reading their input before being polled, i.e.
+/// [`FilterExec`], [`CoalesceBatchesExec`] and column-only
+/// [`ProjectionExec`]s.
+#[derive(Default)]
+pub struct DynamicJoinFilter {}
+
+impl DynamicJoinFilter {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for DynamicJoinFilter {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_dynamic_join_filter {
+            return Ok(plan);
+        }
+
+        plan.transform_up(&|plan| {
+            let Some(join) = plan.as_any().downcast_ref::<HashJoinExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            if !supports_dynamic_filter(join) {
+                return Ok(Transformed::No(plan));
+            }
+
+            let dynamic_filter = Arc::new(DynamicFilter::new());
+            let columns = join.on().iter().map(|(_, right)| right.clone()).collect();
+            match push_dynamic_filter(join.right(), columns, &dynamic_filter)? {
+                Some(right) => {
+                    let join = HashJoinExec::try_new(
+                        join.left().clone(),
+                        right,
+                        join.on().to_vec(),
+                        join.filter().cloned(),
+                        join.join_type(),
+                        *join.partition_mode(),
+                        join.null_equals_null(),
+                    )?
+                    .with_dynamic_filter(dynamic_filter);
+                    Ok(Transformed::Yes(Arc::new(join)))
+                }
+                None => Ok(Transformed::No(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "dynamic_join_filter"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns true if pruning probe side rows whose join keys are outside the
+/// build side bounds can not change the result of `join`
+fn supports_dynamic_filter(join: &HashJoinExec) -> bool {
+    join.dynamic_filter().is_none()
+        && *join.partition_mode() == PartitionMode::CollectLeft
+        && !join.null_equals_null()
+        && matches!(
+            join.join_type(),
+            JoinType::Inner
+                | JoinType::Left
+                | JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::RightSemi
+        )
+}
+
+/// Attach `dynamic_filter` to the [`ParquetExec`] producing `columns` of
+/// `plan`, returning the rewritten plan, or `None` if there is no such scan
+fn push_dynamic_filter(
+    plan: &Arc<dyn ExecutionPlan>,
+    columns: Vec<Column>,
+    dynamic_filter: &Arc<DynamicFilter>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let plan_any = plan.as_any();
+    if let Some(parquet) = plan_any.downcast_ref::<ParquetExec>() {
+        if parquet.dynamic_filter().is_some() {
+            return Ok(None);
+        }
+        // Partition columns are not stored in the files, so can not be used
+        // to prune row groups
+        let file_schema = &parquet.base_config().file_schema;
+        let file_columns = columns
+            .iter()
+            .map(|column| {
+                let index = file_schema.index_of(column.name()).ok()?;
+                Some(Column::new(column.name(), index))
+            })
+            .collect::<Option<Vec<_>>>();
+        return Ok(file_columns.map(|file_columns| {
+            Arc::new(
+                parquet
+                    .clone()
+                    .with_dynamic_filter(dynamic_filter.clone(), file_columns),
+            ) as _
+        }));
+    }
+
+    let input_columns = if plan_any.downcast_ref::<FilterExec>().is_some()
+        || plan_any.downcast_ref::<CoalesceBatchesExec>().is_some()
+    {
+        Some(columns)
+    } else if let Some(projection) = plan_any.downcast_ref::<ProjectionExec>() {
+        columns
+            .iter()
+            .map(|column| {
+                let (expr, _) = projection.expr().get(column.index())?;
+                expr.as_any().downcast_ref::<Column>().cloned()
+            })
+            .collect::<Option<Vec<_>>>()
+    } else {
+        None
+    };
+
+    let Some(input_columns) = input_columns else {
+        return Ok(None);
+    };
+    let input = &plan.children()[0];
+    match push_dynamic_filter(input, input_columns, dynamic_filter)? {
+        Some(input) => Ok(Some(plan.clone().with_new_children(vec![input])?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::listing::PartitionedFile;
+    use crate::datasource::object_store::ObjectStoreUrl;
+    use crate::datasource::physical_plan::FileScanConfig;
+    use crate::physical_plan::displayable;
+    use crate::physical_plan::joins::utils::JoinOn;
+    use crate::physical_plan::repartition::RepartitionExec;
+    use crate::physical_plan::Partitioning;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_common::Statistics;
+    use datafusion_expr::Operator;
+    use datafusion_physical_expr::expressions::{BinaryExpr, Literal};
+    use datafusion_physical_expr::PhysicalExpr;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]))
+    }
+
+    fn parquet_exec(schema: &SchemaRef) -> Arc<ParquetExec> {
+        Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store_url: ObjectStoreUrl::parse("test:///").unwrap(),
+                file_schema: schema.clone(),
+                file_groups: vec![vec![PartitionedFile::new("x".to_string(), 100)]],
+                statistics: Statistics::new_unknown(schema),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: vec![],
+            },
+            None,
+            None,
+        ))
+    }
+
+    fn hash_join(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        join_type: JoinType,
+        mode: PartitionMode,
+    ) -> Arc<dyn ExecutionPlan> {
+        let on: JoinOn = vec![(
+            Column::new_with_schema("a", &left.schema()).unwrap(),
+            Column::new_with_schema("a", &right.schema()).unwrap(),
+        )];
+        Arc::new(
+            HashJoinExec::try_new(left, right, on, None, &join_type, mode, false)
+                .unwrap(),
+        )
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let optimized = DynamicJoinFilter::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap();
+        displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn push_into_probe_side_scan() {
+        let schema = schema();
+        let predicate = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("b", 1)),
+            Operator::Gt,
+            Arc::new(Literal::new(1i64.into())),
+        ));
+        let right: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, parquet_exec(&schema)).unwrap());
+        let plan = hash_join(
+            parquet_exec(&schema),
+            right,
+            JoinType::Inner,
+            PartitionMode::CollectLeft,
+        );
+
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, a@0)]",
+            "  ParquetExec: file_groups={1 group: [[x]]}, projection=[a, b]",
+            "  FilterExec: b@1 > 1",
+            "    ParquetExec: file_groups={1 group: [[x]]}, projection=[a, b], dynamic_filter=[a@0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn push_through_projection() {
+        let schema = schema();
+        let projection: Vec<(Arc<dyn PhysicalExpr>, String)> = vec![
+            (Arc::new(Column::new("b", 1)), "b".to_string()),
+            (Arc::new(Column::new("a", 0)), "a".to_string()),
+        ];
+        let right: Arc<dyn ExecutionPlan> =
+            Arc::new(ProjectionExec::try_new(projection, parquet_exec(&schema)).unwrap());
+        let plan = hash_join(
+            parquet_exec(&schema),
+            right,
+            JoinType::LeftSemi,
+            PartitionMode::CollectLeft,
+        );
+
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=LeftSemi, on=[(a@0, a@1)]",
+            "  ParquetExec: file_groups={1 group: [[x]]}, projection=[a, b]",
+            "  ProjectionExec: expr=[b@1 as b, a@0 as a]",
+            "    ParquetExec: file_groups={1 group: [[x]]}, projection=[a, b], dynamic_filter=[a@0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn not_pushed_for_unsupported_joins() {
+        let schema = schema();
+
+        // probe side rows are preserved by right joins
+        let plan = hash_join(
+            parquet_exec(&schema),
+            parquet_exec(&schema),
+            JoinType::Right,
+            PartitionMode::CollectLeft,
+        );
+        assert!(!optimize(plan).join("\n").contains("dynamic_filter"));
+
+        // each partition has its own build side
+        let plan = hash_join(
+            parquet_exec(&schema),
+            parquet_exec(&schema),
+            JoinType::Inner,
+            PartitionMode::Partitioned,
+        );
+        assert!(!optimize(plan).join("\n").contains("dynamic_filter"));
+
+        // RepartitionExec starts reading its input as soon as it is executed
+        let right = Arc::new(
+            RepartitionExec::try_new(
+                parquet_exec(&schema),
+                Partitioning::RoundRobinBatch(4),
+            )
+            .unwrap(),
+        );
+        let plan = hash_join(
+            parquet_exec(&schema),
+            right,
+            JoinType::Inner,
+            PartitionMode::CollectLeft,
+        );
+        assert!(!optimize(plan).join("\n").contains("dynamic_filter"));
+    }
+}
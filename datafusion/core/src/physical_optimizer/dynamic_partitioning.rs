@@ -0,0 +1,343 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`DynamicPartitioning`] chooses the number of partitions of repartitions
+//! from the statistics of their inputs
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::union::InterleaveExec;
+use crate::physical_plan::{Distribution, ExecutionPlan, Partitioning};
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use log::debug;
+
+/// Optimizer rule that lowers the number of partitions of the
+/// [`RepartitionExec`]s whose input is estimated to be too small to be worth
+/// spreading over `target_partitions` partitions, when
+/// `datafusion.optimizer.enable_dynamic_partitioning` is set.
+///
+/// Each partition receives at least `dynamic_partitioning_min_bytes` bytes of
+/// the estimated size of the input, or `dynamic_partitioning_min_rows` rows
+/// when that size is unknown. For example, with the default minimum of
+/// 131072 rows, a repartition of 1000 rows
+///
+/// ```text
+/// RepartitionExec: partitioning=Hash([a@0], 128), input_partitions=1
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// RepartitionExec: partitioning=Hash([a@0], 1), input_partitions=1
+/// ```
+///
+/// The inputs of the joins that require their inputs to be partitioned
+/// alike, and of [`InterleaveExec`]s, keep the same number of partitions: the
+/// inputs with fewer partitions are repartitioned to as many partitions as the
+/// others.
+#[derive(Default)]
+pub struct DynamicPartitioning {}
+
+impl DynamicPartitioning {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for DynamicPartitioning {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let options = &config.optimizer;
+        if !options.enable_dynamic_partitioning {
+            return Ok(plan);
+        }
+        plan.transform_up(&|plan| {
+            if let Some(repartition) = plan.as_any().downcast_ref::<RepartitionExec>() {
+                let current = repartition.partitioning().partition_count();
+                let count = partition_count(
+                    repartition.input(),
+                    current,
+                    options.dynamic_partitioning_min_bytes,
+                    options.dynamic_partitioning_min_rows,
+                );
+                if count < current {
+                    debug!(
+                        "Lowering the partitions of {} from {current} to {count}",
+                        repartition.name()
+                    );
+                    return Ok(Transformed::Yes(with_partition_count(
+                        repartition,
+                        count,
+                    )?));
+                }
+                Ok(Transformed::No(plan))
+            } else if requires_copartitioned_children(&plan) {
+                align_children(plan)
+            } else {
+                Ok(Transformed::No(plan))
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "dynamic_partitioning"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns the number of partitions, at most `current`, such that each of
+/// them receives at least `min_bytes` bytes, or `min_rows` rows, of the
+/// estimated output of `input`
+fn partition_count(
+    input: &Arc<dyn ExecutionPlan>,
+    current: usize,
+    min_bytes: usize,
+    min_rows: usize,
+) -> usize {
+    let Ok(stats) = input.statistics() else {
+        return current;
+    };
+    // Like the join selection, do not trust the 0 value from stats
+    let count = match (
+        stats.total_byte_size.get_value(),
+        stats.num_rows.get_value(),
+    ) {
+        (Some(bytes), _) if *bytes != 0 && min_bytes != 0 => {
+            (bytes + min_bytes - 1) / min_bytes
+        }
+        (_, Some(rows)) if *rows != 0 && min_rows != 0 => {
+            (rows + min_rows - 1) / min_rows
+        }
+        _ => current,
+    };
+    count.clamp(1, current.max(1))
+}
+
+/// Returns `repartition` with its partitioning changed to `count` partitions
+fn with_partition_count(
+    repartition: &RepartitionExec,
+    count: usize,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let partitioning = match repartition.partitioning() {
+        Partitioning::Hash(exprs, _) => Partitioning::Hash(exprs.clone(), count),
+        Partitioning::RoundRobinBatch(_) => Partitioning::RoundRobinBatch(count),
+        partitioning => partitioning.clone(),
+    };
+    let new = RepartitionExec::try_new(repartition.input().clone(), partitioning)?
+        .with_work_stealing(repartition.work_stealing());
+    Ok(Arc::new(if repartition.preserve_order() {
+        new.with_preserve_order()
+    } else {
+        new
+    }))
+}
+
+/// Whether all the children of `plan` need the same number of partitions
+fn requires_copartitioned_children(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    if plan.as_any().is::<InterleaveExec>() {
+        return true;
+    }
+    let distribution = plan.required_input_distribution();
+    distribution.len() > 1
+        && distribution
+            .iter()
+            .all(|d| matches!(d, Distribution::HashPartitioned(_)))
+}
+
+/// Repartitions the children of `plan` with fewer partitions than the others
+/// to as many partitions as the others
+fn align_children(
+    plan: Arc<dyn ExecutionPlan>,
+) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
+    let children = plan.children();
+    let counts = children
+        .iter()
+        .map(|child| child.output_partitioning().partition_count())
+        .collect::<Vec<_>>();
+    let Some(max) = counts.iter().max().copied() else {
+        return Ok(Transformed::No(plan));
+    };
+    if counts.iter().all(|count| *count == max) {
+        return Ok(Transformed::No(plan));
+    }
+
+    let mut new_children = Vec::with_capacity(children.len());
+    for (child, count) in children.into_iter().zip(counts) {
+        if count == max {
+            new_children.push(child);
+            continue;
+        }
+        match set_partition_count(&child, max)? {
+            Some(child) => new_children.push(child),
+            // Only the children whose repartition was lowered have fewer
+            // partitions, so this is not expected
+            None => return Ok(Transformed::No(plan)),
+        }
+    }
+    Ok(Transformed::Yes(plan.with_new_children(new_children)?))
+}
+
+/// Returns `plan` with the [`RepartitionExec`] it outputs the partitions of
+/// changed to `count` partitions, or `None` if there is no such repartition
+fn set_partition_count(
+    plan: &Arc<dyn ExecutionPlan>,
+    count: usize,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if let Some(repartition) = plan.as_any().downcast_ref::<RepartitionExec>() {
+        return with_partition_count(repartition, count).map(Some);
+    }
+    // Look through the operators that keep the partitions of their input
+    let children = plan.children();
+    let [child] = children.as_slice() else {
+        return Ok(None);
+    };
+    let partitions = plan.output_partitioning().partition_count();
+    if partitions != child.output_partitioning().partition_count() {
+        return Ok(None);
+    }
+    let Some(child) = set_partition_count(child, count)? else {
+        return Ok(None);
+    };
+    let new_plan = plan.clone().with_new_children(vec![child])?;
+    Ok((new_plan.output_partitioning().partition_count() == count).then_some(new_plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_optimizer::test_utils::hash_join_exec;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::{displayable, expressions::Column};
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use datafusion_common::JoinType;
+
+    fn memory_batch(name: &str, rows: i64) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new(name, DataType::Int64, true)]));
+        RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int64Array::from_iter_values(0..rows))],
+        )
+        .unwrap()
+    }
+
+    /// A single partition of `batches` batches of `rows` rows
+    fn memory_exec(name: &str, batches: usize, rows: i64) -> Arc<dyn ExecutionPlan> {
+        let batches = (0..batches).map(|_| memory_batch(name, rows)).collect();
+        let schema = memory_batch(name, 0).schema();
+        Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+    }
+
+    fn hash_repartition(
+        input: Arc<dyn ExecutionPlan>,
+        name: &str,
+        count: usize,
+    ) -> Arc<dyn ExecutionPlan> {
+        let column = Arc::new(Column::new_with_schema(name, &input.schema()).unwrap());
+        Arc::new(
+            RepartitionExec::try_new(input, Partitioning::Hash(vec![column], count))
+                .unwrap(),
+        )
+    }
+
+    fn optimize_plan(
+        plan: Arc<dyn ExecutionPlan>,
+        min_bytes: usize,
+    ) -> Arc<dyn ExecutionPlan> {
+        let mut config = ConfigOptions::new();
+        config.optimizer.enable_dynamic_partitioning = true;
+        config.optimizer.dynamic_partitioning_min_bytes = min_bytes;
+        DynamicPartitioning::new().optimize(plan, &config).unwrap()
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>, min_bytes: usize) -> Vec<String> {
+        let optimized = optimize_plan(plan, min_bytes);
+        displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn small_input() {
+        let plan = hash_repartition(memory_exec("a", 1, 1000), "a", 128);
+
+        let expected = [
+            "RepartitionExec: partitioning=Hash([a@0], 1), input_partitions=1",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_eq!(optimize(plan, 1024 * 1024), expected);
+    }
+
+    #[test]
+    fn partitions_by_size() {
+        // 1000 Int64 values take a little more than 8000 bytes
+        let plan = hash_repartition(memory_exec("a", 1, 1000), "a", 128);
+        let count = optimize_plan(plan, 1000)
+            .output_partitioning()
+            .partition_count();
+        assert!((9..=10).contains(&count), "{count}");
+    }
+
+    #[test]
+    fn disabled() {
+        let plan = hash_repartition(memory_exec("a", 1, 1000), "a", 128);
+        let optimized = DynamicPartitioning::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap();
+        assert_eq!(optimized.output_partitioning().partition_count(), 128);
+    }
+
+    #[test]
+    fn aligned_join_inputs() -> Result<()> {
+        let left = hash_repartition(memory_exec("a", 1, 1000), "a", 8);
+        // the 12.8 MB of the right input are too large to be lowered
+        let right = hash_repartition(memory_exec("b", 16, 100_000), "b", 8);
+        let on = vec![(
+            Column::new_with_schema("a", &left.schema())?,
+            Column::new_with_schema("b", &right.schema())?,
+        )];
+        let join = hash_join_exec(left, right, on, None, &JoinType::Inner)?;
+
+        let expected = [
+            "HashJoinExec: mode=Partitioned, join_type=Inner, on=[(a@0, b@0)]",
+            "  RepartitionExec: partitioning=Hash([a@0], 8), input_partitions=1",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+            "  RepartitionExec: partitioning=Hash([b@0], 8), input_partitions=1",
+            "    MemoryExec: partitions=1, partition_sizes=[16]",
+        ];
+        assert_eq!(optimize(join, 1024 * 1024), expected);
+        Ok(())
+    }
+}
@@ -871,6 +871,8 @@ fn new_join_conditions(
 /// * `input`: Current node.
 /// * `n_target`: desired target partition number, if partition number of the
 ///    current executor is less than this value. Partition number will be increased.
+/// * `work_stealing`: whether the output partitions pull batches from a shared
+///    queue, see [`RepartitionExec::with_work_stealing`].
 ///
 /// # Returns
 ///
@@ -879,6 +881,7 @@ fn new_join_conditions(
 fn add_roundrobin_on_top(
     input: DistributionContext,
     n_target: usize,
+    work_stealing: bool,
 ) -> Result<DistributionContext> {
     // Adding repartition is helpful:
     if input.plan.output_partitioning().partition_count() < n_target {
@@ -890,7 +893,8 @@ fn add_roundrobin_on_top(
         // (determined by flag `config.optimizer.prefer_existing_sort`)
         let partitioning = Partitioning::RoundRobinBatch(n_target);
         let repartition = RepartitionExec::try_new(input.plan.clone(), partitioning)?
-            .with_preserve_order();
+            .with_preserve_order()
+            .with_work_stealing(work_stealing);
 
         let new_plan = Arc::new(repartition) as _;
 
@@ -916,6 +920,8 @@ fn add_roundrobin_on_top(
 /// * `hash_exprs`: Stores Physical Exprs that are used during hashing.
 /// * `n_target`: desired target partition number, if partition number of the
 ///    current executor is less than this value. Partition number will be increased.
+/// * `work_stealing`: whether the round robin repartition added below the hash
+///    repartition uses work stealing, see [`RepartitionExec::with_work_stealing`].
 ///
 /// # Returns
 ///
@@ -926,6 +932,7 @@ fn add_hash_on_top(
     hash_exprs: Vec<Arc<dyn PhysicalExpr>>,
     n_target: usize,
     repartition_beneficial_stats: bool,
+    work_stealing: bool,
 ) -> Result<DistributionContext> {
     // Early return if hash repartition is unnecessary
     if n_target == 1 {
@@ -953,7 +960,7 @@ fn add_hash_on_top(
         if repartition_beneficial_stats {
             // Since hashing benefits from partitioning, add a round-robin repartition
             // before it:
-            input = add_roundrobin_on_top(input, n_target)?;
+            input = add_roundrobin_on_top(input, n_target, work_stealing)?;
         }
 
         let partitioning = Partitioning::Hash(hash_exprs, n_target);
@@ -1086,10 +1093,13 @@ fn replace_order_preserving_variants(
     {
         if repartition.preserve_order() {
             let child = updated_children.swap_remove(0);
-            context.plan = Arc::new(RepartitionExec::try_new(
-                child.plan.clone(),
-                repartition.partitioning().clone(),
-            )?);
+            context.plan = Arc::new(
+                RepartitionExec::try_new(
+                    child.plan.clone(),
+                    repartition.partitioning().clone(),
+                )?
+                .with_work_stealing(repartition.work_stealing()),
+            );
             context.children_nodes = vec![child];
             return Ok(context);
         }
@@ -1150,6 +1160,7 @@ fn ensure_distribution(
     // When `false`, round robin repartition will not be added to increase parallelism
     let enable_round_robin = config.optimizer.enable_round_robin_repartition;
     let repartition_file_scans = config.optimizer.repartition_file_scans;
+    let work_stealing = config.optimizer.repartition_work_stealing;
     let batch_size = config.execution.batch_size;
     let is_unbounded = unbounded_output(&dist_context.plan);
     // Use order preserving variants either of the conditions true
@@ -1228,7 +1239,7 @@ fn ensure_distribution(
                 // on top of the operator. Note that we only do this if the
                 // partition count is not already equal to the desired partition
                 // count.
-                child = add_roundrobin_on_top(child, target_partitions)?;
+                child = add_roundrobin_on_top(child, target_partitions, work_stealing)?;
             }
 
             // Satisfy the distribution requirement if it is unmet.
@@ -1242,6 +1253,7 @@ fn ensure_distribution(
                         exprs.to_vec(),
                         target_partitions,
                         repartition_beneficial_stats,
+                        work_stealing,
                     )?;
                 }
                 Distribution::UnspecifiedDistribution => {}
@@ -2798,7 +2810,7 @@ pub(crate) mod tests {
         ];
         let right = projection_exec_with_alias(parquet_exec(), alias_pairs);
 
-        // SortMergeJoin does not support RightSemi and RightAnti join now
+        // RightSemi and RightAnti joins do not output column `a` the top join is on
         let join_types = vec![
             JoinType::Inner,
             JoinType::Left,
@@ -49,13 +49,15 @@ use crate::physical_optimizer::utils::{
 };
 use crate::physical_optimizer::PhysicalOptimizerRule;
 use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::sorts::partial_sort::PartialSortExec;
 use crate::physical_plan::sorts::sort::SortExec;
 use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
 use crate::physical_plan::windows::{
     get_best_fitting_window, BoundedWindowAggExec, WindowAggExec,
 };
 use crate::physical_plan::{
-    with_new_children_if_necessary, Distribution, ExecutionPlan, InputOrderMode,
+    unbounded_output, with_new_children_if_necessary, Distribution, ExecutionPlan,
+    InputOrderMode,
 };
 
 use datafusion_common::tree_node::{Transformed, TreeNode};
@@ -251,6 +253,35 @@ impl TreeNode for PlanWithCorrespondingCoalescePartitions {
     }
 }
 
+/// Replaces a [`SortExec`] of an unbounded input already sorted on a prefix
+/// of its sort expressions with a [`PartialSortExec`], sorting the runs of
+/// rows with equal prefix values one at a time.
+fn replace_with_partial_sort(
+    plan: Arc<dyn ExecutionPlan>,
+) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
+    let Some(sort_plan) = plan.as_any().downcast_ref::<SortExec>() else {
+        return Ok(Transformed::No(plan));
+    };
+    let child = sort_plan.input();
+    if !unbounded_output(child) {
+        return Ok(Transformed::No(plan));
+    }
+    let sort_exprs = sort_plan.expr();
+    let eq_properties = child.equivalence_properties();
+    let common_prefix_length = (1..sort_exprs.len())
+        .take_while(|&len| eq_properties.ordering_satisfy(&sort_exprs[..len]))
+        .last()
+        .unwrap_or(0);
+    if common_prefix_length == 0 {
+        return Ok(Transformed::No(plan));
+    }
+    let partial_sort =
+        PartialSortExec::new(sort_exprs.to_vec(), child.clone(), common_prefix_length)
+            .with_fetch(sort_plan.fetch())
+            .with_preserve_partitioning(sort_plan.preserve_partitioning());
+    Ok(Transformed::Yes(Arc::new(partial_sort)))
+}
+
 /// The boolean flag `repartition_sorts` defined in the config indicates
 /// whether we elect to transform [`CoalescePartitionsExec`] + [`SortExec`] cascades
 /// into [`SortExec`] + [`SortPreservingMergeExec`] cascades, which enables us to
@@ -291,7 +322,10 @@ impl PhysicalOptimizerRule for EnforceSorting {
         let mut sort_pushdown = SortPushDown::new(updated_plan.plan);
         sort_pushdown.assign_initial_requirements();
         let adjusted = sort_pushdown.transform_down(&pushdown_sorts)?;
-        Ok(adjusted.plan)
+
+        // Sorts of unbounded inputs already sorted on a prefix of their sort
+        // expressions can only run as partial sorts:
+        adjusted.plan.transform_up(&replace_with_partial_sort)
     }
 
     fn name(&self) -> &str {
@@ -2224,6 +2258,27 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_partial_sort_of_unbounded_input() -> Result<()> {
+        let schema = create_test_schema3()?;
+        let source = stream_exec_ordered(&schema, vec![sort_expr("a", &schema)]);
+        let physical_plan = sort_exec(
+            vec![sort_expr("a", &schema), sort_expr("b", &schema)],
+            source,
+        );
+
+        let expected_input = [
+            "SortExec: expr=[a@0 ASC,b@1 ASC]",
+            "  StreamingTableExec: partition_sizes=1, projection=[a, b, c, d, e], infinite_source=true, output_ordering=[a@0 ASC]",
+        ];
+        let expected_optimized = [
+            "PartialSortExec: expr=[a@0 ASC,b@1 ASC], common_prefix_length=[1]",
+            "  StreamingTableExec: partition_sizes=1, projection=[a, b, c, d, e], infinite_source=true, output_ordering=[a@0 ASC]",
+        ];
+        assert_optimized!(expected_input, expected_optimized, physical_plan, true);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_do_not_pushdown_through_spm() -> Result<()> {
         let schema = create_test_schema3()?;
@@ -25,7 +25,7 @@
 
 use std::sync::Arc;
 
-use crate::config::ConfigOptions;
+use crate::config::{ConfigOptions, OptimizerOptions};
 use crate::error::Result;
 use crate::physical_optimizer::pipeline_checker::PipelineStatePropagator;
 use crate::physical_optimizer::PhysicalOptimizerRule;
@@ -40,7 +40,7 @@ use crate::physical_plan::ExecutionPlan;
 use arrow_schema::Schema;
 use datafusion_common::internal_err;
 use datafusion_common::tree_node::{Transformed, TreeNode};
-use datafusion_common::{DataFusionError, JoinType};
+use datafusion_common::{DataFusionError, JoinSide, JoinType};
 use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::PhysicalExpr;
 
@@ -88,7 +88,8 @@ fn should_swap_join_order(
 
 fn supports_collect_by_size(
     plan: &dyn ExecutionPlan,
-    collection_size_threshold: usize,
+    threshold_byte_size: usize,
+    threshold_num_rows: usize,
 ) -> bool {
     // Currently we do not trust the 0 value from stats, due to stats collection might have bug
     // TODO check the logic in datasource::get_statistics_with_limit()
@@ -97,9 +98,9 @@ fn supports_collect_by_size(
     };
 
     if let Some(size) = stats.total_byte_size.get_value() {
-        *size != 0 && *size < collection_size_threshold
+        *size != 0 && *size < threshold_byte_size
     } else if let Some(row_count) = stats.num_rows.get_value() {
-        *row_count != 0 && *row_count < collection_size_threshold
+        *row_count != 0 && *row_count < threshold_num_rows
     } else {
         false
     }
@@ -250,10 +251,9 @@ impl PhysicalOptimizerRule for JoinSelection {
         // - We will also swap left and right sides for cross joins so that the left
         //   side is the small side.
         let config = &config.optimizer;
-        let collect_left_threshold = config.hash_join_single_partition_threshold;
-        state.plan.transform_up(&|plan| {
-            statistical_join_selection_subrule(plan, collect_left_threshold)
-        })
+        state
+            .plan
+            .transform_up(&|plan| statistical_join_selection_subrule(plan, config))
     }
 
     fn name(&self) -> &str {
@@ -270,7 +270,8 @@ impl PhysicalOptimizerRule for JoinSelection {
 /// This function will first consider the given join type and check whether the
 /// `CollectLeft` mode is applicable. Otherwise, it will try to swap the join sides.
 /// When the `collect_threshold` is provided, this function will also check left
-/// and right sizes.
+/// and right sizes against its maximum size in bytes and, for inputs whose size
+/// in bytes is unknown, its maximum number of rows.
 ///
 /// For [`JoinType::Full`], it can not use `CollectLeft` mode and will return `None`.
 /// For [`JoinType::Left`] and [`JoinType::LeftAnti`], it can not run `CollectLeft`
@@ -278,7 +279,7 @@ impl PhysicalOptimizerRule for JoinSelection {
 /// and [`JoinType::RightAnti`], respectively.
 fn try_collect_left(
     hash_join: &HashJoinExec,
-    collect_threshold: Option<usize>,
+    collect_threshold: Option<(usize, usize)>,
 ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
     let left = hash_join.left();
     let right = hash_join.right();
@@ -290,9 +291,11 @@ fn try_collect_left(
         | JoinType::LeftSemi
         | JoinType::Right
         | JoinType::RightSemi
-        | JoinType::RightAnti => collect_threshold.map_or(true, |threshold| {
-            supports_collect_by_size(&**left, threshold)
-        }),
+        | JoinType::RightAnti => {
+            collect_threshold.map_or(true, |(byte_size, num_rows)| {
+                supports_collect_by_size(&**left, byte_size, num_rows)
+            })
+        }
     };
     let right_can_collect = match join_type {
         JoinType::Right | JoinType::Full | JoinType::RightAnti => false,
@@ -300,9 +303,11 @@ fn try_collect_left(
         | JoinType::RightSemi
         | JoinType::Left
         | JoinType::LeftSemi
-        | JoinType::LeftAnti => collect_threshold.map_or(true, |threshold| {
-            supports_collect_by_size(&**right, threshold)
-        }),
+        | JoinType::LeftAnti => {
+            collect_threshold.map_or(true, |(byte_size, num_rows)| {
+                supports_collect_by_size(&**right, byte_size, num_rows)
+            })
+        }
     };
     match (left_can_collect, right_can_collect) {
         (true, true) => {
@@ -342,6 +347,45 @@ fn try_collect_left(
     }
 }
 
+/// Collects the `side` input of `hash_join`, chosen by a `BROADCAST` hint, into
+/// a single partition regardless of the statistics of the inputs, swapping
+/// the inputs if needed. Returns `None` if the join type does not allow
+/// collecting that input.
+fn try_collect_hinted_side(
+    hash_join: &HashJoinExec,
+    side: JoinSide,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let join_type = *hash_join.join_type();
+    match side {
+        JoinSide::Left
+            if !matches!(
+                join_type,
+                JoinType::Left | JoinType::Full | JoinType::LeftAnti
+            ) =>
+        {
+            Ok(Some(Arc::new(HashJoinExec::try_new(
+                Arc::clone(hash_join.left()),
+                Arc::clone(hash_join.right()),
+                hash_join.on().to_vec(),
+                hash_join.filter().cloned(),
+                hash_join.join_type(),
+                PartitionMode::CollectLeft,
+                hash_join.null_equals_null(),
+            )?)))
+        }
+        JoinSide::Right
+            if supports_swap(join_type)
+                && !matches!(
+                    join_type,
+                    JoinType::Right | JoinType::Full | JoinType::RightAnti
+                ) =>
+        {
+            swap_hash_join(hash_join, PartitionMode::CollectLeft).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
 fn partitioned_hash_join(hash_join: &HashJoinExec) -> Result<Arc<dyn ExecutionPlan>> {
     let left = hash_join.left();
     let right = hash_join.right();
@@ -361,36 +405,82 @@ fn partitioned_hash_join(hash_join: &HashJoinExec) -> Result<Arc<dyn ExecutionPl
     }
 }
 
+/// Enables the fallback to [`PartitionMode::Partitioned`] of the hash join
+/// collecting its left input at the root of `plan`, which is either that join
+/// or a projection swapping the columns of its inputs back
+fn enable_partitioned_fallback(
+    plan: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if let Some(hash_join) = plan.as_any().downcast_ref::<HashJoinExec>() {
+        Ok(Arc::new(
+            HashJoinExec::try_new(
+                Arc::clone(hash_join.left()),
+                Arc::clone(hash_join.right()),
+                hash_join.on().to_vec(),
+                hash_join.filter().cloned(),
+                hash_join.join_type(),
+                *hash_join.partition_mode(),
+                hash_join.null_equals_null(),
+            )?
+            .with_partitioned_fallback(true),
+        ))
+    } else if plan.as_any().is::<ProjectionExec>() {
+        let input = enable_partitioned_fallback(Arc::clone(&plan.children()[0]))?;
+        plan.with_new_children(vec![input])
+    } else {
+        Ok(plan)
+    }
+}
+
 /// This subrule tries to modify a given plan so that it can
 /// optimize hash and cross joins in the plan according to available statistical information.
 fn statistical_join_selection_subrule(
     plan: Arc<dyn ExecutionPlan>,
-    collect_left_threshold: usize,
+    config: &OptimizerOptions,
 ) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
     let transformed = if let Some(hash_join) =
         plan.as_any().downcast_ref::<HashJoinExec>()
     {
-        match hash_join.partition_mode() {
-            PartitionMode::Auto => {
-                try_collect_left(hash_join, Some(collect_left_threshold))?.map_or_else(
-                    || partitioned_hash_join(hash_join).map(Some),
-                    |v| Ok(Some(v)),
-                )?
-            }
-            PartitionMode::CollectLeft => try_collect_left(hash_join, None)?
-                .map_or_else(
-                    || partitioned_hash_join(hash_join).map(Some),
-                    |v| Ok(Some(v)),
-                )?,
-            PartitionMode::Partitioned => {
-                let left = hash_join.left();
-                let right = hash_join.right();
-                if should_swap_join_order(&**left, &**right)?
-                    && supports_swap(*hash_join.join_type())
-                {
-                    swap_hash_join(hash_join, PartitionMode::Partitioned).map(Some)?
-                } else {
-                    None
+        let hinted = match hash_join.broadcast_hint() {
+            Some(side) => try_collect_hinted_side(hash_join, side)?,
+            None => None,
+        };
+        if hinted.is_some() {
+            // a `BROADCAST` hint takes precedence over statistics, if the join
+            // type allows collecting the hinted input
+            hinted
+        } else {
+            match hash_join.partition_mode() {
+                PartitionMode::Auto => {
+                    let collect_threshold = (
+                        config.hash_join_single_partition_threshold,
+                        config.hash_join_single_partition_threshold_rows,
+                    );
+                    match try_collect_left(hash_join, Some(collect_threshold))? {
+                        // the statistics used to collect the left input may
+                        // underestimate its size
+                        Some(plan) if config.enable_hash_join_partitioned_fallback => {
+                            Some(enable_partitioned_fallback(plan)?)
+                        }
+                        Some(plan) => Some(plan),
+                        None => Some(partitioned_hash_join(hash_join)?),
+                    }
+                }
+                PartitionMode::CollectLeft => try_collect_left(hash_join, None)?
+                    .map_or_else(
+                        || partitioned_hash_join(hash_join).map(Some),
+                        |v| Ok(Some(v)),
+                    )?,
+                PartitionMode::Partitioned => {
+                    let left = hash_join.left();
+                    let right = hash_join.right();
+                    if should_swap_join_order(&**left, &**right)?
+                        && supports_swap(*hash_join.join_type())
+                    {
+                        swap_hash_join(hash_join, PartitionMode::Partitioned).map(Some)?
+                    } else {
+                        None
+                    }
                 }
             }
         }
@@ -753,6 +843,69 @@ mod tests_statistical {
         );
     }
 
+    #[tokio::test]
+    async fn test_join_with_broadcast_hint() {
+        let (big, small) = create_big_and_small();
+        let on = vec![(
+            Column::new_with_schema("small_col", &small.schema()).unwrap(),
+            Column::new_with_schema("big_col", &big.schema()).unwrap(),
+        )];
+
+        // the hint collects the big input, even though statistics would not
+        let join = HashJoinExec::try_new(
+            Arc::clone(&small),
+            Arc::clone(&big),
+            on.clone(),
+            None,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            false,
+        )
+        .unwrap()
+        .with_broadcast_hint(JoinSide::Right);
+
+        let optimized_join = JoinSelection::new()
+            .optimize(Arc::new(join), &ConfigOptions::new())
+            .unwrap();
+
+        let swapped_join = optimized_join
+            .as_any()
+            .downcast_ref::<ProjectionExec>()
+            .expect("A proj is required to swap columns back to their original order")
+            .input()
+            .as_any()
+            .downcast_ref::<HashJoinExec>()
+            .expect("The type of the plan should not be changed");
+        assert_eq!(*swapped_join.partition_mode(), PartitionMode::CollectLeft);
+        assert_eq!(
+            swapped_join.left().statistics().unwrap().total_byte_size,
+            Precision::Inexact(100000)
+        );
+
+        // the preserved input of a full join can not be collected
+        let join = HashJoinExec::try_new(
+            Arc::clone(&small),
+            Arc::clone(&big),
+            on,
+            None,
+            &JoinType::Full,
+            PartitionMode::CollectLeft,
+            false,
+        )
+        .unwrap()
+        .with_broadcast_hint(JoinSide::Left);
+
+        let optimized_join = JoinSelection::new()
+            .optimize(Arc::new(join), &ConfigOptions::new())
+            .unwrap();
+
+        let join = optimized_join
+            .as_any()
+            .downcast_ref::<HashJoinExec>()
+            .expect("The type of the plan should not be changed");
+        assert_eq!(*join.partition_mode(), PartitionMode::Partitioned);
+    }
+
     #[tokio::test]
     async fn test_left_join_with_swap() {
         let (big, small) = create_big_and_small();
@@ -1155,6 +1308,71 @@ mod tests_statistical {
         );
     }
 
+    #[tokio::test]
+    async fn test_join_selection_row_count_threshold() {
+        // without a size in bytes, the number of rows is compared to
+        // `hash_join_single_partition_threshold_rows`
+        let big1 = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Precision::Inexact(1_000_000),
+                total_byte_size: Precision::Absent,
+                column_statistics: vec![ColumnStatistics::new_unknown()],
+            },
+            Schema::new(vec![Field::new("big_col1", DataType::Int32, false)]),
+        ));
+
+        let big2 = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Precision::Inexact(2_000_000),
+                total_byte_size: Precision::Absent,
+                column_statistics: vec![ColumnStatistics::new_unknown()],
+            },
+            Schema::new(vec![Field::new("big_col2", DataType::Int32, false)]),
+        ));
+
+        let join_on = vec![(
+            Column::new_with_schema("big_col1", &big1.schema()).unwrap(),
+            Column::new_with_schema("big_col2", &big2.schema()).unwrap(),
+        )];
+        check_join_partition_mode(big1, big2, join_on, false, PartitionMode::Partitioned);
+    }
+
+    #[tokio::test]
+    async fn test_join_selection_partitioned_fallback() {
+        let (big, small) = create_big_and_small();
+        let join = HashJoinExec::try_new(
+            Arc::clone(&big),
+            Arc::clone(&small),
+            vec![(
+                Column::new_with_schema("big_col", &big.schema()).unwrap(),
+                Column::new_with_schema("small_col", &small.schema()).unwrap(),
+            )],
+            None,
+            &JoinType::Inner,
+            PartitionMode::Auto,
+            false,
+        )
+        .unwrap();
+
+        let mut config = ConfigOptions::new();
+        config.optimizer.enable_hash_join_partitioned_fallback = true;
+        let optimized_join = JoinSelection::new()
+            .optimize(Arc::new(join), &config)
+            .unwrap();
+
+        let swapping_projection = optimized_join
+            .as_any()
+            .downcast_ref::<ProjectionExec>()
+            .expect("A proj is required to swap columns back to their original order");
+        let swapped_join = swapping_projection
+            .input()
+            .as_any()
+            .downcast_ref::<HashJoinExec>()
+            .expect("The type of the plan should not be changed");
+        assert_eq!(*swapped_join.partition_mode(), PartitionMode::CollectLeft);
+        assert!(swapped_join.partitioned_fallback());
+    }
+
     fn check_join_partition_mode(
         left: Arc<StatisticsExec>,
         right: Arc<StatisticsExec>,
@@ -0,0 +1,275 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`LimitPushdown`] pushes the number of rows needed by limits into the
+//! file scans below them
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::datasource::physical_plan::{CsvExec, FileScanConfig, NdJsonExec};
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+
+/// Optimizer rule that pushes the number of rows needed by a
+/// [`GlobalLimitExec`] or [`LocalLimitExec`] into the [`CsvExec`] or
+/// [`NdJsonExec`] below it, through operators that produce one row for
+/// each of their input rows, so that the scan stops reading its files once
+/// it has produced enough rows, e.g.
+///
+/// ```text
+/// GlobalLimitExec: skip=2, fetch=3
+///   CoalescePartitionsExec
+///     ProjectionExec: expr=[a@0 as a]
+///       CsvExec: file_groups={2 groups: [[x], [y]]}, projection=[a, b], has_header=false
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// GlobalLimitExec: skip=2, fetch=3
+///   CoalescePartitionsExec
+///     ProjectionExec: expr=[a@0 as a]
+///       CsvExec: file_groups={2 groups: [[x], [y]]}, projection=[a, b], limit=5, has_header=false
+/// ```
+///
+/// Each partition of the scan is limited to the rows needed, as any of its
+/// partitions may produce all of the rows of the limit.
+///
+/// The logical `PushDownLimit` rule already passes the limit to
+/// `TableProvider::scan` for plans planned from SQL; this rule covers physical
+/// plans that were built or modified directly.
+#[derive(Default)]
+pub struct LimitPushdown {}
+
+impl LimitPushdown {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for LimitPushdown {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let limit = if let Some(limit) =
+                plan.as_any().downcast_ref::<GlobalLimitExec>()
+            {
+                limit
+                    .fetch()
+                    .map(|fetch| (limit.input(), limit.skip() + fetch))
+            } else if let Some(limit) = plan.as_any().downcast_ref::<LocalLimitExec>() {
+                Some((limit.input(), limit.fetch()))
+            } else {
+                None
+            };
+            let Some((input, fetch)) = limit else {
+                return Ok(Transformed::No(plan));
+            };
+            Ok(match push_fetch(input, fetch)? {
+                Some(input) => {
+                    Transformed::Yes(plan.clone().with_new_children(vec![input])?)
+                }
+                None => Transformed::No(plan),
+            })
+        })
+    }
+
+    fn name(&self) -> &str {
+        "limit_pushdown"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Limits the file scan below the root of `plan` to its first `fetch` rows,
+/// returning `None` if there is no such scan, or it already has a smaller
+/// limit
+fn push_fetch(
+    plan: &Arc<dyn ExecutionPlan>,
+    fetch: usize,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let plan_any = plan.as_any();
+    if let Some(csv) = plan_any.downcast_ref::<CsvExec>() {
+        let Some(file_scan) = limited_file_scan(csv.base_config(), fetch) else {
+            return Ok(None);
+        };
+        Ok(Some(Arc::new(CsvExec::new(
+            file_scan,
+            csv.has_header(),
+            csv.delimiter(),
+            csv.quote(),
+            csv.escape(),
+            csv.file_compression_type,
+        ))))
+    } else if let Some(json) = plan_any.downcast_ref::<NdJsonExec>() {
+        let Some(file_scan) = limited_file_scan(json.base_config(), fetch) else {
+            return Ok(None);
+        };
+        Ok(Some(Arc::new(NdJsonExec::new(
+            file_scan,
+            json.file_compression_type(),
+        ))))
+    } else if plan_any.is::<ProjectionExec>()
+        || plan_any.is::<CoalesceBatchesExec>()
+        || plan_any.is::<CoalescePartitionsExec>()
+        || plan_any.is::<RepartitionExec>()
+    {
+        // these operators produce one row for each of their input rows
+        match push_fetch(&plan.children()[0], fetch)? {
+            Some(input) => Ok(Some(plan.clone().with_new_children(vec![input])?)),
+            None => Ok(None),
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns `file_scan` limited to `fetch` rows, or `None` if it already has a
+/// smaller limit
+fn limited_file_scan(file_scan: &FileScanConfig, fetch: usize) -> Option<FileScanConfig> {
+    if file_scan.limit.map_or(false, |limit| limit <= fetch) {
+        return None;
+    }
+    let mut file_scan = file_scan.clone();
+    file_scan.limit = Some(fetch);
+    Some(file_scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::file_format::file_compression_type::FileCompressionType;
+    use crate::datasource::listing::PartitionedFile;
+    use crate::physical_plan::displayable;
+    use crate::physical_plan::filter::FilterExec;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_common::{ScalarValue, Statistics};
+    use datafusion_execution::object_store::ObjectStoreUrl;
+    use datafusion_physical_expr::expressions::{col, Literal};
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Boolean, true),
+        ]))
+    }
+
+    fn csv_exec(schema: &SchemaRef, limit: Option<usize>) -> Arc<dyn ExecutionPlan> {
+        Arc::new(CsvExec::new(
+            FileScanConfig {
+                object_store_url: ObjectStoreUrl::parse("test:///").unwrap(),
+                file_schema: schema.clone(),
+                file_groups: vec![
+                    vec![PartitionedFile::new("x".to_string(), 100)],
+                    vec![PartitionedFile::new("y".to_string(), 100)],
+                ],
+                statistics: Statistics::new_unknown(schema),
+                projection: None,
+                limit,
+                table_partition_cols: vec![],
+                output_ordering: vec![],
+            },
+            false,
+            b',',
+            b'"',
+            None,
+            FileCompressionType::UNCOMPRESSED,
+        ))
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let optimized = LimitPushdown::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap();
+        displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn global_limit_over_csv() {
+        let schema = schema();
+        let projection = Arc::new(
+            ProjectionExec::try_new(
+                vec![(col("a", &schema).unwrap(), "a".to_string())],
+                csv_exec(&schema, None),
+            )
+            .unwrap(),
+        );
+        let plan = Arc::new(GlobalLimitExec::new(
+            Arc::new(CoalescePartitionsExec::new(projection)),
+            2,
+            Some(3),
+        ));
+
+        let expected = [
+            "GlobalLimitExec: skip=2, fetch=3",
+            "  CoalescePartitionsExec",
+            "    ProjectionExec: expr=[a@0 as a]",
+            "      CsvExec: file_groups={2 groups: [[x], [y]]}, projection=[a, b], limit=5, has_header=false",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn local_limit_keeps_smaller_scan_limit() {
+        let schema = schema();
+        let plan = Arc::new(LocalLimitExec::new(csv_exec(&schema, Some(2)), 10));
+
+        let expected = [
+            "LocalLimitExec: fetch=10",
+            "  CsvExec: file_groups={2 groups: [[x], [y]]}, projection=[a, b], limit=2, has_header=false",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn limit_not_pushed_through_filter() {
+        let schema = schema();
+        let predicate = Arc::new(Literal::new(ScalarValue::Boolean(Some(true))));
+        let filter =
+            Arc::new(FilterExec::try_new(predicate, csv_exec(&schema, None)).unwrap());
+        let plan = Arc::new(GlobalLimitExec::new(filter, 0, Some(10)));
+
+        let expected = [
+            "GlobalLimitExec: skip=0, fetch=10",
+            "  FilterExec: true",
+            "    CsvExec: file_groups={2 groups: [[x], [y]]}, projection=[a, b], has_header=false",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+}
@@ -21,12 +21,19 @@
 //! "Repartition" or "Sortedness"
 //!
 //! [`ExecutionPlan`]: crate::physical_plan::ExecutionPlan
+pub mod adaptive_execution;
 pub mod aggregate_statistics;
 pub mod coalesce_batches;
 pub mod combine_partial_final_agg;
+pub mod combine_window_aggregates;
+pub mod common_subexpr_eliminate;
+pub mod distinct_partition_values;
+pub mod dynamic_join_filter;
+pub mod dynamic_partitioning;
 pub mod enforce_distribution;
 pub mod enforce_sorting;
 pub mod join_selection;
+pub mod limit_pushdown;
 pub mod limited_distinct_aggregation;
 pub mod optimizer;
 pub mod output_requirements;
@@ -34,8 +41,14 @@ pub mod pipeline_checker;
 mod projection_pushdown;
 pub mod pruning;
 pub mod replace_with_order_preserving_variants;
+pub mod scalar_function_cache;
+pub mod scan_sort_pushdown;
+pub mod semi_join_deduplication;
+pub mod share_scans;
+pub mod small_input;
 mod sort_pushdown;
 pub mod topk_aggregation;
+pub mod topk_sort;
 mod utils;
 
 #[cfg(test)]
@@ -21,18 +21,33 @@ use std::sync::Arc;
 
 use super::projection_pushdown::ProjectionPushdown;
 use crate::config::ConfigOptions;
+use crate::physical_optimizer::adaptive_execution::AdaptiveExecution;
 use crate::physical_optimizer::aggregate_statistics::AggregateStatistics;
 use crate::physical_optimizer::coalesce_batches::CoalesceBatches;
 use crate::physical_optimizer::combine_partial_final_agg::CombinePartialFinalAggregate;
+use crate::physical_optimizer::combine_window_aggregates::CombineWindowAggregates;
+use crate::physical_optimizer::common_subexpr_eliminate::CommonSubexprEliminate;
+use crate::physical_optimizer::distinct_partition_values::DistinctPartitionValues;
+use crate::physical_optimizer::dynamic_join_filter::DynamicJoinFilter;
+use crate::physical_optimizer::dynamic_partitioning::DynamicPartitioning;
 use crate::physical_optimizer::enforce_distribution::EnforceDistribution;
 use crate::physical_optimizer::enforce_sorting::EnforceSorting;
 use crate::physical_optimizer::join_selection::JoinSelection;
+use crate::physical_optimizer::limit_pushdown::LimitPushdown;
 use crate::physical_optimizer::limited_distinct_aggregation::LimitedDistinctAggregation;
 use crate::physical_optimizer::output_requirements::OutputRequirements;
 use crate::physical_optimizer::pipeline_checker::PipelineChecker;
+use crate::physical_optimizer::scalar_function_cache::ScalarFunctionCache;
+use crate::physical_optimizer::scan_sort_pushdown::ScanSortPushdown;
+use crate::physical_optimizer::semi_join_deduplication::SemiJoinDeduplication;
+use crate::physical_optimizer::share_scans::ShareScans;
+use crate::physical_optimizer::small_input::SmallInputFastPath;
 use crate::physical_optimizer::topk_aggregation::TopKAggregation;
+use crate::physical_optimizer::topk_sort::TopKSort;
 use crate::{error::Result, physical_plan::ExecutionPlan};
 
+use datafusion_common::{plan_err, DataFusionError};
+
 /// `PhysicalOptimizerRule` transforms one ['ExecutionPlan'] into another which
 /// computes the same results, but in a potentially more efficient
 /// way.
@@ -81,10 +96,26 @@ impl PhysicalOptimizer {
             // repartitioning and local sorting steps to meet distribution and ordering requirements.
             // Therefore, it should run before EnforceDistribution and EnforceSorting.
             Arc::new(JoinSelection::new()),
+            // The SemiJoinDeduplication rule aggregates the filtering input of the
+            // semi joins, so it should run after JoinSelection picks their sides.
+            Arc::new(SemiJoinDeduplication::new()),
             // The LimitedDistinctAggregation rule should be applied before the EnforceDistribution rule,
             // as that rule may inject other operations in between the different AggregateExecs.
             // Applying the rule early means only directly-connected AggregateExecs must be examined.
             Arc::new(LimitedDistinctAggregation::new()),
+            // The DistinctPartitionValues rule makes the scans of partition values below
+            // aggregations without aggregate expressions return a row per file. It runs
+            // before EnforceDistribution, so that it finds few operators in between.
+            Arc::new(DistinctPartitionValues::new()),
+            // The ScanSortPushdown rule removes the sorts of the initial plan whose
+            // ordering the scans below them can return natively. It runs before
+            // EnforceDistribution, which may break the ordering of the scans with
+            // repartitioning, and EnforceSorting, which would keep the sorts.
+            Arc::new(ScanSortPushdown::new()),
+            // The SmallInputFastPath rule plans the aggregations and joins of inputs
+            // fitting in a single batch as single-stage operators. It runs before
+            // EnforceDistribution, so that no repartitioning is added below them.
+            Arc::new(SmallInputFastPath::new()),
             // The EnforceDistribution rule is for adding essential repartitioning to satisfy distribution
             // requirements. Please make sure that the whole plan tree is determined before this rule.
             // This rule increases parallelism if doing so is beneficial to the physical plan; i.e. at
@@ -97,6 +128,13 @@ impl PhysicalOptimizer {
             // Note that one should always run this rule after running the EnforceDistribution rule
             // as the latter may break local sorting requirements.
             Arc::new(EnforceSorting::new()),
+            // The CombineWindowAggregates rule merges the window aggregations whose sorts
+            // were removed by the EnforceSorting rule, so it should run after it.
+            Arc::new(CombineWindowAggregates::new()),
+            // The DynamicPartitioning rule lowers the number of partitions of the
+            // repartitions added by the EnforceDistribution and EnforceSorting rules,
+            // so it runs after them.
+            Arc::new(DynamicPartitioning::new()),
             // The CoalesceBatches rule will not influence the distribution and ordering of the
             // whole plan tree. Therefore, to avoid influencing other rules, it should run last.
             Arc::new(CoalesceBatches::new()),
@@ -108,6 +146,14 @@ impl PhysicalOptimizer {
             // diagnostic error message when this happens. It makes no changes to the
             // given query plan; i.e. it only acts as a final gatekeeping rule.
             Arc::new(PipelineChecker::new()),
+            // The TopKSort rule pushes the number of rows needed by limits into the
+            // sorts below them, so that they only keep the top rows in a bounded heap.
+            // It runs before TopKAggregation, which uses the limits of these sorts.
+            Arc::new(TopKSort::new()),
+            // The LimitPushdown rule pushes the number of rows needed by limits into the
+            // CSV and JSON scans below them, so that they stop reading once they have
+            // produced enough rows.
+            Arc::new(LimitPushdown::new()),
             // The aggregation limiter will try to find situations where the accumulator count
             // is not tied to the cardinality, i.e. when the output of the aggregation is passed
             // into an `order by max(x) limit y`. In this case it will copy the limit value down
@@ -120,6 +166,26 @@ impl PhysicalOptimizer {
             // are not present, the load of executors such as join or union will be
             // reduced by narrowing their input tables.
             Arc::new(ProjectionPushdown::new()),
+            // The CommonSubexprEliminate rule adds projections evaluating function calls
+            // shared between a projection and the filter below it. It runs after the
+            // ProjectionPushdown rule, which would otherwise try to push them down.
+            Arc::new(CommonSubexprEliminate::new()),
+            // The ScalarFunctionCache rule wraps function calls in expressions that
+            // other rules do not recognize, so it runs after the rules inspecting them.
+            Arc::new(ScalarFunctionCache::new()),
+            // The ShareScans rule makes the scans of the same files read them once.
+            // It runs after the rules changing the files, projections and filters
+            // of the scans, and before the DynamicJoinFilter rule, so that the
+            // filters of a join are not applied to the scans shared with others.
+            Arc::new(ShareScans::new()),
+            // The DynamicJoinFilter rule links hash joins with the parquet scans on
+            // their probe side. It should run last, as rules that rebuild the scans
+            // or move operators in between them and the join would break the link.
+            Arc::new(DynamicJoinFilter::new()),
+            // The AdaptiveExecution rule defers re-optimizing the joins of the final
+            // plan until their inputs have been executed, so it runs after all other
+            // rules.
+            Arc::new(AdaptiveExecution::new()),
         ];
 
         Self::with_rules(rules)
@@ -129,4 +195,50 @@ impl PhysicalOptimizer {
     pub fn with_rules(rules: Vec<Arc<dyn PhysicalOptimizerRule + Send + Sync>>) -> Self {
         Self { rules }
     }
+
+    /// Inserts `rule` at `position` among the rules of the optimizer. Returns
+    /// an error if the position refers to a rule that is not registered
+    pub fn add_rule(
+        &mut self,
+        rule: Arc<dyn PhysicalOptimizerRule + Send + Sync>,
+        position: RulePosition,
+    ) -> Result<()> {
+        let index = match &position {
+            RulePosition::First => 0,
+            RulePosition::Last => self.rules.len(),
+            RulePosition::Before(name) => {
+                let Some(index) = self.rules.iter().position(|r| r.name() == name) else {
+                    return plan_err!("Physical optimizer rule {name} is not registered");
+                };
+                index
+            }
+            RulePosition::After(name) => {
+                let Some(index) = self.rules.iter().rposition(|r| r.name() == name)
+                else {
+                    return plan_err!("Physical optimizer rule {name} is not registered");
+                };
+                index + 1
+            }
+        };
+        self.rules.insert(index, rule);
+        Ok(())
+    }
+}
+
+/// Where [`PhysicalOptimizer::add_rule`] inserts a rule, relative to the
+/// [`PhysicalOptimizerRule::name`]s of the registered rules.
+///
+/// For example, a rule relying on the distribution of the partitions of the
+/// plan should run after `EnforceDistribution`, and a rule changing the
+/// batches of the plan before `coalesce_batches`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RulePosition {
+    /// Before all other rules
+    First,
+    /// After all other rules
+    Last,
+    /// Right before the first rule with the given name
+    Before(String),
+    /// Right after the last rule with the given name
+    After(String),
 }
@@ -26,7 +26,11 @@ use std::sync::Arc;
 use super::output_requirements::OutputRequirementExec;
 use super::PhysicalOptimizerRule;
 use crate::datasource::physical_plan::CsvExec;
+#[cfg(feature = "parquet")]
+use crate::datasource::physical_plan::ParquetExec;
 use crate::error::Result;
+#[cfg(feature = "parquet")]
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
 use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use crate::physical_plan::filter::FilterExec;
 use crate::physical_plan::joins::utils::{ColumnIndex, JoinFilter};
@@ -41,11 +45,16 @@ use crate::physical_plan::sorts::sort::SortExec;
 use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
 use crate::physical_plan::{Distribution, ExecutionPlan};
 
-use arrow_schema::SchemaRef;
+use arrow_schema::{DataType, Fields, SchemaRef};
 use datafusion_common::config::ConfigOptions;
 use datafusion_common::tree_node::{Transformed, TreeNode, VisitRecursion};
-use datafusion_common::JoinSide;
-use datafusion_physical_expr::expressions::{Column, Literal};
+use datafusion_common::{JoinSide, ScalarValue};
+use datafusion_physical_expr::expressions::{
+    Column, GetFieldAccessExpr, GetIndexedFieldExpr, Literal,
+};
+use datafusion_physical_expr::utils::{
+    collect_columns, expr_after_projection, expr_before_projection,
+};
 use datafusion_physical_expr::{
     Partitioning, PhysicalExpr, PhysicalSortExpr, PhysicalSortRequirement,
 };
@@ -101,7 +110,12 @@ pub fn remove_unnecessary_projections(
         }
         // If it does, check if we can push it under its child(ren):
         let input = projection.input().as_any();
-        if let Some(csv) = input.downcast_ref::<CsvExec>() {
+        #[cfg(feature = "parquet")]
+        if input.is::<ParquetExec>() {
+            let maybe_pruned = try_pruning_struct_fields_of_parquet(projection)?;
+            return Ok(maybe_pruned.map_or(Transformed::No(plan), Transformed::Yes));
+        }
+        let maybe_swapped = if let Some(csv) = input.downcast_ref::<CsvExec>() {
             try_swapping_with_csv(projection, csv)
         } else if let Some(memory) = input.downcast_ref::<MemoryExec>() {
             try_swapping_with_memory(projection, memory)?
@@ -145,7 +159,15 @@ pub fn remove_unnecessary_projections(
             // When adding new operators, consider adding them here if you
             // think pushing projections under them is beneficial.
             None
+        };
+        // If the projection stays above a Parquet scan, it may still read
+        // only the accessed fields of struct columns:
+        #[cfg(feature = "parquet")]
+        if maybe_swapped.is_none() {
+            let maybe_pruned = try_pruning_struct_fields_of_parquet(projection)?;
+            return Ok(maybe_pruned.map_or(Transformed::No(plan), Transformed::Yes));
         }
+        maybe_swapped
     } else {
         return Ok(Transformed::No(plan));
     };
@@ -178,6 +200,161 @@ fn try_swapping_with_csv(
     })
 }
 
+/// Tries to restrict the struct columns read by the [`ParquetExec`] under
+/// `projection` to the fields accessed by `projection`, e.g. `s["a"]["b"]`.
+/// The scan may be below filters, repartitions and batch coalescing, whose
+/// expressions are taken into account as well. If any struct column is only
+/// accessed through its fields, returns `projection` on top of a
+/// [`ParquetExec`] reading only those fields. Otherwise, returns `None`.
+#[cfg(feature = "parquet")]
+fn try_pruning_struct_fields_of_parquet(
+    projection: &ProjectionExec,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let input = projection.input();
+    // The field paths accessed for each column of the scan, or `None` if
+    // the column is used as a whole. The operators between `projection` and
+    // the scan keep its schema, so the column indices are the same.
+    let mut accessed_paths = vec![Some(vec![]); input.schema().fields().len()];
+    for (expr, _) in projection.expr() {
+        collect_struct_field_paths(expr, &mut accessed_paths);
+    }
+
+    let mut operators = vec![];
+    let mut plan = input.clone();
+    let parquet = loop {
+        let any = plan.as_any();
+        if let Some(parquet) = any.downcast_ref::<ParquetExec>() {
+            break parquet.clone();
+        } else if let Some(filter) = any.downcast_ref::<FilterExec>() {
+            collect_struct_field_paths(filter.predicate(), &mut accessed_paths);
+        } else if let Some(repartition) = any.downcast_ref::<RepartitionExec>() {
+            if let Partitioning::Hash(exprs, _) = repartition.partitioning() {
+                for expr in exprs {
+                    collect_struct_field_paths(expr, &mut accessed_paths);
+                }
+            }
+        } else if !any.is::<CoalesceBatchesExec>() {
+            return Ok(None);
+        }
+        let child = plan.children().swap_remove(0);
+        operators.push(plan);
+        plan = child;
+    };
+
+    let file_scan = parquet.base_config();
+    let file_schema = &file_scan.file_schema;
+    // Columns of the predicate are evaluated as a whole during the scan
+    let predicate_columns = parquet.predicate().map(collect_columns).unwrap_or_default();
+
+    let mut pruned_columns = vec![];
+    for (index, paths) in accessed_paths.into_iter().enumerate() {
+        let Some(paths) = paths.filter(|paths| !paths.is_empty()) else {
+            continue;
+        };
+        let file_index = match &file_scan.projection {
+            Some(projection) => projection[index],
+            None => index,
+        };
+        // Partition columns are not read from the files
+        if file_index >= file_schema.fields().len() {
+            continue;
+        }
+        let field = file_schema.field(file_index);
+        if predicate_columns
+            .iter()
+            .any(|column| column.name() == field.name())
+        {
+            continue;
+        }
+
+        let paths = paths.iter().map(|path| path.as_slice()).collect::<Vec<_>>();
+        let pruned_type = prune_struct_fields(field.data_type(), &paths);
+        if &pruned_type != field.data_type() {
+            pruned_columns.push((file_index, pruned_type));
+        }
+    }
+    if pruned_columns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut new_input: Arc<dyn ExecutionPlan> =
+        Arc::new(parquet.with_pruned_struct_columns(pruned_columns));
+    for operator in operators.into_iter().rev() {
+        new_input = operator.with_new_children(vec![new_input])?;
+    }
+    ProjectionExec::try_new(projection.expr().to_vec(), new_input)
+        .map(|e| Some(Arc::new(e) as _))
+}
+
+/// Collects the field paths of the struct columns accessed by `expr` into
+/// `accessed_paths`, indexed by column. Columns used as a whole are set to `None`.
+#[cfg(feature = "parquet")]
+fn collect_struct_field_paths(
+    expr: &Arc<dyn PhysicalExpr>,
+    accessed_paths: &mut [Option<Vec<Vec<String>>>],
+) {
+    if let Some((column, path)) = struct_field_path(expr) {
+        if let Some(paths) = &mut accessed_paths[column.index()] {
+            paths.push(path);
+        }
+    } else if let Some(column) = expr.as_any().downcast_ref::<Column>() {
+        accessed_paths[column.index()] = None;
+    } else {
+        for child in expr.children() {
+            collect_struct_field_paths(&child, accessed_paths);
+        }
+    }
+}
+
+/// If `expr` accesses a (nested) field of a column, such as `s["a"]["b"]`,
+/// returns the column and the names of the accessed fields.
+#[cfg(feature = "parquet")]
+fn struct_field_path(expr: &Arc<dyn PhysicalExpr>) -> Option<(&Column, Vec<String>)> {
+    let get_field = expr.as_any().downcast_ref::<GetIndexedFieldExpr>()?;
+    let GetFieldAccessExpr::NamedStructField {
+        name: ScalarValue::Utf8(Some(name)),
+    } = get_field.field()
+    else {
+        return None;
+    };
+
+    let arg = get_field.arg();
+    if let Some(column) = arg.as_any().downcast_ref::<Column>() {
+        return Some((column, vec![name.clone()]));
+    }
+    let (column, mut path) = struct_field_path(arg)?;
+    path.push(name.clone());
+    Some((column, path))
+}
+
+/// Returns `data_type` with only the (nested) struct fields on `paths`.
+#[cfg(feature = "parquet")]
+fn prune_struct_fields(data_type: &DataType, paths: &[&[String]]) -> DataType {
+    let DataType::Struct(fields) = data_type else {
+        return data_type.clone();
+    };
+    // An empty path accesses the struct as a whole
+    if paths.iter().any(|path| path.is_empty()) {
+        return data_type.clone();
+    }
+
+    let fields = fields
+        .iter()
+        .filter_map(|field| {
+            let field_paths = paths
+                .iter()
+                .filter(|path| &path[0] == field.name())
+                .map(|path| &path[1..])
+                .collect::<Vec<_>>();
+            (!field_paths.is_empty()).then(|| {
+                let data_type = prune_struct_fields(field.data_type(), &field_paths);
+                Arc::new(field.as_ref().clone().with_data_type(data_type))
+            })
+        })
+        .collect::<Fields>();
+    DataType::Struct(fields)
+}
+
 /// Tries to embed `projection` to its input (`memory`). If possible, returns
 /// [`MemoryExec`] as the top plan. Otherwise, returns `None`.
 fn try_swapping_with_memory(
@@ -423,10 +600,10 @@ fn try_swapping_with_repartition(
         others => others.clone(),
     };
 
-    Ok(Some(Arc::new(RepartitionExec::try_new(
-        new_projection,
-        new_partitioning,
-    )?)))
+    Ok(Some(Arc::new(
+        RepartitionExec::try_new(new_projection, new_partitioning)?
+            .with_work_stealing(repartition.work_stealing()),
+    )))
 }
 
 /// Tries to swap the projection with its input [`SortExec`]. If it can be done,
@@ -866,58 +1043,15 @@ fn update_expr(
     projected_exprs: &[(Arc<dyn PhysicalExpr>, String)],
     sync_with_child: bool,
 ) -> Result<Option<Arc<dyn PhysicalExpr>>> {
-    #[derive(Debug, PartialEq)]
-    enum RewriteState {
-        /// The expression is unchanged.
-        Unchanged,
-        /// Some part of the expression has been rewritten
-        RewrittenValid,
-        /// Some part of the expression has been rewritten, but some column
-        /// references could not be.
-        RewrittenInvalid,
+    // expressions without columns are left where they are
+    if collect_columns(expr).is_empty() {
+        return Ok(None);
+    }
+    if sync_with_child {
+        expr_before_projection(expr.clone(), projected_exprs).map(Some)
+    } else {
+        expr_after_projection(expr.clone(), projected_exprs)
     }
-
-    let mut state = RewriteState::Unchanged;
-
-    let new_expr = expr
-        .clone()
-        .transform_up_mut(&mut |expr: Arc<dyn PhysicalExpr>| {
-            if state == RewriteState::RewrittenInvalid {
-                return Ok(Transformed::No(expr));
-            }
-
-            let Some(column) = expr.as_any().downcast_ref::<Column>() else {
-                return Ok(Transformed::No(expr));
-            };
-            if sync_with_child {
-                state = RewriteState::RewrittenValid;
-                // Update the index of `column`:
-                Ok(Transformed::Yes(projected_exprs[column.index()].0.clone()))
-            } else {
-                // default to invalid, in case we can't find the relevant column
-                state = RewriteState::RewrittenInvalid;
-                // Determine how to update `column` to accommodate `projected_exprs`
-                projected_exprs
-                    .iter()
-                    .enumerate()
-                    .find_map(|(index, (projected_expr, alias))| {
-                        projected_expr.as_any().downcast_ref::<Column>().and_then(
-                            |projected_column| {
-                                column.name().eq(projected_column.name()).then(|| {
-                                    state = RewriteState::RewrittenValid;
-                                    Arc::new(Column::new(alias, index)) as _
-                                })
-                            },
-                        )
-                    })
-                    .map_or_else(
-                        || Ok(Transformed::No(expr)),
-                        |c| Ok(Transformed::Yes(c)),
-                    )
-            }
-        });
-
-    new_expr.map(|e| (state == RewriteState::RewrittenValid).then_some(e))
 }
 
 /// Creates a new [`ProjectionExec`] instance with the given child plan and
@@ -1172,6 +1306,8 @@ mod tests {
         join_table_borders, update_expr, ProjectionPushdown,
     };
     use crate::physical_optimizer::PhysicalOptimizerRule;
+    #[cfg(feature = "parquet")]
+    use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
     use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
     use crate::physical_plan::filter::FilterExec;
     use crate::physical_plan::joins::utils::{ColumnIndex, JoinFilter};
@@ -1620,6 +1756,149 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_struct_fields_after_projection() -> Result<()> {
+        use crate::datasource::physical_plan::ParquetExec;
+        use arrow_schema::Fields;
+        use datafusion_physical_expr::expressions::GetIndexedFieldExpr;
+
+        let struct_type = DataType::Struct(Fields::from(vec![
+            Field::new(
+                "a",
+                DataType::Struct(Fields::from(vec![
+                    Field::new("x", DataType::Int32, true),
+                    Field::new("y", DataType::Int32, true),
+                ])),
+                true,
+            ),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("s", struct_type.clone(), true),
+            Field::new("t", struct_type.clone(), true),
+        ]));
+        let parquet = Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store_url: ObjectStoreUrl::parse("test:///").unwrap(),
+                file_schema: schema.clone(),
+                file_groups: vec![vec![PartitionedFile::new("x".to_string(), 100)]],
+                statistics: Statistics::new_unknown(&schema),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: vec![vec![]],
+            },
+            None,
+            None,
+        ));
+
+        // `s` is only accessed through `s["a"]["y"]`, `t` is used as a whole
+        let s_a_y = Arc::new(GetIndexedFieldExpr::new_field(
+            Arc::new(GetIndexedFieldExpr::new_field(
+                Arc::new(Column::new("s", 1)),
+                "a",
+            )),
+            "y",
+        ));
+        let projection: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![
+                (Arc::new(Column::new("id", 0)), "id".to_string()),
+                (s_a_y, "y".to_string()),
+                (Arc::new(Column::new("t", 2)), "t".to_string()),
+            ],
+            parquet,
+        )?);
+        let initial = get_plan_string(&projection);
+
+        let after_optimize =
+            ProjectionPushdown::new().optimize(projection, &ConfigOptions::new())?;
+        assert_eq!(get_plan_string(&after_optimize), initial);
+
+        let pruned_type = DataType::Struct(Fields::from(vec![Field::new(
+            "a",
+            DataType::Struct(Fields::from(vec![Field::new("y", DataType::Int32, true)])),
+            true,
+        )]));
+        let scan_schema = after_optimize.children()[0].schema();
+        assert_eq!(scan_schema.field(0).data_type(), &DataType::Int32);
+        assert_eq!(scan_schema.field(1).data_type(), &pruned_type);
+        assert_eq!(scan_schema.field(2).data_type(), &struct_type);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_struct_fields_below_filter() -> Result<()> {
+        use crate::datasource::physical_plan::ParquetExec;
+        use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+        use arrow_schema::Fields;
+        use datafusion_physical_expr::expressions::GetIndexedFieldExpr;
+
+        let struct_type = DataType::Struct(Fields::from(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Utf8, true),
+        ]));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("s", struct_type, true),
+        ]));
+        let parquet = Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store_url: ObjectStoreUrl::parse("test:///").unwrap(),
+                file_schema: schema.clone(),
+                file_groups: vec![vec![PartitionedFile::new("x".to_string(), 100)]],
+                statistics: Statistics::new_unknown(&schema),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: vec![vec![]],
+            },
+            None,
+            None,
+        ));
+
+        // `s["b"] = 'x'` is evaluated by the filter, `s["a"]` by the projection
+        let s_field = |name: &str| {
+            Arc::new(GetIndexedFieldExpr::new_field(
+                Arc::new(Column::new("s", 1)),
+                name,
+            )) as Arc<dyn PhysicalExpr>
+        };
+        let predicate = Arc::new(BinaryExpr::new(
+            s_field("b"),
+            Operator::Eq,
+            Arc::new(Literal::new(ScalarValue::from("x"))),
+        ));
+        let filter = Arc::new(FilterExec::try_new(predicate, parquet)?);
+        let coalesce_batches = Arc::new(CoalesceBatchesExec::new(filter, 8192));
+        let projection: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![
+                (Arc::new(Column::new("id", 0)), "id".to_string()),
+                (s_field("a"), "a".to_string()),
+            ],
+            coalesce_batches,
+        )?);
+        let initial = get_plan_string(&projection);
+
+        let after_optimize =
+            ProjectionPushdown::new().optimize(projection, &ConfigOptions::new())?;
+        assert_eq!(get_plan_string(&after_optimize), initial);
+
+        let pruned_type = DataType::Struct(Fields::from(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let scan = after_optimize.children()[0].children()[0].children()[0].clone();
+        assert!(scan.as_any().is::<ParquetExec>());
+        assert_eq!(scan.schema().field(1).data_type(), &pruned_type);
+
+        Ok(())
+    }
+
     #[test]
     fn test_memory_after_projection() -> Result<()> {
         let memory = create_projecting_memory_exec();
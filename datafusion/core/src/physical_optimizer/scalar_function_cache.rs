@@ -0,0 +1,272 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ScalarFunctionCache`] caches the results of deterministic scalar
+//! functions over repeated arguments.
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_expr::Volatility;
+use datafusion_physical_expr::expressions::Literal;
+use datafusion_physical_expr::{
+    CachedScalarFunctionExpr, PhysicalExpr, ScalarFunctionExpr,
+};
+
+/// Optimizer rule that wraps the calls to non-volatile scalar functions in
+/// the expressions of [`ProjectionExec`]s and [`FilterExec`]s with a
+/// [`CachedScalarFunctionExpr`], so that each function is evaluated once per
+/// distinct combination of arguments, when
+/// `datafusion.optimizer.scalar_function_cache_size` is greater than zero.
+///
+/// Calls without arguments, or whose arguments are all literals, are left
+/// unchanged.
+#[derive(Default)]
+pub struct ScalarFunctionCache {}
+
+impl ScalarFunctionCache {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for ScalarFunctionCache {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let capacity = config.optimizer.scalar_function_cache_size;
+        if capacity == 0 {
+            return Ok(plan);
+        }
+
+        plan.transform_up(&|plan| {
+            if let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() {
+                if projection.expr().iter().any(|(e, _)| contains_cacheable(e)) {
+                    let exprs = projection
+                        .expr()
+                        .iter()
+                        .map(|(expr, name)| {
+                            let expr = cache_functions(expr, capacity)?
+                                .unwrap_or_else(|| expr.clone());
+                            Ok((expr, name.clone()))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    let projection =
+                        ProjectionExec::try_new(exprs, projection.input().clone())?;
+                    return Ok(Transformed::Yes(Arc::new(projection)));
+                }
+            } else if let Some(filter) = plan.as_any().downcast_ref::<FilterExec>() {
+                if let Some(predicate) = cache_functions(filter.predicate(), capacity)? {
+                    let filter = FilterExec::try_new(predicate, filter.input().clone())?
+                        .with_default_selectivity(filter.default_selectivity())?;
+                    return Ok(Transformed::Yes(Arc::new(filter)));
+                }
+            }
+            Ok(Transformed::No(plan))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "scalar_function_cache"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+fn is_cacheable(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    expr.as_any()
+        .downcast_ref::<ScalarFunctionExpr>()
+        .map(|func| {
+            func.volatility() != Volatility::Volatile
+                && !func.args().iter().all(|arg| arg.as_any().is::<Literal>())
+        })
+        .unwrap_or(false)
+}
+
+fn contains_cacheable(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    is_cacheable(expr) || expr.children().iter().any(contains_cacheable)
+}
+
+/// Wraps the cacheable function calls in `expr`, or returns `None` if there
+/// are none
+fn cache_functions(
+    expr: &Arc<dyn PhysicalExpr>,
+    capacity: usize,
+) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+    if !contains_cacheable(expr) {
+        return Ok(None);
+    }
+    let expr = expr.clone().transform_up(&|expr| {
+        if !is_cacheable(&expr) {
+            return Ok(Transformed::No(expr));
+        }
+        let func = expr.as_any().downcast_ref::<ScalarFunctionExpr>().unwrap();
+        let func = ScalarFunctionExpr::new(
+            func.name(),
+            func.fun().clone(),
+            func.args().to_vec(),
+            func.return_type().clone(),
+            func.monotonicity().clone(),
+        )
+        .with_volatility(func.volatility());
+        Ok(Transformed::Yes(Arc::new(CachedScalarFunctionExpr::new(
+            Arc::new(func),
+            capacity,
+        ))))
+    })?;
+    Ok(Some(expr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_optimizer::test_utils::memory_exec;
+    use crate::physical_plan::displayable;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_expr::{BuiltinScalarFunction, Operator};
+    use datafusion_physical_expr::execution_props::ExecutionProps;
+    use datafusion_physical_expr::expressions::{binary, col, lit};
+    use datafusion_physical_expr::functions::create_physical_expr;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]))
+    }
+
+    fn abs(arg: Arc<dyn PhysicalExpr>, schema: &SchemaRef) -> Arc<dyn PhysicalExpr> {
+        create_physical_expr(
+            &BuiltinScalarFunction::Abs,
+            &[arg],
+            schema,
+            &ExecutionProps::new(),
+        )
+        .unwrap()
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>, capacity: usize) -> Vec<String> {
+        let mut config = ConfigOptions::new();
+        config.optimizer.scalar_function_cache_size = capacity;
+        let optimized = ScalarFunctionCache::new().optimize(plan, &config).unwrap();
+        displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn cache_projection_and_filter() -> Result<()> {
+        let schema = schema();
+        let filter = Arc::new(FilterExec::try_new(
+            binary(
+                abs(col("a", &schema)?, &schema),
+                Operator::Gt,
+                lit(1i64),
+                &schema,
+            )?,
+            memory_exec(&schema),
+        )?);
+        let plan = Arc::new(ProjectionExec::try_new(
+            vec![
+                (abs(col("b", &schema)?, &schema), "x".to_string()),
+                (col("a", &schema)?, "a".to_string()),
+            ],
+            filter,
+        )?);
+
+        let expected = [
+            "ProjectionExec: expr=[cached(abs(b@1)) as x, a@0 as a]",
+            "  FilterExec: cached(abs(a@0)) > 1",
+            "    MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan.clone(), 100), expected);
+
+        // disabled by default
+        let expected = [
+            "ProjectionExec: expr=[abs(b@1) as x, a@0 as a]",
+            "  FilterExec: abs(a@0) > 1",
+            "    MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan, 0), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn nested_calls() -> Result<()> {
+        let schema = schema();
+        let plan = Arc::new(ProjectionExec::try_new(
+            vec![(
+                abs(abs(col("a", &schema)?, &schema), &schema),
+                "x".to_string(),
+            )],
+            memory_exec(&schema),
+        )?);
+
+        let expected = [
+            "ProjectionExec: expr=[cached(abs(cached(abs(a@0)))) as x]",
+            "  MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan, 100), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn volatile_and_literal_calls_not_cached() -> Result<()> {
+        let schema = schema();
+        let abs_a = abs(col("a", &schema)?, &schema);
+        let abs_a = abs_a.as_any().downcast_ref::<ScalarFunctionExpr>().unwrap();
+        // the volatility of the function is unknown
+        let volatile = Arc::new(ScalarFunctionExpr::new(
+            "f",
+            abs_a.fun().clone(),
+            abs_a.args().to_vec(),
+            DataType::Int64,
+            None,
+        ));
+        let plan = Arc::new(ProjectionExec::try_new(
+            vec![
+                (volatile, "x".to_string()),
+                (abs(lit(-1i64), &schema), "y".to_string()),
+            ],
+            memory_exec(&schema),
+        )?);
+
+        let expected = [
+            "ProjectionExec: expr=[f(a@0) as x, abs(-1) as y]",
+            "  MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan, 100), expected);
+        Ok(())
+    }
+}
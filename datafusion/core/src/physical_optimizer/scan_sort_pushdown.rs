@@ -0,0 +1,334 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ScanSortPushdown`] removes sorts whose ordering the scans below them
+//! can return natively
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::expressions::Column;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_physical_expr::PhysicalSortExpr;
+
+/// Optimizer rule that asks the source below a [`SortExec`] (through
+/// projections of columns, filters and [`CoalesceBatchesExec`]s) to return
+/// its rows in the order of the sort with
+/// [`ExecutionPlan::try_pushdown_sort`]. If it can, the sort is removed, or
+/// replaced with a [`SortPreservingMergeExec`] of the sorted partitions:
+///
+/// ```text
+/// SortExec: expr=[a@0 ASC]
+///   FilterExec: a@0 > 1
+///     TimeSeriesExec: partitions=2
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// SortPreservingMergeExec: [a@0 ASC]
+///   FilterExec: a@0 > 1
+///     TimeSeriesExec: partitions=2, output_ordering=[a@0 ASC]
+/// ```
+///
+/// It runs before `EnforceDistribution`, so it only sees the sorts of the
+/// initial plan, such as those of `ORDER BY` clauses.
+#[derive(Default)]
+pub struct ScanSortPushdown {}
+
+impl ScanSortPushdown {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for ScanSortPushdown {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            let Some(sort) = plan.as_any().downcast_ref::<SortExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            let Some(input) = pushdown_sort(sort.input(), sort.expr())? else {
+                return Ok(Transformed::No(plan));
+            };
+            if !input.equivalence_properties().ordering_satisfy(sort.expr()) {
+                return Ok(Transformed::No(plan));
+            }
+            Ok(Transformed::Yes(remove_sort(sort, input)))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "scan_sort_pushdown"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns `plan` with the source below it replaced by one returning its rows
+/// ordered by `ordering`, or `None` if the source can not do so
+fn pushdown_sort(
+    plan: &Arc<dyn ExecutionPlan>,
+    ordering: &[PhysicalSortExpr],
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let ordering =
+        if let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() {
+            // only sorts on columns of the projection input can be pushed through it
+            let ordering = ordering
+                .iter()
+                .map(|sort| {
+                    let column = sort.expr.as_any().downcast_ref::<Column>()?;
+                    let (expr, _) = &projection.expr()[column.index()];
+                    expr.as_any().downcast_ref::<Column>()?;
+                    Some(PhysicalSortExpr {
+                        expr: expr.clone(),
+                        options: sort.options,
+                    })
+                })
+                .collect::<Option<Vec<_>>>();
+            let Some(ordering) = ordering else {
+                return Ok(None);
+            };
+            ordering
+        } else if plan.as_any().is::<FilterExec>()
+            || plan.as_any().is::<CoalesceBatchesExec>()
+        {
+            ordering.to_vec()
+        } else {
+            return plan.try_pushdown_sort(ordering);
+        };
+    let Some(input) = pushdown_sort(&plan.children()[0], &ordering)? else {
+        return Ok(None);
+    };
+    plan.clone().with_new_children(vec![input]).map(Some)
+}
+
+/// Replaces `sort` with `input`, whose partitions are each already sorted
+fn remove_sort(sort: &SortExec, input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+    let partitions = input.output_partitioning().partition_count();
+    if partitions > 1 && !sort.preserve_partitioning() {
+        return Arc::new(
+            SortPreservingMergeExec::new(sort.expr().to_vec(), input)
+                .with_fetch(sort.fetch()),
+        );
+    }
+    match sort.fetch() {
+        Some(fetch) if partitions > 1 => Arc::new(LocalLimitExec::new(input, fetch)),
+        Some(fetch) => Arc::new(GlobalLimitExec::new(input, 0, Some(fetch))),
+        None => input,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::fmt::Formatter;
+
+    use super::*;
+    use crate::execution::context::TaskContext;
+    use crate::physical_optimizer::test_utils::{filter_exec, sort_exec, sort_expr};
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::{
+        displayable, DisplayAs, DisplayFormatType, Partitioning,
+        SendableRecordBatchStream,
+    };
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_physical_expr::expressions::{lit, BinaryExpr};
+    use datafusion_physical_expr::{Operator, PhysicalExpr};
+
+    /// A source that can return its partitions ordered by column `a`
+    #[derive(Debug)]
+    struct TimeSeriesExec {
+        input: MemoryExec,
+    }
+
+    impl TimeSeriesExec {
+        fn new(schema: &SchemaRef, partitions: usize) -> Self {
+            let input =
+                MemoryExec::try_new(&vec![vec![]; partitions], schema.clone(), None)
+                    .unwrap();
+            Self { input }
+        }
+    }
+
+    impl DisplayAs for TimeSeriesExec {
+        fn fmt_as(&self, _t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+            write!(
+                f,
+                "TimeSeriesExec: partitions={}",
+                self.output_partitioning().partition_count()
+            )?;
+            if let Some(ordering) = self.output_ordering() {
+                let ordering = ordering
+                    .iter()
+                    .map(|sort| sort.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, ", output_ordering=[{}]", ordering.join(", "))?;
+            }
+            Ok(())
+        }
+    }
+
+    impl ExecutionPlan for TimeSeriesExec {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.input.schema()
+        }
+
+        fn output_partitioning(&self) -> Partitioning {
+            self.input.output_partitioning()
+        }
+
+        fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+            self.input.output_ordering()
+        }
+
+        fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+            vec![]
+        }
+
+        fn with_new_children(
+            self: Arc<Self>,
+            _children: Vec<Arc<dyn ExecutionPlan>>,
+        ) -> Result<Arc<dyn ExecutionPlan>> {
+            Ok(self)
+        }
+
+        fn try_pushdown_sort(
+            &self,
+            ordering: &[PhysicalSortExpr],
+        ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+            let time = PhysicalSortExpr {
+                expr: col("a", &self.schema())?,
+                options: Default::default(),
+            };
+            if ordering.len() != 1 || ordering[0] != time {
+                return Ok(None);
+            }
+            let partitions = self.output_partitioning().partition_count();
+            let mut scan = Self::new(&self.schema(), partitions);
+            scan.input = scan.input.with_sort_information(vec![ordering.to_vec()]);
+            Ok(Some(Arc::new(scan)))
+        }
+
+        fn execute(
+            &self,
+            partition: usize,
+            context: Arc<TaskContext>,
+        ) -> Result<SendableRecordBatchStream> {
+            self.input.execute(partition, context)
+        }
+    }
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]))
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let optimized = ScanSortPushdown::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap();
+        displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn sort_removed_above_ordered_scan() {
+        let schema = schema();
+        let scan = Arc::new(TimeSeriesExec::new(&schema, 1));
+        let predicate = Arc::new(BinaryExpr::new(
+            col("b", &schema).unwrap(),
+            Operator::Gt,
+            lit(1i64),
+        )) as Arc<dyn PhysicalExpr>;
+        let plan = Arc::new(
+            SortExec::new(vec![sort_expr("a", &schema)], filter_exec(predicate, scan))
+                .with_fetch(Some(5)),
+        );
+
+        let expected = [
+            "GlobalLimitExec: skip=0, fetch=5",
+            "  FilterExec: b@1 > 1",
+            "    TimeSeriesExec: partitions=1, output_ordering=[a@0 ASC]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn sort_of_partitions_replaced_with_merge() {
+        let schema = schema();
+        let scan = Arc::new(TimeSeriesExec::new(&schema, 2));
+        let projection = Arc::new(
+            ProjectionExec::try_new(
+                vec![
+                    (col("b", &schema).unwrap(), "b".to_string()),
+                    (col("a", &schema).unwrap(), "time".to_string()),
+                ],
+                scan,
+            )
+            .unwrap(),
+        );
+        let plan = sort_exec(vec![sort_expr("time", &projection.schema())], projection);
+
+        let expected = [
+            "SortPreservingMergeExec: [time@1 ASC]",
+            "  ProjectionExec: expr=[b@1 as b, a@0 as time]",
+            "    TimeSeriesExec: partitions=2, output_ordering=[a@0 ASC]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn unsupported_ordering_unchanged() {
+        let schema = schema();
+        let scan = Arc::new(TimeSeriesExec::new(&schema, 1));
+        let plan = sort_exec(vec![sort_expr("b", &schema)], scan);
+
+        let expected = ["SortExec: expr=[b@1 ASC]", "  TimeSeriesExec: partitions=1"];
+        assert_eq!(optimize(plan), expected);
+    }
+}
@@ -0,0 +1,320 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`SemiJoinDeduplication`] removes the duplicate join keys of the
+//! subquery side of semi joins
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::aggregates::{AggregateExec, AggregateMode, PhysicalGroupBy};
+use crate::physical_plan::joins::HashJoinExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::JoinType;
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::PhysicalExpr;
+
+/// Optimizer rule that aggregates the filtering input of a semi
+/// [`HashJoinExec`], such as the subquery of `x IN (SELECT y ...)` or of a
+/// correlated `EXISTS`, on its join keys, when its statistics estimate
+/// that the keys have few distinct values
+/// (see `datafusion.optimizer.semi_join_deduplication_ratio`):
+///
+/// ```text
+/// HashJoinExec: mode=CollectLeft, join_type=RightSemi, on=[(y@0, x@0)]
+///   ProjectionExec: expr=[y@1 as y]
+///     ...
+///   ...
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// HashJoinExec: mode=CollectLeft, join_type=RightSemi, on=[(y@0, x@0)]
+///   AggregateExec: mode=FinalPartitioned, gby=[y@0 as y], aggr=[]
+///     AggregateExec: mode=Partial, gby=[y@0 as y], aggr=[]
+///       ProjectionExec: expr=[y@1 as y]
+///         ...
+///   ...
+/// ```
+///
+/// Whether a row of the other input is returned only depends on the
+/// existence of a matching key, so the duplicates only make the hash table
+/// larger, or the probing slower.
+///
+/// It runs after `JoinSelection`, which picks the sides of the joins.
+#[derive(Default)]
+pub struct SemiJoinDeduplication {}
+
+impl SemiJoinDeduplication {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for SemiJoinDeduplication {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let ratio = config.optimizer.semi_join_deduplication_ratio;
+        if ratio <= 0.0 {
+            return Ok(plan);
+        }
+        plan.transform_up(&|plan| {
+            let Some(hash_join) = plan.as_any().downcast_ref::<HashJoinExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            if hash_join.filter().is_some() {
+                return Ok(Transformed::No(plan));
+            }
+            let (on_left, on_right): (Vec<_>, Vec<_>) =
+                hash_join.on().iter().cloned().unzip();
+            let (mut left, mut right) =
+                (hash_join.left().clone(), hash_join.right().clone());
+            let (on_left, on_right) = match hash_join.join_type() {
+                JoinType::LeftSemi
+                    if has_few_distinct_keys(&right, &on_right, ratio)? =>
+                {
+                    let on_right = deduplicate(&mut right, &on_right, config)?;
+                    (on_left, on_right)
+                }
+                JoinType::RightSemi if has_few_distinct_keys(&left, &on_left, ratio)? => {
+                    let on_left = deduplicate(&mut left, &on_left, config)?;
+                    (on_left, on_right)
+                }
+                _ => return Ok(Transformed::No(plan)),
+            };
+
+            let mut deduplicated = HashJoinExec::try_new(
+                left,
+                right,
+                on_left.into_iter().zip(on_right).collect(),
+                None,
+                hash_join.join_type(),
+                *hash_join.partition_mode(),
+                hash_join.null_equals_null(),
+            )?
+            .with_partitioned_fallback(hash_join.partitioned_fallback());
+            if let Some(side) = hash_join.broadcast_hint() {
+                deduplicated = deduplicated.with_broadcast_hint(side);
+            }
+            Ok(Transformed::Yes(Arc::new(deduplicated)))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "semi_join_deduplication"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns true if the statistics of `plan` estimate that `keys` have at
+/// most `ratio` times as many distinct values as `plan` has rows
+fn has_few_distinct_keys(
+    plan: &Arc<dyn ExecutionPlan>,
+    keys: &[Column],
+    ratio: f64,
+) -> Result<bool> {
+    // the aggregation can not output a column twice
+    let names = keys.iter().map(|key| key.name()).collect::<HashSet<_>>();
+    if names.len() != keys.len() {
+        return Ok(false);
+    }
+    let statistics = plan.statistics()?;
+    let Some(&num_rows) = statistics.num_rows.get_value() else {
+        return Ok(false);
+    };
+    let mut distinct = 1_usize;
+    for key in keys {
+        let column_statistics = &statistics.column_statistics[key.index()];
+        let Some(&distinct_count) = column_statistics.distinct_count.get_value() else {
+            return Ok(false);
+        };
+        distinct = distinct.saturating_mul(distinct_count);
+    }
+    Ok(distinct.min(num_rows) as f64 <= ratio * num_rows as f64)
+}
+
+/// Replaces `input` with an aggregation of it grouped by `keys`, returning
+/// the columns of the keys in the aggregation
+fn deduplicate(
+    input: &mut Arc<dyn ExecutionPlan>,
+    keys: &[Column],
+    config: &ConfigOptions,
+) -> Result<Vec<Column>> {
+    let group_by = PhysicalGroupBy::new_single(
+        keys.iter()
+            .map(|key| {
+                (
+                    Arc::new(key.clone()) as Arc<dyn PhysicalExpr>,
+                    key.name().to_string(),
+                )
+            })
+            .collect(),
+    );
+    let partial = Arc::new(AggregateExec::try_new(
+        AggregateMode::Partial,
+        group_by,
+        vec![],
+        vec![],
+        input.clone(),
+        input.schema(),
+    )?);
+
+    let final_group_by = PhysicalGroupBy::new_single(
+        partial
+            .output_group_expr()
+            .into_iter()
+            .zip(keys)
+            .map(|(expr, key)| (expr, key.name().to_string()))
+            .collect(),
+    );
+    let mode = if config.execution.target_partitions > 1
+        && config.optimizer.repartition_aggregations
+    {
+        AggregateMode::FinalPartitioned
+    } else {
+        AggregateMode::Final
+    };
+    *input = Arc::new(AggregateExec::try_new(
+        mode,
+        final_group_by,
+        vec![],
+        vec![],
+        partial,
+        input.schema(),
+    )?);
+    Ok(keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| Column::new(key.name(), i))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::joins::PartitionMode;
+    use crate::physical_plan::{displayable, ColumnStatistics, Statistics};
+    use crate::test::StatisticsExec;
+
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::stats::Precision;
+
+    /// A source of `rows` rows with a column `name` of `distinct` values
+    /// and a column `other` of unknown values
+    fn source(name: &str, rows: usize, distinct: usize) -> Arc<dyn ExecutionPlan> {
+        Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Precision::Inexact(rows),
+                total_byte_size: Precision::Absent,
+                column_statistics: vec![
+                    ColumnStatistics::new_unknown(),
+                    ColumnStatistics {
+                        distinct_count: Precision::Inexact(distinct),
+                        ..ColumnStatistics::new_unknown()
+                    },
+                ],
+            },
+            Schema::new(vec![
+                Field::new("other", DataType::Int32, true),
+                Field::new(name, DataType::Int32, true),
+            ]),
+        ))
+    }
+
+    fn semi_join(join_type: JoinType, distinct: usize) -> Arc<dyn ExecutionPlan> {
+        let (left, right) = match join_type {
+            JoinType::RightSemi => (source("y", 1000, distinct), source("x", 10, 10)),
+            _ => (source("x", 10, 10), source("y", 1000, distinct)),
+        };
+        let on = vec![(Column::new("x", 1), Column::new("y", 1))];
+        let on = match join_type {
+            JoinType::RightSemi => on.into_iter().map(|(x, y)| (y, x)).collect(),
+            _ => on,
+        };
+        Arc::new(
+            HashJoinExec::try_new(
+                left,
+                right,
+                on,
+                None,
+                &join_type,
+                PartitionMode::CollectLeft,
+                false,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let mut config = ConfigOptions::new();
+        config.execution.target_partitions = 4;
+        let optimized = SemiJoinDeduplication::new()
+            .optimize(plan, &config)
+            .unwrap();
+        displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn deduplicate_subquery_side() {
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=RightSemi, on=[(y@0, x@1)]",
+            "  AggregateExec: mode=FinalPartitioned, gby=[y@0 as y], aggr=[]",
+            "    AggregateExec: mode=Partial, gby=[y@1 as y], aggr=[]",
+            "      StatisticsExec: col_count=2, row_count=Inexact(1000)",
+            "  StatisticsExec: col_count=2, row_count=Inexact(10)",
+        ];
+        assert_eq!(optimize(semi_join(JoinType::RightSemi, 10)), expected);
+
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=LeftSemi, on=[(x@1, y@0)]",
+            "  StatisticsExec: col_count=2, row_count=Inexact(10)",
+            "  AggregateExec: mode=FinalPartitioned, gby=[y@0 as y], aggr=[]",
+            "    AggregateExec: mode=Partial, gby=[y@1 as y], aggr=[]",
+            "      StatisticsExec: col_count=2, row_count=Inexact(1000)",
+        ];
+        assert_eq!(optimize(semi_join(JoinType::LeftSemi, 10)), expected);
+    }
+
+    #[test]
+    fn keep_subquery_side_with_many_distinct_keys() {
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=RightSemi, on=[(y@1, x@1)]",
+            "  StatisticsExec: col_count=2, row_count=Inexact(1000)",
+            "  StatisticsExec: col_count=2, row_count=Inexact(10)",
+        ];
+        assert_eq!(optimize(semi_join(JoinType::RightSemi, 500)), expected);
+    }
+}
@@ -0,0 +1,240 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ShareScans`] reads the files scanned several times by a plan once
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+#[cfg(feature = "parquet")]
+use crate::datasource::physical_plan::ParquetExec;
+use crate::datasource::physical_plan::{ArrowExec, AvroExec, CsvExec, NdJsonExec};
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::shared_scan::SharedScanExec;
+use crate::physical_plan::{displayable, ExecutionPlan};
+
+use datafusion_common::tree_node::{Transformed, TreeNode, VisitRecursion};
+use log::debug;
+
+/// Optimizer rule that replaces the scans of files appearing several times in
+/// a plan with [`SharedScanExec`]s reading the files once, when
+/// `datafusion.optimizer.enable_shared_scans` is set.
+///
+/// Scans are shared when they read the same files, with the same partitioning,
+/// projection, predicate and limit, such as the scans of both sides of a self
+/// join or of the inputs of a union of filters of a table. For example
+///
+/// ```text
+/// HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, b@1)]
+///   CsvExec: file_groups={1 group: [[t.csv]]}, projection=[a, b], has_header=true
+///   CsvExec: file_groups={1 group: [[t.csv]]}, projection=[a, b], has_header=true
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, b@1)]
+///   SharedScanExec: consumer=0, consumers=2
+///     CsvExec: file_groups={1 group: [[t.csv]]}, projection=[a, b], has_header=true
+///   SharedScanExec: consumer=1, consumers=2
+///     CsvExec: file_groups={1 group: [[t.csv]]}, projection=[a, b], has_header=true
+/// ```
+///
+/// The batches read by a shared scan are buffered in memory until all its
+/// consumers read them.
+#[derive(Default)]
+pub struct ShareScans {}
+
+impl ShareScans {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for ShareScans {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_shared_scans {
+            return Ok(plan);
+        }
+
+        // Count the scans by their description, which includes the files,
+        // projection and filters they read
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        plan.apply(&mut |plan| {
+            if is_file_scan(plan) {
+                *counts.entry(scan_key(plan)).or_default() += 1;
+            }
+            Ok(VisitRecursion::Continue)
+        })?;
+        counts.retain(|_, count| *count > 1);
+        if counts.is_empty() {
+            return Ok(plan);
+        }
+
+        // The consumers left to place for each shared scan, created when its
+        // first occurrence is found
+        let consumers: RefCell<HashMap<String, Vec<SharedScanExec>>> =
+            RefCell::new(HashMap::new());
+        plan.transform_up(&|plan| {
+            if !is_file_scan(&plan) {
+                return Ok(Transformed::No(plan));
+            }
+            let key = scan_key(&plan);
+            let Some(count) = counts.get(&key) else {
+                return Ok(Transformed::No(plan));
+            };
+            let mut consumers = consumers.borrow_mut();
+            let scan_consumers = consumers.entry(key).or_insert_with(|| {
+                debug!(
+                    "Sharing the scan read {count} times: {}",
+                    displayable(plan.as_ref()).one_line()
+                );
+                // pop the consumers in order, from the last one
+                let mut scan_consumers = SharedScanExec::consumers(plan.clone(), *count);
+                scan_consumers.reverse();
+                scan_consumers
+            });
+            match scan_consumers.pop() {
+                Some(consumer) => Ok(Transformed::Yes(Arc::new(consumer))),
+                None => Ok(Transformed::No(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "share_scans"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Whether `plan` reads files
+fn is_file_scan(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    let plan_any = plan.as_any();
+    #[cfg(feature = "parquet")]
+    if plan_any.is::<ParquetExec>() {
+        return true;
+    }
+    plan_any.is::<CsvExec>()
+        || plan_any.is::<NdJsonExec>()
+        || plan_any.is::<AvroExec>()
+        || plan_any.is::<ArrowExec>()
+}
+
+/// The description of the scan `plan`, equal for scans producing the same
+/// output
+fn scan_key(plan: &Arc<dyn ExecutionPlan>) -> String {
+    displayable(plan.as_ref()).one_line().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::file_format::file_compression_type::FileCompressionType;
+    use crate::datasource::listing::PartitionedFile;
+    use crate::datasource::object_store::ObjectStoreUrl;
+    use crate::datasource::physical_plan::FileScanConfig;
+    use crate::physical_plan::union::UnionExec;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_common::Statistics;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]))
+    }
+
+    fn csv_exec(file: &str, projection: Vec<usize>) -> Arc<dyn ExecutionPlan> {
+        let schema = schema();
+        Arc::new(CsvExec::new(
+            FileScanConfig {
+                object_store_url: ObjectStoreUrl::parse("test:///").unwrap(),
+                file_schema: schema.clone(),
+                file_groups: vec![vec![PartitionedFile::new(file.to_string(), 100)]],
+                statistics: Statistics::new_unknown(&schema),
+                projection: Some(projection),
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: vec![],
+            },
+            true,
+            b',',
+            b'"',
+            None,
+            FileCompressionType::UNCOMPRESSED,
+        ))
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Result<Vec<String>> {
+        let mut config = ConfigOptions::new();
+        config.optimizer.enable_shared_scans = true;
+        let optimized = ShareScans::new().optimize(plan, &config)?;
+        Ok(displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    #[test]
+    fn shared_scans() -> Result<()> {
+        let plan = Arc::new(UnionExec::new(vec![
+            csv_exec("t.csv", vec![0, 1]),
+            csv_exec("u.csv", vec![0, 1]),
+            csv_exec("t.csv", vec![0, 1]),
+            csv_exec("t.csv", vec![0]),
+        ]));
+
+        let expected = [
+            "UnionExec",
+            "  SharedScanExec: consumer=0, consumers=2",
+            "    CsvExec: file_groups={1 group: [[t.csv]]}, projection=[a, b], has_header=true",
+            "  CsvExec: file_groups={1 group: [[u.csv]]}, projection=[a, b], has_header=true",
+            "  SharedScanExec: consumer=1, consumers=2",
+            "    CsvExec: file_groups={1 group: [[t.csv]]}, projection=[a, b], has_header=true",
+            "  CsvExec: file_groups={1 group: [[t.csv]]}, projection=[a], has_header=true",
+        ];
+        assert_eq!(optimize(plan)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn disabled() -> Result<()> {
+        let plan: Arc<dyn ExecutionPlan> = Arc::new(UnionExec::new(vec![
+            csv_exec("t.csv", vec![0, 1]),
+            csv_exec("t.csv", vec![0, 1]),
+        ]));
+        let optimized =
+            ShareScans::new().optimize(plan.clone(), &ConfigOptions::new())?;
+        assert!(Arc::ptr_eq(&plan, &optimized));
+        Ok(())
+    }
+}
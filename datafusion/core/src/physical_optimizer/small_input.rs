@@ -0,0 +1,333 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`SmallInputFastPath`] plans aggregations and joins of inputs that fit in
+//! a single batch as single-stage, single-partition operators
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::physical_optimizer::combine_partial_final_agg::can_combine;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::aggregates::{AggregateExec, AggregateMode};
+use crate::physical_plan::joins::{HashJoinExec, PartitionMode};
+use crate::physical_plan::{displayable, ExecutionPlan};
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::JoinType;
+use log::debug;
+
+/// Optimizer rule that removes the repartitioning of the aggregations and
+/// joins whose inputs are proven by their statistics to fit in a single
+/// batch, when `datafusion.optimizer.enable_small_input_fast_path` is set.
+///
+/// An input fits in a single batch when its exact number of rows is at most
+/// `datafusion.execution.batch_size`. For such inputs:
+///
+/// * a `Partial` aggregation followed by a `Final` or `FinalPartitioned`
+///   aggregation becomes a single `Single` aggregation, which reads all the
+///   partitions of its input
+/// * a `Partitioned` hash join of two such inputs becomes a `CollectLeft`
+///   hash join, so neither input is hash repartitioned
+///
+/// For example, the aggregation of 100 rows
+///
+/// ```text
+/// AggregateExec: mode=FinalPartitioned, gby=[a@0 as a], aggr=[COUNT(1)]
+///   AggregateExec: mode=Partial, gby=[a@0 as a], aggr=[COUNT(1)]
+///     MemoryExec: partitions=1, partition_sizes=[1]
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// AggregateExec: mode=Single, gby=[a@0 as a], aggr=[COUNT(1)]
+///   MemoryExec: partitions=1, partition_sizes=[1]
+/// ```
+///
+/// This rule runs before the `EnforceDistribution` rule, which then adds
+/// no repartitioning but the merge of the partitions of the inputs.
+#[derive(Default)]
+pub struct SmallInputFastPath {}
+
+impl SmallInputFastPath {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for SmallInputFastPath {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_small_input_fast_path {
+            return Ok(plan);
+        }
+        let batch_size = config.execution.batch_size;
+        plan.transform_up(&|plan| {
+            let new_plan = if let Some(agg) =
+                plan.as_any().downcast_ref::<AggregateExec>()
+            {
+                single_stage_aggregate(agg, batch_size)?
+            } else if let Some(join) = plan.as_any().downcast_ref::<HashJoinExec>() {
+                collect_left_join(join, batch_size)?
+            } else {
+                None
+            };
+            Ok(match new_plan {
+                Some(new_plan) => {
+                    debug!(
+                        "Planning {} as a single stage over inputs fitting in a single batch",
+                        displayable(plan.as_ref()).one_line()
+                    );
+                    Transformed::Yes(new_plan)
+                }
+                None => Transformed::No(plan),
+            })
+        })
+    }
+
+    fn name(&self) -> &str {
+        "small_input_fast_path"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Whether the statistics of `plan` prove that it produces at most
+/// `batch_size` rows
+fn fits_in_batch(plan: &Arc<dyn ExecutionPlan>, batch_size: usize) -> Result<bool> {
+    let num_rows = plan.statistics()?.num_rows;
+    Ok(num_rows.is_exact().unwrap_or(false)
+        && num_rows.get_value().is_some_and(|rows| *rows <= batch_size))
+}
+
+/// Returns the `Single` aggregation combining the final aggregation `agg`
+/// with the partial aggregation below it, if the input of the partial
+/// aggregation fits in a single batch
+fn single_stage_aggregate(
+    agg: &AggregateExec,
+    batch_size: usize,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if !matches!(
+        agg.mode(),
+        AggregateMode::Final | AggregateMode::FinalPartitioned
+    ) {
+        return Ok(None);
+    }
+    let Some(partial) = agg.input().as_any().downcast_ref::<AggregateExec>() else {
+        return Ok(None);
+    };
+    if partial.mode() != &AggregateMode::Partial
+        || !can_combine(
+            (agg.group_by(), agg.aggr_expr(), agg.filter_expr()),
+            (
+                partial.group_by(),
+                partial.aggr_expr(),
+                partial.filter_expr(),
+            ),
+        )
+        || !fits_in_batch(partial.input(), batch_size)?
+    {
+        return Ok(None);
+    }
+    let single = AggregateExec::try_new(
+        AggregateMode::Single,
+        partial.group_by().clone(),
+        partial.aggr_expr().to_vec(),
+        partial.filter_expr().to_vec(),
+        partial.input().clone(),
+        partial.input_schema(),
+    )?
+    .with_limit(agg.limit());
+    Ok(Some(Arc::new(single)))
+}
+
+/// Returns `join` in `CollectLeft` mode if it is a `Partitioned` join whose
+/// inputs both fit in a single batch
+fn collect_left_join(
+    join: &HashJoinExec,
+    batch_size: usize,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    // A full join needs all the rows of its right input in each partition
+    if join.partition_mode() != &PartitionMode::Partitioned
+        || join.join_type() == &JoinType::Full
+        || !fits_in_batch(join.left(), batch_size)?
+        || !fits_in_batch(join.right(), batch_size)?
+    {
+        return Ok(None);
+    }
+    let new_join = HashJoinExec::try_new(
+        join.left().clone(),
+        join.right().clone(),
+        join.on().to_vec(),
+        join.filter().cloned(),
+        join.join_type(),
+        PartitionMode::CollectLeft,
+        join.null_equals_null(),
+    )?;
+    Ok(Some(Arc::new(new_join)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_optimizer::test_utils::hash_join_exec;
+    use crate::physical_plan::aggregates::PhysicalGroupBy;
+    use crate::physical_plan::expressions::{col, lit, Column, Count};
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::AggregateExpr;
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+
+    /// A single partition of a batch of `rows` rows of column `name`
+    fn memory_exec(name: &str, rows: i64) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new(name, DataType::Int64, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from_iter_values(0..rows))],
+        )
+        .unwrap();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    /// `COUNT(1)` of `input` grouped by `a`, in two stages
+    fn two_stage_aggregate(
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = input.schema();
+        let group_by =
+            PhysicalGroupBy::new_single(vec![(col("a", &schema)?, "a".into())]);
+        let aggr_expr: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Count::new(
+            lit(1i8),
+            "COUNT(1)".to_string(),
+            DataType::Int64,
+        ))];
+        let partial = Arc::new(AggregateExec::try_new(
+            AggregateMode::Partial,
+            group_by,
+            aggr_expr.clone(),
+            vec![None],
+            input,
+            schema.clone(),
+        )?);
+        let final_group_by = PhysicalGroupBy::new_single(
+            partial
+                .output_group_expr()
+                .into_iter()
+                .map(|expr| (expr, "a".into()))
+                .collect(),
+        );
+        Ok(Arc::new(AggregateExec::try_new(
+            AggregateMode::FinalPartitioned,
+            final_group_by,
+            aggr_expr,
+            vec![None],
+            partial,
+            schema,
+        )?))
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>, enabled: bool) -> Result<Vec<String>> {
+        let mut config = ConfigOptions::new();
+        config.execution.batch_size = 100;
+        config.optimizer.enable_small_input_fast_path = enabled;
+        let optimized = SmallInputFastPath::new().optimize(plan, &config)?;
+        Ok(displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    #[test]
+    fn small_aggregate() -> Result<()> {
+        let plan = two_stage_aggregate(memory_exec("a", 100))?;
+
+        let expected = [
+            "AggregateExec: mode=Single, gby=[a@0 as a], aggr=[COUNT(1)]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_eq!(optimize(plan.clone(), true)?, expected);
+
+        let expected = [
+            "AggregateExec: mode=FinalPartitioned, gby=[a@0 as a], aggr=[COUNT(1)]",
+            "  AggregateExec: mode=Partial, gby=[a@0 as a], aggr=[COUNT(1)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_eq!(optimize(plan, false)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn large_aggregate() -> Result<()> {
+        let plan = two_stage_aggregate(memory_exec("a", 101))?;
+
+        let expected = [
+            "AggregateExec: mode=FinalPartitioned, gby=[a@0 as a], aggr=[COUNT(1)]",
+            "  AggregateExec: mode=Partial, gby=[a@0 as a], aggr=[COUNT(1)]",
+            "    MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_eq!(optimize(plan, true)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn small_join() -> Result<()> {
+        let join = |left_rows, right_rows, join_type| {
+            let left = memory_exec("a", left_rows);
+            let right = memory_exec("b", right_rows);
+            let on = vec![(
+                Column::new_with_schema("a", &left.schema())?,
+                Column::new_with_schema("b", &right.schema())?,
+            )];
+            hash_join_exec(left, right, on, None, &join_type)
+        };
+
+        let expected = [
+            "HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(a@0, b@0)]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_eq!(optimize(join(10, 100, JoinType::Inner)?, true)?, expected);
+
+        let expected = [
+            "HashJoinExec: mode=Partitioned, join_type=Inner, on=[(a@0, b@0)]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_eq!(optimize(join(10, 1000, JoinType::Inner)?, true)?, expected);
+
+        let expected = [
+            "HashJoinExec: mode=Partitioned, join_type=Full, on=[(a@0, b@0)]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_eq!(optimize(join(10, 10, JoinType::Full)?, true)?, expected);
+        Ok(())
+    }
+}
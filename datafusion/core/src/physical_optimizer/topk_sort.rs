@@ -0,0 +1,236 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`TopKSort`] replaces full sorts below limits with TopK sorts
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+
+/// Optimizer rule that pushes the number of rows needed by a
+/// [`GlobalLimitExec`] or [`LocalLimitExec`] into the [`SortExec`] below it
+/// (possibly through a [`SortPreservingMergeExec`] or
+/// [`CoalesceBatchesExec`]), so that the sort only keeps the top `skip + fetch`
+/// rows of each partition in a bounded heap instead of sorting all of its
+/// input, e.g.
+///
+/// ```text
+/// GlobalLimitExec: skip=2, fetch=3
+///   SortExec: expr=[a@0 ASC]
+///     ...
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// GlobalLimitExec: skip=2, fetch=3
+///   SortExec: TopK(fetch=5), expr=[a@0 ASC]
+///     ...
+/// ```
+///
+/// The logical `PushDownLimit` rule already does this for plans planned from
+/// SQL; this rule covers physical plans that were built or modified directly.
+#[derive(Default)]
+pub struct TopKSort {}
+
+impl TopKSort {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for TopKSort {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let limit = if let Some(limit) =
+                plan.as_any().downcast_ref::<GlobalLimitExec>()
+            {
+                limit
+                    .fetch()
+                    .map(|fetch| (limit.input(), limit.skip() + fetch))
+            } else if let Some(limit) = plan.as_any().downcast_ref::<LocalLimitExec>() {
+                Some((limit.input(), limit.fetch()))
+            } else {
+                None
+            };
+            let Some((input, fetch)) = limit else {
+                return Ok(Transformed::No(plan));
+            };
+            Ok(match push_fetch(input, fetch) {
+                Some(input) => {
+                    Transformed::Yes(plan.clone().with_new_children(vec![input])?)
+                }
+                None => Transformed::No(plan),
+            })
+        })
+    }
+
+    fn name(&self) -> &str {
+        "topk_sort"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Limits the sort at the root of `plan` to its first `fetch` rows, returning
+/// `None` if there is no such sort, or it already has a smaller limit
+fn push_fetch(
+    plan: &Arc<dyn ExecutionPlan>,
+    fetch: usize,
+) -> Option<Arc<dyn ExecutionPlan>> {
+    if let Some(sort) = plan.as_any().downcast_ref::<SortExec>() {
+        if sort.fetch().map_or(false, |f| f <= fetch) {
+            return None;
+        }
+        let sort = SortExec::new(sort.expr().to_vec(), sort.input().clone())
+            .with_fetch(Some(fetch))
+            .with_preserve_partitioning(sort.preserve_partitioning());
+        Some(Arc::new(sort))
+    } else if let Some(merge) = plan.as_any().downcast_ref::<SortPreservingMergeExec>() {
+        // the first rows of the merge are among the first rows of each of
+        // its sorted input partitions
+        let input = push_fetch(merge.input(), fetch);
+        if input.is_none() && merge.fetch().map_or(false, |f| f <= fetch) {
+            return None;
+        }
+        let input = input.unwrap_or_else(|| merge.input().clone());
+        let fetch = merge.fetch().map_or(fetch, |f| f.min(fetch));
+        let merge = SortPreservingMergeExec::new(merge.expr().to_vec(), input)
+            .with_fetch(Some(fetch));
+        Some(Arc::new(merge))
+    } else if let Some(coalesce) = plan.as_any().downcast_ref::<CoalesceBatchesExec>() {
+        let input = push_fetch(coalesce.input(), fetch)?;
+        Some(Arc::new(CoalesceBatchesExec::new(
+            input,
+            coalesce.target_batch_size(),
+        )))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_optimizer::test_utils::{
+        coalesce_batches_exec, memory_exec, sort_exec, sort_expr,
+    };
+    use crate::physical_plan::displayable;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]))
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let optimized = TopKSort::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap();
+        displayable(optimized.as_ref())
+            .indent(true)
+            .to_string()
+            .trim()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn global_limit_over_sort() {
+        let schema = schema();
+        let sort = sort_exec(vec![sort_expr("a", &schema)], memory_exec(&schema));
+        let plan = Arc::new(GlobalLimitExec::new(sort, 2, Some(3)));
+
+        let expected = [
+            "GlobalLimitExec: skip=2, fetch=3",
+            "  SortExec: TopK(fetch=5), expr=[a@0 ASC]",
+            "    MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn limit_over_merge_of_sorted_partitions() {
+        let schema = schema();
+        let sort = Arc::new(
+            SortExec::new(vec![sort_expr("a", &schema)], memory_exec(&schema))
+                .with_preserve_partitioning(true),
+        );
+        let merge = Arc::new(SortPreservingMergeExec::new(
+            vec![sort_expr("a", &schema)],
+            coalesce_batches_exec(sort),
+        ));
+        let plan = Arc::new(GlobalLimitExec::new(merge, 0, Some(10)));
+
+        let expected = [
+            "GlobalLimitExec: skip=0, fetch=10",
+            "  SortPreservingMergeExec: [a@0 ASC], fetch=10",
+            "    CoalesceBatchesExec: target_batch_size=128",
+            "      SortExec: TopK(fetch=10), expr=[a@0 ASC]",
+            "        MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn smaller_fetch_unchanged() {
+        let schema = schema();
+        let sort = Arc::new(
+            SortExec::new(vec![sort_expr("a", &schema)], memory_exec(&schema))
+                .with_fetch(Some(5)),
+        );
+        let plan = Arc::new(LocalLimitExec::new(sort, 10));
+
+        let expected = [
+            "LocalLimitExec: fetch=10",
+            "  SortExec: TopK(fetch=5), expr=[a@0 ASC]",
+            "    MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn limit_without_fetch_unchanged() {
+        let schema = schema();
+        let sort = sort_exec(vec![sort_expr("a", &schema)], memory_exec(&schema));
+        let plan = Arc::new(GlobalLimitExec::new(sort, 3, None));
+
+        let expected = [
+            "GlobalLimitExec: skip=3, fetch=None",
+            "  SortExec: expr=[a@0 ASC]",
+            "    MemoryExec: partitions=1, partition_sizes=[0]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+}
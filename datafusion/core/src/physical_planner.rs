@@ -17,7 +17,7 @@
 
 //! Planner for [`LogicalPlan`] to [`ExecutionPlan`]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::sync::Arc;
 
@@ -35,8 +35,8 @@ use crate::error::{DataFusionError, Result};
 use crate::execution::context::{ExecutionProps, SessionState};
 use crate::logical_expr::utils::generate_sort_key;
 use crate::logical_expr::{
-    Aggregate, EmptyRelation, Join, Projection, Sort, SubqueryAlias, TableScan, Unnest,
-    Window,
+    Aggregate, EmptyRelation, Join, Projection, RecursiveQuery, Sort, SubqueryAlias,
+    TableScan, Unnest, Window,
 };
 use crate::logical_expr::{
     CrossJoin, Expr, LogicalPlan, Partitioning as LogicalPartitioning, PlanType,
@@ -51,13 +51,16 @@ use crate::physical_plan::empty::EmptyExec;
 use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::expressions::{Column, PhysicalSortExpr};
 use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::gap_fill::GapFillExec;
 use crate::physical_plan::joins::utils as join_utils;
 use crate::physical_plan::joins::{
-    CrossJoinExec, HashJoinExec, NestedLoopJoinExec, PartitionMode, SortMergeJoinExec,
+    AsOfJoinExec, CrossJoinExec, HashJoinExec, NestedLoopJoinExec, PartitionMode,
+    RangeJoinExec, SortMergeJoinExec,
 };
 use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
 use crate::physical_plan::memory::MemoryExec;
 use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::recursive_query::RecursiveQueryExec;
 use crate::physical_plan::repartition::RepartitionExec;
 use crate::physical_plan::sorts::sort::SortExec;
 use crate::physical_plan::union::UnionExec;
@@ -70,13 +73,16 @@ use crate::physical_plan::{
 };
 
 use arrow::compute::SortOptions;
-use arrow::datatypes::{Schema, SchemaRef};
+use arrow::datatypes::{
+    DataType, IntervalDayTimeType, IntervalMonthDayNanoType, Schema, SchemaRef, TimeUnit,
+};
 use arrow_array::builder::StringBuilder;
 use arrow_array::RecordBatch;
 use datafusion_common::display::ToStringifiedPlan;
 use datafusion_common::file_options::FileTypeWriterOptions;
 use datafusion_common::{
-    exec_err, internal_err, not_impl_err, plan_err, DFSchema, FileType, ScalarValue,
+    exec_err, internal_err, not_impl_err, plan_err, DFSchema, FileType, JoinSide,
+    ScalarValue, TableReference,
 };
 use datafusion_expr::dml::{CopyOptions, CopyTo};
 use datafusion_expr::expr::{
@@ -86,11 +92,13 @@ use datafusion_expr::expr::{
 };
 use datafusion_expr::expr_rewriter::unnormalize_cols;
 use datafusion_expr::logical_plan::builder::wrap_projection_for_join_if_necessary;
+use datafusion_expr::utils::relation_name;
 use datafusion_expr::{
-    DescribeTable, DmlStatement, ScalarFunctionDefinition, StringifiedPlan, WindowFrame,
-    WindowFrameBound, WriteOp,
+    AsOfJoin, DescribeTable, DmlStatement, GapFill, ScalarFunctionDefinition,
+    StringifiedPlan, WindowFrame, WindowFrameBound, WriteOp,
 };
 use datafusion_physical_expr::expressions::Literal;
+use datafusion_physical_expr::{physical_exprs_contains, physical_exprs_equal};
 use datafusion_physical_plan::placeholder_row::PlaceholderRowExec;
 use datafusion_sql::utils::window_expr_common_partition_keys;
 
@@ -117,6 +125,29 @@ fn create_function_physical_name(
     Ok(format!("{}({}{})", fun, distinct_str, names.join(",")))
 }
 
+/// Returns the input of a join between `left` and `right` that the
+/// `broadcast_join_hint` option, `hint`, asks to collect, if any
+fn broadcast_hint_side(
+    hint: Option<&str>,
+    left: &LogicalPlan,
+    right: &LogicalPlan,
+) -> Option<JoinSide> {
+    let hint = hint?;
+    let is_hinted = |input: &LogicalPlan| {
+        relation_name(input).map_or(false, |relation| {
+            hint.split(',')
+                .any(|name| TableReference::parse_str(name.trim()).resolved_eq(relation))
+        })
+    };
+    if is_hinted(left) {
+        Some(JoinSide::Left)
+    } else if is_hinted(right) {
+        Some(JoinSide::Right)
+    } else {
+        None
+    }
+}
+
 fn physical_name(e: &Expr) -> Result<String> {
     create_physical_name(e, true)
 }
@@ -703,6 +734,20 @@ impl DefaultPhysicalPlanner {
                         vec![]
                     };
 
+                    // Consecutive windows whose partition keys overlap are
+                    // all partitioned on the shared keys, so that their input
+                    // is only repartitioned once
+                    let (input_exec, physical_partition_keys) =
+                        match share_window_partition_keys(
+                            &input_exec,
+                            &physical_partition_keys,
+                        )? {
+                            Some((input_exec, partition_keys)) => {
+                                (input_exec, partition_keys)
+                            }
+                            None => (input_exec, physical_partition_keys),
+                        };
+
                     let get_sort_keys = |expr: &Expr| match expr {
                         Expr::WindowFunction(WindowFunction{
                             ref partition_by,
@@ -1122,7 +1167,34 @@ impl DefaultPhysicalPlanner {
                     };
 
                     let prefer_hash_join = session_state.config_options().optimizer.prefer_hash_join;
-                    if join_on.is_empty() {
+                    let broadcast_hint = broadcast_hint_side(
+                        session_state.config_options().optimizer.broadcast_join_hint.as_deref(),
+                        left,
+                        right,
+                    );
+                    let range_join_filter = match &join_filter {
+                        Some(filter) if join_on.is_empty()
+                            && session_state.config_options().optimizer.enable_range_join =>
+                        {
+                            RangeJoinExec::is_range_join(
+                                filter,
+                                &physical_left.schema(),
+                                &physical_right.schema(),
+                            )?
+                            .then(|| filter.clone())
+                        }
+                        _ => None,
+                    };
+                    if let Some(filter) = range_join_filter {
+                        // the filter bounds the values of one input by the other,
+                        // use the range join
+                        Ok(Arc::new(RangeJoinExec::try_new(
+                            physical_left,
+                            physical_right,
+                            filter,
+                            join_type,
+                        )?))
+                    } else if join_on.is_empty() {
                         // there is no equal join condition, use the nested loop join
                         // TODO optimize the plan, and use the config of `target_partitions` and `repartition_joins`
                         Ok(Arc::new(NestedLoopJoinExec::try_new(
@@ -1131,6 +1203,18 @@ impl DefaultPhysicalPlanner {
                             join_filter,
                             join_type,
                         )?))
+                    } else if let Some(side) = broadcast_hint {
+                        // the join selection collects the input chosen by a
+                        // `BROADCAST` hint, if the join type allows it
+                        Ok(Arc::new(HashJoinExec::try_new(
+                            physical_left,
+                            physical_right,
+                            join_on,
+                            join_filter,
+                            join_type,
+                            PartitionMode::CollectLeft,
+                            null_equals_null,
+                        )?.with_broadcast_hint(side)))
                     } else if session_state.config().target_partitions() > 1
                         && session_state.config().repartition_joins()
                         && !prefer_hash_join
@@ -1228,6 +1312,16 @@ impl DefaultPhysicalPlanner {
                     let schema = SchemaRef::new(schema.as_ref().to_owned().into());
                     Ok(Arc::new(UnnestExec::new(input, column_exec, schema, options.clone())))
                 }
+                LogicalPlan::RecursiveQuery(RecursiveQuery { name, static_term, recursive_term, is_distinct }) => {
+                    let static_term = self.create_initial_plan(static_term, session_state).await?;
+                    let recursive_term = self.create_initial_plan(recursive_term, session_state).await?;
+                    Ok(Arc::new(RecursiveQueryExec::try_new(
+                        name.clone(),
+                        static_term,
+                        recursive_term,
+                        *is_distinct,
+                    )?))
+                }
                 LogicalPlan::Ddl(ddl) => {
                     // There is no default plan for DDl statements --
                     // it must be handled at a higher level (so that
@@ -1277,7 +1371,21 @@ impl DefaultPhysicalPlanner {
                 LogicalPlan::Extension(e) => {
                     let physical_inputs = self.create_initial_plan_multi(e.node.inputs(), session_state).await?;
 
-                    let mut maybe_plan = None;
+                    let node = e.node.as_any();
+                    let mut maybe_plan = if let Some(gap_fill) = node.downcast_ref::<GapFill>() {
+                        Some(create_gap_fill_physical_plan(
+                            gap_fill,
+                            physical_inputs[0].clone(),
+                        )?)
+                    } else if let Some(join) = node.downcast_ref::<AsOfJoin>() {
+                        Some(create_asof_join_physical_plan(
+                            join,
+                            physical_inputs[0].clone(),
+                            physical_inputs[1].clone(),
+                        )?)
+                    } else {
+                        None
+                    };
                     for planner in &self.extension_planners {
                         if maybe_plan.is_some() {
                             break;
@@ -1572,6 +1680,102 @@ fn get_physical_expr_pair(
     Ok((physical_expr, physical_name))
 }
 
+/// Finds the window below `plan`, looking through projections, whose partition
+/// keys share some of the `partition_keys` of a window on top of `plan`, and
+/// repartitions it, and the windows below it that share the same keys, on
+/// only the shared keys, so that the repartitioned input satisfies all of
+/// them.
+///
+/// Returns the updated `plan` and the shared keys in terms of its output
+/// schema, or `None` if there is no such window.
+fn share_window_partition_keys(
+    plan: &Arc<dyn ExecutionPlan>,
+    partition_keys: &[Arc<dyn PhysicalExpr>],
+) -> Result<Option<(Arc<dyn ExecutionPlan>, Vec<Arc<dyn PhysicalExpr>>)>> {
+    if partition_keys.is_empty() {
+        return Ok(None);
+    }
+
+    if let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() {
+        // only the keys the projection passes through can be shared
+        let input_keys = partition_keys
+            .iter()
+            .filter_map(|key| {
+                let column = key.as_any().downcast_ref::<Column>()?;
+                let (expr, _) = &projection.expr()[column.index()];
+                expr.as_any().is::<Column>().then(|| expr.clone())
+            })
+            .collect::<Vec<_>>();
+        let Some((input, shared_keys)) =
+            share_window_partition_keys(projection.input(), &input_keys)?
+        else {
+            return Ok(None);
+        };
+        let shared_keys = shared_keys
+            .iter()
+            .map(|key| {
+                let (index, (_, name)) = projection
+                    .expr()
+                    .iter()
+                    .enumerate()
+                    .find(|(_, (expr, _))| expr.eq(key))
+                    .ok_or_else(|| {
+                        DataFusionError::Internal(format!(
+                            "Shared partition key {key} is not projected"
+                        ))
+                    })?;
+                Ok(Arc::new(Column::new(name, index)) as Arc<dyn PhysicalExpr>)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(Some((
+            plan.clone().with_new_children(vec![input])?,
+            shared_keys,
+        )));
+    }
+
+    let (window_expr, input, window_keys) =
+        if let Some(window) = plan.as_any().downcast_ref::<BoundedWindowAggExec>() {
+            (window.window_expr(), window.input(), &window.partition_keys)
+        } else if let Some(window) = plan.as_any().downcast_ref::<WindowAggExec>() {
+            (window.window_expr(), window.input(), &window.partition_keys)
+        } else {
+            return Ok(None);
+        };
+    // the window passes its input columns through at the same indices
+    let shared_keys = window_keys
+        .iter()
+        .filter(|key| physical_exprs_contains(partition_keys, key))
+        .cloned()
+        .collect::<Vec<_>>();
+    if shared_keys.is_empty() {
+        return Ok(None);
+    }
+    let (input, shared_keys) = share_window_partition_keys(input, &shared_keys)?
+        .unwrap_or_else(|| (input.clone(), shared_keys));
+    if physical_exprs_equal(&shared_keys, window_keys)
+        && Arc::ptr_eq(&input, &plan.children()[0])
+    {
+        return Ok(Some((plan.clone(), shared_keys)));
+    }
+
+    let window: Arc<dyn ExecutionPlan> =
+        if let Some(window) = plan.as_any().downcast_ref::<BoundedWindowAggExec>() {
+            Arc::new(BoundedWindowAggExec::try_new(
+                window_expr.to_vec(),
+                input,
+                shared_keys.clone(),
+                window.input_order_mode.clone(),
+            )?)
+        } else {
+            Arc::new(WindowAggExec::try_new(
+                window_expr.to_vec(),
+                input,
+                shared_keys.clone(),
+            )?)
+        };
+    Ok(Some((window, shared_keys)))
+}
+
 /// Check if window bounds are valid after schema information is available, and
 /// window_frame bounds are casted to the corresponding column type.
 /// queries like:
@@ -1935,12 +2139,10 @@ impl DefaultPhysicalPlanner {
             let input = self.create_physical_plan(&a.input, session_state).await?;
             let schema = SchemaRef::new((*a.schema).clone().into());
             let show_statistics = session_state.config_options().explain.show_statistics;
-            Ok(Some(Arc::new(AnalyzeExec::new(
-                a.verbose,
-                show_statistics,
-                input,
-                schema,
-            ))))
+            Ok(Some(Arc::new(
+                AnalyzeExec::new(a.verbose, show_statistics, input, schema)
+                    .with_format(a.format),
+            )))
         } else {
             Ok(None)
         }
@@ -1967,14 +2169,26 @@ impl DefaultPhysicalPlanner {
             displayable(plan.as_ref()).indent(true)
         );
 
+        let config = session_state.config_options();
+        let disabled = config
+            .optimizer
+            .disabled_physical_optimizer_rules
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect::<HashSet<_>>();
+
         let mut new_plan = plan;
         for optimizer in optimizers {
+            if disabled.contains(optimizer.name()) {
+                continue;
+            }
             let before_schema = new_plan.schema();
-            new_plan = optimizer
-                .optimize(new_plan, session_state.config_options())
-                .map_err(|e| {
-                    DataFusionError::Context(optimizer.name().to_string(), Box::new(e))
-                })?;
+            new_plan = optimizer.optimize(new_plan, config).map_err(|e| {
+                DataFusionError::Context(optimizer.name().to_string(), Box::new(e))
+            })?;
             if optimizer.schema_check() && new_plan.schema() != before_schema {
                 let e = DataFusionError::Internal(format!(
                     "PhysicalOptimizer rule '{}' failed, due to generate a different schema, original schema: {:?}, new schema: {:?}",
@@ -2040,6 +2254,144 @@ impl DefaultPhysicalPlanner {
     }
 }
 
+/// Create a [`GapFillExec`] for `gap_fill` with the physical plan of its
+/// input
+fn create_gap_fill_physical_plan(
+    gap_fill: &GapFill,
+    input: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+    let schema = gap_fill.input.schema();
+    let column = |expr: &Expr| match expr {
+        Expr::Column(column) => {
+            Ok(Column::new(&column.name, schema.index_of_column(column)?))
+        }
+        _ => internal_err!("GapFill expects columns of its input, got {expr}"),
+    };
+    let stride = match &gap_fill.stride {
+        Expr::Literal(ScalarValue::IntervalMonthDayNano(Some(interval))) => {
+            let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(*interval);
+            if months != 0 {
+                return plan_err!("date_bin_gapfill does not support strides of months");
+            }
+            days as i64 * NANOS_PER_DAY + nanos
+        }
+        Expr::Literal(ScalarValue::IntervalDayTime(Some(interval))) => {
+            let (days, millis) = IntervalDayTimeType::to_parts(*interval);
+            days as i64 * NANOS_PER_DAY + millis as i64 * 1_000_000
+        }
+        other => {
+            return plan_err!(
+                "date_bin_gapfill expects a constant stride of days or smaller units, got {other}"
+            )
+        }
+    };
+
+    Ok(Arc::new(GapFillExec::try_new(
+        input,
+        gap_fill
+            .series_expr
+            .iter()
+            .map(column)
+            .collect::<Result<_>>()?,
+        column(&gap_fill.time_expr)?,
+        stride,
+        gap_fill
+            .fill_expr
+            .iter()
+            .map(|(expr, strategy)| Ok((column(expr)?, *strategy)))
+            .collect::<Result<_>>()?,
+    )?))
+}
+
+fn create_asof_join_physical_plan(
+    join: &AsOfJoin,
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let column = |expr: &Expr, schema: &DFSchema| match expr {
+        Expr::Column(column) => {
+            Ok(Column::new(&column.name, schema.index_of_column(column)?))
+        }
+        _ => internal_err!("AsOfJoin expects columns of its inputs, got {expr}"),
+    };
+    let (left_schema, right_schema) = (join.left.schema(), join.right.schema());
+    let on = join
+        .on
+        .iter()
+        .map(|(l, r)| Ok((column(l, left_schema)?, column(r, right_schema)?)))
+        .collect::<Result<_>>()?;
+    let time = (
+        column(&join.left_time, left_schema)?,
+        column(&join.right_time, right_schema)?,
+    );
+    let tolerance = match &join.tolerance {
+        Some(Expr::Literal(tolerance)) => Some(asof_join_tolerance(
+            tolerance,
+            &time.0.data_type(&left.schema())?,
+        )?),
+        Some(other) => {
+            return plan_err!("AsOfJoin expects a constant tolerance, got {other}")
+        }
+        None => None,
+    };
+
+    Ok(Arc::new(AsOfJoinExec::try_new(
+        left,
+        right,
+        on,
+        time,
+        tolerance,
+        &join.join_type,
+    )?))
+}
+
+/// Returns the as-of join `tolerance` in the unit of time columns of type
+/// `time_type`: an integer for integer columns, and an interval or a
+/// duration for date and timestamp columns
+fn asof_join_tolerance(tolerance: &ScalarValue, time_type: &DataType) -> Result<i64> {
+    const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+    let nanos = match tolerance {
+        ScalarValue::IntervalMonthDayNano(Some(interval)) => {
+            let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(*interval);
+            (months == 0).then_some(days as i64 * NANOS_PER_DAY + nanos)
+        }
+        ScalarValue::IntervalDayTime(Some(interval)) => {
+            let (days, millis) = IntervalDayTimeType::to_parts(*interval);
+            Some(days as i64 * NANOS_PER_DAY + millis as i64 * 1_000_000)
+        }
+        ScalarValue::DurationSecond(Some(v)) => Some(v * 1_000_000_000),
+        ScalarValue::DurationMillisecond(Some(v)) => Some(v * 1_000_000),
+        ScalarValue::DurationMicrosecond(Some(v)) => Some(v * 1_000),
+        ScalarValue::DurationNanosecond(Some(v)) => Some(*v),
+        _ => None,
+    };
+    let nanos_per_unit = match time_type {
+        DataType::Date32 => Some(NANOS_PER_DAY),
+        DataType::Date64 | DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            Some(1_000_000)
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => Some(1_000_000_000),
+        DataType::Timestamp(TimeUnit::Microsecond, _) => Some(1_000),
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => Some(1),
+        _ => None,
+    };
+    match (nanos_per_unit, nanos) {
+        (Some(nanos_per_unit), Some(nanos)) if nanos >= 0 => Ok(nanos / nanos_per_unit),
+        (None, None) if tolerance.data_type().is_integer() && !tolerance.is_null() => {
+            match tolerance.cast_to(&DataType::Int64)? {
+                ScalarValue::Int64(Some(v)) if v >= 0 => Ok(v),
+                _ => plan_err!("AsOfJoin expects a non-negative tolerance, got {tolerance}"),
+            }
+        }
+        _ => plan_err!(
+            "AsOfJoin expects a non-negative tolerance of the unit of the time columns of type {time_type}, got {tolerance}"
+        ),
+    }
+}
+
 fn tuple_err<T, R>(value: (Result<T>, Result<R>)) -> Result<(T, R)> {
     match value {
         (Ok(e), Ok(e1)) => Ok((e, e1)),
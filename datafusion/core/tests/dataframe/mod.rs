@@ -1028,6 +1028,71 @@ async fn unnest_column_nulls() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn unnest_column_ordinality() -> Result<()> {
+    let df = table_with_lists_and_nulls().await?;
+
+    let options = UnnestOptions::new().with_ordinality("pos");
+    let results = df
+        .clone()
+        .unnest_column_with_options("list", options)?
+        .collect()
+        .await?;
+    let expected = [
+        "+------+----+-----+",
+        "| list | id | pos |",
+        "+------+----+-----+",
+        "| 1    | A  | 1   |",
+        "| 2    | A  | 2   |",
+        "|      | B  |     |",
+        "| 3    | D  | 1   |",
+        "+------+----+-----+",
+    ];
+    assert_batches_eq!(expected, &results);
+
+    let options = UnnestOptions::new()
+        .with_preserve_nulls(false)
+        .with_ordinality("pos");
+    let results = df
+        .unnest_column_with_options("list", options)?
+        .collect()
+        .await?;
+    let expected = [
+        "+------+----+-----+",
+        "| list | id | pos |",
+        "+------+----+-----+",
+        "| 1    | A  | 1   |",
+        "| 2    | A  | 2   |",
+        "| 3    | D  | 1   |",
+        "+------+----+-----+",
+    ];
+    assert_batches_eq!(expected, &results);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unnest_struct_column() -> Result<()> {
+    let ctx = SessionContext::new();
+    let df = ctx
+        .sql("SELECT id, struct(id * 10, 'a') AS s FROM (VALUES (1), (2)) AS t(id)")
+        .await?;
+
+    // The fields of the struct become columns
+    let results = df.unnest_column("s")?.collect().await?;
+    let expected = [
+        "+----+----+----+",
+        "| id | c0 | c1 |",
+        "+----+----+----+",
+        "| 1  | 10 | a  |",
+        "| 2  | 20 | a  |",
+        "+----+----+----+",
+    ];
+    assert_batches_sorted_eq!(expected, &results);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn unnest_fixed_list() -> Result<()> {
     let batch = get_fixed_list_batch()?;
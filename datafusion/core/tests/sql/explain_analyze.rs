@@ -17,6 +17,7 @@
 
 use super::*;
 
+use datafusion::common::cast::as_string_array;
 use datafusion::config::ConfigOptions;
 use datafusion::physical_plan::display::DisplayableExecutionPlan;
 use datafusion::physical_plan::metrics::Timestamp;
@@ -362,6 +363,29 @@ async fn csv_explain_verbose() {
     assert_contains!(actual, "SAME TEXT AS ABOVE");
 }
 
+#[tokio::test]
+async fn csv_explain_verbose_optimizer_metrics() {
+    let mut config = ConfigOptions::new();
+    config.explain.show_optimizer_metrics = true;
+    let ctx = SessionContext::new_with_config(config.into());
+    register_aggregate_csv_by_sql(&ctx).await;
+    let sql = "EXPLAIN VERBOSE SELECT c1 FROM aggregate_test_100 where c2 > 10";
+    let actual = execute(&ctx, sql).await;
+
+    let metrics = actual
+        .iter()
+        .find(|r| r[0] == "logical_optimizer_metrics")
+        .expect("optimizer metrics");
+    assert_contains!(&metrics[1], "simplify_expressions: elapsed=");
+    assert_contains!(&metrics[1], "push_down_filter: elapsed=");
+    assert_contains!(&metrics[1], "rewrites=");
+
+    // not shown without VERBOSE
+    let sql = "EXPLAIN SELECT c1 FROM aggregate_test_100 where c2 > 10";
+    let actual = execute(&ctx, sql).await;
+    assert!(actual.iter().all(|r| r[0] != "logical_optimizer_metrics"));
+}
+
 #[tokio::test]
 async fn csv_explain_inlist_verbose() {
     let ctx = SessionContext::new();
@@ -702,10 +726,53 @@ async fn csv_explain_analyze() {
     let needle = "AggregateExec: mode=FinalPartitioned, gby=[c1@0 as c1], aggr=[COUNT(*)], metrics=[output_rows=5";
     assert_contains!(&formatted, needle);
 
+    // summary footer with the per stage breakdown
+    assert_contains!(&formatted, "Summary");
+    assert_contains!(&formatted, "spill_count=0, spilled_bytes=0");
+    assert_contains!(&formatted, "stage 0: elapsed=");
+
     let verbose_needle = "Output Rows";
     assert_not_contains!(formatted, verbose_needle);
 }
 
+#[tokio::test]
+#[cfg_attr(tarpaulin, ignore)]
+async fn csv_explain_analyze_partition_rollup() {
+    let config = SessionConfig::new().with_target_partitions(4);
+    let ctx = SessionContext::new_with_config(config);
+    register_aggregate_csv_by_sql(&ctx).await;
+    let sql = "EXPLAIN ANALYZE SELECT count(*), c1 FROM aggregate_test_100 group by c1";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)
+        .unwrap()
+        .to_string();
+
+    // operators with several partitions summarize their metrics across
+    // partitions, without the per partition detail
+    assert_metrics!(
+        &formatted,
+        "AggregateExec: mode=FinalPartitioned",
+        "partition_metrics=[output_rows{min="
+    );
+    assert_not_contains!(&formatted, "partition=0");
+
+    // the per partition detail is shown by VERBOSE, ordered by partition
+    let sql =
+        "EXPLAIN ANALYZE VERBOSE SELECT count(*), c1 FROM aggregate_test_100 group by c1";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)
+        .unwrap()
+        .to_string();
+    let line = formatted
+        .lines()
+        .find(|line| line.contains("output_rows{partition=3}"))
+        .expect("per partition metrics");
+    let positions = (0..4)
+        .map(|p| line.find(&format!("output_rows{{partition={p}}}")).unwrap())
+        .collect::<Vec<_>>();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]), "{line}");
+}
+
 #[tokio::test]
 #[cfg_attr(tarpaulin, ignore)]
 async fn csv_explain_analyze_order_by() {
@@ -739,6 +806,11 @@ async fn parquet_explain_analyze() {
     // should contain aggregated stats
     assert_contains!(&formatted, "output_rows=8");
     assert_contains!(&formatted, "row_groups_pruned=0");
+    assert_contains!(&formatted, "row_groups_pruned_statistics=0");
+    assert_contains!(&formatted, "row_groups_pruned_bloom_filter=0");
+
+    // the summary should account for the bytes read by the scan
+    assert_not_contains!(&formatted, "bytes_scanned=0,");
 }
 
 #[tokio::test]
@@ -774,6 +846,47 @@ async fn csv_explain_analyze_verbose() {
     assert_contains!(formatted, verbose_needle);
 }
 
+#[tokio::test]
+#[cfg_attr(tarpaulin, ignore)]
+async fn csv_explain_analyze_json() {
+    let ctx = SessionContext::new();
+    register_aggregate_csv_by_sql(&ctx).await;
+    let sql = "EXPLAIN ANALYZE FORMAT JSON SELECT count(*), c1 FROM aggregate_test_100 group by c1";
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].num_rows(), 1);
+    let plan = as_string_array(actual[0].column(1)).unwrap().value(0);
+    let plan: serde_json::Value = serde_json::from_str(plan).unwrap();
+
+    assert_eq!(plan["output_rows"], 5);
+    assert_eq!(plan["summary"]["spill_count"], 0);
+
+    // find the final aggregate in the operator tree
+    let mut operators = vec![&plan["plan"]];
+    let mut aggregate = None;
+    while let Some(operator) = operators.pop() {
+        let description = operator["description"].as_str().unwrap();
+        if description.starts_with("AggregateExec: mode=FinalPartitioned") {
+            aggregate = Some(operator);
+        }
+        operators.extend(operator["children"].as_array().unwrap());
+    }
+    let aggregate = aggregate.expect("final aggregate in plan");
+    assert_eq!(aggregate["operator"], "AggregateExec");
+    assert_eq!(aggregate["output_rows"], 5);
+    assert_eq!(aggregate["metrics"]["output_rows"], 5);
+    assert!(aggregate["elapsed_compute_ns"].as_u64().is_some());
+    assert!(aggregate["peak_mem_used"].as_u64().is_some());
+
+    // JSON output requires ANALYZE
+    let sql = "EXPLAIN FORMAT JSON SELECT c1 FROM aggregate_test_100";
+    let err = ctx.sql(sql).await.unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "EXPLAIN FORMAT JSON is only supported with ANALYZE"
+    );
+}
+
 #[tokio::test]
 async fn explain_logical_plan_only() {
     let mut config = ConfigOptions::new();
@@ -0,0 +1,119 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CancellationToken`] to stop the execution of a query
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use datafusion_common::{DataFusionError, Result};
+
+/// Token shared by the streams executing a query, which check it between
+/// batches and stop with a [`DataFusionError::Cancelled`] error once the
+/// query is cancelled with [`Self::cancel`] or runs longer than its timeout.
+///
+/// Clones of a token share its state, so cancelling any of them cancels the
+/// query.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationState>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    /// Set by [`CancellationToken::cancel`]
+    cancelled: AtomicBool,
+    /// The timeout of the query, and the time at which it expires
+    timeout: Option<(Duration, Instant)>,
+}
+
+impl CancellationToken {
+    /// Creates a new [`CancellationToken`] without timeout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new [`CancellationToken`] which expires `timeout` from now
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            inner: Arc::new(CancellationState {
+                cancelled: AtomicBool::new(false),
+                timeout: Some((timeout, Instant::now() + timeout)),
+            }),
+        }
+    }
+
+    /// Cancels the query
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the query was cancelled or its timeout expired
+    pub fn is_cancelled(&self) -> bool {
+        self.check().is_err()
+    }
+
+    /// Returns a [`DataFusionError::Cancelled`] error if the query was
+    /// cancelled or its timeout expired
+    pub fn check(&self) -> Result<()> {
+        if self.inner.cancelled.load(Ordering::Relaxed) {
+            return Err(DataFusionError::Cancelled(
+                "the query was cancelled".to_string(),
+            ));
+        }
+        match self.inner.timeout {
+            Some((timeout, deadline)) if Instant::now() >= deadline => {
+                Err(DataFusionError::Cancelled(format!(
+                    "the query ran longer than its timeout of {}ms",
+                    timeout.as_millis()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(token.check().is_ok());
+
+        clone.cancel();
+        assert!(token.is_cancelled());
+        let err = token.check().unwrap_err();
+        assert_eq!(err.to_string(), "Query cancelled: the query was cancelled");
+    }
+
+    #[test]
+    fn timeout() {
+        let token = CancellationToken::with_timeout(Duration::from_secs(3600));
+        assert!(!token.is_cancelled());
+
+        let token = CancellationToken::with_timeout(Duration::ZERO);
+        let err = token.check().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Query cancelled: the query ran longer than its timeout of 0ms"
+        );
+    }
+}
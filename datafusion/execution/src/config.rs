@@ -22,6 +22,7 @@ use std::{
     sync::Arc,
 };
 
+use arrow::datatypes::Schema;
 use datafusion_common::{config::ConfigOptions, Result, ScalarValue};
 
 /// Configuration options for Execution context
@@ -254,6 +255,14 @@ impl SessionConfig {
         self.options.execution.batch_size
     }
 
+    /// Get the batch size for batches of `schema`, see
+    /// [`ExecutionOptions::batch_size_for_schema`]
+    ///
+    /// [`ExecutionOptions::batch_size_for_schema`]: datafusion_common::config::ExecutionOptions::batch_size_for_schema
+    pub fn batch_size_for_schema(&self, schema: &Schema) -> usize {
+        self.options.execution.batch_size_for_schema(schema)
+    }
+
     /// Get the currently configured scalar_update_factor for aggregate
     pub fn agg_scalar_update_factor(&self) -> usize {
         self.options.execution.aggregate.scalar_update_factor
@@ -18,6 +18,7 @@
 //! DataFusion execution configuration and runtime structures
 
 pub mod cache;
+pub mod cancellation;
 pub mod config;
 pub mod disk_manager;
 pub mod memory_pool;
@@ -27,6 +28,7 @@ pub mod runtime_env;
 mod stream;
 mod task;
 
+pub use cancellation::CancellationToken;
 pub use disk_manager::DiskManager;
 pub use registry::FunctionRegistry;
 pub use stream::{RecordBatchStream, SendableRecordBatchStream};
@@ -49,6 +49,7 @@ pub use pool::*;
 /// * [`UnboundedMemoryPool`]
 /// * [`GreedyMemoryPool`]
 /// * [`FairSpillPool`]
+/// * [`SpillLargestPool`]
 pub trait MemoryPool: Send + Sync + std::fmt::Debug {
     /// Registers a new [`MemoryConsumer`]
     ///
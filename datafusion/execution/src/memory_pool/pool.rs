@@ -17,8 +17,10 @@
 
 use crate::memory_pool::{MemoryConsumer, MemoryPool, MemoryReservation};
 use datafusion_common::{DataFusionError, Result};
+use hashbrown::HashMap;
 use log::debug;
 use parking_lot::Mutex;
+use std::cmp::Reverse;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// A [`MemoryPool`] that enforces no limit
@@ -231,6 +233,176 @@ impl MemoryPool for FairSpillPool {
     }
 }
 
+/// A [`MemoryPool`] that, when an allocation does not fit, asks the largest
+/// spillable consumers to spill instead of failing the allocating consumer.
+///
+/// Consumers cannot be interrupted to spill, so the allocation is granted
+/// beyond the limit by up to the memory of the consumers asked to spill,
+/// and the next [`MemoryPool::try_grow`] of each of them fails, which makes
+/// it spill. A spillable consumer that is itself the largest consumer is
+/// failed, making it spill, as are allocations that would not fit even once
+/// every other spillable consumer has spilled.
+///
+/// This pool works well for queries with multiple concurrent spillable
+/// operators of very different sizes, such as a sort next to a join,
+/// where [`FairSpillPool`] would make the smaller operators spill early.
+/// Until the consumers asked to spill make their next allocation, the
+/// memory used can exceed the limit by up to their size.
+#[derive(Debug)]
+pub struct SpillLargestPool {
+    /// The total memory limit
+    pool_size: usize,
+
+    state: Mutex<SpillLargestPoolState>,
+}
+
+#[derive(Debug, Default)]
+struct SpillLargestPoolState {
+    /// The total amount of memory reserved
+    used: usize,
+
+    /// The spillable consumers that reserved memory, by [`consumer_key`]
+    spillable: HashMap<usize, SpillableConsumer>,
+}
+
+#[derive(Debug, Default)]
+struct SpillableConsumer {
+    /// The amount of memory reserved by the consumer
+    size: usize,
+
+    /// Whether the consumer was asked to spill
+    spill_requested: bool,
+}
+
+impl SpillLargestPoolState {
+    /// The amount of memory to be freed by the consumers asked to spill
+    fn requested_spill(&self) -> usize {
+        self.spillable
+            .values()
+            .filter(|consumer| consumer.spill_requested)
+            .map(|consumer| consumer.size)
+            .sum()
+    }
+}
+
+/// Identifies `consumer` for as long as it is registered, as it does not move
+/// out of the registration shared by its reservations
+fn consumer_key(consumer: &MemoryConsumer) -> usize {
+    consumer as *const MemoryConsumer as usize
+}
+
+impl SpillLargestPool {
+    /// Allocate up to `limit` bytes
+    pub fn new(pool_size: usize) -> Self {
+        debug!("Created new SpillLargestPool(pool_size={pool_size})");
+        Self {
+            pool_size,
+            state: Mutex::new(SpillLargestPoolState::default()),
+        }
+    }
+}
+
+impl MemoryPool for SpillLargestPool {
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        if consumer.can_spill {
+            self.state.lock().spillable.remove(&consumer_key(consumer));
+        }
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        let mut state = self.state.lock();
+        state.used += additional;
+        let consumer = reservation.consumer();
+        if consumer.can_spill {
+            state
+                .spillable
+                .entry(consumer_key(consumer))
+                .or_default()
+                .size += additional;
+        }
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        let mut state = self.state.lock();
+        state.used -= shrink;
+        let consumer = reservation.consumer();
+        if let Some(spillable) = state.spillable.get_mut(&consumer_key(consumer)) {
+            spillable.size -= shrink;
+            // Freeing memory is how a consumer answers a request to spill
+            spillable.spill_requested = false;
+        }
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        let mut state = self.state.lock();
+        let consumer = reservation.consumer();
+        let key = consumer_key(consumer);
+
+        if consumer.can_spill
+            && state
+                .spillable
+                .get(&key)
+                .map_or(false, |spillable| spillable.spill_requested)
+        {
+            return Err(insufficient_capacity_err(reservation, additional, 0));
+        }
+
+        // The memory of the consumers asked to spill is about to be freed
+        let mut available =
+            (self.pool_size + state.requested_spill()).saturating_sub(state.used);
+
+        if available < additional {
+            let mut candidates = state
+                .spillable
+                .iter()
+                .filter(|(_, spillable)| !spillable.spill_requested && spillable.size > 0)
+                .map(|(key, spillable)| (*key, spillable.size))
+                .collect::<Vec<_>>();
+            candidates.sort_by_key(|(_, size)| Reverse(*size));
+
+            let mut victims = vec![];
+            for (candidate, size) in candidates {
+                // The allocating consumer is the largest, it should spill itself
+                if candidate == key {
+                    break;
+                }
+                victims.push(candidate);
+                available += size;
+                if available >= additional {
+                    break;
+                }
+            }
+
+            if available < additional {
+                return Err(insufficient_capacity_err(
+                    reservation,
+                    additional,
+                    self.pool_size.saturating_sub(state.used),
+                ));
+            }
+            for victim in victims {
+                debug!(
+                    "SpillLargestPool requested a spill to grow {}",
+                    consumer.name
+                );
+                if let Some(spillable) = state.spillable.get_mut(&victim) {
+                    spillable.spill_requested = true;
+                }
+            }
+        }
+
+        state.used += additional;
+        if consumer.can_spill {
+            state.spillable.entry(key).or_default().size += additional;
+        }
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.state.lock().used
+    }
+}
+
 fn insufficient_capacity_err(
     reservation: &MemoryReservation,
     additional: usize,
@@ -310,4 +482,58 @@ mod tests {
         let err = r4.try_grow(30).unwrap_err().strip_backtrace();
         assert_eq!(err, "Resources exhausted: Failed to allocate additional 30 bytes for s4 with 0 bytes already allocated - maximum available is 20");
     }
+
+    #[test]
+    fn test_spill_largest() {
+        let pool = Arc::new(SpillLargestPool::new(100)) as _;
+
+        let mut r1 = MemoryConsumer::new("r1")
+            .with_can_spill(true)
+            .register(&pool);
+        r1.try_grow(60).unwrap();
+
+        let mut r2 = MemoryConsumer::new("r2")
+            .with_can_spill(true)
+            .register(&pool);
+        r2.try_grow(30).unwrap();
+        assert_eq!(pool.reserved(), 90);
+
+        // r1 is asked to spill rather than failing r3
+        let mut r3 = MemoryConsumer::new("r3").register(&pool);
+        r3.try_grow(40).unwrap();
+        assert_eq!(pool.reserved(), 130);
+
+        let err = r1.try_grow(1).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 1 bytes for r1 with 60 bytes already allocated - maximum available is 0");
+
+        // r1 spills
+        r1.free();
+        assert_eq!(pool.reserved(), 70);
+        r1.try_grow(10).unwrap();
+        assert_eq!(pool.reserved(), 80);
+
+        // r2 is the largest spillable consumer, so it spills itself
+        let err = r2.try_grow(30).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 30 bytes for r2 with 30 bytes already allocated - maximum available is 20");
+
+        // Spilling every spillable consumer would not be enough
+        let mut r4 = MemoryConsumer::new("r4").register(&pool);
+        let err = r4.try_grow(100).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 100 bytes for r4 with 0 bytes already allocated - maximum available is 20");
+
+        // The failed allocation did not ask anyone to spill
+        r1.try_grow(5).unwrap();
+        r2.try_grow(5).unwrap();
+        assert_eq!(pool.reserved(), 90);
+
+        // r2 alone is asked to spill
+        r4.try_grow(40).unwrap();
+        assert_eq!(pool.reserved(), 130);
+        r1.try_grow(1).unwrap();
+        r2.try_grow(1).unwrap_err();
+
+        // Dropping r2 frees its memory
+        drop(r2);
+        assert_eq!(pool.reserved(), 96);
+    }
 }
@@ -18,6 +18,7 @@
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::Duration,
 };
 
 use datafusion_common::{
@@ -27,6 +28,7 @@ use datafusion_common::{
 use datafusion_expr::{AggregateUDF, ScalarUDF, WindowUDF};
 
 use crate::{
+    cancellation::CancellationToken,
     config::SessionConfig,
     memory_pool::MemoryPool,
     registry::FunctionRegistry,
@@ -56,6 +58,8 @@ pub struct TaskContext {
     window_functions: HashMap<String, Arc<WindowUDF>>,
     /// Runtime environment associated with this task context
     runtime: Arc<RuntimeEnv>,
+    /// Token checked by the streams of the query to stop its execution
+    cancellation: CancellationToken,
 }
 
 impl Default for TaskContext {
@@ -72,6 +76,7 @@ impl Default for TaskContext {
             aggregate_functions: HashMap::new(),
             window_functions: HashMap::new(),
             runtime: Arc::new(runtime),
+            cancellation: CancellationToken::new(),
         }
     }
 }
@@ -91,6 +96,7 @@ impl TaskContext {
         window_functions: HashMap<String, Arc<WindowUDF>>,
         runtime: Arc<RuntimeEnv>,
     ) -> Self {
+        let cancellation = cancellation_token_for(&session_config);
         Self {
             task_id,
             session_id,
@@ -99,6 +105,7 @@ impl TaskContext {
             aggregate_functions,
             window_functions,
             runtime,
+            cancellation,
         }
     }
 
@@ -160,12 +167,27 @@ impl TaskContext {
         self.runtime.clone()
     }
 
+    /// Return the [`CancellationToken`] of the query run with this [TaskContext]
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
     /// Update the [`ConfigOptions`]
+    ///
+    /// This also replaces the [`CancellationToken`] by a new one, expiring
+    /// after the `query_timeout` of `session_config` if set
     pub fn with_session_config(mut self, session_config: SessionConfig) -> Self {
+        self.cancellation = cancellation_token_for(&session_config);
         self.session_config = session_config;
         self
     }
 
+    /// Update the [`CancellationToken`]
+    pub fn with_cancellation_token(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
     /// Update the [`RuntimeEnv`]
     pub fn with_runtime(mut self, runtime: Arc<RuntimeEnv>) -> Self {
         self.runtime = runtime;
@@ -173,6 +195,17 @@ impl TaskContext {
     }
 }
 
+/// Returns a new [`CancellationToken`] expiring after the `query_timeout` of
+/// `session_config`, if set
+fn cancellation_token_for(session_config: &SessionConfig) -> CancellationToken {
+    match session_config.options().execution.query_timeout {
+        Some(timeout) => {
+            CancellationToken::with_timeout(Duration::from_millis(timeout as u64))
+        }
+        None => CancellationToken::new(),
+    }
+}
+
 impl FunctionRegistry for TaskContext {
     fn udfs(&self) -> HashSet<String> {
         self.scalar_functions.keys().cloned().collect()
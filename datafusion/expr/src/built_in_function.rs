@@ -219,6 +219,16 @@ pub enum BuiltinScalarFunction {
     DateTrunc,
     /// date_bin
     DateBin,
+    /// date_bin_gapfill
+    DateBinGapfill,
+    /// locf
+    Locf,
+    /// interpolate
+    Interpolate,
+    /// last_day
+    LastDay,
+    /// next_day
+    NextDay,
     /// initcap
     InitCap,
     /// left
@@ -259,6 +269,10 @@ pub enum BuiltinScalarFunction {
     SHA384,
     /// Sha512
     SHA512,
+    /// spark_murmur3_hash
+    SparkMurmur3Hash,
+    /// hive_hash
+    HiveHash,
     /// split_part
     SplitPart,
     /// string_to_array
@@ -443,6 +457,11 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::DatePart => Volatility::Immutable,
             BuiltinScalarFunction::DateTrunc => Volatility::Immutable,
             BuiltinScalarFunction::DateBin => Volatility::Immutable,
+            BuiltinScalarFunction::DateBinGapfill => Volatility::Immutable,
+            BuiltinScalarFunction::Locf => Volatility::Immutable,
+            BuiltinScalarFunction::Interpolate => Volatility::Immutable,
+            BuiltinScalarFunction::LastDay => Volatility::Immutable,
+            BuiltinScalarFunction::NextDay => Volatility::Immutable,
             BuiltinScalarFunction::InitCap => Volatility::Immutable,
             BuiltinScalarFunction::Left => Volatility::Immutable,
             BuiltinScalarFunction::Lpad => Volatility::Immutable,
@@ -463,6 +482,8 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::SHA256 => Volatility::Immutable,
             BuiltinScalarFunction::SHA384 => Volatility::Immutable,
             BuiltinScalarFunction::SHA512 => Volatility::Immutable,
+            BuiltinScalarFunction::SparkMurmur3Hash => Volatility::Immutable,
+            BuiltinScalarFunction::HiveHash => Volatility::Immutable,
             BuiltinScalarFunction::Digest => Volatility::Immutable,
             BuiltinScalarFunction::SplitPart => Volatility::Immutable,
             BuiltinScalarFunction::StringToArray => Volatility::Immutable,
@@ -681,7 +702,10 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::Concat => Ok(Utf8),
             BuiltinScalarFunction::ConcatWithSeparator => Ok(Utf8),
             BuiltinScalarFunction::DatePart => Ok(Float64),
-            BuiltinScalarFunction::DateBin | BuiltinScalarFunction::DateTrunc => {
+            BuiltinScalarFunction::LastDay | BuiltinScalarFunction::NextDay => Ok(Date32),
+            BuiltinScalarFunction::DateBin
+            | BuiltinScalarFunction::DateBinGapfill
+            | BuiltinScalarFunction::DateTrunc => {
                 match &input_expr_types[1] {
                     Timestamp(Nanosecond, None) | Utf8 | Null => {
                         Ok(Timestamp(Nanosecond, None))
@@ -701,6 +725,9 @@ impl BuiltinScalarFunction {
                 ),
                 }
             }
+            BuiltinScalarFunction::Locf | BuiltinScalarFunction::Interpolate => {
+                Ok(input_expr_types[0].clone())
+            }
             BuiltinScalarFunction::InitCap => {
                 utf8_to_str_type(&input_expr_types[0], "initcap")
             }
@@ -758,6 +785,9 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::Digest => {
                 utf8_or_binary_to_binary_type(&input_expr_types[0], "digest")
             }
+            BuiltinScalarFunction::SparkMurmur3Hash | BuiltinScalarFunction::HiveHash => {
+                Ok(Int32)
+            }
             BuiltinScalarFunction::Encode => Ok(match input_expr_types[0] {
                 Utf8 => Utf8,
                 LargeUtf8 => LargeUtf8,
@@ -989,6 +1019,12 @@ impl BuiltinScalarFunction {
                 self.volatility(),
             ),
             BuiltinScalarFunction::Struct => Signature::variadic_any(self.volatility()),
+            BuiltinScalarFunction::Locf | BuiltinScalarFunction::Interpolate => {
+                Signature::any(1, self.volatility())
+            }
+            BuiltinScalarFunction::SparkMurmur3Hash | BuiltinScalarFunction::HiveHash => {
+                Signature::variadic_any(self.volatility())
+            }
             BuiltinScalarFunction::Concat
             | BuiltinScalarFunction::ConcatWithSeparator => {
                 Signature::variadic(vec![Utf8], self.volatility())
@@ -1161,7 +1197,7 @@ impl BuiltinScalarFunction {
                 ],
                 self.volatility(),
             ),
-            BuiltinScalarFunction::DateBin => {
+            BuiltinScalarFunction::DateBin | BuiltinScalarFunction::DateBinGapfill => {
                 let base_sig = |array_type: TimeUnit| {
                     vec![
                         Exact(vec![
@@ -1211,6 +1247,12 @@ impl BuiltinScalarFunction {
 
                 Signature::one_of(full_sig, self.volatility())
             }
+            BuiltinScalarFunction::LastDay => {
+                Signature::uniform(1, vec![Date32], self.volatility())
+            }
+            BuiltinScalarFunction::NextDay => {
+                Signature::exact(vec![Date32, Utf8], self.volatility())
+            }
             BuiltinScalarFunction::DatePart => Signature::one_of(
                 vec![
                     Exact(vec![Utf8, Timestamp(Nanosecond, None)]),
@@ -1553,8 +1595,13 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::CurrentDate => &["current_date", "today"],
             BuiltinScalarFunction::CurrentTime => &["current_time"],
             BuiltinScalarFunction::DateBin => &["date_bin"],
+            BuiltinScalarFunction::DateBinGapfill => &["date_bin_gapfill"],
+            BuiltinScalarFunction::Locf => &["locf"],
+            BuiltinScalarFunction::Interpolate => &["interpolate"],
             BuiltinScalarFunction::DateTrunc => &["date_trunc", "datetrunc"],
             BuiltinScalarFunction::DatePart => &["date_part", "datepart"],
+            BuiltinScalarFunction::LastDay => &["last_day"],
+            BuiltinScalarFunction::NextDay => &["next_day"],
             BuiltinScalarFunction::ToTimestamp => &["to_timestamp"],
             BuiltinScalarFunction::ToTimestampMillis => &["to_timestamp_millis"],
             BuiltinScalarFunction::ToTimestampMicros => &["to_timestamp_micros"],
@@ -1569,6 +1616,8 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::SHA256 => &["sha256"],
             BuiltinScalarFunction::SHA384 => &["sha384"],
             BuiltinScalarFunction::SHA512 => &["sha512"],
+            BuiltinScalarFunction::SparkMurmur3Hash => &["spark_murmur3_hash"],
+            BuiltinScalarFunction::HiveHash => &["hive_hash"],
 
             // encode/decode
             BuiltinScalarFunction::Encode => &["encode"],
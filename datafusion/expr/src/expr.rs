@@ -30,6 +30,9 @@ use arrow::datatypes::DataType;
 use datafusion_common::tree_node::{Transformed, TreeNode};
 use datafusion_common::{internal_err, DFSchema, OwnedTableReference};
 use datafusion_common::{plan_err, Column, DataFusionError, Result, ScalarValue};
+use sqlparser::ast::{
+    ExceptSelectItem, ExcludeSelectItem, RenameSelectItem, ReplaceSelectElement,
+};
 use std::collections::HashSet;
 use std::fmt;
 use std::fmt::{Display, Formatter, Write};
@@ -169,8 +172,11 @@ pub enum Expr {
     /// with an optional (schema) qualifier.
     ///
     /// This expr has to be resolved to a list of columns before translating logical
-    /// plan into physical plan.
-    Wildcard { qualifier: Option<String> },
+    /// plan into physical plan, applying its [`WildcardOptions`].
+    Wildcard {
+        qualifier: Option<String>,
+        options: WildcardOptions,
+    },
     /// List of grouping set expressions. Only valid in the context of an aggregate
     /// GROUP BY expression list
     GroupingSet(GroupingSet),
@@ -808,6 +814,94 @@ impl Placeholder {
     }
 }
 
+/// Additional options for wildcards, e.g. Snowflake `EXCLUDE`/`RENAME` and
+/// Bigquery `EXCEPT`/`REPLACE`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct WildcardOptions {
+    /// `[EXCLUDE ...]`, the columns left out of the expansion
+    pub exclude: Option<ExcludeSelectItem>,
+    /// `[EXCEPT ...]`, the columns left out of the expansion
+    pub except: Option<ExceptSelectItem>,
+    /// `[REPLACE ...]`, the columns replaced by an expression of the same name
+    pub replace: Option<PlannedReplaceSelectItem>,
+    /// `[RENAME ...]`, the columns given a new name
+    pub rename: Option<RenameSelectItem>,
+}
+
+impl WildcardOptions {
+    /// Set the `EXCLUDE` option
+    pub fn with_exclude(mut self, exclude: ExcludeSelectItem) -> Self {
+        self.exclude = Some(exclude);
+        self
+    }
+
+    /// Set the `EXCEPT` option
+    pub fn with_except(mut self, except: ExceptSelectItem) -> Self {
+        self.except = Some(except);
+        self
+    }
+
+    /// Set the `REPLACE` option
+    pub fn with_replace(mut self, replace: PlannedReplaceSelectItem) -> Self {
+        self.replace = Some(replace);
+        self
+    }
+
+    /// Set the `RENAME` option
+    pub fn with_rename(mut self, rename: RenameSelectItem) -> Self {
+        self.rename = Some(rename);
+        self
+    }
+}
+
+impl Display for WildcardOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(exclude) = &self.exclude {
+            write!(f, " {exclude}")?;
+        }
+        if let Some(except) = &self.except {
+            write!(f, " {except}")?;
+        }
+        if let Some(rename) = &self.rename {
+            write!(f, " {rename}")?;
+        }
+        if let Some(replace) = &self.replace {
+            write!(f, " {replace}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `REPLACE` wildcard option, with its expressions planned
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PlannedReplaceSelectItem {
+    /// The SQL items of the option
+    pub items: Vec<ReplaceSelectElement>,
+    /// The planned expressions of the items, in the same order
+    pub planned_expressions: Vec<Expr>,
+}
+
+impl PlannedReplaceSelectItem {
+    /// Returns the planned expression replacing the column `name`, if any
+    pub fn replacement(&self, name: &str) -> Option<&Expr> {
+        self.items
+            .iter()
+            .position(|item| item.column_name.value == name)
+            .map(|index| &self.planned_expressions[index])
+    }
+}
+
+impl Display for PlannedReplaceSelectItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let items = self
+            .items
+            .iter()
+            .map(|item| item.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "REPLACE ({})", items.join(", "))
+    }
+}
+
 /// Grouping sets
 /// See <https://www.postgresql.org/docs/current/queries-table-expressions.html#QUERIES-GROUPING-SETS>
 /// for Postgres definition.
@@ -1473,9 +1567,9 @@ impl fmt::Display for Expr {
                     write!(f, "{expr} IN ([{}])", expr_vec_fmt!(list))
                 }
             }
-            Expr::Wildcard { qualifier } => match qualifier {
-                Some(qualifier) => write!(f, "{qualifier}.*"),
-                None => write!(f, "*"),
+            Expr::Wildcard { qualifier, options } => match qualifier {
+                Some(qualifier) => write!(f, "{qualifier}.*{options}"),
+                None => write!(f, "*{options}"),
             },
             Expr::GetIndexedField(GetIndexedField { field, expr }) => match field {
                 GetFieldAccess::NamedStructField { name } => {
@@ -1791,11 +1885,11 @@ fn create_name(e: &Expr) -> Result<String> {
         Expr::Sort { .. } => {
             internal_err!("Create name does not support sort expression")
         }
-        Expr::Wildcard { qualifier } => match qualifier {
+        Expr::Wildcard { qualifier, options } => match qualifier {
             Some(qualifier) => internal_err!(
                 "Create name does not support qualified wildcard, got {qualifier}"
             ),
-            None => Ok("*".to_string()),
+            None => Ok(format!("*{options}")),
         },
         Expr::Placeholder(Placeholder { id, .. }) => Ok((*id).to_string()),
     }
@@ -19,7 +19,7 @@
 
 use crate::expr::{
     AggregateFunction, BinaryExpr, Cast, Exists, GroupingSet, InList, InSubquery,
-    Placeholder, ScalarFunction, TryCast,
+    Placeholder, ScalarFunction, TryCast, WildcardOptions,
 };
 use crate::function::PartitionEvaluatorFactory;
 use crate::{
@@ -111,7 +111,29 @@ pub fn placeholder(id: impl Into<String>) -> Expr {
 /// assert_eq!(p.to_string(), "*")
 /// ```
 pub fn wildcard() -> Expr {
-    Expr::Wildcard { qualifier: None }
+    Expr::Wildcard {
+        qualifier: None,
+        options: WildcardOptions::default(),
+    }
+}
+
+/// Create an '*' [`Expr::Wildcard`] expression with the wildcard options
+///
+/// # Example
+///
+/// ```rust
+/// # use datafusion_expr::{wildcard_with_options, WildcardOptions};
+/// # use sqlparser::ast::{ExcludeSelectItem, Ident};
+/// let p = wildcard_with_options(
+///     WildcardOptions::default().with_exclude(ExcludeSelectItem::Single(Ident::new("a"))),
+/// );
+/// assert_eq!(p.to_string(), "* EXCLUDE a")
+/// ```
+pub fn wildcard_with_options(options: WildcardOptions) -> Expr {
+    Expr::Wildcard {
+        qualifier: None,
+        options,
+    }
 }
 
 /// Return a new expression `left <op> right`
@@ -819,6 +841,16 @@ scalar_expr!(SHA224, sha224, string, "SHA-224 hash");
 scalar_expr!(SHA256, sha256, string, "SHA-256 hash");
 scalar_expr!(SHA384, sha384, string, "SHA-384 hash");
 scalar_expr!(SHA512, sha512, string, "SHA-512 hash");
+nary_scalar_expr!(
+    SparkMurmur3Hash,
+    spark_murmur3_hash,
+    "hashes the arguments with the 32 bit Murmur3 hash of Spark's `hash` function"
+);
+nary_scalar_expr!(
+    HiveHash,
+    hive_hash,
+    "hashes the arguments like Hive's `hash` function"
+);
 scalar_expr!(SplitPart, split_part, string delimiter index, "splits a string based on a delimiter and picks out the desired field based on the index.");
 scalar_expr!(StringToArray, string_to_array, string delimiter null_string, "splits a `string` based on a `delimiter` and returns an array of parts. Any parts matching the optional `null_string` will be replaced with `NULL`");
 scalar_expr!(StartsWith, starts_with, string prefix, "whether the `string` starts with the `prefix`");
@@ -877,6 +909,30 @@ nary_scalar_expr!(
 scalar_expr!(DatePart, date_part, part date, "extracts a subfield from the date");
 scalar_expr!(DateTrunc, date_trunc, part date, "truncates the date to a specified level of precision");
 scalar_expr!(DateBin, date_bin, stride source origin, "coerces an arbitrary timestamp to the start of the nearest specified interval");
+nary_scalar_expr!(
+    DateBinGapfill,
+    date_bin_gapfill,
+    "like `date_bin`, but also emits the missing intervals when used to group an aggregation"
+);
+scalar_expr!(
+    Locf,
+    locf,
+    expr,
+    "fills the value of an aggregate for intervals emitted by `date_bin_gapfill` with the previous value"
+);
+scalar_expr!(
+    Interpolate,
+    interpolate,
+    expr,
+    "fills the value of an aggregate for intervals emitted by `date_bin_gapfill` by linear interpolation"
+);
+scalar_expr!(
+    LastDay,
+    last_day,
+    date,
+    "returns the last day of the month that contains the date"
+);
+scalar_expr!(NextDay, next_day, date day_of_week, "returns the first date later than the date that falls on the given day of the week");
 scalar_expr!(
     ToTimestampMillis,
     to_timestamp_millis,
@@ -1311,6 +1367,8 @@ mod test {
         test_scalar_expr!(SHA256, sha256, string);
         test_scalar_expr!(SHA384, sha384, string);
         test_scalar_expr!(SHA512, sha512, string);
+        test_nary_scalar_expr!(SparkMurmur3Hash, spark_murmur3_hash, arg1, arg2);
+        test_nary_scalar_expr!(HiveHash, hive_hash, arg1, arg2);
         test_scalar_expr!(SplitPart, split_part, expr, delimiter, index);
         test_scalar_expr!(StringToArray, string_to_array, expr, delimiter, null_value);
         test_scalar_expr!(StartsWith, starts_with, string, characters);
@@ -1325,6 +1383,11 @@ mod test {
         test_scalar_expr!(DatePart, date_part, part, date);
         test_scalar_expr!(DateTrunc, date_trunc, part, date);
         test_scalar_expr!(DateBin, date_bin, stride, source, origin);
+        test_nary_scalar_expr!(DateBinGapfill, date_bin_gapfill, stride, source);
+        test_scalar_expr!(Locf, locf, expr);
+        test_scalar_expr!(Interpolate, interpolate, expr);
+        test_scalar_expr!(LastDay, last_day, date);
+        test_scalar_expr!(NextDay, next_day, date, day_of_week);
         test_scalar_expr!(FromUnixtime, from_unixtime, unixtime);
 
         test_scalar_expr!(ArrayAppend, array_append, array, element);
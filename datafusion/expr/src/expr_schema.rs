@@ -162,7 +162,7 @@ impl ExprSchemable for Expr {
                     plan_datafusion_err!("Placeholder type could not be resolved")
                 })
             }
-            Expr::Wildcard { qualifier } => {
+            Expr::Wildcard { qualifier, .. } => {
                 // Wildcard do not really have a type and do not appear in projections
                 match qualifier {
                     Some(_) => internal_err!("QualifiedWildcard expressions are not valid in a logical query plan"),
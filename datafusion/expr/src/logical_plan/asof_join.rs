@@ -0,0 +1,172 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`AsOfJoin`] plan joining each row of its left input with the latest
+//! earlier row of its right input
+
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion_common::{plan_err, DFSchemaRef, DataFusionError, Result};
+
+use crate::logical_plan::builder::build_join_schema;
+use crate::{Expr, ExprSchemable, JoinType, LogicalPlan, UserDefinedLogicalNodeCore};
+
+/// Joins each row of the left input with the row of the right input with
+/// the same `on` columns whose time is the closest to, and not later than,
+/// the time of the left row.
+///
+/// When `tolerance` is set, rows of the right input earlier than the time
+/// of the left row by more than `tolerance` are not joined. Left rows
+/// without a matching right row are dropped by [`JoinType::Inner`] joins,
+/// and joined with nulls by [`JoinType::Left`] joins.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AsOfJoin {
+    /// The left input
+    pub left: Arc<LogicalPlan>,
+    /// The right input
+    pub right: Arc<LogicalPlan>,
+    /// The pairs of columns of the left and right inputs that must be equal
+    pub on: Vec<(Expr, Expr)>,
+    /// The time column of the left input
+    pub left_time: Expr,
+    /// The time column of the right input
+    pub right_time: Expr,
+    /// The maximum difference of the times of the joined rows, a literal
+    pub tolerance: Option<Expr>,
+    /// [`JoinType::Inner`] or [`JoinType::Left`]
+    pub join_type: JoinType,
+    /// The output schema, the columns of the left input followed by the
+    /// columns of the right input
+    pub schema: DFSchemaRef,
+}
+
+impl AsOfJoin {
+    /// Creates a new [`AsOfJoin`] of `left` and `right`
+    pub fn try_new(
+        left: Arc<LogicalPlan>,
+        right: Arc<LogicalPlan>,
+        on: Vec<(Expr, Expr)>,
+        left_time: Expr,
+        right_time: Expr,
+        tolerance: Option<Expr>,
+        join_type: JoinType,
+    ) -> Result<Self> {
+        if !matches!(join_type, JoinType::Inner | JoinType::Left) {
+            return plan_err!("AsOfJoin supports Inner and Left joins, got {join_type}");
+        }
+        let pairs = on.iter().map(|(l, r)| (l, r));
+        for (l, r) in pairs.chain([(&left_time, &right_time)]) {
+            let (Expr::Column(_), Expr::Column(_)) = (l, r) else {
+                return plan_err!(
+                    "AsOfJoin expects columns of its inputs, got {l} and {r}"
+                );
+            };
+            let left_type = l.get_type(left.schema())?;
+            let right_type = r.get_type(right.schema())?;
+            if left_type != right_type {
+                return plan_err!(
+                    "AsOfJoin expects columns of the same type, got {l} of type {left_type} and {r} of type {right_type}"
+                );
+            }
+        }
+        if let Some(tolerance) = &tolerance {
+            if !matches!(tolerance, Expr::Literal(_)) {
+                return plan_err!(
+                    "AsOfJoin expects a constant tolerance, got {tolerance}"
+                );
+            }
+        }
+        let schema = build_join_schema(left.schema(), right.schema(), &join_type)?;
+        Ok(Self {
+            left,
+            right,
+            on,
+            left_time,
+            right_time,
+            tolerance,
+            join_type,
+            schema: Arc::new(schema),
+        })
+    }
+}
+
+impl UserDefinedLogicalNodeCore for AsOfJoin {
+    fn name(&self) -> &str {
+        "AsOfJoin"
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.left, &self.right]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        self.on
+            .iter()
+            .flat_map(|(l, r)| [l, r])
+            .chain([&self.left_time, &self.right_time])
+            .chain(&self.tolerance)
+            .cloned()
+            .collect()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let on = self
+            .on
+            .iter()
+            .map(|(l, r)| format!("{l} = {r}"))
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "AsOfJoin: join_type={}, on=[{}], time={} >= {}",
+            self.join_type,
+            on.join(", "),
+            self.left_time,
+            self.right_time
+        )?;
+        if let Some(tolerance) = &self.tolerance {
+            write!(f, ", tolerance={tolerance}")?;
+        }
+        Ok(())
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        let on_len = self.on.len() * 2;
+        let left = Arc::new(inputs[0].clone());
+        let right = Arc::new(inputs[1].clone());
+        let schema = build_join_schema(left.schema(), right.schema(), &self.join_type)
+            .map(Arc::new)
+            .unwrap_or_else(|_| self.schema.clone());
+        Self {
+            left,
+            right,
+            on: exprs[..on_len]
+                .chunks(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+            left_time: exprs[on_len].clone(),
+            right_time: exprs[on_len + 1].clone(),
+            tolerance: exprs.get(on_len + 2).cloned(),
+            join_type: self.join_type,
+            schema,
+        }
+    }
+}
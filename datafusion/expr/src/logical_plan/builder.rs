@@ -25,17 +25,18 @@ use std::iter::zip;
 use std::sync::Arc;
 
 use crate::dml::{CopyOptions, CopyTo};
-use crate::expr::Alias;
+use crate::expr::{Alias, GetFieldAccess, GetIndexedField};
 use crate::expr_rewriter::{
     coerce_plan_expr_for_schema, normalize_col,
     normalize_col_with_schemas_and_ambiguity_check, normalize_cols,
     rewrite_sort_cols_by_aggs,
 };
 use crate::logical_plan::{
-    Aggregate, Analyze, CrossJoin, Distinct, DistinctOn, EmptyRelation, Explain, Filter,
-    Join, JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType, Prepare,
-    Projection, Repartition, Sort, SubqueryAlias, TableScan, Union, Unnest, Values,
-    Window,
+    Aggregate, Analyze, AnalyzeFormat, AsOfJoin, CreateExternalTable, CreateMemoryTable,
+    CreateView, CrossJoin, DdlStatement, Distinct, DistinctOn, DropTable, DropView,
+    EmptyRelation, Explain, Extension, Filter, Join, JoinConstraint, JoinType, Limit,
+    LogicalPlan, Partitioning, PlanType, Prepare, Projection, RecursiveQuery,
+    Repartition, Sort, SubqueryAlias, TableScan, Union, Unnest, Values, Window,
 };
 use crate::type_coercion::binary::comparison_coercion;
 use crate::utils::{
@@ -49,10 +50,11 @@ use crate::{
 
 use arrow::datatypes::{DataType, Schema, SchemaRef};
 use datafusion_common::display::ToStringifiedPlan;
+use datafusion_common::parsers::CompressionTypeVariant;
 use datafusion_common::{
-    get_target_functional_dependencies, plan_datafusion_err, plan_err, Column, DFField,
-    DFSchema, DFSchemaRef, DataFusionError, FileType, OwnedTableReference, Result,
-    ScalarValue, TableReference, ToDFSchema, UnnestOptions,
+    get_target_functional_dependencies, plan_datafusion_err, plan_err, Column,
+    Constraints, DFField, DFSchema, DFSchemaRef, DataFusionError, FileType,
+    OwnedTableReference, Result, ScalarValue, TableReference, ToDFSchema, UnnestOptions,
 };
 
 /// Default table name for unnamed table
@@ -242,6 +244,9 @@ impl LogicalPlanBuilder {
         single_file_output: bool,
         copy_options: CopyOptions,
     ) -> Result<Self> {
+        if output_url.is_empty() {
+            return plan_err!("COPY requires a location to write to");
+        }
         Ok(Self::from(LogicalPlan::Copy(CopyTo {
             input: Arc::new(input),
             output_url,
@@ -258,7 +263,9 @@ impl LogicalPlanBuilder {
         table_schema: &Schema,
         overwrite: bool,
     ) -> Result<Self> {
+        let table_name = table_name.into();
         let table_schema = table_schema.clone().to_dfschema_ref()?;
+        check_write_columns(&input, &table_name, &table_schema)?;
 
         let op = if overwrite {
             WriteOp::InsertOverwrite
@@ -267,13 +274,149 @@ impl LogicalPlanBuilder {
         };
 
         Ok(Self::from(LogicalPlan::Dml(DmlStatement {
-            table_name: table_name.into(),
+            table_name,
             table_schema,
             op,
             input: Arc::new(input),
         })))
     }
 
+    /// Create a [DmlStatement] for deleting the rows of this builder, which
+    /// must be rows of the named table, from that table
+    pub fn delete_from(
+        input: LogicalPlan,
+        table_name: impl Into<OwnedTableReference>,
+        table_schema: &Schema,
+    ) -> Result<Self> {
+        let table_name = table_name.into();
+        let table_schema = table_schema.clone().to_dfschema_ref()?;
+        check_write_columns(&input, &table_name, &table_schema)?;
+
+        Ok(Self::from(LogicalPlan::Dml(DmlStatement {
+            table_name,
+            table_schema,
+            op: WriteOp::Delete,
+            input: Arc::new(input),
+        })))
+    }
+
+    /// Create a [CreateExternalTable] for registering the table described by
+    /// `table`, checking that its options are consistent with its schema
+    pub fn create_external_table(table: CreateExternalTable) -> Result<Self> {
+        let file_type = table.file_type.to_uppercase();
+        if (file_type == "PARQUET" || file_type == "AVRO" || file_type == "ARROW")
+            && table.file_compression_type != CompressionTypeVariant::UNCOMPRESSED
+        {
+            return plan_err!(
+                "File compression type cannot be set for PARQUET, AVRO, or ARROW files."
+            );
+        }
+        if !table.order_exprs.is_empty() && table.schema.fields().is_empty() {
+            return plan_err!(
+                "Provide a schema before specifying the order while creating a table."
+            );
+        }
+        for expr in table.order_exprs.iter().flatten() {
+            for column in expr.to_columns()? {
+                if !table.schema.has_column(&column) {
+                    return plan_err!("Column {column} is not in schema");
+                }
+            }
+        }
+        for name in table.column_defaults.keys() {
+            if !table.schema.has_column_with_unqualified_name(name) {
+                return plan_err!(
+                    "Default value for column {name} not found in table {}",
+                    table.name
+                );
+            }
+        }
+        Ok(Self::from(LogicalPlan::Ddl(
+            DdlStatement::CreateExternalTable(table),
+        )))
+    }
+
+    /// Create a [CreateMemoryTable] for creating the named table from the
+    /// contents of this builder
+    pub fn create_memory_table(
+        input: LogicalPlan,
+        name: impl Into<OwnedTableReference>,
+        if_not_exists: bool,
+        or_replace: bool,
+    ) -> Result<Self> {
+        let name = name.into();
+        if if_not_exists && or_replace {
+            return plan_err!(
+                "Table {name} can not be created with both IF NOT EXISTS and OR REPLACE"
+            );
+        }
+        // the columns of the table are not qualified
+        let mut names = HashSet::new();
+        for field in input.schema().fields() {
+            if !names.insert(field.name()) {
+                return plan_err!(
+                    "Table {name} can not have more than one column named {}",
+                    field.name()
+                );
+            }
+        }
+        Ok(Self::from(LogicalPlan::Ddl(
+            DdlStatement::CreateMemoryTable(CreateMemoryTable {
+                name,
+                constraints: Constraints::empty(),
+                input: Arc::new(input),
+                if_not_exists,
+                or_replace,
+                column_defaults: vec![],
+            }),
+        )))
+    }
+
+    /// Create a [CreateView] for creating the named view of this builder
+    pub fn create_view(
+        input: LogicalPlan,
+        name: impl Into<OwnedTableReference>,
+        or_replace: bool,
+        definition: Option<String>,
+    ) -> Result<Self> {
+        Ok(Self::from(LogicalPlan::Ddl(DdlStatement::CreateView(
+            CreateView {
+                name: name.into(),
+                input: Arc::new(input),
+                or_replace,
+                definition,
+            },
+        ))))
+    }
+
+    /// Create a [DropTable] for dropping the named table
+    pub fn drop_table(
+        name: impl Into<OwnedTableReference>,
+        if_exists: bool,
+    ) -> Result<Self> {
+        Ok(Self::from(LogicalPlan::Ddl(DdlStatement::DropTable(
+            DropTable {
+                name: name.into(),
+                if_exists,
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            },
+        ))))
+    }
+
+    /// Create a [DropView] for dropping the named view
+    pub fn drop_view(
+        name: impl Into<OwnedTableReference>,
+        if_exists: bool,
+    ) -> Result<Self> {
+        Ok(Self::from(LogicalPlan::Ddl(DdlStatement::DropView(
+            DropView {
+                name: name.into(),
+                if_exists,
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            },
+        ))))
+    }
+
     /// Convert a table provider into a builder with a TableScan
     pub fn scan_with_filters(
         table_name: impl Into<OwnedTableReference>,
@@ -556,6 +699,40 @@ impl LogicalPlanBuilder {
         )))))
     }
 
+    /// Convert a regular plan into a recursive query, whose static term is
+    /// the current plan and whose recursive term is `recursive_term`.
+    ///
+    /// `recursive_term` is evaluated repeatedly on the rows produced by its
+    /// previous evaluation, which it reads from the work table `name`, until
+    /// it produces no rows. If `is_distinct` is true, as for `UNION`, the
+    /// rows already produced by the query are discarded.
+    pub fn to_recursive_query(
+        self,
+        name: String,
+        recursive_term: LogicalPlan,
+        is_distinct: bool,
+    ) -> Result<Self> {
+        // Ensure that the static term and the recursive term have the same
+        // number of fields
+        let static_fields_len = self.plan.schema().fields().len();
+        let recursive_fields_len = recursive_term.schema().fields().len();
+        if static_fields_len != recursive_fields_len {
+            return plan_err!(
+                "Non-recursive term and recursive term must have the same number of columns ({static_fields_len} != {recursive_fields_len})"
+            );
+        }
+        // Ensure that the recursive term has the same field types as the
+        // static term
+        let coerced_recursive_term =
+            coerce_plan_expr_for_schema(&recursive_term, self.plan.schema())?;
+        Ok(Self::from(LogicalPlan::RecursiveQuery(RecursiveQuery {
+            name,
+            static_term: Arc::new(self.plan),
+            recursive_term: Arc::new(coerced_recursive_term),
+            is_distinct,
+        })))
+    }
+
     /// Apply deduplication: Only distinct (different) values are returned)
     pub fn distinct(self) -> Result<Self> {
         Ok(Self::from(LogicalPlan::Distinct(Distinct::All(Arc::new(
@@ -864,6 +1041,51 @@ impl LogicalPlanBuilder {
         }
     }
 
+    /// Apply an as-of join, joining each row with the row of `right` with
+    /// equal `join_keys` whose time is the closest to, and not later than,
+    /// the time of the row, see [`AsOfJoin`].
+    ///
+    /// `time` are the time columns of the left and right inputs, and
+    /// `tolerance` the maximum difference of the times of the joined rows.
+    /// Only [`JoinType::Inner`] and [`JoinType::Left`] joins are supported.
+    pub fn join_asof(
+        self,
+        right: LogicalPlan,
+        join_type: JoinType,
+        join_keys: (Vec<impl Into<Column>>, Vec<impl Into<Column>>),
+        time: (impl Into<Column>, impl Into<Column>),
+        tolerance: Option<Expr>,
+    ) -> Result<Self> {
+        if join_keys.0.len() != join_keys.1.len() {
+            return plan_err!("left_keys and right_keys were not the same length");
+        }
+        let on = join_keys
+            .0
+            .into_iter()
+            .zip(join_keys.1)
+            .map(|(l, r)| {
+                Ok((
+                    Expr::Column(Self::normalize(&self.plan, l.into())?),
+                    Expr::Column(Self::normalize(&right, r.into())?),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let left_time = Expr::Column(Self::normalize(&self.plan, time.0.into())?);
+        let right_time = Expr::Column(Self::normalize(&right, time.1.into())?);
+        let join = AsOfJoin::try_new(
+            Arc::new(self.plan),
+            Arc::new(right),
+            on,
+            left_time,
+            right_time,
+            tolerance,
+            join_type,
+        )?;
+        Ok(Self::from(LogicalPlan::Extension(Extension {
+            node: Arc::new(join),
+        })))
+    }
+
     /// Apply a cross join
     pub fn cross_join(self, right: LogicalPlan) -> Result<Self> {
         let join_schema =
@@ -927,6 +1149,7 @@ impl LogicalPlanBuilder {
         if analyze {
             Ok(Self::from(LogicalPlan::Analyze(Analyze {
                 verbose,
+                format: AnalyzeFormat::Text,
                 input: Arc::new(self.plan),
                 schema,
             })))
@@ -1182,6 +1405,22 @@ pub fn build_join_schema(
 ///
 /// This allows MySQL style selects like
 /// `SELECT col FROM t WHERE pk = 5` if col is unique
+/// Checks that `input` has a column for each column of the table written to
+fn check_write_columns(
+    input: &LogicalPlan,
+    table_name: &OwnedTableReference,
+    table_schema: &DFSchema,
+) -> Result<()> {
+    let input_columns = input.schema().fields().len();
+    let table_columns = table_schema.fields().len();
+    if input_columns != table_columns {
+        return plan_err!(
+            "Table {table_name} has {table_columns} columns, but the written rows have {input_columns} columns"
+        );
+    }
+    Ok(())
+}
+
 fn add_group_by_exprs_from_dependencies(
     mut group_expr: Vec<Expr>,
     schema: &DFSchemaRef,
@@ -1339,15 +1578,21 @@ pub fn project(
     for e in expr {
         let e = e.into();
         match e {
-            Expr::Wildcard { qualifier: None } => {
-                projected_expr.extend(expand_wildcard(input_schema, &plan, None)?)
-            }
+            Expr::Wildcard {
+                qualifier: None,
+                options,
+            } => projected_expr.extend(expand_wildcard(
+                input_schema,
+                &plan,
+                Some(&options),
+            )?),
             Expr::Wildcard {
                 qualifier: Some(qualifier),
+                options,
             } => projected_expr.extend(expand_qualified_wildcard(
                 &qualifier,
                 input_schema,
-                None,
+                Some(&options),
             )?),
             _ => projected_expr
                 .push(columnize_expr(normalize_col(e, &plan)?, input_schema)),
@@ -1489,6 +1734,9 @@ pub fn unnest(input: LogicalPlan, column: Column) -> Result<LogicalPlan> {
 }
 
 /// Create a [`LogicalPlan::Unnest`] plan with options
+///
+/// A struct column is instead replaced by one column per field, with a
+/// [`LogicalPlan::Projection`]
 pub fn unnest_with_options(
     input: LogicalPlan,
     column: Column,
@@ -1506,6 +1754,37 @@ pub fn unnest_with_options(
             field.data_type().clone(),
             unnest_field.is_nullable(),
         ),
+        DataType::Struct(fields) => {
+            if options.ordinality.is_some() {
+                return plan_err!(
+                    "Unnesting struct column {column} with ordinality is not supported"
+                );
+            }
+            let exprs = input
+                .schema()
+                .fields()
+                .iter()
+                .flat_map(|f| {
+                    let expr = Expr::Column(f.qualified_column());
+                    if f != unnest_field {
+                        return vec![expr];
+                    }
+                    fields
+                        .iter()
+                        .map(|field| {
+                            Expr::GetIndexedField(GetIndexedField::new(
+                                Box::new(expr.clone()),
+                                GetFieldAccess::NamedStructField {
+                                    name: ScalarValue::from(field.name().as_str()),
+                                },
+                            ))
+                            .alias_qualified(f.qualifier().cloned(), field.name())
+                        })
+                        .collect()
+                })
+                .collect::<Vec<_>>();
+            return project(input, exprs);
+        }
         _ => {
             // If the unnest field is not a list type return the input plan.
             return Ok(input);
@@ -1514,7 +1793,7 @@ pub fn unnest_with_options(
 
     // Update the schema with the unnest column type changed to contain the nested type.
     let input_schema = input.schema();
-    let fields = input_schema
+    let mut fields = input_schema
         .fields()
         .iter()
         .map(|f| {
@@ -1525,6 +1804,15 @@ pub fn unnest_with_options(
             }
         })
         .collect::<Vec<_>>();
+    if let Some(ordinality) = &options.ordinality {
+        // null when a null list is preserved
+        let nullable = options.preserve_nulls && unnest_field.is_nullable();
+        fields.push(DFField::new_unqualified(
+            ordinality,
+            DataType::Int64,
+            nullable,
+        ));
+    }
 
     let metadata = input_schema.metadata().clone();
     let df_schema = DFSchema::new_with_metadata(fields, metadata)?;
@@ -1544,10 +1832,14 @@ pub fn unnest_with_options(
 mod tests {
     use super::*;
     use crate::logical_plan::StringifiedPlan;
-    use crate::{col, expr, expr_fn::exists, in_subquery, lit, scalar_subquery, sum};
+    use crate::{
+        col, expr, expr_fn::exists, in_subquery, lit, scalar_subquery, sum, wildcard,
+        wildcard_with_options, WildcardOptions,
+    };
 
     use arrow::datatypes::{DataType, Field};
     use datafusion_common::{OwnedTableReference, SchemaError, TableReference};
+    use sqlparser::ast::{ExcludeSelectItem, Ident, IdentWithAlias, RenameSelectItem};
 
     #[test]
     fn plan_builder_simple() -> Result<()> {
@@ -1647,7 +1939,7 @@ mod tests {
 
         let plan = table_scan(Some("t1"), &employee_schema(), None)?
             .join_using(t2, JoinType::Inner, vec!["id"])?
-            .project(vec![Expr::Wildcard { qualifier: None }])?
+            .project(vec![wildcard()])?
             .build()?;
 
         // id column should only show up once in projection
@@ -1661,6 +1953,77 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn plan_builder_asof_join() -> Result<()> {
+        let t2 = table_scan(Some("t2"), &employee_schema(), None)?.build()?;
+
+        let plan = table_scan(Some("t1"), &employee_schema(), None)?
+            .join_asof(
+                t2.clone(),
+                JoinType::Left,
+                (vec!["state"], vec!["state"]),
+                ("t1.salary", "t2.salary"),
+                Some(lit(100)),
+            )?
+            .build()?;
+
+        let expected = "AsOfJoin: join_type=Left, on=[t1.state = t2.state], time=t1.salary >= t2.salary, tolerance=Int32(100)\
+        \n  TableScan: t1\
+        \n  TableScan: t2";
+        assert_eq!(expected, format!("{plan:?}"));
+        assert_eq!(plan.schema().fields().len(), 10);
+
+        let err = table_scan(Some("t1"), &employee_schema(), None)?
+            .join_asof(
+                t2.clone(),
+                JoinType::Inner,
+                (Vec::<Column>::new(), Vec::<Column>::new()),
+                ("t1.salary", "t2.state"),
+                None,
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("columns of the same type"),
+            "{err}"
+        );
+
+        let err = table_scan(Some("t1"), &employee_schema(), None)?
+            .join_asof(
+                t2,
+                JoinType::Right,
+                (Vec::<Column>::new(), Vec::<Column>::new()),
+                ("t1.salary", "t2.salary"),
+                None,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Inner and Left joins"), "{err}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_wildcard_with_options() -> Result<()> {
+        let options = WildcardOptions::default()
+            .with_exclude(ExcludeSelectItem::Multiple(vec![
+                Ident::new("first_name"),
+                Ident::new("last_name"),
+            ]))
+            .with_rename(RenameSelectItem::Single(IdentWithAlias {
+                ident: Ident::new("state"),
+                alias: Ident::new("region"),
+            }));
+        let plan = table_scan(Some("employee_csv"), &employee_schema(), None)?
+            .project(vec![wildcard_with_options(options)])?
+            .build()?;
+
+        let expected = "Projection: employee_csv.id, employee_csv.state AS region, employee_csv.salary\
+        \n  TableScan: employee_csv";
+
+        assert_eq!(expected, format!("{plan:?}"));
+
+        Ok(())
+    }
+
     #[test]
     fn plan_builder_union() -> Result<()> {
         let plan =
@@ -1944,6 +2307,107 @@ mod tests {
         table_scan(Some(name), &schema, None)?.build()
     }
 
+    #[test]
+    fn plan_builder_insert_into() -> Result<()> {
+        let input = table_scan(Some("t1"), &employee_schema(), None)?.build()?;
+        let plan =
+            LogicalPlanBuilder::insert_into(input, "t2", &employee_schema(), false)?
+                .build()?;
+        let expected = "Dml: op=[Insert Into] table=[t2]\
+        \n  TableScan: t1";
+        assert_eq!(expected, format!("{plan:?}"));
+
+        let input =
+            table_scan(Some("t1"), &employee_schema(), Some(vec![0, 1]))?.build()?;
+        let err = LogicalPlanBuilder::insert_into(input, "t2", &employee_schema(), false)
+            .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Table t2 has 5 columns, but the written rows have 2 columns"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_create_memory_table() -> Result<()> {
+        let plan = LogicalPlanBuilder::create_memory_table(
+            test_table_scan_with_name("t1")?,
+            "t2",
+            false,
+            true,
+        )?
+        .build()?;
+        let expected = "CreateMemoryTable: Bare { table: \"t2\" }\
+        \n  TableScan: t1";
+        assert_eq!(expected, format!("{plan:?}"));
+
+        // the columns of both inputs of the join are named a, b and c
+        let input = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .cross_join(test_table_scan_with_name("t3")?)?
+            .build()?;
+        let err = LogicalPlanBuilder::create_memory_table(input, "t2", false, false)
+            .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Table t2 can not have more than one column named a"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_create_external_table_errors() {
+        let table = CreateExternalTable {
+            schema: employee_schema().to_dfschema_ref().unwrap(),
+            name: "t".into(),
+            location: "/tmp/t".to_string(),
+            file_type: "PARQUET".to_string(),
+            has_header: false,
+            delimiter: ',',
+            table_partition_cols: vec![],
+            if_not_exists: false,
+            definition: None,
+            order_exprs: vec![],
+            file_compression_type: CompressionTypeVariant::UNCOMPRESSED,
+            unbounded: false,
+            options: HashMap::new(),
+            constraints: Constraints::empty(),
+            column_defaults: HashMap::new(),
+            schema_location: None,
+        };
+        assert!(LogicalPlanBuilder::create_external_table(table.clone()).is_ok());
+
+        let cases = [
+            (
+                CreateExternalTable {
+                    file_compression_type: CompressionTypeVariant::GZIP,
+                    ..table.clone()
+                },
+                "File compression type cannot be set for PARQUET, AVRO, or ARROW files.",
+            ),
+            (
+                CreateExternalTable {
+                    order_exprs: vec![vec![col("age").sort(true, false)]],
+                    ..table.clone()
+                },
+                "Column age is not in schema",
+            ),
+            (
+                CreateExternalTable {
+                    column_defaults: [("age".to_string(), lit(1))].into(),
+                    ..table
+                },
+                "Default value for column age not found in table t",
+            ),
+        ];
+        for (table, expected) in cases {
+            let err = LogicalPlanBuilder::create_external_table(table).unwrap_err();
+            assert_eq!(
+                err.strip_backtrace(),
+                format!("Error during planning: {expected}")
+            );
+        }
+    }
+
     #[test]
     fn plan_builder_intersect_different_num_columns_error() -> Result<()> {
         let plan1 =
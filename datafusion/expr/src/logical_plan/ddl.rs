@@ -196,6 +196,9 @@ pub struct CreateExternalTable {
     pub constraints: Constraints,
     /// Default values for columns
     pub column_defaults: HashMap<String, Expr>,
+    /// Location of a file to read the table schema from, instead of
+    /// inferring it from the data
+    pub schema_location: Option<String>,
 }
 
 // Hashing refers to a subset of fields considered in PartialEq.
@@ -214,6 +217,7 @@ impl Hash for CreateExternalTable {
         self.order_exprs.hash(state);
         self.unbounded.hash(state);
         self.options.len().hash(state); // HashMap is not hashable
+        self.schema_location.hash(state);
     }
 }
 
@@ -0,0 +1,194 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`GapFill`] plan emitting the missing time buckets of aggregations
+//! grouped by `date_bin_gapfill`
+
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use datafusion_common::{plan_err, DFSchemaRef, DataFusionError, Result};
+
+use crate::{Expr, LogicalPlan, UserDefinedLogicalNodeCore};
+
+/// How [`GapFill`] fills the values of a column in the rows it emits for
+/// missing time buckets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillStrategy {
+    /// Fill with nulls
+    Null,
+    /// Fill with the value of the previous row of the series, the last
+    /// observation carried forward (`locf`)
+    PrevValue,
+    /// Fill by linear interpolation between the values of the previous and
+    /// next rows of the series (`interpolate`)
+    LinearInterpolate,
+}
+
+impl Display for FillStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::PrevValue => write!(f, "locf"),
+            Self::LinearInterpolate => write!(f, "interpolate"),
+        }
+    }
+}
+
+/// Emits a row for every time bucket that is missing between the first and
+/// the last time bucket of each series of its input.
+///
+/// The input is usually an aggregation grouped by the time buckets of
+/// `date_bin(stride, time)` and the columns identifying the series. The
+/// other columns of the emitted rows are filled according to their
+/// [`FillStrategy`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GapFill {
+    /// The input plan
+    pub input: Arc<LogicalPlan>,
+    /// The columns identifying the series, other than the time column
+    pub series_expr: Vec<Expr>,
+    /// The column with the time buckets
+    pub time_expr: Expr,
+    /// The width of the time buckets, an interval literal
+    pub stride: Expr,
+    /// The other columns, and how they are filled
+    pub fill_expr: Vec<(Expr, FillStrategy)>,
+}
+
+impl GapFill {
+    /// Creates a new [`GapFill`] of `input`, filling the columns of
+    /// `fill_expr` with nulls
+    pub fn try_new(
+        input: Arc<LogicalPlan>,
+        series_expr: Vec<Expr>,
+        time_expr: Expr,
+        stride: Expr,
+        fill_expr: Vec<Expr>,
+    ) -> Result<Self> {
+        for expr in series_expr.iter().chain([&time_expr]).chain(&fill_expr) {
+            if !matches!(expr, Expr::Column(_)) {
+                return plan_err!("GapFill expects columns of its input, got {expr}");
+            }
+        }
+        Ok(Self {
+            input,
+            series_expr,
+            time_expr,
+            stride,
+            fill_expr: fill_expr
+                .into_iter()
+                .map(|expr| (expr, FillStrategy::Null))
+                .collect(),
+        })
+    }
+
+    /// Returns this plan with the column `expr` filled with `strategy`
+    pub fn with_fill_strategy(
+        mut self,
+        expr: &Expr,
+        strategy: FillStrategy,
+    ) -> Result<Self> {
+        match self.fill_expr.iter_mut().find(|(e, _)| e == expr) {
+            Some((_, s)) => {
+                *s = strategy;
+                Ok(self)
+            }
+            None => plan_err!("{strategy} expects an aggregate column, got {expr}"),
+        }
+    }
+}
+
+impl UserDefinedLogicalNodeCore for GapFill {
+    fn name(&self) -> &str {
+        "GapFill"
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        self.input.schema()
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        self.series_expr
+            .iter()
+            .chain([&self.time_expr, &self.stride])
+            .chain(self.fill_expr.iter().map(|(expr, _)| expr))
+            .cloned()
+            .collect()
+    }
+
+    /// Filters on the time or filled columns would see different rows
+    /// below the gap filling
+    fn prevent_predicate_push_down_columns(&self) -> HashSet<String> {
+        let series = self
+            .series_expr
+            .iter()
+            .filter_map(|expr| match expr {
+                Expr::Column(column) => Some(column.name.as_str()),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        self.schema()
+            .fields()
+            .iter()
+            .map(|field| field.name())
+            .filter(|name| !series.contains(name.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let series = self
+            .series_expr
+            .iter()
+            .map(|expr| expr.to_string())
+            .collect::<Vec<_>>();
+        let fill = self
+            .fill_expr
+            .iter()
+            .map(|(expr, strategy)| format!("{strategy}({expr})"))
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "GapFill: series=[{}], time={}, stride={}, fill=[{}]",
+            series.join(", "),
+            self.time_expr,
+            self.stride,
+            fill.join(", ")
+        )
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        let series_len = self.series_expr.len();
+        Self {
+            input: Arc::new(inputs[0].clone()),
+            series_expr: exprs[..series_len].to_vec(),
+            time_expr: exprs[series_len].clone(),
+            stride: exprs[series_len + 1].clone(),
+            fill_expr: exprs[series_len + 2..]
+                .iter()
+                .zip(&self.fill_expr)
+                .map(|(expr, (_, strategy))| (expr.clone(), *strategy))
+                .collect(),
+        }
+    }
+}
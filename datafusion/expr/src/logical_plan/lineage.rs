@@ -0,0 +1,369 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Column level lineage of [`LogicalPlan`]s
+
+use crate::logical_plan::{
+    Aggregate, Distinct, DistinctOn, Join, JoinType, LogicalPlan, Projection, Window,
+};
+use crate::utils::grouping_set_to_exprlist;
+use crate::Expr;
+use datafusion_common::tree_node::{TreeNode, VisitRecursion};
+use datafusion_common::{Column, Result};
+
+/// The lineage of a column of the output of a [`LogicalPlan`], as returned
+/// by [`column_lineage`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnLineage {
+    /// The output column
+    pub column: Column,
+    /// The columns of the table scans that the values of the column are
+    /// computed from, qualified by the names of their tables
+    pub sources: Vec<Column>,
+    /// The expressions computing the column from the sources, from the
+    /// outermost plan to the innermost one. Each expression refers to the
+    /// columns of the input of the plan it belongs to.
+    pub transformations: Vec<Expr>,
+}
+
+/// Returns the lineage of every column of the output of `plan`, in the
+/// order of its schema.
+///
+/// The lineage is traced through projections, joins, aggregations, window
+/// functions and unions down to the table scans. The columns of other plans
+/// with inputs, such as extensions, are traced through the input column of
+/// the same name, and are otherwise considered to depend on all the columns
+/// of the inputs. Columns of plans without input, such as `VALUES`, have no
+/// sources.
+///
+/// ```
+/// # use datafusion_common::{Column, Result};
+/// # use datafusion_expr::logical_plan::{column_lineage, table_scan};
+/// # use datafusion_expr::{col, lit, sum, Expr};
+/// # use arrow::datatypes::{DataType, Field, Schema};
+/// # fn main() -> Result<()> {
+/// let schema = Schema::new(vec![
+///     Field::new("a", DataType::Int32, false),
+///     Field::new("b", DataType::Int32, false),
+/// ]);
+/// // SELECT a, SUM(b) * 2 AS total FROM t GROUP BY a
+/// let plan = table_scan(Some("t"), &schema, None)?
+///     .aggregate(vec![col("a")], vec![sum(col("b"))])?
+///     .project(vec![
+///         col("t.a"),
+///         (Expr::Column(Column::from_name("SUM(t.b)")) * lit(2)).alias("total"),
+///     ])?
+///     .build()?;
+///
+/// let lineage = column_lineage(&plan)?;
+/// assert_eq!(lineage[1].column, Column::from_name("total"));
+/// assert_eq!(lineage[1].sources, vec![Column::from_qualified_name("t.b")]);
+/// // the projection, then the aggregation
+/// assert_eq!(lineage[1].transformations.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn column_lineage(plan: &LogicalPlan) -> Result<Vec<ColumnLineage>> {
+    Ok(plan
+        .schema()
+        .fields()
+        .iter()
+        .zip(plan_lineage(plan)?)
+        .map(|(field, lineage)| ColumnLineage {
+            column: field.qualified_column(),
+            sources: lineage.sources,
+            transformations: lineage.transformations,
+        })
+        .collect())
+}
+
+/// The sources and transformations of a column
+#[derive(Debug, Clone, Default)]
+struct Lineage {
+    sources: Vec<Column>,
+    transformations: Vec<Expr>,
+}
+
+impl Lineage {
+    /// Adds the sources and transformations of `other` that are not part
+    /// of this lineage yet
+    fn merge(&mut self, other: &Lineage) {
+        for source in &other.sources {
+            if !self.sources.contains(source) {
+                self.sources.push(source.clone());
+            }
+        }
+        for transformation in &other.transformations {
+            if !self.transformations.contains(transformation) {
+                self.transformations.push(transformation.clone());
+            }
+        }
+    }
+}
+
+/// Returns the lineage of every column of the output of `plan`
+fn plan_lineage(plan: &LogicalPlan) -> Result<Vec<Lineage>> {
+    match plan {
+        LogicalPlan::TableScan(scan) => Ok(scan
+            .projected_schema
+            .fields()
+            .iter()
+            .map(|field| Lineage {
+                sources: vec![Column::new(
+                    Some(scan.table_name.clone()),
+                    field.name().clone(),
+                )],
+                transformations: vec![],
+            })
+            .collect()),
+        LogicalPlan::Projection(Projection { expr, input, .. })
+        | LogicalPlan::Distinct(Distinct::On(DistinctOn {
+            select_expr: expr,
+            input,
+            ..
+        })) => exprs_lineage(expr, input),
+        LogicalPlan::Aggregate(Aggregate {
+            input,
+            group_expr,
+            aggr_expr,
+            schema,
+            ..
+        }) => {
+            let mut expr = grouping_set_to_exprlist(group_expr)?;
+            expr.extend(aggr_expr.iter().cloned());
+            if expr.len() != schema.fields().len() {
+                return fallback_lineage(plan);
+            }
+            exprs_lineage(&expr, input)
+        }
+        LogicalPlan::Window(Window {
+            input, window_expr, ..
+        }) => {
+            let mut lineage = plan_lineage(input)?;
+            lineage.extend(exprs_lineage(window_expr, input)?);
+            Ok(lineage)
+        }
+        LogicalPlan::Join(Join {
+            left,
+            right,
+            join_type,
+            ..
+        }) => match join_type {
+            JoinType::LeftSemi | JoinType::LeftAnti => plan_lineage(left),
+            JoinType::RightSemi | JoinType::RightAnti => plan_lineage(right),
+            JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full => {
+                let mut lineage = plan_lineage(left)?;
+                lineage.extend(plan_lineage(right)?);
+                Ok(lineage)
+            }
+        },
+        LogicalPlan::CrossJoin(cross_join) => {
+            let mut lineage = plan_lineage(&cross_join.left)?;
+            lineage.extend(plan_lineage(&cross_join.right)?);
+            Ok(lineage)
+        }
+        LogicalPlan::Union(union) => {
+            let mut lineage = vec![Lineage::default(); union.schema.fields().len()];
+            for input in &union.inputs {
+                for (column, input_column) in lineage.iter_mut().zip(plan_lineage(input)?)
+                {
+                    column.merge(&input_column);
+                }
+            }
+            Ok(lineage)
+        }
+        // the columns of these plans are the columns of their input
+        LogicalPlan::Filter(_)
+        | LogicalPlan::Sort(_)
+        | LogicalPlan::Limit(_)
+        | LogicalPlan::Repartition(_)
+        | LogicalPlan::Distinct(Distinct::All(_))
+        | LogicalPlan::Subquery(_)
+        | LogicalPlan::SubqueryAlias(_)
+        | LogicalPlan::Unnest(_) => {
+            let input = plan.inputs()[0];
+            if input.schema().fields().len() != plan.schema().fields().len() {
+                return fallback_lineage(plan);
+            }
+            plan_lineage(input)
+        }
+        _ => fallback_lineage(plan),
+    }
+}
+
+/// Returns the lineage of `exprs`, which are evaluated over `input`
+fn exprs_lineage(exprs: &[Expr], input: &LogicalPlan) -> Result<Vec<Lineage>> {
+    let input_lineage = plan_lineage(input)?;
+    exprs
+        .iter()
+        .map(|expr| {
+            let mut columns: Vec<Column> = vec![];
+            expr.apply(&mut |expr| {
+                if let Expr::Column(column) = expr {
+                    if !columns.contains(column) {
+                        columns.push(column.clone());
+                    }
+                }
+                Ok(VisitRecursion::Continue)
+            })?;
+
+            let mut lineage = Lineage::default();
+            let expr = expr.clone().unalias();
+            if !matches!(expr, Expr::Column(_)) {
+                lineage.transformations.push(expr);
+            }
+            for column in columns {
+                // outer references of subqueries are not part of the input
+                if let Ok(index) = input.schema().index_of_column(&column) {
+                    lineage.merge(&input_lineage[index]);
+                }
+            }
+            Ok(lineage)
+        })
+        .collect()
+}
+
+/// Returns the lineage of the columns of `plan` by matching them with the
+/// columns of the same name of its inputs
+fn fallback_lineage(plan: &LogicalPlan) -> Result<Vec<Lineage>> {
+    let inputs = plan
+        .inputs()
+        .into_iter()
+        .map(|input| Ok((input, plan_lineage(input)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut all = Lineage::default();
+    for (_, input_lineage) in &inputs {
+        for lineage in input_lineage {
+            all.merge(&Lineage {
+                sources: lineage.sources.clone(),
+                transformations: vec![],
+            });
+        }
+    }
+
+    Ok(plan
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| {
+            let column = field.qualified_column();
+            inputs
+                .iter()
+                .find_map(|(input, input_lineage)| {
+                    let index = input.schema().index_of_column(&column).ok()?;
+                    Some(input_lineage[index].clone())
+                })
+                .unwrap_or_else(|| all.clone())
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::WindowFunction;
+    use crate::logical_plan::{table_scan, LogicalPlanBuilder};
+    use crate::{
+        col, count, lit, max, AggregateFunction, WindowFrame, WindowFunctionDefinition,
+    };
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn scan(name: &str) -> Result<LogicalPlanBuilder> {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("a", DataType::Int32, false),
+        ]);
+        table_scan(Some(name), &schema, None)
+    }
+
+    fn sources(lineage: &ColumnLineage) -> Vec<String> {
+        lineage.sources.iter().map(|c| c.flat_name()).collect()
+    }
+
+    #[test]
+    fn lineage_through_join_and_projection() -> Result<()> {
+        let plan = scan("t1")?
+            .join(
+                scan("t2")?.build()?,
+                JoinType::Inner,
+                (vec!["t1.id"], vec!["t2.id"]),
+                None,
+            )?
+            .project(vec![
+                col("t1.id"),
+                (col("t1.a") + col("t2.a")).alias("total"),
+                lit(1).alias("one"),
+            ])?
+            .build()?;
+
+        let lineage = column_lineage(&plan)?;
+        assert_eq!(lineage.len(), 3);
+        assert_eq!(lineage[0].column.flat_name(), "t1.id");
+        assert_eq!(sources(&lineage[0]), ["t1.id"]);
+        assert!(lineage[0].transformations.is_empty());
+
+        assert_eq!(lineage[1].column.flat_name(), "total");
+        assert_eq!(sources(&lineage[1]), ["t1.a", "t2.a"]);
+        assert_eq!(lineage[1].transformations, [col("t1.a") + col("t2.a")]);
+
+        assert!(lineage[2].sources.is_empty());
+        assert_eq!(lineage[2].transformations, [lit(1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn lineage_through_aggregate_and_window() -> Result<()> {
+        let max_a = Expr::WindowFunction(WindowFunction::new(
+            WindowFunctionDefinition::AggregateFunction(AggregateFunction::Max),
+            vec![col("t.a")],
+            vec![],
+            vec![],
+            WindowFrame::new(false),
+        ));
+        let max_a_column = Expr::Column(Column::from_name(max_a.display_name()?));
+        let plan = scan("t")?
+            .window(vec![max_a.clone()])?
+            .aggregate(
+                vec![col("t.id")],
+                vec![count(col("t.a")), max(max_a_column.clone())],
+            )?
+            .build()?;
+
+        let lineage = column_lineage(&plan)?;
+        assert_eq!(lineage.len(), 3);
+        assert_eq!(sources(&lineage[0]), ["t.id"]);
+        assert_eq!(sources(&lineage[1]), ["t.a"]);
+        assert_eq!(lineage[1].transformations, [count(col("t.a"))]);
+        // the aggregation, then the window function
+        assert_eq!(sources(&lineage[2]), ["t.a"]);
+        assert_eq!(
+            lineage[2].transformations,
+            [max(max_a_column.clone()), max_a]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lineage_through_union() -> Result<()> {
+        let plan = scan("t1")?.union(scan("t2")?.build()?)?.build()?;
+
+        let lineage = column_lineage(&plan)?;
+        assert_eq!(sources(&lineage[1]), ["t1.a", "t2.a"]);
+        Ok(())
+    }
+}
@@ -15,14 +15,18 @@
 // specific language governing permissions and limitations
 // under the License.
 
+mod asof_join;
 pub mod builder;
 mod ddl;
 pub mod display;
 pub mod dml;
 mod extension;
+mod gap_fill;
+mod lineage;
 mod plan;
 mod statement;
 
+pub use asof_join::AsOfJoin;
 pub use builder::{
     build_join_schema, table_scan, union, wrap_projection_for_join_if_necessary,
     LogicalPlanBuilder, UNNAMED_TABLE,
@@ -33,11 +37,11 @@ pub use ddl::{
 };
 pub use dml::{DmlStatement, WriteOp};
 pub use plan::{
-    projection_schema, Aggregate, Analyze, CrossJoin, DescribeTable, Distinct,
-    DistinctOn, EmptyRelation, Explain, Extension, Filter, Join, JoinConstraint,
-    JoinType, Limit, LogicalPlan, Partitioning, PlanType, Prepare, Projection,
-    Repartition, Sort, StringifiedPlan, Subquery, SubqueryAlias, TableScan,
-    ToStringifiedPlan, Union, Unnest, Values, Window,
+    projection_schema, Aggregate, Analyze, AnalyzeFormat, CrossJoin, DescribeTable,
+    Distinct, DistinctOn, EmptyRelation, Explain, Extension, Filter, Join,
+    JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType, Prepare,
+    Projection, RecursiveQuery, Repartition, Sort, StringifiedPlan, Subquery,
+    SubqueryAlias, TableScan, ToStringifiedPlan, Union, Unnest, Values, Window,
 };
 pub use statement::{
     SetVariable, Statement, TransactionAccessMode, TransactionConclusion, TransactionEnd,
@@ -47,3 +51,5 @@ pub use statement::{
 pub use display::display_schema;
 
 pub use extension::{UserDefinedLogicalNode, UserDefinedLogicalNodeCore};
+pub use gap_fill::{FillStrategy, GapFill};
+pub use lineage::{column_lineage, ColumnLineage};
@@ -154,6 +154,9 @@ pub enum LogicalPlan {
     /// Unnest a column that contains a nested list type such as an
     /// ARRAY. This is used to implement SQL `UNNEST`
     Unnest(Unnest),
+    /// A variadic query operation, such as a recursive CTE. This is used
+    /// to implement SQL `WITH RECURSIVE`
+    RecursiveQuery(RecursiveQuery),
 }
 
 impl LogicalPlan {
@@ -191,6 +194,10 @@ impl LogicalPlan {
             LogicalPlan::Copy(CopyTo { input, .. }) => input.schema(),
             LogicalPlan::Ddl(ddl) => ddl.schema(),
             LogicalPlan::Unnest(Unnest { schema, .. }) => schema,
+            LogicalPlan::RecursiveQuery(RecursiveQuery { static_term, .. }) => {
+                // we take the schema of the static term as the schema of the entire recursive query
+                static_term.schema()
+            }
         }
     }
 
@@ -240,7 +247,8 @@ impl LogicalPlan {
             | LogicalPlan::SubqueryAlias(_)
             | LogicalPlan::Union(_)
             | LogicalPlan::Extension(_)
-            | LogicalPlan::TableScan(_) => {
+            | LogicalPlan::TableScan(_)
+            | LogicalPlan::RecursiveQuery(_) => {
                 vec![self.schema()]
             }
             // return children schemas
@@ -397,7 +405,8 @@ impl LogicalPlan {
             | LogicalPlan::Ddl(_)
             | LogicalPlan::Copy(_)
             | LogicalPlan::DescribeTable(_)
-            | LogicalPlan::Prepare(_) => Ok(()),
+            | LogicalPlan::Prepare(_)
+            | LogicalPlan::RecursiveQuery(_) => Ok(()),
         }
     }
 
@@ -430,6 +439,11 @@ impl LogicalPlan {
             LogicalPlan::Ddl(ddl) => ddl.inputs(),
             LogicalPlan::Unnest(Unnest { input, .. }) => vec![input],
             LogicalPlan::Prepare(Prepare { input, .. }) => vec![input],
+            LogicalPlan::RecursiveQuery(RecursiveQuery {
+                static_term,
+                recursive_term,
+                ..
+            }) => vec![static_term, recursive_term],
             // plans without inputs
             LogicalPlan::TableScan { .. }
             | LogicalPlan::Statement { .. }
@@ -540,6 +554,9 @@ impl LogicalPlan {
             | LogicalPlan::Ddl(_)
             | LogicalPlan::DescribeTable(_)
             | LogicalPlan::Unnest(_) => Ok(None),
+            LogicalPlan::RecursiveQuery(RecursiveQuery { static_term, .. }) => {
+                static_term.head_output_expr()
+            }
         }
     }
 
@@ -840,6 +857,7 @@ impl LogicalPlan {
                 assert_eq!(inputs.len(), 1);
                 Ok(LogicalPlan::Analyze(Analyze {
                     verbose: a.verbose,
+                    format: a.format,
                     schema: a.schema.clone(),
                     input: Arc::new(inputs[0].clone()),
                 }))
@@ -891,7 +909,7 @@ impl LogicalPlan {
                 let input = Arc::new(inputs[0].clone());
                 let nested_field = input.schema().field_from_column(column)?;
                 let unnested_field = schema.field_from_column(column)?;
-                let fields = input
+                let mut fields = input
                     .schema()
                     .fields()
                     .iter()
@@ -903,6 +921,10 @@ impl LogicalPlan {
                         }
                     })
                     .collect::<Vec<_>>();
+                // The ordinality column comes after the input columns
+                if options.ordinality.is_some() {
+                    fields.push(schema.field(schema.fields().len() - 1).clone());
+                }
 
                 let schema = Arc::new(
                     DFSchema::new_with_metadata(
@@ -922,6 +944,14 @@ impl LogicalPlan {
                     options: options.clone(),
                 }))
             }
+            LogicalPlan::RecursiveQuery(RecursiveQuery {
+                name, is_distinct, ..
+            }) => Ok(LogicalPlan::RecursiveQuery(RecursiveQuery {
+                name: name.clone(),
+                static_term: Arc::new(inputs[0].clone()),
+                recursive_term: Arc::new(inputs[1].clone()),
+                is_distinct: *is_distinct,
+            })),
         }
     }
     /// Replaces placeholder param values (like `$1`, `$2`) in [`LogicalPlan`]
@@ -1080,7 +1110,7 @@ impl LogicalPlan {
                 Distinct::All(input) | Distinct::On(DistinctOn { input, .. }),
             ) => input.max_rows(),
             LogicalPlan::Values(v) => Some(v.values.len()),
-            LogicalPlan::Unnest(_) => None,
+            LogicalPlan::Unnest(_) | LogicalPlan::RecursiveQuery(_) => None,
             LogicalPlan::Ddl(_)
             | LogicalPlan::Explain(_)
             | LogicalPlan::Analyze(_)
@@ -1687,8 +1717,19 @@ impl LogicalPlan {
                     LogicalPlan::DescribeTable(DescribeTable { .. }) => {
                         write!(f, "DescribeTable")
                     }
-                    LogicalPlan::Unnest(Unnest { column, .. }) => {
-                        write!(f, "Unnest: {column}")
+                    LogicalPlan::Unnest(Unnest {
+                        column, options, ..
+                    }) => {
+                        write!(f, "Unnest: {column}")?;
+                        if let Some(ordinality) = &options.ordinality {
+                            write!(f, ", ordinality={ordinality}")?;
+                        }
+                        Ok(())
+                    }
+                    LogicalPlan::RecursiveQuery(RecursiveQuery {
+                        name, is_distinct, ..
+                    }) => {
+                        write!(f, "RecursiveQuery: name={name}, is_distinct={is_distinct}")
                     }
                 }
             }
@@ -2155,6 +2196,25 @@ pub struct Union {
     pub schema: DFSchemaRef,
 }
 
+/// A variadic query operation, such as a recursive CTE
+///
+/// The static term is evaluated once, then the recursive term is evaluated
+/// repeatedly on the rows produced by its previous evaluation, exposed as
+/// the work table of the query, until it produces no rows.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RecursiveQuery {
+    /// Name of the query, which the recursive term refers to
+    pub name: String,
+    /// The static term (initial contents of the work table)
+    pub static_term: Arc<LogicalPlan>,
+    /// The recursive term (evaluated on the contents of the work table until
+    /// it returns an empty set)
+    pub recursive_term: Arc<LogicalPlan>,
+    /// Should the output of the recursive term be deduplicated (`UNION`) or
+    /// not (`UNION ALL`).
+    pub is_distinct: bool,
+}
+
 /// Prepare a statement but do not execute it. Prepare statements can have 0 or more
 /// `Expr::Placeholder` expressions that are filled in during execution
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -2219,12 +2279,33 @@ pub struct Explain {
 pub struct Analyze {
     /// Should extra detail be included?
     pub verbose: bool,
+    /// How the plan with metrics is printed
+    pub format: AnalyzeFormat,
     /// The logical plan that is being EXPLAIN ANALYZE'd
     pub input: Arc<LogicalPlan>,
     /// The output schema of the explain (2 columns of text)
     pub schema: DFSchemaRef,
 }
 
+/// Output format of `EXPLAIN ANALYZE`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AnalyzeFormat {
+    /// The indented plan annotated with metrics, followed by a summary
+    #[default]
+    Text,
+    /// A single JSON document with the metrics of each operator
+    Json,
+}
+
+impl Display for AnalyzeFormat {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AnalyzeFormat::Text => write!(f, "TEXT"),
+            AnalyzeFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
 /// Extension operator defined outside of DataFusion
 // TODO(clippy): This clippy `allow` should be removed if
 // the manual `PartialEq` is removed in favor of a derive.
@@ -81,9 +81,13 @@ impl Statement {
                         write!(f, "TransactionEnd: {conclusion:?} chain:={chain}")
                     }
                     Statement::SetVariable(SetVariable {
-                        variable, value, ..
+                        variable,
+                        value,
+                        local,
+                        ..
                     }) => {
-                        write!(f, "SetVariable: set {variable:?} to {value:?}")
+                        let set = if *local { "set local" } else { "set" };
+                        write!(f, "SetVariable: {set} {variable:?} to {value:?}")
                     }
                 }
             }
@@ -145,6 +149,9 @@ pub struct SetVariable {
     pub variable: String,
     /// The value to set
     pub value: String,
+    /// If true, the value only applies until the end of the current
+    /// transaction (`SET LOCAL`)
+    pub local: bool,
     /// Dummy schema
     pub schema: DFSchemaRef,
 }
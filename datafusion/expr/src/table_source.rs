@@ -20,9 +20,10 @@
 use crate::{Expr, LogicalPlan};
 
 use arrow::datatypes::SchemaRef;
-use datafusion_common::{Constraints, Result};
+use datafusion_common::{not_impl_err, Constraints, DataFusionError, Result};
 
 use std::any::Any;
+use std::sync::Arc;
 
 /// Indicates whether and how a filter expression can be handled by a
 /// TableProvider for table scans.
@@ -99,6 +100,53 @@ pub trait TableSource: Sync + Send {
             .collect()
     }
 
+    /// Tests whether the table provider can compute the aggregate expressions
+    /// `aggr_expr`, grouped by `group_expr`, over the rows matching `filters`
+    /// without the table being scanned.
+    fn supports_aggregate_pushdown(
+        &self,
+        _group_expr: &[Expr],
+        _aggr_expr: &[Expr],
+        _filters: &[Expr],
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Get a table source for the result of an aggregation accepted by
+    /// [`Self::supports_aggregate_pushdown`], which has one field for each of
+    /// `group_expr` followed by one field for each of `aggr_expr`.
+    fn aggregate_pushdown(
+        &self,
+        _group_expr: &[Expr],
+        _aggr_expr: &[Expr],
+        _filters: &[Expr],
+    ) -> Result<Arc<dyn TableSource>> {
+        not_impl_err!("Aggregate pushdown not implemented for this table source")
+    }
+
+    /// Tests whether the table source can return the first `k` of the rows
+    /// matching `filters` when ordered by `sort_exprs`, without the whole
+    /// table being scanned and sorted.
+    fn supports_topk_pushdown(
+        &self,
+        _sort_exprs: &[Expr],
+        _k: usize,
+        _filters: &[Expr],
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Get a table source with the schema of this table, containing at least
+    /// the top rows accepted by [`Self::supports_topk_pushdown`].
+    fn topk_pushdown(
+        &self,
+        _sort_exprs: &[Expr],
+        _k: usize,
+        _filters: &[Expr],
+    ) -> Result<Arc<dyn TableSource>> {
+        not_impl_err!("Top-k pushdown not implemented for this table source")
+    }
+
     /// Get the Logical plan of this table provider, if available.
     fn get_logical_plan(&self) -> Option<&LogicalPlan> {
         None
@@ -353,7 +353,9 @@ impl TreeNode for Expr {
                 transform_vec(list, &mut transform)?,
                 negated,
             )),
-            Expr::Wildcard { qualifier } => Expr::Wildcard { qualifier },
+            Expr::Wildcard { qualifier, options } => {
+                Expr::Wildcard { qualifier, options }
+            }
             Expr::GetIndexedField(GetIndexedField { expr, field }) => {
                 Expr::GetIndexedField(GetIndexedField::new(
                     transform_boxed(expr, &mut transform)?,
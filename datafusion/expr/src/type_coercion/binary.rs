@@ -634,6 +634,18 @@ fn dictionary_coercion(
 ) -> Option<DataType> {
     use arrow::datatypes::DataType::*;
     match (lhs_type, rhs_type) {
+        // Keep dictionaries of the same values, e.g. join keys, so that their
+        // values are compared once per dictionary rather than once per row
+        (
+            Dictionary(lhs_index_type, lhs_value_type),
+            Dictionary(rhs_index_type, rhs_value_type),
+        ) if preserve_dictionaries && lhs_value_type == rhs_value_type => {
+            comparison_binary_numeric_coercion(lhs_index_type, rhs_index_type)
+                .map(|index_type| {
+                    Dictionary(Box::new(index_type), lhs_value_type.clone())
+                })
+                .or_else(|| comparison_coercion(lhs_value_type, rhs_value_type))
+        }
         (
             Dictionary(_lhs_index_type, lhs_value_type),
             Dictionary(_rhs_index_type, rhs_value_type),
@@ -956,6 +968,20 @@ mod tests {
             Some(Int32)
         );
 
+        // Dictionaries of the same values are kept, with the wider keys
+        let lhs_type = Dictionary(Box::new(Int8), Box::new(Utf8));
+        let rhs_type = Dictionary(Box::new(Int32), Box::new(Utf8));
+        assert_eq!(
+            dictionary_coercion(&lhs_type, &rhs_type, true),
+            Some(rhs_type.clone())
+        );
+        assert_eq!(dictionary_coercion(&lhs_type, &rhs_type, false), Some(Utf8));
+        let rhs_type = Dictionary(Box::new(UInt8), Box::new(Utf8));
+        assert_eq!(
+            dictionary_coercion(&lhs_type, &rhs_type, true),
+            Some(Dictionary(Box::new(Int16), Box::new(Utf8)))
+        );
+
         // Since we can coerce values of Int16 to Utf8 can support this
         let lhs_type = Dictionary(Box::new(Int8), Box::new(Utf8));
         let rhs_type = Dictionary(Box::new(Int8), Box::new(Int16));
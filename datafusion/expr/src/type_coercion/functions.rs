@@ -306,6 +306,14 @@ fn coerced_from<'a>(
         {
             Some(type_into.clone())
         }
+        Date32
+            if matches!(
+                type_from,
+                Null | Date64 | Timestamp(_, _) | Utf8 | LargeUtf8
+            ) =>
+        {
+            Some(type_into.clone())
+        }
         Interval(_) if matches!(type_from, Utf8 | LargeUtf8) => Some(type_into.clone()),
         // Any type can be coerced into strings
         Utf8 | LargeUtf8 => Some(type_into.clone()),
@@ -27,7 +27,7 @@ use crate::logical_plan::Aggregate;
 use crate::signature::{Signature, TypeSignature};
 use crate::{
     and, BinaryExpr, Cast, Expr, ExprSchemable, Filter, GroupingSet, LogicalPlan,
-    Operator, TryCast,
+    Operator, TryCast, WildcardOptions,
 };
 
 use arrow::datatypes::{DataType, TimeUnit};
@@ -35,10 +35,10 @@ use datafusion_common::tree_node::{TreeNode, VisitRecursion};
 use datafusion_common::utils::get_at_indices;
 use datafusion_common::{
     internal_err, plan_datafusion_err, plan_err, Column, DFField, DFSchema, DFSchemaRef,
-    DataFusionError, Result, ScalarValue, TableReference,
+    DataFusionError, OwnedTableReference, Result, ScalarValue, TableReference,
 };
 
-use sqlparser::ast::{ExceptSelectItem, ExcludeSelectItem, WildcardAdditionalOptions};
+use sqlparser::ast::{ExceptSelectItem, ExcludeSelectItem, RenameSelectItem};
 
 ///  The value to which `COUNT(*)` is expanded to in
 ///  `COUNT(<constant>)` expressions
@@ -380,7 +380,7 @@ fn get_exprs_except_skipped(
 pub fn expand_wildcard(
     schema: &DFSchema,
     plan: &LogicalPlan,
-    wildcard_options: Option<&WildcardAdditionalOptions>,
+    wildcard_options: Option<&WildcardOptions>,
 ) -> Result<Vec<Expr>> {
     let using_columns = plan.using_columns()?;
     let mut columns_to_skip = using_columns
@@ -404,26 +404,28 @@ pub fn expand_wildcard(
                 .collect::<Vec<_>>()
         })
         .collect::<HashSet<_>>();
-    let excluded_columns = if let Some(WildcardAdditionalOptions {
-        opt_exclude,
-        opt_except,
-        ..
+    let excluded_columns = if let Some(WildcardOptions {
+        exclude, except, ..
     }) = wildcard_options
     {
-        get_excluded_columns(opt_exclude.as_ref(), opt_except.as_ref(), schema, &None)?
+        get_excluded_columns(exclude.as_ref(), except.as_ref(), schema, &None)?
     } else {
         vec![]
     };
     // Add each excluded `Column` to columns_to_skip
     columns_to_skip.extend(excluded_columns);
-    Ok(get_exprs_except_skipped(schema, columns_to_skip))
+    let exprs = get_exprs_except_skipped(schema, columns_to_skip);
+    match wildcard_options {
+        Some(options) => replace_and_rename_columns(exprs, options),
+        None => Ok(exprs),
+    }
 }
 
 /// Resolves an `Expr::Wildcard` to a collection of qualified `Expr::Column`'s.
 pub fn expand_qualified_wildcard(
     qualifier: &str,
     schema: &DFSchema,
-    wildcard_options: Option<&WildcardAdditionalOptions>,
+    wildcard_options: Option<&WildcardOptions>,
 ) -> Result<Vec<Expr>> {
     let qualifier = TableReference::from(qualifier);
     let qualified_indices = schema.fields_indices_with_qualified(&qualifier);
@@ -438,25 +440,70 @@ pub fn expand_qualified_wildcard(
         DFSchema::new_with_metadata(qualified_fields, schema.metadata().clone())?
             // We can use the functional dependencies as is, since it only stores indices:
             .with_functional_dependencies(projected_func_dependencies)?;
-    let excluded_columns = if let Some(WildcardAdditionalOptions {
-        opt_exclude,
-        opt_except,
-        ..
+    let excluded_columns = if let Some(WildcardOptions {
+        exclude, except, ..
     }) = wildcard_options
     {
-        get_excluded_columns(
-            opt_exclude.as_ref(),
-            opt_except.as_ref(),
-            schema,
-            &Some(qualifier),
-        )?
+        get_excluded_columns(exclude.as_ref(), except.as_ref(), schema, &Some(qualifier))?
     } else {
         vec![]
     };
     // Add each excluded `Column` to columns_to_skip
     let mut columns_to_skip = HashSet::new();
     columns_to_skip.extend(excluded_columns);
-    Ok(get_exprs_except_skipped(&qualified_schema, columns_to_skip))
+    let exprs = get_exprs_except_skipped(&qualified_schema, columns_to_skip);
+    match wildcard_options {
+        Some(options) => replace_and_rename_columns(exprs, options),
+        None => Ok(exprs),
+    }
+}
+
+/// Applies the `REPLACE` and `RENAME` wildcard options to the columns
+/// a wildcard expands to
+/// SELECT * REPLACE(col1 + 1 AS col1) RENAME(col2 AS c), would return
+/// `vec![col1 + 1 AS col1, col2 AS c]`
+fn replace_and_rename_columns(
+    exprs: Vec<Expr>,
+    options: &WildcardOptions,
+) -> Result<Vec<Expr>> {
+    let renames = match &options.rename {
+        Some(RenameSelectItem::Single(rename)) => vec![rename],
+        Some(RenameSelectItem::Multiple(renames)) => renames.iter().collect(),
+        None => vec![],
+    };
+    for rename in &renames {
+        let renamed_column = exprs
+            .iter()
+            .any(|expr| matches!(expr, Expr::Column(c) if c.name == rename.ident.value));
+        if !renamed_column {
+            return plan_err!(
+                "RENAME references column {} which is not part of the wildcard",
+                rename.ident
+            );
+        }
+    }
+
+    Ok(exprs
+        .into_iter()
+        .map(|expr| {
+            let Expr::Column(Column { name, .. }) = &expr else {
+                return expr;
+            };
+            let name = name.clone();
+            let expr = match options
+                .replace
+                .as_ref()
+                .and_then(|replace| replace.replacement(&name))
+            {
+                Some(replacement) => replacement.clone().alias(&name),
+                None => expr,
+            };
+            match renames.iter().find(|rename| rename.ident.value == name) {
+                Some(rename) => expr.unalias().alias(&rename.alias.value),
+                None => expr,
+            }
+        })
+        .collect())
 }
 
 /// (expr, "is the SortExpr for window (either comes from PARTITION BY or ORDER BY columns)")
@@ -1229,6 +1276,24 @@ pub fn merge_schema(inputs: Vec<&LogicalPlan>) -> DFSchema {
     }
 }
 
+/// Returns the name of the relation that `plan` reads, looking through
+/// projections, filters, sorts and limits down to a table scan or subquery
+/// alias, or `None` if `plan` reads more than one relation.
+///
+/// This is the name that optimizer hints, such as `BROADCAST(t)`, use to
+/// refer to the inputs of joins.
+pub fn relation_name(plan: &LogicalPlan) -> Option<&OwnedTableReference> {
+    match plan {
+        LogicalPlan::TableScan(scan) => Some(&scan.table_name),
+        LogicalPlan::SubqueryAlias(alias) => Some(&alias.alias),
+        LogicalPlan::Projection(_)
+        | LogicalPlan::Filter(_)
+        | LogicalPlan::Sort(_)
+        | LogicalPlan::Limit(_) => relation_name(plan.inputs()[0]),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,17 +129,17 @@ impl TreeNodeRewriter for CountWildcardRewriter {
                 order_by,
                 window_frame,
             }) if args.len() == 1 => match args[0] {
-                Expr::Wildcard { qualifier: None } => {
-                    Expr::WindowFunction(expr::WindowFunction {
-                        fun: expr::WindowFunctionDefinition::AggregateFunction(
-                            aggregate_function::AggregateFunction::Count,
-                        ),
-                        args: vec![lit(COUNT_STAR_EXPANSION)],
-                        partition_by,
-                        order_by,
-                        window_frame,
-                    })
-                }
+                Expr::Wildcard {
+                    qualifier: None, ..
+                } => Expr::WindowFunction(expr::WindowFunction {
+                    fun: expr::WindowFunctionDefinition::AggregateFunction(
+                        aggregate_function::AggregateFunction::Count,
+                    ),
+                    args: vec![lit(COUNT_STAR_EXPANSION)],
+                    partition_by,
+                    order_by,
+                    window_frame,
+                }),
 
                 _ => old_expr,
             },
@@ -153,15 +153,15 @@ impl TreeNodeRewriter for CountWildcardRewriter {
                 filter,
                 order_by,
             }) if args.len() == 1 => match args[0] {
-                Expr::Wildcard { qualifier: None } => {
-                    Expr::AggregateFunction(AggregateFunction::new(
-                        aggregate_function::AggregateFunction::Count,
-                        vec![lit(COUNT_STAR_EXPANSION)],
-                        distinct,
-                        filter,
-                        order_by,
-                    ))
-                }
+                Expr::Wildcard {
+                    qualifier: None, ..
+                } => Expr::AggregateFunction(AggregateFunction::new(
+                    aggregate_function::AggregateFunction::Count,
+                    vec![lit(COUNT_STAR_EXPANSION)],
+                    distinct,
+                    filter,
+                    order_by,
+                )),
                 _ => old_expr,
             },
 
@@ -0,0 +1,299 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Analyzer rule planning the gap filling of aggregations grouped by
+//! `date_bin_gapfill`
+
+use std::sync::Arc;
+
+use crate::analyzer::AnalyzerRule;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{plan_err, DataFusionError, Result};
+use datafusion_expr::expr::{Alias, ScalarFunction, ScalarFunctionDefinition};
+use datafusion_expr::{
+    Aggregate, BuiltinScalarFunction, Expr, Extension, FillStrategy, Filter, GapFill,
+    LogicalPlan, Projection,
+};
+
+/// Plans a [`GapFill`] above aggregations grouped by `date_bin_gapfill`.
+///
+/// `date_bin_gapfill` in the `GROUP BY` is replaced by `date_bin`, and the
+/// `locf` and `interpolate` functions in the projection above the
+/// aggregation choose how the aggregate columns are filled:
+///
+/// ```text
+/// Projection: date_bin_gapfill(..) AS minute, locf(AVG(t.temp))
+///   Aggregate: groupBy=[[date_bin_gapfill(.., t.time)]], aggr=[[AVG(t.temp)]]
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// Projection: date_bin_gapfill(..) AS minute, AVG(t.temp) AS locf(AVG(t.temp))
+///   GapFill: series=[], time=date_bin_gapfill(..), stride=.., fill=[locf(AVG(t.temp))]
+///     Aggregate: groupBy=[[date_bin(.., t.time) AS date_bin_gapfill(..)]], aggr=[[AVG(t.temp)]]
+/// ```
+#[derive(Default)]
+pub struct HandleGapFill {}
+
+impl HandleGapFill {
+    pub fn new() -> Self {
+        HandleGapFill {}
+    }
+}
+
+impl AnalyzerRule for HandleGapFill {
+    fn analyze(&self, plan: LogicalPlan, _: &ConfigOptions) -> Result<LogicalPlan> {
+        plan.transform_up(&analyze_internal)
+    }
+
+    fn name(&self) -> &str {
+        "handle_gap_fill"
+    }
+}
+
+fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+    match plan {
+        LogicalPlan::Aggregate(aggregate) => rewrite_aggregate(aggregate),
+        LogicalPlan::Projection(projection) => rewrite_projection(projection),
+        plan => Ok(Transformed::No(plan)),
+    }
+}
+
+/// Returns the arguments of `expr` if it calls `date_bin_gapfill`
+fn date_bin_gapfill_args(expr: &Expr) -> Option<&Vec<Expr>> {
+    match expr {
+        Expr::Alias(Alias { expr, .. }) => date_bin_gapfill_args(expr),
+        Expr::ScalarFunction(ScalarFunction {
+            func_def:
+                ScalarFunctionDefinition::BuiltIn(BuiltinScalarFunction::DateBinGapfill),
+            args,
+        }) => Some(args),
+        _ => None,
+    }
+}
+
+fn rewrite_aggregate(aggregate: Aggregate) -> Result<Transformed<LogicalPlan>> {
+    let gapfill_indices = aggregate
+        .group_expr
+        .iter()
+        .enumerate()
+        .filter(|(_, expr)| date_bin_gapfill_args(expr).is_some())
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let time_index = match gapfill_indices.as_slice() {
+        [] => return Ok(Transformed::No(LogicalPlan::Aggregate(aggregate))),
+        [time_index] => *time_index,
+        _ => return plan_err!("Only one date_bin_gapfill is supported in GROUP BY"),
+    };
+
+    let Aggregate {
+        input,
+        mut group_expr,
+        aggr_expr,
+        ..
+    } = aggregate;
+    let gapfill = &group_expr[time_index];
+    let args = date_bin_gapfill_args(gapfill).cloned().unwrap_or_default();
+    let Some(stride) = args.first().cloned() else {
+        return plan_err!("date_bin_gapfill expects a stride and a time column");
+    };
+    // keep the name of the group so that the plans above still refer to it
+    let name = gapfill.display_name()?;
+    group_expr[time_index] =
+        Expr::ScalarFunction(ScalarFunction::new(BuiltinScalarFunction::DateBin, args))
+            .alias(name);
+    let aggregate = Aggregate::try_new(input, group_expr, aggr_expr)?;
+
+    let group_len = aggregate.group_expr_len()?;
+    let columns = aggregate
+        .schema
+        .fields()
+        .iter()
+        .map(|field| Expr::Column(field.qualified_column()))
+        .collect::<Vec<_>>();
+    let time_expr = columns[time_index].clone();
+    let series_expr = columns[..group_len]
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != time_index)
+        .map(|(_, expr)| expr.clone())
+        .collect();
+    let fill_expr = columns[group_len..].to_vec();
+
+    let gap_fill = GapFill::try_new(
+        Arc::new(LogicalPlan::Aggregate(aggregate)),
+        series_expr,
+        time_expr,
+        stride,
+        fill_expr,
+    )?;
+    Ok(Transformed::Yes(LogicalPlan::Extension(Extension {
+        node: Arc::new(gap_fill),
+    })))
+}
+
+/// Returns the fill strategy and the argument of `expr` if it calls `locf`
+/// or `interpolate`
+fn fill_function(expr: &Expr) -> Option<(FillStrategy, &Expr)> {
+    match expr {
+        Expr::ScalarFunction(ScalarFunction {
+            func_def: ScalarFunctionDefinition::BuiltIn(fun),
+            args,
+        }) if args.len() == 1 => match fun {
+            BuiltinScalarFunction::Locf => Some((FillStrategy::PrevValue, &args[0])),
+            BuiltinScalarFunction::Interpolate => {
+                Some((FillStrategy::LinearInterpolate, &args[0]))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn rewrite_projection(projection: Projection) -> Result<Transformed<LogicalPlan>> {
+    let mut strategies = vec![];
+    let mut exprs = Vec::with_capacity(projection.expr.len());
+    for expr in &projection.expr {
+        let new_expr = match expr {
+            Expr::Alias(alias) => fill_function(&alias.expr).map(|(strategy, column)| {
+                strategies.push((column.clone(), strategy));
+                Expr::Alias(Alias {
+                    expr: Box::new(column.clone()),
+                    ..alias.clone()
+                })
+            }),
+            expr => match fill_function(expr) {
+                Some((strategy, column)) => {
+                    strategies.push((column.clone(), strategy));
+                    Some(column.clone().alias(expr.display_name()?))
+                }
+                None => None,
+            },
+        };
+        exprs.push(new_expr.unwrap_or_else(|| expr.clone()));
+    }
+    if strategies.is_empty() {
+        return Ok(Transformed::No(LogicalPlan::Projection(projection)));
+    }
+
+    match with_fill_strategies(&projection.input, &strategies)? {
+        Some(input) => Ok(Transformed::Yes(LogicalPlan::Projection(
+            Projection::try_new(exprs, Arc::new(input))?,
+        ))),
+        None => plan_err!(
+            "locf and interpolate can only be used in aggregations grouped by date_bin_gapfill"
+        ),
+    }
+}
+
+/// Returns `plan` with the [`GapFill`] below its filters filling the
+/// columns with `strategies`, or `None` if there is no such [`GapFill`]
+fn with_fill_strategies(
+    plan: &LogicalPlan,
+    strategies: &[(Expr, FillStrategy)],
+) -> Result<Option<LogicalPlan>> {
+    match plan {
+        // the HAVING clause
+        LogicalPlan::Filter(filter) => with_fill_strategies(&filter.input, strategies)?
+            .map(|input| {
+                Filter::try_new(filter.predicate.clone(), Arc::new(input))
+                    .map(LogicalPlan::Filter)
+            })
+            .transpose(),
+        LogicalPlan::Extension(extension) => {
+            let Some(gap_fill) = extension.node.as_any().downcast_ref::<GapFill>() else {
+                return Ok(None);
+            };
+            let mut gap_fill = gap_fill.clone();
+            for (column, strategy) in strategies {
+                gap_fill = gap_fill.with_fill_strategy(column, *strategy)?;
+            }
+            Ok(Some(LogicalPlan::Extension(Extension {
+                node: Arc::new(gap_fill),
+            })))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion_common::ScalarValue;
+    use datafusion_expr::{
+        avg, col, date_bin_gapfill, interpolate, lit, locf, max, table_scan,
+        LogicalPlanBuilder,
+    };
+
+    fn scan() -> Result<LogicalPlan> {
+        let schema = Schema::new(vec![
+            Field::new("region", DataType::Utf8, false),
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("temp", DataType::Float64, true),
+        ]);
+        table_scan(Some("t"), &schema, None)?.build()
+    }
+
+    fn stride() -> Expr {
+        lit(ScalarValue::new_interval_mdn(0, 0, 60_000_000_000))
+    }
+
+    #[test]
+    fn gap_fill_aggregate() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(scan()?)
+            .aggregate(
+                vec![col("region"), date_bin_gapfill(vec![stride(), col("time")])],
+                vec![avg(col("temp")), max(col("temp"))],
+            )?
+            .project(vec![
+                col("region"),
+                locf(col("AVG(t.temp)")),
+                interpolate(col("MAX(t.temp)")).alias("m"),
+            ])?
+            .build()?;
+
+        let expected = "Projection: t.region, AVG(t.temp) AS locf(AVG(t.temp)), MAX(t.temp) AS m\
+        \n  GapFill: series=[t.region], time=date_bin_gapfill(IntervalMonthDayNano(\"60000000000\"),t.time), stride=IntervalMonthDayNano(\"60000000000\"), fill=[locf(AVG(t.temp)), interpolate(MAX(t.temp))]\
+        \n    Aggregate: groupBy=[[t.region, date_bin(IntervalMonthDayNano(\"60000000000\"), t.time) AS date_bin_gapfill(IntervalMonthDayNano(\"60000000000\"),t.time)]], aggr=[[AVG(t.temp), MAX(t.temp)]]\
+        \n      TableScan: t";
+        assert_analyzed_plan_eq(Arc::new(HandleGapFill::new()), &plan, expected)
+    }
+
+    #[test]
+    fn fill_function_without_gap_fill() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(scan()?)
+            .aggregate(vec![col("region")], vec![avg(col("temp"))])?
+            .project(vec![col("region"), locf(col("AVG(t.temp)"))])?
+            .build()?;
+
+        let err = HandleGapFill::new()
+            .analyze(plan, &ConfigOptions::default())
+            .unwrap_err();
+        assert!(err.to_string().contains(
+            "locf and interpolate can only be used in aggregations grouped by date_bin_gapfill"
+        ));
+        Ok(())
+    }
+}
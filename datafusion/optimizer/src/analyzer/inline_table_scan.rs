@@ -26,7 +26,7 @@ use datafusion_common::Result;
 use datafusion_expr::expr::Exists;
 use datafusion_expr::expr::InSubquery;
 use datafusion_expr::{
-    logical_plan::LogicalPlan, Expr, Filter, LogicalPlanBuilder, TableScan,
+    logical_plan::LogicalPlan, wildcard, Expr, Filter, LogicalPlanBuilder, TableScan,
 };
 
 /// Analyzed rule that inlines TableScan that provide a [`LogicalPlan`]
@@ -126,7 +126,7 @@ fn generate_projection_expr(
             ));
         }
     } else {
-        exprs.push(Expr::Wildcard { qualifier: None });
+        exprs.push(wildcard());
     }
     Ok(exprs)
 }
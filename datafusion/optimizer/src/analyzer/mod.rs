@@ -16,16 +16,22 @@
 // under the License.
 
 pub mod count_wildcard_rule;
+pub mod gap_fill;
 pub mod inline_table_scan;
+pub mod plan_limits;
 pub mod rewrite_expr;
 pub mod subquery;
 pub mod type_coercion;
+pub mod window_subquery;
 
 use crate::analyzer::count_wildcard_rule::CountWildcardRule;
+use crate::analyzer::gap_fill::HandleGapFill;
 use crate::analyzer::inline_table_scan::InlineTableScan;
+use crate::analyzer::plan_limits::CheckPlanLimits;
 
 use crate::analyzer::subquery::check_subquery_expr;
 use crate::analyzer::type_coercion::TypeCoercion;
+use crate::analyzer::window_subquery::ExtractWindowSubquery;
 use crate::utils::log_plan;
 use datafusion_common::config::ConfigOptions;
 use datafusion_common::tree_node::{TreeNode, VisitRecursion};
@@ -74,12 +80,17 @@ impl Analyzer {
     /// Create a new analyzer using the recommended list of rules
     pub fn new() -> Self {
         let rules: Vec<Arc<dyn AnalyzerRule + Send + Sync>> = vec![
+            // CheckPlanLimits runs first, to reject overly complex queries
+            // before spending time on them
+            Arc::new(CheckPlanLimits::new()),
             Arc::new(InlineTableScan::new()),
             // OperatorToFunction should be run before TypeCoercion, since it rewrite based on the argument types (List or Scalar),
             // and TypeCoercion may cast the argument types from Scalar to List.
             Arc::new(OperatorToFunction::new()),
             Arc::new(TypeCoercion::new()),
+            Arc::new(HandleGapFill::new()),
             Arc::new(CountWildcardRule::new()),
+            Arc::new(ExtractWindowSubquery::new()),
         ];
         Self::with_rules(rules)
     }
@@ -0,0 +1,303 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::analyzer::AnalyzerRule;
+use datafusion_common::config::{ConfigOptions, PlanLimitsOptions};
+use datafusion_common::tree_node::{TreeNode, TreeNodeVisitor, VisitRecursion};
+use datafusion_common::{DataFusionError, Result};
+use datafusion_expr::expr::{Exists, InSubquery};
+use datafusion_expr::{Expr, LogicalPlan};
+
+/// Rejects plans exceeding the limits of `datafusion.limits` with a
+/// [`DataFusionError::QueryTooComplex`] error, before the other analyzer
+/// rules and the optimizer spend time on them.
+///
+/// The rule checks
+///
+/// * the depth of the plan, counted separately within each subquery
+/// * the depth of each expression tree
+/// * the number of joins, including the joins of subqueries
+/// * the number of subqueries nested within each other
+#[derive(Default)]
+pub struct CheckPlanLimits {}
+
+impl CheckPlanLimits {
+    pub fn new() -> Self {
+        CheckPlanLimits {}
+    }
+}
+
+impl AnalyzerRule for CheckPlanLimits {
+    fn analyze(&self, plan: LogicalPlan, config: &ConfigOptions) -> Result<LogicalPlan> {
+        let limits = &config.limits;
+        if limits.max_plan_depth.is_none()
+            && limits.max_expr_depth.is_none()
+            && limits.max_joins.is_none()
+            && limits.max_subquery_depth.is_none()
+        {
+            return Ok(plan);
+        }
+
+        plan.visit(&mut PlanLimitsVisitor {
+            limits,
+            plan_depth: 0,
+            subquery_depth: 0,
+            joins: 0,
+        })?;
+        Ok(plan)
+    }
+
+    fn name(&self) -> &str {
+        "check_plan_limits"
+    }
+}
+
+/// Visits a plan and its subqueries, checking them against `limits`
+struct PlanLimitsVisitor<'a> {
+    limits: &'a PlanLimitsOptions,
+    /// Depth of the visited node within the current (sub)query, starting at
+    /// 1 for its root
+    plan_depth: usize,
+    /// Number of subqueries the visited node is nested in
+    subquery_depth: usize,
+    /// Number of joins visited so far
+    joins: usize,
+}
+
+impl TreeNodeVisitor for PlanLimitsVisitor<'_> {
+    type N = LogicalPlan;
+
+    fn pre_visit(&mut self, plan: &LogicalPlan) -> Result<VisitRecursion> {
+        self.plan_depth += 1;
+        check_limit(
+            self.limits.max_plan_depth,
+            self.plan_depth,
+            "plan depth",
+            "max_plan_depth",
+        )?;
+
+        if matches!(plan, LogicalPlan::Join(_) | LogicalPlan::CrossJoin(_)) {
+            self.joins += 1;
+            check_limit(
+                self.limits.max_joins,
+                self.joins,
+                "number of joins",
+                "max_joins",
+            )?;
+        }
+
+        for expr in plan.expressions() {
+            self.check_expr(&expr, 1)?;
+        }
+        Ok(VisitRecursion::Continue)
+    }
+
+    fn post_visit(&mut self, _plan: &LogicalPlan) -> Result<VisitRecursion> {
+        self.plan_depth -= 1;
+        Ok(VisitRecursion::Continue)
+    }
+}
+
+impl PlanLimitsVisitor<'_> {
+    /// Checks `expr`, at `depth` in its expression tree, its children and the
+    /// subqueries it contains
+    fn check_expr(&mut self, expr: &Expr, depth: usize) -> Result<()> {
+        check_limit(
+            self.limits.max_expr_depth,
+            depth,
+            "expression depth",
+            "max_expr_depth",
+        )?;
+
+        match expr {
+            Expr::Exists(Exists { subquery, .. })
+            | Expr::InSubquery(InSubquery { subquery, .. })
+            | Expr::ScalarSubquery(subquery) => {
+                self.check_subquery(&subquery.subquery)?;
+            }
+            _ => {}
+        }
+
+        expr.apply_children(&mut |child| {
+            self.check_expr(child, depth + 1)?;
+            Ok(VisitRecursion::Continue)
+        })?;
+        Ok(())
+    }
+
+    /// Checks the plan of a subquery, whose depth is counted from its root
+    fn check_subquery(&mut self, subquery: &LogicalPlan) -> Result<()> {
+        self.subquery_depth += 1;
+        check_limit(
+            self.limits.max_subquery_depth,
+            self.subquery_depth,
+            "subquery nesting",
+            "max_subquery_depth",
+        )?;
+
+        let plan_depth = std::mem::take(&mut self.plan_depth);
+        subquery.visit(self)?;
+        self.plan_depth = plan_depth;
+        self.subquery_depth -= 1;
+        Ok(())
+    }
+}
+
+/// Returns a [`DataFusionError::QueryTooComplex`] error if `value` of the
+/// property `what` exceeds `limit`, set by `datafusion.limits.{option}`
+fn check_limit(
+    limit: Option<usize>,
+    value: usize,
+    what: &str,
+    option: &str,
+) -> Result<()> {
+    match limit {
+        Some(limit) if value > limit => Err(DataFusionError::QueryTooComplex(format!(
+            "{what} exceeds {limit}, the limit set by datafusion.limits.{option}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use datafusion_expr::{col, exists, lit, scalar_subquery, LogicalPlanBuilder};
+    use std::sync::Arc;
+
+    fn analyze(plan: LogicalPlan, options: &ConfigOptions) -> Result<LogicalPlan> {
+        CheckPlanLimits::new().analyze(plan, options)
+    }
+
+    fn options(key: &str, value: usize) -> ConfigOptions {
+        let mut options = ConfigOptions::default();
+        options
+            .set(&format!("datafusion.limits.{key}"), &value.to_string())
+            .unwrap();
+        options
+    }
+
+    fn assert_too_complex(result: Result<LogicalPlan>, expected: &str) {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, DataFusionError::QueryTooComplex(_)),
+            "unexpected error: {err}"
+        );
+        assert_eq!(err.to_string(), format!("Query too complex: {expected}"));
+    }
+
+    /// `depth` subqueries nested in filters, the innermost one scanning `t0`
+    fn nested_subqueries(depth: usize) -> Result<LogicalPlan> {
+        let mut plan = test_table_scan_with_name("t0")?;
+        for i in 1..=depth {
+            plan = LogicalPlanBuilder::from(test_table_scan_with_name(&format!("t{i}"))?)
+                .filter(exists(Arc::new(plan)))?
+                .build()?;
+        }
+        Ok(plan)
+    }
+
+    #[test]
+    fn no_limits() -> Result<()> {
+        let plan = nested_subqueries(3)?;
+        analyze(plan, &ConfigOptions::default())?;
+        Ok(())
+    }
+
+    #[test]
+    fn max_joins() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .cross_join(test_table_scan_with_name("t2")?)?
+            .cross_join(test_table_scan_with_name("t3")?)?
+            .build()?;
+
+        analyze(plan.clone(), &options("max_joins", 2))?;
+        assert_too_complex(
+            analyze(plan, &options("max_joins", 1)),
+            "number of joins exceeds 1, the limit set by datafusion.limits.max_joins",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn max_joins_in_subquery() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+            .cross_join(test_table_scan_with_name("t3")?)?
+            .project(vec![col("t2.a")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .cross_join(test_table_scan_with_name("t4")?)?
+            .filter(col("t1.a").eq(scalar_subquery(Arc::new(subquery))))?
+            .build()?;
+
+        analyze(plan.clone(), &options("max_joins", 2))?;
+        assert_too_complex(
+            analyze(plan, &options("max_joins", 1)),
+            "number of joins exceeds 1, the limit set by datafusion.limits.max_joins",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn max_expr_depth() -> Result<()> {
+        // ((a = 1 OR a = 2) OR a = 3), 4 levels deep
+        let predicate = col("a")
+            .eq(lit(1))
+            .or(col("a").eq(lit(2)))
+            .or(col("a").eq(lit(3)));
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(predicate)?
+            .build()?;
+
+        analyze(plan.clone(), &options("max_expr_depth", 4))?;
+        assert_too_complex(
+            analyze(plan, &options("max_expr_depth", 3)),
+            "expression depth exceeds 3, the limit set by datafusion.limits.max_expr_depth",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn max_plan_depth() -> Result<()> {
+        // Limit, Filter, TableScan
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("a").eq(lit(1)))?
+            .limit(0, Some(10))?
+            .build()?;
+
+        analyze(plan.clone(), &options("max_plan_depth", 3))?;
+        assert_too_complex(
+            analyze(plan, &options("max_plan_depth", 2)),
+            "plan depth exceeds 2, the limit set by datafusion.limits.max_plan_depth",
+        );
+
+        // the depth of subqueries is counted from their root
+        analyze(nested_subqueries(3)?, &options("max_plan_depth", 2))?;
+        Ok(())
+    }
+
+    #[test]
+    fn max_subquery_depth() -> Result<()> {
+        analyze(nested_subqueries(2)?, &options("max_subquery_depth", 2))?;
+        assert_too_complex(
+            analyze(nested_subqueries(3)?, &options("max_subquery_depth", 2)),
+            "subquery nesting exceeds 2, the limit set by datafusion.limits.max_subquery_depth",
+        );
+        Ok(())
+    }
+}
@@ -143,6 +143,20 @@ fn check_inner_plan(
     }
     // We want to support as many operators as possible inside the correlated subquery
     match inner_plan {
+        LogicalPlan::Aggregate(Aggregate {
+            group_expr, input, ..
+        }) if is_scalar && group_expr.is_empty() => match input.as_ref() {
+            // Correlated predicates directly below an ungrouped aggregation that
+            // can not be pulled up over it are evaluated against the distinct
+            // values of the outer columns instead (see `ScalarSubqueryToJoin`),
+            // which requires them to be the only correlated expressions.
+            LogicalPlan::Filter(Filter {
+                predicate, input, ..
+            }) if has_non_pullup_correlation(predicate) => {
+                check_inner_plan(input, is_scalar, true, false)
+            }
+            _ => check_inner_plan(input, is_scalar, true, can_contain_outer_ref),
+        },
         LogicalPlan::Aggregate(_) => {
             inner_plan.apply_children(&mut |plan| {
                 check_inner_plan(plan, is_scalar, true, can_contain_outer_ref)?;
@@ -297,6 +311,13 @@ fn get_correlated_expressions(inner_plan: &LogicalPlan) -> Result<Vec<Expr>> {
     Ok(exprs)
 }
 
+/// Check whether the predicate has correlated conjuncts that can not pull up over the aggregation
+pub(crate) fn has_non_pullup_correlation(predicate: &Expr) -> bool {
+    split_conjunction(predicate)
+        .into_iter()
+        .any(|expr| expr.contains_outer() && !can_pullup_over_aggregation(expr))
+}
+
 /// Check whether the expression can pull up over the aggregation without change the result of the query
 fn can_pullup_over_aggregation(expr: &Expr) -> bool {
     if let Expr::BinaryExpr(BinaryExpr {
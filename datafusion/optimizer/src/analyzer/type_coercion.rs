@@ -24,8 +24,8 @@ use arrow::datatypes::{DataType, IntervalUnit};
 use datafusion_common::config::ConfigOptions;
 use datafusion_common::tree_node::{RewriteRecursion, TreeNodeRewriter};
 use datafusion_common::{
-    exec_err, internal_err, plan_datafusion_err, plan_err, DFSchema, DFSchemaRef,
-    DataFusionError, Result, ScalarValue,
+    internal_err, plan_datafusion_err, plan_err, DFSchema, DFSchemaRef, DataFusionError,
+    Result, ScalarValue,
 };
 use datafusion_expr::expr::{
     self, AggregateFunctionDefinition, Between, BinaryExpr, Case, Exists, InList,
@@ -38,10 +38,10 @@ use datafusion_expr::type_coercion::binary::{
     comparison_coercion, get_input_types, like_coercion,
 };
 use datafusion_expr::type_coercion::functions::data_types;
+use datafusion_expr::type_coercion::is_datetime;
 use datafusion_expr::type_coercion::other::{
     get_coerce_type_for_case_expression, get_coerce_type_for_list,
 };
-use datafusion_expr::type_coercion::{is_datetime, is_utf8_or_large_utf8};
 use datafusion_expr::utils::merge_schema;
 use datafusion_expr::{
     is_false, is_not_false, is_not_true, is_not_unknown, is_true, is_unknown,
@@ -427,10 +427,15 @@ fn coerce_scalar(target_type: &DataType, value: &ScalarValue) -> Result<ScalarVa
             if s.is_null() {
                 // Coerce `Null` values:
                 ScalarValue::try_from(target_type)
+            } else if s.data_type().is_numeric()
+                && target_type.is_numeric()
+                && &s.data_type() != target_type
+            {
+                // Numeric values of other types can be built with the
+                // DataFrame API, as opposed to the `Utf8` values we convert
+                // `sqlparser` outputs to
+                s.cast_to(target_type)
             } else {
-                // Values except `Utf8`/`Null` variants already have the right type
-                // (casted before) since we convert `sqlparser` outputs to `Utf8`
-                // for all possible cases. Therefore, we return a clone here.
                 Ok(s.clone())
             }
         }
@@ -451,7 +456,7 @@ fn coerce_scalar_range_aware(
         // If type coercion fails, check if the largest type in family works:
         if let Some(largest_type) = get_widest_type_in_family(target_type) {
             coerce_scalar(largest_type, value).map_or_else(
-                |_| exec_err!("Cannot cast {value:?} to {target_type:?}"),
+                |_| plan_err!("Cannot cast {value:?} to {target_type:?}"),
                 |_| ScalarValue::try_from(target_type),
             )
         } else {
@@ -479,15 +484,52 @@ fn coerce_frame_bound(
 ) -> Result<WindowFrameBound> {
     match bound {
         WindowFrameBound::Preceding(v) => {
-            coerce_scalar_range_aware(target_type, v).map(WindowFrameBound::Preceding)
+            coerce_frame_offset(target_type, v).map(WindowFrameBound::Preceding)
         }
         WindowFrameBound::CurrentRow => Ok(WindowFrameBound::CurrentRow),
         WindowFrameBound::Following(v) => {
-            coerce_scalar_range_aware(target_type, v).map(WindowFrameBound::Following)
+            coerce_frame_offset(target_type, v).map(WindowFrameBound::Following)
         }
     }
 }
 
+/// Coerces the window frame offset `value` to `target_type`, and checks that
+/// the result can be used with it: intervals are only compatible with
+/// interval targets (i.e. date and time `ORDER BY` expressions), and numbers
+/// with numeric ones.
+fn coerce_frame_offset(
+    target_type: &DataType,
+    value: &ScalarValue,
+) -> Result<ScalarValue> {
+    let offset = coerce_scalar_range_aware(target_type, value)?;
+    // Unbounded offsets are compatible with any type
+    if offset.is_null() || target_type == &DataType::Null {
+        return Ok(offset);
+    }
+    let offset_type = offset.data_type();
+    let compatible = match target_type {
+        DataType::Interval(_) => matches!(offset_type, DataType::Interval(_)),
+        t if t.is_numeric() => offset_type.is_numeric(),
+        _ => false,
+    };
+    if compatible {
+        Ok(offset)
+    } else {
+        plan_err!(
+            "Window frame offset {value} of type {} is not compatible with {target_type}",
+            value.data_type()
+        )
+    }
+}
+
+/// Returns true if either bound of `window_frame` has an offset, i.e. is
+/// neither unbounded nor the current row
+fn has_frame_offset(window_frame: &WindowFrame) -> bool {
+    [&window_frame.start_bound, &window_frame.end_bound]
+        .into_iter()
+        .any(|bound| !bound.is_unbounded() && bound != &WindowFrameBound::CurrentRow)
+}
+
 // Coerces the given `window_frame` to use appropriate natural types.
 // For example, ROWS and GROUPS frames use `UInt64` during calculations.
 fn coerce_window_frame(
@@ -503,16 +545,17 @@ fn coerce_window_frame(
     let target_type = match window_frame.units {
         WindowFrameUnits::Range => {
             if let Some(col_type) = current_types.first() {
-                if col_type.is_numeric()
-                    || is_utf8_or_large_utf8(col_type)
-                    || matches!(col_type, DataType::Null)
-                {
+                if col_type.is_numeric() || matches!(col_type, DataType::Null) {
                     col_type
                 } else if is_datetime(col_type) {
                     &DataType::Interval(IntervalUnit::MonthDayNano)
+                } else if !has_frame_offset(&window_frame) {
+                    // Frames without offsets only compare the ORDER BY values
+                    // for equality, so they do not depend on their type
+                    return Ok(window_frame);
                 } else {
-                    return internal_err!(
-                        "Cannot run range queries on datatype: {col_type:?}"
+                    return plan_err!(
+                        "RANGE window frame offsets are not supported for ORDER BY expressions of type {col_type}"
                     );
                 }
             } else {
@@ -746,7 +789,9 @@ mod test {
 
     use arrow::datatypes::Field;
     use datafusion_common::tree_node::TreeNode;
-    use datafusion_common::{DFField, DFSchema, DFSchemaRef, Result, ScalarValue};
+    use datafusion_common::{
+        assert_contains, DFField, DFSchema, DFSchemaRef, Result, ScalarValue,
+    };
     use datafusion_expr::expr::{self, InSubquery, Like, ScalarFunction};
     use datafusion_expr::{
         cast, col, concat, concat_ws, create_udaf, is_true, AccumulatorFactoryFunction,
@@ -758,11 +803,13 @@ mod test {
         lit,
         logical_plan::{EmptyRelation, Projection},
         Expr, LogicalPlan, ReturnTypeFunction, ScalarUDF, Signature, Volatility,
+        WindowFrame, WindowFrameBound, WindowFrameUnits,
     };
     use datafusion_physical_expr::expressions::AvgAccumulator;
 
     use crate::analyzer::type_coercion::{
-        cast_expr, coerce_case_expression, TypeCoercion, TypeCoercionRewriter,
+        cast_expr, coerce_case_expression, coerce_window_frame, TypeCoercion,
+        TypeCoercionRewriter,
     };
     use crate::test::assert_analyzed_plan_eq;
 
@@ -1583,4 +1630,85 @@ mod test {
         assert_analyzed_plan_eq(Arc::new(TypeCoercion::new()), &plan, expected)?;
         Ok(())
     }
+
+    #[test]
+    fn window_frame_offsets() -> Result<()> {
+        let schema = |data_type| empty_with_type(data_type).schema().clone();
+        let order_by = [col("a")];
+        let frame = |units, offset| WindowFrame {
+            units,
+            start_bound: WindowFrameBound::Preceding(offset),
+            end_bound: WindowFrameBound::CurrentRow,
+        };
+        let interval = ScalarValue::new_interval_dt(1, 0);
+
+        // offsets are coerced to the type of the ORDER BY expression
+        let coerced = coerce_window_frame(
+            frame(WindowFrameUnits::Range, ScalarValue::from("1")),
+            &schema(DataType::Int32),
+            &order_by,
+        )?;
+        assert_eq!(
+            coerced.start_bound,
+            WindowFrameBound::Preceding(ScalarValue::Int32(Some(1)))
+        );
+        let coerced = coerce_window_frame(
+            frame(WindowFrameUnits::Range, ScalarValue::Int64(Some(2))),
+            &schema(DataType::Float64),
+            &order_by,
+        )?;
+        assert_eq!(
+            coerced.start_bound,
+            WindowFrameBound::Preceding(ScalarValue::Float64(Some(2.0)))
+        );
+
+        // intervals are only compatible with date and time ORDER BY expressions
+        coerce_window_frame(
+            frame(WindowFrameUnits::Range, interval.clone()),
+            &schema(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+            &order_by,
+        )?;
+        let err = coerce_window_frame(
+            frame(WindowFrameUnits::Range, interval.clone()),
+            &schema(DataType::Int32),
+            &order_by,
+        )
+        .unwrap_err();
+        assert_contains!(err.to_string(), "is not compatible with Int32");
+        let err = coerce_window_frame(
+            frame(WindowFrameUnits::Range, ScalarValue::Int64(Some(1))),
+            &schema(DataType::Date32),
+            &order_by,
+        )
+        .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Window frame offset 1 of type Int64 is not compatible with Interval(MonthDayNano)"
+        );
+        let err = coerce_window_frame(
+            frame(WindowFrameUnits::Rows, interval),
+            &schema(DataType::Int32),
+            &order_by,
+        )
+        .unwrap_err();
+        assert_contains!(err.to_string(), "is not compatible with UInt64");
+
+        // unbounded frames do not depend on the type of the ORDER BY expression
+        let err = coerce_window_frame(
+            frame(WindowFrameUnits::Range, ScalarValue::from("1")),
+            &schema(DataType::Boolean),
+            &order_by,
+        )
+        .unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "RANGE window frame offsets are not supported for ORDER BY expressions of type Boolean"
+        );
+        coerce_window_frame(
+            frame(WindowFrameUnits::Range, ScalarValue::Null),
+            &schema(DataType::Boolean),
+            &order_by,
+        )?;
+        Ok(())
+    }
 }
@@ -0,0 +1,279 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::analyzer::AnalyzerRule;
+use datafusion_common::alias::AliasGenerator;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRewriter};
+use datafusion_common::Result;
+use datafusion_expr::expr::{Exists, InSubquery};
+use datafusion_expr::expr_rewriter::rewrite_preserving_name;
+use datafusion_expr::utils::inspect_expr_pre;
+use datafusion_expr::{Expr, LogicalPlan, LogicalPlanBuilder, Subquery};
+use std::sync::Arc;
+
+/// Moves subqueries out of window function expressions into a projection
+/// below the [`Window`](datafusion_expr::logical_plan::Window), so that they
+/// can be decorrelated like subqueries in the select list.
+///
+/// For example
+///
+/// ```text
+/// Window: SUM((<subquery>)) PARTITION BY [t.a]
+///   TableScan: t
+/// ```
+///
+/// is rewritten to
+///
+/// ```text
+/// Projection: t.a, ..., SUM((<subquery>))
+///   Window: SUM(__window_sq_1) AS SUM((<subquery>)) PARTITION BY [t.a]
+///     Projection: t.a, ..., (<subquery>) AS __window_sq_1
+///       TableScan: t
+/// ```
+#[derive(Default)]
+pub struct ExtractWindowSubquery {}
+
+impl ExtractWindowSubquery {
+    pub fn new() -> Self {
+        ExtractWindowSubquery {}
+    }
+}
+
+impl AnalyzerRule for ExtractWindowSubquery {
+    fn analyze(&self, plan: LogicalPlan, _: &ConfigOptions) -> Result<LogicalPlan> {
+        let alias_generator = AliasGenerator::new();
+        analyze_plan(plan, &alias_generator)
+    }
+
+    fn name(&self) -> &str {
+        "extract_window_subquery"
+    }
+}
+
+fn analyze_plan(
+    plan: LogicalPlan,
+    alias_generator: &AliasGenerator,
+) -> Result<LogicalPlan> {
+    plan.transform_up(&|plan| analyze_internal(plan, alias_generator))
+}
+
+fn analyze_internal(
+    plan: LogicalPlan,
+    alias_generator: &AliasGenerator,
+) -> Result<Transformed<LogicalPlan>> {
+    if !plan.expressions().iter().any(contains_subquery) {
+        return Ok(Transformed::No(plan));
+    }
+
+    // first rewrite the windows inside the subqueries of this plan
+    let exprs = plan
+        .expressions()
+        .into_iter()
+        .map(|expr| {
+            expr.transform_up(&|expr| analyze_subquery_expr(expr, alias_generator))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let inputs = plan.inputs().into_iter().cloned().collect::<Vec<_>>();
+    let plan = plan.with_new_exprs(exprs, &inputs)?;
+
+    let LogicalPlan::Window(window) = plan else {
+        return Ok(Transformed::Yes(plan));
+    };
+
+    // project the subqueries below the window, and replace them with
+    // references to the projected columns
+    let mut rewriter = SubqueryToColumn {
+        alias_generator,
+        subquery_exprs: vec![],
+    };
+    let window_expr = window
+        .window_expr
+        .into_iter()
+        .map(|expr| rewrite_preserving_name(expr, &mut rewriter))
+        .collect::<Result<Vec<_>>>()?;
+
+    let input_exprs = window
+        .input
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| Expr::Column(field.qualified_column()));
+    let output_exprs = window
+        .schema
+        .fields()
+        .iter()
+        .map(|field| Expr::Column(field.qualified_column()))
+        .collect::<Vec<_>>();
+
+    // the final projection removes the subquery columns from the output
+    let plan = LogicalPlanBuilder::from(window.input.as_ref().clone())
+        .project(input_exprs.chain(rewriter.subquery_exprs))?
+        .window(window_expr)?
+        .project(output_exprs)?
+        .build()?;
+    Ok(Transformed::Yes(plan))
+}
+
+/// Replaces subquery expressions with references to columns named by
+/// `alias_generator`, collecting the aliased subqueries
+struct SubqueryToColumn<'a> {
+    alias_generator: &'a AliasGenerator,
+    subquery_exprs: Vec<Expr>,
+}
+
+impl TreeNodeRewriter for SubqueryToColumn<'_> {
+    type N = Expr;
+
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        match expr {
+            Expr::ScalarSubquery(_) | Expr::Exists(_) | Expr::InSubquery(_) => {
+                let alias = self.alias_generator.next("__window_sq");
+                self.subquery_exprs.push(expr.alias(&alias));
+                Ok(Expr::Column(alias.into()))
+            }
+            _ => Ok(expr),
+        }
+    }
+}
+
+fn analyze_subquery_expr(
+    expr: Expr,
+    alias_generator: &AliasGenerator,
+) -> Result<Transformed<Expr>> {
+    let analyze_subquery = |subquery: Subquery| -> Result<Subquery> {
+        Ok(Subquery {
+            subquery: Arc::new(analyze_plan(
+                subquery.subquery.as_ref().clone(),
+                alias_generator,
+            )?),
+            outer_ref_columns: subquery.outer_ref_columns,
+        })
+    };
+    Ok(match expr {
+        Expr::ScalarSubquery(subquery) => {
+            Transformed::Yes(Expr::ScalarSubquery(analyze_subquery(subquery)?))
+        }
+        Expr::Exists(Exists { subquery, negated }) => Transformed::Yes(Expr::Exists(
+            Exists::new(analyze_subquery(subquery)?, negated),
+        )),
+        Expr::InSubquery(InSubquery {
+            expr,
+            subquery,
+            negated,
+        }) => Transformed::Yes(Expr::InSubquery(InSubquery::new(
+            expr,
+            analyze_subquery(subquery)?,
+            negated,
+        ))),
+        _ => Transformed::No(expr),
+    })
+}
+
+fn contains_subquery(expr: &Expr) -> bool {
+    let mut found = false;
+    inspect_expr_pre(expr, |expr| {
+        found |= matches!(
+            expr,
+            Expr::ScalarSubquery(_) | Expr::Exists(_) | Expr::InSubquery(_)
+        );
+        Ok(()) as Result<()>
+    })
+    // As the closure always returns Ok, this "can't" error
+    .expect("Unexpected error");
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use arrow::datatypes::DataType;
+    use datafusion_expr::{
+        col, expr, max, out_ref_col, scalar_subquery, AggregateFunction, WindowFrame,
+        WindowFunctionDefinition,
+    };
+
+    fn assert_plan_eq(plan: &LogicalPlan, expected: &str) -> Result<()> {
+        assert_analyzed_plan_eq(Arc::new(ExtractWindowSubquery::new()), plan, expected)
+    }
+
+    fn sum_window(arg: Expr) -> Expr {
+        Expr::WindowFunction(expr::WindowFunction::new(
+            WindowFunctionDefinition::AggregateFunction(AggregateFunction::Sum),
+            vec![arg],
+            vec![col("t1.a")],
+            vec![],
+            WindowFrame::new(false),
+        ))
+    }
+
+    #[test]
+    fn uncorrelated_subquery_in_window() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+                .aggregate(Vec::<Expr>::new(), vec![max(col("t2.a"))])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .window(vec![sum_window(scalar_subquery(subquery))])?
+            .build()?;
+
+        let expected = "Projection: t1.a, t1.b, t1.c, SUM(MAX(t2.a)) PARTITION BY [t1.a] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING\
+        \n  WindowAggr: windowExpr=[[SUM(__window_sq_1) PARTITION BY [t1.a] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS SUM(MAX(t2.a)) PARTITION BY [t1.a] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]\
+        \n    Projection: t1.a, t1.b, t1.c, (<subquery>) AS __window_sq_1\
+        \n      Subquery:\
+        \n        Aggregate: groupBy=[[]], aggr=[[MAX(t2.a)]]\
+        \n          TableScan: t2\
+        \n      TableScan: t1";
+        assert_plan_eq(&plan, expected)
+    }
+
+    #[test]
+    fn correlated_subquery_in_window() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+                .filter(out_ref_col(DataType::UInt32, "t1.b").eq(col("t2.b")))?
+                .aggregate(Vec::<Expr>::new(), vec![max(col("t2.a"))])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .window(vec![sum_window(scalar_subquery(subquery))])?
+            .build()?;
+
+        let expected = "Projection: t1.a, t1.b, t1.c, SUM(MAX(t2.a)) PARTITION BY [t1.a] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING\
+        \n  WindowAggr: windowExpr=[[SUM(__window_sq_1) PARTITION BY [t1.a] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS SUM(MAX(t2.a)) PARTITION BY [t1.a] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]\
+        \n    Projection: t1.a, t1.b, t1.c, (<subquery>) AS __window_sq_1\
+        \n      Subquery:\
+        \n        Aggregate: groupBy=[[]], aggr=[[MAX(t2.a)]]\
+        \n          Filter: outer_ref(t1.b) = t2.b\
+        \n            TableScan: t2\
+        \n      TableScan: t1";
+        assert_plan_eq(&plan, expected)
+    }
+
+    #[test]
+    fn window_without_subquery() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .window(vec![sum_window(col("t1.b"))])?
+            .build()?;
+
+        let expected = "WindowAggr: windowExpr=[[SUM(t1.b) PARTITION BY [t1.a] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]\
+        \n  TableScan: t1";
+        assert_plan_eq(&plan, expected)
+    }
+}
@@ -164,6 +164,11 @@ impl OptimizerRule for DecorrelatePredicateSubquery {
     fn apply_order(&self) -> Option<ApplyOrder> {
         Some(ApplyOrder::TopDown)
     }
+
+    /// `IN` and `EXISTS` subqueries can not be executed without this rule
+    fn is_optional(&self) -> bool {
+        false
+    }
 }
 
 /// Optimize the subquery to left-anti/left-semi join.
@@ -21,12 +21,12 @@ use std::sync::Arc;
 
 use crate::{utils, OptimizerConfig, OptimizerRule};
 
-use datafusion_common::{plan_err, DataFusionError, Result};
+use datafusion_common::{plan_err, DataFusionError, Result, TableReference};
 use datafusion_expr::expr::{BinaryExpr, Expr};
 use datafusion_expr::logical_plan::{
     CrossJoin, Filter, Join, JoinConstraint, JoinType, LogicalPlan, Projection,
 };
-use datafusion_expr::utils::{can_hash, find_valid_equijoin_key_pair};
+use datafusion_expr::utils::{can_hash, find_valid_equijoin_key_pair, relation_name};
 use datafusion_expr::{build_join_schema, ExprSchemable, Operator};
 
 #[derive(Default)]
@@ -102,14 +102,25 @@ impl OptimizerRule for EliminateCrossJoin {
 
         // Join keys are handled locally:
         let mut all_join_keys = HashSet::<(Expr, Expr)>::new();
+        let hinted_order = sort_by_join_order_hint(config, &mut all_inputs);
         let mut left = all_inputs.remove(0);
         while !all_inputs.is_empty() {
-            left = find_inner_join(
-                &left,
-                &mut all_inputs,
-                &mut possible_join_keys,
-                &mut all_join_keys,
-            )?;
+            left = if hinted_order {
+                // join the next input of the hint, even if that needs a cross join
+                find_inner_join(
+                    &left,
+                    &mut vec![all_inputs.remove(0)],
+                    &mut possible_join_keys,
+                    &mut all_join_keys,
+                )?
+            } else {
+                find_inner_join(
+                    &left,
+                    &mut all_inputs,
+                    &mut possible_join_keys,
+                    &mut all_join_keys,
+                )?
+            };
         }
 
         left = utils::optimize_children(self, &left, config)?.unwrap_or(left);
@@ -190,6 +201,31 @@ fn try_flatten_join_inputs(
     Ok(true)
 }
 
+/// Sorts `inputs` by the position of the relations they read in the
+/// `join_order_hint` option, returning whether the hint names any of them.
+/// Inputs not named by the hint keep their order, after the named ones.
+fn sort_by_join_order_hint(
+    config: &dyn OptimizerConfig,
+    inputs: &mut [LogicalPlan],
+) -> bool {
+    let Some(hint) = &config.options().optimizer.join_order_hint else {
+        return false;
+    };
+    let hint = hint
+        .split(',')
+        .map(|name| TableReference::parse_str(name.trim()))
+        .collect::<Vec<_>>();
+    let position = |input: &LogicalPlan| {
+        let relation = relation_name(input)?;
+        hint.iter().position(|name| name.resolved_eq(relation))
+    };
+    if !inputs.iter().any(|input| position(input).is_some()) {
+        return false;
+    }
+    inputs.sort_by_key(|input| position(input).unwrap_or(usize::MAX));
+    true
+}
+
 fn find_inner_join(
     left_input: &LogicalPlan,
     rights: &mut Vec<LogicalPlan>,
@@ -350,9 +386,17 @@ mod tests {
     };
 
     fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: Vec<&str>) {
+        assert_optimized_plan_eq_with_config(plan, expected, &OptimizerContext::new())
+    }
+
+    fn assert_optimized_plan_eq_with_config(
+        plan: &LogicalPlan,
+        expected: Vec<&str>,
+        config: &OptimizerContext,
+    ) {
         let rule = EliminateCrossJoin::new();
         let optimized_plan = rule
-            .try_optimize(plan, &OptimizerContext::new())
+            .try_optimize(plan, config)
             .unwrap()
             .expect("failed to optimize plan");
         let formatted = optimized_plan.display_indent_schema().to_string();
@@ -611,6 +655,49 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn reorder_join_by_join_order_hint() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+        let t3 = test_table_scan_with_name("t3")?;
+
+        let plan = LogicalPlanBuilder::from(t1)
+            .cross_join(t2)?
+            .cross_join(t3)?
+            .filter(binary_expr(
+                binary_expr(col("t3.a").eq(col("t1.a")), And, col("t3.c").lt(lit(15u32))),
+                And,
+                binary_expr(col("t3.a").eq(col("t2.a")), And, col("t3.b").lt(lit(15u32))),
+            ))?
+            .build()?;
+
+        let expected = vec![
+            "Filter: t3.c < UInt32(15) AND t3.b < UInt32(15) [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32]",
+            "  Projection: t1.a, t1.b, t1.c, t2.a, t2.b, t2.c, t3.a, t3.b, t3.c [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32]",
+            "    Inner Join: t3.a = t1.a [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32]",
+            "      Inner Join: t2.a = t3.a [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32]",
+            "        TableScan: t2 [a:UInt32, b:UInt32, c:UInt32]",
+            "        TableScan: t3 [a:UInt32, b:UInt32, c:UInt32]",
+            "      TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]",
+        ];
+        let config = OptimizerContext::new().with_join_order_hint("t2, t3");
+        assert_optimized_plan_eq_with_config(&plan, expected, &config);
+
+        // the hinted order is kept even if it needs a cross join
+        let expected = vec![
+            "Filter: t3.c < UInt32(15) AND t3.b < UInt32(15) [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32]",
+            "  Inner Join: t1.a = t3.a, t2.a = t3.a [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32]",
+            "    CrossJoin: [a:UInt32, b:UInt32, c:UInt32, a:UInt32, b:UInt32, c:UInt32]",
+            "      TableScan: t1 [a:UInt32, b:UInt32, c:UInt32]",
+            "      TableScan: t2 [a:UInt32, b:UInt32, c:UInt32]",
+            "    TableScan: t3 [a:UInt32, b:UInt32, c:UInt32]",
+        ];
+        let config = OptimizerContext::new().with_join_order_hint("t1,t2,t3");
+        assert_optimized_plan_eq_with_config(&plan, expected, &config);
+
+        Ok(())
+    }
+
     #[test]
     fn eliminate_cross_join_multi_tables() -> Result<()> {
         let t1 = test_table_scan_with_name("t1")?;
@@ -0,0 +1,325 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`EliminateLeftJoin`] removes `LEFT JOIN`s whose right side is not used
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+use datafusion_common::{Column, DFSchema, Dependency, Result};
+use datafusion_expr::logical_plan::{Aggregate, Join, JoinType, LogicalPlan, Projection};
+use datafusion_expr::utils::exprlist_to_columns;
+use datafusion_expr::Expr;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Optimization rule that removes a `LEFT JOIN` below a projection or
+/// aggregation that does not reference any column from its right side, when
+/// the right side is unique on the join keys.
+///
+/// Each row of the left side then matches at most one row of the right side,
+/// so the join produces exactly the rows of its left side. The uniqueness of
+/// the right side is inferred from its functional dependencies, which come
+/// from primary key and unique constraints, and from aggregations (including
+/// `DISTINCT`).
+///
+/// For example, if `t2.id` is the primary key of `t2`
+///
+/// ```text
+/// Projection: t1.a
+///   Left Join: t1.id = t2.id
+///     TableScan: t1
+///     TableScan: t2
+/// ```
+///
+/// is rewritten to
+///
+/// ```text
+/// Projection: t1.a
+///   TableScan: t1
+/// ```
+#[derive(Default)]
+pub struct EliminateLeftJoin;
+
+impl EliminateLeftJoin {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for EliminateLeftJoin {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Projection(projection) => {
+                let Some(left) =
+                    removable_join_input(&projection.input, &projection.expr)?
+                else {
+                    return Ok(None);
+                };
+                Projection::try_new(projection.expr.clone(), left)
+                    .map(LogicalPlan::Projection)
+                    .map(Some)
+            }
+            LogicalPlan::Aggregate(aggregate) => {
+                let exprs = aggregate
+                    .group_expr
+                    .iter()
+                    .chain(&aggregate.aggr_expr)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let Some(left) = removable_join_input(&aggregate.input, &exprs)? else {
+                    return Ok(None);
+                };
+                Aggregate::try_new(
+                    left,
+                    aggregate.group_expr.clone(),
+                    aggregate.aggr_expr.clone(),
+                )
+                .map(LogicalPlan::Aggregate)
+                .map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "eliminate_left_join"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+/// If `input` is a removable `LEFT JOIN` given that only `exprs` are
+/// evaluated over its output, returns its left side
+fn removable_join_input(
+    input: &LogicalPlan,
+    exprs: &[Expr],
+) -> Result<Option<Arc<LogicalPlan>>> {
+    let LogicalPlan::Join(Join {
+        left,
+        right,
+        on,
+        join_type: JoinType::Left,
+        null_equals_null,
+        ..
+    }) = input
+    else {
+        return Ok(None);
+    };
+    if on.is_empty() {
+        return Ok(None);
+    }
+
+    let mut columns = HashSet::new();
+    exprlist_to_columns(exprs, &mut columns)?;
+    if !columns.iter().all(|c| left.schema().has_column(c)) {
+        return Ok(None);
+    }
+
+    let right_keys = on
+        .iter()
+        .map(|(_, key)| match key {
+            Expr::Column(column) => right.schema().index_of_column(column).ok(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+    match right_keys {
+        Some(keys) if is_unique(right.schema(), &keys, *null_equals_null) => {
+            Ok(Some(left.clone()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Returns true if the rows of `schema` are unique on the columns at
+/// `indices`. Rows with nulls in these columns are only considered if
+/// `null_equals_null` is true.
+pub(crate) fn is_unique(
+    schema: &DFSchema,
+    indices: &[usize],
+    null_equals_null: bool,
+) -> bool {
+    schema.functional_dependencies().iter().any(|dependency| {
+        dependency.mode == Dependency::Single
+            && !(dependency.nullable && null_equals_null)
+            && dependency
+                .source_indices
+                .iter()
+                .all(|index| indices.contains(index))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_common::{Constraint, Constraints};
+    use datafusion_expr::{col, count, max, LogicalPlanBuilder, TableSource};
+    use std::any::Any;
+
+    struct ConstrainedSource {
+        schema: SchemaRef,
+        constraints: Constraints,
+    }
+
+    impl TableSource for ConstrainedSource {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+
+        fn constraints(&self) -> Option<&Constraints> {
+            Some(&self.constraints)
+        }
+    }
+
+    fn assert_optimized_plan_equal(plan: &LogicalPlan, expected: &str) -> Result<()> {
+        assert_optimized_plan_eq(Arc::new(EliminateLeftJoin::new()), plan, expected)
+    }
+
+    /// A table `t2` with a primary key on `a`
+    fn table_with_primary_key() -> Result<LogicalPlan> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::UInt32, false),
+        ]);
+        let source = ConstrainedSource {
+            schema: Arc::new(schema),
+            constraints: Constraints::new_unverified(vec![Constraint::PrimaryKey(vec![
+                0,
+            ])]),
+        };
+        LogicalPlanBuilder::scan("t2", Arc::new(source), None)?.build()
+    }
+
+    fn left_join_on(right: LogicalPlan, right_keys: Vec<&str>) -> Result<LogicalPlan> {
+        let left_keys = vec!["t1.a", "t1.b"][..right_keys.len()].to_vec();
+        LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join(
+                right,
+                JoinType::Left,
+                (
+                    left_keys.into_iter().map(Column::from).collect::<Vec<_>>(),
+                    right_keys.into_iter().map(Column::from).collect::<Vec<_>>(),
+                ),
+                None,
+            )?
+            .build()
+    }
+
+    #[test]
+    fn eliminate_join_on_primary_key() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(left_join_on(
+            table_with_primary_key()?,
+            vec!["t2.a"],
+        )?)
+        .project(vec![col("t1.a"), col("t1.c")])?
+        .build()?;
+
+        let expected = "Projection: t1.a, t1.c\
+        \n  TableScan: t1";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_join_below_aggregate() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(left_join_on(
+            table_with_primary_key()?,
+            vec!["t2.a"],
+        )?)
+        .aggregate(vec![col("t1.b")], vec![count(col("t1.c"))])?
+        .build()?;
+
+        let expected = "Aggregate: groupBy=[[t1.b]], aggr=[[COUNT(t1.c)]]\
+        \n  TableScan: t1";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_join_on_distinct_keys() -> Result<()> {
+        let right = LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+            .aggregate(vec![col("t2.a"), col("t2.b")], vec![max(col("t2.c"))])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(left_join_on(right, vec!["t2.a", "t2.b"])?)
+            .project(vec![col("t1.a")])?
+            .build()?;
+
+        let expected = "Projection: t1.a\
+        \n  TableScan: t1";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn right_side_referenced() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(left_join_on(
+            table_with_primary_key()?,
+            vec!["t2.a"],
+        )?)
+        .project(vec![col("t1.a"), col("t2.b")])?
+        .build()?;
+
+        let expected = "Projection: t1.a, t2.b\
+        \n  Left Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn right_side_not_unique() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(left_join_on(
+            table_with_primary_key()?,
+            vec!["t2.b"],
+        )?)
+        .project(vec![col("t1.a")])?
+        .build()?;
+
+        let expected = "Projection: t1.a\
+        \n  Left Join: t1.a = t2.b\
+        \n    TableScan: t1\
+        \n    TableScan: t2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn inner_join_not_eliminated() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join(
+                table_with_primary_key()?,
+                JoinType::Inner,
+                (vec![Column::from("t1.a")], vec![Column::from("t2.a")]),
+                None,
+            )?
+            .project(vec![col("t1.a")])?
+            .build()?;
+
+        let expected = "Projection: t1.a\
+        \n  Inner Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+}
@@ -19,10 +19,11 @@
 use crate::{OptimizerConfig, OptimizerRule};
 use datafusion_common::{Column, DFSchema, Result};
 use datafusion_expr::logical_plan::{Join, JoinType, LogicalPlan};
+use datafusion_expr::utils::conjunction;
 use datafusion_expr::{Expr, Operator};
 
 use crate::optimizer::ApplyOrder;
-use datafusion_expr::expr::{BinaryExpr, Cast, TryCast};
+use datafusion_expr::expr::{Between, BinaryExpr, Cast, InList, Like, TryCast};
 use std::sync::Arc;
 
 #[derive(Default)]
@@ -67,44 +68,9 @@ impl OptimizerRule for EliminateOuterJoin {
         match plan {
             LogicalPlan::Filter(filter) => match filter.input.as_ref() {
                 LogicalPlan::Join(join) => {
-                    let mut non_nullable_cols: Vec<Column> = vec![];
-
-                    extract_non_nullable_columns(
-                        &filter.predicate,
-                        &mut non_nullable_cols,
-                        join.left.schema(),
-                        join.right.schema(),
-                        true,
-                    )?;
-
-                    let new_join_type = if join.join_type.is_outer() {
-                        let mut left_non_nullable = false;
-                        let mut right_non_nullable = false;
-                        for col in non_nullable_cols.iter() {
-                            if join.left.schema().has_column(col) {
-                                left_non_nullable = true;
-                            }
-                            if join.right.schema().has_column(col) {
-                                right_non_nullable = true;
-                            }
-                        }
-                        eliminate_outer(
-                            join.join_type,
-                            left_non_nullable,
-                            right_non_nullable,
-                        )
-                    } else {
-                        join.join_type
-                    };
                     let new_join = LogicalPlan::Join(Join {
-                        left: Arc::new((*join.left).clone()),
-                        right: Arc::new((*join.right).clone()),
-                        join_type: new_join_type,
-                        join_constraint: join.join_constraint,
-                        on: join.on.clone(),
-                        filter: join.filter.clone(),
-                        schema: join.schema.clone(),
-                        null_equals_null: join.null_equals_null,
+                        join_type: reduce_outer_join(join, &filter.predicate)?,
+                        ..join.clone()
                     });
                     let new_plan =
                         plan.with_new_exprs(plan.expressions(), &[new_join])?;
@@ -112,6 +78,43 @@ impl OptimizerRule for EliminateOuterJoin {
                 }
                 _ => Ok(None),
             },
+            // The join conditions of an inner join are evaluated over the output
+            // of its inputs, so they can eliminate outer joins directly below it
+            // just like a filter, e.g. `a LEFT JOIN b ON ... JOIN c ON b.x = c.x`
+            LogicalPlan::Join(join) if join.join_type == JoinType::Inner => {
+                let Some(predicate) = join_conditions(join) else {
+                    return Ok(None);
+                };
+
+                let mut changed = false;
+                let mut new_inputs = Vec::with_capacity(2);
+                for input in [&join.left, &join.right] {
+                    match input.as_ref() {
+                        LogicalPlan::Join(input_join)
+                            if input_join.join_type.is_outer() =>
+                        {
+                            let join_type = reduce_outer_join(input_join, &predicate)?;
+                            changed |= join_type != input_join.join_type;
+                            new_inputs.push(Arc::new(LogicalPlan::Join(Join {
+                                join_type,
+                                ..input_join.clone()
+                            })));
+                        }
+                        _ => new_inputs.push(input.clone()),
+                    }
+                }
+                if !changed {
+                    return Ok(None);
+                }
+
+                let right = new_inputs.pop().unwrap();
+                let left = new_inputs.pop().unwrap();
+                Ok(Some(LogicalPlan::Join(Join {
+                    left,
+                    right,
+                    ..join.clone()
+                })))
+            }
             _ => Ok(None),
         }
     }
@@ -125,6 +128,47 @@ impl OptimizerRule for EliminateOuterJoin {
     }
 }
 
+/// Returns the join type of `join` after eliminating the outer join
+/// sides whose null padded rows are all rejected by `predicate`.
+fn reduce_outer_join(join: &Join, predicate: &Expr) -> Result<JoinType> {
+    if !join.join_type.is_outer() {
+        return Ok(join.join_type);
+    }
+
+    let mut non_nullable_cols: Vec<Column> = vec![];
+    extract_non_nullable_columns(
+        predicate,
+        &mut non_nullable_cols,
+        join.left.schema(),
+        join.right.schema(),
+        true,
+    )?;
+
+    let left_non_nullable = non_nullable_cols
+        .iter()
+        .any(|col| join.left.schema().has_column(col));
+    let right_non_nullable = non_nullable_cols
+        .iter()
+        .any(|col| join.right.schema().has_column(col));
+    Ok(eliminate_outer(
+        join.join_type,
+        left_non_nullable,
+        right_non_nullable,
+    ))
+}
+
+/// Returns the conjunction of the equijoin keys and the filter of `join`.
+///
+/// Equijoin keys compared with `null_equals_null` accept nulls and are skipped.
+fn join_conditions(join: &Join) -> Option<Expr> {
+    let on = join
+        .on
+        .iter()
+        .filter(|_| !join.null_equals_null)
+        .map(|(l, r)| l.clone().eq(r.clone()));
+    conjunction(on.chain(join.filter.clone()))
+}
+
 pub fn eliminate_outer(
     join_type: JoinType,
     left_non_nullable: bool,
@@ -163,7 +207,14 @@ pub fn eliminate_outer(
 /// For or expr, if one of sub exprs returns true, discards all columns from or expr.
 /// For IS NOT NULL/NOT expr, always returns false for NULL input.
 ///     extracts columns from these exprs.
+/// For IS TRUE/IS FALSE/IS NOT UNKNOWN expr, returns false for NULL input
+///     at top level, extracts columns from their argument.
+/// For arithmetic, LIKE, IN list, BETWEEN and negation exprs, the result is
+///     null if the tested input is null, extracts columns from that input.
 /// For all other exprs, fall through
+///
+/// When `top_level` is false, only columns whose null value makes the whole
+/// `expr` null are extracted.
 fn extract_non_nullable_columns(
     expr: &Expr,
     non_nullable_cols: &mut Vec<Column>,
@@ -183,7 +234,22 @@ fn extract_non_nullable_columns(
             | Operator::Lt
             | Operator::LtEq
             | Operator::Gt
-            | Operator::GtEq => {
+            | Operator::GtEq
+            | Operator::RegexMatch
+            | Operator::RegexIMatch
+            | Operator::RegexNotMatch
+            | Operator::RegexNotIMatch
+            // If one of the inputs are null for these operators, the results are null.
+            | Operator::Plus
+            | Operator::Minus
+            | Operator::Multiply
+            | Operator::Divide
+            | Operator::Modulo
+            | Operator::BitwiseAnd
+            | Operator::BitwiseOr
+            | Operator::BitwiseXor
+            | Operator::BitwiseShiftRight
+            | Operator::BitwiseShiftLeft => {
                 extract_non_nullable_columns(
                     left,
                     non_nullable_cols,
@@ -283,6 +349,98 @@ fn extract_non_nullable_columns(
                 false,
             )
         }
+        // IS TRUE returns false for both null and false input, so its argument
+        // is treated like a top level predicate.
+        Expr::IsTrue(arg) => {
+            if !top_level {
+                return Ok(());
+            }
+            extract_non_nullable_columns(
+                arg,
+                non_nullable_cols,
+                left_schema,
+                right_schema,
+                true,
+            )
+        }
+        Expr::IsFalse(arg) | Expr::IsNotUnknown(arg) => {
+            if !top_level {
+                return Ok(());
+            }
+            extract_non_nullable_columns(
+                arg,
+                non_nullable_cols,
+                left_schema,
+                right_schema,
+                false,
+            )
+        }
+        Expr::Negative(arg) => extract_non_nullable_columns(
+            arg,
+            non_nullable_cols,
+            left_schema,
+            right_schema,
+            false,
+        ),
+        Expr::Like(Like { expr, pattern, .. })
+        | Expr::SimilarTo(Like { expr, pattern, .. }) => {
+            extract_non_nullable_columns(
+                expr,
+                non_nullable_cols,
+                left_schema,
+                right_schema,
+                false,
+            )?;
+            extract_non_nullable_columns(
+                pattern,
+                non_nullable_cols,
+                left_schema,
+                right_schema,
+                false,
+            )
+        }
+        // `x IN (...)` is null when `x` is null, regardless of the list items
+        Expr::InList(InList { expr, .. }) => extract_non_nullable_columns(
+            expr,
+            non_nullable_cols,
+            left_schema,
+            right_schema,
+            false,
+        ),
+        Expr::Between(Between {
+            expr,
+            negated,
+            low,
+            high,
+        }) => {
+            extract_non_nullable_columns(
+                expr,
+                non_nullable_cols,
+                left_schema,
+                right_schema,
+                false,
+            )?;
+            // `x BETWEEN low AND high` is `x >= low AND x <= high`, which is
+            // null or false if either bound is null. That does not hold for
+            // `x < low OR x > high`, nor when nested in a NOT.
+            if !*negated && top_level {
+                extract_non_nullable_columns(
+                    low,
+                    non_nullable_cols,
+                    left_schema,
+                    right_schema,
+                    false,
+                )?;
+                extract_non_nullable_columns(
+                    high,
+                    non_nullable_cols,
+                    left_schema,
+                    right_schema,
+                    false,
+                )?;
+            }
+            Ok(())
+        }
         Expr::Cast(Cast { expr, data_type: _ })
         | Expr::TryCast(TryCast { expr, data_type: _ }) => extract_non_nullable_columns(
             expr,
@@ -303,7 +461,7 @@ mod tests {
     use datafusion_expr::{
         binary_expr, cast, col, lit,
         logical_plan::builder::LogicalPlanBuilder,
-        try_cast,
+        not, try_cast,
         Operator::{And, Or},
     };
 
@@ -437,4 +595,214 @@ mod tests {
         \n    TableScan: t2";
         assert_optimized_plan_equal(&plan, expected)
     }
+
+    #[test]
+    fn eliminate_left_with_arithmetic() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+
+        // eliminate to inner join
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(
+                t2,
+                JoinType::Left,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .filter((col("t2.b") + lit(1u32)).gt(col("t1.c")))?
+            .build()?;
+        let expected = "\
+        Filter: t2.b + UInt32(1) > t1.c\
+        \n  Inner Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_full_with_in_list_and_like() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+
+        // eliminate to inner join
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(
+                t2,
+                JoinType::Full,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .filter(
+                col("t1.b")
+                    .in_list(vec![lit(1u32), lit(2u32)], true)
+                    .and(cast(col("t2.c"), DataType::Utf8).like(lit("1%"))),
+            )?
+            .build()?;
+        let expected = "\
+        Filter: t1.b NOT IN ([UInt32(1), UInt32(2)]) AND CAST(t2.c AS Utf8) LIKE Utf8(\"1%\")\
+        \n  Inner Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_left_with_between_bound() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+
+        // eliminate to inner join
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(
+                t2,
+                JoinType::Left,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .filter(col("t1.b").between(lit(1u32), col("t2.c")))?
+            .build()?;
+        let expected = "\
+        Filter: t1.b BETWEEN UInt32(1) AND t2.c\
+        \n  Inner Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_left_with_not_between_bound() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+
+        // could not eliminate to inner join, `t1.b NOT BETWEEN 1 AND NULL`
+        // is true for `t1.b < 1`
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(
+                t2,
+                JoinType::Left,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .filter(col("t1.b").not_between(lit(1u32), col("t2.c")))?
+            .build()?;
+        let expected = "\
+        Filter: t1.b NOT BETWEEN UInt32(1) AND t2.c\
+        \n  Left Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_left_with_is_true() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+
+        // eliminate to inner join
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(
+                t2,
+                JoinType::Left,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .filter(col("t2.b").gt(lit(10u32)).is_true())?
+            .build()?;
+        let expected = "\
+        Filter: t2.b > UInt32(10) IS TRUE\
+        \n  Inner Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_left_with_not_is_true() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+
+        // could not eliminate to inner join, `NOT (NULL IS TRUE)` is true
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(
+                t2,
+                JoinType::Left,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .filter(not(col("t2.b").gt(lit(10u32)).is_true()))?
+            .build()?;
+        let expected = "\
+        Filter: NOT t2.b > UInt32(10) IS TRUE\
+        \n  Left Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_left_below_inner_join() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+        let t3 = test_table_scan_with_name("t3")?;
+
+        // the inner join condition on `t2.b` eliminates the left join
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(
+                t2,
+                JoinType::Left,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .join(
+                t3,
+                JoinType::Inner,
+                (
+                    vec![Column::from_qualified_name("t2.b")],
+                    vec![Column::from_qualified_name("t3.b")],
+                ),
+                None,
+            )?
+            .build()?;
+        let expected = "\
+        Inner Join: t2.b = t3.b\
+        \n  Inner Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2\
+        \n  TableScan: t3";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_left_below_inner_join_on_preserved_side() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+        let t3 = test_table_scan_with_name("t3")?;
+
+        // could not eliminate to inner join, the condition only rejects
+        // nulls of the preserved side
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(
+                t2,
+                JoinType::Left,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .join(
+                t3,
+                JoinType::Inner,
+                (
+                    vec![Column::from_qualified_name("t1.b")],
+                    vec![Column::from_qualified_name("t3.b")],
+                ),
+                None,
+            )?
+            .build()?;
+        let expected = "\
+        Inner Join: t1.b = t3.b\
+        \n  Left Join: t1.a = t2.a\
+        \n    TableScan: t1\
+        \n    TableScan: t2\
+        \n  TableScan: t3";
+        assert_optimized_plan_equal(&plan, expected)
+    }
 }
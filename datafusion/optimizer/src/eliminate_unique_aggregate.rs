@@ -0,0 +1,225 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`EliminateUniqueAggregate`] removes `DISTINCT` and `GROUP BY` on
+//! columns whose values are already unique
+use crate::eliminate_left_join::is_unique;
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+use datafusion_common::Result;
+use datafusion_expr::logical_plan::{Aggregate, Distinct, LogicalPlan, Projection};
+use datafusion_expr::Expr;
+
+/// Optimization rule that removes a `DISTINCT`, or a `GROUP BY` without
+/// aggregate functions, when its input is unique on the grouped columns.
+///
+/// Each group then consists of a single row, so the aggregation only
+/// evaluates the group expressions. The uniqueness of the input is
+/// inferred from its functional dependencies, which come from primary key
+/// and unique constraints, and from inner aggregations.
+///
+/// For example, if `t.id` is the primary key of `t`
+///
+/// ```text
+/// Aggregate: groupBy=[[t.id, t.a]], aggr=[[]]
+///   TableScan: t
+/// ```
+///
+/// is rewritten to
+///
+/// ```text
+/// Projection: t.id, t.a
+///   TableScan: t
+/// ```
+///
+/// `GROUP BY` places all the rows with nulls in the key columns in the same
+/// group, so only keys that can not contain nulls (such as primary keys)
+/// are used.
+#[derive(Default)]
+pub struct EliminateUniqueAggregate;
+
+impl EliminateUniqueAggregate {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for EliminateUniqueAggregate {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Distinct(Distinct::All(input)) => {
+                let indices = (0..input.schema().fields().len()).collect::<Vec<_>>();
+                if is_unique(input.schema(), &indices, true) {
+                    Ok(Some(input.as_ref().clone()))
+                } else {
+                    Ok(None)
+                }
+            }
+            LogicalPlan::Aggregate(Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                ..
+            }) if aggr_expr.is_empty() && !group_expr.is_empty() => {
+                let Some(indices) = group_expr
+                    .iter()
+                    .map(|expr| match expr {
+                        Expr::Column(column) => {
+                            input.schema().index_of_column(column).ok()
+                        }
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return Ok(None);
+                };
+                if !is_unique(input.schema(), &indices, true) {
+                    return Ok(None);
+                }
+                Projection::try_new(group_expr.clone(), input.clone())
+                    .map(LogicalPlan::Projection)
+                    .map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "eliminate_unique_aggregate"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_common::{Constraint, Constraints};
+    use datafusion_expr::{col, count, LogicalPlanBuilder, TableSource};
+    use std::any::Any;
+    use std::sync::Arc;
+
+    struct ConstrainedSource {
+        schema: SchemaRef,
+        constraints: Constraints,
+    }
+
+    impl TableSource for ConstrainedSource {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+
+        fn constraints(&self) -> Option<&Constraints> {
+            Some(&self.constraints)
+        }
+    }
+
+    fn assert_optimized_plan_equal(plan: &LogicalPlan, expected: &str) -> Result<()> {
+        assert_optimized_plan_eq(
+            Arc::new(EliminateUniqueAggregate::new()),
+            plan,
+            expected,
+        )
+    }
+
+    /// A table `t` with columns `a`, `b` and `c`, and the given constraint
+    fn table_with_constraint(constraint: Constraint) -> Result<LogicalPlanBuilder> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::UInt32, true),
+            Field::new("c", DataType::UInt32, true),
+        ]);
+        let source = ConstrainedSource {
+            schema: Arc::new(schema),
+            constraints: Constraints::new_unverified(vec![constraint]),
+        };
+        LogicalPlanBuilder::scan("t", Arc::new(source), None)
+    }
+
+    #[test]
+    fn eliminate_distinct_on_primary_key() -> Result<()> {
+        let plan = table_with_constraint(Constraint::PrimaryKey(vec![0]))?
+            .project(vec![col("t.a"), col("t.b")])?
+            .distinct()?
+            .build()?;
+
+        let expected = "Projection: t.a, t.b\
+        \n  TableScan: t";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn eliminate_group_by_primary_key() -> Result<()> {
+        // the dependent columns of the key are added to the group by
+        let plan = table_with_constraint(Constraint::PrimaryKey(vec![0]))?
+            .aggregate(vec![col("t.a")], Vec::<Expr>::new())?
+            .project(vec![col("t.a"), col("t.c")])?
+            .build()?;
+
+        let expected = "Projection: t.a, t.c\
+        \n  Projection: t.a, t.b, t.c\
+        \n    TableScan: t";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn keep_group_by_non_unique_columns() -> Result<()> {
+        let plan = table_with_constraint(Constraint::PrimaryKey(vec![0]))?
+            .aggregate(vec![col("t.b")], Vec::<Expr>::new())?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[t.b]], aggr=[[]]\
+        \n  TableScan: t";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn keep_group_by_with_aggregates() -> Result<()> {
+        let plan = table_with_constraint(Constraint::PrimaryKey(vec![0]))?
+            .aggregate(vec![col("t.a")], vec![count(col("t.b"))])?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[t.a, t.b, t.c]], aggr=[[COUNT(t.b)]]\
+        \n  TableScan: t";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn keep_group_by_nullable_unique_key() -> Result<()> {
+        // several rows with a null `b` end up in the same group
+        let plan = table_with_constraint(Constraint::Unique(vec![1]))?
+            .aggregate(vec![col("t.b")], Vec::<Expr>::new())?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[t.b, t.a, t.c]], aggr=[[]]\
+        \n  TableScan: t";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+}
@@ -69,13 +69,32 @@ impl OptimizerRule for ExtractEquijoinPredicate {
                 let right_schema = right.schema();
 
                 filter.as_ref().map_or(Result::Ok(None), |expr| {
-                    let (equijoin_predicates, non_equijoin_expr) =
+                    let (mut equijoin_predicates, mut non_equijoin_expr) =
                         split_eq_and_noneq_join_predicate(
                             expr,
                             left_schema,
                             right_schema,
+                            *null_equals_null,
                         )?;
 
+                    // The hash table of a join either matches null keys or not,
+                    // so `IS NOT DISTINCT FROM` predicates only become join keys
+                    // when all the keys of the join match nulls
+                    let mut null_equals_null = *null_equals_null;
+                    if !null_equals_null
+                        && on.is_empty()
+                        && equijoin_predicates.is_empty()
+                    {
+                        (equijoin_predicates, non_equijoin_expr) =
+                            split_eq_and_noneq_join_predicate(
+                                expr,
+                                left_schema,
+                                right_schema,
+                                true,
+                            )?;
+                        null_equals_null = !equijoin_predicates.is_empty();
+                    }
+
                     let optimized_plan = (!equijoin_predicates.is_empty()).then(|| {
                         let mut new_on = on.clone();
                         new_on.extend(equijoin_predicates);
@@ -88,7 +107,7 @@ impl OptimizerRule for ExtractEquijoinPredicate {
                             join_type: *join_type,
                             join_constraint: *join_constraint,
                             schema: schema.clone(),
-                            null_equals_null: *null_equals_null,
+                            null_equals_null,
                         })
                     });
 
@@ -108,22 +127,28 @@ impl OptimizerRule for ExtractEquijoinPredicate {
     }
 }
 
+/// Splits the conjunction `filter` into its equijoin predicates and the
+/// other predicates. The `IS NOT DISTINCT FROM` predicates are equijoin
+/// predicates if `null_equals_null` is true, and the `=` predicates if it is
+/// false.
 fn split_eq_and_noneq_join_predicate(
     filter: &Expr,
     left_schema: &Arc<DFSchema>,
     right_schema: &Arc<DFSchema>,
+    null_equals_null: bool,
 ) -> Result<(Vec<EquijoinPredicate>, Option<Expr>)> {
     let exprs = split_conjunction(filter);
+    let equijoin_op = if null_equals_null {
+        Operator::IsNotDistinctFrom
+    } else {
+        Operator::Eq
+    };
 
     let mut accum_join_keys: Vec<(Expr, Expr)> = vec![];
     let mut accum_filters: Vec<Expr> = vec![];
     for expr in exprs {
         match expr {
-            Expr::BinaryExpr(BinaryExpr {
-                left,
-                op: Operator::Eq,
-                right,
-            }) => {
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) if *op == equijoin_op => {
                 let left = left.as_ref();
                 let right = right.as_ref();
 
@@ -159,9 +184,10 @@ fn split_eq_and_noneq_join_predicate(
 mod tests {
     use super::*;
     use crate::test::*;
+    use crate::OptimizerContext;
     use arrow::datatypes::DataType;
     use datafusion_expr::{
-        col, lit, logical_plan::builder::LogicalPlanBuilder, JoinType,
+        binary_expr, col, lit, logical_plan::builder::LogicalPlanBuilder, JoinType,
     };
 
     fn assert_plan_eq(plan: &LogicalPlan, expected: &str) -> Result<()> {
@@ -377,4 +403,52 @@ mod tests {
 
         assert_plan_eq(&plan, expected)
     }
+
+    fn optimized_join(plan: &LogicalPlan) -> Result<Join> {
+        let optimized = ExtractEquijoinPredicate::new()
+            .try_optimize(plan, &OptimizerContext::new())?
+            .unwrap();
+        match optimized {
+            LogicalPlan::Join(join) => Ok(join),
+            _ => panic!("expected a join, got {optimized:?}"),
+        }
+    }
+
+    #[test]
+    fn join_with_null_safe_equi_predicate() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+
+        // t1.a IS NOT DISTINCT FROM t2.a AND t1.b < t2.b
+        let filter = binary_expr(col("t1.a"), Operator::IsNotDistinctFrom, col("t2.a"))
+            .and(col("t1.b").lt(col("t2.b")));
+        let plan = LogicalPlanBuilder::from(t1)
+            .join_on(t2, JoinType::Inner, Some(filter))?
+            .build()?;
+        let join = optimized_join(&plan)?;
+        assert!(join.null_equals_null);
+        assert_eq!(join.on, vec![(col("t1.a"), col("t2.a"))]);
+        assert_eq!(join.filter, Some(col("t1.b").lt(col("t2.b"))));
+        Ok(())
+    }
+
+    #[test]
+    fn join_with_null_safe_and_equi_predicates() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+
+        // the null-safe predicate stays in the filter, as the join does not
+        // match the nulls of t1.a = t2.a
+        let null_safe =
+            binary_expr(col("t1.b"), Operator::IsNotDistinctFrom, col("t2.b"));
+        let filter = null_safe.clone().and(col("t1.a").eq(col("t2.a")));
+        let plan = LogicalPlanBuilder::from(t1)
+            .join_on(t2, JoinType::Inner, Some(filter))?
+            .build()?;
+        let join = optimized_join(&plan)?;
+        assert!(!join.null_equals_null);
+        assert_eq!(join.on, vec![(col("t1.a"), col("t2.a"))]);
+        assert_eq!(join.filter, Some(null_safe));
+        Ok(())
+    }
 }
@@ -50,7 +50,10 @@ impl OptimizerRule for FilterNullJoinKeys {
         }
 
         match plan {
-            LogicalPlan::Join(join) if join.join_type == JoinType::Inner => {
+            // null keys of joins matching nulls must not be filtered
+            LogicalPlan::Join(join)
+                if join.join_type == JoinType::Inner && !join.null_equals_null =>
+            {
                 let mut join = join.clone();
 
                 let left_schema = join.left.schema();
@@ -23,18 +23,23 @@ pub mod eliminate_cross_join;
 pub mod eliminate_duplicated_expr;
 pub mod eliminate_filter;
 pub mod eliminate_join;
+pub mod eliminate_left_join;
 pub mod eliminate_limit;
 pub mod eliminate_nested_union;
 pub mod eliminate_one_union;
 pub mod eliminate_outer_join;
+pub mod eliminate_unique_aggregate;
 pub mod extract_equijoin_predicate;
 pub mod filter_null_join_keys;
 pub mod optimize_projections;
 pub mod optimizer;
 pub mod propagate_empty_relation;
+pub mod push_down_aggregate;
+pub mod push_down_aggregate_union;
 pub mod push_down_filter;
 pub mod push_down_limit;
 pub mod push_down_projection;
+pub mod push_down_topk;
 pub mod replace_distinct_aggregate;
 pub mod rewrite_disjunctive_predicate;
 pub mod scalar_subquery_to_join;
@@ -46,7 +51,9 @@ pub mod utils;
 #[cfg(test)]
 pub mod test;
 
-pub use optimizer::{OptimizerConfig, OptimizerContext, OptimizerRule};
+pub use optimizer::{
+    OptimizerConfig, OptimizerContext, OptimizerRule, OptimizerRuleMetrics,
+};
 pub use utils::optimize_children;
 
 mod plan_signature;
@@ -18,8 +18,9 @@
 //! Query optimizer traits
 
 use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::common_subexpr_eliminate::CommonSubexprEliminate;
 use crate::decorrelate_predicate_subquery::DecorrelatePredicateSubquery;
@@ -27,17 +28,22 @@ use crate::eliminate_cross_join::EliminateCrossJoin;
 use crate::eliminate_duplicated_expr::EliminateDuplicatedExpr;
 use crate::eliminate_filter::EliminateFilter;
 use crate::eliminate_join::EliminateJoin;
+use crate::eliminate_left_join::EliminateLeftJoin;
 use crate::eliminate_limit::EliminateLimit;
 use crate::eliminate_nested_union::EliminateNestedUnion;
 use crate::eliminate_one_union::EliminateOneUnion;
 use crate::eliminate_outer_join::EliminateOuterJoin;
+use crate::eliminate_unique_aggregate::EliminateUniqueAggregate;
 use crate::extract_equijoin_predicate::ExtractEquijoinPredicate;
 use crate::filter_null_join_keys::FilterNullJoinKeys;
 use crate::optimize_projections::OptimizeProjections;
 use crate::plan_signature::LogicalPlanSignature;
 use crate::propagate_empty_relation::PropagateEmptyRelation;
+use crate::push_down_aggregate::PushDownAggregate;
+use crate::push_down_aggregate_union::PushDownAggregateUnion;
 use crate::push_down_filter::PushDownFilter;
 use crate::push_down_limit::PushDownLimit;
+use crate::push_down_topk::PushDownTopK;
 use crate::replace_distinct_aggregate::ReplaceDistinctWithAggregate;
 use crate::rewrite_disjunctive_predicate::RewriteDisjunctivePredicate;
 use crate::scalar_subquery_to_join::ScalarSubqueryToJoin;
@@ -48,7 +54,8 @@ use crate::utils::log_plan;
 
 use datafusion_common::alias::AliasGenerator;
 use datafusion_common::config::ConfigOptions;
-use datafusion_common::{DataFusionError, Result};
+use datafusion_common::tree_node::{TreeNode, VisitRecursion};
+use datafusion_common::{DFSchema, DataFusionError, Result};
 use datafusion_expr::logical_plan::LogicalPlan;
 
 use chrono::{DateTime, Utc};
@@ -76,6 +83,15 @@ pub trait OptimizerRule {
     fn apply_order(&self) -> Option<ApplyOrder> {
         None
     }
+
+    /// Can this rule be skipped once the optimizer exceeds its time budget
+    /// (see `datafusion.optimizer.max_optimization_time_ms`)?
+    ///
+    /// Rules producing plans that could otherwise not be executed, such as
+    /// the rewriting of subqueries to joins, should return false
+    fn is_optional(&self) -> bool {
+        true
+    }
 }
 
 /// Options to control the DataFusion Optimizer.
@@ -145,6 +161,19 @@ impl OptimizerContext {
         self.options.optimizer.max_passes = v as usize;
         self
     }
+
+    /// Specify the maximum time in milliseconds to spend optimizing the plan
+    pub fn with_max_optimization_time_ms(mut self, v: usize) -> Self {
+        self.options.optimizer.max_optimization_time_ms = v;
+        self
+    }
+
+    /// Specify the comma separated names of tables that inner joins should
+    /// join in this order
+    pub fn with_join_order_hint(mut self, tables: impl Into<String>) -> Self {
+        self.options.optimizer.join_order_hint = Some(tables.into());
+        self
+    }
 }
 
 impl Default for OptimizerContext {
@@ -175,6 +204,46 @@ pub struct Optimizer {
     pub rules: Vec<Arc<dyn OptimizerRule + Send + Sync>>,
 }
 
+/// The time spent in an [`OptimizerRule`] of an [`Optimizer`], and the number
+/// of its rewrites, over all passes of [`Optimizer::optimize_with_metrics`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizerRuleMetrics {
+    /// The name of the rule
+    pub name: String,
+    /// Time spent applying the rule
+    pub elapsed: Duration,
+    /// Number of passes in which the rule rewrote the plan
+    pub rewrites: usize,
+    /// Number of passes in which the rule was skipped because the time
+    /// budget of the optimizer was exceeded
+    pub skipped: usize,
+}
+
+impl OptimizerRuleMetrics {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            elapsed: Duration::ZERO,
+            rewrites: 0,
+            skipped: 0,
+        }
+    }
+}
+
+impl Display for OptimizerRuleMetrics {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: elapsed={:?}, rewrites={}",
+            self.name, self.elapsed, self.rewrites
+        )?;
+        if self.skipped > 0 {
+            write!(f, ", skipped={}", self.skipped)?;
+        }
+        Ok(())
+    }
+}
+
 /// If a rule is with `ApplyOrder`, it means the optimizer will derive to handle children instead of
 /// recursively handling in rule.
 /// We just need handle a subtree pattern itself.
@@ -246,9 +315,15 @@ impl Optimizer {
             Arc::new(EliminateOneUnion::new()),
             Arc::new(FilterNullJoinKeys::default()),
             Arc::new(EliminateOuterJoin::new()),
+            Arc::new(EliminateLeftJoin::new()),
+            Arc::new(EliminateUniqueAggregate::new()),
             // Filters can't be pushed down past Limits, we should do PushDownFilter after PushDownLimit
             Arc::new(PushDownLimit::new()),
             Arc::new(PushDownFilter::new()),
+            // Must be after PushDownFilter, so that sources see the filters
+            Arc::new(PushDownAggregateUnion::new()),
+            Arc::new(PushDownAggregate::new()),
+            Arc::new(PushDownTopK::new()),
             Arc::new(SingleDistinctToGroupBy::new()),
             // The previous optimizations added expressions and projections,
             // that might benefit from the following rules
@@ -272,8 +347,23 @@ impl Optimizer {
         &self,
         plan: &LogicalPlan,
         config: &dyn OptimizerConfig,
-        mut observer: F,
+        observer: F,
     ) -> Result<LogicalPlan>
+    where
+        F: FnMut(&LogicalPlan, &dyn OptimizerRule),
+    {
+        self.optimize_with_metrics(plan, config, observer)
+            .map(|(plan, _)| plan)
+    }
+
+    /// Like [`Self::optimize`], additionally returning the
+    /// [`OptimizerRuleMetrics`] of each rule, in the order of [`Self::rules`]
+    pub fn optimize_with_metrics<F>(
+        &self,
+        plan: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+        mut observer: F,
+    ) -> Result<(LogicalPlan, Vec<OptimizerRuleMetrics>)>
     where
         F: FnMut(&LogicalPlan, &dyn OptimizerRule),
     {
@@ -281,6 +371,17 @@ impl Optimizer {
         let mut new_plan = plan.clone();
 
         let start_time = Instant::now();
+        let time_budget = match options.optimizer.max_optimization_time_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms as u64)),
+        };
+        let budget_exceeded =
+            || time_budget.map_or(false, |budget| start_time.elapsed() > budget);
+        let mut metrics = self
+            .rules
+            .iter()
+            .map(|rule| OptimizerRuleMetrics::new(rule.name()))
+            .collect::<Vec<_>>();
 
         let mut previous_plans = HashSet::with_capacity(16);
         previous_plans.insert(LogicalPlanSignature::new(&new_plan));
@@ -289,17 +390,32 @@ impl Optimizer {
         while i < options.optimizer.max_passes {
             log_plan(&format!("Optimizer input (pass {i})"), &new_plan);
 
-            for rule in &self.rules {
+            for (rule, metrics) in self.rules.iter().zip(metrics.iter_mut()) {
+                if rule.is_optional() && budget_exceeded() {
+                    metrics.skipped += 1;
+                    debug!(
+                        "Skipping optimizer rule '{}' (pass {}): time budget exceeded",
+                        rule.name(),
+                        i
+                    );
+                    continue;
+                }
+                let rule_start = Instant::now();
                 let result =
                     self.optimize_recursively(rule, &new_plan, config)
                         .and_then(|plan| {
                             if let Some(plan) = &plan {
                                 assert_schema_is_the_same(rule.name(), &new_plan, plan)?;
+                                if cfg!(debug_assertions) {
+                                    assert_plan_invariants(rule.name(), plan)?;
+                                }
                             }
                             Ok(plan)
                         });
+                metrics.elapsed += rule_start.elapsed();
                 match result {
                     Ok(Some(plan)) => {
+                        metrics.rewrites += 1;
                         new_plan = plan;
                         observer(&new_plan, rule.as_ref());
                         log_plan(rule.name(), &new_plan);
@@ -341,11 +457,15 @@ impl Optimizer {
                 debug!("optimizer pass {} did not make changes", i);
                 break;
             }
+            if budget_exceeded() {
+                debug!("optimizer time budget exceeded after pass {}", i);
+                break;
+            }
             i += 1;
         }
         log_plan("Final optimized plan", &new_plan);
         debug!("Optimizer took {} ms", start_time.elapsed().as_millis());
-        Ok(new_plan)
+        Ok((new_plan, metrics))
     }
 
     fn optimize_node(
@@ -448,9 +568,48 @@ pub(crate) fn assert_schema_is_the_same(
     }
 }
 
+/// Returns an error if the inputs of a union of `plan` have different types
+/// than the union, such as after a rule retyped a literal in only one of
+/// them, which would fail when planning the union.
+///
+/// Unlike [`assert_schema_is_the_same`], it checks the whole plan, so it is
+/// only run in debug builds.
+pub(crate) fn assert_plan_invariants(rule_name: &str, plan: &LogicalPlan) -> Result<()> {
+    plan.apply(&mut |plan| {
+        let LogicalPlan::Union(union) = plan else {
+            return Ok(VisitRecursion::Continue);
+        };
+        let types = |schema: &DFSchema| {
+            schema
+                .fields()
+                .iter()
+                .map(|field| field.data_type().clone())
+                .collect::<Vec<_>>()
+        };
+        let union_types = types(&union.schema);
+        match union
+            .inputs
+            .iter()
+            .find(|input| types(input.schema()) != union_types)
+        {
+            Some(input) => Err(DataFusionError::Context(
+                String::from(rule_name),
+                Box::new(DataFusionError::Internal(format!(
+                    "Failed due to a union input of different types than the union, union schema: {:?}, input schema: {:?}",
+                    union.schema,
+                    input.schema()
+                ))),
+            )),
+            None => Ok(VisitRecursion::Continue),
+        }
+    })?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use super::ApplyOrder;
     use crate::optimizer::Optimizer;
@@ -461,7 +620,7 @@ mod tests {
         plan_err, DFField, DFSchema, DFSchemaRef, DataFusionError, Result,
     };
     use datafusion_expr::logical_plan::EmptyRelation;
-    use datafusion_expr::{col, lit, LogicalPlan, LogicalPlanBuilder, Projection};
+    use datafusion_expr::{col, lit, LogicalPlan, LogicalPlanBuilder, Projection, Union};
 
     #[test]
     fn skip_failing_rule() {
@@ -514,6 +673,27 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(debug_assertions)]
+    fn generate_different_union_input_types() -> Result<()> {
+        let opt = Optimizer::with_rules(vec![Arc::new(RetypeUnionInputRule {})]);
+        let config = OptimizerContext::new().with_skip_failing_rules(false);
+        let input = LogicalPlanBuilder::from(test_table_scan()?)
+            .project([lit(1i32).alias("x")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(input.clone())
+            .union(input)?
+            .build()?;
+        let err = opt.optimize(&plan, &config, &observe).unwrap_err();
+        assert!(
+            err.strip_backtrace().contains(
+                "retype union input rule\ncaused by\nInternal error: Failed due to a union input of different types than the union"
+            ),
+            "{err}"
+        );
+        Ok(())
+    }
+
     #[test]
     fn skip_generate_different_schema() {
         let opt = Optimizer::with_rules(vec![Arc::new(GetTableScanRule {})]);
@@ -601,6 +781,68 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn optimizer_rule_metrics() -> Result<()> {
+        let opt = Optimizer::with_rules(vec![
+            Arc::new(RotateProjectionRule::new(false)),
+            Arc::new(SleepRule::new(false)),
+        ]);
+        let config = OptimizerContext::new().with_max_passes(16);
+
+        let plan = LogicalPlanBuilder::empty(false)
+            .project([lit(1), lit(2), lit(3)])?
+            .project([lit(100)])?
+            .build()?;
+        let (_, metrics) = opt.optimize_with_metrics(&plan, &config, observe)?;
+
+        let counts = metrics
+            .iter()
+            .map(|m| (m.name.as_str(), m.rewrites, m.skipped))
+            .collect::<Vec<_>>();
+        assert_eq!(counts, [("rotate_projection", 3, 0), ("sleep", 0, 0)]);
+        assert!(metrics[1].elapsed >= SleepRule::DURATION * 3);
+        Ok(())
+    }
+
+    #[test]
+    fn optimizer_time_budget_skips_optional_rules() -> Result<()> {
+        let opt = Optimizer::with_rules(vec![
+            Arc::new(SleepRule::new(false)),
+            Arc::new(RotateProjectionRule::new(false)),
+            Arc::new(SleepRule::new(true)),
+        ]);
+        let config = OptimizerContext::new()
+            .with_max_passes(16)
+            .with_max_optimization_time_ms(1);
+
+        let plan = LogicalPlanBuilder::empty(false)
+            .project([lit(1), lit(2), lit(3)])?
+            .project([lit(100)])?
+            .build()?;
+        let (optimized_plan, metrics) =
+            opt.optimize_with_metrics(&plan, &config, observe)?;
+
+        // the rotation is skipped, and no further passes are attempted
+        assert_eq!(optimized_plan, plan);
+        let counts = metrics
+            .iter()
+            .map(|m| (m.name.as_str(), m.rewrites, m.skipped))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            [
+                ("sleep", 0, 0),
+                ("rotate_projection", 0, 1),
+                ("sleep", 0, 0)
+            ]
+        );
+        assert_eq!(
+            metrics[1].to_string(),
+            "rotate_projection: elapsed=0ns, rewrites=0, skipped=1"
+        );
+        Ok(())
+    }
+
     fn add_metadata_to_fields(schema: &DFSchema) -> DFSchemaRef {
         let new_fields = schema
             .fields()
@@ -659,6 +901,64 @@ mod tests {
         }
     }
 
+    /// Retypes the literal of the inputs of a union, keeping its schema
+    struct RetypeUnionInputRule {}
+
+    impl OptimizerRule for RetypeUnionInputRule {
+        fn try_optimize(
+            &self,
+            plan: &LogicalPlan,
+            _: &dyn OptimizerConfig,
+        ) -> Result<Option<LogicalPlan>> {
+            let LogicalPlan::Union(union) = plan else {
+                return Ok(None);
+            };
+            let input = LogicalPlanBuilder::from(test_table_scan()?)
+                .project([lit(1i64).alias("x")])?
+                .build()?;
+            Ok(Some(LogicalPlan::Union(Union {
+                inputs: vec![Arc::new(input.clone()), Arc::new(input)],
+                schema: union.schema.clone(),
+            })))
+        }
+
+        fn name(&self) -> &str {
+            "retype union input rule"
+        }
+    }
+
+    /// Takes [`SleepRule::DURATION`] to not change the plan
+    struct SleepRule {
+        required: bool,
+    }
+
+    impl SleepRule {
+        const DURATION: Duration = Duration::from_millis(5);
+
+        fn new(required: bool) -> Self {
+            Self { required }
+        }
+    }
+
+    impl OptimizerRule for SleepRule {
+        fn try_optimize(
+            &self,
+            _: &LogicalPlan,
+            _: &dyn OptimizerConfig,
+        ) -> Result<Option<LogicalPlan>> {
+            std::thread::sleep(Self::DURATION);
+            Ok(None)
+        }
+
+        fn name(&self) -> &str {
+            "sleep"
+        }
+
+        fn is_optional(&self) -> bool {
+            !self.required
+        }
+    }
+
     /// A goofy rule doing rotation of columns in all projections.
     ///
     /// Useful to test cycle detection.
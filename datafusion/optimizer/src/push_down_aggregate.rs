@@ -0,0 +1,226 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`PushDownAggregate`] lets table sources compute aggregates themselves
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+use datafusion_common::{plan_err, DataFusionError, Result};
+use datafusion_expr::logical_plan::{Aggregate, LogicalPlan, TableScan};
+use datafusion_expr::{Expr, LogicalPlanBuilder};
+
+/// Optimization rule that replaces an [`Aggregate`] directly on top of a
+/// [`TableScan`] with a scan of the aggregated table, if the table source
+/// supports computing the aggregation (see
+/// [`TableSource::supports_aggregate_pushdown`]).
+///
+/// This rule should be applied after `PushDownFilter`, so that any filters
+/// supported by the source are part of the [`TableScan`].
+///
+/// [`TableSource::supports_aggregate_pushdown`]: datafusion_expr::TableSource::supports_aggregate_pushdown
+#[derive(Default)]
+pub struct PushDownAggregate {}
+
+impl PushDownAggregate {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for PushDownAggregate {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        let LogicalPlan::Aggregate(Aggregate {
+            input,
+            group_expr,
+            aggr_expr,
+            schema,
+            ..
+        }) = plan
+        else {
+            return Ok(None);
+        };
+        let LogicalPlan::TableScan(TableScan {
+            table_name,
+            source,
+            filters,
+            fetch: None,
+            ..
+        }) = input.as_ref()
+        else {
+            return Ok(None);
+        };
+        if group_expr.iter().any(|e| matches!(e, Expr::GroupingSet(_)))
+            || !source.supports_aggregate_pushdown(group_expr, aggr_expr, filters)?
+        {
+            return Ok(None);
+        }
+
+        let aggregated = source.aggregate_pushdown(group_expr, aggr_expr, filters)?;
+        let aggregated_schema = aggregated.schema();
+        let fields = schema.fields();
+        if aggregated_schema.fields().len() != fields.len()
+            || aggregated_schema
+                .fields()
+                .iter()
+                .zip(fields)
+                .any(|(a, b)| a.data_type() != b.data_type())
+        {
+            return plan_err!(
+                "Aggregate pushed down to table {table_name} returned schema {aggregated_schema:?}, \
+                but the aggregation produces {schema:?}"
+            );
+        }
+
+        // Restore the names of the aggregate output columns
+        let scan =
+            LogicalPlanBuilder::scan(table_name.clone(), aggregated, None)?.build()?;
+        let exprs = scan
+            .schema()
+            .fields()
+            .iter()
+            .zip(fields)
+            .map(|(field, output)| {
+                Expr::Column(field.qualified_column())
+                    .alias_qualified(output.qualifier().cloned(), output.name())
+            })
+            .collect::<Vec<_>>();
+        Ok(Some(
+            LogicalPlanBuilder::from(scan).project(exprs)?.build()?,
+        ))
+    }
+
+    fn name(&self) -> &str {
+        "push_down_aggregate"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_expr::{col, count, lit, max, TableSource};
+    use std::any::Any;
+    use std::sync::Arc;
+
+    /// A table source that can compute `MAX` aggregates
+    struct MaxSource {
+        schema: SchemaRef,
+    }
+
+    impl TableSource for MaxSource {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+
+        fn supports_aggregate_pushdown(
+            &self,
+            group_expr: &[Expr],
+            aggr_expr: &[Expr],
+            filters: &[Expr],
+        ) -> Result<bool> {
+            Ok(group_expr.is_empty()
+                && filters.is_empty()
+                && aggr_expr
+                    .iter()
+                    .all(|e| e.display_name().unwrap().starts_with("MAX")))
+        }
+
+        fn aggregate_pushdown(
+            &self,
+            _group_expr: &[Expr],
+            aggr_expr: &[Expr],
+            _filters: &[Expr],
+        ) -> Result<Arc<dyn TableSource>> {
+            let fields = (0..aggr_expr.len())
+                .map(|i| Field::new(format!("max_{i}"), DataType::UInt32, true))
+                .collect::<Vec<_>>();
+            Ok(Arc::new(MaxSource {
+                schema: Arc::new(Schema::new(fields)),
+            }))
+        }
+    }
+
+    fn max_table_scan() -> Result<LogicalPlan> {
+        let source = Arc::new(MaxSource {
+            schema: Arc::new(Schema::new(test_table_scan_fields())),
+        });
+        LogicalPlanBuilder::scan("test", source, None)?.build()
+    }
+
+    fn assert_optimized_plan_equal(plan: &LogicalPlan, expected: &str) -> Result<()> {
+        assert_optimized_plan_eq(Arc::new(PushDownAggregate::new()), plan, expected)
+    }
+
+    #[test]
+    fn push_down_supported_aggregate() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(max_table_scan()?)
+            .aggregate(Vec::<Expr>::new(), vec![max(col("a")), max(col("b"))])?
+            .build()?;
+
+        let expected = "Projection: test.max_0 AS MAX(test.a), test.max_1 AS MAX(test.b)\
+        \n  TableScan: test";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn unsupported_aggregate() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(max_table_scan()?)
+            .aggregate(Vec::<Expr>::new(), vec![count(col("a"))])?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[]], aggr=[[COUNT(test.a)]]\
+        \n  TableScan: test";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn not_pushed_through_filter() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(max_table_scan()?)
+            .filter(col("a").gt(lit(1u32)))?
+            .aggregate(Vec::<Expr>::new(), vec![max(col("b"))])?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[]], aggr=[[MAX(test.b)]]\
+        \n  Filter: test.a > UInt32(1)\
+        \n    TableScan: test";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn unsupported_group_by() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(max_table_scan()?)
+            .aggregate(vec![col("c")], vec![max(col("a"))])?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[test.c]], aggr=[[MAX(test.a)]]\
+        \n  TableScan: test";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+}
@@ -0,0 +1,311 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`PushDownAggregateUnion`] aggregates each input of a `UNION ALL` before
+//! concatenating them
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::Result;
+use datafusion_expr::aggregate_function::AggregateFunction::{
+    self, Count, Max, Min, Sum,
+};
+use datafusion_expr::expr::{self, AggregateFunctionDefinition, Alias};
+use datafusion_expr::logical_plan::{Aggregate, LogicalPlan, Projection, Union};
+use datafusion_expr::{cast, col, Expr, LogicalPlanBuilder};
+
+/// Optimization rule that pushes an [`Aggregate`] below a `UNION ALL`, so
+/// that each input (often a separate file or partition) is reduced before
+/// the inputs are concatenated. The partial results are then merged with a
+/// final [`Aggregate`]:
+///
+/// ```text
+/// Aggregate: groupBy=[[a]], aggr=[[SUM(b), COUNT(c)]]
+///   Union
+///     input1
+///     input2
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// Projection: group_0 AS a, SUM(agg_0) AS SUM(b), SUM(agg_1) AS COUNT(c)
+///   Aggregate: groupBy=[[group_0]], aggr=[[SUM(agg_0), SUM(agg_1)]]
+///     Union
+///       Aggregate: groupBy=[[a AS group_0]], aggr=[[SUM(b) AS agg_0, COUNT(c) AS agg_1]]
+///         input1
+///       Aggregate: groupBy=[[a AS group_0]], aggr=[[SUM(b) AS agg_0, COUNT(c) AS agg_1]]
+///         input2
+/// ```
+///
+/// Only `SUM`, `COUNT`, `MIN` and `MAX` without `DISTINCT`, `FILTER` or
+/// `ORDER BY` can be merged this way. Filters reach the inputs of the
+/// `Union` through `PushDownFilter`, and this rule should run after it so
+/// the aggregates of each input can in turn be pushed to its source by
+/// `PushDownAggregate`.
+#[derive(Default)]
+pub struct PushDownAggregateUnion {}
+
+impl PushDownAggregateUnion {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for PushDownAggregateUnion {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        let LogicalPlan::Aggregate(Aggregate {
+            input,
+            group_expr,
+            aggr_expr,
+            schema,
+            ..
+        }) = plan
+        else {
+            return Ok(None);
+        };
+        let LogicalPlan::Union(Union {
+            inputs,
+            schema: union_schema,
+        }) = input.as_ref()
+        else {
+            return Ok(None);
+        };
+        // A plain `DISTINCT` is already partially aggregated per input by the
+        // physical plan, and aggregated inputs (e.g. after this rule) gain nothing
+        if aggr_expr.is_empty()
+            || group_expr.iter().any(|e| matches!(e, Expr::GroupingSet(_)))
+            || inputs.iter().any(|input| is_aggregate(input))
+        {
+            return Ok(None);
+        }
+        let Some(functions) = aggr_expr
+            .iter()
+            .map(mergeable_aggregate)
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(None);
+        };
+
+        let partial_inputs = inputs
+            .iter()
+            .map(|input| {
+                // the union output columns are the input columns by position
+                let input_schema = input.schema();
+                let rewrite = |expr: &Expr| {
+                    expr.clone().transform_up(&|expr| {
+                        Ok(match expr {
+                            Expr::Column(column) => {
+                                let index = union_schema.index_of_column(&column)?;
+                                Transformed::Yes(Expr::Column(
+                                    input_schema.field(index).qualified_column(),
+                                ))
+                            }
+                            _ => Transformed::No(expr),
+                        })
+                    })
+                };
+                let group_expr = group_expr
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| Ok(rewrite(e)?.unalias().alias(group_alias(i))))
+                    .collect::<Result<Vec<_>>>()?;
+                let aggr_expr = functions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (fun, args))| {
+                        let args = args.iter().map(&rewrite).collect::<Result<_>>()?;
+                        Ok(aggregate(fun.clone(), args).alias(agg_alias(i)))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Arc::new(
+                    LogicalPlanBuilder::from(input.as_ref().clone())
+                        .aggregate(group_expr, aggr_expr)?
+                        .build()?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let union = LogicalPlan::Union(Union {
+            schema: partial_inputs[0].schema().clone(),
+            inputs: partial_inputs,
+        });
+
+        // COUNTs are merged by summing them
+        let final_group = (0..group_expr.len())
+            .map(|i| col(group_alias(i)))
+            .collect::<Vec<_>>();
+        let final_aggr = functions
+            .iter()
+            .enumerate()
+            .map(|(i, (fun, _))| {
+                let fun = if *fun == Count { Sum } else { fun.clone() };
+                aggregate(fun, vec![col(agg_alias(i))])
+            })
+            .collect::<Vec<_>>();
+        let aggregate = LogicalPlanBuilder::from(union)
+            .aggregate(final_group, final_aggr)?
+            .build()?;
+
+        // Restore the names and types of the original aggregate output, e.g.
+        // the SUM of DECIMAL partial sums is wider than a single SUM
+        let exprs = aggregate
+            .schema()
+            .fields()
+            .iter()
+            .zip(schema.fields())
+            .map(|(field, output)| {
+                let expr = Expr::Column(field.qualified_column());
+                let expr = if field.data_type() == output.data_type() {
+                    expr
+                } else {
+                    cast(expr, output.data_type().clone())
+                };
+                expr.alias_qualified(output.qualifier().cloned(), output.name())
+            })
+            .collect::<Vec<_>>();
+        Ok(Some(
+            LogicalPlanBuilder::from(aggregate)
+                .project(exprs)?
+                .build()?,
+        ))
+    }
+
+    fn name(&self) -> &str {
+        "push_down_aggregate_union"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+/// Returns the function and arguments of `expr` if its partial results can
+/// be merged by another aggregate
+fn mergeable_aggregate(expr: &Expr) -> Option<(AggregateFunction, Vec<Expr>)> {
+    match expr {
+        Expr::Alias(Alias { expr, .. }) => mergeable_aggregate(expr),
+        Expr::AggregateFunction(expr::AggregateFunction {
+            func_def:
+                AggregateFunctionDefinition::BuiltIn(fun @ (Sum | Count | Min | Max)),
+            args,
+            distinct: false,
+            filter: None,
+            order_by: None,
+        }) => Some((fun.clone(), args.clone())),
+        _ => None,
+    }
+}
+
+fn is_aggregate(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Aggregate(_) => true,
+        LogicalPlan::Projection(Projection { input, .. }) => is_aggregate(input),
+        _ => false,
+    }
+}
+
+fn aggregate(fun: AggregateFunction, args: Vec<Expr>) -> Expr {
+    Expr::AggregateFunction(expr::AggregateFunction::new(fun, args, false, None, None))
+}
+
+fn group_alias(i: usize) -> String {
+    format!("group_{i}")
+}
+
+fn agg_alias(i: usize) -> String {
+    format!("agg_{i}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use datafusion_expr::{avg, count, count_distinct, max, sum};
+
+    fn union_scan() -> Result<LogicalPlanBuilder> {
+        Ok(LogicalPlanBuilder::from(test_table_scan_with_name("test")?)
+            .union(test_table_scan_with_name("test2")?)?)
+    }
+
+    fn assert_optimized_plan_equal(plan: &LogicalPlan, expected: &str) -> Result<()> {
+        assert_optimized_plan_eq(Arc::new(PushDownAggregateUnion::new()), plan, expected)
+    }
+
+    #[test]
+    fn push_down_aggregate_below_union() -> Result<()> {
+        let plan = union_scan()?
+            .aggregate(vec![col("a")], vec![sum(col("b")), count(col("c"))])?
+            .build()?;
+
+        let expected = "Projection: group_0 AS a, SUM(agg_0) AS SUM(test.b), SUM(agg_1) AS COUNT(test.c)\
+        \n  Aggregate: groupBy=[[group_0]], aggr=[[SUM(agg_0), SUM(agg_1)]]\
+        \n    Union\
+        \n      Aggregate: groupBy=[[test.a AS group_0]], aggr=[[SUM(test.b) AS agg_0, COUNT(test.c) AS agg_1]]\
+        \n        TableScan: test\
+        \n      Aggregate: groupBy=[[test2.a AS group_0]], aggr=[[SUM(test2.b) AS agg_0, COUNT(test2.c) AS agg_1]]\
+        \n        TableScan: test2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn push_down_aggregate_without_group_by() -> Result<()> {
+        let plan = union_scan()?
+            .aggregate(Vec::<Expr>::new(), vec![max(col("a") + col("b"))])?
+            .build()?;
+
+        let expected = "Projection: MAX(agg_0) AS MAX(test.a + test.b)\
+        \n  Aggregate: groupBy=[[]], aggr=[[MAX(agg_0)]]\
+        \n    Union\
+        \n      Aggregate: groupBy=[[]], aggr=[[MAX(test.a + test.b) AS agg_0]]\
+        \n        TableScan: test\
+        \n      Aggregate: groupBy=[[]], aggr=[[MAX(test2.a + test2.b) AS agg_0]]\
+        \n        TableScan: test2";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn unmergeable_aggregate() -> Result<()> {
+        for aggr_expr in [avg(col("b")), count_distinct(col("b"))] {
+            let plan = union_scan()?
+                .aggregate(vec![col("a")], vec![sum(col("c")), aggr_expr])?
+                .build()?;
+            assert_optimization_skipped(Arc::new(PushDownAggregateUnion::new()), &plan)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn aggregated_union_inputs() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("test")?)
+            .aggregate(vec![col("a")], Vec::<Expr>::new())?
+            .union(
+                LogicalPlanBuilder::from(test_table_scan_with_name("test2")?)
+                    .aggregate(vec![col("a")], Vec::<Expr>::new())?
+                    .build()?,
+            )?
+            .aggregate(vec![col("a")], vec![count(col("a"))])?
+            .build()?;
+        assert_optimization_skipped(Arc::new(PushDownAggregateUnion::new()), &plan)
+    }
+}
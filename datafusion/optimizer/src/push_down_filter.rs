@@ -563,16 +563,6 @@ fn push_down_join(
     let mut is_inner_join = false;
     let infer_predicates = if join.join_type == JoinType::Inner {
         is_inner_join = true;
-        // Only allow both side key is column.
-        let join_col_keys = join
-            .on
-            .iter()
-            .flat_map(|(l, r)| match (l.try_into_col(), r.try_into_col()) {
-                (Ok(l_col), Ok(r_col)) => Some((l_col, r_col)),
-                _ => None,
-            })
-            .collect::<Vec<_>>();
-        // TODO refine the logic, introduce EquivalenceProperties to logical plan and infer additional filters to push down
         // For inner joins, duplicate filters for joined columns so filters can be pushed down
         // to both sides. Take the following query as an example:
         //
@@ -583,46 +573,32 @@ fn push_down_join(
         // `t1.id > 1` predicate needs to be pushed down to t1 table scan, while
         // `t2.uid > 1` predicate needs to be pushed down to t2 table scan.
         //
+        // The join keys of the inner joins below this one are taken into account
+        // as well, so in
+        //
+        // ```sql
+        // SELECT * FROM t1 JOIN t2 ON t1.id = t2.id JOIN t3 ON t2.id = t3.id WHERE t1.id > 1
+        // ```
+        //
+        // `t3.id > 1` is pushed down to t3 table scan, even if `t1.id` is not
+        // a key of the join with t3.
+        //
         // Join clauses with `Using` constraints also take advantage of this logic to make sure
         // predicates reference the shared join columns are pushed to both sides.
         // This logic should also been applied to conditions in JOIN ON clause
-        predicates
-            .iter()
-            .chain(on_filters.iter())
-            .filter_map(|predicate| {
-                let mut join_cols_to_replace = HashMap::new();
-                let columns = match predicate.to_columns() {
-                    Ok(columns) => columns,
-                    Err(e) => return Some(Err(e)),
-                };
-
-                for col in columns.iter() {
-                    for (l, r) in join_col_keys.iter() {
-                        if col == l {
-                            join_cols_to_replace.insert(col, r);
-                            break;
-                        } else if col == r {
-                            join_cols_to_replace.insert(col, l);
-                            break;
-                        }
-                    }
-                }
-
-                if join_cols_to_replace.is_empty() {
-                    return None;
+        let classes = join_equivalence_classes(join);
+        let mut infer_predicates: Vec<Expr> = vec![];
+        for predicate in predicates.iter().chain(on_filters.iter()) {
+            for inferred in infer_join_predicates(predicate, &classes)? {
+                if !predicates.contains(&inferred)
+                    && !on_filters.contains(&inferred)
+                    && !infer_predicates.contains(&inferred)
+                {
+                    infer_predicates.push(inferred);
                 }
-
-                let join_side_predicate =
-                    match replace_col(predicate.clone(), &join_cols_to_replace) {
-                        Ok(p) => p,
-                        Err(e) => {
-                            return Some(Err(e));
-                        }
-                    };
-
-                Some(Ok(join_side_predicate))
-            })
-            .collect::<Result<Vec<_>>>()?
+            }
+        }
+        infer_predicates
     } else {
         vec![]
     };
@@ -641,6 +617,88 @@ fn push_down_join(
     )?))
 }
 
+/// Returns the sets of columns that have equal values in the output of the
+/// inner `join`, from the column equality keys of it and of the inner joins
+/// directly below it
+fn join_equivalence_classes(join: &Join) -> Vec<Vec<Column>> {
+    let mut pairs = vec![];
+    collect_join_key_pairs(join, &mut pairs);
+
+    let mut classes: Vec<Vec<Column>> = vec![];
+    for (l, r) in pairs {
+        let mut merged = vec![l, r];
+        classes.retain(|class| {
+            if class.iter().any(|col| merged.contains(col)) {
+                merged.extend(class.iter().cloned());
+                false
+            } else {
+                true
+            }
+        });
+        classes.push(merged.into_iter().unique().collect());
+    }
+    classes
+}
+
+/// Collects the pairs of columns compared by the keys of `join` and of the
+/// inner joins below it
+fn collect_join_key_pairs(join: &Join, pairs: &mut Vec<(Column, Column)>) {
+    // Only allow both side key is column.
+    pairs.extend(join.on.iter().filter_map(|(l, r)| {
+        match (l.try_into_col(), r.try_into_col()) {
+            (Ok(l_col), Ok(r_col)) => Some((l_col, r_col)),
+            _ => None,
+        }
+    }));
+    for input in [&join.left, &join.right] {
+        if let LogicalPlan::Join(input) = input.as_ref() {
+            if input.join_type == JoinType::Inner {
+                collect_join_key_pairs(input, pairs);
+            }
+        }
+    }
+}
+
+/// Returns the predicates equivalent to `predicate` obtained by replacing
+/// its columns with other columns of their equivalence `classes`.
+///
+/// A predicate on a single class, such as `t1.id > 1`, is rewritten onto
+/// every other column of the class. A predicate using several classes is
+/// rewritten once, onto the next column of each class. Predicates that
+/// compare columns of the same class are left alone, as they are join
+/// conditions rather than filters.
+fn infer_join_predicates(predicate: &Expr, classes: &[Vec<Column>]) -> Result<Vec<Expr>> {
+    let columns = predicate.to_columns()?;
+    let mut used = vec![];
+    for class in classes {
+        let mut class_columns = class.iter().filter(|col| columns.contains(*col));
+        match (class_columns.next(), class_columns.next()) {
+            (Some(col), None) => used.push((col, class)),
+            (Some(_), Some(_)) => return Ok(vec![]),
+            _ => {}
+        }
+    }
+
+    match used.as_slice() {
+        [] => Ok(vec![]),
+        [(col, class)] => class
+            .iter()
+            .filter(|other| other != col)
+            .map(|other| replace_col(predicate.clone(), &HashMap::from([(*col, other)])))
+            .collect(),
+        _ => {
+            let replace_map = used
+                .iter()
+                .map(|(col, class)| {
+                    let position = class.iter().position(|other| other == *col).unwrap();
+                    (*col, &class[(position + 1) % class.len()])
+                })
+                .collect::<HashMap<_, _>>();
+            Ok(vec![replace_col(predicate.clone(), &replace_map)?])
+        }
+    }
+}
+
 impl OptimizerRule for PushDownFilter {
     fn name(&self) -> &str {
         "push_down_filter"
@@ -2167,6 +2225,51 @@ mod tests {
     }
 
     /// single table predicate parts of ON condition should be pushed to right input
+    /// post-join predicates on a join key are pushed to the sides of the
+    /// joins below with an equal key
+    #[test]
+    fn filter_on_transitive_join_keys() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .join(
+                test_table_scan_with_name("test2")?,
+                JoinType::Inner,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+                None,
+            )?
+            .join(
+                test_table_scan_with_name("test3")?,
+                JoinType::Inner,
+                (
+                    vec![Column::from_qualified_name("test2.a")],
+                    vec![Column::from_qualified_name("test3.a")],
+                ),
+                None,
+            )?
+            .filter(col("test.a").lt_eq(lit(1i64)))?
+            .build()?;
+
+        // not part of the test, just good to know:
+        assert_eq!(
+            format!("{plan:?}"),
+            "Filter: test.a <= Int64(1)\
+            \n  Inner Join: test2.a = test3.a\
+            \n    Inner Join: test.a = test2.a\
+            \n      TableScan: test\
+            \n      TableScan: test2\
+            \n    TableScan: test3"
+        );
+
+        // `test3.a <= 1` is pushed down, although `test.a` is not a key of the
+        // join with test3
+        let expected = "\
+        Inner Join: test2.a = test3.a\
+        \n  Inner Join: test.a = test2.a\
+        \n    TableScan: test, full_filters=[test.a <= Int64(1)]\
+        \n    TableScan: test2, full_filters=[test2.a <= Int64(1)]\
+        \n  TableScan: test3, full_filters=[test3.a <= Int64(1)]";
+        assert_optimized_plan_eq(&plan, expected)
+    }
+
     #[test]
     fn left_join_on_with_filter() -> Result<()> {
         let table_scan = test_table_scan()?;
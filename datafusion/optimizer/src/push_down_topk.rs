@@ -0,0 +1,247 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`PushDownTopK`] lets table sources return only the top rows of an
+//! `ORDER BY ... LIMIT`
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+use datafusion_common::{plan_err, DataFusionError, Result};
+use datafusion_expr::logical_plan::{LogicalPlan, Sort, TableScan};
+
+/// Optimization rule that replaces the source of a [`TableScan`] directly
+/// below a [`Sort`] with a `fetch` by a source of only the top rows, if the
+/// table source supports it (see [`TableSource::supports_topk_pushdown`]).
+///
+/// The [`Sort`] is kept above the scan, so the source may return more rows
+/// than the `fetch`, in any order.
+///
+/// This rule should be applied after `PushDownLimit` and `PushDownFilter`,
+/// so that the limit is part of the [`Sort`] and any filters supported by
+/// the source are part of the [`TableScan`].
+///
+/// [`TableSource::supports_topk_pushdown`]: datafusion_expr::TableSource::supports_topk_pushdown
+#[derive(Default)]
+pub struct PushDownTopK {}
+
+impl PushDownTopK {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for PushDownTopK {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        let LogicalPlan::Sort(Sort {
+            expr,
+            input,
+            fetch: Some(k),
+        }) = plan
+        else {
+            return Ok(None);
+        };
+        let LogicalPlan::TableScan(
+            scan @ TableScan {
+                table_name,
+                source,
+                filters,
+                fetch: None,
+                ..
+            },
+        ) = input.as_ref()
+        else {
+            return Ok(None);
+        };
+        if !source.supports_topk_pushdown(expr, *k, filters)? {
+            return Ok(None);
+        }
+
+        let topk = source.topk_pushdown(expr, *k, filters)?;
+        if topk.schema() != source.schema() {
+            return plan_err!(
+                "Top-k pushed down to table {table_name} returned schema {:?}, \
+                but the table has schema {:?}",
+                topk.schema(),
+                source.schema()
+            );
+        }
+        let scan = TableScan {
+            source: topk,
+            ..scan.clone()
+        };
+        Ok(Some(LogicalPlan::Sort(Sort {
+            expr: expr.clone(),
+            input: Arc::new(LogicalPlan::TableScan(scan)),
+            fetch: Some(*k),
+        })))
+    }
+
+    fn name(&self) -> &str {
+        "push_down_topk"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use crate::OptimizerContext;
+    use arrow::datatypes::{Schema, SchemaRef};
+    use datafusion_expr::{col, lit, Expr, LogicalPlanBuilder, TableSource};
+    use std::any::Any;
+
+    /// A table source that can return the top rows ordered by column `a`
+    struct IndexedSource {
+        schema: SchemaRef,
+        topk: Option<usize>,
+    }
+
+    impl IndexedSource {
+        fn new() -> Self {
+            Self {
+                schema: Arc::new(Schema::new(test_table_scan_fields())),
+                topk: None,
+            }
+        }
+    }
+
+    impl TableSource for IndexedSource {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+
+        fn supports_topk_pushdown(
+            &self,
+            sort_exprs: &[Expr],
+            _k: usize,
+            filters: &[Expr],
+        ) -> Result<bool> {
+            Ok(self.topk.is_none()
+                && filters.is_empty()
+                && sort_exprs == [col("test.a").sort(true, false)])
+        }
+
+        fn topk_pushdown(
+            &self,
+            _sort_exprs: &[Expr],
+            k: usize,
+            _filters: &[Expr],
+        ) -> Result<Arc<dyn TableSource>> {
+            Ok(Arc::new(Self {
+                schema: self.schema.clone(),
+                topk: Some(k),
+            }))
+        }
+    }
+
+    fn indexed_table_scan() -> Result<LogicalPlanBuilder> {
+        LogicalPlanBuilder::scan("test", Arc::new(IndexedSource::new()), None)
+    }
+
+    /// Sorts `input` by `column`, keeping the first `fetch` rows
+    fn sort_with_fetch(
+        input: LogicalPlanBuilder,
+        column: &str,
+        fetch: Option<usize>,
+    ) -> Result<LogicalPlan> {
+        let LogicalPlan::Sort(sort) =
+            input.sort(vec![col(column).sort(true, false)])?.build()?
+        else {
+            unreachable!()
+        };
+        Ok(LogicalPlan::Sort(Sort { fetch, ..sort }))
+    }
+
+    /// Returns the `k` of the source of the scan below the sort of `plan`
+    fn scan_topk(plan: &LogicalPlan) -> Option<usize> {
+        let LogicalPlan::Sort(Sort { input, .. }) = plan else {
+            panic!("expected Sort, got {plan:?}");
+        };
+        let LogicalPlan::TableScan(scan) = input.as_ref() else {
+            panic!("expected TableScan, got {input:?}");
+        };
+        let source = scan
+            .source
+            .as_any()
+            .downcast_ref::<IndexedSource>()
+            .unwrap();
+        source.topk
+    }
+
+    fn optimize(plan: &LogicalPlan) -> Result<LogicalPlan> {
+        let rule = PushDownTopK::new();
+        let config = OptimizerContext::new();
+        Ok(rule
+            .try_optimize(plan, &config)?
+            .unwrap_or_else(|| plan.clone()))
+    }
+
+    #[test]
+    fn push_down_supported_topk() -> Result<()> {
+        let plan = sort_with_fetch(indexed_table_scan()?, "a", Some(5))?;
+
+        let optimized = optimize(&plan)?;
+        // the sort is kept to check the rows of the source
+        assert_eq!(
+            format!("{optimized:?}"),
+            "Sort: test.a ASC NULLS LAST, fetch=5\
+            \n  TableScan: test"
+        );
+        assert_eq!(scan_topk(&optimized), Some(5));
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_topk() -> Result<()> {
+        let plans = [
+            // without a limit
+            sort_with_fetch(indexed_table_scan()?, "a", None)?,
+            // unsupported ordering
+            sort_with_fetch(indexed_table_scan()?, "b", Some(5))?,
+            // unsupported filter
+            sort_with_fetch(
+                LogicalPlanBuilder::scan_with_filters(
+                    "test",
+                    Arc::new(IndexedSource::new()),
+                    None,
+                    vec![col("b").gt(lit(1u32))],
+                )?,
+                "a",
+                Some(5),
+            )?,
+        ];
+        for plan in plans {
+            assert_optimization_skipped(Arc::new(PushDownTopK::new()), &plan)?;
+            assert_eq!(scan_topk(&optimize(&plan)?), None);
+        }
+        Ok(())
+    }
+}
@@ -150,6 +150,11 @@ impl OptimizerRule for ReplaceDistinctWithAggregate {
     fn apply_order(&self) -> Option<ApplyOrder> {
         Some(BottomUp)
     }
+
+    /// `Distinct` can not be executed without this rule
+    fn is_optional(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::analyzer::subquery::has_non_pullup_correlation;
 use crate::decorrelate::{PullUpCorrelatedExpr, UN_MATCHED_ROW_INDICATOR};
 use crate::optimizer::ApplyOrder;
 use crate::utils::replace_qualified_name;
@@ -23,10 +24,12 @@ use datafusion_common::alias::AliasGenerator;
 use datafusion_common::tree_node::{
     RewriteRecursion, Transformed, TreeNode, TreeNodeRewriter,
 };
-use datafusion_common::{plan_err, Column, DataFusionError, Result, ScalarValue};
+use datafusion_common::{
+    plan_err, Column, DataFusionError, Result, ScalarValue, TableReference,
+};
 use datafusion_expr::expr_rewriter::create_col_from_scalar_expr;
 use datafusion_expr::logical_plan::{JoinType, Subquery};
-use datafusion_expr::utils::conjunction;
+use datafusion_expr::utils::{conjunction, find_out_reference_exprs, split_conjunction};
 use datafusion_expr::{expr, EmptyRelation, Expr, LogicalPlan, LogicalPlanBuilder};
 use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
@@ -193,6 +196,11 @@ impl OptimizerRule for ScalarSubqueryToJoin {
     fn apply_order(&self) -> Option<ApplyOrder> {
         Some(ApplyOrder::TopDown)
     }
+
+    /// Scalar subqueries can not be executed without this rule
+    fn is_optional(&self) -> bool {
+        false
+    }
 }
 
 struct ExtractScalarSubQuery {
@@ -271,7 +279,14 @@ fn build_join(
     filter_input: &LogicalPlan,
     subquery_alias: &str,
 ) -> Result<Option<(LogicalPlan, HashMap<String, Expr>)>> {
-    let subquery_plan = subquery.subquery.as_ref();
+    let subquery_plan = match decorrelate_with_outer_domain(
+        subquery.subquery.as_ref(),
+        filter_input,
+        subquery_alias,
+    )? {
+        Some(plan) => plan,
+        None => subquery.subquery.as_ref().clone(),
+    };
     let mut pull_up = PullUpCorrelatedExpr {
         join_filters: vec![],
         correlated_subquery_cols_map: Default::default(),
@@ -282,7 +297,7 @@ fn build_join(
         collected_count_expr_map: Default::default(),
         pull_up_having_expr: None,
     };
-    let new_plan = subquery_plan.clone().rewrite(&mut pull_up)?;
+    let new_plan = subquery_plan.rewrite(&mut pull_up)?;
     if !pull_up.can_pull_up {
         return Ok(None);
     }
@@ -369,6 +384,126 @@ fn build_join(
     Ok(Some((new_plan, computation_project_expr)))
 }
 
+/// Rewrites an ungrouped aggregation over correlated predicates that can not
+/// be pulled up over the aggregation, such as non-equality predicates or
+/// disjunctions:
+///
+/// ```text
+/// Aggregate: groupBy=[[]], aggr=[[MAX(orders.o_custkey)]]
+///   Filter: orders.o_custkey < outer_ref(customer.c_custkey)
+///     TableScan: orders
+/// ```
+///
+/// into an aggregation over the join of the subquery input with the distinct
+/// values of the correlated outer columns (their "domain"):
+///
+/// ```text
+/// Aggregate: groupBy=[[]], aggr=[[MAX(orders.o_custkey)]]
+///   Filter: __scalar_sq_1_domain.c_custkey = outer_ref(customer.c_custkey)
+///     Inner Join:  Filter: orders.o_custkey < __scalar_sq_1_domain.c_custkey
+///       TableScan: orders
+///       SubqueryAlias: __scalar_sq_1_domain
+///         Aggregate: groupBy=[[customer.c_custkey]], aggr=[[]]
+///           TableScan: customer
+/// ```
+///
+/// The only remaining correlated predicates are then equalities, which are
+/// pulled up as usual. Returns `None` if the subquery does not have this shape.
+fn decorrelate_with_outer_domain(
+    plan: &LogicalPlan,
+    outer_input: &LogicalPlan,
+    subquery_alias: &str,
+) -> Result<Option<LogicalPlan>> {
+    match plan {
+        LogicalPlan::Projection(projection) => {
+            decorrelate_with_outer_domain(&projection.input, outer_input, subquery_alias)?
+                .map(|input| plan.with_new_inputs(&[input]))
+                .transpose()
+        }
+        LogicalPlan::Aggregate(aggregate) if aggregate.group_expr.is_empty() => {
+            let LogicalPlan::Filter(filter) = aggregate.input.as_ref() else {
+                return Ok(None);
+            };
+            if !has_non_pullup_correlation(&filter.predicate) {
+                return Ok(None);
+            }
+            let (correlated, others): (Vec<_>, Vec<_>) =
+                split_conjunction(&filter.predicate)
+                    .into_iter()
+                    .cloned()
+                    .partition(|expr| expr.contains_outer());
+
+            let mut outer_refs = vec![];
+            for expr in correlated.iter().flat_map(find_out_reference_exprs) {
+                match &expr {
+                    Expr::OuterReferenceColumn(_, col)
+                        if outer_input.schema().has_column(col) =>
+                    {
+                        if !outer_refs.contains(&expr) {
+                            outer_refs.push(expr);
+                        }
+                    }
+                    // references a column of a query further out
+                    _ => return Ok(None),
+                }
+            }
+
+            let domain_alias = format!("{subquery_alias}_domain");
+            let domain_col = |col: &Column| {
+                Expr::Column(Column::new(
+                    Some(TableReference::bare(domain_alias.clone())),
+                    col.name.clone(),
+                ))
+            };
+            let outer_cols = outer_refs
+                .iter()
+                .filter_map(|expr| match expr {
+                    Expr::OuterReferenceColumn(_, col) => Some(col.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let domain = LogicalPlanBuilder::from(outer_input.clone())
+                .aggregate(
+                    outer_cols.iter().cloned().map(Expr::Column),
+                    Vec::<Expr>::new(),
+                )?
+                .alias(domain_alias.clone())?
+                .build()?;
+
+            let join_filter = conjunction(correlated)
+                .map(|expr| {
+                    expr.transform_up(&|expr| match expr {
+                        Expr::OuterReferenceColumn(_, col) => {
+                            Ok(Transformed::Yes(domain_col(&col)))
+                        }
+                        _ => Ok(Transformed::No(expr)),
+                    })
+                })
+                .transpose()?;
+            let domain_filter = conjunction(
+                outer_refs
+                    .into_iter()
+                    .zip(&outer_cols)
+                    .map(|(outer_ref, col)| domain_col(col).eq(outer_ref)),
+            );
+
+            let mut input = LogicalPlanBuilder::from(filter.input.as_ref().clone());
+            if let Some(others) = conjunction(others) {
+                input = input.filter(others)?;
+            }
+            let mut input = input.join_on(domain, JoinType::Inner, join_filter)?;
+            if let Some(domain_filter) = domain_filter {
+                input = input.filter(domain_filter)?;
+            }
+            input
+                .aggregate(Vec::<Expr>::new(), aggregate.aggr_expr.clone())?
+                .build()
+                .map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -608,11 +743,23 @@ mod tests {
             .project(vec![col("customer.c_custkey")])?
             .build()?;
 
-        let expected = "check_analyzed_plan\
-        \ncaused by\
-        \nError during planning: Correlated column is not allowed in predicate: outer_ref(customer.c_custkey) != orders.o_custkey";
-
-        assert_analyzer_check_err(vec![], &plan, expected);
+        let expected = "Projection: customer.c_custkey [c_custkey:Int64]\
+        \n  Filter: customer.c_custkey = __scalar_sq_1.MAX(orders.o_custkey) [c_custkey:Int64, c_name:Utf8, MAX(orders.o_custkey):Int64;N, c_custkey:Int64;N]\
+        \n    Left Join:  Filter: __scalar_sq_1.c_custkey = customer.c_custkey [c_custkey:Int64, c_name:Utf8, MAX(orders.o_custkey):Int64;N, c_custkey:Int64;N]\
+        \n      TableScan: customer [c_custkey:Int64, c_name:Utf8]\
+        \n      SubqueryAlias: __scalar_sq_1 [MAX(orders.o_custkey):Int64;N, c_custkey:Int64]\
+        \n        Projection: MAX(orders.o_custkey), __scalar_sq_1_domain.c_custkey [MAX(orders.o_custkey):Int64;N, c_custkey:Int64]\
+        \n          Aggregate: groupBy=[[__scalar_sq_1_domain.c_custkey]], aggr=[[MAX(orders.o_custkey)]] [c_custkey:Int64, MAX(orders.o_custkey):Int64;N]\
+        \n            Inner Join:  Filter: __scalar_sq_1_domain.c_custkey != orders.o_custkey [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N, c_custkey:Int64]\
+        \n              TableScan: orders [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N]\
+        \n              SubqueryAlias: __scalar_sq_1_domain [c_custkey:Int64]\
+        \n                Aggregate: groupBy=[[customer.c_custkey]], aggr=[[]] [c_custkey:Int64]\
+        \n                  TableScan: customer [c_custkey:Int64, c_name:Utf8]";
+        assert_multi_rules_optimized_plan_eq_display_indent(
+            vec![Arc::new(ScalarSubqueryToJoin::new())],
+            &plan,
+            expected,
+        );
         Ok(())
     }
 
@@ -635,11 +782,23 @@ mod tests {
             .project(vec![col("customer.c_custkey")])?
             .build()?;
 
-        let expected = "check_analyzed_plan\
-        \ncaused by\
-        \nError during planning: Correlated column is not allowed in predicate: outer_ref(customer.c_custkey) < orders.o_custkey";
-
-        assert_analyzer_check_err(vec![], &plan, expected);
+        let expected = "Projection: customer.c_custkey [c_custkey:Int64]\
+        \n  Filter: customer.c_custkey = __scalar_sq_1.MAX(orders.o_custkey) [c_custkey:Int64, c_name:Utf8, MAX(orders.o_custkey):Int64;N, c_custkey:Int64;N]\
+        \n    Left Join:  Filter: __scalar_sq_1.c_custkey = customer.c_custkey [c_custkey:Int64, c_name:Utf8, MAX(orders.o_custkey):Int64;N, c_custkey:Int64;N]\
+        \n      TableScan: customer [c_custkey:Int64, c_name:Utf8]\
+        \n      SubqueryAlias: __scalar_sq_1 [MAX(orders.o_custkey):Int64;N, c_custkey:Int64]\
+        \n        Projection: MAX(orders.o_custkey), __scalar_sq_1_domain.c_custkey [MAX(orders.o_custkey):Int64;N, c_custkey:Int64]\
+        \n          Aggregate: groupBy=[[__scalar_sq_1_domain.c_custkey]], aggr=[[MAX(orders.o_custkey)]] [c_custkey:Int64, MAX(orders.o_custkey):Int64;N]\
+        \n            Inner Join:  Filter: __scalar_sq_1_domain.c_custkey < orders.o_custkey [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N, c_custkey:Int64]\
+        \n              TableScan: orders [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N]\
+        \n              SubqueryAlias: __scalar_sq_1_domain [c_custkey:Int64]\
+        \n                Aggregate: groupBy=[[customer.c_custkey]], aggr=[[]] [c_custkey:Int64]\
+        \n                  TableScan: customer [c_custkey:Int64, c_name:Utf8]";
+        assert_multi_rules_optimized_plan_eq_display_indent(
+            vec![Arc::new(ScalarSubqueryToJoin::new())],
+            &plan,
+            expected,
+        );
         Ok(())
     }
 
@@ -663,11 +822,23 @@ mod tests {
             .project(vec![col("customer.c_custkey")])?
             .build()?;
 
-        let expected = "check_analyzed_plan\
-        \ncaused by\
-        \nError during planning: Correlated column is not allowed in predicate: outer_ref(customer.c_custkey) = orders.o_custkey OR orders.o_orderkey = Int32(1)";
-
-        assert_analyzer_check_err(vec![], &plan, expected);
+        let expected = "Projection: customer.c_custkey [c_custkey:Int64]\
+        \n  Filter: customer.c_custkey = __scalar_sq_1.MAX(orders.o_custkey) [c_custkey:Int64, c_name:Utf8, MAX(orders.o_custkey):Int64;N, c_custkey:Int64;N]\
+        \n    Left Join:  Filter: __scalar_sq_1.c_custkey = customer.c_custkey [c_custkey:Int64, c_name:Utf8, MAX(orders.o_custkey):Int64;N, c_custkey:Int64;N]\
+        \n      TableScan: customer [c_custkey:Int64, c_name:Utf8]\
+        \n      SubqueryAlias: __scalar_sq_1 [MAX(orders.o_custkey):Int64;N, c_custkey:Int64]\
+        \n        Projection: MAX(orders.o_custkey), __scalar_sq_1_domain.c_custkey [MAX(orders.o_custkey):Int64;N, c_custkey:Int64]\
+        \n          Aggregate: groupBy=[[__scalar_sq_1_domain.c_custkey]], aggr=[[MAX(orders.o_custkey)]] [c_custkey:Int64, MAX(orders.o_custkey):Int64;N]\
+        \n            Inner Join:  Filter: __scalar_sq_1_domain.c_custkey = orders.o_custkey OR orders.o_orderkey = Int32(1) [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N, c_custkey:Int64]\
+        \n              TableScan: orders [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N]\
+        \n              SubqueryAlias: __scalar_sq_1_domain [c_custkey:Int64]\
+        \n                Aggregate: groupBy=[[customer.c_custkey]], aggr=[[]] [c_custkey:Int64]\
+        \n                  TableScan: customer [c_custkey:Int64, c_name:Utf8]";
+        assert_multi_rules_optimized_plan_eq_display_indent(
+            vec![Arc::new(ScalarSubqueryToJoin::new())],
+            &plan,
+            expected,
+        );
         Ok(())
     }
 
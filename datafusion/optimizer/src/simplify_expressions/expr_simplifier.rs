@@ -34,7 +34,7 @@ use arrow::{
 use datafusion_common::{
     cast::{as_large_list_array, as_list_array},
     plan_err,
-    tree_node::{RewriteRecursion, TreeNode, TreeNodeRewriter},
+    tree_node::{RewriteRecursion, TreeNode, TreeNodeRewriter, VisitRecursion},
 };
 use datafusion_common::{
     exec_err, internal_err, DFSchema, DFSchemaRef, DataFusionError, Result, ScalarValue,
@@ -281,6 +281,15 @@ impl<'a> TreeNodeRewriter for ConstEvaluator<'a> {
 
     fn mutate(&mut self, expr: Expr) -> Result<Expr> {
         match self.can_evaluate.pop() {
+            // User defined functions may not support all the arguments they can
+            // be called with, such as in a branch of a `CASE` that is never taken,
+            // so their errors are left to the execution
+            Some(true) if Self::calls_udf(&expr) => {
+                match self.evaluate_to_scalar(expr.clone()) {
+                    Ok(value) => Ok(Expr::Literal(value)),
+                    Err(_) => Ok(expr),
+                }
+            }
             Some(true) => Ok(Expr::Literal(self.evaluate_to_scalar(expr)?)),
             Some(false) => Ok(expr),
             _ => internal_err!("Failed to pop can_evaluate"),
@@ -375,6 +384,25 @@ impl<'a> ConstEvaluator<'a> {
         }
     }
 
+    /// Does the expression call a user defined function?
+    fn calls_udf(expr: &Expr) -> bool {
+        let mut calls_udf = false;
+        expr.apply(&mut |expr| {
+            if let Expr::ScalarFunction(ScalarFunction {
+                func_def: ScalarFunctionDefinition::UDF(_),
+                ..
+            }) = expr
+            {
+                calls_udf = true;
+                return Ok(VisitRecursion::Stop);
+            }
+            Ok(VisitRecursion::Continue)
+        })
+        // the closure always returns Ok
+        .expect("no way to return error during recursion");
+        calls_udf
+    }
+
     /// Internal helper to evaluates an Expr
     pub(crate) fn evaluate_to_scalar(&mut self, expr: Expr) -> Result<ScalarValue> {
         if let Expr::Literal(s) = expr {
@@ -1590,6 +1618,41 @@ mod tests {
         test_evaluate(expr, expected_expr);
     }
 
+    #[test]
+    fn test_evaluator_udfs_with_errors() {
+        // a UDF dividing 100 by its argument, which fails for 0
+        let fun = make_scalar_function(|args: &[ArrayRef]| {
+            let array = as_int32_array(&args[0])?
+                .iter()
+                .map(|value| match value {
+                    Some(0) => exec_err!("Division by zero"),
+                    value => Ok(value.map(|value| 100 / value)),
+                })
+                .collect::<Result<Int32Array>>()?;
+            Ok(Arc::new(array) as ArrayRef)
+        });
+        let fun = Arc::new(create_udf(
+            "udf_div",
+            vec![DataType::Int32],
+            Arc::new(DataType::Int32),
+            Volatility::Immutable,
+            fun,
+        ));
+        let udf_div = |arg: Expr| {
+            Expr::ScalarFunction(expr::ScalarFunction::new_udf(
+                Arc::clone(&fun),
+                vec![arg],
+            ))
+        };
+
+        // udf_div(2 + 3) --> 20
+        test_evaluate(udf_div(lit(2) + lit(3)), lit(20));
+
+        // the error is left to the execution, with the arguments folded
+        // udf_div(1 - 1) + 1 --> udf_div(0) + 1
+        test_evaluate(udf_div(lit(1) - lit(1)) + lit(1), udf_div(lit(0)) + lit(1));
+    }
+
     // ------------------------------
     // --- Simplifier tests -----
     // ------------------------------
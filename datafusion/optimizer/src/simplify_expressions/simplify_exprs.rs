@@ -21,9 +21,12 @@ use std::sync::Arc;
 
 use super::{ExprSimplifier, SimplifyContext};
 use crate::{OptimizerConfig, OptimizerRule};
-use datafusion_common::{DFSchema, DFSchemaRef, Result};
+use arrow::datatypes::DataType;
+use datafusion_common::{DFSchema, DFSchemaRef, Result, ScalarValue};
+use datafusion_expr::expr::{Alias, Cast, Sort};
 use datafusion_expr::logical_plan::LogicalPlan;
-use datafusion_expr::utils::merge_schema;
+use datafusion_expr::utils::{merge_schema, split_conjunction};
+use datafusion_expr::{Expr, ExprSchemable, Like};
 use datafusion_physical_expr::execution_props::ExecutionProps;
 
 /// Optimizer Pass that simplifies [`LogicalPlan`]s by rewriting
@@ -38,7 +41,12 @@ use datafusion_physical_expr::execution_props::ExecutionProps;
 /// is optimized to
 /// `Filter: b > 2`
 ///
-/// [`Expr`]: datafusion_expr::Expr
+/// A filter on `col LIKE 'prefix%'` is also given the equivalent range on
+/// `col`, so that scans can prune files and row groups using min/max
+/// statistics, e.g.
+/// `Filter: a LIKE 'abc%'`
+/// becomes
+/// `Filter: a LIKE 'abc%' AND a >= 'abc' AND a < 'abd'`
 #[derive(Default)]
 pub struct SimplifyExpressions {}
 
@@ -54,6 +62,8 @@ impl OptimizerRule for SimplifyExpressions {
     ) -> Result<Option<LogicalPlan>> {
         let mut execution_props = ExecutionProps::new();
         execution_props.query_execution_start_time = config.query_execution_start_time();
+        execution_props.time_zone =
+            config.options().execution.time_zone.clone().map(Into::into);
         Ok(Some(Self::optimize_internal(plan, &execution_props)?))
     }
 }
@@ -83,7 +93,7 @@ impl SimplifyExpressions {
         } else {
             Arc::new(DFSchema::empty())
         };
-        let info = SimplifyContext::new(execution_props).with_schema(schema);
+        let info = SimplifyContext::new(execution_props).with_schema(schema.clone());
 
         let simplifier = ExprSimplifier::new(info);
 
@@ -99,7 +109,17 @@ impl SimplifyExpressions {
             .map(|e| {
                 // TODO: unify with `rewrite_preserving_name`
                 let original_name = e.name_for_alias()?;
+                let original_type = e.get_type(&schema).ok();
                 let new_e = simplifier.simplify(e)?;
+                let new_e = match original_type {
+                    Some(data_type) => preserve_type(new_e, &data_type, &schema),
+                    None => new_e,
+                };
+                let new_e = if matches!(plan, LogicalPlan::Filter(_)) {
+                    add_like_prefix_ranges(new_e, &schema)
+                } else {
+                    new_e
+                };
                 new_e.alias_if_changed(original_name)
             })
             .collect::<Result<Vec<_>>>()?;
@@ -115,6 +135,119 @@ impl SimplifyExpressions {
     }
 }
 
+/// Returns `expr` simplified from an expression of type `data_type`, cast
+/// back to `data_type` if its type differs.
+///
+/// Some simplifications return an operand whose type is not the type of the
+/// expression, such as `A * NULL` to `NULL`, which would change the schema
+/// of the plan, and could make the branches of a `UNION` differ.
+fn preserve_type(expr: Expr, data_type: &DataType, schema: &DFSchema) -> Expr {
+    match expr.get_type(schema) {
+        Ok(new_type) if new_type != *data_type => cast_to_type(expr, data_type),
+        _ => expr,
+    }
+}
+
+/// Casts `expr` to `data_type`, inside its alias or sort expression
+fn cast_to_type(expr: Expr, data_type: &DataType) -> Expr {
+    match expr {
+        Expr::Alias(alias) => Expr::Alias(Alias {
+            expr: Box::new(cast_to_type(*alias.expr, data_type)),
+            ..alias
+        }),
+        Expr::Sort(sort) => Expr::Sort(Sort {
+            expr: Box::new(cast_to_type(*sort.expr, data_type)),
+            ..sort
+        }),
+        Expr::Literal(value) => match value.cast_to(data_type) {
+            Ok(value) => Expr::Literal(value),
+            Err(_) => {
+                Expr::Cast(Cast::new(Box::new(Expr::Literal(value)), data_type.clone()))
+            }
+        },
+        expr => Expr::Cast(Cast::new(Box::new(expr), data_type.clone())),
+    }
+}
+
+/// Adds `col >= 'prefix'` and `col < upper` conjuncts to `predicate` for each
+/// of its `col LIKE 'prefix%'` conjuncts, unless it already has them. The
+/// `LIKE` is kept so that the predicate stays exact regardless of how the
+/// upper bound was derived
+fn add_like_prefix_ranges(predicate: Expr, schema: &DFSchema) -> Expr {
+    let conjuncts = split_conjunction(&predicate);
+    let mut ranges: Vec<Expr> = vec![];
+    for conjunct in &conjuncts {
+        let Some((lower, upper)) = like_prefix_range(conjunct, schema) else {
+            continue;
+        };
+        for range in std::iter::once(lower).chain(upper) {
+            if !conjuncts.contains(&&range) && !ranges.contains(&range) {
+                ranges.push(range);
+            }
+        }
+    }
+    ranges.into_iter().fold(predicate, Expr::and)
+}
+
+/// Returns `col >= 'prefix'` and, unless no string is greater than all
+/// strings starting with `prefix`, `col < upper` for a `like` of the form
+/// `col LIKE 'prefix%'`
+fn like_prefix_range(like: &Expr, schema: &DFSchema) -> Option<(Expr, Option<Expr>)> {
+    let Expr::Like(Like {
+        negated: false,
+        expr,
+        pattern,
+        escape_char: None,
+        case_insensitive: false,
+    }) = like
+    else {
+        return None;
+    };
+    let (pattern, string): (&str, fn(Option<String>) -> ScalarValue) = match pattern
+        .as_ref()
+    {
+        Expr::Literal(ScalarValue::Utf8(Some(pattern))) => (pattern, ScalarValue::Utf8),
+        Expr::Literal(ScalarValue::LargeUtf8(Some(pattern))) => {
+            (pattern, ScalarValue::LargeUtf8)
+        }
+        _ => return None,
+    };
+    let prefix = pattern.strip_suffix('%')?;
+    if prefix.is_empty() || prefix.contains(['%', '_', '\\']) {
+        return None;
+    }
+    // only columns can be pruned on, and the comparisons must not need casts
+    if !matches!(expr.as_ref(), Expr::Column(_))
+        || expr.get_type(schema).ok()? != string(None).data_type()
+    {
+        return None;
+    }
+
+    let lower = expr
+        .as_ref()
+        .clone()
+        .gt_eq(Expr::Literal(string(Some(prefix.to_string()))));
+    let upper = prefix_upper_bound(prefix)
+        .map(|upper| expr.as_ref().clone().lt(Expr::Literal(string(Some(upper)))));
+    Some((lower, upper))
+}
+
+/// Returns a string greater than all strings starting with `prefix`, by
+/// incrementing the last character of `prefix` that is not `char::MAX` and
+/// dropping the characters after it
+fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    let mut chars = prefix.chars().collect::<Vec<_>>();
+    while let Some(last) = chars.pop() {
+        // the surrogate code points are not characters, so skip them
+        if let Some(next) = (last as u32 + 1..=char::MAX as u32).find_map(char::from_u32)
+        {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::Not;
@@ -185,6 +318,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_simplify_preserves_expression_types() -> Result<()> {
+        let schema =
+            Schema::new(vec![Field::new("d", DataType::Decimal128(10, 2), true)]);
+        let plan = table_scan(Some("t"), &schema, None)?
+            .project(vec![col("d") * lit(ScalarValue::Decimal128(None, 5, 0))])?
+            .build()?;
+
+        // `d * NULL` is simplified to `NULL`, of the type of the product
+        // rather than of the null operand
+        let optimized_plan = SimplifyExpressions::new()
+            .try_optimize(&plan, &OptimizerContext::new())?
+            .expect("failed to optimize plan");
+        assert_eq!(
+            optimized_plan.schema().field(0).data_type(),
+            plan.schema().field(0).data_type()
+        );
+        let LogicalPlan::Projection(projection) = &optimized_plan else {
+            panic!("expected a projection, got {optimized_plan:?}")
+        };
+        assert!(
+            matches!(&projection.expr[0], Expr::Alias(alias) if matches!(alias.expr.as_ref(), Expr::Literal(value) if value.is_null())),
+            "{optimized_plan:?}"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_simplify_table_full_filter_in_scan() -> Result<()> {
         let fields = vec![
@@ -802,6 +962,64 @@ mod tests {
         assert_optimized_plan_eq(&plan, expected)
     }
 
+    #[test]
+    fn like_prefix_to_range() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Utf8, false),
+            Field::new("b", DataType::Utf8, false),
+        ]);
+        let table_scan = table_scan(Some("test"), &schema, None)
+            .expect("creating scan")
+            .build()
+            .expect("building plan");
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(col("a").like(lit("abc%")).and(col("b").eq(lit("x"))))?
+            .build()?;
+        let expected = "Filter: test.a LIKE Utf8(\"abc%\") AND test.b = Utf8(\"x\") AND test.a >= Utf8(\"abc\") AND test.a < Utf8(\"abd\")\
+        \n  TableScan: test";
+        assert_optimized_plan_eq(&plan, expected)?;
+
+        // the range is only added once
+        let optimized = SimplifyExpressions::new()
+            .try_optimize(&plan, &OptimizerContext::new())?
+            .unwrap();
+        assert_optimized_plan_eq(&optimized, expected)
+    }
+
+    #[test]
+    fn like_prefix_to_range_not_applicable() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Utf8, false),
+            Field::new("b", DataType::Utf8, false),
+        ]);
+        let table_scan = table_scan(Some("test"), &schema, None)
+            .expect("creating scan")
+            .build()
+            .expect("building plan");
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(
+                col("a")
+                    .like(lit("a_c%"))
+                    .and(col("a").not_like(lit("abc%")))
+                    .and(col("a").ilike(lit("abc%")))
+                    .and(col("b").like(lit("%abc"))),
+            )?
+            .build()?;
+        let expected = "Filter: test.a LIKE Utf8(\"a_c%\") AND test.a NOT LIKE Utf8(\"abc%\") AND test.a ILIKE Utf8(\"abc%\") AND test.b LIKE Utf8(\"%abc\")\
+        \n  TableScan: test";
+        assert_optimized_plan_eq(&plan, expected)
+    }
+
+    #[test]
+    fn like_prefix_upper_bound() {
+        assert_eq!(prefix_upper_bound("abc"), Some("abd".to_string()));
+        assert_eq!(prefix_upper_bound("a\u{10FFFF}"), Some("b".to_string()));
+        assert_eq!(prefix_upper_bound("\u{D7FF}"), Some("\u{E000}".to_string()));
+        assert_eq!(prefix_upper_bound("\u{10FFFF}"), None);
+    }
+
     #[test]
     fn simplify_not_distinct_from() -> Result<()> {
         let table_scan = test_table_scan();
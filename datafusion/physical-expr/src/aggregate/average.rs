@@ -153,7 +153,10 @@ impl AggregateExpr for Avg {
     fn groups_accumulator_supported(&self) -> bool {
         use DataType::*;
 
-        matches!(&self.result_data_type, Float64 | Decimal128(_, _))
+        matches!(
+            &self.result_data_type,
+            Float64 | Decimal128(_, _) | Decimal256(_, _)
+        )
     }
 
     fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
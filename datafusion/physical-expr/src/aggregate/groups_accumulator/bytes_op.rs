@@ -0,0 +1,244 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::AsArray;
+use arrow_array::{
+    ArrayRef, BooleanArray, GenericBinaryArray, GenericStringArray, OffsetSizeTrait,
+};
+use arrow_schema::DataType;
+use datafusion_common::{internal_err, DataFusionError, Result};
+
+use crate::GroupsAccumulator;
+
+use super::EmitTo;
+
+/// An accumulator that keeps one of the values of each group of a string or
+/// binary array, chosen by comparing them (such as `Min` or `Max`)
+///
+/// F: The function to compare two elements. The first argument is the
+/// existing value and the second the new value, which replaces the
+/// existing one if the function returns `true`.
+#[derive(Debug)]
+pub struct BytesGroupsAccumulator<F>
+where
+    F: Fn(&[u8], &[u8]) -> bool + Send + Sync,
+{
+    /// The type of the input and output, one of `Utf8`, `LargeUtf8`,
+    /// `Binary` or `LargeBinary`
+    data_type: DataType,
+
+    /// The value of each group, `None` until the group sees a non null value
+    values: Vec<Option<Vec<u8>>>,
+
+    /// The total capacity of the buffers of `values`, in bytes
+    values_size: usize,
+
+    /// Function that decides if the new value replaces the existing one
+    replace_fn: F,
+}
+
+impl<F> BytesGroupsAccumulator<F>
+where
+    F: Fn(&[u8], &[u8]) -> bool + Send + Sync,
+{
+    pub fn new(data_type: &DataType, replace_fn: F) -> Self {
+        Self {
+            data_type: data_type.clone(),
+            values: vec![],
+            values_size: 0,
+            replace_fn,
+        }
+    }
+
+    fn accumulate<'a>(
+        &mut self,
+        values: impl Iterator<Item = Option<&'a [u8]>>,
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+    ) {
+        for (row, (value, &group_index)) in values.zip(group_indices).enumerate() {
+            let Some(value) = value else {
+                continue;
+            };
+            if let Some(filter) = opt_filter {
+                if !filter.is_valid(row) || !filter.value(row) {
+                    continue;
+                }
+            }
+            match &mut self.values[group_index] {
+                Some(current) => {
+                    if (self.replace_fn)(current, value) {
+                        self.values_size -= current.capacity();
+                        current.clear();
+                        current.extend_from_slice(value);
+                        self.values_size += current.capacity();
+                    }
+                }
+                current @ None => {
+                    self.values_size += value.len();
+                    *current = Some(value.to_vec());
+                }
+            }
+        }
+    }
+}
+
+impl<F> GroupsAccumulator for BytesGroupsAccumulator<F>
+where
+    F: Fn(&[u8], &[u8]) -> bool + Send + Sync,
+{
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "single argument to update_batch");
+        let values = &values[0];
+        self.values.resize(total_num_groups, None);
+
+        match values.data_type() {
+            DataType::Utf8 => self.accumulate(
+                values
+                    .as_string::<i32>()
+                    .iter()
+                    .map(|v| v.map(str::as_bytes)),
+                group_indices,
+                opt_filter,
+            ),
+            DataType::LargeUtf8 => self.accumulate(
+                values
+                    .as_string::<i64>()
+                    .iter()
+                    .map(|v| v.map(str::as_bytes)),
+                group_indices,
+                opt_filter,
+            ),
+            DataType::Binary => self.accumulate(
+                values.as_binary::<i32>().iter(),
+                group_indices,
+                opt_filter,
+            ),
+            DataType::LargeBinary => self.accumulate(
+                values.as_binary::<i64>().iter(),
+                group_indices,
+                opt_filter,
+            ),
+            data_type => {
+                return internal_err!(
+                    "BytesGroupsAccumulator does not support {data_type}"
+                )
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let values = emit_to.take_needed(&mut self.values);
+        self.values_size -= values.iter().flatten().map(Vec::capacity).sum::<usize>();
+
+        match &self.data_type {
+            DataType::Utf8 => build_array::<i32>(&values, true),
+            DataType::LargeUtf8 => build_array::<i64>(&values, true),
+            DataType::Binary => build_array::<i32>(&values, false),
+            DataType::LargeBinary => build_array::<i64>(&values, false),
+            data_type => {
+                internal_err!("BytesGroupsAccumulator does not support {data_type}")
+            }
+        }
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        self.evaluate(emit_to).map(|arr| vec![arr])
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        // update / merge are the same
+        self.update_batch(values, group_indices, opt_filter, total_num_groups)
+    }
+
+    fn size(&self) -> usize {
+        self.values.capacity() * std::mem::size_of::<Option<Vec<u8>>>() + self.values_size
+    }
+}
+
+/// Builds a string array if `utf8` is set, and a binary array otherwise,
+/// from the values of the groups
+fn build_array<O: OffsetSizeTrait>(
+    values: &[Option<Vec<u8>>],
+    utf8: bool,
+) -> Result<ArrayRef> {
+    let array = GenericBinaryArray::<O>::from_iter(values.iter().map(Option::as_deref));
+    if utf8 {
+        // the values were all copied from valid strings
+        let array = GenericStringArray::<O>::try_from_binary(array)?;
+        Ok(Arc::new(array))
+    } else {
+        Ok(Arc::new(array))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{BinaryArray, StringArray};
+
+    #[test]
+    fn min_string_per_group() -> Result<()> {
+        let mut accumulator =
+            BytesGroupsAccumulator::new(&DataType::Utf8, |current, new| new < current);
+        let values: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("b"),
+            Some("a"),
+            None,
+            Some("d"),
+            Some("c"),
+        ]));
+        let filter = BooleanArray::from(vec![true, true, true, true, false]);
+        accumulator.update_batch(&[values], &[0, 0, 1, 2, 2], Some(&filter), 4)?;
+
+        let expected: ArrayRef =
+            Arc::new(StringArray::from(vec![Some("a"), None, Some("d"), None]));
+        assert_eq!(&accumulator.evaluate(EmitTo::First(4))?, &expected);
+        assert_eq!(accumulator.size(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn max_binary_merge() -> Result<()> {
+        let mut accumulator =
+            BytesGroupsAccumulator::new(&DataType::Binary, |current, new| new > current);
+        let values: ArrayRef =
+            Arc::new(BinaryArray::from(vec![b"ab".as_ref(), b"b".as_ref()]));
+        accumulator.update_batch(&[values], &[0, 1], None, 2)?;
+        let state = accumulator.state(EmitTo::First(1))?;
+        accumulator.merge_batch(&state, &[0], None, 1)?;
+
+        let expected: ArrayRef = Arc::new(BinaryArray::from(vec![b"b".as_ref()]));
+        assert_eq!(&accumulator.evaluate(EmitTo::All)?, &expected);
+        Ok(())
+    }
+}
@@ -22,6 +22,7 @@ mod adapter;
 pub use adapter::GroupsAccumulatorAdapter;
 
 pub(crate) mod bool_op;
+pub(crate) mod bytes_op;
 pub(crate) mod prim_op;
 
 use arrow_array::{ArrayRef, BooleanArray};
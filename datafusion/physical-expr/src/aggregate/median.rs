@@ -25,9 +25,11 @@ use arrow::datatypes::{DataType, Field};
 use arrow_array::cast::AsArray;
 use arrow_array::{downcast_integer, ArrowNativeTypeOp, ArrowNumericType};
 use arrow_buffer::ArrowNativeType;
-use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_common::{internal_err, DataFusionError, Result, ScalarValue};
 use datafusion_expr::Accumulator;
 use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt::Formatter;
 use std::sync::Arc;
 
@@ -67,29 +69,7 @@ impl AggregateExpr for Median {
     }
 
     fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
-        use arrow_array::types::*;
-        macro_rules! helper {
-            ($t:ty, $dt:expr) => {
-                Ok(Box::new(MedianAccumulator::<$t> {
-                    data_type: $dt.clone(),
-                    all_values: vec![],
-                }))
-            };
-        }
-        let dt = &self.data_type;
-        downcast_integer! {
-            dt => (helper, dt),
-            DataType::Float16 => helper!(Float16Type, dt),
-            DataType::Float32 => helper!(Float32Type, dt),
-            DataType::Float64 => helper!(Float64Type, dt),
-            DataType::Decimal128(_, _) => helper!(Decimal128Type, dt),
-            DataType::Decimal256(_, _) => helper!(Decimal256Type, dt),
-            _ => Err(DataFusionError::NotImplemented(format!(
-                "MedianAccumulator not supported for {} with {}",
-                self.name(),
-                self.data_type
-            ))),
-        }
+        self.create_median_accumulator(false)
     }
 
     fn state_fields(&self) -> Result<Vec<Field>> {
@@ -111,6 +91,47 @@ impl AggregateExpr for Median {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn create_sliding_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        self.create_median_accumulator(true)
+    }
+}
+
+impl Median {
+    /// Creates a [`MedianAccumulator`], or a [`SlidingMedianAccumulator`]
+    /// supporting retraction if `sliding` is true
+    fn create_median_accumulator(&self, sliding: bool) -> Result<Box<dyn Accumulator>> {
+        use arrow_array::types::*;
+        macro_rules! helper {
+            ($t:ty, $dt:expr) => {
+                if sliding {
+                    Ok(Box::new(SlidingMedianAccumulator::<$t> {
+                        data_type: $dt.clone(),
+                        values: OrderStatistics::default(),
+                    }))
+                } else {
+                    Ok(Box::new(MedianAccumulator::<$t> {
+                        data_type: $dt.clone(),
+                        all_values: vec![],
+                    }))
+                }
+            };
+        }
+        let dt = &self.data_type;
+        downcast_integer! {
+            dt => (helper, dt),
+            DataType::Float16 => helper!(Float16Type, dt),
+            DataType::Float32 => helper!(Float32Type, dt),
+            DataType::Float64 => helper!(Float64Type, dt),
+            DataType::Decimal128(_, _) => helper!(Decimal128Type, dt),
+            DataType::Decimal256(_, _) => helper!(Decimal256Type, dt),
+            _ => Err(DataFusionError::NotImplemented(format!(
+                "MedianAccumulator not supported for {} with {}",
+                self.name(),
+                self.data_type
+            ))),
+        }
+    }
 }
 
 impl PartialEq<dyn Any> for Median {
@@ -182,8 +203,7 @@ impl<T: ArrowNumericType> Accumulator for MedianAccumulator<T> {
         } else if len % 2 == 0 {
             let (low, high, _) = d.select_nth_unstable_by(len / 2, cmp);
             let (_, low, _) = low.select_nth_unstable_by(low.len() - 1, cmp);
-            let median = low.add_wrapping(*high).div_wrapping(T::Native::usize_as(2));
-            Some(median)
+            Some(median_of_two(*low, *high))
         } else {
             let (_, median, _) = d.select_nth_unstable_by(len / 2, cmp);
             Some(*median)
@@ -197,6 +217,238 @@ impl<T: ArrowNumericType> Accumulator for MedianAccumulator<T> {
     }
 }
 
+/// The median of the two middle values `low` and `high` of an even number
+/// of values
+fn median_of_two<T: ArrowNativeTypeOp>(low: T, high: T) -> T {
+    low.add_wrapping(high).div_wrapping(T::usize_as(2))
+}
+
+/// A value ordered by [`ArrowNativeTypeOp::compare`], a total order also
+/// for floats
+#[derive(Debug, Clone, Copy)]
+struct TotalOrd<T>(T);
+
+impl<T: ArrowNativeTypeOp> PartialEq for TotalOrd<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.is_eq(other.0)
+    }
+}
+
+impl<T: ArrowNativeTypeOp> Eq for TotalOrd<T> {}
+
+impl<T: ArrowNativeTypeOp> PartialOrd for TotalOrd<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: ArrowNativeTypeOp> Ord for TotalOrd<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.compare(other.0)
+    }
+}
+
+/// A sorted multiset of values
+#[derive(Debug)]
+struct MultiSet<T> {
+    counts: BTreeMap<TotalOrd<T>, usize>,
+    len: usize,
+}
+
+impl<T> Default for MultiSet<T> {
+    fn default() -> Self {
+        Self {
+            counts: BTreeMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T: ArrowNativeTypeOp> MultiSet<T> {
+    fn insert(&mut self, value: T) {
+        *self.counts.entry(TotalOrd(value)).or_default() += 1;
+        self.len += 1;
+    }
+
+    /// Removes one occurrence of `value`, returning false if there is none
+    fn remove(&mut self, value: T) -> bool {
+        let key = TotalOrd(value);
+        match self.counts.get_mut(&key) {
+            Some(count) => {
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(&key);
+                }
+                self.len -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn first(&self) -> Option<T> {
+        self.counts.keys().next().map(|key| key.0)
+    }
+
+    fn last(&self) -> Option<T> {
+        self.counts.keys().next_back().map(|key| key.0)
+    }
+
+    fn pop_first(&mut self) -> Option<T> {
+        let value = self.first()?;
+        self.remove(value);
+        Some(value)
+    }
+
+    fn pop_last(&mut self) -> Option<T> {
+        let value = self.last()?;
+        self.remove(value);
+        Some(value)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.counts
+            .iter()
+            .flat_map(|(key, count)| std::iter::repeat(key.0).take(*count))
+    }
+}
+
+/// Values split into the lower and the upper half, that finds their median in
+/// `O(1)` and inserts or removes a value in `O(log(n))`
+#[derive(Debug)]
+struct OrderStatistics<T> {
+    /// The lower half, with one more value than `high` for an odd number of
+    /// values
+    low: MultiSet<T>,
+    /// The upper half
+    high: MultiSet<T>,
+}
+
+impl<T> Default for OrderStatistics<T> {
+    fn default() -> Self {
+        Self {
+            low: MultiSet::default(),
+            high: MultiSet::default(),
+        }
+    }
+}
+
+impl<T: ArrowNativeTypeOp> OrderStatistics<T> {
+    fn len(&self) -> usize {
+        self.low.len + self.high.len
+    }
+
+    fn insert(&mut self, value: T) {
+        match self.low.last() {
+            Some(low) if value.compare(low).is_gt() => self.high.insert(value),
+            _ => self.low.insert(value),
+        }
+        self.rebalance();
+    }
+
+    /// Removes one occurrence of `value`, returning false if there is none
+    fn remove(&mut self, value: T) -> bool {
+        let removed = match self.low.last() {
+            Some(low) if value.compare(low).is_gt() => self.high.remove(value),
+            _ => self.low.remove(value),
+        };
+        self.rebalance();
+        removed
+    }
+
+    fn rebalance(&mut self) {
+        while self.low.len > self.high.len + 1 {
+            let value = self.low.pop_last().unwrap();
+            self.high.insert(value);
+        }
+        while self.high.len > self.low.len {
+            let value = self.high.pop_first().unwrap();
+            self.low.insert(value);
+        }
+    }
+
+    fn median(&self) -> Option<T> {
+        let low = self.low.last()?;
+        if self.len() % 2 == 0 {
+            let high = self.high.first()?;
+            Some(median_of_two(low, high))
+        } else {
+            Some(low)
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.low.iter().chain(self.high.iter())
+    }
+}
+
+/// The median accumulator of sliding window frames, which keeps the values
+/// of the frame in [`OrderStatistics`] so that the values leaving the frame
+/// can be retracted
+struct SlidingMedianAccumulator<T: ArrowNumericType> {
+    data_type: DataType,
+    values: OrderStatistics<T::Native>,
+}
+
+impl<T: ArrowNumericType> std::fmt::Debug for SlidingMedianAccumulator<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SlidingMedianAccumulator({})", self.data_type)
+    }
+}
+
+impl<T: ArrowNumericType> Accumulator for SlidingMedianAccumulator<T> {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        let all_values = self
+            .values
+            .iter()
+            .map(|x| ScalarValue::new_primitive::<T>(Some(x), &self.data_type))
+            .collect::<Result<Vec<_>>>()?;
+
+        let arr = ScalarValue::new_list(&all_values, &self.data_type);
+        Ok(vec![ScalarValue::List(arr)])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = values[0].as_primitive::<T>();
+        for value in values.iter().flatten() {
+            self.values.insert(value);
+        }
+        Ok(())
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = values[0].as_primitive::<T>();
+        for value in values.iter().flatten() {
+            if !self.values.remove(value) {
+                return internal_err!("Retracted a value missing from the {self:?}");
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let array = states[0].as_list::<i32>();
+        for v in array.iter().flatten() {
+            self.update_batch(&[v])?
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        ScalarValue::new_primitive::<T>(self.values.median(), &self.data_type)
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+
+    fn size(&self) -> usize {
+        let entries = self.values.low.counts.len() + self.values.high.counts.len();
+        std::mem::size_of_val(self)
+            + entries * (std::mem::size_of::<T::Native>() + std::mem::size_of::<usize>())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,4 +583,42 @@ mod tests {
         ]));
         generic_test_op!(a, DataType::Float64, Median, ScalarValue::from(3.5_f64))
     }
+
+    #[test]
+    fn median_f64_sliding() -> Result<()> {
+        let median = Median::new(col("a", &schema_f64())?, "median", DataType::Float64);
+        let mut accumulator = median.create_sliding_accumulator()?;
+
+        let values: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(5_f64),
+            None,
+            Some(1_f64),
+            Some(4_f64),
+            Some(2_f64),
+            Some(3_f64),
+        ]));
+        accumulator.update_batch(&[values.clone()])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(3_f64));
+
+        // 1, 2, 3, 4 are left
+        accumulator.retract_batch(&[values.slice(0, 2)])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(2.5_f64));
+
+        // 3 is left
+        accumulator.retract_batch(&[values.slice(2, 3)])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(3_f64));
+
+        accumulator.retract_batch(&[values.slice(5, 1)])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::Float64(None));
+
+        let err = accumulator
+            .retract_batch(&[values.slice(5, 1)])
+            .unwrap_err();
+        assert!(err.to_string().contains("Retracted a value missing"));
+        Ok(())
+    }
+
+    fn schema_f64() -> Schema {
+        Schema::new(vec![Field::new("a", DataType::Float64, true)])
+    }
 }
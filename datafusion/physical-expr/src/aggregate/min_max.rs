@@ -21,6 +21,7 @@ use std::any::Any;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
+use crate::aggregate::groups_accumulator::bytes_op::BytesGroupsAccumulator;
 use crate::aggregate::groups_accumulator::prim_op::PrimitiveGroupsAccumulator;
 use crate::{AggregateExpr, GroupsAccumulator, PhysicalExpr};
 use arrow::compute;
@@ -192,6 +193,10 @@ impl AggregateExpr for Max {
                 | Time32(_)
                 | Time64(_)
                 | Timestamp(_, _)
+                | Utf8
+                | LargeUtf8
+                | Binary
+                | LargeBinary
         )
     }
 
@@ -246,9 +251,11 @@ impl AggregateExpr for Max {
             Decimal256(_, _) => {
                 instantiate_max_accumulator!(self, i256, Decimal256Type)
             }
-
-            // It would be nice to have a fast implementation for Strings as well
-            // https://github.com/apache/arrow-datafusion/issues/6906
+            Utf8 | LargeUtf8 | Binary | LargeBinary => Ok(Box::new(
+                BytesGroupsAccumulator::new(&self.data_type, |current, new| {
+                    new > current
+                }),
+            )),
 
             // This is only reached if groups_accumulator_supported is out of sync
             _ => internal_err!(
@@ -916,6 +923,10 @@ impl AggregateExpr for Min {
                 | Time32(_)
                 | Time64(_)
                 | Timestamp(_, _)
+                | Utf8
+                | LargeUtf8
+                | Binary
+                | LargeBinary
         )
     }
 
@@ -969,6 +980,12 @@ impl AggregateExpr for Min {
             Decimal256(_, _) => {
                 instantiate_min_accumulator!(self, i256, Decimal256Type)
             }
+            Utf8 | LargeUtf8 | Binary | LargeBinary => Ok(Box::new(
+                BytesGroupsAccumulator::new(&self.data_type, |current, new| {
+                    new < current
+                }),
+            )),
+
             // This is only reached if groups_accumulator_supported is out of sync
             _ => internal_err!(
                 "GroupsAccumulator not supported for min({})",
@@ -21,10 +21,12 @@ use crate::datetime_expressions;
 use crate::expressions::cast_column;
 use arrow::compute::cast;
 use arrow::{
-    array::{Array, ArrayRef, Float64Array, OffsetSizeTrait, PrimitiveArray},
+    array::{
+        Array, ArrayRef, Date32Array, Float64Array, OffsetSizeTrait, PrimitiveArray,
+    },
     compute::kernels::cast_utils::string_to_timestamp_nanos,
     datatypes::{
-        ArrowNumericType, ArrowPrimitiveType, ArrowTemporalType, DataType,
+        ArrowNumericType, ArrowPrimitiveType, ArrowTemporalType, DataType, Date32Type,
         IntervalDayTimeType, IntervalMonthDayNanoType, TimestampMicrosecondType,
         TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
     },
@@ -44,6 +46,7 @@ use datafusion_common::cast::{
     as_timestamp_microsecond_array, as_timestamp_millisecond_array,
     as_timestamp_nanosecond_array, as_timestamp_second_array,
 };
+use datafusion_common::utils::shift_months;
 use datafusion_common::{
     exec_err, internal_err, not_impl_err, DataFusionError, Result, ScalarType,
     ScalarValue,
@@ -194,16 +197,17 @@ pub fn make_now(
 }
 
 /// Create an implementation of `current_date()` that always returns the
-/// specified current date.
+/// specified current date in `time_zone`, or in UTC if `None`.
 ///
 /// The semantics of `current_date()` require it to return the same value
 /// wherever it appears within a single statement. This value is
 /// chosen during planning time.
 pub fn make_current_date(
     now_ts: DateTime<Utc>,
+    time_zone: Option<Tz>,
 ) -> impl Fn(&[ColumnarValue]) -> Result<ColumnarValue> {
     let days = Some(
-        now_ts.num_days_from_ce()
+        local_datetime(now_ts, time_zone).num_days_from_ce()
             - NaiveDate::from_ymd_opt(1970, 1, 1)
                 .unwrap()
                 .num_days_from_ce(),
@@ -212,18 +216,36 @@ pub fn make_current_date(
 }
 
 /// Create an implementation of `current_time()` that always returns the
-/// specified current time.
+/// specified current time in `time_zone`, or in UTC if `None`.
 ///
 /// The semantics of `current_time()` require it to return the same value
 /// wherever it appears within a single statement. This value is
 /// chosen during planning time.
 pub fn make_current_time(
     now_ts: DateTime<Utc>,
+    time_zone: Option<Tz>,
 ) -> impl Fn(&[ColumnarValue]) -> Result<ColumnarValue> {
-    let nano = now_ts.timestamp_nanos_opt().map(|ts| ts % 86400000000000);
+    let time = local_datetime(now_ts, time_zone).time();
+    let nano = Some(
+        time.num_seconds_from_midnight() as i64 * 1_000_000_000
+            + time.nanosecond() as i64,
+    );
     move |_arg| Ok(ColumnarValue::Scalar(ScalarValue::Time64Nanosecond(nano)))
 }
 
+/// Returns the wall clock time of `now_ts` in `time_zone`, or in UTC if `None`
+fn local_datetime(now_ts: DateTime<Utc>, time_zone: Option<Tz>) -> NaiveDateTime {
+    match time_zone {
+        Some(tz) => now_ts.with_timezone(&tz).naive_local(),
+        None => now_ts.naive_utc(),
+    }
+}
+
+/// Returns `true` if `tz` is the UTC time zone, as returned by `now()`
+pub fn is_utc_time_zone(tz: &str) -> bool {
+    matches!(tz, "+00:00" | "+0000" | "+00" | "Z") || tz.eq_ignore_ascii_case("UTC")
+}
+
 fn quarter_month<T>(date: &T) -> u32
 where
     T: chrono::Datelike,
@@ -379,7 +401,7 @@ fn general_date_trunc(
     Ok(result)
 }
 
-fn parse_tz(tz: &Option<Arc<str>>) -> Result<Option<Tz>> {
+pub(crate) fn parse_tz(tz: &Option<Arc<str>>) -> Result<Option<Tz>> {
     tz.as_ref()
         .map(|tz| {
             Tz::from_str(tz).map_err(|op| {
@@ -389,8 +411,39 @@ fn parse_tz(tz: &Option<Arc<str>>) -> Result<Option<Tz>> {
         .transpose()
 }
 
+/// Returns the time zone in which to truncate timestamps in `tz_opt`: the
+/// session `time_zone` for timestamps in UTC, and `tz_opt` otherwise
+fn truncation_time_zone(
+    tz_opt: &Option<Arc<str>>,
+    time_zone: Option<&Arc<str>>,
+) -> Result<Option<Tz>> {
+    match (tz_opt, time_zone) {
+        (Some(tz), Some(time_zone)) if is_utc_time_zone(tz) => {
+            parse_tz(&Some(time_zone.clone()))
+        }
+        _ => parse_tz(tz_opt),
+    }
+}
+
 /// date_trunc SQL function
 pub fn date_trunc(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    date_trunc_in_time_zone(args, None)
+}
+
+/// Create an implementation of `date_trunc()` that truncates the timestamps
+/// in UTC, such as the result of `now()`, in the session `time_zone`.
+/// Timestamps in other time zones are truncated in their own time zone, and
+/// timestamps without time zone are left as is.
+pub fn make_date_trunc(
+    time_zone: Option<Arc<str>>,
+) -> impl Fn(&[ColumnarValue]) -> Result<ColumnarValue> {
+    move |args| date_trunc_in_time_zone(args, time_zone.as_ref())
+}
+
+fn date_trunc_in_time_zone(
+    args: &[ColumnarValue],
+    time_zone: Option<&Arc<str>>,
+) -> Result<ColumnarValue> {
     let (granularity, array) = (&args[0], &args[1]);
 
     let granularity =
@@ -404,8 +457,9 @@ pub fn date_trunc(args: &[ColumnarValue]) -> Result<ColumnarValue> {
         array: &dyn Array,
         granularity: String,
         tz_opt: &Option<Arc<str>>,
+        time_zone: Option<&Arc<str>>,
     ) -> Result<ColumnarValue> {
-        let parsed_tz = parse_tz(tz_opt)?;
+        let parsed_tz = truncation_time_zone(tz_opt, time_zone)?;
         let array = as_primitive_array::<T>(array)?;
         let array = array
             .iter()
@@ -419,8 +473,9 @@ pub fn date_trunc(args: &[ColumnarValue]) -> Result<ColumnarValue> {
         v: &Option<i64>,
         granularity: String,
         tz_opt: &Option<Arc<str>>,
+        time_zone: Option<&Arc<str>>,
     ) -> Result<ColumnarValue> {
-        let parsed_tz = parse_tz(tz_opt)?;
+        let parsed_tz = truncation_time_zone(tz_opt, time_zone)?;
         let value = general_date_trunc(T::UNIT, v, parsed_tz, granularity.as_str())?;
         let value = ScalarValue::new_timestamp::<T>(value, tz_opt.clone());
         Ok(ColumnarValue::Scalar(value))
@@ -428,33 +483,58 @@ pub fn date_trunc(args: &[ColumnarValue]) -> Result<ColumnarValue> {
 
     Ok(match array {
         ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(v, tz_opt)) => {
-            process_scalar::<TimestampNanosecondType>(v, granularity, tz_opt)?
+            process_scalar::<TimestampNanosecondType>(v, granularity, tz_opt, time_zone)?
         }
         ColumnarValue::Scalar(ScalarValue::TimestampMicrosecond(v, tz_opt)) => {
-            process_scalar::<TimestampMicrosecondType>(v, granularity, tz_opt)?
+            process_scalar::<TimestampMicrosecondType>(v, granularity, tz_opt, time_zone)?
         }
         ColumnarValue::Scalar(ScalarValue::TimestampMillisecond(v, tz_opt)) => {
-            process_scalar::<TimestampMillisecondType>(v, granularity, tz_opt)?
+            process_scalar::<TimestampMillisecondType>(v, granularity, tz_opt, time_zone)?
         }
         ColumnarValue::Scalar(ScalarValue::TimestampSecond(v, tz_opt)) => {
-            process_scalar::<TimestampSecondType>(v, granularity, tz_opt)?
+            process_scalar::<TimestampSecondType>(v, granularity, tz_opt, time_zone)?
         }
         ColumnarValue::Array(array) => {
             let array_type = array.data_type();
             match array_type {
                 DataType::Timestamp(TimeUnit::Second, tz_opt) => {
-                    process_array::<TimestampSecondType>(array, granularity, tz_opt)?
+                    process_array::<TimestampSecondType>(
+                        array,
+                        granularity,
+                        tz_opt,
+                        time_zone,
+                    )?
                 }
                 DataType::Timestamp(TimeUnit::Millisecond, tz_opt) => {
-                    process_array::<TimestampMillisecondType>(array, granularity, tz_opt)?
+                    process_array::<TimestampMillisecondType>(
+                        array,
+                        granularity,
+                        tz_opt,
+                        time_zone,
+                    )?
                 }
                 DataType::Timestamp(TimeUnit::Microsecond, tz_opt) => {
-                    process_array::<TimestampMicrosecondType>(array, granularity, tz_opt)?
+                    process_array::<TimestampMicrosecondType>(
+                        array,
+                        granularity,
+                        tz_opt,
+                        time_zone,
+                    )?
                 }
                 DataType::Timestamp(TimeUnit::Nanosecond, tz_opt) => {
-                    process_array::<TimestampNanosecondType>(array, granularity, tz_opt)?
+                    process_array::<TimestampNanosecondType>(
+                        array,
+                        granularity,
+                        tz_opt,
+                        time_zone,
+                    )?
                 }
-                _ => process_array::<TimestampNanosecondType>(array, granularity, &None)?,
+                _ => process_array::<TimestampNanosecondType>(
+                    array,
+                    granularity,
+                    &None,
+                    time_zone,
+                )?,
             }
         }
         _ => {
@@ -749,6 +829,74 @@ fn date_bin_impl(
     })
 }
 
+/// Returns the last day of the month containing `days`, both expressed
+/// as days since the UNIX epoch
+fn last_day_of_month(days: i32) -> i32 {
+    let date = Date32Type::to_naive_date(days);
+    // day 1 exists in every month
+    let first_of_month = date.with_day(1).unwrap();
+    let last_of_month = shift_months(first_of_month, 1) - Duration::days(1);
+    Date32Type::from_naive_date(last_of_month)
+}
+
+/// Returns the first date strictly after `days` that falls on `weekday`,
+/// both dates expressed as days since the UNIX epoch
+fn next_weekday(days: i32, weekday: Weekday) -> i32 {
+    let current = Date32Type::to_naive_date(days).weekday();
+    let delta = (7 + weekday.num_days_from_monday() - current.num_days_from_monday()) % 7;
+    days + if delta == 0 { 7 } else { delta as i32 }
+}
+
+/// Applies `op` to each element of a `Date32` scalar or array
+fn map_date32<F>(arg: &ColumnarValue, op: F, name: &str) -> Result<ColumnarValue>
+where
+    F: Fn(i32) -> i32,
+{
+    match arg {
+        ColumnarValue::Scalar(ScalarValue::Date32(v)) => {
+            Ok(ColumnarValue::Scalar(ScalarValue::Date32(v.map(op))))
+        }
+        ColumnarValue::Array(array) => {
+            let array = as_date32_array(array)?;
+            let array: Date32Array = array.unary(op);
+            Ok(ColumnarValue::Array(Arc::new(array)))
+        }
+        other => exec_err!(
+            "{name} expects argument to be a DATE but got {}",
+            other.data_type()
+        ),
+    }
+}
+
+/// LAST_DAY SQL function
+pub fn last_day(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    if args.len() != 1 {
+        return exec_err!("LAST_DAY expected one argument");
+    }
+    map_date32(&args[0], last_day_of_month, "LAST_DAY")
+}
+
+/// NEXT_DAY SQL function
+pub fn next_day(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    if args.len() != 2 {
+        return exec_err!("NEXT_DAY expected two arguments");
+    }
+    let weekday = match &args[1] {
+        ColumnarValue::Scalar(ScalarValue::Utf8(Some(v))) => Weekday::from_str(v)
+            .map_err(|_| {
+                DataFusionError::Execution(format!(
+                    "NEXT_DAY expects a valid day of the week but got '{v}'"
+                ))
+            })?,
+        _ => {
+            return exec_err!(
+                "Second argument of `NEXT_DAY` must be non-null scalar Utf8"
+            )
+        }
+    };
+    map_date32(&args[0], |days| next_weekday(days, weekday), "NEXT_DAY")
+}
+
 macro_rules! extract_date_part {
     ($ARRAY: expr, $FN:expr) => {
         match $ARRAY.data_type() {
@@ -1203,6 +1351,62 @@ mod tests {
         });
     }
 
+    #[test]
+    fn current_date_and_time_in_time_zone() -> Result<()> {
+        let now = Utc.with_ymd_and_hms(2000, 1, 1, 20, 0, 0).unwrap();
+        let tz = Some(Tz::from_str("+08:00").unwrap());
+
+        let date = make_current_date(now, None)(&[])?;
+        assert_eq!(as_scalar(date), ScalarValue::Date32(Some(10957)));
+        let date = make_current_date(now, tz)(&[])?;
+        assert_eq!(as_scalar(date), ScalarValue::Date32(Some(10958)));
+
+        let time = make_current_time(now, tz)(&[])?;
+        assert_eq!(
+            as_scalar(time),
+            ScalarValue::Time64Nanosecond(Some(4 * 3600 * 1_000_000_000))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn date_trunc_in_session_time_zone() -> Result<()> {
+        // 2000-01-01T20:00:00Z is 2000-01-02T04:00:00+08:00
+        let ts = 946_756_800_000_000_000;
+        let date_trunc = make_date_trunc(Some("+08:00".into()));
+        let truncate = |tz: &str| {
+            date_trunc(&[
+                ColumnarValue::Scalar(ScalarValue::from("day")),
+                ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(
+                    Some(ts),
+                    Some(tz.into()),
+                )),
+            ])
+        };
+
+        // timestamps in UTC are truncated in the session time zone
+        let expected = ScalarValue::TimestampNanosecond(
+            Some(946_742_400_000_000_000),
+            Some("+00:00".into()),
+        );
+        assert_eq!(as_scalar(truncate("+00:00")?), expected);
+
+        // other timestamps are truncated in their own time zone
+        let expected = ScalarValue::TimestampNanosecond(
+            Some(946_702_800_000_000_000),
+            Some("-05:00".into()),
+        );
+        assert_eq!(as_scalar(truncate("-05:00")?), expected);
+        Ok(())
+    }
+
+    fn as_scalar(value: ColumnarValue) -> ScalarValue {
+        match value {
+            ColumnarValue::Scalar(value) => value,
+            ColumnarValue::Array(_) => panic!("Expected a scalar value"),
+        }
+    }
+
     #[test]
     fn test_date_trunc_timezones() {
         let cases = vec![
@@ -1653,6 +1857,66 @@ mod tests {
             });
     }
 
+    fn date32(s: &str) -> i32 {
+        Date32Type::from_naive_date(NaiveDate::from_str(s).unwrap())
+    }
+
+    #[test]
+    fn test_last_day() -> Result<()> {
+        let cases = vec![
+            ("2023-01-15", "2023-01-31"),
+            ("2023-02-01", "2023-02-28"),
+            ("2024-02-10", "2024-02-29"),
+            ("2023-04-30", "2023-04-30"),
+            ("2023-12-31", "2023-12-31"),
+        ];
+        let input: Date32Array = cases.iter().map(|(d, _)| Some(date32(d))).collect();
+        let expected: Date32Array = cases.iter().map(|(_, d)| Some(date32(d))).collect();
+
+        let ColumnarValue::Array(result) =
+            last_day(&[ColumnarValue::Array(Arc::new(input))])?
+        else {
+            panic!("unexpected column type");
+        };
+        assert_eq!(as_date32_array(&result)?, &expected);
+
+        let result = last_day(&[ColumnarValue::Scalar(ScalarValue::Date32(None))])?;
+        assert!(matches!(
+            result,
+            ColumnarValue::Scalar(ScalarValue::Date32(None))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_day() -> Result<()> {
+        // 2023-06-14 is a Wednesday
+        let cases = vec![
+            ("friday", "2023-06-16"),
+            ("FRI", "2023-06-16"),
+            ("Wednesday", "2023-06-21"),
+            ("mon", "2023-06-19"),
+        ];
+        for (weekday, expected) in cases {
+            let result = next_day(&[
+                ColumnarValue::Scalar(ScalarValue::Date32(Some(date32("2023-06-14")))),
+                ColumnarValue::Scalar(ScalarValue::Utf8(Some(weekday.to_string()))),
+            ])?;
+            let ColumnarValue::Scalar(ScalarValue::Date32(Some(result))) = result else {
+                panic!("unexpected column type");
+            };
+            assert_eq!(result, date32(expected), "next_day(.., '{weekday}')");
+        }
+
+        let err = next_day(&[
+            ColumnarValue::Scalar(ScalarValue::Date32(Some(date32("2023-06-14")))),
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some("someday".to_string()))),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("valid day of the week"));
+        Ok(())
+    }
+
     #[test]
     fn to_timestamp_invalid_input_type() -> Result<()> {
         // pass the wrong type of input array to to_timestamp and test
@@ -35,6 +35,9 @@ use std::sync::Arc;
 #[derive(Clone, Debug)]
 pub struct ExecutionProps {
     pub query_execution_start_time: DateTime<Utc>,
+    /// The time zone of the session, used by the functions depending on the
+    /// local time, such as `current_date()`, or UTC if `None`
+    pub time_zone: Option<Arc<str>>,
     /// Alias generator used by subquery optimizer rules
     pub alias_generator: Arc<AliasGenerator>,
     /// Providers for scalar variables
@@ -54,6 +57,7 @@ impl ExecutionProps {
             // Set this to a fixed sentinel to make it obvious if this is
             // not being updated / propagated correctly
             query_execution_start_time: Utc.timestamp_nanos(0),
+            time_zone: None,
             alias_generator: Arc::new(AliasGenerator::new()),
             var_providers: None,
         }
@@ -68,6 +72,12 @@ impl ExecutionProps {
         self
     }
 
+    /// Set the time zone of the session
+    pub fn with_time_zone(mut self, time_zone: Option<Arc<str>>) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
     /// Marks the execution of query started timestamp.
     /// This also instantiates a new alias generator.
     pub fn start_execution(&mut self) -> &Self {
@@ -109,6 +119,6 @@ mod test {
     #[test]
     fn debug() {
         let props = ExecutionProps::new();
-        assert_eq!("ExecutionProps { query_execution_start_time: 1970-01-01T00:00:00Z, alias_generator: AliasGenerator { next_id: 1 }, var_providers: None }", format!("{props:?}"));
+        assert_eq!("ExecutionProps { query_execution_start_time: 1970-01-01T00:00:00Z, time_zone: None, alias_generator: AliasGenerator { next_id: 1 }, var_providers: None }", format!("{props:?}"));
     }
 }
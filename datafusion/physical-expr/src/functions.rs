@@ -34,15 +34,15 @@ use crate::execution_props::ExecutionProps;
 use crate::sort_properties::SortProperties;
 use crate::{
     array_expressions, conditional_expressions, datetime_expressions,
-    expressions::nullif_func, math_expressions, string_expressions, struct_expressions,
-    PhysicalExpr, ScalarFunctionExpr,
+    expressions::nullif_func, hash_expressions, math_expressions, string_expressions,
+    struct_expressions, PhysicalExpr, ScalarFunctionExpr,
 };
 use arrow::{
     array::ArrayRef,
     compute::kernels::length::{bit_length, length},
     datatypes::{DataType, Int32Type, Int64Type, Schema},
 };
-use datafusion_common::{internal_err, DataFusionError, Result, ScalarValue};
+use datafusion_common::{exec_err, internal_err, DataFusionError, Result, ScalarValue};
 pub use datafusion_expr::FuncMonotonicity;
 use datafusion_expr::{
     type_coercion::functions::data_types, BuiltinScalarFunction, ColumnarValue,
@@ -74,13 +74,16 @@ pub fn create_physical_expr(
 
     let monotonicity = fun.monotonicity();
 
-    Ok(Arc::new(ScalarFunctionExpr::new(
-        &format!("{fun}"),
-        fun_expr,
-        input_phy_exprs.to_vec(),
-        data_type,
-        monotonicity,
-    )))
+    Ok(Arc::new(
+        ScalarFunctionExpr::new(
+            &format!("{fun}"),
+            fun_expr,
+            input_phy_exprs.to_vec(),
+            data_type,
+            monotonicity,
+        )
+        .with_volatility(fun.volatility()),
+    ))
 }
 
 #[cfg(feature = "encoding_expressions")]
@@ -491,8 +494,23 @@ pub fn create_physical_fun(
             Arc::new(|args| make_scalar_function(string_expressions::concat_ws)(args))
         }
         BuiltinScalarFunction::DatePart => Arc::new(datetime_expressions::date_part),
-        BuiltinScalarFunction::DateTrunc => Arc::new(datetime_expressions::date_trunc),
+        BuiltinScalarFunction::DateTrunc => Arc::new(
+            datetime_expressions::make_date_trunc(execution_props.time_zone.clone()),
+        ),
         BuiltinScalarFunction::DateBin => Arc::new(datetime_expressions::date_bin),
+        // these are replaced by a gap filling plan when planning aggregations
+        BuiltinScalarFunction::DateBinGapfill
+        | BuiltinScalarFunction::Locf
+        | BuiltinScalarFunction::Interpolate => {
+            let name = fun.to_string();
+            Arc::new(move |_| {
+                exec_err!(
+                    "{name} can only be used in aggregations grouped by date_bin_gapfill"
+                )
+            })
+        }
+        BuiltinScalarFunction::LastDay => Arc::new(datetime_expressions::last_day),
+        BuiltinScalarFunction::NextDay => Arc::new(datetime_expressions::next_day),
         BuiltinScalarFunction::Now => {
             // bind value for now at plan time
             Arc::new(datetime_expressions::make_now(
@@ -503,12 +521,14 @@ pub fn create_physical_fun(
             // bind value for current_date at plan time
             Arc::new(datetime_expressions::make_current_date(
                 execution_props.query_execution_start_time,
+                datetime_expressions::parse_tz(&execution_props.time_zone)?,
             ))
         }
         BuiltinScalarFunction::CurrentTime => {
             // bind value for current_time at plan time
             Arc::new(datetime_expressions::make_current_time(
                 execution_props.query_execution_start_time,
+                datetime_expressions::parse_tz(&execution_props.time_zone)?,
             ))
         }
         BuiltinScalarFunction::ToTimestamp => {
@@ -725,6 +745,10 @@ pub fn create_physical_fun(
         BuiltinScalarFunction::SHA512 => {
             Arc::new(invoke_if_crypto_expressions_feature_flag!(sha512, "sha512"))
         }
+        BuiltinScalarFunction::SparkMurmur3Hash => {
+            Arc::new(hash_expressions::spark_murmur3_hash)
+        }
+        BuiltinScalarFunction::HiveHash => Arc::new(hash_expressions::hive_hash),
         BuiltinScalarFunction::SplitPart => Arc::new(|args| match args[0].data_type() {
             DataType::Utf8 => {
                 make_scalar_function(string_expressions::split_part::<i32>)(args)
@@ -0,0 +1,368 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Hash expressions computing the same values as Spark and Hive, such as
+//! the hashes that they bucket tables by
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, ArrowPrimitiveType, AsArray, Int32Array};
+use arrow::datatypes::{
+    DataType, Date32Type, Date64Type, Decimal128Type, Float32Type, Float64Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, TimeUnit, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
+};
+use datafusion_common::{not_impl_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::ColumnarValue;
+
+/// The seed of the hashes of Spark
+const SPARK_SEED: i32 = 42;
+
+/// Hashes the arguments like Spark's `hash` function, with the 32 bit
+/// Murmur3 hash of seed 42: every argument is hashed with the hash of the
+/// previous arguments as seed, skipping nulls.
+///
+/// Spark hashes timestamps as microseconds, so only timestamps of that unit
+/// have the same hashes.
+pub fn spark_murmur3_hash(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    hash_columns(args, SPARK_SEED, spark_murmur3_hash_array)
+}
+
+/// Hashes the arguments like Hive's `hash` function: the hash of every
+/// argument is added to 31 times the hash of the previous arguments, with
+/// nulls hashing to 0.
+pub fn hive_hash(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    hash_columns(args, 0, hive_hash_array)
+}
+
+/// Combines the hashes of `args` into one hash per row, starting from
+/// `initial`
+fn hash_columns(
+    args: &[ColumnarValue],
+    initial: i32,
+    hash_array: fn(&ArrayRef, &mut [i32]) -> Result<()>,
+) -> Result<ColumnarValue> {
+    let len = args.iter().find_map(|arg| match arg {
+        ColumnarValue::Array(array) => Some(array.len()),
+        ColumnarValue::Scalar(_) => None,
+    });
+    let mut hashes = vec![initial; len.unwrap_or(1)];
+    for arg in args {
+        let array = arg.clone().into_array(hashes.len())?;
+        hash_array(&array, &mut hashes)?;
+    }
+
+    let hashes = Int32Array::from(hashes);
+    match len {
+        Some(_) => Ok(ColumnarValue::Array(Arc::new(hashes))),
+        None => Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+            &hashes, 0,
+        )?)),
+    }
+}
+
+/// Updates `hashes` with the valid values of the primitive `array`
+fn hash_primitive<T: ArrowPrimitiveType>(
+    array: &ArrayRef,
+    hashes: &mut [i32],
+    hash: impl Fn(T::Native, i32) -> i32,
+) {
+    let array = array.as_primitive::<T>();
+    for (i, h) in hashes.iter_mut().enumerate() {
+        if array.is_valid(i) {
+            *h = hash(array.value(i), *h);
+        }
+    }
+}
+
+/// Updates `hashes` with the valid values of the string or binary `array`
+fn hash_bytes(array: &ArrayRef, hashes: &mut [i32], hash: impl Fn(&[u8], i32) -> i32) {
+    match array.data_type() {
+        DataType::Utf8 => {
+            let values = array.as_string::<i32>().iter();
+            hash_values(values.map(|v| v.map(str::as_bytes)), hashes, hash)
+        }
+        DataType::LargeUtf8 => {
+            let values = array.as_string::<i64>().iter();
+            hash_values(values.map(|v| v.map(str::as_bytes)), hashes, hash)
+        }
+        DataType::Binary => hash_values(array.as_binary::<i32>().iter(), hashes, hash),
+        _ => hash_values(array.as_binary::<i64>().iter(), hashes, hash),
+    }
+}
+
+fn hash_values<'a>(
+    values: impl Iterator<Item = Option<&'a [u8]>>,
+    hashes: &mut [i32],
+    hash: impl Fn(&[u8], i32) -> i32,
+) {
+    for (value, h) in values.zip(hashes.iter_mut()) {
+        if let Some(value) = value {
+            *h = hash(value, *h);
+        }
+    }
+}
+
+/// Returns the bits of `value` like Java's `Float.floatToIntBits`, with
+/// `-0.0` hashed as `0.0` like Spark does
+fn float_bits(value: f32) -> i32 {
+    if value == 0.0 {
+        0
+    } else if value.is_nan() {
+        0x7fc00000
+    } else {
+        value.to_bits() as i32
+    }
+}
+
+/// Returns the bits of `value` like Java's `Double.doubleToLongBits`, with
+/// `-0.0` hashed as `0.0` like Spark does
+fn double_bits(value: f64) -> i64 {
+    if value == 0.0 {
+        0
+    } else if value.is_nan() {
+        0x7ff8000000000000
+    } else {
+        value.to_bits() as i64
+    }
+}
+
+fn spark_murmur3_hash_array(array: &ArrayRef, hashes: &mut [i32]) -> Result<()> {
+    match array.data_type() {
+        DataType::Null => {}
+        DataType::Boolean => {
+            let array = array.as_boolean();
+            for (i, h) in hashes.iter_mut().enumerate() {
+                if array.is_valid(i) {
+                    *h = murmur3_hash_int(array.value(i) as i32, *h);
+                }
+            }
+        }
+        DataType::Int8 => hash_primitive::<Int8Type>(array, hashes, |v, h| {
+            murmur3_hash_int(v as i32, h)
+        }),
+        DataType::Int16 => hash_primitive::<Int16Type>(array, hashes, |v, h| {
+            murmur3_hash_int(v as i32, h)
+        }),
+        DataType::Int32 => hash_primitive::<Int32Type>(array, hashes, murmur3_hash_int),
+        DataType::Date32 => hash_primitive::<Date32Type>(array, hashes, murmur3_hash_int),
+        DataType::Int64 => hash_primitive::<Int64Type>(array, hashes, murmur3_hash_long),
+        DataType::Date64 => {
+            hash_primitive::<Date64Type>(array, hashes, murmur3_hash_long)
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            hash_primitive::<TimestampSecondType>(array, hashes, murmur3_hash_long)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            hash_primitive::<TimestampMillisecondType>(array, hashes, murmur3_hash_long)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            hash_primitive::<TimestampMicrosecondType>(array, hashes, murmur3_hash_long)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            hash_primitive::<TimestampNanosecondType>(array, hashes, murmur3_hash_long)
+        }
+        DataType::Float32 => hash_primitive::<Float32Type>(array, hashes, |v, h| {
+            murmur3_hash_int(float_bits(v), h)
+        }),
+        DataType::Float64 => hash_primitive::<Float64Type>(array, hashes, |v, h| {
+            murmur3_hash_long(double_bits(v), h)
+        }),
+        // Spark hashes the unscaled values of decimals of up to 18 digits
+        DataType::Decimal128(precision, _) if *precision <= 18 => {
+            hash_primitive::<Decimal128Type>(array, hashes, |v, h| {
+                murmur3_hash_long(v as i64, h)
+            })
+        }
+        DataType::Utf8
+        | DataType::LargeUtf8
+        | DataType::Binary
+        | DataType::LargeBinary => hash_bytes(array, hashes, murmur3_hash_bytes),
+        other => {
+            return not_impl_err!(
+                "Unsupported data type {other} for function spark_murmur3_hash"
+            )
+        }
+    }
+    Ok(())
+}
+
+fn hive_hash_array(array: &ArrayRef, hashes: &mut [i32]) -> Result<()> {
+    // nulls hash to 0
+    for h in hashes.iter_mut() {
+        *h = h.wrapping_mul(31);
+    }
+    let combine = |value: i32, h: i32| h.wrapping_add(value);
+    match array.data_type() {
+        DataType::Null => {}
+        DataType::Boolean => {
+            let array = array.as_boolean();
+            for (i, h) in hashes.iter_mut().enumerate() {
+                if array.is_valid(i) {
+                    *h = combine(array.value(i) as i32, *h);
+                }
+            }
+        }
+        DataType::Int8 => {
+            hash_primitive::<Int8Type>(array, hashes, |v, h| combine(v as i32, h))
+        }
+        DataType::Int16 => {
+            hash_primitive::<Int16Type>(array, hashes, |v, h| combine(v as i32, h))
+        }
+        DataType::Int32 => hash_primitive::<Int32Type>(array, hashes, combine),
+        DataType::Date32 => hash_primitive::<Date32Type>(array, hashes, combine),
+        DataType::Int64 => hash_primitive::<Int64Type>(array, hashes, |v, h| {
+            combine(hive_hash_long(v), h)
+        }),
+        DataType::Float32 => {
+            hash_primitive::<Float32Type>(array, hashes, |v, h| combine(float_bits(v), h))
+        }
+        DataType::Float64 => hash_primitive::<Float64Type>(array, hashes, |v, h| {
+            combine(hive_hash_long(double_bits(v)), h)
+        }),
+        DataType::Utf8
+        | DataType::LargeUtf8
+        | DataType::Binary
+        | DataType::LargeBinary => hash_bytes(array, hashes, |bytes, h| {
+            let value = bytes.iter().fold(0_i32, |acc, &b| {
+                acc.wrapping_mul(31).wrapping_add(b as i8 as i32)
+            });
+            combine(value, h)
+        }),
+        other => {
+            return not_impl_err!("Unsupported data type {other} for function hive_hash")
+        }
+    }
+    Ok(())
+}
+
+fn hive_hash_long(value: i64) -> i32 {
+    (value ^ ((value as u64) >> 32) as i64) as i32
+}
+
+fn mix_k1(k1: i32) -> i32 {
+    (k1 as u32)
+        .wrapping_mul(0xcc9e2d51)
+        .rotate_left(15)
+        .wrapping_mul(0x1b873593) as i32
+}
+
+fn mix_h1(h1: i32, k1: i32) -> i32 {
+    ((h1 ^ k1) as u32)
+        .rotate_left(13)
+        .wrapping_mul(5)
+        .wrapping_add(0xe6546b64) as i32
+}
+
+fn fmix(h1: i32, len: i32) -> i32 {
+    let mut h1 = (h1 ^ len) as u32;
+    h1 ^= h1 >> 16;
+    h1 = h1.wrapping_mul(0x85ebca6b);
+    h1 ^= h1 >> 13;
+    h1 = h1.wrapping_mul(0xc2b2ae35);
+    h1 ^= h1 >> 16;
+    h1 as i32
+}
+
+/// The Murmur3 hash of an int, like Spark's `Murmur3_x86_32.hashInt`
+fn murmur3_hash_int(value: i32, seed: i32) -> i32 {
+    fmix(mix_h1(seed, mix_k1(value)), 4)
+}
+
+/// The Murmur3 hash of a long, like Spark's `Murmur3_x86_32.hashLong`
+fn murmur3_hash_long(value: i64, seed: i32) -> i32 {
+    let low = value as i32;
+    let high = ((value as u64) >> 32) as i32;
+    let h1 = mix_h1(seed, mix_k1(low));
+    fmix(mix_h1(h1, mix_k1(high)), 8)
+}
+
+/// The Murmur3 hash of bytes, like Spark's
+/// `Murmur3_x86_32.hashUnsafeBytes`, which mixes the trailing bytes one at
+/// a time rather than as a single block
+fn murmur3_hash_bytes(bytes: &[u8], seed: i32) -> i32 {
+    let mut chunks = bytes.chunks_exact(4);
+    let mut h1 = seed;
+    for chunk in &mut chunks {
+        let k1 = i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        h1 = mix_h1(h1, mix_k1(k1));
+    }
+    for &b in chunks.remainder() {
+        h1 = mix_h1(h1, mix_k1(b as i8 as i32));
+    }
+    fmix(h1, bytes.len() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+
+    fn hashes(
+        f: fn(&[ColumnarValue]) -> Result<ColumnarValue>,
+        args: Vec<ArrayRef>,
+    ) -> Result<Vec<Option<i32>>> {
+        let args = args
+            .into_iter()
+            .map(ColumnarValue::Array)
+            .collect::<Vec<_>>();
+        let hashes = f(&args)?.into_array(1)?;
+        Ok(hashes.as_primitive::<Int32Type>().iter().collect())
+    }
+
+    #[test]
+    fn spark_hashes() -> Result<()> {
+        // the values of `hash(1)`, `hash(1L)` and `hash('Spark')` in Spark
+        let ints: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None]));
+        assert_eq!(
+            hashes(spark_murmur3_hash, vec![ints.clone()])?,
+            [Some(-559580957), Some(42)]
+        );
+        let longs: ArrayRef = Arc::new(Int64Array::from(vec![1]));
+        assert_eq!(
+            hashes(spark_murmur3_hash, vec![longs])?,
+            [Some(-1712319331)]
+        );
+        let strings: ArrayRef = Arc::new(StringArray::from(vec!["Spark", "Spark"]));
+        assert_eq!(
+            hashes(spark_murmur3_hash, vec![strings.clone()])?,
+            [Some(228093765), Some(228093765)]
+        );
+        // `hash('Spark', 1)`, and `hash('Spark', null)`
+        assert_eq!(
+            hashes(spark_murmur3_hash, vec![strings, ints])?,
+            [Some(205329825), Some(228093765)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hive_hashes() -> Result<()> {
+        let ints: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None]));
+        let strings: ArrayRef = Arc::new(StringArray::from(vec!["abc", "abc"]));
+        // the hash of a string is the one of Java
+        assert_eq!(
+            hashes(hive_hash, vec![strings.clone()])?,
+            [Some(96354), Some(96354)]
+        );
+        assert_eq!(
+            hashes(hive_hash, vec![ints, strings])?,
+            [Some(31 + 96354), Some(96354)]
+        );
+        Ok(())
+    }
+}
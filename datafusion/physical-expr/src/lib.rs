@@ -28,6 +28,7 @@ pub mod equivalence;
 pub mod execution_props;
 pub mod expressions;
 pub mod functions;
+pub mod hash_expressions;
 pub mod intervals;
 pub mod math_expressions;
 mod partitioning;
@@ -35,7 +36,10 @@ mod physical_expr;
 pub mod planner;
 #[cfg(feature = "regex_expressions")]
 pub mod regex_expressions;
+mod row_evaluator;
 mod scalar_function;
+mod scalar_function_cache;
+pub mod selectivity;
 mod sort_expr;
 pub mod sort_properties;
 pub mod string_expressions;
@@ -60,7 +64,10 @@ pub use physical_expr::{
     PhysicalExpr, PhysicalExprRef,
 };
 pub use planner::create_physical_expr;
+pub use row_evaluator::RowEvaluator;
 pub use scalar_function::ScalarFunctionExpr;
+pub use scalar_function_cache::CachedScalarFunctionExpr;
+pub use selectivity::estimate_selectivity;
 pub use sort_expr::{
     LexOrdering, LexOrderingRef, LexRequirement, LexRequirementRef, PhysicalSortExpr,
     PhysicalSortRequirement,
@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::datetime_expressions::is_utc_time_zone;
 use crate::expressions::GetFieldAccessExpr;
 use crate::var_provider::is_system_variables;
 use crate::{
@@ -24,7 +25,7 @@ use crate::{
     var_provider::VarType,
     PhysicalExpr,
 };
-use arrow::datatypes::Schema;
+use arrow::datatypes::{DataType, Schema};
 use datafusion_common::{
     exec_err, internal_err, not_impl_err, plan_err, DFSchema, DataFusionError, Result,
     ScalarValue,
@@ -275,16 +276,38 @@ pub fn create_physical_expr(
                 };
             Ok(expressions::case(expr, when_then_expr, else_expr)?)
         }
-        Expr::Cast(Cast { expr, data_type }) => expressions::cast(
-            create_physical_expr(expr, input_dfschema, input_schema, execution_props)?,
-            input_schema,
-            data_type.clone(),
-        ),
-        Expr::TryCast(TryCast { expr, data_type }) => expressions::try_cast(
-            create_physical_expr(expr, input_dfschema, input_schema, execution_props)?,
-            input_schema,
-            data_type.clone(),
-        ),
+        Expr::Cast(Cast { expr, data_type }) => {
+            let expr = create_physical_expr(
+                expr,
+                input_dfschema,
+                input_schema,
+                execution_props,
+            )?;
+            let expr = string_to_session_time_zone(
+                expr,
+                input_schema,
+                data_type,
+                execution_props,
+                expressions::cast,
+            )?;
+            expressions::cast(expr, input_schema, data_type.clone())
+        }
+        Expr::TryCast(TryCast { expr, data_type }) => {
+            let expr = create_physical_expr(
+                expr,
+                input_dfschema,
+                input_schema,
+                execution_props,
+            )?;
+            let expr = string_to_session_time_zone(
+                expr,
+                input_schema,
+                data_type,
+                execution_props,
+                expressions::try_cast,
+            )?;
+            expressions::try_cast(expr, input_schema, data_type.clone())
+        }
         Expr::Not(expr) => expressions::not(create_physical_expr(
             expr,
             input_dfschema,
@@ -451,6 +474,43 @@ pub fn create_physical_expr(
     }
 }
 
+/// Casts `expr` to a timestamp in the session time zone if it is a string
+/// cast to a timestamp in UTC, so that the strings without time zone offset
+/// are interpreted in the session time zone rather than in UTC.
+///
+/// The result is then cast to UTC by the caller, which preserves the instants.
+fn string_to_session_time_zone(
+    expr: Arc<dyn PhysicalExpr>,
+    input_schema: &Schema,
+    data_type: &DataType,
+    execution_props: &ExecutionProps,
+    cast_fn: fn(
+        Arc<dyn PhysicalExpr>,
+        &Schema,
+        DataType,
+    ) -> Result<Arc<dyn PhysicalExpr>>,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let (DataType::Timestamp(unit, Some(tz)), Some(time_zone)) =
+        (data_type, &execution_props.time_zone)
+    else {
+        return Ok(expr);
+    };
+    if !is_utc_time_zone(tz)
+        || is_utc_time_zone(time_zone)
+        || !matches!(
+            expr.data_type(input_schema)?,
+            DataType::Utf8 | DataType::LargeUtf8
+        )
+    {
+        return Ok(expr);
+    }
+    cast_fn(
+        expr,
+        input_schema,
+        DataType::Timestamp(unit.clone(), Some(time_zone.clone())),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
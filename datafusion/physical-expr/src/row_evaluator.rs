@@ -0,0 +1,289 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Evaluation of physical expressions over individual rows
+
+use std::sync::Arc;
+
+use crate::expressions::{Column, Literal};
+use crate::utils::collect_columns;
+use crate::PhysicalExpr;
+
+use arrow::array::{new_empty_array, ArrayRef};
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{internal_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::ColumnarValue;
+
+/// Evaluates a [`PhysicalExpr`] over individual rows, or a few rows at a
+/// time, such as the keys probed in an index or the bounds of an interval.
+///
+/// [`PhysicalExpr::evaluate`] works on whole [`RecordBatch`]es, so evaluating
+/// it over a single row requires a batch with an array for every column of
+/// the input schema. A `RowEvaluator` instead rewrites the expression once to
+/// refer to only the columns it uses, and then:
+///
+/// * returns the value of the column or literal directly if the expression
+///   is a single [`Column`] or [`Literal`]
+/// * otherwise builds arrays for only the used columns of the rows
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow::datatypes::{DataType, Field, Schema};
+/// # use datafusion_common::ScalarValue;
+/// # use datafusion_expr::Operator;
+/// # use datafusion_physical_expr::expressions::{binary, col, lit};
+/// # use datafusion_physical_expr::RowEvaluator;
+/// let schema = Schema::new(vec![
+///     Field::new("a", DataType::Int32, false),
+///     Field::new("b", DataType::Int32, false),
+/// ]);
+/// // b + 1
+/// let expr = binary(col("b", &schema).unwrap(), Operator::Plus, lit(1), &schema).unwrap();
+/// let evaluator = RowEvaluator::try_new(expr, &schema).unwrap();
+///
+/// let row = [ScalarValue::Int32(Some(10)), ScalarValue::Int32(Some(20))];
+/// assert_eq!(evaluator.evaluate_row(&row).unwrap(), ScalarValue::Int32(Some(21)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RowEvaluator {
+    /// The expression, referring to the columns of `schema`
+    expr: Arc<dyn PhysicalExpr>,
+    /// The columns of the input schema used by the expression
+    schema: SchemaRef,
+    /// The indices of the columns of `schema` in the input schema
+    indices: Vec<usize>,
+}
+
+impl RowEvaluator {
+    /// Creates an evaluator of `expr` over rows of `input_schema`
+    pub fn try_new(expr: Arc<dyn PhysicalExpr>, input_schema: &Schema) -> Result<Self> {
+        let mut indices = collect_columns(&expr)
+            .iter()
+            .map(|column| column.index())
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        let schema = Arc::new(input_schema.project(&indices)?);
+        let expr = expr.transform_up(&|expr| {
+            let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+                return Ok(Transformed::No(expr));
+            };
+            let index = indices.binary_search(&column.index()).unwrap();
+            Ok(Transformed::Yes(Arc::new(Column::new(
+                column.name(),
+                index,
+            ))))
+        })?;
+        Ok(Self {
+            expr,
+            schema,
+            indices,
+        })
+    }
+
+    /// The evaluated expression, referring to the columns used by it only
+    pub fn expr(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    /// Evaluates the expression over `row`, which holds a value for every
+    /// column of the input schema
+    pub fn evaluate_row(&self, row: &[ScalarValue]) -> Result<ScalarValue> {
+        if let Some(column) = self.expr.as_any().downcast_ref::<Column>() {
+            return self.value(row, column.index()).cloned();
+        }
+        if let Some(literal) = self.expr.as_any().downcast_ref::<Literal>() {
+            return Ok(literal.value().clone());
+        }
+        let columns = (0..self.indices.len())
+            .map(|i| self.value(row, i)?.to_array())
+            .collect::<Result<Vec<_>>>()?;
+        let batch = self.batch(columns, 1)?;
+        match self.expr.evaluate(&batch)? {
+            ColumnarValue::Scalar(value) => Ok(value),
+            ColumnarValue::Array(array) => ScalarValue::try_from_array(&array, 0),
+        }
+    }
+
+    /// Evaluates the expression over `rows`, each of which holds a value for
+    /// every column of the input schema, returning an array of the results
+    pub fn evaluate_rows(&self, rows: &[Vec<ScalarValue>]) -> Result<ArrayRef> {
+        let columns = (0..self.indices.len())
+            .map(|i| {
+                let values = rows
+                    .iter()
+                    .map(|row| self.value(row, i).cloned())
+                    .collect::<Result<Vec<_>>>()?;
+                if values.is_empty() {
+                    Ok(new_empty_array(self.schema.field(i).data_type()))
+                } else {
+                    ScalarValue::iter_to_array(values)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let batch = self.batch(columns, rows.len())?;
+        self.expr.evaluate(&batch)?.into_array(rows.len())
+    }
+
+    /// Evaluates the expression over the row at `index` of `batch`, which has
+    /// the input schema, without copying any of its columns
+    pub fn evaluate_batch_row(
+        &self,
+        batch: &RecordBatch,
+        index: usize,
+    ) -> Result<ScalarValue> {
+        if index >= batch.num_rows() {
+            return internal_err!(
+                "Row {index} is out of bounds of a batch of {} rows",
+                batch.num_rows()
+            );
+        }
+        let columns = self
+            .indices
+            .iter()
+            .map(|&i| batch.column(i).slice(index, 1))
+            .collect();
+        let batch = self.batch(columns, 1)?;
+        match self.expr.evaluate(&batch)? {
+            ColumnarValue::Scalar(value) => Ok(value),
+            ColumnarValue::Array(array) => ScalarValue::try_from_array(&array, 0),
+        }
+    }
+
+    /// Returns the value of the `i`-th used column in `row`
+    fn value<'a>(&self, row: &'a [ScalarValue], i: usize) -> Result<&'a ScalarValue> {
+        row.get(self.indices[i]).ok_or_else(|| {
+            DataFusionError::Internal(format!(
+                "Row of {} values has no column {}",
+                row.len(),
+                self.indices[i]
+            ))
+        })
+    }
+
+    fn batch(&self, columns: Vec<ArrayRef>, num_rows: usize) -> Result<RecordBatch> {
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        Ok(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &options,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{binary, col, lit};
+    use arrow::array::{Array, Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field};
+    use datafusion_expr::Operator;
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Int32, true),
+            Field::new("c", DataType::Int32, true),
+        ])
+    }
+
+    /// `c * 2 + b`
+    fn expr(schema: &Schema) -> Result<Arc<dyn PhysicalExpr>> {
+        let c = binary(col("c", schema)?, Operator::Multiply, lit(2), schema)?;
+        binary(c, Operator::Plus, col("b", schema)?, schema)
+    }
+
+    fn row(a: &str, b: i32, c: i32) -> Vec<ScalarValue> {
+        vec![
+            ScalarValue::from(a),
+            ScalarValue::Int32(Some(b)),
+            ScalarValue::Int32(Some(c)),
+        ]
+    }
+
+    #[test]
+    fn evaluate_single_rows() -> Result<()> {
+        let schema = schema();
+        let evaluator = RowEvaluator::try_new(expr(&schema)?, &schema)?;
+        // only `b` and `c` are used
+        assert_eq!(evaluator.expr().to_string(), "c@1 * 2 + b@0");
+
+        let value = evaluator.evaluate_row(&row("x", 1, 2))?;
+        assert_eq!(value, ScalarValue::Int32(Some(5)));
+
+        let column = RowEvaluator::try_new(col("a", &schema)?, &schema)?;
+        assert_eq!(
+            column.evaluate_row(&row("x", 1, 2))?,
+            ScalarValue::from("x")
+        );
+
+        let literal = RowEvaluator::try_new(lit(7), &schema)?;
+        assert_eq!(literal.evaluate_row(&[])?, ScalarValue::Int32(Some(7)));
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_several_rows() -> Result<()> {
+        let schema = schema();
+        let evaluator = RowEvaluator::try_new(expr(&schema)?, &schema)?;
+
+        let rows = vec![row("x", 1, 2), row("y", 3, 4)];
+        let values = evaluator.evaluate_rows(&rows)?;
+        assert_eq!(
+            values.as_ref(),
+            &Int32Array::from(vec![5, 11]) as &dyn Array
+        );
+
+        let values = evaluator.evaluate_rows(&[])?;
+        assert!(values.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_row_of_batch() -> Result<()> {
+        let schema = schema();
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![
+                Arc::new(StringArray::from(vec!["x", "y", "z"])),
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(Int32Array::from(vec![Some(10), None, Some(30)])),
+            ],
+        )?;
+        let evaluator = RowEvaluator::try_new(expr(&schema)?, &schema)?;
+
+        assert_eq!(
+            evaluator.evaluate_batch_row(&batch, 2)?,
+            ScalarValue::Int32(Some(63))
+        );
+        assert_eq!(
+            evaluator.evaluate_batch_row(&batch, 1)?,
+            ScalarValue::Int32(None)
+        );
+        assert!(evaluator.evaluate_batch_row(&batch, 3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn row_without_used_column() -> Result<()> {
+        let schema = schema();
+        let evaluator = RowEvaluator::try_new(expr(&schema)?, &schema)?;
+        assert!(evaluator.evaluate_row(&[ScalarValue::from("x")]).is_err());
+        Ok(())
+    }
+}
@@ -44,7 +44,7 @@ use arrow::record_batch::RecordBatch;
 use datafusion_common::Result;
 use datafusion_expr::{
     expr_vec_fmt, BuiltinScalarFunction, ColumnarValue, FuncMonotonicity,
-    ScalarFunctionImplementation,
+    ScalarFunctionImplementation, Volatility,
 };
 
 /// Physical expression of a scalar function
@@ -58,6 +58,8 @@ pub struct ScalarFunctionExpr {
     // and it specifies the effect of an increase or decrease in
     // the corresponding `arg` to the function value.
     monotonicity: Option<FuncMonotonicity>,
+    // Volatility of the function, `Volatile` if unknown
+    volatility: Volatility,
 }
 
 impl Debug for ScalarFunctionExpr {
@@ -86,9 +88,16 @@ impl ScalarFunctionExpr {
             args,
             return_type,
             monotonicity,
+            volatility: Volatility::Volatile,
         }
     }
 
+    /// Set the volatility of the function
+    pub fn with_volatility(mut self, volatility: Volatility) -> Self {
+        self.volatility = volatility;
+        self
+    }
+
     /// Get the scalar function implementation
     pub fn fun(&self) -> &ScalarFunctionImplementation {
         &self.fun
@@ -113,6 +122,11 @@ impl ScalarFunctionExpr {
     pub fn monotonicity(&self) -> &Option<FuncMonotonicity> {
         &self.monotonicity
     }
+
+    /// Volatility of the function
+    pub fn volatility(&self) -> Volatility {
+        self.volatility
+    }
 }
 
 impl fmt::Display for ScalarFunctionExpr {
@@ -169,13 +183,16 @@ impl PhysicalExpr for ScalarFunctionExpr {
         self: Arc<Self>,
         children: Vec<Arc<dyn PhysicalExpr>>,
     ) -> Result<Arc<dyn PhysicalExpr>> {
-        Ok(Arc::new(ScalarFunctionExpr::new(
-            &self.name,
-            self.fun.clone(),
-            children,
-            self.return_type().clone(),
-            self.monotonicity.clone(),
-        )))
+        Ok(Arc::new(
+            ScalarFunctionExpr::new(
+                &self.name,
+                self.fun.clone(),
+                children,
+                self.return_type().clone(),
+                self.monotonicity.clone(),
+            )
+            .with_volatility(self.volatility),
+        ))
     }
 
     fn dyn_hash(&self, state: &mut dyn Hasher) {
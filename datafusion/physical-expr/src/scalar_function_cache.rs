@@ -0,0 +1,327 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Caching of scalar function results over repeated arguments
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::Hasher;
+use std::sync::{Arc, Mutex};
+
+use crate::physical_expr::down_cast_any_ref;
+use crate::sort_properties::SortProperties;
+use crate::{PhysicalExpr, ScalarFunctionExpr};
+
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::compute::take;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::ColumnarValue;
+
+/// Wraps a deterministic [`ScalarFunctionExpr`], evaluating the function only
+/// once per distinct combination of argument values within a batch, and
+/// caching the results for up to `capacity` distinct arguments across
+/// batches.
+///
+/// This is beneficial for expensive functions over low cardinality inputs,
+/// such as regular expressions over dictionary encoded strings. The function
+/// must not be volatile, as the results are reused for all rows with the same
+/// arguments.
+pub struct CachedScalarFunctionExpr {
+    inner: Arc<ScalarFunctionExpr>,
+    capacity: usize,
+    cache: Mutex<HashMap<OwnedRow, ScalarValue>>,
+}
+
+impl Debug for CachedScalarFunctionExpr {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("CachedScalarFunctionExpr")
+            .field("inner", &self.inner)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl CachedScalarFunctionExpr {
+    /// Create a new cached function call, caching the results for up to
+    /// `capacity` distinct arguments
+    pub fn new(inner: Arc<ScalarFunctionExpr>, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The wrapped function call
+    pub fn inner(&self) -> &Arc<ScalarFunctionExpr> {
+        &self.inner
+    }
+
+    /// Maximum number of distinct arguments to cache results for
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Evaluates the function for the distinct rows of `args`
+    fn evaluate_distinct(&self, args: &[ArrayRef]) -> Result<ArrayRef> {
+        let converter = RowConverter::new(
+            args.iter()
+                .map(|arg| SortField::new(arg.data_type().clone()))
+                .collect(),
+        )?;
+        let rows = converter.convert_columns(args)?;
+
+        // the index of the first row with each distinct argument values, and
+        // the index of the distinct values of each row
+        let mut distinct_rows = vec![];
+        let mut distinct = HashMap::new();
+        let indices = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                *distinct.entry(row).or_insert_with(|| {
+                    distinct_rows.push(i as u32);
+                    distinct_rows.len() as u32 - 1
+                })
+            })
+            .collect::<UInt32Array>();
+
+        let mut cache = self.cache.lock().unwrap();
+        let mut results = distinct_rows
+            .iter()
+            .map(|&i| cache.get(&rows.row(i as usize).owned()).cloned())
+            .collect::<Vec<_>>();
+
+        // evaluate the function for the arguments that are not cached
+        let misses = results
+            .iter()
+            .zip(&distinct_rows)
+            .filter(|(result, _)| result.is_none())
+            .map(|(_, &i)| i)
+            .collect::<UInt32Array>();
+        if !misses.is_empty() {
+            let miss_args = args
+                .iter()
+                .map(|arg| Ok(ColumnarValue::Array(take(arg, &misses, None)?)))
+                .collect::<Result<Vec<_>>>()?;
+            let values =
+                (self.inner.fun().as_ref())(&miss_args)?.into_array(misses.len())?;
+
+            let mut next = 0;
+            for (result, &i) in results.iter_mut().zip(&distinct_rows) {
+                if result.is_some() {
+                    continue;
+                }
+                let value = ScalarValue::try_from_array(&values, next)?;
+                next += 1;
+                if cache.len() < self.capacity {
+                    cache.insert(rows.row(i as usize).owned(), value.clone());
+                }
+                *result = Some(value);
+            }
+        }
+        drop(cache);
+
+        let distinct_values = ScalarValue::iter_to_array(results.into_iter().flatten())?;
+        Ok(take(&distinct_values, &indices, None)?)
+    }
+}
+
+impl fmt::Display for CachedScalarFunctionExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cached({})", self.inner)
+    }
+}
+
+impl PhysicalExpr for CachedScalarFunctionExpr {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        self.inner.data_type(input_schema)
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        self.inner.nullable(input_schema)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        if self.inner.args().is_empty() || batch.num_rows() == 0 {
+            return self.inner.evaluate(batch);
+        }
+        let args = self
+            .inner
+            .args()
+            .iter()
+            .map(|arg| arg.evaluate(batch))
+            .collect::<Result<Vec<_>>>()?;
+        if args
+            .iter()
+            .all(|arg| matches!(arg, ColumnarValue::Scalar(_)))
+        {
+            return (self.inner.fun().as_ref())(&args);
+        }
+
+        let args = args
+            .into_iter()
+            .map(|arg| arg.into_array(batch.num_rows()))
+            .collect::<Result<Vec<_>>>()?;
+        self.evaluate_distinct(&args).map(ColumnarValue::Array)
+    }
+
+    fn children(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        self.inner.children()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        let inner = ScalarFunctionExpr::new(
+            self.inner.name(),
+            self.inner.fun().clone(),
+            children,
+            self.inner.return_type().clone(),
+            self.inner.monotonicity().clone(),
+        )
+        .with_volatility(self.inner.volatility());
+        Ok(Arc::new(Self::new(Arc::new(inner), self.capacity)))
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        self.inner.dyn_hash(state);
+    }
+
+    fn get_ordering(&self, children: &[SortProperties]) -> SortProperties {
+        self.inner.get_ordering(children)
+    }
+}
+
+impl PartialEq<dyn Any> for CachedScalarFunctionExpr {
+    fn eq(&self, other: &dyn Any) -> bool {
+        down_cast_any_ref(other)
+            .downcast_ref::<Self>()
+            .map(|x| self.inner.eq(x.inner.as_any()) && self.capacity == x.capacity)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{col, lit};
+    use arrow::array::{Array, DictionaryArray, Int32Array, StringArray};
+    use arrow::compute::cast;
+    use arrow::datatypes::{Field, Int32Type};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// An identity function over `a`, counting the number of values it is
+    /// evaluated for
+    fn cached_identity(
+        schema: &Schema,
+        capacity: usize,
+    ) -> Result<(CachedScalarFunctionExpr, Arc<AtomicUsize>)> {
+        let evaluated = Arc::new(AtomicUsize::new(0));
+        let counter = evaluated.clone();
+        let inner = ScalarFunctionExpr::new(
+            "identity",
+            Arc::new(move |args: &[ColumnarValue]| {
+                if let ColumnarValue::Array(array) = &args[0] {
+                    counter.fetch_add(array.len(), Ordering::SeqCst);
+                }
+                Ok(args[0].clone())
+            }),
+            vec![col("a", schema)?],
+            schema.field(0).data_type().clone(),
+            None,
+        );
+        Ok((
+            CachedScalarFunctionExpr::new(Arc::new(inner), capacity),
+            evaluated,
+        ))
+    }
+
+    #[test]
+    fn cached_results() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let (expr, evaluated) = cached_identity(&schema, 2)?;
+        let array = StringArray::from(vec![
+            Some("a"),
+            Some("b"),
+            None,
+            Some("a"),
+            Some("c"),
+            Some("b"),
+        ]);
+        let batch =
+            RecordBatch::try_new(Arc::new(schema), vec![Arc::new(array.clone())])?;
+
+        let result = expr.evaluate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(result.as_ref(), &array as &dyn Array);
+        assert_eq!(evaluated.load(Ordering::SeqCst), 4);
+
+        // only the results for "a" and "b" fit in the cache
+        let result = expr.evaluate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(result.as_ref(), &array as &dyn Array);
+        assert_eq!(evaluated.load(Ordering::SeqCst), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn cached_dictionary_arguments() -> Result<()> {
+        let values = StringArray::from(vec!["x", "y"]);
+        let keys = Int32Array::from(vec![0, 1, 1, 0]);
+        let array = DictionaryArray::<Int32Type>::try_new(keys, Arc::new(values))?;
+        let schema = Schema::new(vec![Field::new("a", array.data_type().clone(), true)]);
+        let (expr, evaluated) = cached_identity(&schema, 10)?;
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(array)])?;
+
+        let result = expr.evaluate(&batch)?.into_array(batch.num_rows())?;
+        let expected = StringArray::from(vec!["x", "y", "y", "x"]);
+        assert_eq!(
+            cast(&result, &DataType::Utf8)?.as_ref(),
+            &expected as &dyn Array
+        );
+        assert_eq!(evaluated.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_arguments() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let (expr, _) = cached_identity(&schema, 10)?;
+        let expr = Arc::new(expr).with_new_children(vec![lit("z")])?;
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(StringArray::from(vec!["a"]))],
+        )?;
+
+        match expr.evaluate(&batch)? {
+            ColumnarValue::Scalar(value) => {
+                assert_eq!(value, ScalarValue::Utf8(Some("z".to_string())))
+            }
+            _ => panic!("expected scalar result"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,524 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Estimates the selectivity of filter predicates from column statistics
+
+use std::cmp::Ordering;
+use std::iter;
+use std::sync::Arc;
+
+use crate::expressions::{
+    BinaryExpr, Column, InListExpr, IsNotNullExpr, IsNullExpr, LikeExpr, Literal, NotExpr,
+};
+use crate::PhysicalExpr;
+
+use arrow::datatypes::DataType;
+use datafusion_common::stats::Histogram;
+use datafusion_common::{ColumnStatistics, ScalarValue, Statistics};
+use datafusion_expr::Operator;
+
+/// Estimates the fraction of the rows described by `statistics` for which
+/// `predicate` is true.
+///
+/// Supports comparisons between a column and a literal, `IN` lists of
+/// literals, `IS [NOT] NULL`, `LIKE` with a constant prefix such as
+/// `'abc%'`, and `AND`, `OR` and `NOT` of those predicates. Equality is
+/// estimated from the distinct count of the column and ranges from its
+/// [`Histogram`] when there is one, or by assuming the values are evenly
+/// spread between its minimum and maximum. Predicates on different columns
+/// are assumed to be independent.
+///
+/// Returns `None` if the statistics are not sufficient to estimate the
+/// selectivity of `predicate`.
+pub fn estimate_selectivity(
+    predicate: &Arc<dyn PhysicalExpr>,
+    statistics: &Statistics,
+) -> Option<f64> {
+    selectivity(predicate, statistics).map(|selectivity| selectivity.clamp(0.0, 1.0))
+}
+
+fn selectivity(
+    predicate: &Arc<dyn PhysicalExpr>,
+    statistics: &Statistics,
+) -> Option<f64> {
+    let predicate_any = predicate.as_any();
+    if let Some(binary) = predicate_any.downcast_ref::<BinaryExpr>() {
+        match binary.op() {
+            Operator::And => {
+                let left = selectivity(binary.left(), statistics)?;
+                let right = selectivity(binary.right(), statistics)?;
+                Some(left * right)
+            }
+            Operator::Or => {
+                let left = selectivity(binary.left(), statistics)?;
+                let right = selectivity(binary.right(), statistics)?;
+                Some(left + right - left * right)
+            }
+            op => comparison_selectivity(binary.left(), *op, binary.right(), statistics),
+        }
+    } else if let Some(not) = predicate_any.downcast_ref::<NotExpr>() {
+        selectivity(not.arg(), statistics).map(|selectivity| 1.0 - selectivity)
+    } else if let Some(is_null) = predicate_any.downcast_ref::<IsNullExpr>() {
+        let column_stats = column_statistics(is_null.arg(), statistics)?;
+        null_fraction(statistics, column_stats)
+    } else if let Some(is_not_null) = predicate_any.downcast_ref::<IsNotNullExpr>() {
+        let column_stats = column_statistics(is_not_null.arg(), statistics)?;
+        null_fraction(statistics, column_stats).map(|fraction| 1.0 - fraction)
+    } else if let Some(in_list) = predicate_any.downcast_ref::<InListExpr>() {
+        in_list_selectivity(in_list, statistics)
+    } else if let Some(like) = predicate_any.downcast_ref::<LikeExpr>() {
+        like_selectivity(like, statistics)
+    } else if let Some(literal) = predicate_any.downcast_ref::<Literal>() {
+        match literal.value() {
+            ScalarValue::Boolean(Some(true)) => Some(1.0),
+            ScalarValue::Boolean(_) => Some(0.0),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Estimates the selectivity of `left op right`, where one side is a column
+/// and the other a literal
+fn comparison_selectivity(
+    left: &Arc<dyn PhysicalExpr>,
+    op: Operator,
+    right: &Arc<dyn PhysicalExpr>,
+    statistics: &Statistics,
+) -> Option<f64> {
+    let (column_stats, op, value) = match (
+        column_statistics(left, statistics),
+        right.as_any().downcast_ref::<Literal>(),
+    ) {
+        (Some(column_stats), Some(literal)) => (column_stats, op, literal.value()),
+        _ => {
+            let column_stats = column_statistics(right, statistics)?;
+            let literal = left.as_any().downcast_ref::<Literal>()?;
+            (column_stats, op.swap()?, literal.value())
+        }
+    };
+    if value.is_null() {
+        // comparisons with null are never true
+        return Some(0.0);
+    }
+    let equal = || equal_fraction(column_stats, value);
+    let fraction = match op {
+        Operator::Eq => equal()?,
+        Operator::NotEq => 1.0 - equal()?,
+        Operator::Lt => fraction_below(column_stats, value)?,
+        Operator::LtEq => fraction_below(column_stats, value)? + equal().unwrap_or(0.0),
+        Operator::Gt => {
+            1.0 - fraction_below(column_stats, value)? - equal().unwrap_or(0.0)
+        }
+        Operator::GtEq => 1.0 - fraction_below(column_stats, value)?,
+        _ => return None,
+    };
+    Some(fraction.clamp(0.0, 1.0) * non_null_fraction(statistics, column_stats))
+}
+
+/// Estimates the selectivity of `expr [NOT] IN (list)` from the fraction of
+/// the values of `expr` equal to each literal in the list
+fn in_list_selectivity(in_list: &InListExpr, statistics: &Statistics) -> Option<f64> {
+    let column_stats = column_statistics(in_list.expr(), statistics)?;
+    let mut fraction = 0.0;
+    for item in in_list.list() {
+        let value = item.as_any().downcast_ref::<Literal>()?.value();
+        if !value.is_null() {
+            fraction += equal_fraction(column_stats, value)?;
+        }
+    }
+    let fraction = fraction.min(1.0);
+    let fraction = if in_list.negated() {
+        1.0 - fraction
+    } else {
+        fraction
+    };
+    Some(fraction * non_null_fraction(statistics, column_stats))
+}
+
+/// Estimates the selectivity of `expr [NOT] LIKE 'prefix%'` as that of
+/// `expr >= 'prefix' AND expr < 'prefiy'`
+fn like_selectivity(like: &LikeExpr, statistics: &Statistics) -> Option<f64> {
+    if like.case_insensitive() {
+        return None;
+    }
+    let column_stats = column_statistics(like.expr(), statistics)?;
+    let pattern = match like.pattern().as_any().downcast_ref::<Literal>()?.value() {
+        ScalarValue::Utf8(Some(pattern)) | ScalarValue::LargeUtf8(Some(pattern)) => {
+            pattern
+        }
+        _ => return None,
+    };
+    let prefix = pattern.strip_suffix('%')?;
+    if prefix.is_empty() || prefix.contains(['%', '_', '\\']) {
+        return None;
+    }
+    let mut upper = prefix.to_string();
+    let last = upper.pop()?;
+    upper.push(char::from_u32(last as u32 + 1)?);
+
+    let lower = ScalarValue::Utf8(Some(prefix.to_string()));
+    let upper = ScalarValue::Utf8(Some(upper));
+    let fraction = (fraction_below(column_stats, &upper)?
+        - fraction_below(column_stats, &lower)?)
+    .clamp(0.0, 1.0);
+    let fraction = if like.negated() {
+        1.0 - fraction
+    } else {
+        fraction
+    };
+    Some(fraction * non_null_fraction(statistics, column_stats))
+}
+
+/// Returns the statistics of `expr` if it is a column
+fn column_statistics<'a>(
+    expr: &Arc<dyn PhysicalExpr>,
+    statistics: &'a Statistics,
+) -> Option<&'a ColumnStatistics> {
+    let column = expr.as_any().downcast_ref::<Column>()?;
+    statistics.column_statistics.get(column.index())
+}
+
+/// Returns the fraction of the rows in which the column is null
+fn null_fraction(
+    statistics: &Statistics,
+    column_stats: &ColumnStatistics,
+) -> Option<f64> {
+    let num_rows = *statistics.num_rows.get_value()?;
+    let null_count = *column_stats.null_count.get_value()?;
+    if num_rows == 0 {
+        return Some(0.0);
+    }
+    Some((null_count as f64 / num_rows as f64).min(1.0))
+}
+
+/// Returns the fraction of the rows in which the column is not null, assuming
+/// there are no nulls if the null count is not known
+fn non_null_fraction(statistics: &Statistics, column_stats: &ColumnStatistics) -> f64 {
+    1.0 - null_fraction(statistics, column_stats).unwrap_or(0.0)
+}
+
+/// Estimates the fraction of the non null values of the column equal to
+/// `value`
+fn equal_fraction(column_stats: &ColumnStatistics, value: &ScalarValue) -> Option<f64> {
+    let min_value = column_stats.min_value.get_value();
+    let max_value = column_stats.max_value.get_value();
+    let below_min = min_value
+        .and_then(|min| value.partial_cmp(min))
+        .map_or(false, |ordering| ordering == Ordering::Less);
+    let above_max = max_value
+        .and_then(|max| value.partial_cmp(max))
+        .map_or(false, |ordering| ordering == Ordering::Greater);
+    if below_min || above_max {
+        return Some(0.0);
+    }
+    match column_stats.distinct_count.get_value() {
+        Some(&distinct_count) if distinct_count > 0 => Some(1.0 / distinct_count as f64),
+        _ if min_value.is_some() && min_value == max_value => Some(1.0),
+        _ => None,
+    }
+}
+
+/// Estimates the fraction of the non null values of the column less than
+/// `value`, from its histogram if it has one, or else from its minimum and
+/// maximum values
+fn fraction_below(column_stats: &ColumnStatistics, value: &ScalarValue) -> Option<f64> {
+    let min_value = column_stats.min_value.get_value();
+    if let Some(histogram) = &column_stats.histogram {
+        if let Some(fraction) = histogram_fraction_below(histogram, min_value, value) {
+            return Some(fraction);
+        }
+    }
+    let max_value = column_stats.max_value.get_value()?;
+    interpolate(min_value?, max_value, value)
+}
+
+/// Estimates the fraction of the values in `histogram` less than `value`,
+/// assuming the values of the bucket containing it are evenly spread between
+/// the bounds of the bucket. The lower bound of the first bucket is the
+/// minimum value of the column, `min_value`.
+fn histogram_fraction_below(
+    histogram: &Histogram,
+    min_value: Option<&ScalarValue>,
+    value: &ScalarValue,
+) -> Option<f64> {
+    let total_count = histogram.total_count();
+    if total_count == 0 {
+        return None;
+    }
+    let mut below = 0.0;
+    let mut lower_bound = min_value;
+    for bucket in &histogram.buckets {
+        if bucket.upper_bound.partial_cmp(value)? == Ordering::Less {
+            below += bucket.count as f64;
+            lower_bound = Some(&bucket.upper_bound);
+            continue;
+        }
+        let fraction_of_bucket = lower_bound
+            .and_then(|lower_bound| interpolate(lower_bound, &bucket.upper_bound, value))
+            .unwrap_or(0.5);
+        below += bucket.count as f64 * fraction_of_bucket;
+        break;
+    }
+    Some(below / total_count as f64)
+}
+
+/// Returns where `value` falls between `lower` and `upper`, from `0.0` at or
+/// below `lower` to `1.0` at or above `upper`
+fn interpolate(
+    lower: &ScalarValue,
+    upper: &ScalarValue,
+    value: &ScalarValue,
+) -> Option<f64> {
+    let (lower, upper, value) = (position(lower)?, position(upper)?, position(value)?);
+    Some(if value <= lower {
+        0.0
+    } else if value >= upper {
+        1.0
+    } else {
+        (value - lower) / (upper - lower)
+    })
+}
+
+/// Maps `value` to a number that preserves the order of the values of its
+/// type, so that distances between values can be compared. Strings are
+/// mapped from their first 8 bytes.
+fn position(value: &ScalarValue) -> Option<f64> {
+    match value {
+        ScalarValue::Utf8(Some(value)) | ScalarValue::LargeUtf8(Some(value)) => Some(
+            value
+                .bytes()
+                .chain(iter::repeat(0))
+                .take(8)
+                .fold(0.0, |position, byte| position * 256.0 + byte as f64),
+        ),
+        ScalarValue::Date32(Some(value)) => Some(*value as f64),
+        ScalarValue::Date64(Some(value))
+        | ScalarValue::TimestampSecond(Some(value), _)
+        | ScalarValue::TimestampMillisecond(Some(value), _)
+        | ScalarValue::TimestampMicrosecond(Some(value), _)
+        | ScalarValue::TimestampNanosecond(Some(value), _) => Some(*value as f64),
+        value if value.data_type().is_numeric() && !value.is_null() => {
+            match value.cast_to(&DataType::Float64).ok()? {
+                ScalarValue::Float64(Some(value)) => Some(value),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{col, in_list, lit};
+
+    use arrow::datatypes::{Field, Schema};
+    use datafusion_common::stats::{HistogramBucket, Precision};
+    use datafusion_common::Result;
+
+    /// Table of 100 rows:
+    ///      a: Int64, min=0, max=100, 10 nulls, 50 distinct values
+    ///      b: Utf8, min='a', buckets ('b', 10), ('m', 20), ('z', 10)
+    fn schema_and_statistics() -> (Schema, Statistics) {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        let bucket = |upper_bound: &str, count| HistogramBucket {
+            upper_bound: ScalarValue::from(upper_bound),
+            count,
+        };
+        let statistics = Statistics {
+            num_rows: Precision::Exact(100),
+            total_byte_size: Precision::Absent,
+            column_statistics: vec![
+                ColumnStatistics {
+                    null_count: Precision::Exact(10),
+                    min_value: Precision::Exact(ScalarValue::Int64(Some(0))),
+                    max_value: Precision::Exact(ScalarValue::Int64(Some(100))),
+                    distinct_count: Precision::Exact(50),
+                    histogram: None,
+                },
+                ColumnStatistics {
+                    null_count: Precision::Exact(60),
+                    min_value: Precision::Exact(ScalarValue::from("a")),
+                    max_value: Precision::Exact(ScalarValue::from("z")),
+                    distinct_count: Precision::Absent,
+                    histogram: Some(Arc::new(Histogram::new(vec![
+                        bucket("b", 10),
+                        bucket("m", 20),
+                        bucket("z", 10),
+                    ]))),
+                },
+            ],
+        };
+        (schema, statistics)
+    }
+
+    fn assert_selectivity(
+        predicate: Arc<dyn PhysicalExpr>,
+        statistics: &Statistics,
+        expected: Option<f64>,
+    ) {
+        let actual = estimate_selectivity(&predicate, statistics);
+        match (actual, expected) {
+            (Some(actual), Some(expected)) => assert!(
+                (actual - expected).abs() < 1e-9,
+                "{predicate}: expected {expected}, got {actual}"
+            ),
+            _ => assert_eq!(actual, expected, "{predicate}"),
+        }
+    }
+
+    fn binary(
+        left: Arc<dyn PhysicalExpr>,
+        op: Operator,
+        right: Arc<dyn PhysicalExpr>,
+    ) -> Arc<dyn PhysicalExpr> {
+        Arc::new(BinaryExpr::new(left, op, right))
+    }
+
+    #[test]
+    fn estimate_comparison_selectivity() -> Result<()> {
+        let (schema, statistics) = schema_and_statistics();
+        let a = col("a", &schema)?;
+
+        // 90% of the rows are not null, 1 in 50 of which are equal to 10
+        assert_selectivity(
+            binary(a.clone(), Operator::Eq, lit(10i64)),
+            &statistics,
+            Some(0.018),
+        );
+        assert_selectivity(
+            binary(a.clone(), Operator::NotEq, lit(10i64)),
+            &statistics,
+            Some(0.882),
+        );
+        assert_selectivity(
+            binary(a.clone(), Operator::Lt, lit(25i64)),
+            &statistics,
+            Some(0.225),
+        );
+        // literal on the left
+        assert_selectivity(
+            binary(lit(25i64), Operator::Gt, a.clone()),
+            &statistics,
+            Some(0.225),
+        );
+        assert_selectivity(
+            binary(a.clone(), Operator::GtEq, lit(25i64)),
+            &statistics,
+            Some(0.675),
+        );
+        // out of range
+        assert_selectivity(
+            binary(a.clone(), Operator::Eq, lit(200i64)),
+            &statistics,
+            Some(0.0),
+        );
+        assert_selectivity(
+            binary(a.clone(), Operator::Gt, lit(200i64)),
+            &statistics,
+            Some(0.0),
+        );
+        // unsupported operator
+        assert_selectivity(binary(a, Operator::Plus, lit(1i64)), &statistics, None);
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_histogram_selectivity() -> Result<()> {
+        let (schema, statistics) = schema_and_statistics();
+        let b = col("b", &schema)?;
+
+        // 40% of the rows are not null, 10 of the 40 values in the histogram
+        // are below 'b'
+        assert_selectivity(
+            binary(b.clone(), Operator::Lt, lit("b")),
+            &statistics,
+            Some(0.1),
+        );
+        // 'd' is 2/11 of the way from 'b' to 'm'
+        assert_selectivity(
+            binary(b.clone(), Operator::Lt, lit("d")),
+            &statistics,
+            Some(0.4 * (10.0 + 20.0 * 2.0 / 11.0) / 40.0),
+        );
+        // b >= 'd' AND b < 'e'
+        let like = Arc::new(LikeExpr::new(false, false, b.clone(), lit("d%")));
+        assert_selectivity(like, &statistics, Some(0.4 * (20.0 / 11.0) / 40.0));
+        // LIKE with wildcards before the end is not supported
+        let like = Arc::new(LikeExpr::new(false, false, b, lit("d%e")));
+        assert_selectivity(like, &statistics, None);
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_compound_selectivity() -> Result<()> {
+        let (schema, statistics) = schema_and_statistics();
+        let a = col("a", &schema)?;
+        let b = col("b", &schema)?;
+
+        assert_selectivity(Arc::new(IsNullExpr::new(a.clone())), &statistics, Some(0.1));
+        assert_selectivity(
+            Arc::new(IsNotNullExpr::new(b.clone())),
+            &statistics,
+            Some(0.4),
+        );
+        assert_selectivity(
+            in_list(
+                a.clone(),
+                vec![lit(1i64), lit(2i64), lit(300i64)],
+                &false,
+                &schema,
+            )?,
+            &statistics,
+            Some(0.036),
+        );
+        assert_selectivity(
+            binary(
+                binary(a.clone(), Operator::Eq, lit(10i64)),
+                Operator::Or,
+                Arc::new(IsNullExpr::new(a.clone())),
+            ),
+            &statistics,
+            Some(0.018 + 0.1 - 0.018 * 0.1),
+        );
+        assert_selectivity(
+            binary(
+                Arc::new(IsNullExpr::new(a.clone())),
+                Operator::And,
+                Arc::new(IsNotNullExpr::new(b.clone())),
+            ),
+            &statistics,
+            Some(0.04),
+        );
+        // the distinct count of b is not known
+        assert_selectivity(
+            binary(
+                Arc::new(IsNullExpr::new(a)),
+                Operator::And,
+                binary(b, Operator::Eq, lit("c")),
+            ),
+            &statistics,
+            None,
+        );
+        Ok(())
+    }
+}
@@ -34,11 +34,14 @@ pub fn create_physical_expr(
         .map(|e| e.data_type(input_schema))
         .collect::<Result<Vec<_>>>()?;
 
-    Ok(Arc::new(ScalarFunctionExpr::new(
-        fun.name(),
-        fun.fun(),
-        input_phy_exprs.to_vec(),
-        fun.return_type(&input_exprs_types)?,
-        None,
-    )))
+    Ok(Arc::new(
+        ScalarFunctionExpr::new(
+            fun.name(),
+            fun.fun(),
+            input_phy_exprs.to_vec(),
+            fun.return_type(&input_exprs_types)?,
+            None,
+        )
+        .with_volatility(fun.signature().volatility),
+    ))
 }
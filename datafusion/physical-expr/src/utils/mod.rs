@@ -16,7 +16,11 @@
 // under the License.
 
 mod guarantee;
+mod rewrite;
 pub use guarantee::{Guarantee, LiteralGuarantee};
+pub use rewrite::{
+    canonicalize_expr, expr_after_projection, expr_before_projection, map_columns,
+};
 
 use std::borrow::{Borrow, Cow};
 use std::collections::{HashMap, HashSet};
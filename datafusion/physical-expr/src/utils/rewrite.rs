@@ -0,0 +1,242 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Rewrites of [`PhysicalExpr`]s commonly needed by physical optimizer rules.
+//!
+//! [`PhysicalExpr`] trees implement [`TreeNode`], so custom traversals can
+//! be written with [`TreeNode::apply`], [`TreeNode::transform_up`] or a
+//! [`TreeNodeRewriter`](datafusion_common::tree_node::TreeNodeRewriter);
+//! the functions of this module are built on them.
+
+use std::sync::Arc;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{internal_err, DataFusionError, Result};
+use datafusion_expr::Operator;
+
+use crate::expressions::{BinaryExpr, Column};
+use crate::PhysicalExpr;
+
+/// Replaces the [`Column`]s of `expr` for which `f` returns an expression by
+/// that expression, keeping the others.
+///
+/// For instance, renaming column `a` to `x`:
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use datafusion_common::Result;
+/// # use datafusion_physical_expr::PhysicalExpr;
+/// # use datafusion_physical_expr::expressions::{col, Column};
+/// # use datafusion_physical_expr::utils::map_columns;
+/// # use arrow_schema::{DataType, Field, Schema};
+/// # fn main() -> Result<()> {
+/// # let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+/// let expr = col("a", &schema)?;
+/// let renamed = map_columns(expr, &mut |column| {
+///     Ok((column.name() == "a")
+///         .then(|| Arc::new(Column::new("x", column.index())) as Arc<dyn PhysicalExpr>))
+/// })?;
+/// assert_eq!(renamed.to_string(), "x@0");
+/// # Ok(())
+/// # }
+/// ```
+pub fn map_columns<F>(
+    expr: Arc<dyn PhysicalExpr>,
+    f: &mut F,
+) -> Result<Arc<dyn PhysicalExpr>>
+where
+    F: FnMut(&Column) -> Result<Option<Arc<dyn PhysicalExpr>>>,
+{
+    expr.transform_up_mut(&mut |expr: Arc<dyn PhysicalExpr>| {
+        let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+            return Ok(Transformed::No(expr));
+        };
+        Ok(match f(column)? {
+            Some(new_expr) => Transformed::Yes(new_expr),
+            None => Transformed::No(expr),
+        })
+    })
+}
+
+/// Rewrites `expr`, which refers to the output of a projection of
+/// `projected_exprs`, so that it refers to the input of the projection
+/// instead.
+///
+/// Each column `c@i` of `expr` is replaced by the `i`th projected expression:
+/// given the projection `a@1 + b@2 AS x, c@0 AS y`, the expression `x@0 > y@1`
+/// becomes `a@1 + b@2 > c@0`.
+pub fn expr_before_projection(
+    expr: Arc<dyn PhysicalExpr>,
+    projected_exprs: &[(Arc<dyn PhysicalExpr>, String)],
+) -> Result<Arc<dyn PhysicalExpr>> {
+    map_columns(
+        expr,
+        &mut |column| match projected_exprs.get(column.index()) {
+            Some((projected_expr, _)) => Ok(Some(projected_expr.clone())),
+            None => internal_err!(
+                "Column {column} is not in a projection of {} expressions",
+                projected_exprs.len()
+            ),
+        },
+    )
+}
+
+/// Rewrites `expr`, which refers to the input of a projection of
+/// `projected_exprs`, so that it refers to the output of the projection
+/// instead, or returns `None` if one of its columns is not projected.
+///
+/// Each column of `expr` is replaced by the output column of the projected
+/// column with the same name: given the projection `a@1 AS x, c@0 AS y`,
+/// the expression `c@0 > a@1` becomes `y@1 > x@0`, while `b@2 > a@1` can not
+/// be rewritten.
+pub fn expr_after_projection(
+    expr: Arc<dyn PhysicalExpr>,
+    projected_exprs: &[(Arc<dyn PhysicalExpr>, String)],
+) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+    let mut projected = true;
+    let expr = map_columns(expr, &mut |column| {
+        let new_column = projected_exprs.iter().enumerate().find_map(
+            |(index, (projected_expr, alias))| {
+                let projected_column =
+                    projected_expr.as_any().downcast_ref::<Column>()?;
+                (projected_column.name() == column.name())
+                    .then(|| Arc::new(Column::new(alias, index)) as _)
+            },
+        );
+        projected &= new_column.is_some();
+        Ok(new_column)
+    })?;
+    Ok(projected.then_some(expr))
+}
+
+/// Rewrites `expr` into a canonical form, so that expressions that only
+/// differ by the order of the operands of commutative operators, such as
+/// `a = b` and `b = a` or `a < b` and `b > a`, become equal.
+///
+/// The operands of `AND`, `OR`, `IS [NOT] DISTINCT FROM`, the bitwise
+/// `&`, `|` and `^` and the comparison operators are ordered by their
+/// display, flipping the comparison operators when they are swapped.
+///
+/// Unlike [`EquivalenceProperties::normalize_expr`], this does not replace
+/// columns by the representatives of their equivalence classes.
+///
+/// [`EquivalenceProperties::normalize_expr`]: crate::EquivalenceProperties::normalize_expr
+pub fn canonicalize_expr(expr: Arc<dyn PhysicalExpr>) -> Result<Arc<dyn PhysicalExpr>> {
+    expr.transform_up(&|expr| {
+        let Some(binary) = expr.as_any().downcast_ref::<BinaryExpr>() else {
+            return Ok(Transformed::No(expr));
+        };
+        let swapped_op = match binary.op() {
+            Operator::And
+            | Operator::Or
+            | Operator::IsDistinctFrom
+            | Operator::IsNotDistinctFrom
+            | Operator::BitwiseAnd
+            | Operator::BitwiseOr
+            | Operator::BitwiseXor => *binary.op(),
+            op => match op.swap() {
+                Some(swapped_op) => swapped_op,
+                None => return Ok(Transformed::No(expr)),
+            },
+        };
+        if binary.left().to_string() <= binary.right().to_string() {
+            return Ok(Transformed::No(expr));
+        }
+        Ok(Transformed::Yes(Arc::new(BinaryExpr::new(
+            binary.right().clone(),
+            swapped_op,
+            binary.left().clone(),
+        ))))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{binary, col, lit};
+    use crate::utils::collect_columns;
+    use arrow_schema::{DataType, Field, Schema};
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("c", DataType::Int32, false),
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ])
+    }
+
+    #[test]
+    fn rewrite_through_projection() -> Result<()> {
+        let schema = schema();
+        let projected_exprs = vec![
+            (col("a", &schema)?, "x".to_string()),
+            (col("c", &schema)?, "y".to_string()),
+        ];
+
+        let expr = binary(
+            col("c", &schema)?,
+            Operator::Gt,
+            col("a", &schema)?,
+            &schema,
+        )?;
+        let after = expr_after_projection(expr.clone(), &projected_exprs)?.unwrap();
+        assert_eq!(after.to_string(), "y@1 > x@0");
+        let before = expr_before_projection(after, &projected_exprs)?;
+        assert_eq!(before.to_string(), expr.to_string());
+
+        let expr = binary(
+            col("b", &schema)?,
+            Operator::Gt,
+            col("a", &schema)?,
+            &schema,
+        )?;
+        assert!(expr_after_projection(expr, &projected_exprs)?.is_none());
+
+        let missing: Arc<dyn PhysicalExpr> = Arc::new(Column::new("z", 2));
+        assert!(expr_before_projection(missing, &projected_exprs).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn canonicalize() -> Result<()> {
+        let schema = schema();
+        let a = col("a", &schema)?;
+        let b = col("b", &schema)?;
+
+        let lt = binary(a.clone(), Operator::Lt, b.clone(), &schema)?;
+        let gt = binary(b.clone(), Operator::Gt, a.clone(), &schema)?;
+        assert_eq!(canonicalize_expr(gt)?.to_string(), lt.to_string());
+
+        let and = binary(
+            binary(b.clone(), Operator::Eq, a.clone(), &schema)?,
+            Operator::And,
+            binary(lit(1), Operator::Lt, a.clone(), &schema)?,
+            &schema,
+        )?;
+        assert_eq!(canonicalize_expr(and)?.to_string(), "1 < a@1 AND a@1 = b@2");
+
+        // not commutative
+        let minus = binary(b, Operator::Minus, a, &schema)?;
+        assert_eq!(
+            canonicalize_expr(minus.clone())?.to_string(),
+            minus.to_string()
+        );
+
+        assert_eq!(collect_columns(&lt).len(), 2);
+        Ok(())
+    }
+}
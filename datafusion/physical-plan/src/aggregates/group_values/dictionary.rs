@@ -0,0 +1,178 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::aggregates::group_values::{new_group_values, GroupValues};
+use arrow::compute::take;
+use arrow::record_batch::RecordBatch;
+use arrow_array::cast::AsArray;
+use arrow_array::types::ArrowDictionaryKeyType;
+use arrow_array::{
+    new_null_array, Array, ArrayRef, DictionaryArray, PrimitiveArray, UInt32Array,
+};
+use arrow_buffer::ArrowNativeType;
+use arrow_schema::{DataType, Field, Schema};
+use datafusion_common::{exec_err, DataFusionError, Result};
+use datafusion_execution::memory_pool::proxy::VecAllocExt;
+use datafusion_physical_expr::EmitTo;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Marks the dictionary values not referenced by the current batch
+const UNREFERENCED: usize = usize::MAX;
+
+/// A [`GroupValues`] storing a single dictionary encoded column
+///
+/// Rather than converting every row of the input to its dictionary value,
+/// only the distinct dictionary values referenced by each batch are interned,
+/// and the rows are mapped to their groups through their dictionary keys.
+/// This is significantly faster than grouping on the values when the
+/// dictionaries are small compared to the batches, as for low cardinality
+/// string columns.
+pub struct GroupValuesDictionary<K: ArrowDictionaryKeyType> {
+    /// The data type of the output array
+    data_type: DataType,
+    /// Stores the group values, as the dictionary values
+    values: Box<dyn GroupValues>,
+    /// The group index of each value of the dictionary of the current
+    /// batch, or [`UNREFERENCED`]
+    value_groups: Vec<usize>,
+    /// The indices of the dictionary values referenced by the current batch,
+    /// in the order they first appear
+    referenced: Vec<u32>,
+    /// The group indices of the referenced dictionary values
+    referenced_groups: Vec<usize>,
+    phantom: PhantomData<K>,
+}
+
+impl<K: ArrowDictionaryKeyType> GroupValuesDictionary<K> {
+    pub fn try_new(data_type: DataType) -> Result<Self> {
+        let DataType::Dictionary(_, value_type) = &data_type else {
+            return exec_err!(
+                "Expected a dictionary type for the group values, got {data_type}"
+            );
+        };
+        let value_schema =
+            Schema::new(vec![Field::new("value", value_type.as_ref().clone(), true)]);
+        Ok(Self {
+            values: new_group_values(Arc::new(value_schema))?,
+            data_type,
+            value_groups: vec![],
+            referenced: vec![],
+            referenced_groups: vec![],
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<K: ArrowDictionaryKeyType> GroupValues for GroupValuesDictionary<K> {
+    fn intern(&mut self, cols: &[ArrayRef], groups: &mut Vec<usize>) -> Result<()> {
+        assert_eq!(cols.len(), 1);
+        let array = cols[0].as_dictionary::<K>();
+        let keys = array.keys();
+        let dictionary = array.values();
+
+        // Find the dictionary values referenced by the batch, as unreferenced
+        // values must not create groups
+        self.value_groups.clear();
+        self.value_groups.resize(dictionary.len(), UNREFERENCED);
+        self.referenced.clear();
+        let mut has_null_keys = false;
+        for (row, key) in keys.values().iter().enumerate() {
+            if keys.is_null(row) {
+                has_null_keys = true;
+                continue;
+            }
+            let key = key.as_usize();
+            if self.value_groups[key] == UNREFERENCED {
+                self.value_groups[key] = 0;
+                self.referenced.push(key as u32);
+            }
+        }
+
+        // Intern the referenced values only, and remember their groups
+        let indices = UInt32Array::from(self.referenced.clone());
+        let referenced_values = take(dictionary.as_ref(), &indices, None)?;
+        self.values
+            .intern(&[referenced_values], &mut self.referenced_groups)?;
+        for (key, group) in self.referenced.iter().zip(&self.referenced_groups) {
+            self.value_groups[*key as usize] = *group;
+        }
+
+        let null_group = if has_null_keys {
+            let null_value = new_null_array(dictionary.data_type(), 1);
+            self.values
+                .intern(&[null_value], &mut self.referenced_groups)?;
+            Some(self.referenced_groups[0])
+        } else {
+            None
+        };
+
+        groups.clear();
+        groups.extend(keys.iter().map(|key| match key {
+            Some(key) => self.value_groups[key.as_usize()],
+            None => null_group.unwrap(),
+        }));
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        self.values.size()
+            + self.value_groups.allocated_size()
+            + self.referenced.allocated_size()
+            + self.referenced_groups.allocated_size()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn emit(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let mut output = self.values.emit(emit_to)?;
+        assert_eq!(output.len(), 1);
+        let values = output.pop().unwrap();
+
+        // Each group value is emitted once, so the keys are the group indices
+        let keys = (0..values.len())
+            .map(|group| {
+                K::Native::from_usize(group).ok_or_else(|| {
+                    DataFusionError::Execution(format!(
+                        "Too many groups for dictionary type {}",
+                        self.data_type
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let keys = PrimitiveArray::<K>::new(keys.into(), values.nulls().cloned());
+        let array = DictionaryArray::<K>::try_new(keys, values)?;
+        Ok(vec![Arc::new(array)])
+    }
+
+    fn clear_shrink(&mut self, batch: &RecordBatch) {
+        let count = batch.num_rows();
+        self.values.clear_shrink(batch);
+        self.value_groups.clear();
+        self.value_groups.shrink_to(count);
+        self.referenced.clear();
+        self.referenced.shrink_to(count);
+        self.referenced_groups.clear();
+        self.referenced_groups.shrink_to(count);
+    }
+}
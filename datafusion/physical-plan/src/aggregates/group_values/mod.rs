@@ -16,14 +16,21 @@
 // under the License.
 
 use arrow::record_batch::RecordBatch;
+use arrow_array::types::{
+    Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type, UInt64Type,
+    UInt8Type,
+};
 use arrow_array::{downcast_primitive, ArrayRef};
-use arrow_schema::SchemaRef;
+use arrow_schema::{DataType, SchemaRef};
 use datafusion_common::Result;
 use datafusion_physical_expr::EmitTo;
 
 pub(crate) mod primitive;
 use primitive::GroupValuesPrimitive;
 
+mod dictionary;
+use dictionary::GroupValuesDictionary;
+
 mod row;
 use row::GroupValuesRows;
 
@@ -62,6 +69,26 @@ pub fn new_group_values(schema: SchemaRef) -> Result<Box<dyn GroupValues>> {
             d => (downcast_helper, d),
             _ => {}
         }
+
+        if let DataType::Dictionary(key_type, _) = d {
+            macro_rules! dictionary_helper {
+                ($t:ty) => {
+                    return Ok(Box::new(GroupValuesDictionary::<$t>::try_new(d.clone())?))
+                };
+            }
+
+            match key_type.as_ref() {
+                DataType::Int8 => dictionary_helper!(Int8Type),
+                DataType::Int16 => dictionary_helper!(Int16Type),
+                DataType::Int32 => dictionary_helper!(Int32Type),
+                DataType::Int64 => dictionary_helper!(Int64Type),
+                DataType::UInt8 => dictionary_helper!(UInt8Type),
+                DataType::UInt16 => dictionary_helper!(UInt16Type),
+                DataType::UInt32 => dictionary_helper!(UInt32Type),
+                DataType::UInt64 => dictionary_helper!(UInt64Type),
+                _ => {}
+            }
+        }
     }
 
     Ok(Box::new(GroupValuesRows::try_new(schema)?))
@@ -1749,6 +1749,143 @@ mod tests {
         check_grouping_sets(input, true).await
     }
 
+    async fn check_streaming_distinct(spill: bool) -> Result<()> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::UInt32, false)]));
+        let batches = [vec![2, 3, 4, 4], vec![2, 3, 3, 4], vec![1, 5, 2, 1]]
+            .into_iter()
+            .map(|values| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(UInt32Array::from(values))],
+                )
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let input = Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+
+        let task_ctx = if spill {
+            new_spill_ctx(2, 1500)
+        } else {
+            Arc::new(TaskContext::default())
+        };
+
+        // `DISTINCT a` is a final aggregation without aggregate expressions
+        let distinct = Arc::new(AggregateExec::try_new(
+            AggregateMode::Final,
+            PhysicalGroupBy::new_single(vec![(col("a", &schema)?, "a".to_string())]),
+            vec![],
+            vec![],
+            input,
+            schema,
+        )?);
+
+        let result = common::collect(distinct.execute(0, task_ctx)?).await?;
+        if !spill {
+            // the groups of the first batch are emitted before reading the rest
+            assert_eq!(result[0].num_rows(), 3);
+        }
+
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "| 4 |", "| 5 |",
+            "+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &result);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn streaming_distinct() -> Result<()> {
+        check_streaming_distinct(false).await
+    }
+
+    #[tokio::test]
+    async fn streaming_distinct_with_spill() -> Result<()> {
+        check_streaming_distinct(true).await
+    }
+
+    #[tokio::test]
+    async fn spill_partially_sorted_groups() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::UInt32, false),
+        ]));
+        // sorted on `a` only
+        let batches = [
+            (vec![1, 1, 1, 1], vec![4, 3, 2, 1]),
+            (vec![1, 2, 2, 2], vec![4, 3, 2, 1]),
+            (vec![2, 2, 3, 3], vec![3, 2, 2, 1]),
+        ]
+        .into_iter()
+        .map(|(a, b)| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(UInt32Array::from(a)),
+                    Arc::new(UInt32Array::from(b)),
+                ],
+            )
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+        let input = Arc::new(
+            MemoryExec::try_new(&[batches], schema.clone(), None)?.with_sort_information(
+                vec![vec![PhysicalSortExpr {
+                    expr: col("a", &schema)?,
+                    options: SortOptions::default(),
+                }]],
+            ),
+        );
+
+        let aggregate = Arc::new(AggregateExec::try_new(
+            AggregateMode::Single,
+            PhysicalGroupBy::new_single(vec![
+                (col("a", &schema)?, "a".to_string()),
+                (col("b", &schema)?, "b".to_string()),
+            ]),
+            vec![Arc::new(Count::new(
+                col("b", &schema)?,
+                "COUNT(b)".to_string(),
+                DataType::Int64,
+            ))],
+            vec![None],
+            input,
+            schema,
+        )?);
+        assert_eq!(
+            aggregate.input_order_mode,
+            InputOrderMode::PartiallySorted(vec![0])
+        );
+
+        let result =
+            common::collect(aggregate.execute(0, new_spill_ctx(2, 1500))?).await?;
+        let expected = vec![
+            "+---+---+----------+",
+            "| a | b | COUNT(b) |",
+            "+---+---+----------+",
+            "| 1 | 1 | 1        |",
+            "| 1 | 2 | 1        |",
+            "| 1 | 3 | 1        |",
+            "| 1 | 4 | 2        |",
+            "| 2 | 1 | 1        |",
+            "| 2 | 2 | 2        |",
+            "| 2 | 3 | 2        |",
+            "| 3 | 1 | 1        |",
+            "| 3 | 2 | 1        |",
+            "+---+---+----------+",
+        ];
+        assert_batches_sorted_eq!(expected, &result);
+
+        // the output is still sorted on `a`
+        let output = concat_batches(&result[0].schema(), &result)?;
+        let a = output
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+        assert!(a.values().windows(2).all(|w| w[0] <= w[1]));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_oom() -> Result<()> {
         let input: Arc<dyn ExecutionPlan> =
@@ -26,7 +26,7 @@ use crate::{RecordBatchStream, SendableRecordBatchStream};
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::Result;
-use datafusion_execution::TaskContext;
+use datafusion_execution::{CancellationToken, TaskContext};
 use datafusion_physical_expr::PhysicalExpr;
 use futures::stream::BoxStream;
 use std::borrow::Cow;
@@ -61,6 +61,7 @@ struct AggregateStreamInner {
     filter_expressions: Vec<Option<Arc<dyn PhysicalExpr>>>,
     accumulators: Vec<AccumulatorItem>,
     reservation: MemoryReservation,
+    cancellation: CancellationToken,
     finished: bool,
 }
 
@@ -100,6 +101,7 @@ impl AggregateStream {
             filter_expressions,
             accumulators,
             reservation,
+            cancellation: context.cancellation_token().clone(),
             finished: false,
         };
         let stream = futures::stream::unfold(inner, |mut this| async move {
@@ -113,13 +115,15 @@ impl AggregateStream {
                 let result = match this.input.next().await {
                     Some(Ok(batch)) => {
                         let timer = elapsed_compute.timer();
-                        let result = aggregate_batch(
-                            &this.mode,
-                            batch,
-                            &mut this.accumulators,
-                            &this.aggregate_expressions,
-                            &this.filter_expressions,
-                        );
+                        let result = this.cancellation.check().and_then(|_| {
+                            aggregate_batch(
+                                &this.mode,
+                                batch,
+                                &mut this.accumulators,
+                                &this.aggregate_expressions,
+                                &this.filter_expressions,
+                            )
+                        });
 
                         timer.done();
 
@@ -27,7 +27,7 @@ use crate::aggregates::{
     evaluate_group_by, evaluate_many, evaluate_optional, group_schema, AggregateMode,
     PhysicalGroupBy,
 };
-use crate::common::IPCWriter;
+use crate::common::{normalize_float_keys, IPCWriter};
 use crate::metrics::{BaselineMetrics, RecordOutput};
 use crate::sorts::sort::{read_spill_as_stream, sort_batch};
 use crate::sorts::streaming_merge;
@@ -37,13 +37,13 @@ use crate::{RecordBatchStream, SendableRecordBatchStream};
 
 use arrow::array::*;
 use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
-use arrow_schema::SortOptions;
+use arrow_schema::{DataType, Field, Schema, SortOptions};
 use datafusion_common::{DataFusionError, Result};
 use datafusion_execution::disk_manager::RefCountedTempFile;
 use datafusion_execution::memory_pool::proxy::VecAllocExt;
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_execution::runtime_env::RuntimeEnv;
-use datafusion_execution::TaskContext;
+use datafusion_execution::{CancellationToken, TaskContext};
 use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::{
     AggregateExpr, EmitTo, GroupsAccumulator, GroupsAccumulatorAdapter, PhysicalSortExpr,
@@ -86,6 +86,14 @@ struct SpillState {
 
     /// GROUP BY expressions for merging spilled data
     merging_group_by: PhysicalGroupBy,
+
+    /// If a streaming `DISTINCT` had to spill, the location of the
+    /// spill file holding the groups that were already emitted
+    emitted_spill: Option<RefCountedTempFile>,
+
+    /// true when the merged spilled data carries the marker column
+    /// of groups that were already emitted
+    merging_emitted: bool,
 }
 
 /// HashTable based Grouping Aggregator
@@ -163,6 +171,8 @@ struct SpillState {
 /// later stream-merge sort on reading back the spilled data does re-grouping. Note the rows cannot
 /// be grouped once spilled onto disk, the read back data needs to be re-grouped again. In addition,
 /// re-grouping may cause out of memory again. Thus, re-grouping has to be a sort based aggregation.
+/// When the input is partially sorted on the group keys, spilled groups are sorted on that order
+/// first, so that the merged groups are output in the same order as without spilling.
 ///
 /// ```text
 /// Partial Aggregation [batch_size = 2] (max memory = 3 rows)
@@ -254,6 +264,10 @@ pub(crate) struct GroupedHashAggregateStream {
     /// max rows in output RecordBatches
     batch_size: usize,
 
+    /// Should NaNs and signed zeros in the grouping keys form a single
+    /// group each (see [`normalize_float_keys`])
+    normalize_float_keys: bool,
+
     /// Optional ordering information, that might allow groups to be
     /// emitted from the hash table prior to seeing the end of the
     /// input
@@ -273,6 +287,21 @@ pub(crate) struct GroupedHashAggregateStream {
     /// the `GroupedHashAggregateStream` operation immediately switches to
     /// output mode and emits all groups.
     group_values_soft_limit: Option<usize>,
+
+    /// true when this is the final stage of a `DISTINCT` (grouping without
+    /// aggregates) and every group is emitted as soon as it is first seen.
+    /// The groups stay in [`Self::group_values`] to discard later duplicates.
+    streaming_distinct: bool,
+
+    /// The groups first seen in the last input batch, not yet emitted
+    new_distinct_groups: Option<RecordBatch>,
+
+    /// When merging the spills of a streaming `DISTINCT`, whether each
+    /// group was already emitted before spilling
+    emitted_groups: Vec<bool>,
+
+    /// Stops the aggregation when the query is cancelled
+    cancellation: CancellationToken,
 }
 
 impl GroupedHashAggregateStream {
@@ -288,6 +317,11 @@ impl GroupedHashAggregateStream {
         let agg_filter_expr = agg.filter_expr.clone();
 
         let batch_size = context.session_config().batch_size();
+        let normalize_float_keys = context
+            .session_config()
+            .options()
+            .execution
+            .normalize_float_keys;
         let input = agg.input.execute(partition, Arc::clone(&context))?;
         let baseline_metrics = BaselineMetrics::new(&agg.metrics, partition);
 
@@ -325,21 +359,11 @@ impl GroupedHashAggregateStream {
             .collect::<Result<_>>()?;
 
         let group_schema = group_schema(&agg_schema, agg_group_by.expr.len());
-        let spill_expr = group_schema
-            .fields
-            .into_iter()
-            .enumerate()
-            .map(|(idx, field)| PhysicalSortExpr {
-                expr: Arc::new(Column::new(field.name().as_str(), idx)) as _,
-                options: SortOptions::default(),
-            })
-            .collect();
-
         let name = format!("GroupedHashAggregateStream[{partition}]");
         let reservation = MemoryConsumer::new(name)
             .with_can_spill(true)
             .register(context.memory_pool());
-        let (ordering, _) = agg
+        let (ordering, ordered_indices) = agg
             .equivalence_properties()
             .find_longest_permutation(&agg_group_by.output_exprs());
         let group_ordering = GroupOrdering::try_new(
@@ -348,6 +372,32 @@ impl GroupedHashAggregateStream {
             ordering.as_slice(),
         )?;
 
+        // Spilled groups are sorted on the output ordering first, so that the
+        // merged groups are output in that order when the input is partially
+        // sorted
+        let spill_expr = ordering
+            .iter()
+            .cloned()
+            .chain(
+                group_schema
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| !ordered_indices.contains(idx))
+                    .map(|(idx, field)| PhysicalSortExpr {
+                        expr: Arc::new(Column::new(field.name().as_str(), idx)) as _,
+                        options: SortOptions::default(),
+                    }),
+            )
+            .collect();
+
+        // Without aggregates, the final value of a group is its key, which
+        // can be emitted right away
+        let streaming_distinct = agg.aggr_expr.is_empty()
+            && agg_group_by.is_single()
+            && !matches!(agg.mode, AggregateMode::Partial)
+            && matches!(group_ordering, GroupOrdering::None);
+
         let group_values = new_group_values(group_schema)?;
         timer.done();
 
@@ -360,6 +410,8 @@ impl GroupedHashAggregateStream {
             is_stream_merging: false,
             merging_aggregate_arguments,
             merging_group_by: PhysicalGroupBy::new_single(agg_group_by.expr.clone()),
+            emitted_spill: None,
+            merging_emitted: false,
         };
 
         Ok(GroupedHashAggregateStream {
@@ -376,11 +428,16 @@ impl GroupedHashAggregateStream {
             exec_state,
             baseline_metrics,
             batch_size,
+            normalize_float_keys,
             group_ordering,
             input_done: false,
             runtime: context.runtime_env(),
             spill_state,
             group_values_soft_limit: agg.limit,
+            streaming_distinct,
+            new_distinct_groups: None,
+            emitted_groups: vec![],
+            cancellation: context.cancellation_token().clone(),
         })
     }
 }
@@ -430,6 +487,7 @@ impl Stream for GroupedHashAggregateStream {
                     match ready!(self.input.poll_next_unpin(cx)) {
                         // new batch to aggregate
                         Some(Ok(batch)) => {
+                            extract_ok!(self.cancellation.check());
                             let timer = elapsed_compute.timer();
                             // Make sure we have enough capacity for `batch`, otherwise spill
                             extract_ok!(self.spill_previous_if_necessary(&batch));
@@ -458,6 +516,13 @@ impl Stream for GroupedHashAggregateStream {
                                 break 'reading_input;
                             }
 
+                            if let Some(batch) = self.new_distinct_groups.take() {
+                                self.exec_state = ExecutionState::ProducingOutput(batch);
+                                timer.done();
+                                // make sure the exec_state just set is not overwritten below
+                                break 'reading_input;
+                            }
+
                             extract_ok!(self.emit_early_if_necessary());
 
                             timer.done();
@@ -526,6 +591,14 @@ impl GroupedHashAggregateStream {
         } else {
             evaluate_group_by(&self.group_by, &batch)?
         };
+        let group_by_values = if self.normalize_float_keys {
+            group_by_values
+                .into_iter()
+                .map(|values| values.into_iter().map(normalize_float_keys).collect())
+                .collect()
+        } else {
+            group_by_values
+        };
 
         // Evaluate the aggregation expressions.
         let input_values = if self.spill_state.is_stream_merging {
@@ -559,6 +632,38 @@ impl GroupedHashAggregateStream {
                 )?;
             }
 
+            if self.streaming_distinct && total_num_groups > starting_num_groups {
+                // group indexes are assigned in the order the groups are
+                // first seen, so these rows are the first of each new group
+                let mut next_group = starting_num_groups;
+                let first_rows: UInt32Array = group_indices
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(row, &group)| {
+                        (group == next_group).then(|| {
+                            next_group += 1;
+                            row as u32
+                        })
+                    })
+                    .collect();
+                let columns = group_values
+                    .iter()
+                    .map(|array| arrow::compute::take(array, &first_rows, None))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.new_distinct_groups =
+                    Some(RecordBatch::try_new(self.schema.clone(), columns)?);
+            }
+
+            if self.spill_state.merging_emitted {
+                let emitted = batch.column(batch.num_columns() - 1).as_boolean();
+                self.emitted_groups.resize(total_num_groups, false);
+                for (row, &group) in group_indices.iter().enumerate() {
+                    if emitted.value(row) {
+                        self.emitted_groups[group] = true;
+                    }
+                }
+            }
+
             // Gather the inputs to call the actual accumulator
             let t = self
                 .accumulators
@@ -615,7 +720,8 @@ impl GroupedHashAggregateStream {
         self.reservation.try_resize(
             acc + self.group_values.size()
                 + self.group_ordering.size()
-                + self.current_group_indices.allocated_size(),
+                + self.current_group_indices.allocated_size()
+                + self.emitted_groups.allocated_size(),
         )
     }
 
@@ -636,6 +742,15 @@ impl GroupedHashAggregateStream {
             self.group_ordering.remove_groups(n);
         }
 
+        if self.streaming_distinct && !spilling {
+            // every group was already emitted when it was first seen
+            let _ = self.update_memory_reservation();
+            return Ok(self
+                .new_distinct_groups
+                .take()
+                .unwrap_or_else(|| RecordBatch::new_empty(schema)));
+        }
+
         // Next output each aggregate value
         for acc in self.accumulators.iter_mut() {
             match self.mode {
@@ -652,6 +767,16 @@ impl GroupedHashAggregateStream {
             }
         }
 
+        if self.spill_state.merging_emitted && !spilling {
+            // skip the groups a streaming `DISTINCT` emitted before spilling
+            let emitted = emit_to.take_needed(&mut self.emitted_groups);
+            let predicate: BooleanArray = emitted.into_iter().map(|e| Some(!e)).collect();
+            output = output
+                .iter()
+                .map(|array| arrow::compute::filter(array, &predicate))
+                .collect::<Result<_, _>>()?;
+        }
+
         // emit reduces the memory usage. Ignore Err from update_memory_reservation. Even if it is
         // over the target memory size after emission, we can emit again rather than returning Err.
         let _ = self.update_memory_reservation();
@@ -663,10 +788,12 @@ impl GroupedHashAggregateStream {
     /// (~ 1 [`RecordBatch`]) for simplicity. In such cases, spill the data to disk and clear the
     /// memory. Currently only [`GroupOrdering::None`] is supported for spilling.
     fn spill_previous_if_necessary(&mut self, batch: &RecordBatch) -> Result<()> {
-        // TODO: support group_ordering for spilling
         if self.group_values.len() > 0
             && batch.num_rows() > 0
-            && matches!(self.group_ordering, GroupOrdering::None)
+            && matches!(
+                self.group_ordering,
+                GroupOrdering::None | GroupOrdering::Partial(_)
+            )
             && !matches!(self.mode, AggregateMode::Partial)
             && !self.spill_state.is_stream_merging
             && self.update_memory_reservation().is_err()
@@ -676,6 +803,13 @@ impl GroupedHashAggregateStream {
             self.spill_state.spill_schema = batch.schema();
             self.spill()?;
             self.clear_shrink(batch);
+            // The groups of the current sort key prefix may be both spilled
+            // and in the remaining input, so the groups are no longer
+            // emitted as their prefix ends, but when merging the spills.
+            // The groups emitted before never appear again.
+            if let GroupOrdering::Partial(_) = self.group_ordering {
+                self.group_ordering = GroupOrdering::None;
+            }
         }
         Ok(())
     }
@@ -698,7 +832,14 @@ impl GroupedHashAggregateStream {
         }
 
         writer.finish()?;
-        self.spill_state.spills.push(spillfile);
+        if self.streaming_distinct {
+            // the dropped groups can no longer be deduplicated in memory, so
+            // stop streaming and filter the emitted groups out when merging
+            self.streaming_distinct = false;
+            self.spill_state.emitted_spill = Some(spillfile);
+        } else {
+            self.spill_state.spills.push(spillfile);
+        }
         Ok(())
     }
 
@@ -753,6 +894,21 @@ impl GroupedHashAggregateStream {
             let stream = read_spill_as_stream(spill, schema.clone())?;
             streams.push(stream);
         }
+        let schema = if let Some(spill) = self.spill_state.emitted_spill.take() {
+            let emitted_stream = read_spill_as_stream(spill, schema.clone())?;
+            let mut fields = schema.fields().to_vec();
+            fields.push(Arc::new(Field::new("__emitted", DataType::Boolean, false)));
+            let schema = Arc::new(Schema::new(fields));
+            streams = streams
+                .into_iter()
+                .map(|stream| with_emitted_marker(stream, &schema, false))
+                .collect();
+            streams.push(with_emitted_marker(emitted_stream, &schema, true));
+            self.spill_state.merging_emitted = true;
+            schema
+        } else {
+            schema
+        };
         self.spill_state.is_stream_merging = true;
         self.input = streaming_merge(
             streams,
@@ -783,7 +939,9 @@ impl GroupedHashAggregateStream {
         self.group_ordering.input_done();
         let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
         let timer = elapsed_compute.timer();
-        self.exec_state = if self.spill_state.spills.is_empty() {
+        self.exec_state = if self.spill_state.spills.is_empty()
+            && self.spill_state.emitted_spill.is_none()
+        {
             let batch = self.emit(EmitTo::All, false)?;
             ExecutionState::ProducingOutput(batch)
         } else {
@@ -795,3 +953,25 @@ impl GroupedHashAggregateStream {
         Ok(())
     }
 }
+
+/// Appends a column to every batch of `input` flagging whether its rows were
+/// already emitted by a streaming `DISTINCT`
+fn with_emitted_marker(
+    input: SendableRecordBatchStream,
+    schema: &SchemaRef,
+    emitted: bool,
+) -> SendableRecordBatchStream {
+    let schema = schema.clone();
+    Box::pin(RecordBatchStreamAdapter::new(
+        schema.clone(),
+        input.map(move |batch| -> Result<RecordBatch> {
+            let batch = batch?;
+            let mut columns = batch.columns().to_vec();
+            columns.push(Arc::new(BooleanArray::from(vec![
+                emitted;
+                batch.num_rows()
+            ])));
+            Ok(RecordBatch::try_new(schema.clone(), columns)?)
+        }),
+    ))
+}
@@ -17,6 +17,8 @@
 
 //! Defines the ANALYZE operator
 
+use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 use std::{any::Any, time::Instant};
 
@@ -24,12 +26,18 @@ use super::expressions::PhysicalSortExpr;
 use super::stream::{RecordBatchReceiverStream, RecordBatchStreamAdapter};
 use super::{DisplayAs, Distribution, SendableRecordBatchStream};
 
+use crate::coalesce_partitions::CoalescePartitionsExec;
 use crate::display::DisplayableExecutionPlan;
+use crate::metrics::{MetricValue, MetricsSet};
+use crate::repartition::RepartitionExec;
+use crate::sorts::sort_preserving_merge::SortPreservingMergeExec;
 use crate::{DisplayFormatType, ExecutionPlan, Partitioning};
 
 use arrow::{array::StringBuilder, datatypes::SchemaRef, record_batch::RecordBatch};
+use chrono::{DateTime, Utc};
 use datafusion_common::{internal_err, DataFusionError, Result};
 use datafusion_execution::TaskContext;
+use datafusion_expr::AnalyzeFormat;
 
 use futures::StreamExt;
 
@@ -41,6 +49,8 @@ pub struct AnalyzeExec {
     verbose: bool,
     /// if statistics should be displayed
     show_statistics: bool,
+    /// how the plan with metrics is printed
+    format: AnalyzeFormat,
     /// The input plan (the plan being analyzed)
     pub(crate) input: Arc<dyn ExecutionPlan>,
     /// The output schema for RecordBatches of this exec node
@@ -58,11 +68,18 @@ impl AnalyzeExec {
         AnalyzeExec {
             verbose,
             show_statistics,
+            format: AnalyzeFormat::Text,
             input,
             schema,
         }
     }
 
+    /// Set how the plan with metrics is printed
+    pub fn with_format(mut self, format: AnalyzeFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     /// access to verbose
     pub fn verbose(&self) -> bool {
         self.verbose
@@ -73,6 +90,11 @@ impl AnalyzeExec {
         self.show_statistics
     }
 
+    /// access to format
+    pub fn format(&self) -> AnalyzeFormat {
+        self.format
+    }
+
     /// The input plan
     pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
         &self.input
@@ -87,7 +109,11 @@ impl DisplayAs for AnalyzeExec {
     ) -> std::fmt::Result {
         match t {
             DisplayFormatType::Default | DisplayFormatType::Verbose => {
-                write!(f, "AnalyzeExec verbose={}", self.verbose)
+                write!(f, "AnalyzeExec verbose={}", self.verbose)?;
+                if self.format != AnalyzeFormat::Text {
+                    write!(f, ", format={}", self.format)?;
+                }
+                Ok(())
             }
         }
     }
@@ -136,12 +162,15 @@ impl ExecutionPlan for AnalyzeExec {
         self: Arc<Self>,
         mut children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(Self::new(
-            self.verbose,
-            self.show_statistics,
-            children.pop().unwrap(),
-            self.schema.clone(),
-        )))
+        Ok(Arc::new(
+            Self::new(
+                self.verbose,
+                self.show_statistics,
+                children.pop().unwrap(),
+                self.schema.clone(),
+            )
+            .with_format(self.format),
+        ))
     }
 
     fn execute(
@@ -172,6 +201,7 @@ impl ExecutionPlan for AnalyzeExec {
         let captured_schema = self.schema.clone();
         let verbose = self.verbose;
         let show_statistics = self.show_statistics;
+        let format = self.format;
 
         // future that gathers the results from all the tasks in the
         // JoinSet that computes the overall row count and final
@@ -184,6 +214,14 @@ impl ExecutionPlan for AnalyzeExec {
             }
 
             let duration = Instant::now() - start;
+            if format == AnalyzeFormat::Json {
+                return create_json_output_batch(
+                    total_rows,
+                    duration,
+                    captured_input,
+                    captured_schema,
+                );
+            }
             create_output_batch(
                 verbose,
                 show_statistics,
@@ -222,6 +260,9 @@ fn create_output_batch(
         .to_string();
     plan_builder.append_value(annotated_plan);
 
+    type_builder.append_value("Summary");
+    plan_builder.append_value(PlanSummary::new(input.as_ref()).to_string());
+
     // Verbose output
     // TODO make this more sophisticated
     if verbose {
@@ -250,16 +291,342 @@ fn create_output_batch(
     .map_err(DataFusionError::from)
 }
 
+/// Creates the output of AnalyzeExec in [`AnalyzeFormat::Json`], a single
+/// "Plan with Metrics" row holding a JSON document of the form
+///
+/// ```text
+/// {
+///   "output_rows": 3,
+///   "duration_ns": 1234,
+///   "summary": { "bytes_scanned": 0, "peak_mem_used": 0, ... },
+///   "plan": {
+///     "operator": "ProjectionExec",
+///     "description": "ProjectionExec: expr=[a@0 as a]",
+///     "output_rows": 3,
+///     "elapsed_compute_ns": 100,
+///     ...
+///     "metrics": { "output_rows": 3, ... },
+///     "partition_metrics": {
+///       "output_rows": { "partitions": 2, "min": 1, "median": 1, "max": 2 },
+///       ...
+///     },
+///     "children": [ ... ]
+///   }
+/// }
+/// ```
+fn create_json_output_batch(
+    total_rows: usize,
+    duration: std::time::Duration,
+    input: Arc<dyn ExecutionPlan>,
+    schema: SchemaRef,
+) -> Result<RecordBatch> {
+    let summary = PlanSummary::new(input.as_ref());
+    let mut json = String::new();
+    json.push_str(&format!(
+        "{{\"output_rows\":{total_rows},\"duration_ns\":{},",
+        duration.as_nanos()
+    ));
+    json.push_str(&format!(
+        "\"summary\":{{\"bytes_scanned\":{},\"peak_mem_used\":{},\"spill_count\":{},\"spilled_bytes\":{}}},",
+        summary.bytes_scanned,
+        summary.peak_mem_used,
+        summary.spill_count,
+        summary.spilled_bytes
+    ));
+    json.push_str("\"plan\":");
+    write_json_operator(input.as_ref(), &mut json);
+    json.push('}');
+
+    let mut type_builder = StringBuilder::with_capacity(1, 32);
+    let mut plan_builder = StringBuilder::with_capacity(1, json.len());
+    type_builder.append_value("Plan with Metrics");
+    plan_builder.append_value(json);
+
+    RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(type_builder.finish()),
+            Arc::new(plan_builder.finish()),
+        ],
+    )
+    .map_err(DataFusionError::from)
+}
+
+/// Appends the JSON object describing `plan` and its children to `json`
+fn write_json_operator(plan: &dyn ExecutionPlan, json: &mut String) {
+    let description = DisplayableExecutionPlan::new(plan).one_line().to_string();
+    let operator = operator_name(&description);
+    json.push_str(&format!(
+        "{{\"operator\":{},\"description\":{}",
+        json_string(operator),
+        json_string(description.trim_end())
+    ));
+
+    let partition_metrics = plan.metrics().unwrap_or_default();
+    let metrics = partition_metrics.aggregate_by_name().sorted_for_display();
+    let mut summary = PlanSummary {
+        stages: vec![StageSummary::default()],
+        ..Default::default()
+    };
+    summary.record(&metrics, 0);
+    json.push_str(&format!(
+        ",\"output_rows\":{},\"elapsed_compute_ns\":{},\"bytes_scanned\":{},\"spill_count\":{},\"spilled_bytes\":{},\"peak_mem_used\":{}",
+        metrics.output_rows().unwrap_or_default(),
+        summary.stages[0].elapsed_compute,
+        summary.bytes_scanned,
+        summary.spill_count,
+        summary.spilled_bytes,
+        summary.peak_mem_used
+    ));
+
+    let metrics = metrics
+        .iter()
+        .map(|m| format!("{}:{}", json_string(m.value().name()), m.value().as_usize()))
+        .collect::<Vec<_>>();
+    json.push_str(&format!(",\"metrics\":{{{}}}", metrics.join(",")));
+
+    let rollup = partition_metrics
+        .partition_rollup()
+        .iter()
+        .map(|r| {
+            format!(
+                "{}:{{\"partitions\":{},\"min\":{},\"median\":{},\"max\":{}}}",
+                json_string(r.name()),
+                r.partitions(),
+                r.min().as_usize(),
+                r.median().as_usize(),
+                r.max().as_usize()
+            )
+        })
+        .collect::<Vec<_>>();
+    json.push_str(&format!(",\"partition_metrics\":{{{}}}", rollup.join(",")));
+
+    json.push_str(",\"children\":[");
+    for (i, child) in plan.children().iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_json_operator(child.as_ref(), json);
+    }
+    json.push_str("]}");
+}
+
+/// Quotes and escapes `s` as a JSON string
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The name of the operator displayed as `description`, e.g.
+/// `ProjectionExec` for `ProjectionExec: expr=[a@0 as a]`
+pub(crate) fn operator_name(description: &str) -> &str {
+    description
+        .split(|c: char| c == ':' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+}
+
+/// Metrics of a single pipeline stage of an analyzed plan.
+///
+/// Stages are delimited by exchange operators ([`RepartitionExec`],
+/// [`CoalescePartitionsExec`] and [`SortPreservingMergeExec`]): the exchange
+/// belongs to the stage consuming its output and each of its inputs starts
+/// a new stage.
+#[derive(Debug, Default)]
+struct StageSummary {
+    /// Names of the operators in this stage, in pre-order
+    operators: Vec<String>,
+    /// Total CPU time spent by the operators in this stage
+    elapsed_compute: usize,
+    /// Earliest start time of any operator in this stage
+    start: Option<DateTime<Utc>>,
+    /// Latest end time of any operator in this stage
+    end: Option<DateTime<Utc>>,
+}
+
+impl StageSummary {
+    /// Wall clock time between the first operator starting and the last
+    /// operator finishing, if known
+    fn wall_clock(&self) -> Option<std::time::Duration> {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => (end - start).to_std().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Summary of the metrics of all operators of an analyzed plan, displayed
+/// as the footer of `EXPLAIN ANALYZE`
+#[derive(Debug, Default)]
+struct PlanSummary {
+    /// Total bytes read by the scans of the plan
+    bytes_scanned: usize,
+    /// Sum of the peak memory reported by each operator. As operators do not
+    /// necessarily reach their peak at the same time this is an upper bound
+    peak_mem_used: usize,
+    /// Total number of spills
+    spill_count: usize,
+    /// Total number of spilled bytes
+    spilled_bytes: usize,
+    /// Per stage breakdown, stage 0 produces the output of the plan
+    stages: Vec<StageSummary>,
+    /// Time spent evaluating each expression, keyed by its display, when
+    /// `datafusion.execution.expression_metrics` is enabled
+    expression_times: HashMap<String, usize>,
+}
+
+/// Number of most expensive expressions listed in the summary
+const TOP_EXPRESSIONS: usize = 5;
+
+impl PlanSummary {
+    fn new(plan: &dyn ExecutionPlan) -> Self {
+        let mut summary = Self {
+            stages: vec![StageSummary::default()],
+            ..Default::default()
+        };
+        summary.visit(plan, 0);
+        summary
+    }
+
+    fn visit(&mut self, plan: &dyn ExecutionPlan, stage: usize) {
+        let name = DisplayableExecutionPlan::new(plan).one_line().to_string();
+        self.stages[stage]
+            .operators
+            .push(operator_name(&name).to_string());
+
+        if let Some(metrics) = plan.metrics() {
+            self.record(&metrics, stage);
+        }
+
+        let any = plan.as_any();
+        let is_exchange = any.is::<RepartitionExec>()
+            || any.is::<CoalescePartitionsExec>()
+            || any.is::<SortPreservingMergeExec>();
+
+        for child in plan.children() {
+            let child_stage = if is_exchange {
+                self.stages.push(StageSummary::default());
+                self.stages.len() - 1
+            } else {
+                stage
+            };
+            self.visit(child.as_ref(), child_stage);
+        }
+    }
+
+    fn record(&mut self, metrics: &MetricsSet, stage: usize) {
+        let sum_by_name = |name: &str| {
+            metrics
+                .sum_by_name(name)
+                .map(|v| v.as_usize())
+                .unwrap_or_default()
+        };
+
+        self.bytes_scanned += sum_by_name("bytes_scanned");
+        self.spill_count += metrics.spill_count().unwrap_or_default();
+        self.spilled_bytes += metrics.spilled_bytes().unwrap_or_default();
+
+        // operators report either their current or their peak memory usage
+        let mem_used = metrics
+            .sum(|m| matches!(m.value(), MetricValue::CurrentMemoryUsage(_)))
+            .map(|v| v.as_usize())
+            .unwrap_or_default();
+        self.peak_mem_used += mem_used.max(sum_by_name("peak_mem_used"));
+
+        let stage = &mut self.stages[stage];
+        stage.elapsed_compute += metrics.elapsed_compute().unwrap_or_default();
+        for metric in metrics.iter() {
+            match metric.value() {
+                MetricValue::StartTimestamp(ts) => {
+                    if let Some(ts) = ts.value() {
+                        stage.start = Some(stage.start.map_or(ts, |s| s.min(ts)));
+                    }
+                }
+                MetricValue::EndTimestamp(ts) => {
+                    if let Some(ts) = ts.value() {
+                        stage.end = Some(stage.end.map_or(ts, |e| e.max(ts)));
+                    }
+                }
+                MetricValue::Time { name, time } if name == "expr_eval_time" => {
+                    if let Some(expr) =
+                        metric.labels().iter().find(|l| l.name() == "expr")
+                    {
+                        *self
+                            .expression_times
+                            .entry(expr.value().to_string())
+                            .or_default() += time.value();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl fmt::Display for PlanSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "bytes_scanned={}, peak_mem_used={}, spill_count={}, spilled_bytes={}",
+            self.bytes_scanned, self.peak_mem_used, self.spill_count, self.spilled_bytes
+        )?;
+        for (i, stage) in self.stages.iter().enumerate() {
+            write!(f, "\nstage {i}: ")?;
+            if let Some(wall_clock) = stage.wall_clock() {
+                write!(f, "elapsed={wall_clock:?}, ")?;
+            }
+            let elapsed_compute =
+                std::time::Duration::from_nanos(stage.elapsed_compute as u64);
+            write!(
+                f,
+                "elapsed_compute={elapsed_compute:?}, operators=[{}]",
+                stage.operators.join(", ")
+            )?;
+        }
+
+        let mut expressions = self.expression_times.iter().collect::<Vec<_>>();
+        expressions.sort_unstable_by(|(a_expr, a), (b_expr, b)| {
+            b.cmp(a).then_with(|| a_expr.cmp(b_expr))
+        });
+        for (expr, time) in expressions.into_iter().take(TOP_EXPRESSIONS) {
+            let elapsed_compute = std::time::Duration::from_nanos(*time as u64);
+            write!(
+                f,
+                "\nexpression: elapsed_compute={elapsed_compute:?}, expr={expr}"
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use arrow::datatypes::{DataType, Field, Schema};
     use futures::FutureExt;
 
+    use datafusion_execution::config::SessionConfig;
+
     use crate::{
         collect,
+        expressions::col,
+        projection::ProjectionExec,
         test::{
             assert_is_pending,
             exec::{assert_strong_count_converges_to_zero, BlockingExec},
+            scan_partitioned,
         },
     };
 
@@ -284,4 +651,99 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_summary_stages() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let coalesce = Arc::new(CoalescePartitionsExec::new(scan_partitioned(2)));
+        collect(coalesce.clone(), task_ctx).await?;
+
+        let summary = PlanSummary::new(coalesce.as_ref());
+        assert_eq!(summary.stages.len(), 2);
+        assert_eq!(summary.stages[0].operators, vec!["CoalescePartitionsExec"]);
+        assert_eq!(summary.stages[1].operators, vec!["MemoryExec"]);
+        assert!(summary.stages[0].start.is_some());
+        assert!(summary.stages[0].end.is_some());
+        assert_eq!(summary.spill_count, 0);
+
+        let formatted = summary.to_string();
+        assert!(formatted.starts_with(
+            "bytes_scanned=0, peak_mem_used=0, spill_count=0, spilled_bytes=0\nstage 0: "
+        ));
+        assert!(formatted.contains("operators=[CoalescePartitionsExec]"));
+        assert!(
+            formatted.contains("stage 1: elapsed_compute=0ns, operators=[MemoryExec]")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_summary_expressions() -> Result<()> {
+        let config = SessionConfig::new()
+            .set_bool("datafusion.execution.expression_metrics", true);
+        let task_ctx = Arc::new(TaskContext::default().with_session_config(config));
+        let input = scan_partitioned(2);
+        let expr = col("i", &input.schema())?;
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![(expr, "i".to_string())],
+            input,
+        )?);
+        collect(projection.clone(), task_ctx).await?;
+
+        let summary = PlanSummary::new(projection.as_ref());
+        assert_eq!(summary.expression_times.len(), 1);
+        assert!(summary.expression_times.contains_key("i@0"));
+        assert!(summary
+            .to_string()
+            .contains("\nexpression: elapsed_compute="));
+        assert!(summary.to_string().ends_with(", expr=i@0"));
+
+        // expressions are not timed unless enabled
+        let input = scan_partitioned(2);
+        let expr = col("i", &input.schema())?;
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![(expr, "i".to_string())],
+            input,
+        )?);
+        collect(projection.clone(), Arc::new(TaskContext::default())).await?;
+        assert!(PlanSummary::new(projection.as_ref())
+            .expression_times
+            .is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_format() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("plan_type", DataType::Utf8, false),
+            Field::new("plan", DataType::Utf8, false),
+        ]));
+        let coalesce = Arc::new(CoalescePartitionsExec::new(scan_partitioned(2)));
+        let analyze_exec = Arc::new(
+            AnalyzeExec::new(false, false, coalesce, schema)
+                .with_format(AnalyzeFormat::Json),
+        );
+
+        let batches = collect(analyze_exec, task_ctx).await?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 1);
+        let plan = batches[0]
+            .column(1)
+            .as_any()
+            .downcast_ref::<arrow::array::StringArray>()
+            .unwrap()
+            .value(0);
+
+        assert!(plan.starts_with("{\"output_rows\":200,\"duration_ns\":"));
+        assert!(plan.contains(
+            "\"summary\":{\"bytes_scanned\":0,\"peak_mem_used\":0,\"spill_count\":0,\"spilled_bytes\":0}"
+        ));
+        assert!(plan.contains(
+            "\"plan\":{\"operator\":\"CoalescePartitionsExec\",\"description\":\"CoalescePartitionsExec\",\"output_rows\":200,"
+        ));
+        assert!(plan.contains("\"children\":[{\"operator\":\"MemoryExec\","));
+        assert!(plan.ends_with("\"children\":[]}]}}"));
+        Ok(())
+    }
 }
@@ -19,6 +19,7 @@
 //! vectorized processing by upstream operators.
 
 use std::any::Any;
+use std::borrow::Cow;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -27,7 +28,7 @@ use super::expressions::PhysicalSortExpr;
 use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
 use super::{DisplayAs, Statistics};
 use crate::{
-    DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
+    DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties, RecordBatchStream,
     SendableRecordBatchStream,
 };
 
@@ -51,15 +52,21 @@ pub struct CoalesceBatchesExec {
     target_batch_size: usize,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
+    /// The properties of the output, computed once
+    cache: PlanProperties,
 }
 
 impl CoalesceBatchesExec {
     /// Create a new CoalesceBatchesExec
     pub fn new(input: Arc<dyn ExecutionPlan>, target_batch_size: usize) -> Self {
+        // The coalesce batches operator does not make any changes to the
+        // partitioning and sorting of its input
+        let cache = input.properties().into_owned();
         Self {
             input,
             target_batch_size,
             metrics: ExecutionPlanMetricsSet::new(),
+            cache,
         }
     }
 
@@ -108,10 +115,13 @@ impl ExecutionPlan for CoalesceBatchesExec {
         vec![self.input.clone()]
     }
 
+    fn properties(&self) -> Cow<'_, PlanProperties> {
+        Cow::Borrowed(&self.cache)
+    }
+
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        // The coalesce batches operator does not make any changes to the partitioning of its input
-        self.input.output_partitioning()
+        self.cache.output_partitioning().clone()
     }
 
     /// Specifies whether this plan generates an infinite stream of records.
@@ -122,8 +132,7 @@ impl ExecutionPlan for CoalesceBatchesExec {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        // The coalesce batches operator does not make any changes to the sorting of its input
-        self.input.output_ordering()
+        self.cache.output_ordering()
     }
 
     fn maintains_input_order(&self) -> Vec<bool> {
@@ -135,7 +144,7 @@ impl ExecutionPlan for CoalesceBatchesExec {
     }
 
     fn equivalence_properties(&self) -> EquivalenceProperties {
-        self.input.equivalence_properties()
+        self.cache.equivalence_properties().clone()
     }
 
     fn with_new_children(
@@ -27,7 +27,8 @@ use super::SendableRecordBatchStream;
 use crate::stream::RecordBatchReceiverStream;
 use crate::{ColumnStatistics, ExecutionPlan, Statistics};
 
-use arrow::datatypes::Schema;
+use arrow::array::{ArrayRef, AsArray};
+use arrow::datatypes::{DataType, Float16Type, Float32Type, Float64Type, Schema};
 use arrow::ipc::writer::{FileWriter, IpcWriteOptions};
 use arrow::record_batch::RecordBatch;
 use arrow_array::Array;
@@ -38,6 +39,7 @@ use datafusion_physical_expr::expressions::{BinaryExpr, Column};
 use datafusion_physical_expr::{PhysicalExpr, PhysicalSortExpr};
 
 use futures::{Future, StreamExt, TryStreamExt};
+use half::f16;
 use parking_lot::Mutex;
 use pin_project_lite::pin_project;
 use tokio::task::JoinHandle;
@@ -237,6 +239,31 @@ pub fn transpose<T>(original: Vec<Vec<T>>) -> Vec<Vec<T>> {
     }
 }
 
+/// Replaces the floating point values of `array` that are equal as grouping
+/// or join keys with a single representative: every NaN with the canonical
+/// NaN, and `-0.0` with `+0.0`. Arrays of other types are returned unchanged.
+pub fn normalize_float_keys(array: ArrayRef) -> ArrayRef {
+    macro_rules! normalize {
+        ($t:ty, $nan:expr, $zero:expr) => {
+            Arc::new(array.as_primitive::<$t>().unary::<_, $t>(|v| {
+                if v.is_nan() {
+                    $nan
+                } else if v == $zero {
+                    $zero
+                } else {
+                    v
+                }
+            }))
+        };
+    }
+    match array.data_type() {
+        DataType::Float16 => normalize!(Float16Type, f16::NAN, f16::ZERO),
+        DataType::Float32 => normalize!(Float32Type, f32::NAN, 0.0),
+        DataType::Float64 => normalize!(Float64Type, f64::NAN, 0.0),
+        _ => array,
+    }
+}
+
 /// Calculates the "meet" of given orderings.
 /// The meet is the finest ordering that satisfied by all the given
 /// orderings, see <https://en.wikipedia.org/wiki/Join_and_meet>.
@@ -722,12 +749,14 @@ mod tests {
                     max_value: Precision::Absent,
                     min_value: Precision::Absent,
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Absent,
                     max_value: Precision::Absent,
                     min_value: Precision::Absent,
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
             ],
         };
@@ -744,4 +773,23 @@ mod tests {
         assert_eq!(expected, transposed);
         Ok(())
     }
+
+    #[test]
+    fn test_normalize_float_keys() {
+        let array: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(f64::from_bits(f64::NAN.to_bits() | 1)),
+            Some(-0.0),
+            Some(1.5),
+            None,
+        ]));
+        let normalized = normalize_float_keys(array);
+        let normalized = normalized.as_primitive::<Float64Type>();
+        assert_eq!(normalized.value(0).to_bits(), f64::NAN.to_bits());
+        assert_eq!(normalized.value(1).to_bits(), 0.0f64.to_bits());
+        assert_eq!(normalized.value(2), 1.5);
+        assert!(normalized.is_null(3));
+
+        let array: ArrayRef = Arc::new(UInt64Array::from(vec![1, 2]));
+        assert_eq!(&normalize_float_keys(array.clone()), &array);
+    }
 }
@@ -219,6 +219,43 @@ enum ShowMetrics {
     Full,
 }
 
+impl ShowMetrics {
+    /// Formats the metrics of `plan`, or returns `None` if no metrics are
+    /// shown.
+    ///
+    /// Aggregated metrics are followed by the minimum, median and maximum
+    /// of each metric across the partitions of `plan`, if it has several.
+    fn format(&self, plan: &dyn ExecutionPlan) -> Option<String> {
+        let metrics = plan.metrics().unwrap_or_default();
+        match self {
+            ShowMetrics::None => None,
+            ShowMetrics::Aggregated => {
+                let rollup = metrics
+                    .partition_rollup()
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect::<Vec<_>>();
+                let metrics = metrics
+                    .aggregate_by_name()
+                    .sorted_for_display()
+                    .timestamps_removed();
+                if rollup.is_empty() {
+                    Some(format!("metrics=[{metrics}]"))
+                } else {
+                    Some(format!(
+                        "metrics=[{metrics}], partition_metrics=[{}]",
+                        rollup.join(", ")
+                    ))
+                }
+            }
+            ShowMetrics::Full => {
+                let metrics = metrics.sorted_for_display();
+                Some(format!("metrics=[{metrics}]"))
+            }
+        }
+    }
+}
+
 /// Formats plans with a single line per node.
 struct IndentVisitor<'a, 'b> {
     /// How to format each node
@@ -238,27 +275,8 @@ impl<'a, 'b> ExecutionPlanVisitor for IndentVisitor<'a, 'b> {
     fn pre_visit(&mut self, plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
         write!(self.f, "{:indent$}", "", indent = self.indent * 2)?;
         plan.fmt_as(self.t, self.f)?;
-        match self.show_metrics {
-            ShowMetrics::None => {}
-            ShowMetrics::Aggregated => {
-                if let Some(metrics) = plan.metrics() {
-                    let metrics = metrics
-                        .aggregate_by_name()
-                        .sorted_for_display()
-                        .timestamps_removed();
-
-                    write!(self.f, ", metrics=[{metrics}]")?;
-                } else {
-                    write!(self.f, ", metrics=[]")?;
-                }
-            }
-            ShowMetrics::Full => {
-                if let Some(metrics) = plan.metrics() {
-                    write!(self.f, ", metrics=[{metrics}]")?;
-                } else {
-                    write!(self.f, ", metrics=[]")?;
-                }
-            }
+        if let Some(metrics) = self.show_metrics.format(plan) {
+            write!(self.f, ", {metrics}")?;
         }
         if self.show_statistics {
             let stats = plan.statistics().map_err(|_e| fmt::Error)?;
@@ -318,28 +336,7 @@ impl ExecutionPlanVisitor for GraphvizVisitor<'_, '_> {
 
         let label = { format!("{}", Wrapper(plan, self.t)) };
 
-        let metrics = match self.show_metrics {
-            ShowMetrics::None => "".to_string(),
-            ShowMetrics::Aggregated => {
-                if let Some(metrics) = plan.metrics() {
-                    let metrics = metrics
-                        .aggregate_by_name()
-                        .sorted_for_display()
-                        .timestamps_removed();
-
-                    format!("metrics=[{metrics}]")
-                } else {
-                    "metrics=[]".to_string()
-                }
-            }
-            ShowMetrics::Full => {
-                if let Some(metrics) = plan.metrics() {
-                    format!("metrics=[{metrics}]")
-                } else {
-                    "metrics=[]".to_string()
-                }
-            }
-        };
+        let metrics = self.show_metrics.format(plan).unwrap_or_default();
 
         let statistics = if self.show_statistics {
             let stats = plan.statistics().map_err(|_e| fmt::Error)?;
@@ -26,8 +26,11 @@ use crate::{memory::MemoryStream, DisplayFormatType, ExecutionPlan, Partitioning
 
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
+use datafusion_common::stats::Precision;
 use datafusion_common::{internal_err, DataFusionError, Result};
 use datafusion_execution::TaskContext;
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::{EquivalenceProperties, PhysicalExpr};
 
 use log::trace;
 
@@ -97,11 +100,26 @@ impl ExecutionPlan for EmptyExec {
         None
     }
 
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        // without rows, every column is constant and any ordering is satisfied
+        EquivalenceProperties::new(self.schema()).add_constants(
+            self.schema
+                .fields()
+                .iter()
+                .enumerate()
+                .map(|(index, field)| {
+                    Arc::new(Column::new(field.name(), index)) as Arc<dyn PhysicalExpr>
+                }),
+        )
+    }
+
     fn with_new_children(
         self: Arc<Self>,
         _: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(EmptyExec::new(self.schema.clone())))
+        Ok(Arc::new(
+            EmptyExec::new(self.schema.clone()).with_partitions(self.partitions),
+        ))
     }
 
     fn execute(
@@ -130,11 +148,12 @@ impl ExecutionPlan for EmptyExec {
         let batch = self
             .data()
             .expect("Create empty RecordBatch should not fail");
-        Ok(common::compute_record_batch_statistics(
-            &[batch],
-            &self.schema,
-            None,
-        ))
+        let mut statistics =
+            common::compute_record_batch_statistics(&[batch], &self.schema, None);
+        for column_statistics in statistics.column_statistics.iter_mut() {
+            column_statistics.null_count = Precision::Exact(0);
+        }
+        Ok(statistics)
     }
 }
 
@@ -168,6 +187,11 @@ mod tests {
         let empty2 = with_new_children_if_necessary(empty.clone(), vec![])?.into();
         assert_eq!(empty.schema(), empty2.schema());
 
+        let partitioned = Arc::new(EmptyExec::new(schema.clone()).with_partitions(3));
+        let partitioned2: Arc<dyn ExecutionPlan> =
+            with_new_children_if_necessary(partitioned, vec![])?.into();
+        assert_eq!(partitioned2.output_partitioning().partition_count(), 3);
+
         let too_many_kids = vec![empty2];
         assert!(
             with_new_children_if_necessary(empty, too_many_kids).is_err(),
@@ -19,6 +19,7 @@
 //! include in its output batches.
 
 use std::any::Any;
+use std::borrow::Cow;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -28,8 +29,10 @@ use super::{
     ColumnStatistics, DisplayAs, RecordBatchStream, SendableRecordBatchStream, Statistics,
 };
 use crate::{
-    metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
-    Column, DisplayFormatType, ExecutionPlan, Partitioning,
+    metrics::{
+        BaselineMetrics, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet, Time,
+    },
+    Column, DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties,
 };
 
 use arrow::compute::filter_record_batch;
@@ -44,8 +47,8 @@ use datafusion_physical_expr::expressions::BinaryExpr;
 use datafusion_physical_expr::intervals::utils::check_support;
 use datafusion_physical_expr::utils::collect_columns;
 use datafusion_physical_expr::{
-    analyze, split_conjunction, AnalysisContext, EquivalenceProperties, ExprBoundaries,
-    PhysicalExpr,
+    analyze, estimate_selectivity, split_conjunction, AnalysisContext,
+    EquivalenceProperties, ExprBoundaries, PhysicalExpr,
 };
 
 use futures::stream::{Stream, StreamExt};
@@ -63,6 +66,8 @@ pub struct FilterExec {
     metrics: ExecutionPlanMetricsSet,
     /// Selectivity for statistics. 0 = no rows, 100 all rows
     default_selectivity: u8,
+    /// The properties of the output, computed once
+    cache: PlanProperties,
 }
 
 impl FilterExec {
@@ -72,12 +77,18 @@ impl FilterExec {
         input: Arc<dyn ExecutionPlan>,
     ) -> Result<Self> {
         match predicate.data_type(input.schema().as_ref())? {
-            DataType::Boolean => Ok(Self {
-                predicate,
-                input: input.clone(),
-                metrics: ExecutionPlanMetricsSet::new(),
-                default_selectivity: 20,
-            }),
+            DataType::Boolean => {
+                let default_selectivity = 20;
+                let cache =
+                    Self::compute_properties(&input, &predicate, default_selectivity);
+                Ok(Self {
+                    predicate,
+                    input,
+                    metrics: ExecutionPlanMetricsSet::new(),
+                    default_selectivity,
+                    cache,
+                })
+            }
             other => {
                 plan_err!("Filter predicate must return boolean values, not {other:?}")
             }
@@ -91,6 +102,8 @@ impl FilterExec {
         if default_selectivity > 100 {
             return plan_err!("Default flter selectivity needs to be less than 100");
         }
+        // The selectivity only changes the estimated number of rows, not the
+        // singleton columns of the properties
         self.default_selectivity = default_selectivity;
         Ok(self)
     }
@@ -109,6 +122,96 @@ impl FilterExec {
     pub fn default_selectivity(&self) -> u8 {
         self.default_selectivity
     }
+
+    /// Returns true if the statistics of the input are not sufficient to
+    /// estimate the selectivity of the predicate, so that the statistics of
+    /// this plan assume the [default selectivity](Self::default_selectivity)
+    pub fn uses_default_selectivity(&self) -> Result<bool> {
+        if check_support(&self.predicate, &self.schema()) {
+            return Ok(false);
+        }
+        let input_stats = self.input.statistics()?;
+        Ok(estimate_selectivity(&self.predicate, &input_stats).is_none())
+    }
+
+    /// Computes the properties of the filter of `input` on `predicate`
+    fn compute_properties(
+        input: &Arc<dyn ExecutionPlan>,
+        predicate: &Arc<dyn PhysicalExpr>,
+        default_selectivity: u8,
+    ) -> PlanProperties {
+        // Combine the equal predicates with the input equivalence properties
+        let input_properties = input.properties();
+        let mut eq_properties = input_properties.equivalence_properties().clone();
+        let (equal_pairs, _) = collect_columns_from_predicate(predicate);
+        for (lhs, rhs) in equal_pairs {
+            let lhs_expr = Arc::new(lhs.clone()) as _;
+            let rhs_expr = Arc::new(rhs.clone()) as _;
+            eq_properties.add_equal_conditions(&lhs_expr, &rhs_expr)
+        }
+        // Add the columns that have only one value (singleton) after filtering
+        // to constants, when the statistics are known
+        if let Ok(stats) = Self::statistics_helper(input, predicate, default_selectivity)
+        {
+            let constants = collect_columns(predicate)
+                .into_iter()
+                .filter(|column| stats.column_statistics[column.index()].is_singleton())
+                .map(|column| Arc::new(column) as _);
+            eq_properties = eq_properties.add_constants(constants);
+        }
+        PlanProperties::new(
+            eq_properties,
+            input_properties.output_partitioning().clone(),
+            input_properties.execution_mode(),
+        )
+    }
+
+    /// The statistics of the filter of `input` on `predicate`, see
+    /// [`ExecutionPlan::statistics`]
+    fn statistics_helper(
+        input: &Arc<dyn ExecutionPlan>,
+        predicate: &Arc<dyn PhysicalExpr>,
+        default_selectivity: u8,
+    ) -> Result<Statistics> {
+        let input_stats = input.statistics()?;
+        let schema = input.schema();
+        if !check_support(predicate, &schema) {
+            // fall back to the default selectivity if the statistics are not
+            // sufficient to estimate it
+            let selectivity = estimate_selectivity(predicate, &input_stats)
+                .unwrap_or(default_selectivity as f64 / 100.0);
+            let mut stats = input_stats.into_inexact();
+            stats.num_rows = stats.num_rows.with_estimated_selectivity(selectivity);
+            stats.total_byte_size = stats
+                .total_byte_size
+                .with_estimated_selectivity(selectivity);
+            return Ok(stats);
+        }
+
+        let num_rows = input_stats.num_rows;
+        let total_byte_size = input_stats.total_byte_size;
+        let input_analysis_ctx = AnalysisContext::try_from_statistics(
+            &schema,
+            &input_stats.column_statistics,
+        )?;
+
+        let analysis_ctx = analyze(predicate, input_analysis_ctx, &schema)?;
+
+        // Estimate (inexact) selectivity of predicate
+        let selectivity = analysis_ctx.selectivity.unwrap_or(1.0);
+        let num_rows = num_rows.with_estimated_selectivity(selectivity);
+        let total_byte_size = total_byte_size.with_estimated_selectivity(selectivity);
+
+        let column_statistics = collect_new_statistics(
+            &input_stats.column_statistics,
+            analysis_ctx.boundaries,
+        );
+        Ok(Statistics {
+            num_rows,
+            total_byte_size,
+            column_statistics,
+        })
+    }
 }
 
 impl DisplayAs for FilterExec {
@@ -141,9 +244,13 @@ impl ExecutionPlan for FilterExec {
         vec![self.input.clone()]
     }
 
+    fn properties(&self) -> Cow<'_, PlanProperties> {
+        Cow::Borrowed(&self.cache)
+    }
+
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        self.input.output_partitioning()
+        self.cache.output_partitioning().clone()
     }
 
     /// Specifies whether this plan generates an infinite stream of records.
@@ -154,7 +261,7 @@ impl ExecutionPlan for FilterExec {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        self.input.output_ordering()
+        self.cache.output_ordering()
     }
 
     fn maintains_input_order(&self) -> Vec<bool> {
@@ -163,21 +270,7 @@ impl ExecutionPlan for FilterExec {
     }
 
     fn equivalence_properties(&self) -> EquivalenceProperties {
-        let stats = self.statistics().unwrap();
-        // Combine the equal predicates with the input equivalence properties
-        let mut result = self.input.equivalence_properties();
-        let (equal_pairs, _) = collect_columns_from_predicate(&self.predicate);
-        for (lhs, rhs) in equal_pairs {
-            let lhs_expr = Arc::new(lhs.clone()) as _;
-            let rhs_expr = Arc::new(rhs.clone()) as _;
-            result.add_equal_conditions(&lhs_expr, &rhs_expr)
-        }
-        // Add the columns that have only one value (singleton) after filtering to constants.
-        let constants = collect_columns(self.predicate())
-            .into_iter()
-            .filter(|column| stats.column_statistics[column.index()].is_singleton())
-            .map(|column| Arc::new(column) as _);
-        result.add_constants(constants)
+        self.cache.equivalence_properties().clone()
     }
 
     fn with_new_children(
@@ -199,11 +292,21 @@ impl ExecutionPlan for FilterExec {
     ) -> Result<SendableRecordBatchStream> {
         trace!("Start FilterExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
         let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let predicate_time = context
+            .session_config()
+            .options()
+            .execution
+            .expression_metrics
+            .then(|| {
+                MetricBuilder::new(&self.metrics)
+                    .expression_time(self.predicate.to_string(), partition)
+            });
         Ok(Box::pin(FilterExecStream {
             schema: self.input.schema(),
             predicate: self.predicate.clone(),
             input: self.input.execute(partition, context)?,
             baseline_metrics,
+            predicate_time,
         }))
     }
 
@@ -214,43 +317,7 @@ impl ExecutionPlan for FilterExec {
     /// The output statistics of a filtering operation can be estimated if the
     /// predicate's selectivity value can be determined for the incoming data.
     fn statistics(&self) -> Result<Statistics> {
-        let predicate = self.predicate();
-
-        let input_stats = self.input.statistics()?;
-        let schema = self.schema();
-        if !check_support(predicate, &schema) {
-            let selectivity = self.default_selectivity as f64 / 100.0;
-            let mut stats = input_stats.into_inexact();
-            stats.num_rows = stats.num_rows.with_estimated_selectivity(selectivity);
-            stats.total_byte_size = stats
-                .total_byte_size
-                .with_estimated_selectivity(selectivity);
-            return Ok(stats);
-        }
-
-        let num_rows = input_stats.num_rows;
-        let total_byte_size = input_stats.total_byte_size;
-        let input_analysis_ctx = AnalysisContext::try_from_statistics(
-            &self.input.schema(),
-            &input_stats.column_statistics,
-        )?;
-
-        let analysis_ctx = analyze(predicate, input_analysis_ctx, &self.schema())?;
-
-        // Estimate (inexact) selectivity of predicate
-        let selectivity = analysis_ctx.selectivity.unwrap_or(1.0);
-        let num_rows = num_rows.with_estimated_selectivity(selectivity);
-        let total_byte_size = total_byte_size.with_estimated_selectivity(selectivity);
-
-        let column_statistics = collect_new_statistics(
-            &input_stats.column_statistics,
-            analysis_ctx.boundaries,
-        );
-        Ok(Statistics {
-            num_rows,
-            total_byte_size,
-            column_statistics,
-        })
+        Self::statistics_helper(&self.input, &self.predicate, self.default_selectivity)
     }
 }
 
@@ -285,6 +352,7 @@ fn collect_new_statistics(
                     max_value,
                     min_value,
                     distinct_count: distinct_count.to_inexact(),
+                    histogram: None,
                 }
             },
         )
@@ -302,20 +370,34 @@ struct FilterExecStream {
     input: SendableRecordBatchStream,
     /// runtime metrics recording
     baseline_metrics: BaselineMetrics,
+    /// Time spent evaluating the predicate, if expression metrics are enabled
+    predicate_time: Option<Time>,
 }
 
 pub(crate) fn batch_filter(
     batch: &RecordBatch,
     predicate: &Arc<dyn PhysicalExpr>,
 ) -> Result<RecordBatch> {
-    predicate
+    timed_batch_filter(batch, predicate, None)
+}
+
+/// Filters `batch` with `predicate`, recording the time spent evaluating
+/// `predicate` to `predicate_time`, if any
+fn timed_batch_filter(
+    batch: &RecordBatch,
+    predicate: &Arc<dyn PhysicalExpr>,
+    predicate_time: Option<&Time>,
+) -> Result<RecordBatch> {
+    let timer = predicate_time.map(Time::timer);
+    let array = predicate
         .evaluate(batch)
-        .and_then(|v| v.into_array(batch.num_rows()))
-        .and_then(|array| {
-            Ok(as_boolean_array(&array)?)
-                // apply filter array to record batch
-                .and_then(|filter_array| Ok(filter_record_batch(batch, filter_array)?))
-        })
+        .and_then(|v| v.into_array(batch.num_rows()));
+    drop(timer);
+    array.and_then(|array| {
+        Ok(as_boolean_array(&array)?)
+            // apply filter array to record batch
+            .and_then(|filter_array| Ok(filter_record_batch(batch, filter_array)?))
+    })
 }
 
 impl Stream for FilterExecStream {
@@ -331,7 +413,11 @@ impl Stream for FilterExecStream {
                 Poll::Ready(value) => match value {
                     Some(Ok(batch)) => {
                         let timer = self.baseline_metrics.elapsed_compute().timer();
-                        let filtered_batch = batch_filter(&batch, &self.predicate)?;
+                        let filtered_batch = timed_batch_filter(
+                            &batch,
+                            &self.predicate,
+                            self.predicate_time.as_ref(),
+                        )?;
                         // skip entirely filtered batches
                         if filtered_batch.num_rows() == 0 {
                             continue;
@@ -978,6 +1064,7 @@ mod tests {
                 min_value: Precision::Inexact(ScalarValue::Int32(Some(5))),
                 max_value: Precision::Inexact(ScalarValue::Int32(Some(10))),
                 distinct_count: Precision::Absent,
+                histogram: None,
             }],
         };
 
@@ -1057,4 +1144,33 @@ mod tests {
         assert_eq!(statistics.total_byte_size, Precision::Inexact(1600));
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_filter_statistics_estimated_selectivity() -> Result<()> {
+        // Interval analysis does not support strings, so the selectivity is
+        // estimated from the distinct count
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, false)]);
+        let input = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Precision::Inexact(1000),
+                total_byte_size: Precision::Inexact(4000),
+                column_statistics: vec![ColumnStatistics {
+                    distinct_count: Precision::Inexact(20),
+                    ..Default::default()
+                }],
+            },
+            schema,
+        ));
+        // WHERE a = 'x'
+        let predicate = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("a", 0)),
+            Operator::Eq,
+            Arc::new(Literal::new(ScalarValue::from("x"))),
+        ));
+        let filter = FilterExec::try_new(predicate, input)?;
+        let statistics = filter.statistics()?;
+        assert_eq!(statistics.num_rows, Precision::Inexact(50));
+        assert_eq!(statistics.total_byte_size, Precision::Inexact(200));
+        Ok(())
+    }
 }
@@ -0,0 +1,548 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the gap filling plan, emitting the time buckets missing in the
+//! series of its input
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::expressions::{Column, PhysicalSortExpr};
+use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::{DisplayAs, RecordBatchStream, SendableRecordBatchStream};
+use crate::{DisplayFormatType, Distribution, ExecutionPlan, Partitioning, PhysicalExpr};
+
+use arrow::array::{Array, ArrayRef, AsArray, Float64Array, Int64Array, UInt32Array};
+use arrow::compute::{cast, concat_batches, take};
+use arrow::datatypes::{DataType, Float64Type, Int64Type, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, Rows, SortField};
+use datafusion_common::{plan_err, DataFusionError, Result};
+use datafusion_execution::TaskContext;
+use datafusion_expr::FillStrategy;
+use datafusion_physical_expr::PhysicalSortRequirement;
+
+use futures::stream::{Stream, StreamExt};
+
+/// Execution plan emitting a row for every time bucket that is missing
+/// between the first and the last time bucket of each series of its input,
+/// see [`GapFill`](datafusion_expr::GapFill).
+///
+/// The input must be sorted on the series columns and then on the time
+/// column, and all rows of a series must be in the same partition.
+#[derive(Debug)]
+pub struct GapFillExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The columns identifying the series, other than the time column
+    series_expr: Vec<Column>,
+    /// The timestamp column with the time buckets
+    time_expr: Column,
+    /// The width of the time buckets, in the unit of the time column
+    stride: i64,
+    /// The columns filled with something else than nulls
+    fill_expr: Vec<(Column, FillStrategy)>,
+    /// The ordering required of the input, and kept in the output
+    ordering: Vec<PhysicalSortExpr>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl GapFillExec {
+    /// Create a new [`GapFillExec`], with `stride` the width of the time
+    /// buckets in nanoseconds
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        series_expr: Vec<Column>,
+        time_expr: Column,
+        stride: i64,
+        fill_expr: Vec<(Column, FillStrategy)>,
+    ) -> Result<Self> {
+        let schema = input.schema();
+        let nanos_per_unit = match schema.field(time_expr.index()).data_type() {
+            DataType::Timestamp(TimeUnit::Second, _) => 1_000_000_000,
+            DataType::Timestamp(TimeUnit::Millisecond, _) => 1_000_000,
+            DataType::Timestamp(TimeUnit::Microsecond, _) => 1_000,
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => 1,
+            other => {
+                return plan_err!("GapFillExec expects a timestamp column, got {other}")
+            }
+        };
+        if stride <= 0 || stride % nanos_per_unit != 0 {
+            return plan_err!(
+                "GapFillExec expects a positive stride in whole units of the time column, got {stride} nanoseconds"
+            );
+        }
+        for (column, strategy) in &fill_expr {
+            let data_type = schema.field(column.index()).data_type();
+            if *strategy == FillStrategy::LinearInterpolate && !data_type.is_numeric() {
+                return plan_err!(
+                    "interpolate expects a numeric column, got {column} of type {data_type}"
+                );
+            }
+        }
+
+        let ordering = series_expr
+            .iter()
+            .chain([&time_expr])
+            .map(|column| PhysicalSortExpr {
+                expr: Arc::new(column.clone()),
+                options: Default::default(),
+            })
+            .collect();
+        Ok(Self {
+            input,
+            series_expr,
+            time_expr,
+            stride: stride / nanos_per_unit,
+            fill_expr,
+            ordering,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The columns identifying the series, other than the time column
+    pub fn series_expr(&self) -> &[Column] {
+        &self.series_expr
+    }
+
+    /// The timestamp column with the time buckets
+    pub fn time_expr(&self) -> &Column {
+        &self.time_expr
+    }
+
+    /// The columns filled with something else than nulls
+    pub fn fill_expr(&self) -> &[(Column, FillStrategy)] {
+        &self.fill_expr
+    }
+}
+
+impl DisplayAs for GapFillExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let series = self
+                    .series_expr
+                    .iter()
+                    .map(|column| column.to_string())
+                    .collect::<Vec<_>>();
+                let fill = self
+                    .fill_expr
+                    .iter()
+                    .map(|(column, strategy)| format!("{strategy}({column})"))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "GapFillExec: series=[{}], time={}, stride={}, fill=[{}]",
+                    series.join(", "),
+                    self.time_expr,
+                    self.stride,
+                    fill.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for GapFillExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        Ok(children[0])
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            input: children[0].clone(),
+            series_expr: self.series_expr.clone(),
+            time_expr: self.time_expr.clone(),
+            stride: self.stride,
+            fill_expr: self.fill_expr.clone(),
+            ordering: self.ordering.clone(),
+            metrics: ExecutionPlanMetricsSet::new(),
+        }))
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.series_expr.is_empty() {
+            vec![Distribution::SinglePartition]
+        } else {
+            let series = self
+                .series_expr
+                .iter()
+                .map(|column| Arc::new(column.clone()) as Arc<dyn PhysicalExpr>)
+                .collect();
+            vec![Distribution::HashPartitioned(series)]
+        }
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        vec![Some(PhysicalSortRequirement::from_sort_exprs(
+            &self.ordering,
+        ))]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        Some(&self.ordering)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema();
+        let series_fields = self
+            .series_expr
+            .iter()
+            .map(|column| {
+                SortField::new(schema.field(column.index()).data_type().clone())
+            })
+            .collect();
+        Ok(Box::pin(GapFillStream {
+            input: self.input.execute(partition, context)?,
+            schema,
+            series_expr: self.series_expr.clone(),
+            time_expr: self.time_expr.clone(),
+            stride: self.stride,
+            fill_expr: self.fill_expr.clone(),
+            converter: RowConverter::new(series_fields)?,
+            prev: None,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+/// A stream emitting the rows of its input and the rows of the missing time
+/// buckets between them
+struct GapFillStream {
+    input: SendableRecordBatchStream,
+    schema: SchemaRef,
+    series_expr: Vec<Column>,
+    time_expr: Column,
+    stride: i64,
+    fill_expr: Vec<(Column, FillStrategy)>,
+    /// Converts the series columns to rows to find where series end
+    converter: RowConverter,
+    /// The last row of the previous batch, so that the gaps between batches
+    /// are filled too
+    prev: Option<RecordBatch>,
+    baseline_metrics: BaselineMetrics,
+}
+
+/// A row emitted by [`GapFillStream`]
+struct OutputRow {
+    /// The input row, or the row before the gap for gap rows
+    prev: u32,
+    /// The input row, or the row after the gap for gap rows
+    next: u32,
+    /// The time bucket of gap rows
+    gap_time: Option<i64>,
+}
+
+impl GapFillStream {
+    fn fill_batch(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+        let _timer = elapsed_compute.timer();
+        let (batch, first_row) = match self.prev.take() {
+            Some(prev) => (concat_batches(&self.schema, [&prev, &batch])?, 1),
+            None => (batch, 0),
+        };
+        let num_rows = batch.num_rows();
+        if num_rows == first_row {
+            self.prev = (num_rows > 0).then(|| batch.slice(num_rows - 1, 1));
+            return Ok(RecordBatch::new_empty(self.schema.clone()));
+        }
+
+        let series = self.series_rows(&batch)?;
+        let same_series = |i: usize| match &series {
+            Some(rows) => rows.row(i - 1) == rows.row(i),
+            None => true,
+        };
+        let times = cast(batch.column(self.time_expr.index()), &DataType::Int64)?;
+        let times = times.as_primitive::<Int64Type>();
+
+        let mut output = vec![];
+        for i in first_row..num_rows {
+            if i > 0 && same_series(i) && times.is_valid(i - 1) && times.is_valid(i) {
+                let mut time = times.value(i - 1).checked_add(self.stride);
+                while let Some(gap_time) = time.filter(|t| *t < times.value(i)) {
+                    output.push(OutputRow {
+                        prev: (i - 1) as u32,
+                        next: i as u32,
+                        gap_time: Some(gap_time),
+                    });
+                    time = gap_time.checked_add(self.stride);
+                }
+            }
+            output.push(OutputRow {
+                prev: i as u32,
+                next: i as u32,
+                gap_time: None,
+            });
+        }
+        self.prev = Some(batch.slice(num_rows - 1, 1));
+
+        let prev_indices =
+            UInt32Array::from_iter_values(output.iter().map(|row| row.prev));
+        let columns = batch
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(index, array)| {
+                if index == self.time_expr.index() {
+                    let output_times = output
+                        .iter()
+                        .map(|row| match row.gap_time {
+                            Some(time) => Some(time),
+                            None => times
+                                .is_valid(row.prev as usize)
+                                .then(|| times.value(row.prev as usize)),
+                        })
+                        .collect::<Int64Array>();
+                    return Ok(cast(&output_times, array.data_type())?);
+                }
+                let strategy = self
+                    .fill_expr
+                    .iter()
+                    .find(|(column, _)| column.index() == index)
+                    .map(|(_, strategy)| *strategy);
+                match strategy {
+                    Some(FillStrategy::Null) => {
+                        let indices = output
+                            .iter()
+                            .map(|row| row.gap_time.is_none().then_some(row.prev))
+                            .collect::<UInt32Array>();
+                        Ok(take(array, &indices, None)?)
+                    }
+                    Some(FillStrategy::LinearInterpolate) => {
+                        interpolate(array, times, &output)
+                    }
+                    // the series columns are the same as the row before
+                    // the gap
+                    Some(FillStrategy::PrevValue) | None => {
+                        Ok(take(array, &prev_indices, None)?)
+                    }
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+
+    /// Returns the series columns of `batch` as rows, or `None` if all rows
+    /// are of the same series
+    fn series_rows(&mut self, batch: &RecordBatch) -> Result<Option<Rows>> {
+        if self.series_expr.is_empty() {
+            return Ok(None);
+        }
+        let columns = self
+            .series_expr
+            .iter()
+            .map(|column| batch.column(column.index()).clone())
+            .collect::<Vec<_>>();
+        Ok(Some(self.converter.convert_columns(&columns)?))
+    }
+}
+
+/// Returns the values of `array` for the `output` rows, with the values of
+/// gap rows interpolated linearly between the rows before and after them
+fn interpolate(
+    array: &ArrayRef,
+    times: &Int64Array,
+    output: &[OutputRow],
+) -> Result<ArrayRef> {
+    let values = cast(array, &DataType::Float64)?;
+    let values = values.as_primitive::<Float64Type>();
+    let value = |i: u32| {
+        let i = i as usize;
+        values.is_valid(i).then(|| values.value(i))
+    };
+    let interpolated = output
+        .iter()
+        .map(|row| match row.gap_time {
+            None => value(row.prev),
+            Some(time) => {
+                let (prev, next) = (value(row.prev)?, value(row.next)?);
+                let prev_time = times.value(row.prev as usize);
+                let next_time = times.value(row.next as usize);
+                let fraction = (time - prev_time) as f64 / (next_time - prev_time) as f64;
+                Some(prev + (next - prev) * fraction)
+            }
+        })
+        .collect::<Float64Array>();
+    Ok(cast(&interpolated, array.data_type())?)
+}
+
+impl Stream for GapFillStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx).map(|x| match x {
+            Some(Ok(batch)) => Some(self.fill_batch(batch)),
+            other => other,
+        });
+
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for GapFillStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collect;
+    use crate::memory::MemoryExec;
+
+    use arrow::array::{StringArray, TimestampNanosecondArray};
+    use arrow::datatypes::{Field, Schema};
+    use arrow::util::pretty::pretty_format_batches;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("region", DataType::Utf8, false),
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("a", DataType::Float64, true),
+            Field::new("b", DataType::Int64, true),
+            Field::new("c", DataType::Int64, true),
+        ]))
+    }
+
+    fn batch(
+        region: Vec<&str>,
+        time: Vec<i64>,
+        values: Vec<Option<i64>>,
+    ) -> Result<RecordBatch> {
+        let floats = values
+            .iter()
+            .map(|v| v.map(|v| v as f64))
+            .collect::<Vec<_>>();
+        Ok(RecordBatch::try_new(
+            schema(),
+            vec![
+                Arc::new(StringArray::from(region)),
+                Arc::new(TimestampNanosecondArray::from(time)),
+                Arc::new(Float64Array::from(floats)),
+                Arc::new(Int64Array::from(values.clone())),
+                Arc::new(Int64Array::from(values)),
+            ],
+        )?)
+    }
+
+    #[tokio::test]
+    async fn gap_fill_series() -> Result<()> {
+        let schema = schema();
+        // the gap between 10 and 40 of region a spans two batches
+        let batches = vec![
+            batch(vec!["a", "a"], vec![0, 10], vec![Some(0), Some(10)])?,
+            batch(
+                vec!["a", "b", "b"],
+                vec![40, 10, 30],
+                vec![Some(40), Some(1), None],
+            )?,
+        ];
+        let input = Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+        let gap_fill = Arc::new(GapFillExec::try_new(
+            input,
+            vec![Column::new("region", 0)],
+            Column::new("time", 1),
+            10,
+            vec![
+                (Column::new("a", 2), FillStrategy::LinearInterpolate),
+                (Column::new("b", 3), FillStrategy::PrevValue),
+                (Column::new("c", 4), FillStrategy::Null),
+            ],
+        )?);
+
+        let batches = collect(gap_fill, Arc::new(TaskContext::default())).await?;
+        let expected = vec![
+            "+--------+-------------------------------+------+----+----+",
+            "| region | time                          | a    | b  | c  |",
+            "+--------+-------------------------------+------+----+----+",
+            "| a      | 1970-01-01T00:00:00           | 0.0  | 0  | 0  |",
+            "| a      | 1970-01-01T00:00:00.000000010 | 10.0 | 10 | 10 |",
+            "| a      | 1970-01-01T00:00:00.000000020 | 20.0 | 10 |    |",
+            "| a      | 1970-01-01T00:00:00.000000030 | 30.0 | 10 |    |",
+            "| a      | 1970-01-01T00:00:00.000000040 | 40.0 | 40 | 40 |",
+            "| b      | 1970-01-01T00:00:00.000000010 | 1.0  | 1  | 1  |",
+            "| b      | 1970-01-01T00:00:00.000000020 |      | 1  |    |",
+            "| b      | 1970-01-01T00:00:00.000000030 |      |    |    |",
+            "+--------+-------------------------------+------+----+----+",
+        ];
+        let actual = pretty_format_batches(&batches)?.to_string();
+        assert_eq!(actual.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn gap_fill_stride() -> Result<()> {
+        let input = Arc::new(MemoryExec::try_new(&[vec![]], schema(), None)?);
+        let err = GapFillExec::try_new(input, vec![], Column::new("time", 1), 0, vec![])
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("GapFillExec expects a positive stride"));
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`tracing`] instrumentation of execution plans, see [`instrument_plan`]
+
+use std::any::Any;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::analyze::operator_name;
+use crate::display::DisplayableExecutionPlan;
+use crate::metrics::MetricsSet;
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
+    RecordBatchStream, SendableRecordBatchStream,
+};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::{Result, Statistics};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{
+    EquivalenceProperties, PhysicalSortExpr, PhysicalSortRequirement,
+};
+
+use futures::{Stream, StreamExt};
+use tracing::field::Empty;
+use tracing::{info_span, Span};
+
+/// Wraps every operator of `plan` in an [`InstrumentedExec`], so that
+/// executing the plan opens a [`tracing`] span per operator and partition
+pub fn instrument_plan(plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    let children = plan
+        .children()
+        .into_iter()
+        .map(instrument_plan)
+        .collect::<Result<Vec<_>>>()?;
+    let plan = if children.is_empty() {
+        plan
+    } else {
+        plan.with_new_children(children)?
+    };
+    Ok(Arc::new(InstrumentedExec::new(plan)))
+}
+
+/// Execution plan that opens a `datafusion.execute` [`tracing`] span for each
+/// partition of its input it executes.
+///
+/// The span is entered while the input executes and is polled, so that the
+/// spans of the children of the input are nested below it. Once the stream
+/// of the partition is dropped the span records the number of rows and bytes
+/// it produced.
+///
+/// `InstrumentedExec` is transparent: it is displayed as, and reports the
+/// properties, metrics and statistics of, its input.
+#[derive(Debug)]
+pub struct InstrumentedExec {
+    /// The instrumented operator
+    inner: Arc<dyn ExecutionPlan>,
+}
+
+impl InstrumentedExec {
+    /// Create a new InstrumentedExec
+    pub fn new(inner: Arc<dyn ExecutionPlan>) -> Self {
+        Self { inner }
+    }
+
+    /// The instrumented operator
+    pub fn inner(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.inner
+    }
+
+    /// Wraps `plan`, if any, in an [`InstrumentedExec`]
+    fn wrap(plan: Option<Arc<dyn ExecutionPlan>>) -> Option<Arc<dyn ExecutionPlan>> {
+        plan.map(|plan| Arc::new(Self::new(plan)) as Arc<dyn ExecutionPlan>)
+    }
+}
+
+impl DisplayAs for InstrumentedExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt_as(t, f)
+    }
+}
+
+impl ExecutionPlan for InstrumentedExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.inner.output_partitioning()
+    }
+
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        self.inner.unbounded_output(children)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.inner.output_ordering()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        self.inner.required_input_distribution()
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        self.inner.required_input_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        self.inner.maintains_input_order()
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        self.inner.benefits_from_input_partitioning()
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        self.inner.equivalence_properties()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.inner.children()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let inner = self.inner.clone().with_new_children(children)?;
+        Ok(Arc::new(Self::new(inner)))
+    }
+
+    fn repartitioned(
+        &self,
+        target_partitions: usize,
+        config: &ConfigOptions,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let plan = self.inner.repartitioned(target_partitions, config)?;
+        Ok(Self::wrap(plan))
+    }
+
+    fn try_pushdown_sort(
+        &self,
+        ordering: &[PhysicalSortExpr],
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let plan = self.inner.try_pushdown_sort(ordering)?;
+        Ok(Self::wrap(plan))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let description = DisplayableExecutionPlan::new(self.inner.as_ref())
+            .one_line()
+            .to_string();
+        let span = info_span!(
+            "datafusion.execute",
+            operator = operator_name(&description),
+            description = description.trim_end(),
+            partition,
+            output_rows = Empty,
+            output_bytes = Empty,
+        );
+        let input = span.in_scope(|| self.inner.execute(partition, context))?;
+        Ok(Box::pin(InstrumentedStream {
+            input,
+            span,
+            output_rows: 0,
+            output_bytes: 0,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.inner.metrics()
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.inner.statistics()
+    }
+}
+
+/// Stream of an [`InstrumentedExec`] partition, entering `span` while
+/// polling `input`
+struct InstrumentedStream {
+    input: SendableRecordBatchStream,
+    span: Span,
+    /// Number of rows produced so far
+    output_rows: usize,
+    /// Memory size of the batches produced so far
+    output_bytes: usize,
+}
+
+impl Stream for InstrumentedStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let _entered = this.span.enter();
+        let poll = this.input.poll_next_unpin(cx);
+        if let Poll::Ready(Some(Ok(batch))) = &poll {
+            this.output_rows += batch.num_rows();
+            this.output_bytes += batch.get_array_memory_size();
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for InstrumentedStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+impl Drop for InstrumentedStream {
+    fn drop(&mut self) {
+        self.span.record("output_rows", self.output_rows);
+        self.span.record("output_bytes", self.output_bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coalesce_partitions::CoalescePartitionsExec;
+    use crate::test::scan_partitioned;
+    use crate::{collect, displayable};
+
+    #[tokio::test]
+    async fn test_instrument_plan() -> Result<()> {
+        let plan: Arc<dyn ExecutionPlan> =
+            Arc::new(CoalescePartitionsExec::new(scan_partitioned(2)));
+        let expected = displayable(plan.as_ref()).indent(false).to_string();
+
+        let instrumented = instrument_plan(plan)?;
+        assert!(instrumented.as_any().is::<InstrumentedExec>());
+        assert!(instrumented.children()[0].as_any().is::<InstrumentedExec>());
+        assert_eq!(
+            displayable(instrumented.as_ref()).indent(false).to_string(),
+            expected
+        );
+
+        let batches = collect(instrumented, Arc::new(TaskContext::default())).await?;
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 200);
+        Ok(())
+    }
+}
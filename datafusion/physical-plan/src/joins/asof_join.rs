@@ -0,0 +1,687 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the as-of join plan, joining each row of the left input with the
+//! latest row of the right input that is not later than it.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::{self, Formatter};
+use std::mem::size_of;
+use std::sync::Arc;
+use std::task::Poll;
+
+use crate::joins::nested_loop_join::load_specified_partition_of_input;
+use crate::joins::utils::{
+    build_batch_from_indices, build_join_schema, check_join_is_valid,
+    BuildProbeJoinMetrics, ColumnIndex, JoinOn, OnceAsync, OnceFut,
+};
+use crate::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
+    RecordBatchStream, SendableRecordBatchStream,
+};
+
+use arrow::array::{Array, ArrayRef, Int64Array, UInt32Builder, UInt64Builder};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, Rows, SortField};
+use datafusion_common::cast::as_int64_array;
+use datafusion_common::stats::Precision;
+use datafusion_common::{plan_err, DataFusionError, JoinSide, Result, Statistics};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_expr::JoinType;
+use datafusion_physical_expr::equivalence::join_equivalence_properties;
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::{EquivalenceProperties, PhysicalExpr, PhysicalSortExpr};
+
+use futures::{ready, Stream, StreamExt};
+
+/// The rows of the right input, grouped by their `on` columns
+struct AsOfJoinRightData {
+    /// The rows of the right input
+    batch: RecordBatch,
+    /// The times and indices of the rows of each group, sorted on time, by
+    /// the row format of the `on` columns of the group. The rows with a null
+    /// time or `on` column are not in any group.
+    groups: HashMap<Box<[u8]>, Vec<(i64, u32)>>,
+    /// Memory reservation of the right input
+    _reservation: MemoryReservation,
+}
+
+/// AsOfJoinExec joins each row of the left input with the row of the right
+/// input with equal `on` columns whose time is the closest to, and not
+/// later than, the time of the left row, such as the latest quote of a
+/// stock at the time of each trade.
+///
+/// When `tolerance` is set, rows of the right input earlier than the left
+/// row by more than `tolerance` are not joined. Left rows without a
+/// matching right row are dropped by [`JoinType::Inner`] joins, and joined
+/// with nulls by [`JoinType::Left`] joins. Of the right rows with the same
+/// time, the last one is joined.
+///
+/// The right input is collected into a single partition and grouped by its
+/// `on` columns, so that the matching row of each left row is found by a
+/// binary search of the times of its group. The left input is streamed and
+/// its order is kept.
+#[derive(Debug)]
+pub struct AsOfJoinExec {
+    /// left side
+    left: Arc<dyn ExecutionPlan>,
+    /// right side
+    right: Arc<dyn ExecutionPlan>,
+    /// The pairs of columns of the left and right inputs that must be equal
+    on: JoinOn,
+    /// The time column of the left input
+    left_time: Column,
+    /// The time column of the right input
+    right_time: Column,
+    /// The maximum difference of the times of the joined rows, in the unit
+    /// of the time columns
+    tolerance: Option<i64>,
+    /// How the join is performed
+    join_type: JoinType,
+    /// The schema once the join is applied
+    schema: SchemaRef,
+    /// The grouped right input
+    right_table: OnceAsync<AsOfJoinRightData>,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl AsOfJoinExec {
+    /// Try to create a new [`AsOfJoinExec`]
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        time: (Column, Column),
+        tolerance: Option<i64>,
+        join_type: &JoinType,
+    ) -> Result<Self> {
+        if !matches!(join_type, JoinType::Inner | JoinType::Left) {
+            return plan_err!(
+                "AsOfJoinExec supports Inner and Left joins, got {join_type}"
+            );
+        }
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        check_join_is_valid(&left_schema, &right_schema, &on)?;
+        let (left_time, right_time) = time;
+        let left_type = left_time.data_type(&left_schema)?;
+        let right_type = right_time.data_type(&right_schema)?;
+        if left_type != right_type || !is_time_type(&left_type) {
+            return plan_err!(
+                "AsOfJoinExec expects time columns of the same integer, date or timestamp type, got {left_type} and {right_type}"
+            );
+        }
+        let (schema, column_indices) =
+            build_join_schema(&left_schema, &right_schema, join_type);
+        Ok(AsOfJoinExec {
+            left,
+            right,
+            on,
+            left_time,
+            right_time,
+            tolerance,
+            join_type: *join_type,
+            schema: Arc::new(schema),
+            right_table: Default::default(),
+            column_indices,
+            metrics: Default::default(),
+        })
+    }
+
+    /// left side
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
+
+    /// right side
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
+    }
+
+    /// The pairs of columns of the left and right inputs that must be equal
+    pub fn on(&self) -> &[(Column, Column)] {
+        &self.on
+    }
+
+    /// The time columns of the left and right inputs
+    pub fn time(&self) -> (&Column, &Column) {
+        (&self.left_time, &self.right_time)
+    }
+
+    /// The maximum difference of the times of the joined rows
+    pub fn tolerance(&self) -> Option<i64> {
+        self.tolerance
+    }
+
+    /// How the join is performed
+    pub fn join_type(&self) -> &JoinType {
+        &self.join_type
+    }
+}
+
+/// Whether the time columns can be of type `data_type`, that is whether
+/// they can be cast to `i64` preserving their order
+fn is_time_type(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::Date32
+            | DataType::Date64
+            | DataType::Timestamp(_, _)
+    )
+}
+
+impl DisplayAs for AsOfJoinExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let on = self
+                    .on
+                    .iter()
+                    .map(|(l, r)| format!("({l}, {r})"))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "AsOfJoinExec: join_type={:?}, on=[{}], time=({}, {})",
+                    self.join_type,
+                    on.join(", "),
+                    self.left_time,
+                    self.right_time
+                )?;
+                if let Some(tolerance) = self.tolerance {
+                    write!(f, ", tolerance={tolerance}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for AsOfJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        // the columns of the left input keep their indices in the output
+        self.left.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.left.output_ordering()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![
+            Distribution::UnspecifiedDistribution,
+            Distribution::SinglePartition,
+        ]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        join_equivalence_properties(
+            self.left.equivalence_properties(),
+            self.right.equivalence_properties(),
+            &self.join_type,
+            self.schema(),
+            &self.maintains_input_order(),
+            Some(JoinSide::Left),
+            &self.on,
+        )
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(AsOfJoinExec::try_new(
+            children[0].clone(),
+            children[1].clone(),
+            self.on.clone(),
+            (self.left_time.clone(), self.right_time.clone()),
+            self.tolerance,
+            &self.join_type,
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
+
+        // Initialization reservation for load of the right input
+        let load_reservation = MemoryConsumer::new(format!("AsOfJoinLoad[{partition}]"))
+            .register(context.memory_pool());
+
+        let right_on = self.on.iter().map(|(_, r)| r.clone()).collect::<Vec<_>>();
+        let right_table = self.right_table.once(|| {
+            load_grouped_right_table(
+                self.right.clone(),
+                right_on,
+                self.right_time.clone(),
+                context.clone(),
+                join_metrics.clone(),
+                load_reservation,
+            )
+        });
+        let left_input = self.left.execute(partition, context)?;
+
+        Ok(Box::pin(AsOfJoinStream {
+            schema: self.schema.clone(),
+            join_type: self.join_type,
+            left_input,
+            right_table,
+            left_on: self.on.iter().map(|(l, _)| l.clone()).collect(),
+            left_time: self.left_time.clone(),
+            tolerance: self.tolerance,
+            converter: None,
+            column_indices: self.column_indices.clone(),
+            join_metrics,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        // each left row is joined with at most one right row
+        let num_rows = match self.join_type {
+            JoinType::Left => self.left.statistics()?.num_rows,
+            _ => self.left.statistics()?.num_rows.to_inexact(),
+        };
+        Ok(Statistics {
+            num_rows,
+            total_byte_size: Precision::Absent,
+            column_statistics: Statistics::unknown_column(&self.schema),
+        })
+    }
+}
+
+/// The times of the rows of `batch`, cast to `i64`, the row format of its
+/// `on` columns, and whether each row has neither a null time nor a null
+/// `on` column
+fn evaluate_times_and_keys(
+    batch: &RecordBatch,
+    on: &[Column],
+    time: &Column,
+    converter: &mut Option<RowConverter>,
+) -> Result<(Int64Array, Option<Rows>, Vec<bool>)> {
+    let num_rows = batch.num_rows();
+    let times = time.evaluate(batch)?.into_array(num_rows)?;
+    let times = as_int64_array(&cast(&times, &DataType::Int64)?)?.clone();
+    let keys = on
+        .iter()
+        .map(|column| {
+            let array = column.evaluate(batch)?.into_array(num_rows)?;
+            // the row format of dictionaries depends on the converter, so
+            // they are compared on their values
+            match array.data_type() {
+                DataType::Dictionary(_, value_type) => Ok(cast(&array, value_type)?),
+                _ => Ok(array),
+            }
+        })
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    let valid = (0..num_rows)
+        .map(|i| times.is_valid(i) && keys.iter().all(|key| key.is_valid(i)))
+        .collect();
+    let rows = if keys.is_empty() {
+        None
+    } else {
+        let converter = match converter {
+            Some(converter) => converter,
+            None => converter.insert(RowConverter::new(
+                keys.iter()
+                    .map(|key| SortField::new(key.data_type().clone()))
+                    .collect(),
+            )?),
+        };
+        Some(converter.convert_columns(&keys)?)
+    };
+    Ok((times, rows, valid))
+}
+
+/// The group of the row `i`, the row format of its `on` columns
+fn group_key(rows: &Option<Rows>, i: usize) -> &[u8] {
+    match rows {
+        Some(rows) => rows.row(i).as_ref(),
+        None => &[],
+    }
+}
+
+/// Asynchronously collects the right input, and groups its rows by their
+/// `on` columns
+async fn load_grouped_right_table(
+    input: Arc<dyn ExecutionPlan>,
+    on: Vec<Column>,
+    time: Column,
+    context: Arc<TaskContext>,
+    join_metrics: BuildProbeJoinMetrics,
+    reservation: MemoryReservation,
+) -> Result<AsOfJoinRightData> {
+    let (batch, mut reservation) = load_specified_partition_of_input(
+        0,
+        input,
+        context,
+        join_metrics.clone(),
+        reservation,
+    )
+    .await?;
+    let (times, rows, valid) = evaluate_times_and_keys(&batch, &on, &time, &mut None)?;
+
+    let mut groups: HashMap<Box<[u8]>, Vec<(i64, u32)>> = HashMap::new();
+    let mut groups_size = 0;
+    for i in (0..batch.num_rows()).filter(|i| valid[*i]) {
+        let key = group_key(&rows, i);
+        let row = (times.value(i), i as u32);
+        match groups.get_mut(key) {
+            Some(group) => group.push(row),
+            None => {
+                groups_size += key.len() + size_of::<(Box<[u8]>, Vec<(i64, u32)>)>();
+                groups.insert(key.into(), vec![row]);
+            }
+        }
+        groups_size += size_of::<(i64, u32)>();
+    }
+    // the last of the rows with the same time comes last
+    groups.values_mut().for_each(|group| group.sort_unstable());
+    reservation.try_grow(groups_size)?;
+    join_metrics.build_mem_used.add(groups_size);
+
+    Ok(AsOfJoinRightData {
+        batch,
+        groups,
+        _reservation: reservation,
+    })
+}
+
+/// A stream that issues [RecordBatch]es as they arrive from the left input
+/// of the join
+struct AsOfJoinStream {
+    /// Input schema
+    schema: Arc<Schema>,
+    /// type of the join
+    join_type: JoinType,
+    /// the left input
+    left_input: SendableRecordBatchStream,
+    /// the grouped right input
+    right_table: OnceFut<AsOfJoinRightData>,
+    /// The `on` columns of the left input
+    left_on: Vec<Column>,
+    /// The time column of the left input
+    left_time: Column,
+    /// The maximum difference of the times of the joined rows
+    tolerance: Option<i64>,
+    /// Converts the `on` columns of the left input to the row format
+    converter: Option<RowConverter>,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// Join execution metrics
+    join_metrics: BuildProbeJoinMetrics,
+}
+
+impl AsOfJoinStream {
+    fn poll_next_impl(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        let build_timer = self.join_metrics.build_time.timer();
+        let right_data = match ready!(self.right_table.get(cx)) {
+            Ok(data) => data,
+            Err(e) => return Poll::Ready(Some(Err(e))),
+        };
+        build_timer.done();
+
+        match ready!(self.left_input.poll_next_unpin(cx)) {
+            Some(Ok(left_batch)) => {
+                // Setting up timer & updating input metrics
+                self.join_metrics.input_batches.add(1);
+                self.join_metrics.input_rows.add(left_batch.num_rows());
+                let timer = self.join_metrics.join_time.timer();
+
+                let result = self.join_left_batch(right_data, &left_batch);
+
+                // Recording time & updating output metrics
+                if let Ok(batch) = &result {
+                    timer.done();
+                    self.join_metrics.output_batches.add(1);
+                    self.join_metrics.output_rows.add(batch.num_rows());
+                }
+
+                Poll::Ready(Some(result))
+            }
+            other => Poll::Ready(other),
+        }
+    }
+
+    /// Joins the rows of `left_batch` with their latest earlier right row
+    fn join_left_batch(
+        &mut self,
+        right_data: &AsOfJoinRightData,
+        left_batch: &RecordBatch,
+    ) -> Result<RecordBatch> {
+        let (times, rows, valid) = evaluate_times_and_keys(
+            left_batch,
+            &self.left_on,
+            &self.left_time,
+            &mut self.converter,
+        )?;
+
+        let mut left_indices = UInt32Builder::with_capacity(left_batch.num_rows());
+        let mut right_indices = UInt64Builder::with_capacity(left_batch.num_rows());
+        for i in 0..left_batch.num_rows() {
+            let group = match valid[i] {
+                true => right_data.groups.get(group_key(&rows, i)),
+                false => None,
+            };
+            let matched = group.and_then(|group| {
+                let time = times.value(i);
+                let end = group.partition_point(|(t, _)| *t <= time);
+                let (t, index) = group.get(end.checked_sub(1)?)?;
+                let within_tolerance = self.tolerance.map_or(true, |tolerance| {
+                    time.checked_sub(*t).is_some_and(|d| d <= tolerance)
+                });
+                within_tolerance.then_some(*index)
+            });
+            match matched {
+                Some(index) => {
+                    left_indices.append_value(i as u32);
+                    right_indices.append_value(index as u64);
+                }
+                None if self.join_type == JoinType::Left => {
+                    left_indices.append_value(i as u32);
+                    right_indices.append_null();
+                }
+                None => {}
+            }
+        }
+
+        build_batch_from_indices(
+            &self.schema,
+            &right_data.batch,
+            left_batch,
+            &right_indices.finish(),
+            &left_indices.finish(),
+            &self.column_indices,
+            JoinSide::Right,
+        )
+    }
+}
+
+impl Stream for AsOfJoinStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.poll_next_impl(cx)
+    }
+}
+
+impl RecordBatchStream for AsOfJoinStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common, memory::MemoryExec, test::build_table_i32};
+
+    use datafusion_common::assert_batches_eq;
+
+    fn build_table(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<i32>),
+        c: (&str, &Vec<i32>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let batch = build_table_i32(a, b, c);
+        let schema = batch.schema();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    /// The trades of the left table
+    fn build_trades() -> Arc<dyn ExecutionPlan> {
+        build_table(
+            ("ts", &vec![1, 5, 10, 15, 20]),
+            ("stock", &vec![1, 2, 1, 1, 2]),
+            ("qty", &vec![10, 20, 30, 40, 50]),
+        )
+    }
+
+    /// The quotes of the right table
+    fn build_quotes() -> Arc<dyn ExecutionPlan> {
+        build_table(
+            ("ts2", &vec![0, 4, 4, 9, 12, 30]),
+            ("stock2", &vec![1, 2, 1, 1, 2, 1]),
+            ("price", &vec![100, 200, 101, 102, 201, 103]),
+        )
+    }
+
+    async fn join_collect(
+        join_type: &JoinType,
+        tolerance: Option<i64>,
+    ) -> Result<Vec<RecordBatch>> {
+        let left = build_trades();
+        let right = build_quotes();
+        let on = vec![(
+            Column::new_with_schema("stock", &left.schema())?,
+            Column::new_with_schema("stock2", &right.schema())?,
+        )];
+        let time = (
+            Column::new_with_schema("ts", &left.schema())?,
+            Column::new_with_schema("ts2", &right.schema())?,
+        );
+        let join = AsOfJoinExec::try_new(left, right, on, time, tolerance, join_type)?;
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        common::collect(stream).await
+    }
+
+    #[tokio::test]
+    async fn join_inner() -> Result<()> {
+        let batches = join_collect(&JoinType::Inner, None).await?;
+        let expected = [
+            "+----+-------+-----+-----+--------+-------+",
+            "| ts | stock | qty | ts2 | stock2 | price |",
+            "+----+-------+-----+-----+--------+-------+",
+            "| 1  | 1     | 10  | 0   | 1      | 100   |",
+            "| 5  | 2     | 20  | 4   | 2      | 200   |",
+            "| 10 | 1     | 30  | 9   | 1      | 102   |",
+            "| 15 | 1     | 40  | 9   | 1      | 102   |",
+            "| 20 | 2     | 50  | 12  | 2      | 201   |",
+            "+----+-------+-----+-----+--------+-------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_left_with_tolerance() -> Result<()> {
+        let batches = join_collect(&JoinType::Left, Some(5)).await?;
+        let expected = [
+            "+----+-------+-----+-----+--------+-------+",
+            "| ts | stock | qty | ts2 | stock2 | price |",
+            "+----+-------+-----+-----+--------+-------+",
+            "| 1  | 1     | 10  | 0   | 1      | 100   |",
+            "| 5  | 2     | 20  | 4   | 2      | 200   |",
+            "| 10 | 1     | 30  | 9   | 1      | 102   |",
+            "| 15 | 1     | 40  |     |        |       |",
+            "| 20 | 2     | 50  |     |        |       |",
+            "+----+-------+-----+-----+--------+-------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_without_on_columns() -> Result<()> {
+        let left = build_trades();
+        let right = build_quotes();
+        let time = (
+            Column::new_with_schema("ts", &left.schema())?,
+            Column::new_with_schema("ts2", &right.schema())?,
+        );
+        let join =
+            AsOfJoinExec::try_new(left, right, vec![], time, None, &JoinType::Inner)?;
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        let batches = common::collect(stream).await?;
+        // of the quotes at time 4, the last one is joined
+        let expected = [
+            "+----+-------+-----+-----+--------+-------+",
+            "| ts | stock | qty | ts2 | stock2 | price |",
+            "+----+-------+-----+-----+--------+-------+",
+            "| 1  | 1     | 10  | 0   | 1      | 100   |",
+            "| 5  | 2     | 20  | 4   | 1      | 101   |",
+            "| 10 | 1     | 30  | 9   | 1      | 102   |",
+            "| 15 | 1     | 40  | 12  | 2      | 201   |",
+            "| 20 | 2     | 50  | 12  | 2      | 201   |",
+            "+----+-------+-----+-----+--------+-------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+}
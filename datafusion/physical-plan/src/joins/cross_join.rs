@@ -37,7 +37,7 @@ use arrow_array::RecordBatchOptions;
 use datafusion_common::stats::Precision;
 use datafusion_common::{plan_err, DataFusionError, JoinType, Result, ScalarValue};
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
-use datafusion_execution::TaskContext;
+use datafusion_execution::{CancellationToken, TaskContext};
 use datafusion_physical_expr::equivalence::join_equivalence_properties;
 use datafusion_physical_expr::EquivalenceProperties;
 
@@ -232,6 +232,7 @@ impl ExecutionPlan for CrossJoinExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         let stream = self.right.execute(partition, context.clone())?;
+        let cancellation = context.cancellation_token().clone();
 
         let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
 
@@ -255,6 +256,7 @@ impl ExecutionPlan for CrossJoinExec {
             right_batch: Arc::new(parking_lot::Mutex::new(None)),
             left_index: 0,
             join_metrics,
+            cancellation,
         }))
     }
 
@@ -294,12 +296,14 @@ fn stats_cartesian_product(
             distinct_count: s.distinct_count,
             min_value: s.min_value,
             max_value: s.max_value,
+            histogram: s.histogram,
         })
         .chain(right_col_stats.into_iter().map(|s| ColumnStatistics {
             null_count: s.null_count.multiply(&left_row_count),
             distinct_count: s.distinct_count,
             min_value: s.min_value,
             max_value: s.max_value,
+            histogram: s.histogram,
         }))
         .collect();
 
@@ -324,6 +328,8 @@ struct CrossJoinStream {
     right_batch: Arc<parking_lot::Mutex<Option<RecordBatch>>>,
     /// join execution metrics
     join_metrics: BuildProbeJoinMetrics,
+    /// Stops the join when the query is cancelled
+    cancellation: CancellationToken,
 }
 
 impl RecordBatchStream for CrossJoinStream {
@@ -368,6 +374,9 @@ impl Stream for CrossJoinStream {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
+        if let Err(e) = self.cancellation.check() {
+            return Poll::Ready(Some(Err(e)));
+        }
         self.poll_next_impl(cx)
     }
 }
@@ -473,12 +482,14 @@ mod tests {
                     max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
                     min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Exact(1),
                     max_value: Precision::Exact(ScalarValue::from("x")),
                     min_value: Precision::Exact(ScalarValue::from("a")),
                     null_count: Precision::Exact(3),
+                    histogram: None,
                 },
             ],
         };
@@ -491,6 +502,7 @@ mod tests {
                 max_value: Precision::Exact(ScalarValue::Int64(Some(12))),
                 min_value: Precision::Exact(ScalarValue::Int64(Some(0))),
                 null_count: Precision::Exact(2),
+                histogram: None,
             }],
         };
 
@@ -505,18 +517,21 @@ mod tests {
                     max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
                     min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Exact(1),
                     max_value: Precision::Exact(ScalarValue::from("x")),
                     min_value: Precision::Exact(ScalarValue::from("a")),
                     null_count: Precision::Exact(3 * right_row_count),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Exact(3),
                     max_value: Precision::Exact(ScalarValue::Int64(Some(12))),
                     min_value: Precision::Exact(ScalarValue::Int64(Some(0))),
                     null_count: Precision::Exact(2 * left_row_count),
+                    histogram: None,
                 },
             ],
         };
@@ -537,12 +552,14 @@ mod tests {
                     max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
                     min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Exact(1),
                     max_value: Precision::Exact(ScalarValue::from("x")),
                     min_value: Precision::Exact(ScalarValue::from("a")),
                     null_count: Precision::Exact(3),
+                    histogram: None,
                 },
             ],
         };
@@ -555,6 +572,7 @@ mod tests {
                 max_value: Precision::Exact(ScalarValue::Int64(Some(12))),
                 min_value: Precision::Exact(ScalarValue::Int64(Some(0))),
                 null_count: Precision::Exact(2),
+                histogram: None,
             }],
         };
 
@@ -569,18 +587,21 @@ mod tests {
                     max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
                     min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
                     null_count: Precision::Absent, // we don't know the row count on the right
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Exact(1),
                     max_value: Precision::Exact(ScalarValue::from("x")),
                     min_value: Precision::Exact(ScalarValue::from("a")),
                     null_count: Precision::Absent, // we don't know the row count on the right
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Exact(3),
                     max_value: Precision::Exact(ScalarValue::Int64(Some(12))),
                     min_value: Precision::Exact(ScalarValue::Int64(Some(0))),
                     null_count: Precision::Exact(2 * left_row_count),
+                    histogram: None,
                 },
             ],
         };
@@ -0,0 +1,151 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`DynamicFilter`] carries join key bounds from the build side of a
+//! hash join to the scan feeding its probe side.
+
+use std::sync::Arc;
+
+use crate::expressions::{BinaryExpr, Column, Literal};
+use crate::PhysicalExpr;
+
+use datafusion_common::ScalarValue;
+use datafusion_expr::Operator;
+
+use parking_lot::RwLock;
+
+/// Runtime filter populated by [`HashJoinExec`] once its build side has been
+/// collected.
+///
+/// The filter records the minimum and maximum value of every join key on the
+/// build side. A scan on the probe side can use these bounds to skip data
+/// (e.g. parquet row groups) that cannot produce any join matches. Until the
+/// build side is complete the filter is empty and does not exclude anything.
+///
+/// [`HashJoinExec`]: crate::joins::HashJoinExec
+#[derive(Debug, Default)]
+pub struct DynamicFilter {
+    /// `(min, max)` of each join key, in the order of the join's `on` columns
+    bounds: RwLock<Option<Vec<(ScalarValue, ScalarValue)>>>,
+}
+
+impl DynamicFilter {
+    /// Create a new, empty filter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publish the `(min, max)` bounds of the build side join keys
+    pub fn update(&self, bounds: Vec<(ScalarValue, ScalarValue)>) {
+        *self.bounds.write() = Some(bounds);
+    }
+
+    /// Returns true once the build side bounds have been published
+    pub fn is_complete(&self) -> bool {
+        self.bounds.read().is_some()
+    }
+
+    /// Returns true if the build side is known to contain no row with a
+    /// non-null value for every join key, in which case no probe row can
+    /// match.
+    pub fn matches_none(&self) -> bool {
+        match self.bounds.read().as_ref() {
+            Some(bounds) => bounds
+                .iter()
+                .any(|(min, max)| min.is_null() || max.is_null()),
+            None => false,
+        }
+    }
+
+    /// Build a predicate of the form `col >= min AND col <= max` for every
+    /// join key, where `columns` are the probe side columns matching the
+    /// build side keys in order.
+    ///
+    /// Returns `None` if the bounds are not yet known, or no key has usable
+    /// bounds.
+    pub fn predicate(&self, columns: &[Column]) -> Option<Arc<dyn PhysicalExpr>> {
+        let guard = self.bounds.read();
+        let bounds = guard.as_ref()?;
+        columns
+            .iter()
+            .zip(bounds.iter())
+            .filter(|(_, (min, max))| !min.is_null() && !max.is_null())
+            .flat_map(|(column, (min, max))| {
+                let column: Arc<dyn PhysicalExpr> = Arc::new(column.clone());
+                [
+                    binary_expr(&column, Operator::GtEq, min),
+                    binary_expr(&column, Operator::LtEq, max),
+                ]
+            })
+            .reduce(|acc, expr| Arc::new(BinaryExpr::new(acc, Operator::And, expr)))
+    }
+}
+
+fn binary_expr(
+    column: &Arc<dyn PhysicalExpr>,
+    op: Operator,
+    value: &ScalarValue,
+) -> Arc<dyn PhysicalExpr> {
+    Arc::new(BinaryExpr::new(
+        column.clone(),
+        op,
+        Arc::new(Literal::new(value.clone())),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_filter() {
+        let filter = DynamicFilter::new();
+        assert!(!filter.is_complete());
+        assert!(!filter.matches_none());
+        assert!(filter.predicate(&[Column::new("a", 0)]).is_none());
+    }
+
+    #[test]
+    fn predicate_from_bounds() {
+        let filter = DynamicFilter::new();
+        filter.update(vec![
+            (ScalarValue::Int32(Some(1)), ScalarValue::Int32(Some(10))),
+            (
+                ScalarValue::Utf8(Some("a".into())),
+                ScalarValue::Utf8(Some("z".into())),
+            ),
+        ]);
+        assert!(filter.is_complete());
+        assert!(!filter.matches_none());
+
+        let predicate = filter
+            .predicate(&[Column::new("x", 0), Column::new("y", 1)])
+            .unwrap();
+        assert_eq!(
+            predicate.to_string(),
+            "x@0 >= 1 AND x@0 <= 10 AND y@1 >= a AND y@1 <= z"
+        );
+    }
+
+    #[test]
+    fn null_bounds() {
+        let filter = DynamicFilter::new();
+        filter.update(vec![(ScalarValue::Int32(None), ScalarValue::Int32(None))]);
+        assert!(filter.matches_none());
+        assert!(filter.predicate(&[Column::new("x", 0)]).is_none());
+    }
+}
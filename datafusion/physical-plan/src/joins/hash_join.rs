@@ -30,6 +30,7 @@ use crate::joins::utils::{
 };
 use crate::{
     coalesce_partitions::CoalescePartitionsExec,
+    common,
     expressions::Column,
     expressions::PhysicalSortExpr,
     hash_utils::create_hashes,
@@ -39,6 +40,8 @@ use crate::{
         BuildProbeJoinMetrics, ColumnIndex, JoinFilter, JoinOn, StatefulStreamResult,
     },
     metrics::{ExecutionPlanMetricsSet, MetricsSet},
+    repartition::RepartitionExec,
+    stream::RecordBatchStreamAdapter,
     DisplayFormatType, Distribution, ExecutionPlan, Partitioning, PhysicalExpr,
     RecordBatchStream, SendableRecordBatchStream, Statistics,
 };
@@ -46,30 +49,34 @@ use crate::{handle_state, DisplayAs};
 
 use super::{
     utils::{OnceAsync, OnceFut},
-    PartitionMode,
+    DynamicFilter, PartitionMode,
 };
 
 use arrow::array::{
-    Array, ArrayRef, BooleanArray, BooleanBufferBuilder, PrimitiveArray, UInt32Array,
-    UInt64Array,
+    downcast_primitive_array, Array, ArrayRef, AsArray, BooleanArray,
+    BooleanBufferBuilder, PrimitiveArray, UInt32Array, UInt64Array,
 };
 use arrow::compute::kernels::cmp::{eq, not_distinct};
 use arrow::compute::{and, concat_batches, take, FilterBuilder};
-use arrow::datatypes::{Schema, SchemaRef};
+use arrow::datatypes::{ArrowNativeTypeOp, ArrowPrimitiveType, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use arrow::util::bit_util;
 use arrow_array::cast::downcast_array;
 use arrow_schema::ArrowError;
 use datafusion_common::{
     exec_err, internal_err, plan_err, DataFusionError, JoinSide, JoinType, Result,
+    ScalarValue,
 };
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
-use datafusion_execution::TaskContext;
+use datafusion_execution::{CancellationToken, TaskContext};
+use datafusion_expr::Accumulator;
 use datafusion_physical_expr::equivalence::join_equivalence_properties;
+use datafusion_physical_expr::expressions::{MaxAccumulator, MinAccumulator};
 use datafusion_physical_expr::EquivalenceProperties;
 
 use ahash::RandomState;
 use futures::{ready, Stream, StreamExt, TryStreamExt};
+use parking_lot::Mutex;
 
 /// HashTable and input data for the left (build side) of a join
 struct JoinLeftData {
@@ -301,6 +308,17 @@ pub struct HashJoinExec {
     /// Otherwise, rows that have `null`s in the join columns will not be
     /// matched and thus will not appear in the output.
     pub null_equals_null: bool,
+    /// Runtime filter populated with the bounds of the build side join keys
+    dynamic_filter: Option<Arc<DynamicFilter>>,
+    /// The input that a `BROADCAST` hint asks to collect, if any
+    broadcast_hint: Option<JoinSide>,
+    /// Whether a [`PartitionMode::CollectLeft`] join repartitions both inputs
+    /// and joins them partition by partition when its left input does not fit
+    /// in memory
+    partitioned_fallback: bool,
+    /// The [`PartitionMode::Partitioned`] join executed on fallback, created
+    /// once and shared by all partitions
+    fallback_plan: Arc<Mutex<Option<Arc<dyn ExecutionPlan>>>>,
 }
 
 impl HashJoinExec {
@@ -354,9 +372,46 @@ impl HashJoinExec {
             column_indices,
             null_equals_null,
             output_order,
+            dynamic_filter: None,
+            broadcast_hint: None,
+            partitioned_fallback: false,
+            fallback_plan: Default::default(),
         })
     }
 
+    /// Publish the bounds of the build side join keys to `dynamic_filter`
+    /// once the build side has been collected.
+    ///
+    /// Only supported for [`PartitionMode::CollectLeft`], where the whole
+    /// build side is known before any probe side partition is read.
+    pub fn with_dynamic_filter(mut self, dynamic_filter: Arc<DynamicFilter>) -> Self {
+        self.dynamic_filter = Some(dynamic_filter);
+        self
+    }
+
+    /// Mark the `side` input as the one a `BROADCAST` hint asks to collect
+    /// into a single partition, so that the join selection keeps it as the
+    /// build side regardless of statistics, if the join type allows it.
+    pub fn with_broadcast_hint(mut self, side: JoinSide) -> Self {
+        self.broadcast_hint = Some(side);
+        self
+    }
+
+    /// When `partitioned_fallback` is true and the left input of a
+    /// [`PartitionMode::CollectLeft`] join exceeds its memory reservation,
+    /// repartition both inputs on the join keys and execute the join in
+    /// [`PartitionMode::Partitioned`] instead of failing.
+    ///
+    /// As either mode may produce the output, the join does not preserve the
+    /// order or the partitioning of its right input.
+    pub fn with_partitioned_fallback(mut self, partitioned_fallback: bool) -> Self {
+        self.partitioned_fallback = partitioned_fallback;
+        if self.falls_back() {
+            self.output_order = None;
+        }
+        self
+    }
+
     /// left (build) side which gets hashed
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
@@ -392,6 +447,62 @@ impl HashJoinExec {
         self.null_equals_null
     }
 
+    /// The runtime filter populated by this join, if any
+    pub fn dynamic_filter(&self) -> Option<&Arc<DynamicFilter>> {
+        self.dynamic_filter.as_ref()
+    }
+
+    /// The input that a `BROADCAST` hint asks to collect, if any
+    pub fn broadcast_hint(&self) -> Option<JoinSide> {
+        self.broadcast_hint
+    }
+
+    /// Whether the join falls back to [`PartitionMode::Partitioned`] when its
+    /// collected left input does not fit in memory
+    pub fn partitioned_fallback(&self) -> bool {
+        self.partitioned_fallback
+    }
+
+    fn falls_back(&self) -> bool {
+        self.partitioned_fallback && self.mode == PartitionMode::CollectLeft
+    }
+
+    /// Returns the [`PartitionMode::Partitioned`] join executed when the left
+    /// input does not fit in memory, which hash repartitions copies of both
+    /// inputs into as many partitions as the right input has
+    fn partitioned_fallback_plan(&self) -> Result<Arc<dyn ExecutionPlan>> {
+        let mut fallback_plan = self.fallback_plan.lock();
+        if let Some(plan) = fallback_plan.as_ref() {
+            return Ok(plan.clone());
+        }
+
+        let partition_count = self.right.output_partitioning().partition_count();
+        let repartition = |input: &Arc<dyn ExecutionPlan>,
+                           keys: Vec<Column>|
+         -> Result<Arc<dyn ExecutionPlan>> {
+            let keys = keys
+                .into_iter()
+                .map(|key| Arc::new(key) as Arc<dyn PhysicalExpr>)
+                .collect();
+            Ok(Arc::new(RepartitionExec::try_new(
+                unexecuted_copy(input)?,
+                Partitioning::Hash(keys, partition_count),
+            )?))
+        };
+        let (on_left, on_right) = self.on.iter().cloned().unzip();
+        let plan: Arc<dyn ExecutionPlan> = Arc::new(HashJoinExec::try_new(
+            repartition(&self.left, on_left)?,
+            repartition(&self.right, on_right)?,
+            self.on.clone(),
+            self.filter.clone(),
+            &self.join_type,
+            PartitionMode::Partitioned,
+            self.null_equals_null,
+        )?);
+        *fallback_plan = Some(plan.clone());
+        Ok(plan)
+    }
+
     /// Calculate order preservation flags for this hash join.
     fn maintains_input_order(join_type: JoinType) -> Vec<bool> {
         vec![
@@ -504,6 +615,11 @@ impl ExecutionPlan for HashJoinExec {
     }
 
     fn output_partitioning(&self) -> Partitioning {
+        if self.falls_back() {
+            return Partitioning::UnknownPartitioning(
+                self.right.output_partitioning().partition_count(),
+            );
+        }
         let left_columns_len = self.left.schema().fields.len();
         match self.mode {
             PartitionMode::CollectLeft => match self.join_type {
@@ -554,6 +670,9 @@ impl ExecutionPlan for HashJoinExec {
     // are processed sequentially in the probe phase, and unmatched rows are directly output
     // as results, these results tend to retain the order of the probe side table.
     fn maintains_input_order(&self) -> Vec<bool> {
+        if self.falls_back() {
+            return vec![false, false];
+        }
         Self::maintains_input_order(self.join_type)
     }
 
@@ -577,7 +696,7 @@ impl ExecutionPlan for HashJoinExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(HashJoinExec::try_new(
+        let mut join = HashJoinExec::try_new(
             children[0].clone(),
             children[1].clone(),
             self.on.clone(),
@@ -585,7 +704,12 @@ impl ExecutionPlan for HashJoinExec {
             &self.join_type,
             self.mode,
             self.null_equals_null,
-        )?))
+        )?;
+        join.dynamic_filter = self.dynamic_filter.clone();
+        join.broadcast_hint = self.broadcast_hint;
+        Ok(Arc::new(
+            join.with_partitioned_fallback(self.partitioned_fallback),
+        ))
     }
 
     fn execute(
@@ -619,6 +743,7 @@ impl ExecutionPlan for HashJoinExec {
                     context.clone(),
                     join_metrics.clone(),
                     reservation,
+                    self.dynamic_filter.clone(),
                 )
             }),
             PartitionMode::Partitioned => {
@@ -634,6 +759,7 @@ impl ExecutionPlan for HashJoinExec {
                     context.clone(),
                     join_metrics.clone(),
                     reservation,
+                    None,
                 ))
             }
             PartitionMode::Auto => {
@@ -647,11 +773,29 @@ impl ExecutionPlan for HashJoinExec {
         let reservation = MemoryConsumer::new(format!("HashJoinStream[{partition}]"))
             .register(context.memory_pool());
 
+        let fallback = if self.falls_back() {
+            Some((self.partitioned_fallback_plan()?, left_fut.clone()))
+        } else {
+            None
+        };
+
         // we have the batches and the hash map with their keys. We can how create a stream
         // over the right that uses this information to issue new batches.
-        let right_stream = self.right.execute(partition, context)?;
+        let right_stream: SendableRecordBatchStream = if fallback.is_some() {
+            // the right input is only executed once the left input is
+            // collected, as it is read by the fallback plan otherwise
+            let right = self.right.clone();
+            let context = context.clone();
+            Box::pin(RecordBatchStreamAdapter::new(
+                right.schema(),
+                futures::stream::once(async move { right.execute(partition, context) })
+                    .try_flatten(),
+            ))
+        } else {
+            self.right.execute(partition, context.clone())?
+        };
 
-        Ok(Box::pin(HashJoinStream {
+        let stream: SendableRecordBatchStream = Box::pin(HashJoinStream {
             schema: self.schema(),
             on_left,
             on_right,
@@ -662,10 +806,38 @@ impl ExecutionPlan for HashJoinExec {
             random_state: self.random_state.clone(),
             join_metrics,
             null_equals_null: self.null_equals_null,
+            normalize_float_keys: context
+                .session_config()
+                .options()
+                .execution
+                .normalize_float_keys,
             reservation,
             state: HashJoinStreamState::WaitBuildSide,
             build_side: BuildSide::Initial(BuildSideInitialState { left_fut }),
-        }))
+            cancellation: context.cancellation_token().clone(),
+        });
+
+        let Some((fallback_plan, mut left_fut)) = fallback else {
+            return Ok(stream);
+        };
+        let fallback_stream = futures::stream::once(async move {
+            match futures::future::poll_fn(|cx| left_fut.get_shared(cx)).await {
+                Err(e)
+                    if matches!(
+                        e.find_root(),
+                        DataFusionError::ResourcesExhausted(_)
+                    ) =>
+                {
+                    fallback_plan.execute(partition, context)
+                }
+                _ => Ok(stream),
+            }
+        })
+        .try_flatten();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            fallback_stream,
+        )))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -686,8 +858,24 @@ impl ExecutionPlan for HashJoinExec {
     }
 }
 
+/// Copies `plan` and all of its descendants, so that the copy can be executed
+/// independently of `plan`
+fn unexecuted_copy(plan: &Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    let children = plan
+        .children()
+        .iter()
+        .map(unexecuted_copy)
+        .collect::<Result<Vec<_>>>()?;
+    if children.is_empty() {
+        Ok(plan.clone())
+    } else {
+        plan.clone().with_new_children(children)
+    }
+}
+
 /// Reads the left (build) side of the input, buffering it in memory, to build a
 /// hash table (`LeftJoinData`)
+#[allow(clippy::too_many_arguments)]
 async fn collect_left_input(
     partition: Option<usize>,
     random_state: RandomState,
@@ -696,6 +884,7 @@ async fn collect_left_input(
     context: Arc<TaskContext>,
     metrics: BuildProbeJoinMetrics,
     reservation: MemoryReservation,
+    dynamic_filter: Option<Arc<DynamicFilter>>,
 ) -> Result<JoinLeftData> {
     let schema = left.schema();
 
@@ -775,11 +964,52 @@ async fn collect_left_input(
     // Merge all batches into a single batch, so we
     // can directly index into the arrays
     let single_batch = concat_batches(&schema, batches_iter)?;
+
+    // The bounds are only used to prune the probe side, so key types without
+    // min/max support simply leave the filter empty
+    if let Some(dynamic_filter) = dynamic_filter {
+        let normalize_float_keys = context
+            .session_config()
+            .options()
+            .execution
+            .normalize_float_keys;
+        if let Ok(bounds) = join_key_bounds(&on_left, &single_batch, normalize_float_keys)
+        {
+            dynamic_filter.update(bounds);
+        }
+    }
+
     let data = JoinLeftData::new(hashmap, single_batch, reservation);
 
     Ok(data)
 }
 
+/// Computes the `(min, max)` of each of the `on` columns of `batch`
+///
+/// When `normalize_float_keys` is set, floating point keys get null bounds:
+/// the bounds compare in total order, in which e.g. `-0.0` is outside of
+/// `[0.0, 1.0]` although it matches the key `0.0`.
+fn join_key_bounds(
+    on: &[Column],
+    batch: &RecordBatch,
+    normalize_float_keys: bool,
+) -> Result<Vec<(ScalarValue, ScalarValue)>> {
+    on.iter()
+        .map(|column| {
+            let array = column.evaluate(batch)?.into_array(batch.num_rows())?;
+            if normalize_float_keys && array.data_type().is_floating() {
+                let null = ScalarValue::try_from(array.data_type())?;
+                return Ok((null.clone(), null));
+            }
+            let mut min = MinAccumulator::try_new(array.data_type())?;
+            let mut max = MaxAccumulator::try_new(array.data_type())?;
+            min.update_batch(&[array.clone()])?;
+            max.update_batch(&[array])?;
+            Ok((min.evaluate()?, max.evaluate()?))
+        })
+        .collect()
+}
+
 /// Updates `hash_map` with new entries from `batch` evaluated against the expressions `on`
 /// using `offset` as a start value for `batch` row indices.
 ///
@@ -954,12 +1184,16 @@ struct HashJoinStream {
     column_indices: Vec<ColumnIndex>,
     /// If null_equals_null is true, null == null else null != null
     null_equals_null: bool,
+    /// If true, all NaNs are equal keys and so are -0.0 and +0.0
+    normalize_float_keys: bool,
     /// Memory reservation
     reservation: MemoryReservation,
     /// State of the stream
     state: HashJoinStreamState,
     /// Build side
     build_side: BuildSide,
+    /// Stops the join when the query is cancelled
+    cancellation: CancellationToken,
 }
 
 impl RecordBatchStream for HashJoinStream {
@@ -1027,18 +1261,24 @@ pub fn build_equal_condition_join_indices<T: JoinHashMapType>(
     build_side: JoinSide,
     deleted_offset: Option<usize>,
     fifo_hashmap: bool,
+    normalize_float_keys: bool,
 ) -> Result<(UInt64Array, UInt32Array)> {
-    let keys_values = probe_on
-        .iter()
-        .map(|c| c.evaluate(probe_batch)?.into_array(probe_batch.num_rows()))
-        .collect::<Result<Vec<_>>>()?;
-    let build_join_values = build_on
-        .iter()
-        .map(|c| {
-            c.evaluate(build_input_buffer)?
-                .into_array(build_input_buffer.num_rows())
-        })
-        .collect::<Result<Vec<_>>>()?;
+    // NaNs and signed zeros already hash the same, so they only need to be
+    // normalized for the equality check of the matched rows
+    let evaluate_keys = |on: &[Column], batch: &RecordBatch| {
+        on.iter()
+            .map(|c| {
+                let array = c.evaluate(batch)?.into_array(batch.num_rows())?;
+                Ok(if normalize_float_keys {
+                    common::normalize_float_keys(array)
+                } else {
+                    array
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+    let keys_values = evaluate_keys(probe_on, probe_batch)?;
+    let build_join_values = evaluate_keys(build_on, build_input_buffer)?;
     hashes_buffer.clear();
     hashes_buffer.resize(probe_batch.num_rows(), 0);
     let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
@@ -1130,6 +1370,23 @@ pub fn equal_rows_arr(
     right_arrays: &[ArrayRef],
     null_equals_null: bool,
 ) -> Result<(UInt64Array, UInt32Array)> {
+    if let ([left], [right]) = (left_arrays, right_arrays) {
+        let (left, right) = (left.as_ref(), right.as_ref());
+        if left.null_count() == 0
+            && right.null_count() == 0
+            && indices_left.null_count() == 0
+            && indices_right.null_count() == 0
+            && left.data_type() == right.data_type()
+        {
+            downcast_primitive_array! {
+                left => {
+                    return Ok(equal_rows_primitive(indices_left, indices_right, left, right));
+                }
+                _ => {}
+            }
+        }
+    }
+
     let mut iter = left_arrays.iter().zip(right_arrays.iter());
 
     let (first_left, first_right) = iter.next().ok_or_else(|| {
@@ -1165,6 +1422,26 @@ pub fn equal_rows_arr(
     ))
 }
 
+/// [`equal_rows_arr`] for a single primitive key without nulls, comparing
+/// the values of the key at the matched indices in place, rather than taking
+/// them into new arrays to compare
+fn equal_rows_primitive<T: ArrowPrimitiveType>(
+    indices_left: &UInt64Array,
+    indices_right: &UInt32Array,
+    left: &PrimitiveArray<T>,
+    right: &dyn Array,
+) -> (UInt64Array, UInt32Array) {
+    let left_values = left.values();
+    let right_values = right.as_primitive::<T>().values();
+    let (matched_left, matched_right): (Vec<u64>, Vec<u32>) = indices_left
+        .values()
+        .iter()
+        .zip(indices_right.values().iter())
+        .filter(|(&l, &r)| left_values[l as usize].is_eq(right_values[r as usize]))
+        .unzip();
+    (matched_left.into(), matched_right.into())
+}
+
 impl HashJoinStream {
     /// Separate implementation function that unpins the [`HashJoinStream`] so
     /// that partial borrows work correctly
@@ -1181,6 +1458,10 @@ impl HashJoinStream {
                     handle_state!(ready!(self.fetch_probe_batch(cx)))
                 }
                 HashJoinStreamState::ProcessProbeBatch(_) => {
+                    if let Err(e) = self.cancellation.check() {
+                        self.state = HashJoinStreamState::Completed;
+                        return Poll::Ready(Some(Err(e)));
+                    }
                     handle_state!(self.process_probe_batch())
                 }
                 HashJoinStreamState::ExhaustedProbeSide => {
@@ -1292,6 +1573,7 @@ impl HashJoinStream {
             JoinSide::Left,
             None,
             true,
+            self.normalize_float_keys,
         );
 
         let result = match left_right_indices {
@@ -1404,8 +1686,10 @@ mod tests {
         repartition::RepartitionExec, test::build_table_i32, test::exec::MockExec,
     };
 
-    use arrow::array::{ArrayRef, Date32Array, Int32Array, UInt32Builder, UInt64Builder};
-    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::array::{
+        ArrayRef, Date32Array, DictionaryArray, Int32Array, UInt32Builder, UInt64Builder,
+    };
+    use arrow::datatypes::{DataType, Field, Int32Type, Schema};
     use datafusion_common::{
         assert_batches_eq, assert_batches_sorted_eq, assert_contains, ScalarValue,
     };
@@ -1533,6 +1817,38 @@ mod tests {
         Ok((columns, batches))
     }
 
+    #[tokio::test]
+    async fn join_publishes_dynamic_filter() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 8]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b1", &right.schema())?,
+        )];
+
+        let dynamic_filter = Arc::new(DynamicFilter::new());
+        let join = join(left, right, on, &JoinType::Inner, false)?
+            .with_dynamic_filter(dynamic_filter.clone());
+        assert!(!dynamic_filter.is_complete());
+
+        let stream = join.execute(0, task_ctx)?;
+        common::collect(stream).await?;
+
+        let predicate = dynamic_filter.predicate(&[Column::new("b1", 1)]).unwrap();
+        assert_eq!(predicate.to_string(), "b1@1 >= 4 AND b1@1 <= 8");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_inner_one() -> Result<()> {
         let task_ctx = Arc::new(TaskContext::default());
@@ -2860,6 +3176,7 @@ mod tests {
             JoinSide::Left,
             None,
             false,
+            true,
         )?;
 
         let mut left_ids = UInt64Builder::with_capacity(0);
@@ -2877,6 +3194,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn equal_rows_single_primitive_key() -> Result<()> {
+        let left: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        let right: ArrayRef = Arc::new(Int32Array::from(vec![1, 3, 3]));
+        let indices_left = UInt64Array::from(vec![0, 1, 2, 2, 0]);
+        let indices_right = UInt32Array::from(vec![0, 1, 1, 2, 2]);
+
+        let expected = (
+            UInt64Array::from(vec![0, 2, 2]),
+            UInt32Array::from(vec![0, 1, 2]),
+        );
+        let single_key = equal_rows_arr(
+            &indices_left,
+            &indices_right,
+            &[left.clone()],
+            &[right.clone()],
+            false,
+        )?;
+        assert_eq!(single_key, expected);
+
+        // the same key twice is compared by the kernels
+        let two_keys = equal_rows_arr(
+            &indices_left,
+            &indices_right,
+            &[left.clone(), left],
+            &[right.clone(), right],
+            false,
+        )?;
+        assert_eq!(two_keys, expected);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_with_duplicated_column_names() -> Result<()> {
         let task_ctx = Arc::new(TaskContext::default());
@@ -3154,6 +3503,64 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn join_dictionary() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "k",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                true,
+            ),
+            Field::new("n", DataType::Int32, false),
+        ]));
+        let batch = |keys: Vec<Option<&str>>, n: Vec<i32>| {
+            let keys: DictionaryArray<Int32Type> = keys.into_iter().collect();
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(keys), Arc::new(Int32Array::from(n))],
+            )
+        };
+
+        // the keys are matched by value, whatever their dictionary
+        let left = vec![batch(
+            vec![Some("a"), Some("b"), None, Some("a")],
+            vec![1, 2, 3, 4],
+        )?];
+        let right = vec![
+            batch(vec![Some("b"), Some("c")], vec![5, 6])?,
+            batch(vec![None, Some("a")], vec![7, 8])?,
+        ];
+        let left = Arc::new(MemoryExec::try_new(&[left], schema.clone(), None)?);
+        let right = Arc::new(MemoryExec::try_new(&[right], schema.clone(), None)?);
+        let on = vec![(
+            Column::new_with_schema("k", &schema)?,
+            Column::new_with_schema("k", &schema)?,
+        )];
+
+        let (_, batches) = join_collect(
+            left,
+            right,
+            on,
+            &JoinType::Inner,
+            false,
+            Arc::new(TaskContext::default()),
+        )
+        .await?;
+
+        let expected = [
+            "+---+---+---+---+",
+            "| k | n | k | n |",
+            "+---+---+---+---+",
+            "| a | 1 | a | 8 |",
+            "| a | 4 | a | 8 |",
+            "| b | 2 | b | 5 |",
+            "+---+---+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_with_error_right() {
         let left = build_table(
@@ -3262,6 +3669,68 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn single_partition_join_partitioned_fallback() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b1", &right.schema())?,
+        )];
+
+        // the fallback repartitions both inputs and joins them partition by partition
+        let join_exec = join(
+            left.clone(),
+            right.clone(),
+            on.clone(),
+            &JoinType::Inner,
+            false,
+        )?
+        .with_partitioned_fallback(true);
+        assert!(matches!(
+            join_exec.output_partitioning(),
+            Partitioning::UnknownPartitioning(1)
+        ));
+        assert_eq!(join_exec.maintains_input_order(), vec![false, false]);
+
+        let fallback_plan = join_exec.partitioned_fallback_plan()?;
+        let batches =
+            crate::collect(fallback_plan, Arc::new(TaskContext::default())).await?;
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 5  | 9  | 20 | 5  | 80 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // the fallback plan is executed when the left input exceeds its reservation
+        let runtime_config = RuntimeConfig::new().with_memory_limit(100, 1.0);
+        let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(runtime));
+
+        let join_exec = join(left, right, on, &JoinType::Inner, false)?
+            .with_partitioned_fallback(true);
+        let stream = join_exec.execute(0, task_ctx)?;
+        let err = common::collect(stream).await.unwrap_err();
+
+        assert_contains!(err.to_string(), "Resources exhausted");
+        assert!(!err.to_string().contains("for HashJoinInput with"), "{err}");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn partitioned_join_overallocation() -> Result<()> {
         // Prepare partitioned inputs for HashJoinExec
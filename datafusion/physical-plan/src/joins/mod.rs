@@ -17,15 +17,21 @@
 
 //! DataFusion Join implementations
 
+pub use asof_join::AsOfJoinExec;
 pub use cross_join::CrossJoinExec;
+pub use dynamic_filter::DynamicFilter;
 pub use hash_join::HashJoinExec;
 pub use nested_loop_join::NestedLoopJoinExec;
+pub use range_join::{RangeJoinCondition, RangeJoinExec};
 // Note: SortMergeJoin is not used in plans yet
 pub use sort_merge_join::SortMergeJoinExec;
 pub use symmetric_hash_join::SymmetricHashJoinExec;
+mod asof_join;
 mod cross_join;
+mod dynamic_filter;
 mod hash_join;
 mod nested_loop_join;
+mod range_join;
 mod sort_merge_join;
 mod stream_join_utils;
 mod symmetric_hash_join;
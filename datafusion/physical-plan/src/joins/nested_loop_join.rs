@@ -26,8 +26,8 @@ use std::task::Poll;
 
 use crate::coalesce_batches::concat_batches;
 use crate::joins::utils::{
-    append_right_indices, apply_join_filter_to_indices, build_batch_from_indices,
-    build_join_schema, check_join_is_valid, estimate_join_statistics, get_anti_indices,
+    append_right_indices, build_batch_from_indices, build_join_schema,
+    check_join_is_valid, estimate_join_statistics, get_anti_indices,
     get_anti_u64_indices, get_final_indices_from_bit_map, get_semi_indices,
     get_semi_u64_indices, partitioned_join_output_partitioning, BuildProbeJoinMetrics,
     ColumnIndex, JoinFilter, OnceAsync, OnceFut,
@@ -39,22 +39,26 @@ use crate::{
 };
 
 use arrow::array::{
-    BooleanBufferBuilder, UInt32Array, UInt32Builder, UInt64Array, UInt64Builder,
+    downcast_array, Array, BooleanBufferBuilder, UInt32Array, UInt32Builder, UInt64Array,
+    UInt64Builder,
 };
+use arrow::compute::{self, filter_record_batch};
 use arrow::datatypes::{Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use arrow::util::bit_util;
-use datafusion_common::{exec_err, DataFusionError, JoinSide, Result, Statistics};
+use datafusion_common::cast::as_boolean_array;
+use datafusion_common::{DataFusionError, JoinSide, Result, Statistics};
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
-use datafusion_execution::TaskContext;
+use datafusion_execution::{CancellationToken, TaskContext};
 use datafusion_expr::JoinType;
 use datafusion_physical_expr::equivalence::join_equivalence_properties;
+use datafusion_physical_expr::utils::split_conjunction;
 use datafusion_physical_expr::{EquivalenceProperties, PhysicalSortExpr};
 
 use futures::{ready, Stream, StreamExt, TryStreamExt};
 
 /// Data of the inner table side
-type JoinLeftData = (RecordBatch, MemoryReservation);
+pub(crate) type JoinLeftData = (RecordBatch, MemoryReservation);
 
 /// NestedLoopJoinExec executes partitions in parallel.
 /// One input will be collected to a single partition, call it inner-table.
@@ -226,6 +230,8 @@ impl ExecutionPlan for NestedLoopJoinExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
+        let block_size = context.session_config().batch_size();
+        let cancellation = context.cancellation_token().clone();
 
         // Initialization reservation for load of inner table
         let load_reservation =
@@ -276,6 +282,8 @@ impl ExecutionPlan for NestedLoopJoinExec {
             column_indices: self.column_indices.clone(),
             join_metrics,
             reservation,
+            block_size,
+            cancellation,
         }))
     }
 
@@ -296,7 +304,7 @@ impl ExecutionPlan for NestedLoopJoinExec {
 
 // For the nested loop join, different join type need the different distribution for
 // left and right node.
-fn distribution_from_join_type(join_type: &JoinType) -> Vec<Distribution> {
+pub(crate) fn distribution_from_join_type(join_type: &JoinType) -> Vec<Distribution> {
     match join_type {
         JoinType::Inner | JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti => {
             // need the left data, and the right should be one partition
@@ -320,7 +328,7 @@ fn distribution_from_join_type(join_type: &JoinType) -> Vec<Distribution> {
 }
 
 /// Asynchronously collect the specified partition data of the input
-async fn load_specified_partition_of_input(
+pub(crate) async fn load_specified_partition_of_input(
     partition: usize,
     input: Arc<dyn ExecutionPlan>,
     context: Arc<TaskContext>,
@@ -387,33 +395,98 @@ struct NestedLoopJoinStream {
     join_metrics: BuildProbeJoinMetrics,
     /// Memory reservation for visited_left_side
     reservation: MemoryReservation,
+    /// The number of pairs of rows the join filter is evaluated on at once
+    block_size: usize,
+    /// Stops the join when the query is cancelled
+    cancellation: CancellationToken,
 }
 
+/// Returns the pairs of rows of `left_batch` and `right_batch` satisfying
+/// `filter`, ordered by left row and then by right row.
+///
+/// Rather than evaluating the filter once per left row, it is evaluated on
+/// blocks of the cartesian product of the batches of about `block_size`
+/// pairs. The conjuncts of the filter are evaluated one at a time, only on
+/// the pairs satisfying the previous conjuncts, and the evaluation of a
+/// block stops as soon as none of its pairs is left.
 fn build_join_indices(
-    left_row_index: usize,
-    right_batch: &RecordBatch,
     left_batch: &RecordBatch,
+    right_batch: &RecordBatch,
     filter: Option<&JoinFilter>,
+    block_size: usize,
 ) -> Result<(UInt64Array, UInt32Array)> {
-    // left indices: [left_index, left_index, ...., left_index]
-    // right indices: [0, 1, 2, 3, 4,....,right_row_count]
-
+    let left_row_count = left_batch.num_rows();
     let right_row_count = right_batch.num_rows();
-    let left_indices = UInt64Array::from(vec![left_row_index as u64; right_row_count]);
-    let right_indices = UInt32Array::from_iter_values(0..(right_row_count as u32));
-    // in the nested loop join, the filter can contain non-equal and equal condition.
-    if let Some(filter) = filter {
-        apply_join_filter_to_indices(
-            left_batch,
-            right_batch,
-            left_indices,
-            right_indices,
-            filter,
-            JoinSide::Left,
-        )
-    } else {
-        Ok((left_indices, right_indices))
+    let block_left_rows = (block_size / right_row_count.max(1)).max(1);
+
+    let mut left_indices_builder = UInt64Builder::new();
+    let mut right_indices_builder = UInt32Builder::new();
+    for block_start in (0..left_row_count).step_by(block_left_rows) {
+        let block_end = (block_start + block_left_rows).min(left_row_count);
+        // left indices: [l, l, ..., l, l + 1, l + 1, ..., l + 1, ...]
+        // right indices: [0, 1, ..., right_row_count - 1, 0, 1, ...]
+        let left_indices = UInt64Array::from_iter_values(
+            (block_start..block_end)
+                .flat_map(|l| std::iter::repeat(l as u64).take(right_row_count)),
+        );
+        let right_indices = UInt32Array::from_iter_values(
+            (block_start..block_end).flat_map(|_| 0..right_row_count as u32),
+        );
+        // in the nested loop join, the filter can contain non-equal and equal condition.
+        let (left_indices, right_indices) = match filter {
+            Some(filter) => apply_join_filter_to_block(
+                left_batch,
+                right_batch,
+                left_indices,
+                right_indices,
+                filter,
+            )?,
+            None => (left_indices, right_indices),
+        };
+        left_indices_builder.append_slice(left_indices.values());
+        right_indices_builder.append_slice(right_indices.values());
+    }
+    Ok((
+        left_indices_builder.finish(),
+        right_indices_builder.finish(),
+    ))
+}
+
+/// Returns the pairs of rows of a block of the cartesian product satisfying
+/// `filter`, evaluating its conjuncts one at a time on the remaining pairs
+fn apply_join_filter_to_block(
+    left_batch: &RecordBatch,
+    right_batch: &RecordBatch,
+    mut left_indices: UInt64Array,
+    mut right_indices: UInt32Array,
+    filter: &JoinFilter,
+) -> Result<(UInt64Array, UInt32Array)> {
+    let mut intermediate_batch = build_batch_from_indices(
+        filter.schema(),
+        left_batch,
+        right_batch,
+        &left_indices,
+        &right_indices,
+        filter.column_indices(),
+        JoinSide::Left,
+    )?;
+    for conjunct in split_conjunction(filter.expression()) {
+        if intermediate_batch.num_rows() == 0 {
+            break;
+        }
+        let result = conjunct
+            .evaluate(&intermediate_batch)?
+            .into_array(intermediate_batch.num_rows())?;
+        let mask = as_boolean_array(&result)?;
+        // null results do not satisfy the filter either
+        if mask.null_count() == 0 && mask.true_count() == mask.len() {
+            continue;
+        }
+        intermediate_batch = filter_record_batch(&intermediate_batch, mask)?;
+        left_indices = downcast_array(compute::filter(&left_indices, mask)?.as_ref());
+        right_indices = downcast_array(compute::filter(&right_indices, mask)?.as_ref());
     }
+    Ok((left_indices, right_indices))
 }
 
 impl NestedLoopJoinStream {
@@ -468,6 +541,7 @@ impl NestedLoopJoinStream {
                         &self.column_indices,
                         &self.schema,
                         visited_left_side,
+                        self.block_size,
                     );
 
                     // Recording time & updating output metrics
@@ -553,6 +627,7 @@ impl NestedLoopJoinStream {
                         &self.column_indices,
                         &self.schema,
                         &mut empty_visited_left_side,
+                        self.block_size,
                     );
 
                     // Recording time & updating output metrics
@@ -570,6 +645,7 @@ impl NestedLoopJoinStream {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn join_left_and_right_batch(
     left_batch: &RecordBatch,
     right_batch: &RecordBatch,
@@ -578,65 +654,45 @@ fn join_left_and_right_batch(
     column_indices: &[ColumnIndex],
     schema: &Schema,
     visited_left_side: &mut BooleanBufferBuilder,
+    block_size: usize,
 ) -> Result<RecordBatch> {
-    let indices_result = (0..left_batch.num_rows())
-        .map(|left_row_index| {
-            build_join_indices(left_row_index, right_batch, left_batch, filter)
-        })
-        .collect::<Result<Vec<(UInt64Array, UInt32Array)>>>();
-
-    let mut left_indices_builder = UInt64Builder::new();
-    let mut right_indices_builder = UInt32Builder::new();
-    let left_right_indices = match indices_result {
-        Err(err) => {
-            exec_err!("Fail to build join indices in NestedLoopJoinExec, error:{err}")
-        }
-        Ok(indices) => {
-            for (left_side, right_side) in indices {
-                left_indices_builder
-                    .append_values(left_side.values(), &vec![true; left_side.len()]);
-                right_indices_builder
-                    .append_values(right_side.values(), &vec![true; right_side.len()]);
-            }
-            Ok((
-                left_indices_builder.finish(),
-                right_indices_builder.finish(),
-            ))
-        }
-    };
-    match left_right_indices {
-        Ok((left_side, right_side)) => {
-            // set the left bitmap
-            // and only full join need the left bitmap
-            if join_type == JoinType::Full {
-                left_side.iter().flatten().for_each(|x| {
-                    visited_left_side.set_bit(x as usize, true);
-                });
-            }
-            // adjust the two side indices base on the join type
-            let (left_side, right_side) = adjust_indices_by_join_type(
-                left_side,
-                right_side,
-                left_batch.num_rows(),
-                right_batch.num_rows(),
-                join_type,
-            );
+    let (left_side, right_side) =
+        build_join_indices(left_batch, right_batch, filter, block_size).map_err(
+            |err| {
+                DataFusionError::Execution(format!(
+                    "Fail to build join indices in NestedLoopJoinExec, error:{err}"
+                ))
+            },
+        )?;
 
-            build_batch_from_indices(
-                schema,
-                left_batch,
-                right_batch,
-                &left_side,
-                &right_side,
-                column_indices,
-                JoinSide::Left,
-            )
-        }
-        Err(e) => Err(e),
+    // set the left bitmap
+    // and only full join need the left bitmap
+    if join_type == JoinType::Full {
+        left_side.iter().flatten().for_each(|x| {
+            visited_left_side.set_bit(x as usize, true);
+        });
     }
+    // adjust the two side indices base on the join type
+    let (left_side, right_side) = adjust_indices_by_join_type(
+        left_side,
+        right_side,
+        left_batch.num_rows(),
+        right_batch.num_rows(),
+        join_type,
+    );
+
+    build_batch_from_indices(
+        schema,
+        left_batch,
+        right_batch,
+        &left_side,
+        &right_side,
+        column_indices,
+        JoinSide::Left,
+    )
 }
 
-fn adjust_indices_by_join_type(
+pub(crate) fn adjust_indices_by_join_type(
     left_indices: UInt64Array,
     right_indices: UInt32Array,
     count_left_batch: usize,
@@ -725,6 +781,9 @@ impl Stream for NestedLoopJoinStream {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
+        if let Err(e) = self.cancellation.check() {
+            return Poll::Ready(Some(Err(e)));
+        }
         if left_is_build_side(self.join_type) {
             self.poll_next_impl_for_build_left(cx)
         } else {
@@ -751,6 +810,7 @@ mod tests {
 
     use arrow::datatypes::{DataType, Field};
     use datafusion_common::{assert_batches_sorted_eq, assert_contains, ScalarValue};
+    use datafusion_execution::config::SessionConfig;
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
     use datafusion_expr::Operator;
     use datafusion_physical_expr::expressions::{BinaryExpr, Literal};
@@ -1167,6 +1227,40 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn join_with_filter_in_small_blocks() -> Result<()> {
+        // a batch size of 2 evaluates the filter on blocks of a single left row
+        let session_config = SessionConfig::new().with_batch_size(2);
+        let task_ctx = TaskContext::default().with_session_config(session_config);
+        let task_ctx = Arc::new(task_ctx);
+        let left = build_left_table();
+        let right = build_right_table();
+
+        let filter = prepare_join_filter();
+        let (columns, batches) = multi_partitioned_join_collect(
+            left,
+            right,
+            &JoinType::Left,
+            Some(filter),
+            task_ctx,
+        )
+        .await?;
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+        let expected = [
+            "+----+----+-----+----+----+----+",
+            "| a1 | b1 | c1  | a2 | b2 | c2 |",
+            "+----+----+-----+----+----+----+",
+            "| 11 | 8  | 110 |    |    |    |",
+            "| 5  | 5  | 50  | 2  | 2  | 80 |",
+            "| 9  | 8  | 90  |    |    |    |",
+            "+----+----+-----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
     /// Returns the column names on the schema
     fn columns(schema: &Schema) -> Vec<String> {
         schema.fields().iter().map(|f| f.name().clone()).collect()
@@ -0,0 +1,982 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the range join plan, joining the rows of an input with the rows
+//! of the other input whose values are within a range, such as
+//! `a.ts BETWEEN b.start AND b.end`, or whose ranges overlap, such as
+//! `a.start <= b.end AND a.end >= b.start`.
+
+use std::any::Any;
+use std::fmt::{self, Formatter};
+use std::sync::Arc;
+use std::task::Poll;
+
+use crate::joins::nested_loop_join::{
+    adjust_indices_by_join_type, distribution_from_join_type, left_is_build_side,
+    load_specified_partition_of_input,
+};
+use crate::joins::utils::{
+    apply_join_filter_to_indices, build_batch_from_indices, build_join_schema,
+    check_join_is_valid, estimate_join_statistics, get_final_indices_from_bit_map,
+    partitioned_join_output_partitioning, BuildProbeJoinMetrics, ColumnIndex, JoinFilter,
+    OnceAsync, OnceFut,
+};
+use crate::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
+    RecordBatchStream, SendableRecordBatchStream,
+};
+
+use arrow::array::{
+    ArrayRef, BooleanBufferBuilder, UInt32Array, UInt32Builder, UInt64Array,
+    UInt64Builder,
+};
+use arrow::compute::take;
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use arrow::row::{RowConverter, Rows, SortField};
+use arrow::util::bit_util;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{plan_err, DataFusionError, JoinSide, Result, Statistics};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_expr::{JoinType, Operator};
+use datafusion_physical_expr::equivalence::join_equivalence_properties;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column};
+use datafusion_physical_expr::utils::{collect_columns, split_conjunction};
+use datafusion_physical_expr::{EquivalenceProperties, PhysicalExpr, PhysicalSortExpr};
+
+use futures::{ready, Stream, StreamExt};
+
+/// An inequality `left op right` of a join filter between an expression of
+/// the left input and an expression of the right input
+#[derive(Debug, Clone)]
+pub struct RangeJoinCondition {
+    /// The expression of the left input, on the schema of the left input
+    pub left: Arc<dyn PhysicalExpr>,
+    /// One of `<`, `<=`, `>` and `>=`
+    pub op: Operator,
+    /// The expression of the right input, on the schema of the right input
+    pub right: Arc<dyn PhysicalExpr>,
+}
+
+impl fmt::Display for RangeJoinCondition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.left, self.op, self.right)
+    }
+}
+
+impl RangeJoinCondition {
+    /// Whether the condition bounds the left expression from above
+    fn is_upper_bound(&self) -> bool {
+        matches!(self.op, Operator::Lt | Operator::LtEq)
+    }
+}
+
+/// Returns the inequalities of the conjunction `filter` comparing an
+/// expression of the left input with an expression of the same type of the
+/// right input
+fn range_join_conditions(
+    filter: &JoinFilter,
+    left_schema: &Schema,
+    right_schema: &Schema,
+) -> Result<Vec<RangeJoinCondition>> {
+    let mut conditions = vec![];
+    for expr in split_conjunction(filter.expression()) {
+        let Some(binary) = expr.as_any().downcast_ref::<BinaryExpr>() else {
+            continue;
+        };
+        let op = *binary.op();
+        if !matches!(
+            op,
+            Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+        ) {
+            continue;
+        }
+        let (left, op, right) = match (
+            expression_side(binary.left(), filter),
+            expression_side(binary.right(), filter),
+        ) {
+            (Some(JoinSide::Left), Some(JoinSide::Right)) => {
+                (binary.left(), op, binary.right())
+            }
+            (Some(JoinSide::Right), Some(JoinSide::Left)) => {
+                // `right op left` is `left swapped_op right`
+                (binary.right(), op.swap().unwrap(), binary.left())
+            }
+            _ => continue,
+        };
+        let left = rewrite_filter_columns(left, filter)?;
+        let right = rewrite_filter_columns(right, filter)?;
+        let left_type = left.data_type(left_schema)?;
+        if left_type != right.data_type(right_schema)?
+            || !RowConverter::supports_fields(&[SortField::new(left_type)])
+        {
+            continue;
+        }
+        conditions.push(RangeJoinCondition { left, op, right });
+    }
+    Ok(conditions)
+}
+
+/// Returns the input all the columns of the filter expression `expr` refer
+/// to, or `None` if it refers to no column or to both inputs
+fn expression_side(
+    expr: &Arc<dyn PhysicalExpr>,
+    filter: &JoinFilter,
+) -> Option<JoinSide> {
+    let mut sides = collect_columns(expr)
+        .into_iter()
+        .map(|column| filter.column_indices()[column.index()].side);
+    let side = sides.next()?;
+    sides.all(|s| s == side).then_some(side)
+}
+
+/// Rewrites the filter expression `expr` from the intermediate schema of
+/// `filter` to the schema of the input it refers to
+fn rewrite_filter_columns(
+    expr: &Arc<dyn PhysicalExpr>,
+    filter: &JoinFilter,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    expr.clone().transform_up(&|expr| {
+        Ok(match expr.as_any().downcast_ref::<Column>() {
+            Some(column) => {
+                let ColumnIndex { index, .. } = filter.column_indices()[column.index()];
+                Transformed::Yes(Arc::new(Column::new(column.name(), index)))
+            }
+            None => Transformed::No(expr),
+        })
+    })
+}
+
+/// Data of the inner table side, sorted on the range key
+struct RangeJoinInnerData {
+    /// The rows of the inner table, sorted on `keys`
+    batch: RecordBatch,
+    /// The sorted range keys of the rows, with their nulls first
+    keys: ArrayRef,
+    /// Memory reservation of the inner table
+    _reservation: MemoryReservation,
+}
+
+/// RangeJoinExec joins the rows of its inputs satisfying a join filter that
+/// bounds an expression of one input both from below and from above with
+/// expressions of the other input, such as
+///
+/// ```text
+/// a.ts BETWEEN b.start AND b.end
+/// a.start <= b.end AND a.end >= b.start
+/// ```
+///
+/// Like [`NestedLoopJoinExec`], one input is collected into a single
+/// partition, the inner table, as decided by the join type. The inner table
+/// is sorted on its expression of one of the bounds, so that the rows of the
+/// inner table within that bound of each row of the other input are found
+/// by a binary search rather than by comparing every pair of rows. The join
+/// filter is only evaluated on these candidate pairs.
+///
+/// [`NestedLoopJoinExec`]: crate::joins::NestedLoopJoinExec
+#[derive(Debug)]
+pub struct RangeJoinExec {
+    /// left side
+    left: Arc<dyn ExecutionPlan>,
+    /// right side
+    right: Arc<dyn ExecutionPlan>,
+    /// Filters which are applied while finding matching rows
+    filter: JoinFilter,
+    /// The bound of the filter used to find the candidate rows
+    range: RangeJoinCondition,
+    /// How the join is performed
+    join_type: JoinType,
+    /// The schema once the join is applied
+    schema: SchemaRef,
+    /// Build-side data
+    inner_table: OnceAsync<RangeJoinInnerData>,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl RangeJoinExec {
+    /// Try to create a new [`RangeJoinExec`], returning an error if `filter`
+    /// is not a range join filter, see [`Self::is_range_join`]
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        filter: JoinFilter,
+        join_type: &JoinType,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        check_join_is_valid(&left_schema, &right_schema, &[])?;
+        let conditions = range_join_conditions(&filter, &left_schema, &right_schema)?;
+        if !is_range(&conditions) {
+            return plan_err!(
+                "RangeJoinExec requires a filter bounding an expression from below and from above, got {}",
+                filter.expression()
+            );
+        }
+        let range = conditions.into_iter().next().unwrap();
+        let (schema, column_indices) =
+            build_join_schema(&left_schema, &right_schema, join_type);
+        Ok(RangeJoinExec {
+            left,
+            right,
+            filter,
+            range,
+            join_type: *join_type,
+            schema: Arc::new(schema),
+            inner_table: Default::default(),
+            column_indices,
+            metrics: Default::default(),
+        })
+    }
+
+    /// Whether the join `filter` of inputs with schemas `left_schema` and
+    /// `right_schema` can be run by a [`RangeJoinExec`], that is whether it
+    /// bounds an expression of the left input both from below and from
+    /// above with expressions of the right input
+    pub fn is_range_join(
+        filter: &JoinFilter,
+        left_schema: &Schema,
+        right_schema: &Schema,
+    ) -> Result<bool> {
+        let conditions = range_join_conditions(filter, left_schema, right_schema)?;
+        Ok(is_range(&conditions))
+    }
+
+    /// left side
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
+
+    /// right side
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
+    }
+
+    /// Filters applied before join output
+    pub fn filter(&self) -> &JoinFilter {
+        &self.filter
+    }
+
+    /// The bound of the filter used to find the candidate rows
+    pub fn range(&self) -> &RangeJoinCondition {
+        &self.range
+    }
+
+    /// How the join is performed
+    pub fn join_type(&self) -> &JoinType {
+        &self.join_type
+    }
+}
+
+/// Whether `conditions` bound an expression from below and from above
+fn is_range(conditions: &[RangeJoinCondition]) -> bool {
+    conditions.iter().any(|c| c.is_upper_bound())
+        && conditions.iter().any(|c| !c.is_upper_bound())
+}
+
+impl DisplayAs for RangeJoinExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "RangeJoinExec: join_type={:?}, range={}, filter={}",
+                    self.join_type,
+                    self.range,
+                    self.filter.expression()
+                )
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for RangeJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        // the partition of output is determined by the rule of `required_input_distribution`
+        if self.join_type == JoinType::Full {
+            self.left.output_partitioning()
+        } else {
+            partitioned_join_output_partitioning(
+                self.join_type,
+                self.left.output_partitioning(),
+                self.right.output_partitioning(),
+                self.left.schema().fields.len(),
+            )
+        }
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        // no specified order for the output
+        None
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        distribution_from_join_type(&self.join_type)
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        join_equivalence_properties(
+            self.left.equivalence_properties(),
+            self.right.equivalence_properties(),
+            &self.join_type,
+            self.schema(),
+            &self.maintains_input_order(),
+            None,
+            // No on columns in range join
+            &[],
+        )
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(RangeJoinExec::try_new(
+            children[0].clone(),
+            children[1].clone(),
+            self.filter.clone(),
+            &self.join_type,
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
+
+        // Initialization reservation for load of inner table
+        let load_reservation = MemoryConsumer::new(format!("RangeJoinLoad[{partition}]"))
+            .register(context.memory_pool());
+
+        // Initialization of stream-level reservation
+        let reservation = MemoryConsumer::new(format!("RangeJoinStream[{partition}]"))
+            .register(context.memory_pool());
+
+        // The range seen from the inner table: `inner_key op outer_key`
+        let left_is_build = left_is_build_side(self.join_type);
+        let (inner, inner_key, outer, outer_key, op) = if left_is_build {
+            (
+                &self.left,
+                &self.range.left,
+                &self.right,
+                &self.range.right,
+                self.range.op,
+            )
+        } else {
+            (
+                &self.right,
+                &self.range.right,
+                &self.left,
+                &self.range.left,
+                self.range.op.swap().unwrap(),
+            )
+        };
+
+        let inner_table = self.inner_table.once(|| {
+            load_sorted_inner_table(
+                inner.clone(),
+                inner_key.clone(),
+                context.clone(),
+                join_metrics.clone(),
+                load_reservation,
+            )
+        });
+        let outer_table = outer.execute(partition, context)?;
+        let key_type = outer_key.data_type(&outer.schema())?;
+
+        Ok(Box::pin(RangeJoinStream {
+            schema: self.schema.clone(),
+            filter: self.filter.clone(),
+            join_type: self.join_type,
+            outer_table,
+            inner_table,
+            left_is_build,
+            outer_key: outer_key.clone(),
+            inner_key_is_lower: matches!(op, Operator::Lt | Operator::LtEq),
+            converter: RowConverter::new(vec![SortField::new(key_type)])?,
+            inner_rows: None,
+            is_exhausted: false,
+            visited_left_side: None,
+            column_indices: self.column_indices.clone(),
+            join_metrics,
+            reservation,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        estimate_join_statistics(
+            self.left.clone(),
+            self.right.clone(),
+            vec![],
+            &self.join_type,
+            &self.schema,
+        )
+    }
+}
+
+/// Asynchronously collects the inner table, and sorts it on `key`
+async fn load_sorted_inner_table(
+    input: Arc<dyn ExecutionPlan>,
+    key: Arc<dyn PhysicalExpr>,
+    context: Arc<TaskContext>,
+    join_metrics: BuildProbeJoinMetrics,
+    reservation: MemoryReservation,
+) -> Result<RangeJoinInnerData> {
+    let (batch, reservation) =
+        load_specified_partition_of_input(0, input, context, join_metrics, reservation)
+            .await?;
+    let num_rows = batch.num_rows();
+    let keys = key.evaluate(&batch)?.into_array(num_rows)?;
+
+    // Sort on the row format, as the rows of the other input are compared
+    // with the keys on it
+    let mut converter =
+        RowConverter::new(vec![SortField::new(keys.data_type().clone())])?;
+    let rows = converter.convert_columns(&[keys.clone()])?;
+    let mut indices = (0..num_rows as u32).collect::<Vec<_>>();
+    indices.sort_unstable_by(|a, b| rows.row(*a as usize).cmp(&rows.row(*b as usize)));
+    let indices = UInt32Array::from(indices);
+
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|column| take(column, &indices, None))
+        .collect::<Result<Vec<_>, _>>()?;
+    let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+    let batch = RecordBatch::try_new_with_options(batch.schema(), columns, &options)?;
+    let keys = take(&keys, &indices, None)?;
+
+    Ok(RangeJoinInnerData {
+        batch,
+        keys,
+        _reservation: reservation,
+    })
+}
+
+/// A stream that issues [RecordBatch]es as they arrive from the outer table
+/// of the join
+struct RangeJoinStream {
+    /// Input schema
+    schema: Arc<Schema>,
+    /// join filter
+    filter: JoinFilter,
+    /// type of the join
+    join_type: JoinType,
+    /// the outer table data of the range join
+    outer_table: SendableRecordBatchStream,
+    /// the inner table data of the range join
+    inner_table: OnceFut<RangeJoinInnerData>,
+    /// Whether the left input is the inner table
+    left_is_build: bool,
+    /// The expression of the outer table bounding the inner keys
+    outer_key: Arc<dyn PhysicalExpr>,
+    /// Whether the inner keys are bounded from above by the outer keys,
+    /// rather than from below
+    inner_key_is_lower: bool,
+    /// Converts the keys to the row format, to compare keys of any type
+    converter: RowConverter,
+    /// The sorted keys of the inner table, in the row format
+    inner_rows: Option<Rows>,
+    /// There is nothing to process anymore and left side is processed in case of full join
+    is_exhausted: bool,
+    /// Keeps track of the left side rows whether they are visited
+    visited_left_side: Option<BooleanBufferBuilder>,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// Join execution metrics
+    join_metrics: BuildProbeJoinMetrics,
+    /// Memory reservation for visited_left_side
+    reservation: MemoryReservation,
+}
+
+impl RangeJoinStream {
+    fn poll_next_impl(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        let build_timer = self.join_metrics.build_time.timer();
+        let inner_data = match ready!(self.inner_table.get(cx)) {
+            Ok(data) => data,
+            Err(e) => return Poll::Ready(Some(Err(e))),
+        };
+        build_timer.done();
+
+        if self.inner_rows.is_none() {
+            let rows = self.converter.convert_columns(&[inner_data.keys.clone()])?;
+            self.inner_rows = Some(rows);
+        }
+        let inner_rows = self.inner_rows.as_ref().unwrap();
+
+        if self.visited_left_side.is_none() && self.join_type == JoinType::Full {
+            let visited_bitmap_size = bit_util::ceil(inner_data.batch.num_rows(), 8);
+            self.reservation.try_grow(visited_bitmap_size)?;
+            self.join_metrics.build_mem_used.add(visited_bitmap_size);
+        }
+
+        // add a bitmap for full join.
+        let visited_left_side = self.visited_left_side.get_or_insert_with(|| {
+            // only full join need bitmap
+            if self.join_type == JoinType::Full {
+                let left_num_rows = inner_data.batch.num_rows();
+                let mut buffer = BooleanBufferBuilder::new(left_num_rows);
+                buffer.append_n(left_num_rows, false);
+                buffer
+            } else {
+                BooleanBufferBuilder::new(0)
+            }
+        });
+
+        match ready!(self.outer_table.poll_next_unpin(cx)) {
+            Some(Ok(outer_batch)) => {
+                // Setting up timer & updating input metrics
+                self.join_metrics.input_batches.add(1);
+                self.join_metrics.input_rows.add(outer_batch.num_rows());
+                let timer = self.join_metrics.join_time.timer();
+
+                let (left_batch, right_batch) = if self.left_is_build {
+                    (&inner_data.batch, &outer_batch)
+                } else {
+                    (&outer_batch, &inner_data.batch)
+                };
+                let result = candidate_indices(
+                    inner_data,
+                    inner_rows,
+                    &outer_batch,
+                    &self.outer_key,
+                    self.inner_key_is_lower,
+                    self.left_is_build,
+                    &mut self.converter,
+                )
+                .and_then(|(left_indices, right_indices)| {
+                    join_left_and_right_batch(
+                        left_batch,
+                        right_batch,
+                        left_indices,
+                        right_indices,
+                        self.join_type,
+                        &self.filter,
+                        &self.column_indices,
+                        &self.schema,
+                        visited_left_side,
+                    )
+                });
+
+                // Recording time & updating output metrics
+                if let Ok(batch) = &result {
+                    timer.done();
+                    self.join_metrics.output_batches.add(1);
+                    self.join_metrics.output_rows.add(batch.num_rows());
+                }
+
+                Poll::Ready(Some(result))
+            }
+            Some(err) => Poll::Ready(Some(err)),
+            None => {
+                if self.join_type == JoinType::Full && !self.is_exhausted {
+                    // Only setting up timer, input is exhausted
+                    let timer = self.join_metrics.join_time.timer();
+
+                    // use the global left bitmap to produce the left indices and right indices
+                    let (left_side, right_side) =
+                        get_final_indices_from_bit_map(visited_left_side, self.join_type);
+                    let empty_right_batch =
+                        RecordBatch::new_empty(self.outer_table.schema());
+                    // use the left and right indices to produce the batch result
+                    let result = build_batch_from_indices(
+                        &self.schema,
+                        &inner_data.batch,
+                        &empty_right_batch,
+                        &left_side,
+                        &right_side,
+                        &self.column_indices,
+                        JoinSide::Left,
+                    );
+                    self.is_exhausted = true;
+
+                    // Recording time & updating output metrics
+                    if let Ok(batch) = &result {
+                        timer.done();
+                        self.join_metrics.output_batches.add(1);
+                        self.join_metrics.output_rows.add(batch.num_rows());
+                    }
+
+                    Poll::Ready(Some(result))
+                } else {
+                    // end of the join loop
+                    Poll::Ready(None)
+                }
+            }
+        }
+    }
+}
+
+/// Returns the left and right indices of the pairs of rows of the inner
+/// table and of `outer_batch` within the range, found by a binary search of
+/// the sorted inner keys for each outer key
+fn candidate_indices(
+    inner_data: &RangeJoinInnerData,
+    inner_rows: &Rows,
+    outer_batch: &RecordBatch,
+    outer_key: &Arc<dyn PhysicalExpr>,
+    inner_key_is_lower: bool,
+    left_is_build: bool,
+    converter: &mut RowConverter,
+) -> Result<(UInt64Array, UInt32Array)> {
+    let outer_keys = outer_key
+        .evaluate(outer_batch)?
+        .into_array(outer_batch.num_rows())?;
+    let outer_rows = converter.convert_columns(&[outer_keys.clone()])?;
+    let num_inner_rows = inner_rows.num_rows();
+    // the inner keys are sorted with their nulls first, which never match
+    let first_non_null = inner_data.keys.null_count();
+
+    let mut left_indices = UInt64Builder::new();
+    let mut right_indices = UInt32Builder::new();
+    for outer_index in 0..outer_batch.num_rows() {
+        if outer_keys.is_null(outer_index) {
+            continue;
+        }
+        let outer_row = outer_rows.row(outer_index);
+        let matches = if inner_key_is_lower {
+            // inner_key <= outer_key
+            first_non_null..partition_point(num_inner_rows, |i| {
+                inner_rows.row(i) <= outer_row
+            })
+        } else {
+            // inner_key >= outer_key
+            partition_point(num_inner_rows, |i| inner_rows.row(i) < outer_row)
+                .max(first_non_null)..num_inner_rows
+        };
+        for inner_index in matches {
+            if left_is_build {
+                left_indices.append_value(inner_index as u64);
+                right_indices.append_value(outer_index as u32);
+            } else {
+                left_indices.append_value(outer_index as u64);
+                right_indices.append_value(inner_index as u32);
+            }
+        }
+    }
+    Ok((left_indices.finish(), right_indices.finish()))
+}
+
+/// Returns the index of the first of `len` elements for which `pred` is
+/// false, `pred` being true for all the elements before it
+fn partition_point(len: usize, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+#[allow(clippy::too_many_arguments)]
+fn join_left_and_right_batch(
+    left_batch: &RecordBatch,
+    right_batch: &RecordBatch,
+    left_indices: UInt64Array,
+    right_indices: UInt32Array,
+    join_type: JoinType,
+    filter: &JoinFilter,
+    column_indices: &[ColumnIndex],
+    schema: &Schema,
+    visited_left_side: &mut BooleanBufferBuilder,
+) -> Result<RecordBatch> {
+    // the candidates only satisfy one bound of the filter
+    let (left_side, right_side) = apply_join_filter_to_indices(
+        left_batch,
+        right_batch,
+        left_indices,
+        right_indices,
+        filter,
+        JoinSide::Left,
+    )?;
+    // set the left bitmap
+    // and only full join need the left bitmap
+    if join_type == JoinType::Full {
+        left_side.iter().flatten().for_each(|x| {
+            visited_left_side.set_bit(x as usize, true);
+        });
+    }
+    // adjust the two side indices base on the join type
+    let (left_side, right_side) = adjust_indices_by_join_type(
+        left_side,
+        right_side,
+        left_batch.num_rows(),
+        right_batch.num_rows(),
+        join_type,
+    );
+
+    build_batch_from_indices(
+        schema,
+        left_batch,
+        right_batch,
+        &left_side,
+        &right_side,
+        column_indices,
+        JoinSide::Left,
+    )
+}
+
+impl Stream for RangeJoinStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.poll_next_impl(cx)
+    }
+}
+
+impl RecordBatchStream for RangeJoinStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common, memory::MemoryExec, repartition::RepartitionExec, test::build_table_i32,
+    };
+
+    use arrow::datatypes::{DataType, Field};
+    use datafusion_common::assert_batches_sorted_eq;
+
+    fn build_table(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<i32>),
+        c: (&str, &Vec<i32>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let batch = build_table_i32(a, b, c);
+        let schema = batch.schema();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    /// The points of the left table
+    fn build_left_table() -> Arc<dyn ExecutionPlan> {
+        build_table(
+            ("ts", &vec![1, 5, 10, 15, 20]),
+            ("b1", &vec![0, 1, 2, 3, 4]),
+            ("c1", &vec![10, 50, 100, 150, 200]),
+        )
+    }
+
+    /// The ranges of the right table
+    fn build_right_table() -> Arc<dyn ExecutionPlan> {
+        build_table(
+            ("start", &vec![0, 4, 12, 30]),
+            ("end", &vec![5, 10, 20, 40]),
+            ("c2", &vec![1, 2, 3, 4]),
+        )
+    }
+
+    /// `left.ts >= right.start AND left.ts <= right.end`
+    fn prepare_join_filter() -> JoinFilter {
+        let column_indices = vec![
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Right,
+            },
+        ];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("ts", DataType::Int32, true),
+            Field::new("start", DataType::Int32, true),
+            Field::new("end", DataType::Int32, true),
+        ]);
+        let lower = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("ts", 0)),
+            Operator::GtEq,
+            Arc::new(Column::new("start", 1)),
+        )) as Arc<dyn PhysicalExpr>;
+        // right.end >= left.ts is left.ts <= right.end
+        let upper = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("end", 2)),
+            Operator::GtEq,
+            Arc::new(Column::new("ts", 0)),
+        )) as Arc<dyn PhysicalExpr>;
+        let filter_expression = Arc::new(BinaryExpr::new(lower, Operator::And, upper))
+            as Arc<dyn PhysicalExpr>;
+
+        JoinFilter::new(filter_expression, column_indices, intermediate_schema)
+    }
+
+    async fn multi_partitioned_join_collect(
+        join_type: &JoinType,
+    ) -> Result<Vec<RecordBatch>> {
+        let partition_count = 4;
+        let mut output_partition = 1;
+        let distribution = distribution_from_join_type(join_type);
+        let mut repartition = |input: Arc<dyn ExecutionPlan>,
+                               distribution: &Distribution|
+         -> Result<Arc<dyn ExecutionPlan>> {
+            if matches!(distribution, Distribution::SinglePartition) {
+                Ok(input)
+            } else {
+                output_partition = partition_count;
+                Ok(Arc::new(RepartitionExec::try_new(
+                    input,
+                    Partitioning::RoundRobinBatch(partition_count),
+                )?))
+            }
+        };
+        let left = repartition(build_left_table(), &distribution[0])?;
+        let right = repartition(build_right_table(), &distribution[1])?;
+
+        let range_join =
+            RangeJoinExec::try_new(left, right, prepare_join_filter(), join_type)?;
+        let context = Arc::new(TaskContext::default());
+        let mut batches = vec![];
+        for i in 0..output_partition {
+            let stream = range_join.execute(i, context.clone())?;
+            batches.extend(common::collect(stream).await?);
+        }
+        Ok(batches)
+    }
+
+    #[tokio::test]
+    async fn join_inner() -> Result<()> {
+        let batches = multi_partitioned_join_collect(&JoinType::Inner).await?;
+        let expected = [
+            "+----+----+-----+-------+-----+----+",
+            "| ts | b1 | c1  | start | end | c2 |",
+            "+----+----+-----+-------+-----+----+",
+            "| 1  | 0  | 10  | 0     | 5   | 1  |",
+            "| 5  | 1  | 50  | 0     | 5   | 1  |",
+            "| 5  | 1  | 50  | 4     | 10  | 2  |",
+            "| 10 | 2  | 100 | 4     | 10  | 2  |",
+            "| 15 | 3  | 150 | 12    | 20  | 3  |",
+            "| 20 | 4  | 200 | 12    | 20  | 3  |",
+            "+----+----+-----+-------+-----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_full() -> Result<()> {
+        let batches = multi_partitioned_join_collect(&JoinType::Full).await?;
+        let expected = [
+            "+----+----+-----+-------+-----+----+",
+            "| ts | b1 | c1  | start | end | c2 |",
+            "+----+----+-----+-------+-----+----+",
+            "|    |    |     | 30    | 40  | 4  |",
+            "| 1  | 0  | 10  | 0     | 5   | 1  |",
+            "| 5  | 1  | 50  | 0     | 5   | 1  |",
+            "| 5  | 1  | 50  | 4     | 10  | 2  |",
+            "| 10 | 2  | 100 | 4     | 10  | 2  |",
+            "| 15 | 3  | 150 | 12    | 20  | 3  |",
+            "| 20 | 4  | 200 | 12    | 20  | 3  |",
+            "+----+----+-----+-------+-----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_left_anti() -> Result<()> {
+        let left = build_table(
+            ("ts", &vec![3, 11, 25]),
+            ("b1", &vec![0, 1, 2]),
+            ("c1", &vec![0, 0, 0]),
+        );
+        let range_join = RangeJoinExec::try_new(
+            left,
+            build_right_table(),
+            prepare_join_filter(),
+            &JoinType::LeftAnti,
+        )?;
+        let batches =
+            common::collect(range_join.execute(0, Arc::new(TaskContext::default()))?)
+                .await?;
+        let expected = [
+            "+----+----+----+",
+            "| ts | b1 | c1 |",
+            "+----+----+----+",
+            "| 11 | 1  | 0  |",
+            "| 25 | 2  | 0  |",
+            "+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[test]
+    fn range_join_filter() -> Result<()> {
+        let left = build_left_table();
+        let right = build_right_table();
+        let filter = prepare_join_filter();
+        assert!(RangeJoinExec::is_range_join(
+            &filter,
+            &left.schema(),
+            &right.schema()
+        )?);
+
+        // a single bound is not a range
+        let single_bound = JoinFilter::new(
+            split_conjunction(filter.expression())[0].clone(),
+            filter.column_indices().to_vec(),
+            filter.schema().clone(),
+        );
+        assert!(!RangeJoinExec::is_range_join(
+            &single_bound,
+            &left.schema(),
+            &right.schema()
+        )?);
+        assert!(
+            RangeJoinExec::try_new(left, right, single_bound, &JoinType::Inner).is_err()
+        );
+        Ok(())
+    }
+}
@@ -50,7 +50,7 @@ use datafusion_common::{
     internal_err, not_impl_err, plan_err, DataFusionError, JoinSide, JoinType, Result,
 };
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
-use datafusion_execution::TaskContext;
+use datafusion_execution::{CancellationToken, TaskContext};
 use datafusion_physical_expr::equivalence::join_equivalence_properties;
 use datafusion_physical_expr::{EquivalenceProperties, PhysicalSortRequirement};
 
@@ -100,12 +100,6 @@ impl SortMergeJoinExec {
         let left_schema = left.schema();
         let right_schema = right.schema();
 
-        if join_type == JoinType::RightSemi {
-            return not_impl_err!(
-                "SortMergeJoinExec does not support JoinType::RightSemi"
-            );
-        }
-
         check_join_is_valid(&left_schema, &right_schema, &on)?;
         if sort_options.len() != on.len() {
             return plan_err!(
@@ -359,6 +353,7 @@ impl ExecutionPlan for SortMergeJoinExec {
             batch_size,
             SortMergeJoinMetrics::new(partition, &self.metrics),
             reservation,
+            context.cancellation_token().clone(),
         )?))
     }
 
@@ -624,6 +619,8 @@ struct SMJStream {
     pub join_metrics: SortMergeJoinMetrics,
     /// Memory reservation
     pub reservation: MemoryReservation,
+    /// Stops the join when the query is cancelled
+    pub cancellation: CancellationToken,
 }
 
 impl RecordBatchStream for SMJStream {
@@ -639,6 +636,7 @@ impl Stream for SMJStream {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
+        self.cancellation.check()?;
         let join_time = self.join_metrics.join_time.clone();
         let _timer = join_time.timer();
 
@@ -742,6 +740,7 @@ impl SMJStream {
         batch_size: usize,
         join_metrics: SortMergeJoinMetrics,
         reservation: MemoryReservation,
+        cancellation: CancellationToken,
     ) -> Result<Self> {
         let streamed_schema = streamed.schema();
         let buffered_schema = buffered.schema();
@@ -769,6 +768,7 @@ impl SMJStream {
             join_type,
             join_metrics,
             reservation,
+            cancellation,
         })
     }
 
@@ -955,15 +955,15 @@ impl SMJStream {
                     self.join_type,
                     JoinType::Left
                         | JoinType::Right
-                        | JoinType::RightSemi
                         | JoinType::Full
                         | JoinType::LeftAnti
+                        | JoinType::RightAnti
                 ) {
                     join_streamed = !self.streamed_joined;
                 }
             }
             Ordering::Equal => {
-                if matches!(self.join_type, JoinType::LeftSemi) {
+                if matches!(self.join_type, JoinType::LeftSemi | JoinType::RightSemi) {
                     join_streamed = !self.streamed_joined;
                 }
                 if matches!(
@@ -1105,23 +1105,28 @@ impl SMJStream {
 
             let buffered_indices: UInt64Array = chunk.buffered_indices.finish();
 
-            let mut buffered_columns =
-                if matches!(self.join_type, JoinType::LeftSemi | JoinType::LeftAnti) {
-                    vec![]
-                } else if let Some(buffered_idx) = chunk.buffered_batch_idx {
-                    self.buffered_data.batches[buffered_idx]
-                        .batch
-                        .columns()
-                        .iter()
-                        .map(|column| take(column, &buffered_indices, None))
-                        .collect::<Result<Vec<_>, ArrowError>>()?
-                } else {
-                    self.buffered_schema
-                        .fields()
-                        .iter()
-                        .map(|f| new_null_array(f.data_type(), buffered_indices.len()))
-                        .collect::<Vec<_>>()
-                };
+            let mut buffered_columns = if matches!(
+                self.join_type,
+                JoinType::LeftSemi
+                    | JoinType::LeftAnti
+                    | JoinType::RightSemi
+                    | JoinType::RightAnti
+            ) {
+                vec![]
+            } else if let Some(buffered_idx) = chunk.buffered_batch_idx {
+                self.buffered_data.batches[buffered_idx]
+                    .batch
+                    .columns()
+                    .iter()
+                    .map(|column| take(column, &buffered_indices, None))
+                    .collect::<Result<Vec<_>, ArrowError>>()?
+            } else {
+                self.buffered_schema
+                    .fields()
+                    .iter()
+                    .map(|f| new_null_array(f.data_type(), buffered_indices.len()))
+                    .collect::<Vec<_>>()
+            };
 
             let columns = if matches!(self.join_type, JoinType::Right) {
                 buffered_columns.extend(streamed_columns);
@@ -1970,6 +1975,69 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn join_right_anti() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![70, 80, 90]),
+        );
+        let right = build_table(
+            ("a2", &vec![1, 2, 2, 3, 5]),
+            ("b1", &vec![4, 5, 5, 7, 7]), // 7 does not exist on the left
+            ("c2", &vec![7, 8, 8, 9, 11]),
+        );
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b1", &right.schema())?,
+        )];
+
+        let (_, batches) = join_collect(left, right, on, JoinType::RightAnti).await?;
+        let expected = [
+            "+----+----+----+",
+            "| a2 | b1 | c2 |",
+            "+----+----+----+",
+            "| 3  | 7  | 9  |",
+            "| 5  | 7  | 11 |",
+            "+----+----+----+",
+        ];
+        // The output order is important as SMJ preserves sortedness
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_right_semi() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]), // 5 is double on the left
+            ("c1", &vec![70, 80, 90]),
+        );
+        let right = build_table(
+            ("a2", &vec![1, 2, 2, 3]),
+            ("b1", &vec![4, 5, 5, 7]), // 7 does not exist on the left
+            ("c2", &vec![7, 8, 8, 9]),
+        );
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b1", &right.schema())?,
+        )];
+
+        let (_, batches) = join_collect(left, right, on, JoinType::RightSemi).await?;
+        let expected = [
+            "+----+----+----+",
+            "| a2 | b1 | c2 |",
+            "+----+----+----+",
+            "| 1  | 4  | 7  |",
+            "| 2  | 5  | 8  |",
+            "| 2  | 5  | 8  |",
+            "+----+----+----+",
+        ];
+        // The output order is important as SMJ preserves sortedness
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_with_duplicated_column_names() -> Result<()> {
         let left = build_table(
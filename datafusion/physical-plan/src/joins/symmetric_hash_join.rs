@@ -484,6 +484,11 @@ impl ExecutionPlan for SymmetricHashJoinExec {
             left_sorted_filter_expr,
             right_sorted_filter_expr,
             null_equals_null: self.null_equals_null,
+            normalize_float_keys: context
+                .session_config()
+                .options()
+                .execution
+                .normalize_float_keys,
             state: EagerJoinStreamState::PullRight,
             reservation,
         }))
@@ -517,6 +522,8 @@ struct SymmetricHashJoinStream {
     random_state: RandomState,
     /// If null_equals_null is true, null == null else null != null
     null_equals_null: bool,
+    /// If true, all NaNs are equal keys and so are -0.0 and +0.0
+    normalize_float_keys: bool,
     /// Metrics
     metrics: StreamJoinMetrics,
     /// Memory reservation
@@ -739,6 +746,7 @@ pub(crate) fn build_side_determined_results(
 /// * `column_indices` - An array of columns to be selected for the result of the join.
 /// * `random_state` - The random state for the join.
 /// * `null_equals_null` - A boolean indicating whether NULL values should be treated as equal when joining.
+/// * `normalize_float_keys` - A boolean indicating whether all NaNs, as well as -0.0 and +0.0, should be treated as equal when joining.
 ///
 /// # Returns
 ///
@@ -755,6 +763,7 @@ pub(crate) fn join_with_probe_batch(
     column_indices: &[ColumnIndex],
     random_state: &RandomState,
     null_equals_null: bool,
+    normalize_float_keys: bool,
 ) -> Result<Option<RecordBatch>> {
     if build_hash_joiner.input_buffer.num_rows() == 0 || probe_batch.num_rows() == 0 {
         return Ok(None);
@@ -772,6 +781,7 @@ pub(crate) fn join_with_probe_batch(
         build_hash_joiner.build_side,
         Some(build_hash_joiner.deleted_offset),
         false,
+        normalize_float_keys,
     )?;
     if need_to_produce_result_in_final(build_hash_joiner.build_side, join_type) {
         record_visited_indices(
@@ -1063,6 +1073,7 @@ impl SymmetricHashJoinStream {
         size += std::mem::size_of_val(&self.right_sorted_filter_expr);
         size += std::mem::size_of_val(&self.random_state);
         size += std::mem::size_of_val(&self.null_equals_null);
+        size += std::mem::size_of_val(&self.normalize_float_keys);
         size += std::mem::size_of_val(&self.metrics);
         size
     }
@@ -1118,6 +1129,7 @@ impl SymmetricHashJoinStream {
             &self.column_indices,
             &self.random_state,
             self.null_equals_null,
+            self.normalize_float_keys,
         )?;
         // Increment the offset for the probe hash joiner:
         probe_hash_joiner.offset += probe_batch.num_rows();
@@ -18,6 +18,7 @@
 //! Traits for physical query plan, supporting parallel execution for partitioned relations.
 
 use std::any::Any;
+use std::borrow::Cow;
 use std::fmt::Debug;
 use std::sync::Arc;
 
@@ -53,28 +54,39 @@ pub mod display;
 pub mod empty;
 pub mod explain;
 pub mod filter;
+pub mod gap_fill;
 pub mod insert;
+#[cfg(feature = "tracing")]
+pub mod instrument;
 pub mod joins;
 pub mod limit;
 pub mod memory;
 pub mod metrics;
 mod ordering;
 pub mod placeholder_row;
+pub mod progress;
 pub mod projection;
+pub mod properties;
+pub mod recursive_query;
 pub mod repartition;
+pub mod shared_scan;
 pub mod sorts;
 pub mod stream;
 pub mod streaming;
+pub mod time_window;
 pub mod tree_node;
 pub mod udaf;
 pub mod union;
 pub mod unnest;
 pub mod values;
 pub mod windows;
+pub mod work_table;
 
 pub use crate::display::{DefaultDisplay, DisplayAs, DisplayFormatType, VerboseDisplay};
 pub use crate::metrics::Metric;
 pub use crate::ordering::InputOrderMode;
+pub use crate::progress::{OperatorProgress, QueryProgress};
+pub use crate::properties::{ExecutionMode, PlanProperties};
 pub use crate::topk::TopK;
 pub use crate::visitor::{accept, visit_execution_plan, ExecutionPlanVisitor};
 
@@ -227,6 +239,20 @@ pub trait ExecutionPlan: Debug + DisplayAs + Send + Sync {
         EquivalenceProperties::new(self.schema())
     }
 
+    /// Get the [`PlanProperties`] of the output of this `ExecutionPlan`: its
+    /// equivalence properties and ordering, its partitioning and whether it
+    /// is bounded, consistent with each other.
+    ///
+    /// The default implementation computes them from
+    /// [`Self::equivalence_properties`], [`Self::output_ordering`],
+    /// [`Self::output_partitioning`] and [`Self::unbounded_output`] each time
+    /// it is called. An `ExecutionPlan` whose properties are expensive to
+    /// compute should instead compute them once, when it is created, and
+    /// implement these methods from them, see [`PlanProperties`].
+    fn properties(&self) -> Cow<'_, PlanProperties> {
+        Cow::Owned(PlanProperties::from_plan(self))
+    }
+
     /// Get a list of children `ExecutionPlan`s that act as inputs to this plan.
     /// The returned list will be empty for leaf nodes such as scans, will contain
     /// a single value for unary nodes, or two values for binary nodes (such as
@@ -265,6 +291,22 @@ pub trait ExecutionPlan: Debug + DisplayAs + Send + Sync {
         Ok(None)
     }
 
+    /// If supported, attempt to return a version of this plan whose output
+    /// partitions are each ordered by `ordering`, without sorting them.
+    ///
+    /// Data sources that store their data in a known order, such as a time
+    /// ordered dataset, implement this method to advertise that they can
+    /// return their rows in that order. The returned plan must produce the
+    /// same rows as this plan and report `ordering` in its
+    /// [`Self::output_ordering`], which allows sorts on these expressions
+    /// above the scan to be removed.
+    fn try_pushdown_sort(
+        &self,
+        _ordering: &[PhysicalSortExpr],
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        Ok(None)
+    }
+
     /// Begin execution of `partition`, returning a [`Stream`] of
     /// [`RecordBatch`]es.
     ///
@@ -532,6 +574,17 @@ pub fn execute_stream(
     }
 }
 
+/// Execute the [ExecutionPlan] and return a single stream of results, like
+/// [`execute_stream`], along with a [`QueryProgress`] reporting the progress
+/// of the execution.
+pub fn execute_stream_with_progress(
+    plan: Arc<dyn ExecutionPlan>,
+    context: Arc<TaskContext>,
+) -> Result<(SendableRecordBatchStream, QueryProgress)> {
+    let progress = QueryProgress::new(plan.clone());
+    Ok((execute_stream(plan, context)?, progress))
+}
+
 /// Execute the [ExecutionPlan] and collect the results in memory
 pub async fn collect_partitioned(
     plan: Arc<dyn ExecutionPlan>,
@@ -31,7 +31,7 @@ use super::{
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::{internal_err, project_schema, DataFusionError, Result};
-use datafusion_execution::TaskContext;
+use datafusion_execution::{CancellationToken, TaskContext};
 use datafusion_physical_expr::{EquivalenceProperties, LexOrdering};
 
 use futures::Stream;
@@ -140,13 +140,16 @@ impl ExecutionPlan for MemoryExec {
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        Ok(Box::pin(MemoryStream::try_new(
-            self.partitions[partition].clone(),
-            self.projected_schema.clone(),
-            self.projection.clone(),
-        )?))
+        Ok(Box::pin(
+            MemoryStream::try_new(
+                self.partitions[partition].clone(),
+                self.projected_schema.clone(),
+                self.projection.clone(),
+            )?
+            .with_cancellation_token(context.cancellation_token().clone()),
+        ))
     }
 
     /// We recompute the statistics dynamically from the arrow metadata as it is pretty cheap to do so
@@ -221,6 +224,8 @@ pub struct MemoryStream {
     projection: Option<Vec<usize>>,
     /// Index into the data
     index: usize,
+    /// Stops the stream when the query is cancelled
+    cancellation: CancellationToken,
 }
 
 impl MemoryStream {
@@ -235,8 +240,15 @@ impl MemoryStream {
             schema,
             projection,
             index: 0,
+            cancellation: CancellationToken::new(),
         })
     }
+
+    /// Set the [`CancellationToken`] checked before returning each batch
+    pub fn with_cancellation_token(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
 }
 
 impl Stream for MemoryStream {
@@ -247,6 +259,10 @@ impl Stream for MemoryStream {
         _: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         Poll::Ready(if self.index < self.data.len() {
+            if let Err(e) = self.cancellation.check() {
+                self.index = self.data.len();
+                return Poll::Ready(Some(Err(e)));
+            }
             self.index += 1;
             let batch = &self.data[self.index - 1];
 
@@ -197,6 +197,18 @@ impl<'a> MetricBuilder<'a> {
         time
     }
 
+    /// Consumes self and creates a new Timer for recording the time spent
+    /// evaluating the expression displayed as `expr`, which is attached as
+    /// the `expr` label.
+    pub fn expression_time(
+        self,
+        expr: impl Into<Cow<'static, str>>,
+        partition: usize,
+    ) -> Time {
+        self.with_new_label("expr", expr)
+            .subset_time("expr_eval_time", partition)
+    }
+
     /// Consumes self and creates a new Timestamp for recording the
     /// starting time of execution for a partition
     pub fn start_timestamp(self, partition: usize) -> Timestamp {
@@ -24,6 +24,7 @@ mod value;
 use parking_lot::Mutex;
 use std::{
     borrow::Cow,
+    collections::BTreeMap,
     fmt::{Debug, Display},
     sync::Arc,
 };
@@ -291,10 +292,68 @@ impl MetricsSet {
         }
     }
 
-    /// Sort the order of metrics so the "most useful" show up first
+    /// Returns the distribution of each metric across the partitions
+    /// that reported it, ordered for display.
+    ///
+    /// Metrics reported by less than two partitions, metrics without a
+    /// partition and timestamps are skipped.
+    pub fn partition_rollup(&self) -> Vec<PartitionRollup> {
+        // operators may report several metrics with the same name for a
+        // partition (e.g. one per file), so first aggregate them by partition
+        let mut by_name: BTreeMap<(u8, &str), BTreeMap<usize, MetricValue>> =
+            BTreeMap::new();
+        for metric in &self.metrics {
+            let value = metric.value();
+            let Some(partition) = metric.partition() else {
+                continue;
+            };
+            if value.is_timestamp() {
+                continue;
+            }
+            by_name
+                .entry((value.display_sort_key(), value.name()))
+                .or_default()
+                .entry(partition)
+                .or_insert_with(|| value.new_empty())
+                .aggregate(value);
+        }
+
+        by_name
+            .into_iter()
+            .filter(|(_, partitions)| partitions.len() > 1)
+            .map(|((_, name), partitions)| {
+                let mut values = partitions.into_values().collect::<Vec<_>>();
+                values.sort_by_key(|v| v.as_usize());
+                PartitionRollup {
+                    name: name.to_string(),
+                    partitions: values.len(),
+                    min: values[0].clone(),
+                    median: values[(values.len() - 1) / 2].clone(),
+                    max: values[values.len() - 1].clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Sort the order of metrics so the "most useful" show up first.
+    ///
+    /// Metrics of the same kind are ordered by name, partition and labels,
+    /// so the order does not depend on the order in which the partitions
+    /// registered their metrics.
     pub fn sorted_for_display(mut self) -> Self {
-        self.metrics
-            .sort_unstable_by_key(|metric| metric.value().display_sort_key());
+        self.metrics.sort_by_cached_key(|metric| {
+            let labels = metric
+                .labels()
+                .iter()
+                .map(|l| (l.name().to_string(), l.value().to_string()))
+                .collect::<Vec<_>>();
+            (
+                metric.value().display_sort_key(),
+                metric.value().name().to_string(),
+                metric.partition(),
+                labels,
+            )
+        });
         self
     }
 
@@ -328,6 +387,57 @@ impl Display for MetricsSet {
     }
 }
 
+/// The minimum, median and maximum value of a metric across the partitions
+/// of an operator, see [`MetricsSet::partition_rollup`].
+///
+/// Displayed as `name{min=1, median=2, max=3}`
+#[derive(Debug, Clone)]
+pub struct PartitionRollup {
+    name: String,
+    partitions: usize,
+    min: MetricValue,
+    median: MetricValue,
+    max: MetricValue,
+}
+
+impl PartitionRollup {
+    /// The name of the metric
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of partitions that reported the metric
+    pub fn partitions(&self) -> usize {
+        self.partitions
+    }
+
+    /// The smallest value reported by a partition
+    pub fn min(&self) -> &MetricValue {
+        &self.min
+    }
+
+    /// The median of the values reported by the partitions, the lower one
+    /// for an even number of partitions
+    pub fn median(&self) -> &MetricValue {
+        &self.median
+    }
+
+    /// The largest value reported by a partition
+    pub fn max(&self) -> &MetricValue {
+        &self.max
+    }
+}
+
+impl Display for PartitionRollup {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{{min={}, median={}, max={}}}",
+            self.name, self.min, self.median, self.max
+        )
+    }
+}
+
 /// A set of [`Metric`]s for an individual "operator" (e.g. `&dyn
 /// ExecutionPlan`).
 ///
@@ -672,4 +782,51 @@ mod tests {
         let metrics = metrics.sorted_for_display();
         assert_eq!("output_rows, elapsed_compute, the_counter, the_time, start_timestamp, end_timestamp", metric_names(&metrics));
     }
+
+    #[test]
+    fn test_sorted_for_display_partitions() {
+        let metrics = ExecutionPlanMetricsSet::new();
+        MetricBuilder::new(&metrics).counter("b", 1);
+        MetricBuilder::new(&metrics).counter("a", 1);
+        MetricBuilder::new(&metrics).output_rows(1);
+        MetricBuilder::new(&metrics).counter("b", 0);
+        MetricBuilder::new(&metrics).output_rows(0);
+        MetricBuilder::new(&metrics).counter("a", 0);
+
+        let metrics = metrics.clone_inner().sorted_for_display();
+        assert_eq!(
+            metrics.to_string(),
+            "output_rows{partition=0}=0, output_rows{partition=1}=0, \
+             a{partition=0}=0, a{partition=1}=0, b{partition=0}=0, b{partition=1}=0"
+        );
+    }
+
+    #[test]
+    fn test_partition_rollup() {
+        let metrics = ExecutionPlanMetricsSet::new();
+        for (partition, rows) in [(2, 30), (0, 10), (1, 50), (3, 20)] {
+            MetricBuilder::new(&metrics)
+                .output_rows(partition)
+                .add(rows);
+            MetricBuilder::new(&metrics)
+                .start_timestamp(partition)
+                .record();
+        }
+        // two metrics of the same name for partition 0 are added together
+        MetricBuilder::new(&metrics).counter("files", 0).add(1);
+        MetricBuilder::new(&metrics).counter("files", 0).add(2);
+        MetricBuilder::new(&metrics).counter("files", 1).add(1);
+        // metrics reported by a single partition are skipped
+        MetricBuilder::new(&metrics).counter("single", 0).add(1);
+
+        let rollup = metrics.clone_inner().partition_rollup();
+        let rollup = rollup.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            rollup,
+            vec![
+                "output_rows{min=10, median=20, max=50}",
+                "files{min=1, median=1, max=3}",
+            ]
+        );
+    }
 }
@@ -0,0 +1,210 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Progress reporting of running plans, see [`QueryProgress`]
+
+use std::sync::Arc;
+
+use crate::analyze::operator_name;
+use crate::display::DisplayableExecutionPlan;
+use crate::ExecutionPlan;
+
+/// Reports the progress of the execution of an [`ExecutionPlan`].
+///
+/// The progress is derived from the metrics of the operators of the plan,
+/// which are updated while it runs, so a `QueryProgress` can be polled from
+/// another task than the one consuming the results, for example to render a
+/// progress bar. See [`execute_stream_with_progress`].
+///
+/// Scans of files report the number of files (or file ranges) they have
+/// read out of the number of files of the partitions they have started.
+/// Other operators report the number of rows they have produced out of the
+/// number estimated from their statistics, if any.
+///
+/// [`execute_stream_with_progress`]: crate::execute_stream_with_progress
+#[derive(Debug, Clone)]
+pub struct QueryProgress {
+    plan: Arc<dyn ExecutionPlan>,
+    /// Estimated number of output rows of each operator of `plan`, in
+    /// pre-order, computed once as statistics may be expensive to compute
+    estimated_rows: Vec<Option<usize>>,
+}
+
+/// Progress of a single operator of a plan, see [`QueryProgress::operators`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorProgress {
+    /// Name of the operator, such as `ProjectionExec`
+    pub operator: String,
+    /// Depth of the operator in the plan, 0 for the root
+    pub depth: usize,
+    /// Number of rows produced so far
+    pub output_rows: usize,
+    /// Estimated total number of output rows, if known
+    pub estimated_rows: Option<usize>,
+    /// Number of files the operator has read
+    pub files_scanned: usize,
+    /// Number of files of the partitions the operator has started reading,
+    /// or `None` if it does not read files
+    pub files_total: Option<usize>,
+}
+
+impl OperatorProgress {
+    /// Fraction of the work of the operator done so far, between 0 and 1, if
+    /// known.
+    ///
+    /// This is the fraction of files scanned for scans of files, and the
+    /// fraction of the estimated rows produced otherwise
+    pub fn fraction(&self) -> Option<f64> {
+        match (self.files_total, self.estimated_rows) {
+            (Some(0), _) => None,
+            (Some(total), _) => Some(self.files_scanned as f64 / total as f64),
+            (None, Some(0)) => None,
+            (None, Some(estimated)) => {
+                Some((self.output_rows as f64 / estimated as f64).min(1.0))
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+impl QueryProgress {
+    /// Create a new QueryProgress reporting the progress of `plan`
+    pub fn new(plan: Arc<dyn ExecutionPlan>) -> Self {
+        let mut estimated_rows = vec![];
+        collect_estimated_rows(plan.as_ref(), &mut estimated_rows);
+        Self {
+            plan,
+            estimated_rows,
+        }
+    }
+
+    /// Returns the current progress of each operator of the plan, in
+    /// pre-order
+    pub fn operators(&self) -> Vec<OperatorProgress> {
+        let mut operators = Vec::with_capacity(self.estimated_rows.len());
+        self.collect_operators(self.plan.as_ref(), 0, &mut operators);
+        operators
+    }
+
+    /// Returns the fraction of the plan executed so far, between 0 and 1, if
+    /// known.
+    ///
+    /// This is the fraction of files scanned by the plan if it reads files,
+    /// and the fraction of the estimated rows produced by the root operator
+    /// otherwise
+    pub fn fraction(&self) -> Option<f64> {
+        let operators = self.operators();
+        let (scanned, total) = operators
+            .iter()
+            .filter_map(|op| op.files_total.map(|total| (op.files_scanned, total)))
+            .fold((0, 0), |(scanned, total), (s, t)| (scanned + s, total + t));
+        if total > 0 {
+            return Some(scanned as f64 / total as f64);
+        }
+        operators.first().and_then(|root| root.fraction())
+    }
+
+    fn collect_operators(
+        &self,
+        plan: &dyn ExecutionPlan,
+        depth: usize,
+        operators: &mut Vec<OperatorProgress>,
+    ) {
+        let description = DisplayableExecutionPlan::new(plan).one_line().to_string();
+        let metrics = plan.metrics().unwrap_or_default();
+        let sum_by_name = |name: &str| metrics.sum_by_name(name).map(|v| v.as_usize());
+        operators.push(OperatorProgress {
+            operator: operator_name(&description).to_string(),
+            depth,
+            output_rows: metrics.output_rows().unwrap_or_default(),
+            estimated_rows: self.estimated_rows.get(operators.len()).copied().flatten(),
+            files_scanned: sum_by_name("files_scanned").unwrap_or_default(),
+            files_total: sum_by_name("files_total"),
+        });
+        for child in plan.children() {
+            self.collect_operators(child.as_ref(), depth + 1, operators);
+        }
+    }
+}
+
+/// Appends the estimated number of output rows of `plan` and its children,
+/// in pre-order, to `estimated_rows`
+fn collect_estimated_rows(
+    plan: &dyn ExecutionPlan,
+    estimated_rows: &mut Vec<Option<usize>>,
+) {
+    let rows = plan
+        .statistics()
+        .ok()
+        .and_then(|statistics| statistics.num_rows.get_value().copied());
+    estimated_rows.push(rows);
+    for child in plan.children() {
+        collect_estimated_rows(child.as_ref(), estimated_rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coalesce_partitions::CoalescePartitionsExec;
+    use crate::test::scan_partitioned;
+    use crate::{collect, execute_stream_with_progress};
+    use datafusion_common::Result;
+    use datafusion_execution::TaskContext;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_progress() -> Result<()> {
+        let plan: Arc<dyn ExecutionPlan> =
+            Arc::new(CoalescePartitionsExec::new(scan_partitioned(2)));
+        let progress = QueryProgress::new(plan.clone());
+
+        let operators = progress.operators();
+        assert_eq!(operators.len(), 2);
+        assert_eq!(operators[0].operator, "CoalescePartitionsExec");
+        assert_eq!(operators[0].depth, 0);
+        assert_eq!(operators[0].output_rows, 0);
+        assert_eq!(operators[0].estimated_rows, Some(200));
+        assert_eq!(operators[0].files_total, None);
+        assert_eq!(operators[0].fraction(), Some(0.0));
+        assert_eq!(operators[1].operator, "MemoryExec");
+        assert_eq!(operators[1].depth, 1);
+
+        collect(plan, Arc::new(TaskContext::default())).await?;
+        let operators = progress.operators();
+        assert_eq!(operators[0].output_rows, 200);
+        assert_eq!(operators[0].fraction(), Some(1.0));
+        assert_eq!(progress.fraction(), Some(1.0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_execute_stream_with_progress() -> Result<()> {
+        let plan = Arc::new(CoalescePartitionsExec::new(scan_partitioned(2)));
+        let (mut stream, progress) =
+            execute_stream_with_progress(plan, Arc::new(TaskContext::default()))?;
+        assert_eq!(progress.fraction(), Some(0.0));
+
+        stream.next().await.unwrap()?;
+        assert!(progress.fraction().unwrap() > 0.0);
+        while let Some(batch) = stream.next().await {
+            batch?;
+        }
+        assert_eq!(progress.fraction(), Some(1.0));
+        Ok(())
+    }
+}
@@ -21,16 +21,20 @@
 //! projection expressions. `SELECT` without `FROM` will only evaluate expressions.
 
 use std::any::Any;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use super::expressions::{Column, PhysicalSortExpr};
-use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet, Time,
+};
 use super::{DisplayAs, RecordBatchStream, SendableRecordBatchStream, Statistics};
 use crate::{
     ColumnStatistics, DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr,
+    PlanProperties,
 };
 
 use arrow::datatypes::{Field, Schema, SchemaRef};
@@ -54,13 +58,10 @@ pub struct ProjectionExec {
     schema: SchemaRef,
     /// The input plan
     input: Arc<dyn ExecutionPlan>,
-    /// The output ordering
-    output_ordering: Option<Vec<PhysicalSortExpr>>,
-    /// The mapping used to normalize expressions like Partitioning and
-    /// PhysicalSortExpr that maps input to output
-    projection_mapping: ProjectionMapping,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
+    /// The properties of the output, computed once
+    cache: PlanProperties,
 }
 
 impl ProjectionExec {
@@ -95,20 +96,49 @@ impl ProjectionExec {
         // construct a map from the input expressions to the output expression of the Projection
         let projection_mapping = ProjectionMapping::try_new(&expr, &input_schema)?;
 
-        let input_eqs = input.equivalence_properties();
-        let project_eqs = input_eqs.project(&projection_mapping, schema.clone());
-        let output_ordering = project_eqs.oeq_class().output_ordering();
+        let cache = Self::compute_properties(&input, &projection_mapping, &schema);
 
         Ok(Self {
             expr,
             schema,
             input,
-            output_ordering,
-            projection_mapping,
             metrics: ExecutionPlanMetricsSet::new(),
+            cache,
         })
     }
 
+    /// Computes the properties of the projection of `input` by
+    /// `projection_mapping` into `schema`
+    fn compute_properties(
+        input: &Arc<dyn ExecutionPlan>,
+        projection_mapping: &ProjectionMapping,
+        schema: &SchemaRef,
+    ) -> PlanProperties {
+        let input_properties = input.properties();
+        let input_eqs = input_properties.equivalence_properties();
+        let project_eqs = input_eqs.project(projection_mapping, schema.clone());
+
+        // Output partition need to respect the alias
+        let partitioning = match input_properties.output_partitioning() {
+            Partitioning::Hash(exprs, part) => {
+                let normalized_exprs = exprs
+                    .iter()
+                    .map(|expr| {
+                        input_eqs
+                            .project_expr(expr, projection_mapping)
+                            .unwrap_or_else(|| {
+                                Arc::new(UnKnownColumn::new(&expr.to_string()))
+                            })
+                    })
+                    .collect();
+                Partitioning::Hash(normalized_exprs, *part)
+            }
+            partitioning => partitioning.clone(),
+        };
+
+        PlanProperties::new(project_eqs, partitioning, input_properties.execution_mode())
+    }
+
     /// The projection expressions stored as tuples of (expression, output column name)
     pub fn expr(&self) -> &[(Arc<dyn PhysicalExpr>, String)] {
         &self.expr
@@ -169,30 +199,17 @@ impl ExecutionPlan for ProjectionExec {
         vec![self.input.clone()]
     }
 
+    fn properties(&self) -> Cow<'_, PlanProperties> {
+        Cow::Borrowed(&self.cache)
+    }
+
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        // Output partition need to respect the alias
-        let input_partition = self.input.output_partitioning();
-        let input_eq_properties = self.input.equivalence_properties();
-        if let Partitioning::Hash(exprs, part) = input_partition {
-            let normalized_exprs = exprs
-                .into_iter()
-                .map(|expr| {
-                    input_eq_properties
-                        .project_expr(&expr, &self.projection_mapping)
-                        .unwrap_or_else(|| {
-                            Arc::new(UnKnownColumn::new(&expr.to_string()))
-                        })
-                })
-                .collect();
-            Partitioning::Hash(normalized_exprs, part)
-        } else {
-            input_partition
-        }
+        self.cache.output_partitioning().clone()
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        self.output_ordering.as_deref()
+        self.cache.output_ordering()
     }
 
     fn maintains_input_order(&self) -> Vec<bool> {
@@ -201,9 +218,7 @@ impl ExecutionPlan for ProjectionExec {
     }
 
     fn equivalence_properties(&self) -> EquivalenceProperties {
-        self.input
-            .equivalence_properties()
-            .project(&self.projection_mapping, self.schema())
+        self.cache.equivalence_properties().clone()
     }
 
     fn with_new_children(
@@ -230,11 +245,28 @@ impl ExecutionPlan for ProjectionExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         trace!("Start ProjectionExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+        let expr_times = if context
+            .session_config()
+            .options()
+            .execution
+            .expression_metrics
+        {
+            self.expr
+                .iter()
+                .map(|(expr, _)| {
+                    MetricBuilder::new(&self.metrics)
+                        .expression_time(expr.to_string(), partition)
+                })
+                .collect()
+        } else {
+            vec![]
+        };
         Ok(Box::pin(ProjectionStream {
             schema: self.schema.clone(),
             expr: self.expr.iter().map(|x| x.0.clone()).collect(),
             input: self.input.execute(partition, context)?,
             baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+            expr_times,
         }))
     }
 
@@ -306,7 +338,9 @@ impl ProjectionStream {
         let arrays = self
             .expr
             .iter()
-            .map(|expr| {
+            .enumerate()
+            .map(|(idx, expr)| {
+                let _expr_timer = self.expr_times.get(idx).map(Time::timer);
                 expr.evaluate(batch)
                     .and_then(|v| v.into_array(batch.num_rows()))
             })
@@ -329,6 +363,9 @@ struct ProjectionStream {
     expr: Vec<Arc<dyn PhysicalExpr>>,
     input: SendableRecordBatchStream,
     baseline_metrics: BaselineMetrics,
+    /// Time spent evaluating each of `expr`, empty unless expression metrics
+    /// are enabled
+    expr_times: Vec<Time>,
 }
 
 impl Stream for ProjectionStream {
@@ -394,18 +431,21 @@ mod tests {
                     max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
                     min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Exact(1),
                     max_value: Precision::Exact(ScalarValue::from("x")),
                     min_value: Precision::Exact(ScalarValue::from("a")),
                     null_count: Precision::Exact(3),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Absent,
                     max_value: Precision::Exact(ScalarValue::Float32(Some(1.1))),
                     min_value: Precision::Exact(ScalarValue::Float32(Some(0.1))),
                     null_count: Precision::Absent,
+                    histogram: None,
                 },
             ],
         }
@@ -438,12 +478,14 @@ mod tests {
                     max_value: Precision::Exact(ScalarValue::from("x")),
                     min_value: Precision::Exact(ScalarValue::from("a")),
                     null_count: Precision::Exact(3),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Exact(5),
                     max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
                     min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
             ],
         };
@@ -472,12 +514,14 @@ mod tests {
                     max_value: Precision::Exact(ScalarValue::Float32(Some(1.1))),
                     min_value: Precision::Exact(ScalarValue::Float32(Some(0.1))),
                     null_count: Precision::Absent,
+                    histogram: None,
                 },
                 ColumnStatistics {
                     distinct_count: Precision::Exact(5),
                     max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
                     min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
             ],
         };
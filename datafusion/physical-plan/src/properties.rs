@@ -0,0 +1,263 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Properties of the output of an [`ExecutionPlan`], see [`PlanProperties`]
+
+use crate::{unbounded_output, ExecutionPlan, Partitioning};
+
+use datafusion_common::Result;
+use datafusion_physical_expr::{EquivalenceProperties, LexOrdering, PhysicalSortExpr};
+
+/// Whether an [`ExecutionPlan`] produces a finite or an infinite stream of
+/// rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionMode {
+    /// The plan produces a finite number of rows
+    Bounded,
+    /// The plan produces an infinite stream of rows, which it processes
+    /// incrementally
+    Unbounded,
+    /// The plan reads an infinite stream of rows, but needs all of them to
+    /// produce its output, such as a sort, so it can not run
+    PipelineBreaking,
+}
+
+impl ExecutionMode {
+    /// Whether the plan produces an infinite stream of rows, or can not run
+    /// over its infinite input
+    pub fn is_unbounded(&self) -> bool {
+        !matches!(self, ExecutionMode::Bounded)
+    }
+
+    /// The mode of a plan from the result of its
+    /// [`ExecutionPlan::unbounded_output`]
+    pub fn from_unbounded_output(unbounded_output: Result<bool>) -> Self {
+        match unbounded_output {
+            Ok(false) => ExecutionMode::Bounded,
+            Ok(true) => ExecutionMode::Unbounded,
+            Err(_) => ExecutionMode::PipelineBreaking,
+        }
+    }
+}
+
+/// The properties of the output of an [`ExecutionPlan`]: its equivalence
+/// properties and ordering, its partitioning and its execution mode.
+///
+/// The output ordering is always derived from the equivalence properties, so
+/// that optimizer rules looking at the ordering of a plan and the ones
+/// looking at its equivalences see compatible properties.
+///
+/// Operators whose properties are expensive to compute, for example because
+/// they depend on the properties of their inputs, compute their
+/// `PlanProperties` once when they are created, return them from
+/// [`ExecutionPlan::properties`] and implement
+/// [`ExecutionPlan::output_partitioning`],
+/// [`ExecutionPlan::output_ordering`] and
+/// [`ExecutionPlan::equivalence_properties`] from them:
+///
+/// ```text
+/// fn properties(&self) -> Cow<'_, PlanProperties> {
+///     Cow::Borrowed(&self.cache)
+/// }
+///
+/// fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+///     self.cache.output_ordering()
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlanProperties {
+    /// Equivalences, constants and orderings of the output columns
+    eq_properties: EquivalenceProperties,
+    /// How the output is split into partitions
+    partitioning: Partitioning,
+    /// Whether the output is bounded
+    execution_mode: ExecutionMode,
+    /// The ordering of each output partition, derived from `eq_properties`
+    output_ordering: Option<LexOrdering>,
+}
+
+impl PlanProperties {
+    /// Create the properties of a plan, deriving its output ordering from
+    /// `eq_properties`
+    pub fn new(
+        eq_properties: EquivalenceProperties,
+        partitioning: Partitioning,
+        execution_mode: ExecutionMode,
+    ) -> Self {
+        let output_ordering = eq_properties.oeq_class().output_ordering();
+        Self {
+            eq_properties,
+            partitioning,
+            execution_mode,
+            output_ordering,
+        }
+    }
+
+    /// Computes the properties of `plan` from its
+    /// [`ExecutionPlan::equivalence_properties`],
+    /// [`ExecutionPlan::output_ordering`],
+    /// [`ExecutionPlan::output_partitioning`] and
+    /// [`ExecutionPlan::unbounded_output`].
+    ///
+    /// The output ordering of `plan` is added to its equivalence properties
+    /// if they do not already satisfy it.
+    pub fn from_plan<P: ExecutionPlan + ?Sized>(plan: &P) -> Self {
+        let mut eq_properties = plan.equivalence_properties();
+        if let Some(ordering) = plan.output_ordering() {
+            if !eq_properties.ordering_satisfy(ordering) {
+                eq_properties.add_new_orderings([ordering.to_vec()]);
+            }
+        }
+        let children = plan
+            .children()
+            .iter()
+            .map(unbounded_output)
+            .collect::<Vec<_>>();
+        let execution_mode =
+            ExecutionMode::from_unbounded_output(plan.unbounded_output(&children));
+        Self::new(eq_properties, plan.output_partitioning(), execution_mode)
+    }
+
+    /// Replace the equivalence properties, and the output ordering derived
+    /// from them
+    pub fn with_eq_properties(self, eq_properties: EquivalenceProperties) -> Self {
+        Self::new(eq_properties, self.partitioning, self.execution_mode)
+    }
+
+    /// Replace the partitioning
+    pub fn with_partitioning(mut self, partitioning: Partitioning) -> Self {
+        self.partitioning = partitioning;
+        self
+    }
+
+    /// Replace the execution mode
+    pub fn with_execution_mode(mut self, execution_mode: ExecutionMode) -> Self {
+        self.execution_mode = execution_mode;
+        self
+    }
+
+    /// Equivalences, constants and orderings of the output columns
+    pub fn equivalence_properties(&self) -> &EquivalenceProperties {
+        &self.eq_properties
+    }
+
+    /// How the output is split into partitions
+    pub fn output_partitioning(&self) -> &Partitioning {
+        &self.partitioning
+    }
+
+    /// The ordering of each output partition, if any
+    pub fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.output_ordering.as_deref()
+    }
+
+    /// Whether the output is bounded
+    pub fn execution_mode(&self) -> ExecutionMode {
+        self.execution_mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::coalesce_batches::CoalesceBatchesExec;
+    use crate::expressions::col;
+    use crate::filter::FilterExec;
+    use crate::memory::MemoryExec;
+    use crate::projection::ProjectionExec;
+    use crate::sorts::sort::SortExec;
+    use crate::test::make_partition;
+
+    use arrow_schema::SortOptions;
+    use datafusion_expr::Operator;
+    use datafusion_physical_expr::expressions::{lit, BinaryExpr};
+    use datafusion_physical_expr::PhysicalExpr;
+
+    fn sorted_memory_exec() -> Result<Arc<dyn ExecutionPlan>> {
+        let batch = make_partition(10);
+        let schema = batch.schema();
+        let ordering = vec![PhysicalSortExpr {
+            expr: col("i", &schema)?,
+            options: SortOptions::default(),
+        }];
+        let exec =
+            MemoryExec::try_new(&[vec![batch.clone()], vec![batch]], schema, None)?
+                .with_sort_information(vec![ordering]);
+        Ok(Arc::new(exec))
+    }
+
+    /// Checks that the properties of `plan` match its other methods
+    fn assert_consistent(plan: &dyn ExecutionPlan) {
+        let properties = plan.properties();
+        assert_eq!(properties.output_ordering(), plan.output_ordering());
+        assert_eq!(
+            properties.output_partitioning().partition_count(),
+            plan.output_partitioning().partition_count()
+        );
+        if let Some(ordering) = plan.output_ordering() {
+            assert!(plan.equivalence_properties().ordering_satisfy(ordering));
+        }
+    }
+
+    #[test]
+    fn test_properties_from_plan() -> Result<()> {
+        let input = sorted_memory_exec()?;
+        let properties = input.properties();
+        assert_eq!(properties.output_ordering(), input.output_ordering());
+        assert_eq!(properties.output_partitioning().partition_count(), 2);
+        assert_eq!(properties.execution_mode(), ExecutionMode::Bounded);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cached_properties() -> Result<()> {
+        let input = sorted_memory_exec()?;
+        let schema = input.schema();
+        let predicate: Arc<dyn PhysicalExpr> =
+            Arc::new(BinaryExpr::new(col("i", &schema)?, Operator::Gt, lit(5i32)));
+        let filter = Arc::new(FilterExec::try_new(predicate, input)?);
+        assert_consistent(filter.as_ref());
+
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![(col("i", &schema)?, "j".to_string())],
+            filter,
+        )?);
+        assert_consistent(projection.as_ref());
+        assert_eq!(
+            projection.output_ordering().unwrap()[0].to_string(),
+            "j@0 ASC"
+        );
+
+        let coalesce = CoalesceBatchesExec::new(projection, 100);
+        assert_consistent(&coalesce);
+
+        let sort = SortExec::new(
+            vec![PhysicalSortExpr {
+                expr: col("i", &schema)?,
+                options: SortOptions {
+                    descending: true,
+                    nulls_first: true,
+                },
+            }],
+            sorted_memory_exec()?,
+        );
+        assert_consistent(&sort);
+        Ok(())
+    }
+}
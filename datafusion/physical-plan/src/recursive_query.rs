@@ -0,0 +1,556 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the recursive query plan, evaluating recursive CTEs
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::expressions::PhysicalSortExpr;
+use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::work_table::{WorkTable, WorkTableExec};
+use super::{DisplayAs, RecordBatchStream, SendableRecordBatchStream, Statistics};
+use crate::{DisplayFormatType, Distribution, ExecutionPlan, Partitioning};
+
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+use arrow_array::BooleanArray;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{exec_err, internal_err, DataFusionError, Result};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+
+use futures::{ready, Stream, StreamExt};
+
+/// Execution plan of a recursive query, such as a recursive CTE.
+///
+/// The static term is executed first, then the recursive term is executed
+/// repeatedly, reading the rows produced by its previous execution (or by
+/// the static term for the first one) from the [`WorkTable`] of the query,
+/// until it produces no rows. The output of the plan is the rows produced
+/// by all these executions.
+///
+/// When `is_distinct` is true, as for `UNION`, the rows already produced by
+/// the query are discarded, including from the work table, which stops the
+/// recursion on cyclic data.
+///
+/// The number of executions of the recursive term is bounded by the
+/// `datafusion.execution.max_recursive_iterations` setting.
+#[derive(Debug)]
+pub struct RecursiveQueryExec {
+    /// Name of the query
+    name: String,
+    /// The work table shared with the recursive term
+    work_table: Arc<WorkTable>,
+    /// The static term
+    static_term: Arc<dyn ExecutionPlan>,
+    /// The recursive term, reading the work table
+    recursive_term: Arc<dyn ExecutionPlan>,
+    /// Whether the rows already produced are discarded
+    is_distinct: bool,
+    /// The schema of the static term, with the columns that may be null in
+    /// either term nullable
+    schema: SchemaRef,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl RecursiveQueryExec {
+    /// Create a new recursive query plan, whose recursive term reads the
+    /// rows of its previous execution through [`WorkTableExec`]s named
+    /// `name`
+    pub fn try_new(
+        name: String,
+        static_term: Arc<dyn ExecutionPlan>,
+        recursive_term: Arc<dyn ExecutionPlan>,
+        is_distinct: bool,
+    ) -> Result<Self> {
+        let static_schema = static_term.schema();
+        let recursive_schema = recursive_term.schema();
+        if static_schema.fields().len() != recursive_schema.fields().len() {
+            return internal_err!(
+                "The static and recursive terms of recursive query {name} have different numbers of columns"
+            );
+        }
+        let fields = static_schema
+            .fields()
+            .iter()
+            .zip(recursive_schema.fields())
+            .map(|(static_field, recursive_field)| {
+                static_field.as_ref().clone().with_nullable(
+                    static_field.is_nullable() || recursive_field.is_nullable(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            static_schema.metadata().clone(),
+        ));
+
+        let work_table = Arc::new(WorkTable::new());
+        let recursive_term = assign_work_table(recursive_term, &name, &work_table)?;
+        Ok(Self {
+            name,
+            work_table,
+            static_term,
+            recursive_term,
+            is_distinct,
+            schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// Name of the query
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The static term
+    pub fn static_term(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.static_term
+    }
+
+    /// The recursive term
+    pub fn recursive_term(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.recursive_term
+    }
+
+    /// Whether the rows already produced are discarded
+    pub fn is_distinct(&self) -> bool {
+        self.is_distinct
+    }
+}
+
+impl DisplayAs for RecursiveQueryExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "RecursiveQueryExec: name={}, is_distinct={}",
+                    self.name, self.is_distinct
+                )
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for RecursiveQueryExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.static_term.clone(), self.recursive_term.clone()]
+    }
+
+    // Distribution on a recursive query is really tricky to handle.
+    // For now, we are going to use a single partition but in the
+    // future we might find a better way to handle this.
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition, Distribution::SinglePartition]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false, false]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![false, false]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(RecursiveQueryExec::try_new(
+            self.name.clone(),
+            children[0].clone(),
+            children[1].clone(),
+            self.is_distinct,
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        // RecursiveQueryExec has a single output partition
+        if partition != 0 {
+            return internal_err!(
+                "RecursiveQueryExec got an invalid partition {partition} (expected 0)"
+            );
+        }
+
+        let static_stream = self.static_term.execute(partition, context.clone())?;
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let reservation = MemoryConsumer::new(format!("RecursiveQueryExec[{partition}]"))
+            .register(context.memory_pool());
+        let distinct_rows = if self.is_distinct {
+            let fields = self
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| SortField::new(field.data_type().clone()))
+                .collect();
+            Some(DistinctRows {
+                converter: RowConverter::new(fields)?,
+                seen: HashSet::new(),
+            })
+        } else {
+            None
+        };
+        let max_iterations = context
+            .session_config()
+            .options()
+            .execution
+            .max_recursive_iterations;
+
+        Ok(Box::pin(RecursiveQueryStream {
+            name: self.name.clone(),
+            task_context: context,
+            work_table: self.work_table.clone(),
+            recursive_term: self.recursive_term.clone(),
+            stream: static_stream,
+            schema: self.schema(),
+            buffer: vec![],
+            iterations: 0,
+            max_iterations,
+            distinct_rows,
+            reservation,
+            baseline_metrics,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(Statistics::new_unknown(&self.schema()))
+    }
+}
+
+/// The rows already produced by a recursive query with `is_distinct`
+struct DistinctRows {
+    converter: RowConverter,
+    seen: HashSet<OwnedRow>,
+}
+
+impl DistinctRows {
+    /// Removes the rows of `batch` produced before, and remembers the
+    /// others. Returns the number of bytes added.
+    fn filter_batch(&mut self, batch: RecordBatch) -> Result<(RecordBatch, usize)> {
+        let rows = self.converter.convert_columns(batch.columns())?;
+        let mut added_size = 0;
+        let mask = rows
+            .iter()
+            .map(|row| {
+                let is_new = self.seen.insert(row.owned());
+                if is_new {
+                    added_size += row.as_ref().len() + std::mem::size_of::<OwnedRow>();
+                }
+                Some(is_new)
+            })
+            .collect::<BooleanArray>();
+        Ok((filter_record_batch(&batch, &mask)?, added_size))
+    }
+}
+
+/// The stream of a [`RecursiveQueryExec`]
+struct RecursiveQueryStream {
+    /// Name of the query
+    name: String,
+    /// The context to execute the recursive term with
+    task_context: Arc<TaskContext>,
+    /// The work table shared with the recursive term
+    work_table: Arc<WorkTable>,
+    /// The recursive term
+    recursive_term: Arc<dyn ExecutionPlan>,
+    /// The stream of the static term, then of the current execution of the
+    /// recursive term
+    stream: SendableRecordBatchStream,
+    /// The output schema, the schema of the static term
+    schema: SchemaRef,
+    /// The rows produced by the current execution, to be read by the next
+    buffer: Vec<RecordBatch>,
+    /// Number of executions of the recursive term so far
+    iterations: usize,
+    /// Maximum number of executions of the recursive term
+    max_iterations: usize,
+    /// The rows already produced, when they are discarded
+    distinct_rows: Option<DistinctRows>,
+    /// Tracks the memory of the buffered and distinct rows
+    reservation: MemoryReservation,
+    /// Execution metrics
+    baseline_metrics: BaselineMetrics,
+}
+
+impl RecursiveQueryStream {
+    /// Returns the output batch for `batch`, and buffers it for the next
+    /// execution of the recursive term
+    fn push_batch(&mut self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        // the terms may name their columns differently
+        let mut batch =
+            RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec())?;
+        if let Some(distinct_rows) = self.distinct_rows.as_mut() {
+            let (filtered, added_size) = distinct_rows.filter_batch(batch)?;
+            self.reservation.try_grow(added_size)?;
+            batch = filtered;
+        }
+        if batch.num_rows() == 0 {
+            return Ok(None);
+        }
+        self.reservation.try_grow(batch.get_array_memory_size())?;
+        self.buffer.push(batch.clone());
+        Ok(Some(batch))
+    }
+
+    /// Executes the recursive term on the buffered rows, or returns false if
+    /// there are none
+    fn execute_recursive_term(&mut self) -> Result<bool> {
+        if self.buffer.is_empty() {
+            return Ok(false);
+        }
+        if self.iterations >= self.max_iterations {
+            return exec_err!(
+                "Recursive query {} exceeded the maximum of {} iterations, see datafusion.execution.max_recursive_iterations",
+                self.name,
+                self.max_iterations
+            );
+        }
+        self.iterations += 1;
+
+        let batches = std::mem::take(&mut self.buffer);
+        let buffered_size = batches
+            .iter()
+            .map(|batch| batch.get_array_memory_size())
+            .sum();
+        self.reservation.shrink(buffered_size);
+        self.work_table.update(batches);
+
+        // the operators of the recursive term may keep state from their
+        // previous execution, such as the build side of hash joins
+        let recursive_term = reset_plan_states(self.recursive_term.clone())?;
+        self.stream = recursive_term.execute(0, self.task_context.clone())?;
+        Ok(true)
+    }
+
+    fn poll_next_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        loop {
+            match ready!(self.stream.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => match self.push_batch(batch) {
+                    Ok(Some(batch)) => return Poll::Ready(Some(Ok(batch))),
+                    Ok(None) => continue,
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                },
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => match self.execute_recursive_term() {
+                    Ok(true) => continue,
+                    Ok(false) => return Poll::Ready(None),
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                },
+            }
+        }
+    }
+}
+
+impl Stream for RecursiveQueryStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_inner(cx);
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for RecursiveQueryStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Makes the [`WorkTableExec`]s of `plan` named `name` read `work_table`
+fn assign_work_table(
+    plan: Arc<dyn ExecutionPlan>,
+    name: &str,
+    work_table: &Arc<WorkTable>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    plan.transform_down(
+        &|plan| match plan.as_any().downcast_ref::<WorkTableExec>() {
+            Some(exec) if exec.name() == name => Ok(Transformed::Yes(Arc::new(
+                exec.with_work_table(work_table.clone()),
+            ))),
+            _ => Ok(Transformed::No(plan)),
+        },
+    )
+}
+
+/// Recreates the operators of `plan`, so that it does not reuse the state
+/// of a previous execution
+fn reset_plan_states(plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    plan.transform_up(&|plan| {
+        // the work tables read the rows of the current iteration
+        if plan.as_any().is::<WorkTableExec>() || plan.children().is_empty() {
+            Ok(Transformed::No(plan))
+        } else {
+            let children = plan.children();
+            Ok(Transformed::Yes(plan.with_new_children(children)?))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coalesce_partitions::CoalescePartitionsExec;
+    use crate::expressions::{col, lit, BinaryExpr};
+    use crate::filter::FilterExec;
+    use crate::memory::MemoryExec;
+    use crate::projection::ProjectionExec;
+    use crate::{collect, common, PhysicalExpr};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::Int64Array;
+    use datafusion_common::cast::as_int64_array;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_expr::Operator;
+
+    /// `SELECT 1 AS n UNION [ALL] SELECT n + 1 FROM t WHERE n < {limit}`,
+    /// or `SELECT n % {modulo}` to loop over the same values
+    fn counter(
+        start: Vec<i64>,
+        limit: i64,
+        modulo: Option<i64>,
+        is_distinct: bool,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(start))],
+        )?;
+        let static_term =
+            Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None)?);
+
+        let work_table =
+            Arc::new(WorkTableExec::try_new("t".into(), schema.clone(), None)?);
+        let n = col("n", &schema)?;
+        let filter = Arc::new(FilterExec::try_new(
+            Arc::new(BinaryExpr::new(n.clone(), Operator::Lt, lit(limit))),
+            work_table,
+        )?);
+        let next = Arc::new(BinaryExpr::new(n, Operator::Plus, lit(1i64)));
+        let next: Arc<dyn PhysicalExpr> = match modulo {
+            Some(modulo) => {
+                Arc::new(BinaryExpr::new(next, Operator::Modulo, lit(modulo)))
+            }
+            None => next,
+        };
+        let recursive_term = Arc::new(ProjectionExec::try_new(
+            vec![(next, "n_next".to_string())],
+            Arc::new(CoalescePartitionsExec::new(filter)),
+        )?);
+        Ok(Arc::new(RecursiveQueryExec::try_new(
+            "t".into(),
+            static_term,
+            recursive_term,
+            is_distinct,
+        )?))
+    }
+
+    fn values(batches: &[RecordBatch]) -> Vec<i64> {
+        batches
+            .iter()
+            .flat_map(|batch| as_int64_array(batch.column(0)).unwrap().values().to_vec())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn recursive_query_until_empty() -> Result<()> {
+        let plan = counter(vec![1], 5, None, false)?;
+        let batches = collect(plan.clone(), Arc::new(TaskContext::default())).await?;
+        assert_eq!(values(&batches), vec![1, 2, 3, 4, 5]);
+        assert_eq!(batches[0].schema(), plan.schema());
+        assert_eq!(batches[4].schema(), plan.schema());
+
+        // executing the plan again starts from the static term
+        let batches = collect(plan, Arc::new(TaskContext::default())).await?;
+        assert_eq!(values(&batches), vec![1, 2, 3, 4, 5]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn recursive_query_distinct() -> Result<()> {
+        // without deduplication, the values loop forever
+        let plan = counter(vec![0, 0, 1], 10, Some(3), true)?;
+        let batches = collect(plan, Arc::new(TaskContext::default())).await?;
+        assert_eq!(values(&batches), vec![0, 1, 2]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn recursive_query_max_iterations() -> Result<()> {
+        let mut options = ConfigOptions::new();
+        options.execution.max_recursive_iterations = 3;
+        let task_ctx =
+            TaskContext::default().with_session_config(SessionConfig::from(options));
+
+        let task_ctx = Arc::new(task_ctx);
+
+        let plan = counter(vec![1], 10, Some(3), false)?;
+        let stream = plan.execute(0, task_ctx.clone())?;
+        let err = common::collect(stream).await.unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Recursive query t exceeded the maximum of 3 iterations, see datafusion.execution.max_recursive_iterations"
+        );
+
+        // the last iteration producing no rows is within the limit
+        let plan = counter(vec![1], 3, None, false)?;
+        let batches = collect(plan, task_ctx).await?;
+        assert_eq!(values(&batches), vec![1, 2, 3]);
+        Ok(())
+    }
+}
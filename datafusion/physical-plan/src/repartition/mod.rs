@@ -32,6 +32,7 @@ use futures::{FutureExt, StreamExt};
 use hashbrown::HashMap;
 use log::trace;
 use parking_lot::Mutex;
+use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
 use datafusion_common::{arrow_datafusion_err, not_impl_err, DataFusionError, Result};
@@ -44,6 +45,7 @@ use crate::hash_utils::create_hashes;
 use crate::metrics::BaselineMetrics;
 use crate::repartition::distributor_channels::{channels, partition_aware_channels};
 use crate::sorts::streaming_merge;
+use crate::stream::RecordBatchStreamAdapter;
 use crate::{DisplayFormatType, ExecutionPlan, Partitioning, Statistics};
 
 use super::common::{AbortOnDropMany, AbortOnDropSingle, SharedMemoryReservation};
@@ -73,10 +75,18 @@ struct RepartitionExecState {
         ),
     >,
 
+    /// Queue shared by all output partitions in work stealing mode, with the
+    /// number of output partitions that have not been executed yet.
+    work_queue: Option<(SharedWorkQueue, SharedMemoryReservation, usize)>,
+
     /// Helper that ensures that that background job is killed once it is no longer needed.
     abort_helper: Arc<AbortOnDropMany<()>>,
 }
 
+/// Receiving end of the queue of batches shared by all output partitions of a
+/// work stealing [`RepartitionExec`]
+type SharedWorkQueue = Arc<tokio::sync::Mutex<mpsc::Receiver<Result<RecordBatch>>>>;
+
 /// A utility that can be used to partition batches based on [`Partitioning`]
 pub struct BatchPartitioner {
     state: BatchPartitionerState,
@@ -93,6 +103,11 @@ enum BatchPartitionerState {
     RoundRobin {
         num_partitions: usize,
         next_idx: usize,
+        /// Number of rows received so far
+        num_rows: usize,
+        /// Number of rows each partition should receive before the next
+        /// partition becomes active, 0 to use all partitions from the start
+        min_rows_per_partition: usize,
     },
 }
 
@@ -106,6 +121,8 @@ impl BatchPartitioner {
                 BatchPartitionerState::RoundRobin {
                     num_partitions,
                     next_idx: 0,
+                    num_rows: 0,
+                    min_rows_per_partition: 0,
                 }
             }
             Partitioning::Hash(exprs, num_partitions) => BatchPartitionerState::Hash {
@@ -121,6 +138,23 @@ impl BatchPartitioner {
         Ok(Self { state, timer })
     }
 
+    /// For round robin partitioning, only send batches to as many partitions
+    /// as needed for each of them to receive at least `min_rows` of the rows
+    /// received so far, so that small inputs are not spread over many
+    /// partitions. Partitions become active as more rows are received.
+    ///
+    /// Has no effect on hash partitioning, or when `min_rows` is 0
+    pub fn with_min_rows_per_partition(mut self, min_rows: usize) -> Self {
+        if let BatchPartitionerState::RoundRobin {
+            min_rows_per_partition,
+            ..
+        } = &mut self.state
+        {
+            *min_rows_per_partition = min_rows;
+        }
+        self
+    }
+
     /// Partition the provided [`RecordBatch`] into one or more partitioned [`RecordBatch`]
     /// based on the [`Partitioning`] specified on construction
     ///
@@ -154,9 +188,19 @@ impl BatchPartitioner {
                 BatchPartitionerState::RoundRobin {
                     num_partitions,
                     next_idx,
+                    num_rows,
+                    min_rows_per_partition,
                 } => {
+                    let active = if *min_rows_per_partition == 0 {
+                        *num_partitions
+                    } else {
+                        (*num_rows / *min_rows_per_partition + 1).min(*num_partitions)
+                    };
+                    *num_rows += batch.num_rows();
+                    // `next_idx` is below the number of active partitions,
+                    // which never decreases
                     let idx = *next_idx;
-                    *next_idx = (*next_idx + 1) % *num_partitions;
+                    *next_idx = (*next_idx + 1) % active;
                     Box::new(std::iter::once(Ok((idx, batch))))
                 }
                 BatchPartitionerState::Hash {
@@ -308,6 +352,10 @@ pub struct RepartitionExec {
     /// Boolean flag to decide whether to preserve ordering. If true means
     /// `SortPreservingRepartitionExec`, false means `RepartitionExec`.
     preserve_order: bool,
+
+    /// Whether output partitions pull batches from a shared queue instead
+    /// of receiving every `n`th batch, see [`Self::with_work_stealing`]
+    work_stealing: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -368,6 +416,12 @@ impl RepartitionExec {
         self.preserve_order
     }
 
+    /// Whether the output partitions pull batches from a shared queue, see
+    /// [`Self::with_work_stealing`]. Ignored when the order is preserved
+    pub fn work_stealing(&self) -> bool {
+        self.work_stealing
+    }
+
     /// Get name used to display this Exec
     pub fn name(&self) -> &str {
         "RepartitionExec"
@@ -394,6 +448,10 @@ impl DisplayAs for RepartitionExec {
                     write!(f, ", preserve_order=true")?;
                 }
 
+                if self.work_stealing && !self.preserve_order {
+                    write!(f, ", work_stealing=true")?;
+                }
+
                 if let Some(sort_exprs) = self.sort_exprs() {
                     write!(
                         f,
@@ -431,7 +489,7 @@ impl ExecutionPlan for RepartitionExec {
         if self.preserve_order {
             repartition = repartition.with_preserve_order();
         }
-        Ok(Arc::new(repartition))
+        Ok(Arc::new(repartition.with_work_stealing(self.work_stealing)))
     }
 
     /// Specifies whether this plan generates an infinite stream of records.
@@ -485,6 +543,10 @@ impl ExecutionPlan for RepartitionExec {
             self.name(),
             partition
         );
+        if self.work_stealing && !self.preserve_order {
+            return self.execute_work_stealing(context);
+        }
+
         // lock mutexes
         let mut state = self.state.lock();
 
@@ -638,13 +700,33 @@ impl RepartitionExec {
             partitioning,
             state: Arc::new(Mutex::new(RepartitionExecState {
                 channels: HashMap::new(),
+                work_queue: None,
                 abort_helper: Arc::new(AbortOnDropMany::<()>(vec![])),
             })),
             metrics: ExecutionPlanMetricsSet::new(),
             preserve_order: false,
+            work_stealing: false,
         })
     }
 
+    /// Specify if the output partitions of a round robin repartitioning
+    /// should pull batches from a queue shared by all of them, rather than
+    /// each receiving every `n`th batch of the input.
+    ///
+    /// An output partition then only waits when no batch is available at
+    /// all, so consumers that process their batches faster take more of
+    /// them and a slow batch no longer holds back the batches queued behind
+    /// it for the same partition.
+    ///
+    /// This is a no op for hash repartitioning, which must send all rows
+    /// with the same key to the same partition, and when the order of the
+    /// input is preserved.
+    pub fn with_work_stealing(mut self, work_stealing: bool) -> Self {
+        self.work_stealing = work_stealing
+            && matches!(self.partitioning, Partitioning::RoundRobinBatch(_));
+        self
+    }
+
     /// Specify if this reparititoning operation should preserve the order of
     /// rows from its input when producing output. Preserving order is more
     /// expensive at runtime, so should only be set if the output of this
@@ -686,8 +768,15 @@ impl RepartitionExec {
         metrics: RepartitionMetrics,
         context: Arc<TaskContext>,
     ) -> Result<()> {
+        let options = &context.session_config().options().optimizer;
+        let min_rows_per_partition = if options.enable_dynamic_partitioning {
+            options.dynamic_partitioning_min_rows
+        } else {
+            0
+        };
         let mut partitioner =
-            BatchPartitioner::try_new(partitioning, metrics.repartition_time.clone())?;
+            BatchPartitioner::try_new(partitioning, metrics.repartition_time.clone())?
+                .with_min_rows_per_partition(min_rows_per_partition);
 
         // execute the child operator
         let timer = metrics.fetch_time.timer();
@@ -753,6 +842,138 @@ impl RepartitionExec {
         Ok(())
     }
 
+    /// Returns the stream of an output partition in work stealing mode,
+    /// starting to pull from the inputs on the first call
+    fn execute_work_stealing(
+        &self,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let mut state = self.state.lock();
+
+        if state.work_queue.is_none() {
+            let num_input_partitions = self.input.output_partitioning().partition_count();
+            let num_output_partitions = self.partitioning.partition_count();
+            // allow each output partition to have a batch ready
+            let (tx, rx) = mpsc::channel(num_output_partitions.max(1));
+            let reservation = Arc::new(Mutex::new(
+                MemoryConsumer::new(format!("{}[work queue]", self.name()))
+                    .register(context.memory_pool()),
+            ));
+
+            let mut join_handles = Vec::with_capacity(num_input_partitions);
+            for i in 0..num_input_partitions {
+                let r_metrics = RepartitionMetrics::new(i, i, &self.metrics);
+                let input_task: JoinHandle<Result<()>> =
+                    tokio::spawn(Self::pull_into_work_queue(
+                        self.input.clone(),
+                        i,
+                        tx.clone(),
+                        Arc::clone(&reservation),
+                        r_metrics,
+                        context.clone(),
+                    ));
+                join_handles.push(tokio::spawn(Self::wait_for_work_queue_task(
+                    AbortOnDropSingle::new(input_task),
+                    tx.clone(),
+                )));
+            }
+            state.abort_helper = Arc::new(AbortOnDropMany(join_handles));
+            state.work_queue = Some((
+                Arc::new(tokio::sync::Mutex::new(rx)),
+                reservation,
+                num_output_partitions,
+            ));
+        }
+
+        let (queue, reservation, remaining) =
+            state.work_queue.as_mut().expect("work queue initialized");
+        let queue = Arc::clone(queue);
+        let reservation = Arc::clone(reservation);
+        *remaining -= 1;
+        if *remaining == 0 {
+            // all output partitions are running, a new execution starts over
+            state.work_queue = None;
+        }
+        let drop_helper = Arc::clone(&state.abort_helper);
+
+        // whichever output partition polls first receives the next batch
+        let stream = futures::stream::unfold(
+            (queue, reservation, drop_helper),
+            |(queue, reservation, drop_helper)| async move {
+                let batch = queue.lock().await.recv().await?;
+                if let Ok(batch) = &batch {
+                    reservation.lock().shrink(batch.get_array_memory_size());
+                }
+                Some((batch, (queue, reservation, drop_helper)))
+            },
+        );
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    /// Pulls data from the specified input partition into the queue shared
+    /// by all output partitions in work stealing mode
+    async fn pull_into_work_queue(
+        input: Arc<dyn ExecutionPlan>,
+        partition: usize,
+        tx: mpsc::Sender<Result<RecordBatch>>,
+        reservation: SharedMemoryReservation,
+        metrics: RepartitionMetrics,
+        context: Arc<TaskContext>,
+    ) -> Result<()> {
+        let timer = metrics.fetch_time.timer();
+        let mut stream = input.execute(partition, context)?;
+        timer.done();
+
+        loop {
+            let timer = metrics.fetch_time.timer();
+            let result = stream.next().await;
+            timer.done();
+
+            let batch = match result {
+                Some(result) => result?,
+                None => break,
+            };
+            let size = batch.get_array_memory_size();
+            reservation.lock().try_grow(size)?;
+
+            let timer = metrics.send_time.timer();
+            let sent = tx.send(Ok(batch)).await;
+            timer.done();
+            if sent.is_err() {
+                // all output partitions hung up, e.g. because of a LIMIT
+                reservation.lock().shrink(size);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Waits for `input_task` which is pulling one of the inputs into the
+    /// shared queue of a work stealing repartitioning to complete, and sends
+    /// its error, if any, to the queue.
+    async fn wait_for_work_queue_task(
+        input_task: AbortOnDropSingle<Result<()>>,
+        tx: mpsc::Sender<Result<RecordBatch>>,
+    ) {
+        // note we ignore errors on send (.ok) as that means the receivers have already shutdown.
+        match input_task.await {
+            Err(e) => {
+                let err = DataFusionError::Context(
+                    "Join Error".to_string(),
+                    Box::new(DataFusionError::External(Box::new(e))),
+                );
+                tx.send(Err(err)).await.ok();
+            }
+            Ok(Err(e)) => {
+                tx.send(Err(e)).await.ok();
+            }
+            Ok(Ok(())) => {}
+        }
+    }
+
     /// Waits for `input_task` which is consuming one of the inputs to
     /// complete. Upon each successful completion, sends a `None` to
     /// each of the output tx channels to signal one of the inputs is
@@ -929,6 +1150,7 @@ mod tests {
 
     use datafusion_common::cast::as_string_array;
     use datafusion_common::{assert_batches_sorted_eq, exec_err};
+    use datafusion_execution::config::SessionConfig;
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 
     use crate::{
@@ -1002,6 +1224,98 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn many_to_many_round_robin_work_stealing() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = test_schema();
+        let partition = create_vec_batches(50);
+        let partitions = vec![partition.clone(), partition.clone(), partition.clone()];
+        let exec = MemoryExec::try_new(&partitions, schema, None)?;
+        let exec =
+            RepartitionExec::try_new(Arc::new(exec), Partitioning::RoundRobinBatch(5))?
+                .with_work_stealing(true);
+        assert!(exec.work_stealing());
+
+        // execute all output partitions, and drain them one after the other
+        let streams = (0..5)
+            .map(|i| exec.execute(i, task_ctx.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let mut output_partitions = vec![];
+        for stream in streams {
+            output_partitions.push(crate::common::collect(stream).await?);
+        }
+
+        // the first output partition takes all the batches, as no other
+        // partition asks for one before the inputs are exhausted
+        assert_eq!(150, output_partitions[0].len());
+        for output_partition in &output_partitions[1..] {
+            assert!(output_partition.is_empty());
+        }
+
+        // executing again starts over
+        let stream = exec.execute(3, task_ctx)?;
+        assert_eq!(150, crate::common::collect(stream).await?.len());
+        Ok(())
+    }
+
+    #[test]
+    fn round_robin_min_rows_per_partition() -> Result<()> {
+        let mut partitioner = BatchPartitioner::try_new(
+            Partitioning::RoundRobinBatch(4),
+            metrics::Time::new(),
+        )?
+        .with_min_rows_per_partition(16);
+
+        // batches of 8 rows, a new partition becomes active every 16 rows
+        let mut partitions = vec![];
+        for batch in create_vec_batches(8) {
+            partitioner.partition(batch, |partition, _| {
+                partitions.push(partition);
+                Ok(())
+            })?;
+        }
+        assert_eq!(partitions, vec![0, 0, 0, 1, 0, 1, 2, 3]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn small_input_round_robin_single_partition() -> Result<()> {
+        let mut config = SessionConfig::new();
+        config.options_mut().optimizer.enable_dynamic_partitioning = true;
+        let task_ctx = Arc::new(TaskContext::default().with_session_config(config));
+
+        let schema = test_schema();
+        let exec = MemoryExec::try_new(&[create_vec_batches(50)], schema, None)?;
+        let exec =
+            RepartitionExec::try_new(Arc::new(exec), Partitioning::RoundRobinBatch(4))?;
+
+        let mut output_partitions = vec![];
+        for i in 0..4 {
+            let stream = exec.execute(i, task_ctx.clone())?;
+            output_partitions.push(crate::common::collect(stream).await?);
+        }
+
+        // 400 rows are well below the default minimum rows per partition
+        assert_eq!(50, output_partitions[0].len());
+        for output_partition in &output_partitions[1..] {
+            assert!(output_partition.is_empty());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn work_stealing_is_no_op_for_hash_partitioning() -> Result<()> {
+        let schema = test_schema();
+        let exec = MemoryExec::try_new(&[create_vec_batches(1)], schema.clone(), None)?;
+        let exec = RepartitionExec::try_new(
+            Arc::new(exec),
+            Partitioning::Hash(vec![col("c0", &schema)?], 8),
+        )?
+        .with_work_stealing(true);
+        assert!(!exec.work_stealing());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn many_to_many_hash_partition() -> Result<()> {
         // define input partitions
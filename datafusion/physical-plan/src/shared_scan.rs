@@ -0,0 +1,336 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the [`SharedScanExec`] operator, which reads its input once for
+//! several consumers
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::common::AbortOnDropSingle;
+use crate::stream::RecordBatchStreamAdapter;
+use crate::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::{DataFusionError, Result, SharedResult, Statistics};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{EquivalenceProperties, PhysicalSortExpr};
+
+use futures::StreamExt;
+use parking_lot::Mutex;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Input of a group of [`SharedScanExec`]s, executed once per partition for
+/// all of them
+#[derive(Debug)]
+struct SharedScan {
+    input: Arc<dyn ExecutionPlan>,
+    /// Number of [`SharedScanExec`]s reading `input`
+    consumers: usize,
+    /// The partitions of `input` being executed, by partition index
+    partitions: Mutex<HashMap<usize, SharedPartition>>,
+}
+
+/// A partition of the input of a [`SharedScan`] being executed
+#[derive(Debug)]
+struct SharedPartition {
+    /// The receiver of the batches of the partition for each consumer, until
+    /// the consumer executes the partition
+    receivers: Vec<Option<UnboundedReceiver<SharedResult<RecordBatch>>>>,
+    /// The task executing the partition, aborted once the plan is dropped
+    _task: AbortOnDropSingle<()>,
+}
+
+impl SharedScan {
+    /// Returns the stream of `partition` of the input for `consumer`
+    ///
+    /// The first consumer executing a partition starts a task reading it,
+    /// which sends each batch to all consumers. A consumer executing a
+    /// partition a second time, such as when the plan is executed again,
+    /// starts a new read of the partition for all consumers.
+    fn execute(
+        &self,
+        consumer: usize,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let mut partitions = self.partitions.lock();
+        let receiver = match partitions
+            .get_mut(&partition)
+            .and_then(|shared| shared.receivers[consumer].take())
+        {
+            Some(receiver) => receiver,
+            None => {
+                let input = self.input.execute(partition, context)?;
+                let (senders, mut receivers): (Vec<_>, Vec<_>) = (0..self.consumers)
+                    .map(|_| {
+                        let (sender, receiver) = unbounded_channel();
+                        (sender, Some(receiver))
+                    })
+                    .unzip();
+                let receiver = receivers[consumer].take().unwrap();
+                let task = tokio::spawn(send_to_consumers(input, senders));
+                partitions.insert(
+                    partition,
+                    SharedPartition {
+                        receivers,
+                        _task: AbortOnDropSingle::new(task),
+                    },
+                );
+                receiver
+            }
+        };
+
+        let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+            let batch = receiver.recv().await?;
+            let batch = batch.map_err(|e| DataFusionError::External(Box::new(e)));
+            Some((batch, receiver))
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.input.schema(),
+            stream,
+        )))
+    }
+}
+
+/// Sends the batches of `input` to all the consumers, until the input is
+/// exhausted, fails, or all the consumers are dropped
+async fn send_to_consumers(
+    mut input: SendableRecordBatchStream,
+    senders: Vec<UnboundedSender<SharedResult<RecordBatch>>>,
+) {
+    while let Some(batch) = input.next().await {
+        let batch = batch.map_err(Arc::new);
+        let failed = batch.is_err();
+        let mut sent = false;
+        for sender in &senders {
+            sent |= sender.send(batch.clone()).is_ok();
+        }
+        if failed || !sent {
+            return;
+        }
+    }
+}
+
+/// Execution plan that reads its input once for several consumers.
+///
+/// A group of `SharedScanExec`s created by [`SharedScanExec::consumers`]
+/// share the same input, typically a scan of files appearing several times in
+/// a plan, such as the scans of a self join. Each partition of the input is
+/// executed once for the group, and each of its batches is sent to all the
+/// `SharedScanExec`s of the group.
+///
+/// The batches are buffered in memory until each `SharedScanExec` reads them,
+/// so the consumers of a join, which read their inputs one after the other,
+/// do not wait for each other.
+#[derive(Debug)]
+pub struct SharedScanExec {
+    scan: Arc<SharedScan>,
+    /// Index of this consumer among the consumers of `scan`
+    consumer: usize,
+}
+
+impl SharedScanExec {
+    /// Create `count` SharedScanExecs reading `input` once
+    pub fn consumers(input: Arc<dyn ExecutionPlan>, count: usize) -> Vec<Self> {
+        let scan = Arc::new(SharedScan {
+            input,
+            consumers: count,
+            partitions: Mutex::new(HashMap::new()),
+        });
+        (0..count)
+            .map(|consumer| Self {
+                scan: scan.clone(),
+                consumer,
+            })
+            .collect()
+    }
+
+    /// The shared input
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.scan.input
+    }
+
+    /// Index of this consumer among the consumers of the input
+    pub fn consumer(&self) -> usize {
+        self.consumer
+    }
+
+    /// Number of consumers of the input
+    pub fn consumer_count(&self) -> usize {
+        self.scan.consumers
+    }
+}
+
+impl DisplayAs for SharedScanExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "SharedScanExec: consumer={}, consumers={}",
+                    self.consumer, self.scan.consumers
+                )
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for SharedScanExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.scan.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.scan.input.output_partitioning()
+    }
+
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        Ok(children[0])
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.scan.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        self.scan.input.equivalence_properties()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.scan.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let input = children.swap_remove(0);
+        if Arc::ptr_eq(&input, &self.scan.input) {
+            return Ok(self);
+        }
+        // A new input is no longer shared with the other consumers
+        let consumer = Self::consumers(input, 1).swap_remove(0);
+        Ok(Arc::new(consumer))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        self.scan.execute(self.consumer, partition, context)
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.scan.input.statistics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collect;
+    use crate::test::exec::MockExec;
+    use crate::test::{make_partition, scan_partitioned};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    /// Returns the number of rows of each consumer of `input`, executing the
+    /// consumers one after the other
+    async fn collect_consumers(
+        input: Arc<dyn ExecutionPlan>,
+        count: usize,
+    ) -> Result<Vec<usize>> {
+        let context = Arc::new(TaskContext::default());
+        let mut rows = vec![];
+        for consumer in SharedScanExec::consumers(input, count) {
+            let batches = collect(Arc::new(consumer), context.clone()).await?;
+            rows.push(batches.iter().map(|b| b.num_rows()).sum());
+        }
+        Ok(rows)
+    }
+
+    #[tokio::test]
+    async fn test_shared_scan() -> Result<()> {
+        let input = scan_partitioned(2);
+        assert_eq!(collect_consumers(input, 3).await?, vec![200, 200, 200]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_scan_concurrent_consumers() -> Result<()> {
+        let batch = make_partition(10);
+        let input = Arc::new(MockExec::new(vec![Ok(batch.clone())], batch.schema()));
+        let consumers = SharedScanExec::consumers(input, 2);
+        let context = Arc::new(TaskContext::default());
+        let first = consumers[0].execute(0, context.clone())?;
+        let second = consumers[1].execute(0, context)?;
+        let first = crate::common::collect(first).await?;
+        let second = crate::common::collect(second).await?;
+        assert_eq!(first, vec![batch.clone()]);
+        assert_eq!(second, vec![batch]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_scan_error() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let input = Arc::new(MockExec::new(
+            vec![Err(DataFusionError::Execution("bad data".into()))],
+            schema,
+        ));
+        for consumer in SharedScanExec::consumers(input, 2) {
+            let err = collect(Arc::new(consumer), Arc::new(TaskContext::default()))
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("bad data"), "{err}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_scan_new_children() -> Result<()> {
+        let input = scan_partitioned(1);
+        let consumer: Arc<dyn ExecutionPlan> =
+            Arc::new(SharedScanExec::consumers(input.clone(), 2).swap_remove(0));
+
+        let same = consumer.clone().with_new_children(vec![input])?;
+        assert!(Arc::ptr_eq(&same, &consumer));
+
+        let other = consumer.with_new_children(vec![scan_partitioned(1)])?;
+        let other = other.as_any().downcast_ref::<SharedScanExec>().unwrap();
+        assert_eq!(other.consumer_count(), 1);
+        Ok(())
+    }
+}
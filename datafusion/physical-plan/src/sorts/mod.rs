@@ -21,6 +21,7 @@ mod builder;
 mod cursor;
 mod index;
 mod merge;
+pub mod partial_sort;
 pub mod sort;
 pub mod sort_preserving_merge;
 mod stream;
@@ -0,0 +1,527 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Partial sort of inputs already sorted on a prefix of the sort
+//! expressions, sorting the runs of rows with equal prefix values one at a
+//! time rather than the whole input.
+//!
+//! For example, with an input sorted on `a`
+//!
+//! ```text
+//! +---+---+
+//! | a | b |
+//! +---+---+
+//! | 1 | 3 |
+//! | 1 | 1 |
+//! | 2 | 2 |
+//! | 2 | 0 |
+//! +---+---+
+//! ```
+//!
+//! sorting on `a, b` only sorts the rows of `a = 1`, then the rows of
+//! `a = 2`, so that the rows of `a = 1` are emitted as soon as the first
+//! row of `a = 2` arrives. The memory used is bounded by the size of the
+//! runs rather than the size of the input, and unbounded inputs can be
+//! sorted.
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::expressions::PhysicalSortExpr;
+use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::sorts::sort::sort_batch;
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
+    RecordBatchStream, SendableRecordBatchStream, Statistics,
+};
+
+use arrow::compute::concat_batches;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::utils::evaluate_partition_ranges;
+use datafusion_common::{plan_err, DataFusionError, Result};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::EquivalenceProperties;
+
+use futures::{ready, Stream, StreamExt};
+use log::trace;
+
+/// Partial sort execution plan, sorting an input already sorted on the
+/// first `common_prefix_length` sort expressions, see the
+/// [module documentation](self)
+#[derive(Debug, Clone)]
+pub struct PartialSortExec {
+    /// Input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Sort expressions
+    expr: Vec<PhysicalSortExpr>,
+    /// The number of leading sort expressions the input is already sorted
+    /// on
+    common_prefix_length: usize,
+    /// Containing all metrics set created during sort
+    metrics_set: ExecutionPlanMetricsSet,
+    /// Preserve partitions of input plan. If false, the input partitions
+    /// will be sorted and merged into a single output partition.
+    preserve_partitioning: bool,
+    /// Fetch highest/lowest n results
+    fetch: Option<usize>,
+}
+
+impl PartialSortExec {
+    /// Create a new partial sort execution plan, with `input` sorted on the
+    /// first `common_prefix_length` expressions of `expr`
+    pub fn new(
+        expr: Vec<PhysicalSortExpr>,
+        input: Arc<dyn ExecutionPlan>,
+        common_prefix_length: usize,
+    ) -> Self {
+        assert!(
+            common_prefix_length > 0,
+            "common_prefix_length must be at least 1"
+        );
+        Self {
+            input,
+            expr,
+            common_prefix_length,
+            metrics_set: ExecutionPlanMetricsSet::new(),
+            preserve_partitioning: false,
+            fetch: None,
+        }
+    }
+
+    /// Whether this `PartialSortExec` preserves partitioning of the children
+    pub fn preserve_partitioning(&self) -> bool {
+        self.preserve_partitioning
+    }
+
+    /// Specify the partitioning behavior of this partial sort exec, see
+    /// [`SortExec::with_preserve_partitioning`]
+    ///
+    /// [`SortExec::with_preserve_partitioning`]: crate::sorts::sort::SortExec::with_preserve_partitioning
+    pub fn with_preserve_partitioning(mut self, preserve_partitioning: bool) -> Self {
+        self.preserve_partitioning = preserve_partitioning;
+        self
+    }
+
+    /// Modify how many rows to include in the result
+    pub fn with_fetch(mut self, fetch: Option<usize>) -> Self {
+        self.fetch = fetch;
+        self
+    }
+
+    /// Input schema
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Sort expressions
+    pub fn expr(&self) -> &[PhysicalSortExpr] {
+        &self.expr
+    }
+
+    /// The number of leading sort expressions the input is already sorted
+    /// on
+    pub fn common_prefix_length(&self) -> usize {
+        self.common_prefix_length
+    }
+
+    /// If `Some(fetch)`, limits output to only the first "fetch" items
+    pub fn fetch(&self) -> Option<usize> {
+        self.fetch
+    }
+}
+
+impl DisplayAs for PartialSortExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let expr = PhysicalSortExpr::format_list(&self.expr);
+                let common_prefix_length = self.common_prefix_length;
+                match self.fetch {
+                    Some(fetch) => write!(
+                        f,
+                        "PartialSortExec: TopK(fetch={fetch}), expr=[{expr}], common_prefix_length=[{common_prefix_length}]"
+                    ),
+                    None => write!(
+                        f,
+                        "PartialSortExec: expr=[{expr}], common_prefix_length=[{common_prefix_length}]"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for PartialSortExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        if self.preserve_partitioning {
+            self.input.output_partitioning()
+        } else {
+            Partitioning::UnknownPartitioning(1)
+        }
+    }
+
+    /// The runs are emitted as soon as they end, so that unbounded inputs
+    /// can be sorted
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        Ok(children[0])
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.preserve_partitioning {
+            vec![Distribution::UnspecifiedDistribution]
+        } else {
+            vec![Distribution::SinglePartition]
+        }
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        Some(&self.expr)
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        // Reset the ordering equivalence class with the new ordering:
+        self.input
+            .equivalence_properties()
+            .with_reorder(self.expr.to_vec())
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let new_partial_sort = PartialSortExec::new(
+            self.expr.clone(),
+            children[0].clone(),
+            self.common_prefix_length,
+        )
+        .with_fetch(self.fetch)
+        .with_preserve_partitioning(self.preserve_partitioning);
+
+        Ok(Arc::new(new_partial_sort))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        trace!("Start PartialSortExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+
+        if !self
+            .input
+            .equivalence_properties()
+            .ordering_satisfy(&self.expr[..self.common_prefix_length])
+        {
+            return plan_err!(
+                "PartialSortExec expects its input to be sorted on the first {} sort expressions",
+                self.common_prefix_length
+            );
+        }
+
+        let reservation = MemoryConsumer::new(format!("PartialSortExec[{partition}]"))
+            .register(context.memory_pool());
+        let input = self.input.execute(partition, context)?;
+
+        Ok(Box::pin(PartialSortStream {
+            input,
+            expr: self.expr.clone(),
+            common_prefix_length: self.common_prefix_length,
+            in_mem_batches: vec![],
+            reservation,
+            fetch: self.fetch,
+            is_closed: false,
+            baseline_metrics: BaselineMetrics::new(&self.metrics_set, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics_set.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.input.statistics()
+    }
+}
+
+/// A stream sorting the runs of rows with equal prefix values of its input
+struct PartialSortStream {
+    /// The input plan
+    input: SendableRecordBatchStream,
+    /// Sort expressions
+    expr: Vec<PhysicalSortExpr>,
+    /// The number of leading sort expressions the input is already sorted
+    /// on
+    common_prefix_length: usize,
+    /// The rows of the run that has not ended yet
+    in_mem_batches: Vec<RecordBatch>,
+    /// Tracks the memory used by `in_mem_batches`
+    reservation: MemoryReservation,
+    /// The number of rows left to emit, if limited
+    fetch: Option<usize>,
+    /// Whether the stream has finished returning all of its data or not
+    is_closed: bool,
+    /// Execution metrics
+    baseline_metrics: BaselineMetrics,
+}
+
+impl Stream for PartialSortStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_inner(cx);
+        self.baseline_metrics.record_poll(poll)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // we can't predict the size of incoming batches so re-use the size hint from the input
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for PartialSortStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+impl PartialSortStream {
+    fn poll_next_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        loop {
+            if self.is_closed {
+                return Poll::Ready(None);
+            }
+            match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    match self.add_batch(batch) {
+                        Ok(Some(sorted)) => return Poll::Ready(Some(Ok(sorted))),
+                        Ok(None) => continue,
+                        Err(e) => return Poll::Ready(Some(Err(e))),
+                    };
+                }
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => {
+                    // the end of the input ends the last run
+                    self.is_closed = true;
+                    return Poll::Ready(self.sort_in_mem_batches().transpose());
+                }
+            }
+        }
+    }
+
+    /// Adds `batch` to the current run, returning the sorted rows of the runs
+    /// it ends, if any
+    fn add_batch(&mut self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        self.reservation.try_grow(batch.get_array_memory_size())?;
+        let Some(slice_point) = self.get_slice_point(&batch)? else {
+            self.in_mem_batches.push(batch);
+            return Ok(None);
+        };
+        self.in_mem_batches.push(batch.slice(0, slice_point));
+        let remaining = batch.slice(slice_point, batch.num_rows() - slice_point);
+        let sorted = self.sort_in_mem_batches()?;
+        self.reservation
+            .try_grow(remaining.get_array_memory_size())?;
+        self.in_mem_batches.push(remaining);
+        Ok(sorted)
+    }
+
+    /// Sorts the rows of the ended runs, and releases their memory
+    fn sort_in_mem_batches(&mut self) -> Result<Option<RecordBatch>> {
+        if self.in_mem_batches.is_empty() {
+            return Ok(None);
+        }
+        let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+        let _timer = elapsed_compute.timer();
+        let schema = self.input.schema();
+        let input_batch = concat_batches(&schema, &self.in_mem_batches)?;
+        self.in_mem_batches.clear();
+        self.reservation.free();
+        let result = sort_batch(&input_batch, &self.expr, self.fetch)?;
+        if let Some(fetch) = self.fetch.as_mut() {
+            *fetch -= result.num_rows();
+            if *fetch == 0 {
+                self.is_closed = true;
+            }
+        }
+        if result.num_rows() == 0 {
+            return Ok(None);
+        }
+        Ok(Some(result))
+    }
+
+    /// Returns the start of the last run of `batch`, that may continue in
+    /// the next batches, or `None` if all of its rows may belong to the
+    /// current run.
+    ///
+    /// For example, if the runs of `batch` are `0..100`, `100..200` and
+    /// `200..300`, the rows up to `200` are complete, while the run
+    /// `200..300` may continue in the next batch.
+    fn get_slice_point(&self, batch: &RecordBatch) -> Result<Option<usize>> {
+        let common_prefix_sort_keys = self.expr[..self.common_prefix_length]
+            .iter()
+            .map(|expr| expr.evaluate_to_sort_column(batch))
+            .collect::<Result<Vec<_>>>()?;
+        let partition_points =
+            evaluate_partition_ranges(batch.num_rows(), &common_prefix_sort_keys)?;
+        if partition_points.len() >= 2 {
+            Ok(Some(partition_points[partition_points.len() - 2].end))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collect;
+    use crate::expressions::col;
+    use crate::memory::MemoryExec;
+
+    use arrow::array::Int32Array;
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::assert_batches_eq;
+
+    fn batch(a: Vec<i32>, b: Vec<i32>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+        )
+        .unwrap()
+    }
+
+    fn sort_expr(schema: &SchemaRef) -> Vec<PhysicalSortExpr> {
+        ["a", "b"]
+            .iter()
+            .map(|name| PhysicalSortExpr {
+                expr: col(name, schema).unwrap(),
+                options: SortOptions::default(),
+            })
+            .collect()
+    }
+
+    fn partial_sort(
+        batches: Vec<RecordBatch>,
+        fetch: Option<usize>,
+    ) -> Arc<PartialSortExec> {
+        let schema = batches[0].schema();
+        let sort_exprs = sort_expr(&schema);
+        let input = MemoryExec::try_new(&[batches], schema, None)
+            .unwrap()
+            .with_sort_information(vec![sort_exprs[..1].to_vec()]);
+        Arc::new(PartialSortExec::new(sort_exprs, Arc::new(input), 1).with_fetch(fetch))
+    }
+
+    #[tokio::test]
+    async fn test_partial_sort() -> Result<()> {
+        // the run of a = 2 spans two batches
+        let batches = vec![
+            batch(vec![1, 1, 2, 2], vec![3, 1, 4, 2]),
+            batch(vec![2, 3, 3], vec![0, 2, 1]),
+        ];
+        let partial_sort = partial_sort(batches, None);
+        let result = collect(partial_sort, Arc::new(TaskContext::default())).await?;
+
+        // the runs are emitted as soon as they end
+        let num_rows = result.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+        assert_eq!(num_rows, vec![2, 3, 2]);
+        let expected = [
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 1 | 1 |",
+            "| 1 | 3 |",
+            "| 2 | 0 |",
+            "| 2 | 2 |",
+            "| 2 | 4 |",
+            "| 3 | 1 |",
+            "| 3 | 2 |",
+            "+---+---+",
+        ];
+        assert_batches_eq!(expected, &result);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partial_sort_with_fetch() -> Result<()> {
+        let batches = vec![
+            batch(vec![1, 1, 2, 2], vec![3, 1, 4, 2]),
+            batch(vec![2, 3, 3], vec![0, 2, 1]),
+        ];
+        let partial_sort = partial_sort(batches, Some(3));
+        let result = collect(partial_sort, Arc::new(TaskContext::default())).await?;
+
+        let expected = [
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 1 | 1 |",
+            "| 1 | 3 |",
+            "| 2 | 0 |",
+            "+---+---+",
+        ];
+        assert_batches_eq!(expected, &result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_sort_display() {
+        let partial_sort = partial_sort(vec![batch(vec![], vec![])], None);
+        let display = crate::displayable(partial_sort.as_ref())
+            .one_line()
+            .to_string();
+        assert_eq!(
+            display.trim(),
+            "PartialSortExec: expr=[a@0 ASC,b@1 ASC], common_prefix_length=[1]"
+        );
+    }
+}
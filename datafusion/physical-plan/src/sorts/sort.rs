@@ -196,6 +196,11 @@ impl ExternalSorterMetrics {
 ///
 ///  in_mem_batches
 /// ```
+///
+/// Merging reads from all spill files at once. To bound the number of open
+/// files and read buffers, if there are more than `merge_fan_in` sorted runs,
+/// the oldest spill files are first merged into longer runs on disk, in as
+/// many passes as needed for the remaining runs to be merged at once.
 struct ExternalSorter {
     /// schema of the output (and the input)
     schema: SchemaRef,
@@ -230,6 +235,10 @@ struct ExternalSorter {
     /// the data will be concated and sorted in place rather than
     /// sort/merged.
     sort_in_place_threshold_bytes: usize,
+    /// The maximum number of sorted runs merged at once
+    merge_fan_in: usize,
+    /// The number of batches read ahead from each spill file while merging
+    spill_read_buffer_batches: usize,
 }
 
 impl ExternalSorter {
@@ -244,6 +253,8 @@ impl ExternalSorter {
         fetch: Option<usize>,
         sort_spill_reservation_bytes: usize,
         sort_in_place_threshold_bytes: usize,
+        merge_fan_in: usize,
+        spill_read_buffer_batches: usize,
         metrics: &ExecutionPlanMetricsSet,
         runtime: Arc<RuntimeEnv>,
     ) -> Self {
@@ -270,6 +281,8 @@ impl ExternalSorter {
             batch_size,
             sort_spill_reservation_bytes,
             sort_in_place_threshold_bytes,
+            merge_fan_in: merge_fan_in.max(2),
+            spill_read_buffer_batches,
         }
     }
 
@@ -321,8 +334,17 @@ impl ExternalSorter {
     ///
     /// 2. A combined streaming merge incorporating both in-memory
     /// batches and data from spill files on disk.
-    fn sort(&mut self) -> Result<SendableRecordBatchStream> {
+    async fn sort(&mut self) -> Result<SendableRecordBatchStream> {
         if self.spilled_before() {
+            // the in memory batches are merged as one more run
+            let in_mem_runs = usize::from(!self.in_mem_batches.is_empty());
+            let max_spills = self.merge_fan_in - in_mem_runs;
+            while self.spills.len() > max_spills {
+                // merging `count` runs into one leaves `count - 1` fewer runs
+                let count = (self.spills.len() - max_spills + 1).min(self.merge_fan_in);
+                self.merge_spills(count).await?;
+            }
+
             let mut streams = vec![];
             if !self.in_mem_batches.is_empty() {
                 let in_mem_stream =
@@ -330,15 +352,8 @@ impl ExternalSorter {
                 streams.push(in_mem_stream);
             }
 
-            for spill in self.spills.drain(..) {
-                if !spill.path().exists() {
-                    return Err(DataFusionError::Internal(format!(
-                        "Spill file {:?} does not exist",
-                        spill.path()
-                    )));
-                }
-                let stream = read_spill_as_stream(spill, self.schema.clone())?;
-                streams.push(stream);
+            for spill in std::mem::take(&mut self.spills) {
+                streams.push(self.read_spill(spill)?);
             }
 
             streaming_merge(
@@ -357,6 +372,54 @@ impl ExternalSorter {
         }
     }
 
+    /// Returns a stream of the sorted run of `spill`
+    fn read_spill(&self, spill: RefCountedTempFile) -> Result<SendableRecordBatchStream> {
+        if !spill.path().exists() {
+            return Err(DataFusionError::Internal(format!(
+                "Spill file {:?} does not exist",
+                spill.path()
+            )));
+        }
+        read_spill_as_buffered_stream(
+            spill,
+            self.schema.clone(),
+            self.spill_read_buffer_batches,
+        )
+    }
+
+    /// Merges the `count` oldest spill files into a new spill file with a
+    /// single sorted run
+    async fn merge_spills(&mut self, count: usize) -> Result<()> {
+        debug!("Merging {count} spill files of ExternalSorter into one");
+
+        let spills = self.spills.drain(..count).collect::<Vec<_>>();
+        let streams = spills
+            .into_iter()
+            .map(|spill| self.read_spill(spill))
+            .collect::<Result<Vec<_>>>()?;
+        let mut merged = streaming_merge(
+            streams,
+            self.schema.clone(),
+            &self.expr,
+            self.metrics.baseline.intermediate(),
+            self.batch_size,
+            self.fetch,
+            self.reservation.new_empty(),
+        )?;
+
+        let spillfile = self.runtime.disk_manager.create_tmp_file("Sorting")?;
+        let mut writer = IPCWriter::new(spillfile.path(), self.schema.as_ref())?;
+        while let Some(batch) = merged.next().await {
+            writer.write(&batch?)?;
+        }
+        writer.finish()?;
+
+        self.metrics.spill_count.add(1);
+        self.metrics.spilled_bytes.add(writer.num_bytes as usize);
+        self.spills.push(spillfile);
+        Ok(())
+    }
+
     /// How much memory is buffered in this `ExternalSorter`?
     fn used(&self) -> usize {
         self.reservation.size()
@@ -615,7 +678,16 @@ pub(crate) fn read_spill_as_stream(
     path: RefCountedTempFile,
     schema: SchemaRef,
 ) -> Result<SendableRecordBatchStream> {
-    let mut builder = RecordBatchReceiverStream::builder(schema, 2);
+    read_spill_as_buffered_stream(path, schema, 2)
+}
+
+/// Reads a spill file as a stream, reading up to `buffer` batches ahead
+fn read_spill_as_buffered_stream(
+    path: RefCountedTempFile,
+    schema: SchemaRef,
+    buffer: usize,
+) -> Result<SendableRecordBatchStream> {
+    let mut builder = RecordBatchReceiverStream::builder(schema, buffer.max(1));
     let sender = builder.tx();
 
     builder.spawn_blocking(move || {
@@ -897,6 +969,8 @@ impl ExecutionPlan for SortExec {
                 self.fetch,
                 execution_options.sort_spill_reservation_bytes,
                 execution_options.sort_in_place_threshold_bytes,
+                execution_options.sort_spill_merge_fan_in,
+                execution_options.sort_spill_read_buffer_batches,
                 &self.metrics_set,
                 context.runtime_env(),
             );
@@ -908,7 +982,7 @@ impl ExecutionPlan for SortExec {
                         let batch = batch?;
                         sorter.insert_batch(batch).await?;
                     }
-                    sorter.sort()
+                    sorter.sort().await
                 })
                 .try_flatten(),
             )))
@@ -1031,6 +1105,61 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_sort_spill_multi_pass_merge() -> Result<()> {
+        // sorts the same input as `test_sort_spill` with the given merge fan-in,
+        // returning the output and the number of spill files written
+        async fn sort_spilled(merge_fan_in: usize) -> Result<(Vec<RecordBatch>, usize)> {
+            let mut session_config = SessionConfig::new();
+            session_config
+                .options_mut()
+                .execution
+                .sort_spill_merge_fan_in = merge_fan_in;
+            let sort_spill_reservation_bytes = session_config
+                .options()
+                .execution
+                .sort_spill_reservation_bytes;
+            let rt_config = RuntimeConfig::new()
+                .with_memory_limit(sort_spill_reservation_bytes + 12288, 1.0);
+            let task_ctx = Arc::new(
+                TaskContext::default()
+                    .with_session_config(session_config)
+                    .with_runtime(Arc::new(RuntimeEnv::new(rt_config)?)),
+            );
+
+            let input = test::scan_partitioned(100);
+            let schema = input.schema();
+            let sort_exec = Arc::new(SortExec::new(
+                vec![PhysicalSortExpr {
+                    expr: col("i", &schema)?,
+                    options: SortOptions::default(),
+                }],
+                Arc::new(CoalescePartitionsExec::new(input)),
+            ));
+            let result = collect(sort_exec.clone(), task_ctx.clone()).await?;
+
+            assert_eq!(
+                task_ctx.runtime_env().memory_pool.reserved(),
+                0,
+                "The sort should have returned all memory used back to the memory manager"
+            );
+            let spill_count = sort_exec.metrics().unwrap().spill_count().unwrap();
+            Ok((result, spill_count))
+        }
+
+        let (expected, single_pass_spills) = sort_spilled(usize::MAX).await?;
+        assert!(single_pass_spills > 2);
+
+        let (result, multi_pass_spills) = sort_spilled(2).await?;
+        // the spill files merged in the intermediate passes are spilled again
+        assert!(multi_pass_spills > single_pass_spills);
+        assert_eq!(
+            arrow::util::pretty::pretty_format_batches(&result)?.to_string(),
+            arrow::util::pretty::pretty_format_batches(&expected)?.to_string(),
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_sort_fetch_memory_calculation() -> Result<()> {
         // This test mirrors down the size from the example above.
@@ -0,0 +1,732 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the time window aggregation plan, aggregating tumbling or hopping
+//! time windows of unbounded inputs as they are completed by a [`Watermark`]
+
+use std::any::Any;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::expressions::{Column, PhysicalSortExpr};
+use super::metrics::{
+    BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
+use super::{DisplayAs, RecordBatchStream, SendableRecordBatchStream};
+use crate::{
+    AggregateExpr, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
+    PhysicalExpr,
+};
+
+use arrow::array::{Array, AsArray, Int64Array, UInt32Array};
+use arrow::compute::{cast, take};
+use arrow::datatypes::{DataType, Field, Int64Type, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+use datafusion_common::{plan_err, DataFusionError, Result, ScalarValue};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_expr::Accumulator;
+
+use futures::ready;
+use futures::stream::{Stream, StreamExt};
+
+/// The event time up to which the input of a [`TimeWindowAggregateExec`] is
+/// considered complete.
+///
+/// The watermark trails the largest time seen so far by the allowed
+/// lateness: the input may be out of order by up to the allowed lateness,
+/// and the rows older than the watermark are late.
+#[derive(Debug, Clone)]
+pub struct Watermark {
+    /// How far behind the largest time the watermark trails
+    allowed_lateness: i64,
+    /// The largest time seen so far
+    max_time: Option<i64>,
+}
+
+impl Watermark {
+    /// Create a new [`Watermark`] trailing the largest time seen by
+    /// `allowed_lateness`
+    pub fn new(allowed_lateness: i64) -> Self {
+        Self {
+            allowed_lateness,
+            max_time: None,
+        }
+    }
+
+    /// Advance the watermark with the non null times of `times`
+    pub fn update(&mut self, times: &Int64Array) {
+        if let Some(max) = arrow::compute::max(times) {
+            self.max_time = Some(self.max_time.map_or(max, |time| time.max(max)));
+        }
+    }
+
+    /// The current watermark, or `None` if no time was seen yet
+    pub fn current(&self) -> Option<i64> {
+        self.max_time
+            .map(|time| time.saturating_sub(self.allowed_lateness))
+    }
+}
+
+/// Execution plan aggregating the rows of its input by time windows, and by
+/// the values of group expressions within each window.
+///
+/// Windows of `size` start every `slide`, aligned on the Unix epoch: the
+/// windows are tumbling when `slide` equals `size`, and hopping, with each
+/// row in several windows, when `slide` is smaller. A window is emitted, and
+/// its state released, once the [`Watermark`] of the input passes its end,
+/// so that unbounded inputs can be aggregated in bounded memory. The rows
+/// of windows that were already emitted are late, and dropped.
+///
+/// The output has the start and the end of the windows, then the group
+/// columns and the aggregates, and is ordered by the start of the windows.
+#[derive(Debug)]
+pub struct TimeWindowAggregateExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The timestamp column with the event times
+    time_expr: Column,
+    /// The width of the windows, in the unit of the time column
+    size: i64,
+    /// The time between the starts of the windows, in the unit of the time
+    /// column
+    slide: i64,
+    /// How far behind the largest time the watermark trails, in the unit of
+    /// the time column
+    allowed_lateness: i64,
+    /// The group expressions, and the names of their columns
+    group_expr: Vec<(Arc<dyn PhysicalExpr>, String)>,
+    /// The aggregate expressions
+    aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+    /// The output schema
+    schema: SchemaRef,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl TimeWindowAggregateExec {
+    /// Create a new [`TimeWindowAggregateExec`], with `size`, `slide` and
+    /// `allowed_lateness` in nanoseconds
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        time_expr: Column,
+        size: i64,
+        slide: i64,
+        allowed_lateness: i64,
+        group_expr: Vec<(Arc<dyn PhysicalExpr>, String)>,
+        aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+    ) -> Result<Self> {
+        let input_schema = input.schema();
+        let time_type = input_schema.field(time_expr.index()).data_type().clone();
+        let nanos_per_unit = match &time_type {
+            DataType::Timestamp(TimeUnit::Second, _) => 1_000_000_000,
+            DataType::Timestamp(TimeUnit::Millisecond, _) => 1_000_000,
+            DataType::Timestamp(TimeUnit::Microsecond, _) => 1_000,
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => 1,
+            other => {
+                return plan_err!(
+                    "TimeWindowAggregateExec expects a timestamp column, got {other}"
+                )
+            }
+        };
+        for (name, value) in [("size", size), ("slide", slide)] {
+            if value <= 0 || value % nanos_per_unit != 0 {
+                return plan_err!(
+                    "TimeWindowAggregateExec expects a positive window {name} in whole units of the time column, got {value} nanoseconds"
+                );
+            }
+        }
+        if allowed_lateness < 0 {
+            return plan_err!(
+                "TimeWindowAggregateExec expects a non negative allowed lateness, got {allowed_lateness} nanoseconds"
+            );
+        }
+
+        let mut fields = vec![
+            Field::new("window_start", time_type.clone(), false),
+            Field::new("window_end", time_type, false),
+        ];
+        for (expr, name) in &group_expr {
+            fields.push(Field::new(
+                name,
+                expr.data_type(&input_schema)?,
+                expr.nullable(&input_schema)?,
+            ));
+        }
+        for expr in &aggr_expr {
+            fields.push(expr.field()?);
+        }
+        Ok(Self {
+            input,
+            time_expr,
+            size: size / nanos_per_unit,
+            slide: slide / nanos_per_unit,
+            // round the lateness up to whole units of the time column
+            allowed_lateness: (allowed_lateness + nanos_per_unit - 1) / nanos_per_unit,
+            group_expr,
+            aggr_expr,
+            schema: Arc::new(Schema::new(fields)),
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The timestamp column with the event times
+    pub fn time_expr(&self) -> &Column {
+        &self.time_expr
+    }
+
+    /// The group expressions, and the names of their columns
+    pub fn group_expr(&self) -> &[(Arc<dyn PhysicalExpr>, String)] {
+        &self.group_expr
+    }
+
+    /// The aggregate expressions
+    pub fn aggr_expr(&self) -> &[Arc<dyn AggregateExpr>] {
+        &self.aggr_expr
+    }
+}
+
+impl DisplayAs for TimeWindowAggregateExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let group = self
+                    .group_expr
+                    .iter()
+                    .map(|(expr, name)| {
+                        let expr = expr.to_string();
+                        if expr == *name {
+                            expr
+                        } else {
+                            format!("{expr} as {name}")
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let aggr = self
+                    .aggr_expr
+                    .iter()
+                    .map(|expr| expr.name().to_string())
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "TimeWindowAggregateExec: time={}, size={}, slide={}, allowed_lateness={}, gby=[{}], aggr=[{}]",
+                    self.time_expr,
+                    self.size,
+                    self.slide,
+                    self.allowed_lateness,
+                    group.join(", "),
+                    aggr.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for TimeWindowAggregateExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Windows are emitted as the input progresses, so that unbounded
+    /// inputs can be aggregated
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        Ok(children[0])
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            input: children[0].clone(),
+            time_expr: self.time_expr.clone(),
+            size: self.size,
+            slide: self.slide,
+            allowed_lateness: self.allowed_lateness,
+            group_expr: self.group_expr.clone(),
+            aggr_expr: self.aggr_expr.clone(),
+            schema: self.schema.clone(),
+            metrics: ExecutionPlanMetricsSet::new(),
+        }))
+    }
+
+    /// The watermark is tracked over the whole input
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let input_schema = self.input.schema();
+        let group_fields = self
+            .group_expr
+            .iter()
+            .map(|(expr, _)| Ok(SortField::new(expr.data_type(&input_schema)?)))
+            .collect::<Result<Vec<_>>>()?;
+        // Without group expressions there is a single group per window
+        let converter = if group_fields.is_empty() {
+            None
+        } else {
+            Some(RowConverter::new(group_fields)?)
+        };
+        let reservation =
+            MemoryConsumer::new(format!("TimeWindowAggregateExec[{partition}]"))
+                .register(context.memory_pool());
+        Ok(Box::pin(TimeWindowAggregateStream {
+            input: self.input.execute(partition, context)?,
+            schema: self.schema.clone(),
+            time_expr: self.time_expr.clone(),
+            size: self.size,
+            slide: self.slide,
+            group_expr: self.group_expr.clone(),
+            aggr_expr: self.aggr_expr.clone(),
+            converter,
+            watermark: Watermark::new(self.allowed_lateness),
+            emitted_until: None,
+            windows: BTreeMap::new(),
+            reservation,
+            finished: false,
+            late_rows: MetricBuilder::new(&self.metrics).counter("late_rows", partition),
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+/// The groups of a window, by the values of the group expressions, or by
+/// `None` without group expressions
+type WindowGroups = BTreeMap<Option<OwnedRow>, Vec<Box<dyn Accumulator>>>;
+
+/// A stream aggregating its input by time windows, emitting the windows the
+/// watermark has passed
+struct TimeWindowAggregateStream {
+    input: SendableRecordBatchStream,
+    schema: SchemaRef,
+    time_expr: Column,
+    size: i64,
+    slide: i64,
+    group_expr: Vec<(Arc<dyn PhysicalExpr>, String)>,
+    aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+    /// Converts the group columns to rows, `None` without group expressions
+    converter: Option<RowConverter>,
+    watermark: Watermark,
+    /// The windows ending at or before this time were emitted
+    emitted_until: Option<i64>,
+    /// The windows that were not emitted yet, by start time
+    windows: BTreeMap<i64, WindowGroups>,
+    /// Tracks the memory used by `windows`
+    reservation: MemoryReservation,
+    finished: bool,
+    late_rows: Count,
+    baseline_metrics: BaselineMetrics,
+}
+
+impl TimeWindowAggregateStream {
+    /// Adds `batch` to the windows, and returns the windows completed by it
+    fn aggregate_batch(&mut self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+        let _timer = elapsed_compute.timer();
+        let num_rows = batch.num_rows();
+        let times = cast(batch.column(self.time_expr.index()), &DataType::Int64)?;
+        let times = times.as_primitive::<Int64Type>();
+        let group_rows = match &mut self.converter {
+            Some(converter) => {
+                let columns = self
+                    .group_expr
+                    .iter()
+                    .map(|(expr, _)| expr.evaluate(&batch)?.into_array(num_rows))
+                    .collect::<Result<Vec<_>>>()?;
+                Some(converter.convert_columns(&columns)?)
+            }
+            None => None,
+        };
+        let args = self
+            .aggr_expr
+            .iter()
+            .map(|expr| {
+                expr.expressions()
+                    .iter()
+                    .map(|arg| arg.evaluate(&batch)?.into_array(num_rows))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The rows of each group of each window
+        let mut indices = BTreeMap::<(i64, Option<OwnedRow>), Vec<u32>>::new();
+        let mut late_rows = 0;
+        for (row, time) in times.iter().enumerate() {
+            let Some(time) = time else {
+                continue;
+            };
+            let mut start = time.div_euclid(self.slide) * self.slide;
+            while start + self.size > time {
+                if self
+                    .emitted_until
+                    .is_some_and(|end| start + self.size <= end)
+                {
+                    late_rows += 1;
+                } else {
+                    let key = group_rows.as_ref().map(|rows| rows.row(row).owned());
+                    indices.entry((start, key)).or_default().push(row as u32);
+                }
+                start -= self.slide;
+            }
+        }
+        self.late_rows.add(late_rows);
+
+        let mut size_delta = 0isize;
+        for ((start, key), rows) in indices {
+            let rows = UInt32Array::from(rows);
+            let groups = self.windows.entry(start).or_default();
+            let accumulators = match groups.entry(key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let accumulators = self
+                        .aggr_expr
+                        .iter()
+                        .map(|expr| expr.create_accumulator())
+                        .collect::<Result<Vec<_>>>()?;
+                    size_delta += entry
+                        .key()
+                        .as_ref()
+                        .map_or(0, |key| key.row().as_ref().len())
+                        as isize;
+                    entry.insert(accumulators)
+                }
+            };
+            for (accumulator, args) in accumulators.iter_mut().zip(&args) {
+                let args = args
+                    .iter()
+                    .map(|arg| Ok(take(arg, &rows, None)?))
+                    .collect::<Result<Vec<_>>>()?;
+                size_delta -= accumulator.size() as isize;
+                accumulator.update_batch(&args)?;
+                size_delta += accumulator.size() as isize;
+            }
+        }
+        self.resize_reservation(size_delta)?;
+
+        self.watermark.update(times);
+        match self.watermark.current() {
+            Some(watermark) => self.emit(Some(watermark)),
+            None => Ok(None),
+        }
+    }
+
+    /// Emits and releases the windows ending at or before `until`, or all
+    /// windows if `None`
+    fn emit(&mut self, until: Option<i64>) -> Result<Option<RecordBatch>> {
+        let windows = match until {
+            Some(until) => {
+                // the windows starting after `until - size` are still open
+                let open = self.windows.split_off(&(until - self.size + 1));
+                self.emitted_until =
+                    Some(self.emitted_until.map_or(until, |end| end.max(until)));
+                std::mem::replace(&mut self.windows, open)
+            }
+            None => std::mem::take(&mut self.windows),
+        };
+        if windows.is_empty() {
+            return Ok(None);
+        }
+
+        let mut starts = vec![];
+        let mut keys = vec![];
+        let mut values = vec![vec![]; self.aggr_expr.len()];
+        let mut released = 0;
+        for (start, groups) in windows {
+            for (key, accumulators) in groups {
+                starts.push(start);
+                if let Some(key) = key {
+                    released += key.row().as_ref().len();
+                    keys.push(key);
+                }
+                for (values, mut accumulator) in values.iter_mut().zip(accumulators) {
+                    released += accumulator.size();
+                    values.push(accumulator.evaluate()?);
+                }
+            }
+        }
+        self.resize_reservation(-(released as isize))?;
+
+        let time_type = self.schema.field(0).data_type();
+        let ends = starts
+            .iter()
+            .map(|start| start + self.size)
+            .collect::<Vec<_>>();
+        let mut columns = vec![
+            cast(&Int64Array::from(starts), time_type)?,
+            cast(&Int64Array::from(ends), time_type)?,
+        ];
+        if let Some(converter) = &self.converter {
+            columns.extend(converter.convert_rows(keys.iter().map(|key| key.row()))?);
+        }
+        for values in values {
+            let array = ScalarValue::iter_to_array(values)?;
+            // the aggregates may not evaluate to the type of their field,
+            // e.g. for empty inputs
+            let data_type = self.schema.field(columns.len()).data_type();
+            columns.push(if array.data_type() == data_type {
+                array
+            } else {
+                cast(&array, data_type)?
+            });
+        }
+        Ok(Some(RecordBatch::try_new(self.schema.clone(), columns)?))
+    }
+
+    fn resize_reservation(&mut self, delta: isize) -> Result<()> {
+        if delta > 0 {
+            self.reservation.try_grow(delta as usize)
+        } else {
+            self.reservation
+                .shrink((-delta as usize).min(self.reservation.size()));
+            Ok(())
+        }
+    }
+
+    fn poll_next_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        while !self.finished {
+            match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => match self.aggregate_batch(batch) {
+                    Ok(Some(batch)) => return Poll::Ready(Some(Ok(batch))),
+                    Ok(None) => continue,
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                },
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => {
+                    // the end of the input completes all windows
+                    self.finished = true;
+                    let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+                    let _timer = elapsed_compute.timer();
+                    return Poll::Ready(self.emit(None).transpose());
+                }
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+impl Stream for TimeWindowAggregateStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_inner(cx);
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for TimeWindowAggregateStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collect;
+    use crate::expressions::{col, Count as CountExpr, Sum};
+    use crate::memory::MemoryExec;
+
+    use arrow::array::{StringArray, TimestampNanosecondArray};
+    use arrow::util::pretty::pretty_format_batches;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("region", DataType::Utf8, false),
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("v", DataType::Int64, true),
+        ]))
+    }
+
+    fn batch(region: Vec<&str>, time: Vec<i64>, v: Vec<i64>) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            schema(),
+            vec![
+                Arc::new(StringArray::from(region)),
+                Arc::new(TimestampNanosecondArray::from(time)),
+                Arc::new(Int64Array::from(v)),
+            ],
+        )?)
+    }
+
+    fn time_window_aggregate(
+        batches: Vec<RecordBatch>,
+        size: i64,
+        slide: i64,
+        allowed_lateness: i64,
+        group_by_region: bool,
+    ) -> Result<Arc<TimeWindowAggregateExec>> {
+        let schema = schema();
+        let input = Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+        let group_expr = if group_by_region {
+            vec![(col("region", &schema)?, "region".to_string())]
+        } else {
+            vec![]
+        };
+        let aggr_expr: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(Sum::new(col("v", &schema)?, "SUM(v)", DataType::Int64)),
+            Arc::new(CountExpr::new(
+                col("v", &schema)?,
+                "COUNT(v)",
+                DataType::Int64,
+            )),
+        ];
+        Ok(Arc::new(TimeWindowAggregateExec::try_new(
+            input,
+            Column::new("time", 1),
+            size,
+            slide,
+            allowed_lateness,
+            group_expr,
+            aggr_expr,
+        )?))
+    }
+
+    fn format(batches: &[RecordBatch]) -> Result<Vec<String>> {
+        Ok(pretty_format_batches(batches)?
+            .to_string()
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn tumbling_windows() -> Result<()> {
+        // 5 and 1 arrive after later times, but before their window is
+        // emitted, 2 after it
+        let batches = vec![
+            batch(vec!["a", "b", "a"], vec![0, 3, 7], vec![1, 2, 3])?,
+            batch(vec!["a", "b"], vec![12, 5], vec![4, 5])?,
+            batch(vec!["a", "a"], vec![21, 1], vec![6, 7])?,
+            batch(vec!["a"], vec![2], vec![8])?,
+        ];
+        let exec = time_window_aggregate(batches, 10, 10, 5, true)?;
+        let batches = collect(exec.clone(), Arc::new(TaskContext::default())).await?;
+
+        // the first window is emitted once the watermark reaches 16, the
+        // others at the end of the input
+        assert_eq!(batches.len(), 2);
+        let expected = vec![
+            "+-------------------------------+-------------------------------+--------+--------+----------+",
+            "| window_start                  | window_end                    | region | SUM(v) | COUNT(v) |",
+            "+-------------------------------+-------------------------------+--------+--------+----------+",
+            "| 1970-01-01T00:00:00           | 1970-01-01T00:00:00.000000010 | a      | 11     | 3        |",
+            "| 1970-01-01T00:00:00           | 1970-01-01T00:00:00.000000010 | b      | 7      | 2        |",
+            "| 1970-01-01T00:00:00.000000010 | 1970-01-01T00:00:00.000000020 | a      | 4      | 1        |",
+            "| 1970-01-01T00:00:00.000000020 | 1970-01-01T00:00:00.000000030 | a      | 6      | 1        |",
+            "+-------------------------------+-------------------------------+--------+--------+----------+",
+        ];
+        assert_eq!(format(&batches)?, expected);
+        let late_rows = exec.metrics().unwrap().sum_by_name("late_rows").unwrap();
+        assert_eq!(late_rows.as_usize(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hopping_windows() -> Result<()> {
+        let batches = vec![batch(vec!["a", "a", "a"], vec![1, 6, 11], vec![1, 2, 3])?];
+        let exec = time_window_aggregate(batches, 10, 5, 0, false)?;
+        let batches = collect(exec, Arc::new(TaskContext::default())).await?;
+
+        let expected = vec![
+            "+-------------------------------+-------------------------------+--------+----------+",
+            "| window_start                  | window_end                    | SUM(v) | COUNT(v) |",
+            "+-------------------------------+-------------------------------+--------+----------+",
+            "| 1969-12-31T23:59:59.999999995 | 1970-01-01T00:00:00.000000005 | 1      | 1        |",
+            "| 1970-01-01T00:00:00           | 1970-01-01T00:00:00.000000010 | 3      | 2        |",
+            "| 1970-01-01T00:00:00.000000005 | 1970-01-01T00:00:00.000000015 | 5      | 2        |",
+            "| 1970-01-01T00:00:00.000000010 | 1970-01-01T00:00:00.000000020 | 3      | 1        |",
+            "+-------------------------------+-------------------------------+--------+----------+",
+        ];
+        assert_eq!(format(&batches)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn watermark() {
+        let mut watermark = Watermark::new(5);
+        assert_eq!(watermark.current(), None);
+        watermark.update(&Int64Array::from(vec![Some(10), None, Some(3)]));
+        assert_eq!(watermark.current(), Some(5));
+        watermark.update(&Int64Array::from(vec![Some(8)]));
+        assert_eq!(watermark.current(), Some(5));
+        watermark.update(&Int64Array::from(vec![None]));
+        assert_eq!(watermark.current(), Some(5));
+    }
+
+    #[test]
+    fn invalid_window() -> Result<()> {
+        let err = time_window_aggregate(vec![], 0, 10, 0, false).unwrap_err();
+        assert!(err.to_string().contains("expects a positive window size"));
+        let err = time_window_aggregate(vec![], 10, 10, -1, false).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("expects a non negative allowed lateness"));
+        Ok(())
+    }
+}
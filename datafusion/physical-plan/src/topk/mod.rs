@@ -147,6 +147,7 @@ impl TopK {
     pub fn insert_batch(&mut self, batch: RecordBatch) -> Result<()> {
         // Updates on drop
         let _timer = self.metrics.baseline.elapsed_compute().timer();
+        self.metrics.input_rows.add(batch.num_rows());
 
         let sort_keys: Vec<ArrayRef> = self
             .expr
@@ -236,6 +237,9 @@ struct TopKMetrics {
     /// metrics
     pub baseline: BaselineMetrics,
 
+    /// count of how many rows were inserted into the topk
+    pub input_rows: Count,
+
     /// count of how many rows were replaced in the heap
     pub row_replacements: Count,
 }
@@ -244,6 +248,7 @@ impl TopKMetrics {
     fn new(metrics: &ExecutionPlanMetricsSet, partition: usize) -> Self {
         Self {
             baseline: BaselineMetrics::new(metrics, partition),
+            input_rows: MetricBuilder::new(metrics).counter("input_rows", partition),
             row_replacements: MetricBuilder::new(metrics)
                 .counter("row_replacements", partition),
         }
@@ -324,12 +324,22 @@ fn build_batch_generic_list<T: OffsetSizeTrait, P: ArrowPrimitiveType<Native = T
     let take_indicies =
         create_take_indicies_generic::<T, P>(list_array, unnested_array.len(), options);
 
+    let ordinality = options.ordinality.as_ref().map(|_| {
+        let lengths = (0..list_array.len()).map(|row| {
+            list_array
+                .is_valid(row)
+                .then(|| list_array.value_length(row).as_usize())
+        });
+        create_ordinality(lengths, unnested_array.len(), options)
+    });
+
     batch_from_indices(
         batch,
         schema,
         unnest_column_idx,
         &unnested_array,
         &take_indicies,
+        ordinality,
     )
 }
 
@@ -398,12 +408,22 @@ fn build_batch_fixedsize_list(
     let take_indicies =
         create_take_indicies_fixed(list_array, unnested_array.len(), options);
 
+    let ordinality = options.ordinality.as_ref().map(|_| {
+        let lengths = (0..list_array.len()).map(|row| {
+            list_array
+                .is_valid(row)
+                .then_some(list_array.value_length() as usize)
+        });
+        create_ordinality(lengths, unnested_array.len(), options)
+    });
+
     batch_from_indices(
         batch,
         schema,
         unnest_column_idx,
         &unnested_array,
         &take_indicies,
+        ordinality,
     )
 }
 
@@ -534,6 +554,36 @@ fn create_take_indicies_fixed(
     builder.finish()
 }
 
+/// Creates the ordinality column, the 1-based position of each unnested value
+/// in its list, given the length of each list, `None` for null lists.
+///
+/// If the column being unnested looks like this:
+///
+/// ```ignore
+/// [1], null, [2, 3, 4], null, [5, 6]
+/// ```
+/// Then `create_ordinality` will return an array like this
+///
+/// ```ignore
+/// [1, null, 1, 2, 3, null, 1, 2]
+/// ```
+///
+fn create_ordinality(
+    lengths: impl Iterator<Item = Option<usize>>,
+    capacity: usize,
+    options: &UnnestOptions,
+) -> ArrayRef {
+    let mut builder = PrimitiveArray::<Int64Type>::builder(capacity);
+    for length in lengths {
+        match length {
+            Some(length) => (1..=length as i64).for_each(|i| builder.append_value(i)),
+            None if options.preserve_nulls => builder.append_null(),
+            None => {}
+        }
+    }
+    Arc::new(builder.finish())
+}
+
 /// Create the final batch given the unnested column array and a `indices` array
 /// that is used by the take kernel to copy values.
 ///
@@ -571,11 +621,12 @@ fn batch_from_indices<T>(
     unnest_column_idx: usize,
     unnested_array: &ArrayRef,
     indices: &PrimitiveArray<T>,
+    ordinality: Option<ArrayRef>,
 ) -> Result<RecordBatch>
 where
     T: ArrowPrimitiveType,
 {
-    let arrays = batch
+    let mut arrays = batch
         .columns()
         .iter()
         .enumerate()
@@ -587,6 +638,7 @@ where
             }
         })
         .collect::<Result<Vec<_>>>()?;
+    arrays.extend(ordinality);
 
     Ok(RecordBatch::try_new(schema.clone(), arrays.to_vec())?)
 }
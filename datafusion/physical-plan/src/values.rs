@@ -18,6 +18,7 @@
 //! Values execution plan
 
 use std::any::Any;
+use std::cmp::Ordering;
 use std::sync::Arc;
 
 use super::expressions::PhysicalSortExpr;
@@ -27,11 +28,14 @@ use crate::{
     PhysicalExpr,
 };
 
-use arrow::array::new_null_array;
+use arrow::array::{new_null_array, Array};
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
+use datafusion_common::stats::Precision;
 use datafusion_common::{internal_err, plan_err, DataFusionError, Result, ScalarValue};
 use datafusion_execution::TaskContext;
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::{EquivalenceProperties, LexOrdering};
 
 /// Execution plan for values list based relation (produces constant rows)
 #[derive(Debug)]
@@ -40,6 +44,8 @@ pub struct ValuesExec {
     schema: SchemaRef,
     /// The data
     data: Vec<RecordBatch>,
+    /// Orderings the data is declared to satisfy
+    sort_information: Vec<LexOrdering>,
 }
 
 impl ValuesExec {
@@ -87,7 +93,11 @@ impl ValuesExec {
             .collect::<Result<Vec<_>>>()?;
         let batch = RecordBatch::try_new(schema.clone(), arr)?;
         let data: Vec<RecordBatch> = vec![batch];
-        Ok(Self { schema, data })
+        Ok(Self {
+            schema,
+            data,
+            sort_information: vec![],
+        })
     }
 
     /// Create a new plan using the provided schema and batches.
@@ -114,13 +124,53 @@ impl ValuesExec {
         Ok(ValuesExec {
             schema,
             data: batches,
+            sort_information: vec![],
         })
     }
 
+    /// Declares the orderings that the rows satisfy, in the order they are
+    /// produced. The rows are not sorted, so the caller must ensure they are
+    /// already in such an order.
+    pub fn with_sort_information(mut self, sort_information: Vec<LexOrdering>) -> Self {
+        self.sort_information = sort_information;
+        self
+    }
+
     /// provides the data
     pub fn data(&self) -> Vec<RecordBatch> {
         self.data.clone()
     }
+
+    /// Returns the exact minimum and maximum non-null values of the column at
+    /// `index`, if there are any and they are comparable
+    fn min_max(&self, index: usize) -> Result<Option<(ScalarValue, ScalarValue)>> {
+        let mut min_max: Option<(ScalarValue, ScalarValue)> = None;
+        for batch in &self.data {
+            let array = batch.column(index);
+            for row in 0..array.len() {
+                if array.is_null(row) {
+                    continue;
+                }
+                let value = ScalarValue::try_from_array(array, row)?;
+                min_max = match min_max {
+                    None => Some((value.clone(), value)),
+                    Some((min, max)) => {
+                        let (Some(to_min), Some(to_max)) =
+                            (value.partial_cmp(&min), value.partial_cmp(&max))
+                        else {
+                            return Ok(None);
+                        };
+                        match (to_min, to_max) {
+                            (Ordering::Less, _) => Some((value, max)),
+                            (_, Ordering::Greater) => Some((min, value)),
+                            _ => Some((min, max)),
+                        }
+                    }
+                };
+            }
+        }
+        Ok(min_max)
+    }
 }
 
 impl DisplayAs for ValuesExec {
@@ -131,7 +181,15 @@ impl DisplayAs for ValuesExec {
     ) -> std::fmt::Result {
         match t {
             DisplayFormatType::Default | DisplayFormatType::Verbose => {
-                write!(f, "ValuesExec")
+                write!(f, "ValuesExec")?;
+                if let Some(output_ordering) = self.sort_information.first() {
+                    write!(
+                        f,
+                        ": output_ordering={}",
+                        PhysicalSortExpr::format_list(output_ordering)
+                    )?;
+                }
+                Ok(())
             }
         }
     }
@@ -156,7 +214,26 @@ impl ExecutionPlan for ValuesExec {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.sort_information
+            .first()
+            .map(|ordering| ordering.as_slice())
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        let properties = EquivalenceProperties::new_with_orderings(
+            self.schema(),
+            &self.sort_information,
+        );
+        // a single row satisfies any ordering
+        let num_rows = self.data.iter().map(RecordBatch::num_rows).sum::<usize>();
+        if num_rows > 1 {
+            return properties;
+        }
+        properties.add_constants(self.schema.fields().iter().enumerate().map(
+            |(index, field)| {
+                Arc::new(Column::new(field.name(), index)) as Arc<dyn PhysicalExpr>
+            },
+        ))
     }
 
     fn with_new_children(
@@ -166,6 +243,7 @@ impl ExecutionPlan for ValuesExec {
         Ok(Arc::new(ValuesExec {
             schema: self.schema.clone(),
             data: self.data.clone(),
+            sort_information: self.sort_information.clone(),
         }))
     }
 
@@ -190,11 +268,24 @@ impl ExecutionPlan for ValuesExec {
 
     fn statistics(&self) -> Result<Statistics> {
         let batch = self.data();
-        Ok(common::compute_record_batch_statistics(
-            &[batch],
-            &self.schema,
-            None,
-        ))
+        let mut statistics =
+            common::compute_record_batch_statistics(&[batch], &self.schema, None);
+        // the values are known, so their statistics are exact
+        for (index, column_statistics) in
+            statistics.column_statistics.iter_mut().enumerate()
+        {
+            let null_count = self
+                .data
+                .iter()
+                .map(|batch| batch.column(index).null_count())
+                .sum();
+            column_statistics.null_count = Precision::Exact(null_count);
+            if let Some((min, max)) = self.min_max(index)? {
+                column_statistics.min_value = Precision::Exact(min);
+                column_statistics.max_value = Precision::Exact(max);
+            }
+        }
+        Ok(statistics)
     }
 }
 
@@ -203,7 +294,10 @@ mod tests {
     use super::*;
     use crate::test::{self, make_partition};
 
-    use arrow_schema::{DataType, Field, Schema};
+    use crate::expressions::lit;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow_schema::{DataType, Field, Schema, SortOptions};
 
     #[tokio::test]
     async fn values_empty_case() -> Result<()> {
@@ -229,6 +323,58 @@ mod tests {
         let _ = ValuesExec::try_new_from_batches(schema, Vec::new()).unwrap_err();
     }
 
+    #[test]
+    fn values_statistics() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batches = [vec![Some(3), None], vec![Some(1), Some(2)]]
+            .into_iter()
+            .map(|a| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from(a)),
+                        Arc::new(StringArray::from(vec![None::<&str>, None])),
+                    ],
+                )
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let values = ValuesExec::try_new_from_batches(schema, batches)?;
+
+        let statistics = values.statistics()?;
+        assert_eq!(statistics.num_rows, Precision::Exact(4));
+        let a = &statistics.column_statistics[0];
+        assert_eq!(a.null_count, Precision::Exact(1));
+        assert_eq!(a.min_value, Precision::Exact(ScalarValue::Int32(Some(1))));
+        assert_eq!(a.max_value, Precision::Exact(ScalarValue::Int32(Some(3))));
+        let b = &statistics.column_statistics[1];
+        assert_eq!(b.null_count, Precision::Exact(4));
+        assert_eq!(b.min_value, Precision::Absent);
+        Ok(())
+    }
+
+    #[test]
+    fn values_orderings() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let ordering = vec![PhysicalSortExpr {
+            expr: Arc::new(Column::new("a", 0)),
+            options: SortOptions::default(),
+        }];
+
+        let one_row = ValuesExec::try_new(schema.clone(), vec![vec![lit(1)]])?;
+        assert!(one_row.output_ordering().is_none());
+        assert!(one_row.equivalence_properties().ordering_satisfy(&ordering));
+
+        let values = ValuesExec::try_new(schema, vec![vec![lit(1)], vec![lit(2)]])?;
+        assert!(!values.equivalence_properties().ordering_satisfy(&ordering));
+        let values = values.with_sort_information(vec![ordering.clone()]);
+        assert_eq!(values.output_ordering(), Some(ordering.as_slice()));
+        assert!(values.equivalence_properties().ordering_satisfy(&ordering));
+        Ok(())
+    }
+
     #[test]
     fn new_exec_with_batches_invalid_schema() {
         let batch = make_partition(7);
@@ -169,10 +169,10 @@ impl BoundedWindowAggExec {
                 {
                     return exec_err!("All partition by columns should have an ordering in Sorted mode.");
                 }
-                Box::new(SortedSearch {
+                Box::new(SortedSearch::new(
                     partition_by_sort_keys,
                     ordered_partition_by_indices,
-                })
+                ))
             }
             InputOrderMode::Linear | InputOrderMode::PartiallySorted(_) => {
                 Box::new(LinearSearch::new(ordered_partition_by_indices))
@@ -692,6 +692,19 @@ pub struct SortedSearch {
     ordered_partition_by_indices: Vec<usize>,
 }
 
+impl SortedSearch {
+    /// Initialize a new [`SortedSearch`] partition searcher.
+    pub(crate) fn new(
+        partition_by_sort_keys: Vec<PhysicalSortExpr>,
+        ordered_partition_by_indices: Vec<usize>,
+    ) -> Self {
+        SortedSearch {
+            partition_by_sort_keys,
+            ordered_partition_by_indices,
+        }
+    }
+}
+
 impl PartitionSearcher for SortedSearch {
     /// This method constructs new output columns using the result of each window expression.
     fn calculate_out_columns(
@@ -878,7 +891,7 @@ impl Stream for BoundedWindowAggStream {
 
 impl BoundedWindowAggStream {
     /// Create a new BoundedWindowAggStream
-    fn new(
+    pub(crate) fn new(
         schema: SchemaRef,
         window_expr: Vec<Arc<dyn WindowExpr>>,
         input: SendableRecordBatchStream,
@@ -500,13 +500,17 @@ mod tests {
     use crate::aggregates::AggregateFunction;
     use crate::collect;
     use crate::expressions::col;
+    use crate::memory::MemoryExec;
     use crate::streaming::StreamingTableExec;
     use crate::test::assert_is_pending;
     use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
 
-    use arrow::compute::SortOptions;
-    use arrow::datatypes::{DataType, Field, SchemaRef};
+    use arrow::array::{AsArray, Int32Array, RecordBatch};
+    use arrow::compute::{concat_batches, SortOptions};
+    use arrow::datatypes::{DataType, Field, Int64Type, SchemaRef, UInt64Type};
+    use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
     use datafusion_execution::TaskContext;
+    use datafusion_expr::{WindowFrameBound, WindowFrameUnits};
 
     use futures::FutureExt;
 
@@ -671,6 +675,89 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_window_agg_spill() -> Result<()> {
+        // 10 batches of 1000 rows, the first 8 in partition `a = 0`
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batches = (0..10)
+            .map(|i| {
+                let a = Int32Array::from(vec![i32::from(i >= 8); 1000]);
+                let b = Int32Array::from_iter_values(i * 1000..(i + 1) * 1000);
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(a), Arc::new(b)])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let input = Arc::new(
+            MemoryExec::try_new(&[batches], schema.clone(), None)?.with_sort_information(
+                vec![vec![sort_expr("a", &schema), sort_expr("b", &schema)]],
+            ),
+        );
+
+        let partition_by = vec![col("a", &schema)?];
+        let order_by = vec![sort_expr("b", &schema)];
+        let window_expr = vec![
+            // aggregated over the whole partition
+            create_window_expr(
+                &WindowFunctionDefinition::AggregateFunction(AggregateFunction::Sum),
+                "sum".to_owned(),
+                &[col("b", &schema)?],
+                &partition_by,
+                &order_by,
+                Arc::new(WindowFrame {
+                    units: WindowFrameUnits::Rows,
+                    start_bound: WindowFrameBound::Preceding(ScalarValue::UInt64(None)),
+                    end_bound: WindowFrameBound::Following(ScalarValue::UInt64(None)),
+                }),
+                schema.as_ref(),
+            )?,
+            // evaluated with bounded memory
+            create_window_expr(
+                &WindowFunctionDefinition::BuiltInWindowFunction(
+                    BuiltInWindowFunction::RowNumber,
+                ),
+                "row_number".to_owned(),
+                &[],
+                &partition_by,
+                &order_by,
+                Arc::new(WindowFrame::new(true)),
+                schema.as_ref(),
+            )?,
+        ];
+        let window_agg_exec =
+            Arc::new(WindowAggExec::try_new(window_expr, input, partition_by)?);
+
+        let expected =
+            collect(window_agg_exec.clone(), Arc::new(TaskContext::default())).await?;
+        assert_eq!(window_agg_exec.metrics().unwrap().spill_count(), Some(0));
+
+        // the rows of partition `a = 0` do not fit in memory
+        let runtime =
+            RuntimeEnv::new(RuntimeConfig::new().with_memory_limit(20_000, 1.0))?;
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(Arc::new(runtime)));
+        let window_agg_exec = window_agg_exec
+            .clone()
+            .with_new_children(vec![window_agg_exec.children()[0].clone()])?;
+        let result = collect(window_agg_exec.clone(), task_ctx.clone()).await?;
+
+        let metrics = window_agg_exec.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert!(metrics.spilled_bytes().unwrap() > 0);
+        assert_eq!(task_ctx.runtime_env().memory_pool.reserved(), 0);
+
+        let result = concat_batches(&result[0].schema(), &result)?;
+        assert_eq!(result, concat_batches(&expected[0].schema(), &expected)?);
+        let sums = result.column(2).as_primitive::<Int64Type>();
+        let row_numbers = result.column(3).as_primitive::<UInt64Type>();
+        assert_eq!(sums.value(0), 31_996_000);
+        assert_eq!(sums.value(9_999), 17_999_000);
+        assert_eq!(row_numbers.value(7_999), 8_000);
+        assert_eq!(row_numbers.value(8_000), 1);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_satisfiy_nullable() -> Result<()> {
         let schema = create_test_schema()?;
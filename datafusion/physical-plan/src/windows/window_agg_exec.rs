@@ -18,26 +18,36 @@
 //! Stream and channel implementations for window function expressions.
 
 use std::any::Any;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
-use crate::common::transpose;
+use super::bounded_window_agg_exec::{BoundedWindowAggStream, SortedSearch};
+use crate::common::{transpose, IPCWriter};
 use crate::expressions::PhysicalSortExpr;
-use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::metrics::{
+    BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
+use crate::sorts::sort::read_spill_as_stream;
+use crate::stream::RecordBatchStreamAdapter;
 use crate::windows::{
     calc_requirements, get_ordered_partition_by_indices, get_partition_by_sort_exprs,
     window_equivalence_properties,
 };
 use crate::{
-    ColumnStatistics, DisplayAs, DisplayFormatType, Distribution, ExecutionPlan,
-    Partitioning, PhysicalExpr, RecordBatchStream, SendableRecordBatchStream, Statistics,
-    WindowExpr,
+    AggregateExpr, ColumnStatistics, DisplayAs, DisplayFormatType, Distribution,
+    ExecutionPlan, Partitioning, PhysicalExpr, RecordBatchStream,
+    SendableRecordBatchStream, Statistics, WindowExpr,
 };
 
-use arrow::compute::{concat, concat_batches};
+use arrow::compute::{concat, concat_batches, SortColumn};
 use arrow::datatypes::SchemaBuilder;
 use arrow::error::ArrowError;
+use arrow::ipc::reader::FileReader;
 use arrow::{
     array::ArrayRef,
     datatypes::{Schema, SchemaRef},
@@ -45,14 +55,26 @@ use arrow::{
 };
 use datafusion_common::stats::Precision;
 use datafusion_common::utils::evaluate_partition_ranges;
-use datafusion_common::{internal_err, plan_err, DataFusionError, Result};
+use datafusion_common::{
+    exec_err, internal_err, plan_err, DataFusionError, Result, ScalarValue,
+};
+use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::runtime_env::RuntimeEnv;
 use datafusion_execution::TaskContext;
+use datafusion_expr::WindowFrameUnits;
+use datafusion_physical_expr::window::PlainAggregateWindowExpr;
 use datafusion_physical_expr::{EquivalenceProperties, PhysicalSortRequirement};
 
-use futures::stream::Stream;
-use futures::{ready, StreamExt};
+use futures::stream::{self, Stream};
+use futures::{ready, StreamExt, TryStreamExt};
+use tokio::task;
 
 /// Window execution plan
+///
+/// Partitions whose rows do not fit in memory are spilled to disk, provided
+/// the window expressions either aggregate over whole partitions or can run
+/// with bounded memory. See [`WindowAggStream`] for details.
 #[derive(Debug)]
 pub struct WindowAggExec {
     /// Input plan
@@ -231,14 +253,16 @@ impl ExecutionPlan for WindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
+        let input = self.input.execute(partition, context.clone())?;
         let stream = Box::pin(WindowAggStream::new(
             self.schema.clone(),
             self.window_expr.clone(),
             input,
-            BaselineMetrics::new(&self.metrics, partition),
             self.partition_by_sort_keys()?,
             self.ordered_partition_by_indices.clone(),
+            partition,
+            &self.metrics,
+            context.runtime_env(),
         )?);
         Ok(stream)
     }
@@ -291,57 +315,147 @@ fn compute_window_aggregates(
         .collect()
 }
 
+/// Returns the aggregate of `window_expr` if its window frame spans its
+/// whole partition, such as for `SUM(x) OVER (PARTITION BY y)`
+fn whole_partition_aggregate(
+    window_expr: &Arc<dyn WindowExpr>,
+) -> Option<&Arc<dyn AggregateExpr>> {
+    let aggregate = window_expr
+        .as_any()
+        .downcast_ref::<PlainAggregateWindowExpr>()?;
+    let window_frame = window_expr.get_window_frame();
+    let unbounded =
+        window_frame.start_bound.is_unbounded() && window_frame.end_bound.is_unbounded();
+    // Without ORDER BY, all the rows of a partition are peers
+    let peers =
+        window_expr.order_by().is_empty() && window_frame.units != WindowFrameUnits::Rows;
+    (unbounded || peers).then(|| aggregate.get_aggregate_expr())
+}
+
+/// Returns true if `window_expr` can be evaluated over a spilled partition
+/// without reading it back into memory all at once
+fn supports_spilling(window_expr: &Arc<dyn WindowExpr>) -> bool {
+    whole_partition_aggregate(window_expr).is_some() || window_expr.uses_bounded_memory()
+}
+
+/// Returns the values of `partition_columns` at `row`
+fn partition_key(
+    partition_columns: &[SortColumn],
+    row: usize,
+) -> Result<Vec<ScalarValue>> {
+    partition_columns
+        .iter()
+        .map(|column| ScalarValue::try_from_array(&column.values, row))
+        .collect()
+}
+
+/// Results of a [`WindowAggStream`] that are ready to be returned
+enum WindowAggOutput {
+    /// The results of partitions computed in memory
+    Batch(RecordBatch),
+    /// The results of a spilled partition, computed while reading it back
+    Spilled(SendableRecordBatchStream),
+}
+
 /// stream for window aggregation plan
+///
+/// As the input is sorted on the PARTITION BY columns, the results of a
+/// partition are computed as soon as its last row is read. The rows of a
+/// partition that does not fit in memory are spilled to disk in input
+/// order, i.e. as a single run sorted on the ORDER BY columns. The window
+/// expressions are then evaluated while reading the run back: aggregates
+/// over the whole partition in a first pass, and the others, which must run
+/// with bounded memory, incrementally in a second pass.
 pub struct WindowAggStream {
     schema: SchemaRef,
     input: SendableRecordBatchStream,
+    /// Buffered rows of the current partition, which may continue in the
+    /// next input batches
     batches: Vec<RecordBatch>,
+    /// Memory reserved for `batches`
+    batches_size: usize,
+    /// PARTITION BY values of the current partition
+    partition_key: Option<Vec<ScalarValue>>,
+    /// Rows of the ended partitions whose results are not computed yet
+    ended_batches: Vec<RecordBatch>,
+    /// Spill file of the current partition, if its rows did not fit in memory
+    spill: Option<(RefCountedTempFile, IPCWriter)>,
+    /// Results ready to be returned, in order
+    output: VecDeque<WindowAggOutput>,
     finished: bool,
     window_expr: Vec<Arc<dyn WindowExpr>>,
     partition_by_sort_keys: Vec<PhysicalSortExpr>,
     baseline_metrics: BaselineMetrics,
+    /// Count of spills during the execution of the operator
+    spill_count: Count,
+    /// Total spilled bytes during the execution of the operator
+    spilled_bytes: Count,
     ordered_partition_by_indices: Vec<usize>,
+    /// Whether the window expressions can be evaluated over spilled partitions
+    can_spill: bool,
+    reservation: MemoryReservation,
+    runtime: Arc<RuntimeEnv>,
 }
 
 impl WindowAggStream {
     /// Create a new WindowAggStream
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         schema: SchemaRef,
         window_expr: Vec<Arc<dyn WindowExpr>>,
         input: SendableRecordBatchStream,
-        baseline_metrics: BaselineMetrics,
         partition_by_sort_keys: Vec<PhysicalSortExpr>,
         ordered_partition_by_indices: Vec<usize>,
+        partition: usize,
+        metrics: &ExecutionPlanMetricsSet,
+        runtime: Arc<RuntimeEnv>,
     ) -> Result<Self> {
         // In WindowAggExec all partition by columns should be ordered.
         if window_expr[0].partition_by().len() != ordered_partition_by_indices.len() {
             return internal_err!("All partition by columns should have an ordering");
         }
+        let can_spill = window_expr.iter().all(supports_spilling);
+        let reservation = MemoryConsumer::new(format!("WindowAggStream[{partition}]"))
+            .with_can_spill(can_spill)
+            .register(&runtime.memory_pool);
         Ok(Self {
             schema,
             input,
             batches: vec![],
+            batches_size: 0,
+            partition_key: None,
+            ended_batches: vec![],
+            spill: None,
+            output: VecDeque::new(),
             finished: false,
             window_expr,
-            baseline_metrics,
+            baseline_metrics: BaselineMetrics::new(metrics, partition),
+            spill_count: MetricBuilder::new(metrics).spill_count(partition),
+            spilled_bytes: MetricBuilder::new(metrics).spilled_bytes(partition),
             partition_by_sort_keys,
             ordered_partition_by_indices,
+            can_spill,
+            reservation,
+            runtime,
         })
     }
 
-    fn compute_aggregates(&self) -> Result<RecordBatch> {
+    fn partition_columns(&self, batch: &RecordBatch) -> Result<Vec<SortColumn>> {
+        self.ordered_partition_by_indices
+            .iter()
+            .map(|idx| self.partition_by_sort_keys[*idx].evaluate_to_sort_column(batch))
+            .collect()
+    }
+
+    fn compute_aggregates(&self, batches: &[RecordBatch]) -> Result<RecordBatch> {
         // record compute time on drop
         let _timer = self.baseline_metrics.elapsed_compute().timer();
-        let batch = concat_batches(&self.input.schema(), &self.batches)?;
+        let batch = concat_batches(&self.input.schema(), batches)?;
         if batch.num_rows() == 0 {
             return Ok(RecordBatch::new_empty(self.schema.clone()));
         }
 
-        let partition_by_sort_keys = self
-            .ordered_partition_by_indices
-            .iter()
-            .map(|idx| self.partition_by_sort_keys[*idx].evaluate_to_sort_column(&batch))
-            .collect::<Result<Vec<_>>>()?;
+        let partition_by_sort_keys = self.partition_columns(&batch)?;
         let partition_points =
             evaluate_partition_ranges(batch.num_rows(), &partition_by_sort_keys)?;
 
@@ -369,6 +483,221 @@ impl WindowAggStream {
         batch_columns.extend_from_slice(&columns);
         Ok(RecordBatch::try_new(self.schema.clone(), batch_columns)?)
     }
+
+    /// Buffers the rows of `batch` and computes the results of the
+    /// partitions ending in it.
+    fn process_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let partition_columns = self.partition_columns(&batch)?;
+        let partition_ranges =
+            evaluate_partition_ranges(batch.num_rows(), &partition_columns)?;
+        let num_ranges = partition_ranges.len();
+        for (idx, range) in partition_ranges.iter().enumerate() {
+            if idx > 0 {
+                self.end_partition()?;
+            } else if self.partition_key.as_ref()
+                != Some(&partition_key(&partition_columns, 0)?)
+            {
+                self.end_partition()?;
+            }
+
+            let rows = batch.slice(range.start, range.end - range.start);
+            if idx == 0 || idx + 1 == num_ranges {
+                self.buffer(rows)?;
+            } else {
+                // The partition starts and ends in `batch`
+                self.ended_batches.push(rows);
+            }
+        }
+        let last_start = partition_ranges[num_ranges - 1].start;
+        self.partition_key = Some(partition_key(&partition_columns, last_start)?);
+        self.compute_ended_partitions()
+    }
+
+    /// Buffers `rows` of the current partition, spilling the buffered rows
+    /// of the partition to disk if they do not fit in memory.
+    fn buffer(&mut self, rows: RecordBatch) -> Result<()> {
+        // The buffered rows of each input batch are a single slice, so the
+        // memory of the whole batch is attributed to them
+        let size = rows.get_array_memory_size();
+        self.batches.push(rows);
+        match self.reservation.try_grow(size) {
+            Ok(()) => {
+                self.batches_size += size;
+                Ok(())
+            }
+            Err(e) if !self.can_spill => Err(e),
+            Err(_) => self.spill_partition(),
+        }
+    }
+
+    /// Appends the buffered rows of the current partition to its spill file.
+    fn spill_partition(&mut self) -> Result<()> {
+        if self.spill.is_none() {
+            let spill_file = self
+                .runtime
+                .disk_manager
+                .create_tmp_file("WindowAggSpill")?;
+            let writer = IPCWriter::new(spill_file.path(), &self.input.schema())?;
+            self.spill = Some((spill_file, writer));
+        }
+        if let Some((_, writer)) = &mut self.spill {
+            for batch in self.batches.drain(..) {
+                writer.write(&batch)?;
+            }
+        }
+        self.reservation.shrink(self.batches_size);
+        self.batches_size = 0;
+        self.spill_count.add(1);
+        Ok(())
+    }
+
+    /// Ends the current partition. The results of a partition in memory are
+    /// computed along with the other ended partitions, and those of a spilled
+    /// partition while reading back its spill file.
+    fn end_partition(&mut self) -> Result<()> {
+        let batches = std::mem::take(&mut self.batches);
+        self.reservation.shrink(self.batches_size);
+        self.batches_size = 0;
+        let Some((spill_file, mut writer)) = self.spill.take() else {
+            self.ended_batches.extend(batches);
+            return Ok(());
+        };
+
+        // The results of the partitions before must be returned first
+        self.compute_ended_partitions()?;
+        for batch in &batches {
+            writer.write(batch)?;
+        }
+        writer.finish()?;
+        self.spilled_bytes.add(writer.num_bytes as usize);
+        let stream = evaluate_spilled_partition(
+            spill_file,
+            self.input.schema(),
+            self.schema.clone(),
+            self.window_expr.clone(),
+            self.partition_by_sort_keys.clone(),
+            self.ordered_partition_by_indices.clone(),
+        );
+        self.output.push_back(WindowAggOutput::Spilled(stream));
+        Ok(())
+    }
+
+    /// Computes the results of the ended partitions kept in memory.
+    fn compute_ended_partitions(&mut self) -> Result<()> {
+        if self.ended_batches.is_empty() {
+            return Ok(());
+        }
+        let batches = std::mem::take(&mut self.ended_batches);
+        let batch = self.compute_aggregates(&batches)?;
+        self.output.push_back(WindowAggOutput::Batch(batch));
+        Ok(())
+    }
+}
+
+/// Evaluates `window_expr` over a partition spilled to `spill_file`, reading
+/// it back one batch at a time.
+fn evaluate_spilled_partition(
+    spill_file: RefCountedTempFile,
+    input_schema: SchemaRef,
+    schema: SchemaRef,
+    window_expr: Vec<Arc<dyn WindowExpr>>,
+    partition_by_sort_keys: Vec<PhysicalSortExpr>,
+    ordered_partition_by_indices: Vec<usize>,
+) -> SendableRecordBatchStream {
+    let stream_schema = schema.clone();
+    // First pass: aggregates over the whole partition
+    let aggregates = async move {
+        let task = task::spawn_blocking(move || -> Result<_> {
+            let results = aggregate_spilled_partition(spill_file.path(), &window_expr)?;
+            Ok((spill_file, window_expr, results))
+        });
+        match task.await {
+            Ok(r) => r,
+            Err(e) => exec_err!("Error occurred while reading spill {e}"),
+        }
+    };
+    // Second pass: the other window expressions
+    let stream = stream::once(aggregates)
+        .map(move |aggregates| -> Result<_> {
+            let (spill_file, window_expr, results) = aggregates?;
+            let num_input_columns = input_schema.fields().len();
+            let input = read_spill_as_stream(spill_file, input_schema.clone())?;
+            let bounded_expr = window_expr
+                .iter()
+                .zip(&results)
+                .filter(|(_, result)| result.is_none())
+                .map(|(expr, _)| expr.clone())
+                .collect::<Vec<_>>();
+            let input: SendableRecordBatchStream = if bounded_expr.is_empty() {
+                input
+            } else {
+                let bounded_schema = create_schema(&input_schema, &bounded_expr)?;
+                Box::pin(BoundedWindowAggStream::new(
+                    Arc::new(bounded_schema),
+                    bounded_expr,
+                    input,
+                    BaselineMetrics::new(&ExecutionPlanMetricsSet::new(), 0),
+                    Box::new(SortedSearch::new(
+                        partition_by_sort_keys.clone(),
+                        ordered_partition_by_indices.clone(),
+                    )),
+                )?)
+            };
+
+            let schema = schema.clone();
+            Ok(input.map(move |batch| -> Result<RecordBatch> {
+                let batch = batch?;
+                let (input_columns, bounded_columns) =
+                    batch.columns().split_at(num_input_columns);
+                let mut bounded_columns = bounded_columns.iter();
+                let mut columns = input_columns.to_vec();
+                for result in &results {
+                    columns.push(match result {
+                        Some(value) => value.to_array_of_size(batch.num_rows())?,
+                        None => bounded_columns.next().unwrap().clone(),
+                    });
+                }
+                Ok(RecordBatch::try_new(schema.clone(), columns)?)
+            }))
+        })
+        .try_flatten();
+    Box::pin(RecordBatchStreamAdapter::new(stream_schema, stream))
+}
+
+/// Computes the aggregates over the whole partition spilled to `path`, if
+/// any, for each of `window_expr`.
+fn aggregate_spilled_partition(
+    path: &Path,
+    window_expr: &[Arc<dyn WindowExpr>],
+) -> Result<Vec<Option<ScalarValue>>> {
+    let mut accumulators = window_expr
+        .iter()
+        .map(|expr| {
+            whole_partition_aggregate(expr)
+                .map(|aggregate| aggregate.create_accumulator())
+                .transpose()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if accumulators.iter().all(Option::is_none) {
+        return Ok(vec![None; window_expr.len()]);
+    }
+
+    let reader = FileReader::try_new(BufReader::new(File::open(path)?), None)?;
+    for batch in reader {
+        let batch = batch?;
+        for (expr, accumulator) in window_expr.iter().zip(&mut accumulators) {
+            if let Some(accumulator) = accumulator {
+                accumulator.update_batch(&expr.evaluate_args(&batch)?)?;
+            }
+        }
+    }
+    accumulators
+        .iter()
+        .map(|accumulator| accumulator.as_ref().map(|a| a.evaluate()).transpose())
+        .collect()
 }
 
 impl Stream for WindowAggStream {
@@ -389,23 +718,42 @@ impl WindowAggStream {
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<RecordBatch>>> {
-        if self.finished {
-            return Poll::Ready(None);
-        }
-
         loop {
-            let result = match ready!(self.input.poll_next_unpin(cx)) {
-                Some(Ok(batch)) => {
-                    self.batches.push(batch);
-                    continue;
+            match self.output.front_mut() {
+                Some(WindowAggOutput::Batch(_)) => {
+                    let Some(WindowAggOutput::Batch(batch)) = self.output.pop_front()
+                    else {
+                        unreachable!()
+                    };
+                    return Poll::Ready(Some(Ok(batch)));
+                }
+                Some(WindowAggOutput::Spilled(stream)) => {
+                    match ready!(stream.poll_next_unpin(cx)) {
+                        Some(result) => return Poll::Ready(Some(result)),
+                        None => {
+                            self.output.pop_front();
+                            continue;
+                        }
+                    }
                 }
+                None if self.finished => return Poll::Ready(None),
+                None => {}
+            }
+
+            let result = match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => self.process_batch(batch),
                 Some(Err(e)) => Err(e),
-                None => self.compute_aggregates(),
+                None => {
+                    self.finished = true;
+                    self.end_partition()
+                        .and_then(|_| self.compute_ended_partitions())
+                }
             };
-
-            self.finished = true;
-
-            return Poll::Ready(Some(result));
+            if let Err(e) = result {
+                self.finished = true;
+                self.output.clear();
+                return Poll::Ready(Some(Err(e)));
+            }
         }
     }
 }
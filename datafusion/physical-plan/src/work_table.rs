@@ -0,0 +1,212 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the work table query plan, reading the rows produced by the
+//! previous iteration of a recursive query
+
+use std::any::Any;
+use std::sync::Arc;
+
+use super::expressions::PhysicalSortExpr;
+use super::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, RecordOutput,
+};
+use super::{DisplayAs, SendableRecordBatchStream, Statistics};
+use crate::memory::MemoryStream;
+use crate::stream::RecordBatchStreamAdapter;
+use crate::{DisplayFormatType, ExecutionPlan, Partitioning};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::{internal_err, project_schema, DataFusionError, Result};
+use datafusion_execution::TaskContext;
+
+use futures::StreamExt;
+use parking_lot::Mutex;
+
+/// The work table of a recursive query: the rows produced by the previous
+/// iteration of the query, which its recursive term reads through
+/// [`WorkTableExec`]
+#[derive(Debug, Default)]
+pub struct WorkTable {
+    batches: Mutex<Option<Vec<RecordBatch>>>,
+}
+
+impl WorkTable {
+    /// Create a new, unset, work table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the rows of the work table, or an error if they were not set
+    /// yet
+    fn batches(&self) -> Result<Vec<RecordBatch>> {
+        match self.batches.lock().as_ref() {
+            Some(batches) => Ok(batches.clone()),
+            None => internal_err!("Unexpected empty work table"),
+        }
+    }
+
+    /// Replace the rows of the work table
+    pub fn update(&self, batches: Vec<RecordBatch>) {
+        self.batches.lock().replace(batches);
+    }
+}
+
+/// Execution plan reading the [`WorkTable`] of the recursive query named
+/// `name`, from its recursive term.
+///
+/// The work table is shared with the `RecursiveQueryExec` containing the
+/// plan, which updates it before each evaluation of its recursive term.
+#[derive(Debug)]
+pub struct WorkTableExec {
+    /// Name of the recursive query
+    name: String,
+    /// The schema of the work table
+    schema: SchemaRef,
+    /// Optional projection
+    projection: Option<Vec<usize>>,
+    /// The schema after the projection
+    projected_schema: SchemaRef,
+    /// The work table
+    work_table: Arc<WorkTable>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl WorkTableExec {
+    /// Create a new execution plan reading the work table of the recursive
+    /// query `name`, whose schema is `schema`
+    pub fn try_new(
+        name: String,
+        schema: SchemaRef,
+        projection: Option<Vec<usize>>,
+    ) -> Result<Self> {
+        let projected_schema = project_schema(&schema, projection.as_ref())?;
+        Ok(Self {
+            name,
+            schema,
+            projection,
+            projected_schema,
+            work_table: Arc::new(WorkTable::new()),
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// Name of the recursive query
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The schema of the work table, before the projection
+    pub fn table_schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Optional projection
+    pub fn projection(&self) -> Option<&Vec<usize>> {
+        self.projection.as_ref()
+    }
+
+    /// Returns a new plan reading `work_table`
+    pub fn with_work_table(&self, work_table: Arc<WorkTable>) -> Self {
+        Self {
+            name: self.name.clone(),
+            schema: self.schema.clone(),
+            projection: self.projection.clone(),
+            projected_schema: self.projected_schema.clone(),
+            work_table,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl DisplayAs for WorkTableExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "WorkTableExec: name={}", self.name)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for WorkTableExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        // WorkTableExec has a single output partition
+        if partition != 0 {
+            return internal_err!(
+                "WorkTableExec got an invalid partition {partition} (expected 0)"
+            );
+        }
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let stream = MemoryStream::try_new(
+            self.work_table.batches()?,
+            self.projected_schema.clone(),
+            self.projection.clone(),
+        )?
+        .with_cancellation_token(context.cancellation_token().clone())
+        .map(move |batch| batch.record_output(&baseline_metrics));
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.projected_schema.clone(),
+            stream,
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(Statistics::new_unknown(&self.schema()))
+    }
+}
@@ -1083,6 +1083,9 @@ impl serde::Serialize for AnalyzeExecNode {
         if self.schema.is_some() {
             len += 1;
         }
+        if self.json {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion.AnalyzeExecNode", len)?;
         if self.verbose {
             struct_ser.serialize_field("verbose", &self.verbose)?;
@@ -1096,6 +1099,9 @@ impl serde::Serialize for AnalyzeExecNode {
         if let Some(v) = self.schema.as_ref() {
             struct_ser.serialize_field("schema", v)?;
         }
+        if self.json {
+            struct_ser.serialize_field("json", &self.json)?;
+        }
         struct_ser.end()
     }
 }
@@ -1111,6 +1117,7 @@ impl<'de> serde::Deserialize<'de> for AnalyzeExecNode {
             "showStatistics",
             "input",
             "schema",
+            "json",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -1119,6 +1126,7 @@ impl<'de> serde::Deserialize<'de> for AnalyzeExecNode {
             ShowStatistics,
             Input,
             Schema,
+            Json,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -1144,6 +1152,7 @@ impl<'de> serde::Deserialize<'de> for AnalyzeExecNode {
                             "showStatistics" | "show_statistics" => Ok(GeneratedField::ShowStatistics),
                             "input" => Ok(GeneratedField::Input),
                             "schema" => Ok(GeneratedField::Schema),
+                            "json" => Ok(GeneratedField::Json),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -1167,6 +1176,7 @@ impl<'de> serde::Deserialize<'de> for AnalyzeExecNode {
                 let mut show_statistics__ = None;
                 let mut input__ = None;
                 let mut schema__ = None;
+                let mut json__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::Verbose => {
@@ -1193,6 +1203,12 @@ impl<'de> serde::Deserialize<'de> for AnalyzeExecNode {
                             }
                             schema__ = map_.next_value()?;
                         }
+                        GeneratedField::Json => {
+                            if json__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("json"));
+                            }
+                            json__ = Some(map_.next_value()?);
+                        }
                     }
                 }
                 Ok(AnalyzeExecNode {
@@ -1200,6 +1216,7 @@ impl<'de> serde::Deserialize<'de> for AnalyzeExecNode {
                     show_statistics: show_statistics__.unwrap_or_default(),
                     input: input__,
                     schema: schema__,
+                    json: json__.unwrap_or_default(),
                 })
             }
         }
@@ -1220,6 +1237,9 @@ impl serde::Serialize for AnalyzeNode {
         if self.verbose {
             len += 1;
         }
+        if self.json {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion.AnalyzeNode", len)?;
         if let Some(v) = self.input.as_ref() {
             struct_ser.serialize_field("input", v)?;
@@ -1227,6 +1247,9 @@ impl serde::Serialize for AnalyzeNode {
         if self.verbose {
             struct_ser.serialize_field("verbose", &self.verbose)?;
         }
+        if self.json {
+            struct_ser.serialize_field("json", &self.json)?;
+        }
         struct_ser.end()
     }
 }
@@ -1239,12 +1262,14 @@ impl<'de> serde::Deserialize<'de> for AnalyzeNode {
         const FIELDS: &[&str] = &[
             "input",
             "verbose",
+            "json",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
             Input,
             Verbose,
+            Json,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -1268,6 +1293,7 @@ impl<'de> serde::Deserialize<'de> for AnalyzeNode {
                         match value {
                             "input" => Ok(GeneratedField::Input),
                             "verbose" => Ok(GeneratedField::Verbose),
+                            "json" => Ok(GeneratedField::Json),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -1289,6 +1315,7 @@ impl<'de> serde::Deserialize<'de> for AnalyzeNode {
             {
                 let mut input__ = None;
                 let mut verbose__ = None;
+                let mut json__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::Input => {
@@ -1303,11 +1330,18 @@ impl<'de> serde::Deserialize<'de> for AnalyzeNode {
                             }
                             verbose__ = Some(map_.next_value()?);
                         }
+                        GeneratedField::Json => {
+                            if json__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("json"));
+                            }
+                            json__ = Some(map_.next_value()?);
+                        }
                     }
                 }
                 Ok(AnalyzeNode {
                     input: input__,
                     verbose: verbose__.unwrap_or_default(),
+                    json: json__.unwrap_or_default(),
                 })
             }
         }
@@ -4193,6 +4227,9 @@ impl serde::Serialize for CreateExternalTableNode {
         if !self.column_defaults.is_empty() {
             len += 1;
         }
+        if !self.schema_location.is_empty() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion.CreateExternalTableNode", len)?;
         if let Some(v) = self.name.as_ref() {
             struct_ser.serialize_field("name", v)?;
@@ -4239,6 +4276,9 @@ impl serde::Serialize for CreateExternalTableNode {
         if !self.column_defaults.is_empty() {
             struct_ser.serialize_field("columnDefaults", &self.column_defaults)?;
         }
+        if !self.schema_location.is_empty() {
+            struct_ser.serialize_field("schemaLocation", &self.schema_location)?;
+        }
         struct_ser.end()
     }
 }
@@ -4271,6 +4311,8 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
             "constraints",
             "column_defaults",
             "columnDefaults",
+            "schema_location",
+            "schemaLocation",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -4290,6 +4332,7 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
             Options,
             Constraints,
             ColumnDefaults,
+            SchemaLocation,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -4326,6 +4369,7 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
                             "options" => Ok(GeneratedField::Options),
                             "constraints" => Ok(GeneratedField::Constraints),
                             "columnDefaults" | "column_defaults" => Ok(GeneratedField::ColumnDefaults),
+                            "schemaLocation" | "schema_location" => Ok(GeneratedField::SchemaLocation),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -4360,6 +4404,7 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
                 let mut options__ = None;
                 let mut constraints__ = None;
                 let mut column_defaults__ = None;
+                let mut schema_location__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::Name => {
@@ -4456,6 +4501,12 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
                                 map_.next_value::<std::collections::HashMap<_, _>>()?
                             );
                         }
+                        GeneratedField::SchemaLocation => {
+                            if schema_location__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("schemaLocation"));
+                            }
+                            schema_location__ = Some(map_.next_value()?);
+                        }
                     }
                 }
                 Ok(CreateExternalTableNode {
@@ -4474,6 +4525,7 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
                     options: options__.unwrap_or_default(),
                     constraints: constraints__,
                     column_defaults: column_defaults__.unwrap_or_default(),
+                    schema_location: schema_location__.unwrap_or_default(),
                 })
             }
         }
@@ -20496,6 +20548,9 @@ impl serde::Serialize for PlanType {
                 plan_type::PlanTypeEnum::FinalPhysicalPlanWithStats(v) => {
                     struct_ser.serialize_field("FinalPhysicalPlanWithStats", v)?;
                 }
+                plan_type::PlanTypeEnum::LogicalOptimizerMetrics(v) => {
+                    struct_ser.serialize_field("LogicalOptimizerMetrics", v)?;
+                }
             }
         }
         struct_ser.end()
@@ -20518,6 +20573,7 @@ impl<'de> serde::Deserialize<'de> for PlanType {
             "OptimizedPhysicalPlan",
             "FinalPhysicalPlan",
             "FinalPhysicalPlanWithStats",
+            "LogicalOptimizerMetrics",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -20532,6 +20588,7 @@ impl<'de> serde::Deserialize<'de> for PlanType {
             OptimizedPhysicalPlan,
             FinalPhysicalPlan,
             FinalPhysicalPlanWithStats,
+            LogicalOptimizerMetrics,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -20563,6 +20620,7 @@ impl<'de> serde::Deserialize<'de> for PlanType {
                             "OptimizedPhysicalPlan" => Ok(GeneratedField::OptimizedPhysicalPlan),
                             "FinalPhysicalPlan" => Ok(GeneratedField::FinalPhysicalPlan),
                             "FinalPhysicalPlanWithStats" => Ok(GeneratedField::FinalPhysicalPlanWithStats),
+                            "LogicalOptimizerMetrics" => Ok(GeneratedField::LogicalOptimizerMetrics),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -20653,6 +20711,13 @@ impl<'de> serde::Deserialize<'de> for PlanType {
                                 return Err(serde::de::Error::duplicate_field("FinalPhysicalPlanWithStats"));
                             }
                             plan_type_enum__ = map_.next_value::<::std::option::Option<_>>()?.map(plan_type::PlanTypeEnum::FinalPhysicalPlanWithStats)
+;
+                        }
+                        GeneratedField::LogicalOptimizerMetrics => {
+                            if plan_type_enum__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("LogicalOptimizerMetrics"));
+                            }
+                            plan_type_enum__ = map_.next_value::<::std::option::Option<_>>()?.map(plan_type::PlanTypeEnum::LogicalOptimizerMetrics)
 ;
                         }
                     }
@@ -22332,6 +22397,13 @@ impl serde::Serialize for ScalarFunction {
             Self::FindInSet => "FindInSet",
             Self::ArraySort => "ArraySort",
             Self::ArrayDistinct => "ArrayDistinct",
+            Self::LastDay => "LastDay",
+            Self::NextDay => "NextDay",
+            Self::SparkMurmur3Hash => "SparkMurmur3Hash",
+            Self::HiveHash => "HiveHash",
+            Self::DateBinGapfill => "DateBinGapfill",
+            Self::Locf => "Locf",
+            Self::Interpolate => "Interpolate",
         };
         serializer.serialize_str(variant)
     }
@@ -22473,6 +22545,13 @@ impl<'de> serde::Deserialize<'de> for ScalarFunction {
             "FindInSet",
             "ArraySort",
             "ArrayDistinct",
+            "LastDay",
+            "NextDay",
+            "SparkMurmur3Hash",
+            "HiveHash",
+            "DateBinGapfill",
+            "Locf",
+            "Interpolate",
         ];
 
         struct GeneratedVisitor;
@@ -22643,6 +22722,13 @@ impl<'de> serde::Deserialize<'de> for ScalarFunction {
                     "FindInSet" => Ok(ScalarFunction::FindInSet),
                     "ArraySort" => Ok(ScalarFunction::ArraySort),
                     "ArrayDistinct" => Ok(ScalarFunction::ArrayDistinct),
+                    "LastDay" => Ok(ScalarFunction::LastDay),
+                    "NextDay" => Ok(ScalarFunction::NextDay),
+                    "SparkMurmur3Hash" => Ok(ScalarFunction::SparkMurmur3Hash),
+                    "HiveHash" => Ok(ScalarFunction::HiveHash),
+                    "DateBinGapfill" => Ok(ScalarFunction::DateBinGapfill),
+                    "Locf" => Ok(ScalarFunction::Locf),
+                    "Interpolate" => Ok(ScalarFunction::Interpolate),
                     _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
                 }
             }
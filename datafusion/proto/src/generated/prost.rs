@@ -367,6 +367,8 @@ pub struct CreateExternalTableNode {
         ::prost::alloc::string::String,
         LogicalExprNode,
     >,
+    #[prost(string, tag = "17")]
+    pub schema_location: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -437,6 +439,8 @@ pub struct AnalyzeNode {
     pub input: ::core::option::Option<::prost::alloc::boxed::Box<LogicalPlanNode>>,
     #[prost(bool, tag = "2")]
     pub verbose: bool,
+    #[prost(bool, tag = "3")]
+    pub json: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1477,7 +1481,7 @@ pub struct OptimizedPhysicalPlanType {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PlanType {
-    #[prost(oneof = "plan_type::PlanTypeEnum", tags = "1, 7, 8, 2, 3, 4, 9, 5, 6, 10")]
+    #[prost(oneof = "plan_type::PlanTypeEnum", tags = "1, 7, 8, 2, 3, 4, 9, 5, 6, 10, 11")]
     pub plan_type_enum: ::core::option::Option<plan_type::PlanTypeEnum>,
 }
 /// Nested message and enum types in `PlanType`.
@@ -1505,6 +1509,8 @@ pub mod plan_type {
         FinalPhysicalPlan(super::EmptyMessage),
         #[prost(message, tag = "10")]
         FinalPhysicalPlanWithStats(super::EmptyMessage),
+        #[prost(message, tag = "11")]
+        LogicalOptimizerMetrics(super::EmptyMessage),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2212,6 +2218,8 @@ pub struct AnalyzeExecNode {
     pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
     #[prost(message, optional, tag = "4")]
     pub schema: ::core::option::Option<Schema>,
+    #[prost(bool, tag = "5")]
+    pub json: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2754,6 +2762,13 @@ pub enum ScalarFunction {
     FindInSet = 127,
     ArraySort = 128,
     ArrayDistinct = 129,
+    LastDay = 130,
+    NextDay = 131,
+    SparkMurmur3Hash = 132,
+    HiveHash = 133,
+    DateBinGapfill = 134,
+    Locf = 135,
+    Interpolate = 136,
 }
 impl ScalarFunction {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -2892,6 +2907,13 @@ impl ScalarFunction {
             ScalarFunction::FindInSet => "FindInSet",
             ScalarFunction::ArraySort => "ArraySort",
             ScalarFunction::ArrayDistinct => "ArrayDistinct",
+            ScalarFunction::LastDay => "LastDay",
+            ScalarFunction::NextDay => "NextDay",
+            ScalarFunction::SparkMurmur3Hash => "SparkMurmur3Hash",
+            ScalarFunction::HiveHash => "HiveHash",
+            ScalarFunction::DateBinGapfill => "DateBinGapfill",
+            ScalarFunction::Locf => "Locf",
+            ScalarFunction::Interpolate => "Interpolate",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -3027,6 +3049,13 @@ impl ScalarFunction {
             "FindInSet" => Some(Self::FindInSet),
             "ArraySort" => Some(Self::ArraySort),
             "ArrayDistinct" => Some(Self::ArrayDistinct),
+            "LastDay" => Some(Self::LastDay),
+            "NextDay" => Some(Self::NextDay),
+            "SparkMurmur3Hash" => Some(Self::SparkMurmur3Hash),
+            "HiveHash" => Some(Self::HiveHash),
+            "DateBinGapfill" => Some(Self::DateBinGapfill),
+            "Locf" => Some(Self::Locf),
+            "Interpolate" => Some(Self::Interpolate),
             _ => None,
         }
     }
@@ -20,8 +20,8 @@ use crate::protobuf::{
     plan_type::PlanTypeEnum::{
         AnalyzedLogicalPlan, FinalAnalyzedLogicalPlan, FinalLogicalPlan,
         FinalPhysicalPlan, FinalPhysicalPlanWithStats, InitialLogicalPlan,
-        InitialPhysicalPlan, InitialPhysicalPlanWithStats, OptimizedLogicalPlan,
-        OptimizedPhysicalPlan,
+        InitialPhysicalPlan, InitialPhysicalPlanWithStats, LogicalOptimizerMetrics,
+        OptimizedLogicalPlan, OptimizedPhysicalPlan,
     },
     AnalyzedLogicalPlanType, CubeNode, GroupingSetNode, OptimizedLogicalPlanType,
     OptimizedPhysicalPlanType, PlaceholderNode, RollupNode,
@@ -49,23 +49,25 @@ use datafusion_expr::{
     array_replace, array_replace_all, array_replace_n, array_slice, array_sort,
     array_to_string, arrow_typeof, ascii, asin, asinh, atan, atan2, atanh, bit_length,
     btrim, cardinality, cbrt, ceil, character_length, chr, coalesce, concat_expr,
-    concat_ws_expr, cos, cosh, cot, current_date, current_time, date_bin, date_part,
-    date_trunc, decode, degrees, digest, encode, exp,
+    concat_ws_expr, cos, cosh, cot, current_date, current_time, date_bin,
+    date_bin_gapfill, date_part, date_trunc, decode, degrees, digest, encode, exp,
     expr::{self, InList, Sort, WindowFunction},
-    factorial, find_in_set, flatten, floor, from_unixtime, gcd, gen_range, isnan, iszero,
-    lcm, left, levenshtein, ln, log, log10, log2,
+    factorial, find_in_set, flatten, floor, from_unixtime, gcd, gen_range, hive_hash,
+    interpolate, isnan, iszero, last_day, lcm, left, levenshtein, ln, locf, log, log10,
+    log2,
     logical_plan::{PlanType, StringifiedPlan},
-    lower, lpad, ltrim, md5, nanvl, now, nullif, octet_length, overlay, pi, power,
-    radians, random, regexp_match, regexp_replace, repeat, replace, reverse, right,
-    round, rpad, rtrim, sha224, sha256, sha384, sha512, signum, sin, sinh, split_part,
-    sqrt, starts_with, string_to_array, strpos, struct_fun, substr, substr_index,
-    substring, tan, tanh, to_hex, to_timestamp_micros, to_timestamp_millis,
-    to_timestamp_nanos, to_timestamp_seconds, translate, trim, trunc, upper, uuid,
-    AggregateFunction, Between, BinaryExpr, BuiltInWindowFunction, BuiltinScalarFunction,
-    Case, Cast, Expr, GetFieldAccess, GetIndexedField, GroupingSet,
+    lower, lpad, ltrim, md5, nanvl, next_day, now, nullif, octet_length, overlay, pi,
+    power, radians, random, regexp_match, regexp_replace, repeat, replace, reverse,
+    right, round, rpad, rtrim, sha224, sha256, sha384, sha512, signum, sin, sinh,
+    spark_murmur3_hash, split_part, sqrt, starts_with, string_to_array, strpos,
+    struct_fun, substr, substr_index, substring, tan, tanh, to_hex, to_timestamp_micros,
+    to_timestamp_millis, to_timestamp_nanos, to_timestamp_seconds, translate, trim,
+    trunc, upper, uuid, AggregateFunction, Between, BinaryExpr, BuiltInWindowFunction,
+    BuiltinScalarFunction, Case, Cast, Expr, GetFieldAccess, GetIndexedField,
+    GroupingSet,
     GroupingSet::GroupingSets,
-    JoinConstraint, JoinType, Like, Operator, TryCast, WindowFrame, WindowFrameBound,
-    WindowFrameUnits,
+    JoinConstraint, JoinType, Like, Operator, TryCast, WildcardOptions, WindowFrame,
+    WindowFrameBound, WindowFrameUnits,
 };
 use datafusion_expr::{
     array_empty, array_pop_back, array_pop_front,
@@ -419,6 +421,7 @@ impl From<&protobuf::StringifiedPlan> for StringifiedPlan {
                         optimizer_name: optimizer_name.clone(),
                     }
                 }
+                LogicalOptimizerMetrics(_) => PlanType::LogicalOptimizerMetrics,
                 FinalLogicalPlan(_) => PlanType::FinalLogicalPlan,
                 InitialPhysicalPlan(_) => PlanType::InitialPhysicalPlan,
                 InitialPhysicalPlanWithStats(_) => PlanType::InitialPhysicalPlanWithStats,
@@ -513,11 +516,18 @@ impl From<&protobuf::ScalarFunction> for BuiltinScalarFunction {
             ScalarFunction::DatePart => Self::DatePart,
             ScalarFunction::DateTrunc => Self::DateTrunc,
             ScalarFunction::DateBin => Self::DateBin,
+            ScalarFunction::DateBinGapfill => Self::DateBinGapfill,
+            ScalarFunction::Locf => Self::Locf,
+            ScalarFunction::Interpolate => Self::Interpolate,
+            ScalarFunction::LastDay => Self::LastDay,
+            ScalarFunction::NextDay => Self::NextDay,
             ScalarFunction::Md5 => Self::MD5,
             ScalarFunction::Sha224 => Self::SHA224,
             ScalarFunction::Sha256 => Self::SHA256,
             ScalarFunction::Sha384 => Self::SHA384,
             ScalarFunction::Sha512 => Self::SHA512,
+            ScalarFunction::SparkMurmur3Hash => Self::SparkMurmur3Hash,
+            ScalarFunction::HiveHash => Self::HiveHash,
             ScalarFunction::Digest => Self::Digest,
             ScalarFunction::Encode => Self::Encode,
             ScalarFunction::Decode => Self::Decode,
@@ -1344,6 +1354,7 @@ pub fn parse_expr(
             } else {
                 Some(qualifier.clone())
             },
+            options: WildcardOptions::default(),
         }),
         ExprType::ScalarFunction(expr) => {
             let scalar_function = protobuf::ScalarFunction::try_from(expr.fun)
@@ -1548,10 +1559,37 @@ pub fn parse_expr(
                     parse_expr(&args[1], registry)?,
                     parse_expr(&args[2], registry)?,
                 )),
+                ScalarFunction::DateBinGapfill => Ok(date_bin_gapfill(
+                    args.to_owned()
+                        .iter()
+                        .map(|expr| parse_expr(expr, registry))
+                        .collect::<Result<Vec<_>, _>>()?,
+                )),
+                ScalarFunction::Locf => Ok(locf(parse_expr(&args[0], registry)?)),
+                ScalarFunction::Interpolate => {
+                    Ok(interpolate(parse_expr(&args[0], registry)?))
+                }
+                ScalarFunction::LastDay => Ok(last_day(parse_expr(&args[0], registry)?)),
+                ScalarFunction::NextDay => Ok(next_day(
+                    parse_expr(&args[0], registry)?,
+                    parse_expr(&args[1], registry)?,
+                )),
                 ScalarFunction::Sha224 => Ok(sha224(parse_expr(&args[0], registry)?)),
                 ScalarFunction::Sha256 => Ok(sha256(parse_expr(&args[0], registry)?)),
                 ScalarFunction::Sha384 => Ok(sha384(parse_expr(&args[0], registry)?)),
                 ScalarFunction::Sha512 => Ok(sha512(parse_expr(&args[0], registry)?)),
+                ScalarFunction::SparkMurmur3Hash => Ok(spark_murmur3_hash(
+                    args.to_owned()
+                        .iter()
+                        .map(|expr| parse_expr(expr, registry))
+                        .collect::<Result<Vec<_>, _>>()?,
+                )),
+                ScalarFunction::HiveHash => Ok(hive_hash(
+                    args.to_owned()
+                        .iter()
+                        .map(|expr| parse_expr(expr, registry))
+                        .collect::<Result<Vec<_>, _>>()?,
+                )),
                 ScalarFunction::Md5 => Ok(md5(parse_expr(&args[0], registry)?)),
                 ScalarFunction::Encode => Ok(encode(
                     parse_expr(&args[0], registry)?,
@@ -56,10 +56,10 @@ use datafusion_common::{
 use datafusion_expr::{
     dml,
     logical_plan::{
-        builder::project, Aggregate, CreateCatalog, CreateCatalogSchema,
-        CreateExternalTable, CreateView, CrossJoin, DdlStatement, Distinct,
-        EmptyRelation, Extension, Join, JoinConstraint, Limit, Prepare, Projection,
-        Repartition, Sort, SubqueryAlias, TableScan, Values, Window,
+        builder::project, Aggregate, Analyze, AnalyzeFormat, CreateCatalog,
+        CreateCatalogSchema, CreateExternalTable, CreateView, CrossJoin, DdlStatement,
+        Distinct, EmptyRelation, Extension, Join, JoinConstraint, Limit, Prepare,
+        Projection, Repartition, Sort, SubqueryAlias, TableScan, Values, Window,
     },
     DistinctOn, DropView, Expr, LogicalPlan, LogicalPlanBuilder,
 };
@@ -516,6 +516,12 @@ impl AsLogicalPlan for LogicalPlanNode {
                     None
                 };
 
+                let schema_location = if !create_extern_table.schema_location.is_empty() {
+                    Some(create_extern_table.schema_location.clone())
+                } else {
+                    None
+                };
+
                 let file_type = create_extern_table.file_type.as_str();
                 if ctx.table_factory(file_type).is_none() {
                     internal_err!("No TableProviderFactory for file type: {file_type}")?
@@ -558,6 +564,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                     options: create_extern_table.options.clone(),
                     constraints: constraints.into(),
                     column_defaults,
+                    schema_location,
                 })))
             }
             LogicalPlanType::CreateView(create_view) => {
@@ -615,9 +622,18 @@ impl AsLogicalPlan for LogicalPlanNode {
             LogicalPlanType::Analyze(analyze) => {
                 let input: LogicalPlan =
                     into_logical_plan!(analyze.input, ctx, extension_codec)?;
-                LogicalPlanBuilder::from(input)
+                let plan = LogicalPlanBuilder::from(input)
                     .explain(analyze.verbose, true)?
-                    .build()
+                    .build()?;
+                match plan {
+                    LogicalPlan::Analyze(a) if analyze.json => {
+                        Ok(LogicalPlan::Analyze(Analyze {
+                            format: AnalyzeFormat::Json,
+                            ..a
+                        }))
+                    }
+                    plan => Ok(plan),
+                }
             }
             LogicalPlanType::Explain(explain) => {
                 let input: LogicalPlan =
@@ -1390,6 +1406,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                     options,
                     constraints,
                     column_defaults,
+                    schema_location,
                 },
             )) => {
                 let mut converted_order_exprs: Vec<LogicalExprNodeCollection> = vec![];
@@ -1428,6 +1445,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                             options: options.clone(),
                             constraints: Some(constraints.clone().into()),
                             column_defaults: converted_column_defaults,
+                            schema_location: schema_location.clone().unwrap_or_default(),
                         },
                     )),
                 })
@@ -1488,6 +1506,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                         protobuf::AnalyzeNode {
                             input: Some(Box::new(input)),
                             verbose: a.verbose,
+                            json: a.format == AnalyzeFormat::Json,
                         },
                     ))),
                 })
@@ -1702,6 +1721,9 @@ impl AsLogicalPlan for LogicalPlanNode {
             LogicalPlan::DescribeTable(_) => Err(proto_error(
                 "LogicalPlan serde is not yet implemented for DescribeTable",
             )),
+            LogicalPlan::RecursiveQuery(_) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for RecursiveQuery",
+            )),
         }
     }
 }
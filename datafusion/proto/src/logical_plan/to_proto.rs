@@ -25,8 +25,8 @@ use crate::protobuf::{
     plan_type::PlanTypeEnum::{
         AnalyzedLogicalPlan, FinalAnalyzedLogicalPlan, FinalLogicalPlan,
         FinalPhysicalPlan, FinalPhysicalPlanWithStats, InitialLogicalPlan,
-        InitialPhysicalPlan, InitialPhysicalPlanWithStats, OptimizedLogicalPlan,
-        OptimizedPhysicalPlan,
+        InitialPhysicalPlan, InitialPhysicalPlanWithStats, LogicalOptimizerMetrics,
+        OptimizedLogicalPlan, OptimizedPhysicalPlan,
     },
     AnalyzedLogicalPlanType, CubeNode, EmptyMessage, GroupingSetNode, LogicalExprList,
     OptimizedLogicalPlanType, OptimizedPhysicalPlanType, PlaceholderNode, RollupNode,
@@ -51,7 +51,8 @@ use datafusion_expr::expr::{
 use datafusion_expr::{
     logical_plan::PlanType, logical_plan::StringifiedPlan, AggregateFunction,
     BuiltInWindowFunction, BuiltinScalarFunction, Expr, JoinConstraint, JoinType,
-    TryCast, WindowFrame, WindowFrameBound, WindowFrameUnits, WindowFunctionDefinition,
+    TryCast, WildcardOptions, WindowFrame, WindowFrameBound, WindowFrameUnits,
+    WindowFunctionDefinition,
 };
 
 #[derive(Debug)]
@@ -340,6 +341,9 @@ impl From<&StringifiedPlan> for protobuf::StringifiedPlan {
                         )),
                     })
                 }
+                PlanType::LogicalOptimizerMetrics => Some(protobuf::PlanType {
+                    plan_type_enum: Some(LogicalOptimizerMetrics(EmptyMessage {})),
+                }),
                 PlanType::FinalLogicalPlan => Some(protobuf::PlanType {
                     plan_type_enum: Some(FinalLogicalPlan(EmptyMessage {})),
                 }),
@@ -998,11 +1002,18 @@ impl TryFrom<&Expr> for protobuf::LogicalExprNode {
                     expr_type: Some(ExprType::InList(expr)),
                 }
             }
-            Expr::Wildcard { qualifier } => Self {
-                expr_type: Some(ExprType::Wildcard(protobuf::Wildcard {
-                    qualifier: qualifier.clone().unwrap_or("".to_string()),
-                })),
-            },
+            Expr::Wildcard { qualifier, options } => {
+                if options != &WildcardOptions::default() {
+                    return Err(Error::General(format!(
+                        "Proto serialization error: wildcard options are not supported, got {options}"
+                    )));
+                }
+                Self {
+                    expr_type: Some(ExprType::Wildcard(protobuf::Wildcard {
+                        qualifier: qualifier.clone().unwrap_or("".to_string()),
+                    })),
+                }
+            }
             Expr::ScalarSubquery(_)
             | Expr::InSubquery(_)
             | Expr::Exists { .. }
@@ -1540,11 +1551,18 @@ impl TryFrom<&BuiltinScalarFunction> for protobuf::ScalarFunction {
             BuiltinScalarFunction::DatePart => Self::DatePart,
             BuiltinScalarFunction::DateTrunc => Self::DateTrunc,
             BuiltinScalarFunction::DateBin => Self::DateBin,
+            BuiltinScalarFunction::DateBinGapfill => Self::DateBinGapfill,
+            BuiltinScalarFunction::Locf => Self::Locf,
+            BuiltinScalarFunction::Interpolate => Self::Interpolate,
+            BuiltinScalarFunction::LastDay => Self::LastDay,
+            BuiltinScalarFunction::NextDay => Self::NextDay,
             BuiltinScalarFunction::MD5 => Self::Md5,
             BuiltinScalarFunction::SHA224 => Self::Sha224,
             BuiltinScalarFunction::SHA256 => Self::Sha256,
             BuiltinScalarFunction::SHA384 => Self::Sha384,
             BuiltinScalarFunction::SHA512 => Self::Sha512,
+            BuiltinScalarFunction::SparkMurmur3Hash => Self::SparkMurmur3Hash,
+            BuiltinScalarFunction::HiveHash => Self::HiveHash,
             BuiltinScalarFunction::Digest => Self::Digest,
             BuiltinScalarFunction::Decode => Self::Decode,
             BuiltinScalarFunction::Encode => Self::Encode,
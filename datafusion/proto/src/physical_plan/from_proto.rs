@@ -605,6 +605,7 @@ impl From<&protobuf::ColumnStats> for ColumnStatistics {
             } else {
                 Precision::Absent
             },
+            histogram: None,
         }
     }
 }
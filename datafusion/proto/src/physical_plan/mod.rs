@@ -31,6 +31,7 @@ use datafusion::datasource::physical_plan::ParquetExec;
 use datafusion::datasource::physical_plan::{AvroExec, CsvExec};
 use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::execution::FunctionRegistry;
+use datafusion::logical_expr::AnalyzeFormat;
 use datafusion::physical_plan::aggregates::{create_aggregate_expr, AggregateMode};
 use datafusion::physical_plan::aggregates::{AggregateExec, PhysicalGroupBy};
 use datafusion::physical_plan::analyze::AnalyzeExec;
@@ -886,12 +887,20 @@ impl AsExecutionPlan for PhysicalPlanNode {
                     runtime,
                     extension_codec,
                 )?;
-                Ok(Arc::new(AnalyzeExec::new(
-                    analyze.verbose,
-                    analyze.show_statistics,
-                    input,
-                    Arc::new(convert_required!(analyze.schema)?),
-                )))
+                let format = if analyze.json {
+                    AnalyzeFormat::Json
+                } else {
+                    AnalyzeFormat::Text
+                };
+                Ok(Arc::new(
+                    AnalyzeExec::new(
+                        analyze.verbose,
+                        analyze.show_statistics,
+                        input,
+                        Arc::new(convert_required!(analyze.schema)?),
+                    )
+                    .with_format(format),
+                ))
             }
             PhysicalPlanType::JsonSink(sink) => {
                 let input =
@@ -1049,6 +1058,7 @@ impl AsExecutionPlan for PhysicalPlanNode {
                         show_statistics: exec.show_statistics(),
                         input: Some(Box::new(input)),
                         schema: Some(exec.schema().as_ref().try_into()?),
+                        json: exec.format() == AnalyzeFormat::Json,
                     },
                 ))),
             });
@@ -32,7 +32,7 @@ use datafusion::datasource::physical_plan::{
 };
 use datafusion::execution::context::ExecutionProps;
 use datafusion::logical_expr::{
-    create_udf, BuiltinScalarFunction, JoinType, Operator, Volatility,
+    create_udf, AnalyzeFormat, BuiltinScalarFunction, JoinType, Operator, Volatility,
 };
 use datafusion::parquet::file::properties::WriterProperties;
 use datafusion::physical_expr::window::SlidingAggregateWindowExpr;
@@ -747,6 +747,19 @@ fn roundtrip_analyze() -> Result<()> {
     )))
 }
 
+#[test]
+fn roundtrip_analyze_json() -> Result<()> {
+    let field_a = Field::new("plan_type", DataType::Utf8, false);
+    let field_b = Field::new("plan", DataType::Utf8, false);
+    let schema = Schema::new(vec![field_a, field_b]);
+    let input = Arc::new(PlaceholderRowExec::new(Arc::new(schema.clone())));
+
+    roundtrip_test(Arc::new(
+        AnalyzeExec::new(false, false, input, Arc::new(schema))
+            .with_format(AnalyzeFormat::Json),
+    ))
+}
+
 #[test]
 fn roundtrip_json_sink() -> Result<()> {
     let field_a = Field::new("plan_type", DataType::Utf8, false);
@@ -29,6 +29,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             BinaryOperator::LtEq => Ok(Operator::LtEq),
             BinaryOperator::Eq => Ok(Operator::Eq),
             BinaryOperator::NotEq => Ok(Operator::NotEq),
+            BinaryOperator::Spaceship => Ok(Operator::IsNotDistinctFrom),
             BinaryOperator::Plus => Ok(Operator::Plus),
             BinaryOperator::Minus => Ok(Operator::Minus),
             BinaryOperator::Multiply => Ok(Operator::Multiply),
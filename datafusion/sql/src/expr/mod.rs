@@ -22,6 +22,7 @@ mod grouping_set;
 mod identifier;
 mod json_access;
 mod order_by;
+mod row_value;
 mod subquery;
 mod substring;
 mod unary_op;
@@ -68,12 +69,28 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 StackEntry::SQLExpr(sql_expr) => {
                     match *sql_expr {
                         SQLExpr::BinaryOp { left, op, right } => {
-                            // Note the order that we push the entries to the stack
-                            // is important. We want to visit the left node first.
                             let op = self.parse_sql_binary_op(op)?;
-                            stack.push(StackEntry::Operator(op));
-                            stack.push(StackEntry::SQLExpr(right));
-                            stack.push(StackEntry::SQLExpr(left));
+                            match (*left, *right) {
+                                // Row values such as `(a, b) < (1, 2)` are
+                                // compared element by element
+                                (SQLExpr::Tuple(left), SQLExpr::Tuple(right)) => {
+                                    let expr = self.parse_row_value_comparison(
+                                        left,
+                                        op,
+                                        right,
+                                        schema,
+                                        planner_context,
+                                    )?;
+                                    eval_stack.push(expr);
+                                }
+                                (left, right) => {
+                                    // Note the order that we push the entries to the stack
+                                    // is important. We want to visit the left node first.
+                                    stack.push(StackEntry::Operator(op));
+                                    stack.push(StackEntry::SQLExpr(Box::new(right)));
+                                    stack.push(StackEntry::SQLExpr(Box::new(left)));
+                                }
+                            }
                         }
                         SQLExpr::JsonAccess {
                             left,
@@ -358,7 +375,18 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 expr,
                 list,
                 negated,
-            } => self.sql_in_list_to_expr(*expr, list, negated, schema, planner_context),
+            } => match *expr {
+                SQLExpr::Tuple(row) => self.parse_row_value_in_list(
+                    row,
+                    list,
+                    negated,
+                    schema,
+                    planner_context,
+                ),
+                expr => {
+                    self.sql_in_list_to_expr(expr, list, negated, schema, planner_context)
+                }
+            },
 
             SQLExpr::Like {
                 negated,
@@ -500,13 +528,30 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 expr,
                 subquery,
                 negated,
-            } => {
-                self.parse_in_subquery(*expr, *subquery, negated, schema, planner_context)
-            }
+            } => match *expr {
+                SQLExpr::Tuple(row) => self.parse_row_value_in_subquery(
+                    row,
+                    *subquery,
+                    negated,
+                    schema,
+                    planner_context,
+                ),
+                expr => self.parse_in_subquery(
+                    expr,
+                    *subquery,
+                    negated,
+                    schema,
+                    planner_context,
+                ),
+            },
             SQLExpr::Subquery(subquery) => {
                 self.parse_scalar_subquery(*subquery, schema, planner_context)
             }
 
+            SQLExpr::ArraySubquery(subquery) => {
+                self.parse_array_subquery(*subquery, schema, planner_context)
+            }
+
             SQLExpr::ArrayAgg(array_agg) => {
                 self.parse_array_agg(array_agg, schema, planner_context)
             }
@@ -567,10 +612,6 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             None
         };
 
-        if let Some(limit) = limit {
-            return not_impl_err!("LIMIT not supported in ARRAY_AGG: {limit}");
-        }
-
         if within_group {
             return not_impl_err!("WITHIN GROUP not supported in ARRAY_AGG");
         }
@@ -580,9 +621,23 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 
         // next, aggregate built-ins
         let fun = AggregateFunction::ArrayAgg;
-        Ok(Expr::AggregateFunction(expr::AggregateFunction::new(
+        let array_agg = Expr::AggregateFunction(expr::AggregateFunction::new(
             fun, args, distinct, None, order_by,
-        )))
+        ));
+
+        // `ARRAY_AGG(x ORDER BY y LIMIT n)` keeps the first `n` elements
+        // of the (ordered) aggregated array
+        match limit {
+            Some(limit) => {
+                let limit =
+                    self.sql_expr_to_logical_expr(*limit, input_schema, planner_context)?;
+                Ok(Expr::ScalarFunction(ScalarFunction::new(
+                    BuiltinScalarFunction::ArraySlice,
+                    vec![array_agg, lit(1_i64), limit],
+                )))
+            }
+            None => Ok(array_agg),
+        }
     }
 
     fn sql_in_list_to_expr(
@@ -0,0 +1,203 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Planning of row value expressions such as `(a, b) < (1, 2)`,
+//! `(a, b) IN ((1, 2), (3, 4))` and `(a, b) IN (SELECT x, y FROM t)`
+
+use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{plan_err, DFSchema, DataFusionError, Result};
+use datafusion_expr::expr::Exists;
+use datafusion_expr::utils::{conjunction, disjunction};
+use datafusion_expr::{binary_expr, not, Expr, LogicalPlanBuilder, Operator, Subquery};
+use sqlparser::ast::Expr as SQLExpr;
+use sqlparser::ast::Query;
+use std::sync::Arc;
+
+impl<'a, S: ContextProvider> SqlToRel<'a, S> {
+    /// Plans a comparison of two row values as the equivalent comparison of
+    /// their elements, e.g. `(a, b) < (x, y)` becomes
+    /// `a < x OR (a = x AND b < y)`
+    pub(super) fn parse_row_value_comparison(
+        &self,
+        left: Vec<SQLExpr>,
+        op: Operator,
+        right: Vec<SQLExpr>,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        let left = self.row_value_to_exprs(left, schema, planner_context)?;
+        let right = self.row_value_to_exprs(right, schema, planner_context)?;
+        row_value_comparison(left, op, right)
+    }
+
+    /// Plans `(a, b) [NOT] IN ((x1, y1), (x2, y2), ...)` as a disjunction of
+    /// row value equalities
+    pub(super) fn parse_row_value_in_list(
+        &self,
+        row: Vec<SQLExpr>,
+        list: Vec<SQLExpr>,
+        negated: bool,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        let row = self.row_value_to_exprs(row, schema, planner_context)?;
+        let equalities = list
+            .into_iter()
+            .map(|item| {
+                let SQLExpr::Tuple(item) = item else {
+                    return plan_err!(
+                        "IN list of a row value should only contain row values, but found {item}"
+                    );
+                };
+                let item = self.row_value_to_exprs(item, schema, planner_context)?;
+                row_value_comparison(row.clone(), Operator::Eq, item)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let Some(expr) = disjunction(equalities) else {
+            return plan_err!("IN list should not be empty");
+        };
+        Ok(if negated { not(expr) } else { expr })
+    }
+
+    /// Plans `(a, b) [NOT] IN (SELECT x, y FROM ...)` as a correlated
+    /// `[NOT] EXISTS` subquery, which is then decorrelated into a semi (anti)
+    /// join on all of the row value elements.
+    ///
+    /// `NOT IN` must not match a row if comparing it with any subquery row is
+    /// unknown because of nulls, so each element equality is extended with
+    /// null checks of both sides.
+    pub(super) fn parse_row_value_in_subquery(
+        &self,
+        row: Vec<SQLExpr>,
+        subquery: Query,
+        negated: bool,
+        input_schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        let row = self.row_value_to_exprs(row, input_schema, planner_context)?;
+        let old_outer_query_schema =
+            planner_context.set_outer_query_schema(Some(input_schema.clone()));
+        let sub_plan = self.query_to_plan(subquery, planner_context)?;
+        planner_context.set_outer_query_schema(old_outer_query_schema);
+
+        let fields = sub_plan.schema().fields();
+        if fields.len() != row.len() {
+            return plan_err!(
+                "IN subquery should return {} columns to match the row value, but found {}",
+                row.len(),
+                fields.len()
+            );
+        }
+
+        let predicates = row
+            .into_iter()
+            .zip(fields.iter())
+            .map(|(expr, field)| {
+                let outer = to_outer_reference(expr, input_schema)?;
+                let inner = Expr::Column(field.qualified_column());
+                let eq = outer.clone().eq(inner.clone());
+                Ok(if negated {
+                    eq.or(outer.is_null()).or(inner.is_null())
+                } else {
+                    eq
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // the row value has at least one element
+        let predicate = conjunction(predicates).unwrap();
+
+        let sub_plan = LogicalPlanBuilder::from(sub_plan)
+            .filter(predicate)?
+            .build()?;
+        let outer_ref_columns = sub_plan.all_out_ref_exprs();
+        Ok(Expr::Exists(Exists {
+            subquery: Subquery {
+                subquery: Arc::new(sub_plan),
+                outer_ref_columns,
+            },
+            negated,
+        }))
+    }
+
+    fn row_value_to_exprs(
+        &self,
+        row: Vec<SQLExpr>,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Vec<Expr>> {
+        if row.is_empty() {
+            return plan_err!("Row value should have at least one element");
+        }
+        row.into_iter()
+            .map(|expr| self.sql_expr_to_logical_expr(expr, schema, planner_context))
+            .collect()
+    }
+}
+
+/// Expands the comparison of two row values into a lexicographic comparison
+/// of their elements
+fn row_value_comparison(left: Vec<Expr>, op: Operator, right: Vec<Expr>) -> Result<Expr> {
+    if left.len() != right.len() {
+        return plan_err!(
+            "Row values should have the same number of elements, but found {} and {}",
+            left.len(),
+            right.len()
+        );
+    }
+    let pairs = left.into_iter().zip(right);
+    let expr = match op {
+        Operator::Eq => conjunction(pairs.map(|(l, r)| l.eq(r))),
+        Operator::NotEq => disjunction(pairs.map(|(l, r)| l.not_eq(r))),
+        Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq => {
+            let strict = match op {
+                Operator::Lt | Operator::LtEq => Operator::Lt,
+                _ => Operator::Gt,
+            };
+            // Build from the last element, which is the only one compared
+            // with `op` itself, e.g. for `(a, b, c) <= (x, y, z)`:
+            // a < x OR (a = x AND (b < y OR (b = y AND c <= z)))
+            pairs.rev().fold(None, |acc, (l, r)| {
+                Some(match acc {
+                    None => binary_expr(l, op, r),
+                    Some(acc) => {
+                        binary_expr(l.clone(), strict, r.clone()).or(l.eq(r).and(acc))
+                    }
+                })
+            })
+        }
+        _ => return plan_err!("Unsupported operator {op} for row value comparison"),
+    };
+    expr.ok_or_else(|| {
+        DataFusionError::Plan("Row value should have at least one element".to_string())
+    })
+}
+
+/// Turns the columns of `expr`, planned against the outer query `schema`, into
+/// outer references so that `expr` can be used inside of a subquery
+fn to_outer_reference(expr: Expr, schema: &DFSchema) -> Result<Expr> {
+    expr.transform_up(&|expr| {
+        if let Expr::Column(column) = expr {
+            let data_type = schema.field_from_column(&column)?.data_type().clone();
+            Ok(Transformed::Yes(Expr::OuterReferenceColumn(
+                data_type, column,
+            )))
+        } else {
+            Ok(Transformed::No(expr))
+        }
+    })
+}
@@ -16,10 +16,11 @@
 // under the License.
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
-use datafusion_common::{DFSchema, Result};
-use datafusion_expr::expr::Exists;
-use datafusion_expr::expr::InSubquery;
-use datafusion_expr::{Expr, Subquery};
+use datafusion_common::{plan_err, DFSchema, DataFusionError, Result};
+use datafusion_expr::expr::{AggregateFunction, Exists, InSubquery};
+use datafusion_expr::{
+    aggregate_function, Expr, LogicalPlan, LogicalPlanBuilder, Subquery,
+};
 use sqlparser::ast::Expr as SQLExpr;
 use sqlparser::ast::Query;
 use std::sync::Arc;
@@ -86,4 +87,83 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             outer_ref_columns,
         }))
     }
+    pub(super) fn parse_array_subquery(
+        &self,
+        subquery: Query,
+        input_schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        let old_outer_query_schema =
+            planner_context.set_outer_query_schema(Some(input_schema.clone()));
+        let sub_plan = self.query_to_plan(subquery, planner_context)?;
+        let sub_plan = array_subquery_to_aggregate(sub_plan)?;
+        let outer_ref_columns = sub_plan.all_out_ref_exprs();
+        planner_context.set_outer_query_schema(old_outer_query_schema);
+        Ok(Expr::ScalarSubquery(Subquery {
+            subquery: Arc::new(sub_plan),
+            outer_ref_columns,
+        }))
+    }
+}
+
+/// Rewrites the plan of an `ARRAY(<subquery>)` expression into an aggregate
+/// collecting the single output column of the subquery with `ARRAY_AGG`, so
+/// that it can be planned like any other scalar subquery.
+///
+/// The order of the rows flowing into an aggregate is not guaranteed, so the
+/// `ORDER BY` of the subquery becomes the ordering of the `ARRAY_AGG`. The
+/// sort itself is only kept below the aggregate when a `LIMIT` depends on it.
+fn array_subquery_to_aggregate(plan: LogicalPlan) -> Result<LogicalPlan> {
+    let fields = plan.schema().fields();
+    if fields.len() != 1 {
+        return plan_err!(
+            "ARRAY subquery should return exactly one column, but found {}",
+            fields.len()
+        );
+    }
+    let column = Expr::Column(fields[0].qualified_column());
+
+    // SELECT ... ORDER BY ... LIMIT is planned as Limit(Projection(Sort(..))),
+    // where the projection and limit are optional
+    let (input, limit) = match &plan {
+        LogicalPlan::Limit(limit) => {
+            (limit.input.as_ref(), Some((limit.skip, limit.fetch)))
+        }
+        _ => (&plan, None),
+    };
+    let (input, value) = match input {
+        LogicalPlan::Projection(projection)
+            if matches!(projection.input.as_ref(), LogicalPlan::Sort(_)) =>
+        {
+            (
+                projection.input.as_ref(),
+                projection.expr[0].clone().unalias(),
+            )
+        }
+        _ => (input, column),
+    };
+
+    let (input, order_by) = match input {
+        LogicalPlan::Sort(sort) => {
+            let input = match limit {
+                Some((skip, fetch)) => LogicalPlanBuilder::from(input.clone())
+                    .limit(skip, fetch)?
+                    .build()?,
+                None => sort.input.as_ref().clone(),
+            };
+            (input, Some(sort.expr.clone()))
+        }
+        _ => (plan.clone(), None),
+    };
+
+    let array_agg = Expr::AggregateFunction(AggregateFunction::new(
+        aggregate_function::AggregateFunction::ArrayAgg,
+        vec![value],
+        false,
+        None,
+        order_by,
+    ));
+    LogicalPlanBuilder::from(input)
+        .aggregate(Vec::<Expr>::new(), vec![array_agg])?
+        .build()
 }
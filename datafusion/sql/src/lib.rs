@@ -36,6 +36,7 @@ mod relation;
 mod select;
 mod set_expr;
 mod statement;
+mod unnest;
 pub mod utils;
 mod values;
 
@@ -18,7 +18,7 @@
 //! [`DFParser`]: DataFusion SQL Parser based on [`sqlparser`]
 
 use datafusion_common::parsers::CompressionTypeVariant;
-use sqlparser::ast::{OrderByExpr, Query, Value};
+use sqlparser::ast::{AnalyzeFormat, OrderByExpr, Query, Value};
 use sqlparser::tokenizer::Word;
 use sqlparser::{
     ast::{
@@ -50,6 +50,7 @@ fn parse_file_type(s: &str) -> Result<String, ParserError> {
 pub struct ExplainStatement {
     pub analyze: bool,
     pub verbose: bool,
+    pub format: Option<AnalyzeFormat>,
     pub statement: Box<Statement>,
 }
 
@@ -58,6 +59,7 @@ impl fmt::Display for ExplainStatement {
         let Self {
             analyze,
             verbose,
+            format,
             statement,
         } = self;
 
@@ -68,6 +70,9 @@ impl fmt::Display for ExplainStatement {
         if *verbose {
             write!(f, "VERBOSE ")?;
         }
+        if let Some(format) = format {
+            write!(f, "FORMAT {format} ")?;
+        }
 
         write!(f, "{statement}")
     }
@@ -161,6 +166,7 @@ pub(crate) type LexOrdering = Vec<OrderByExpr>;
 /// [ PARTITIONED BY (<column list>) ]
 /// [ WITH ORDER (<ordered column list>)
 /// [ OPTIONS (<key_value_list>) ]
+/// [ SCHEMA <literal> ]
 /// LOCATION <literal>
 ///
 /// <column_definition> := (<column_name> <data_type>, ...)
@@ -199,17 +205,85 @@ pub struct CreateExternalTable {
     pub options: HashMap<String, String>,
     /// A table-level constraint
     pub constraints: Vec<TableConstraint>,
+    /// Path to a file holding the table schema
+    pub schema_location: Option<String>,
 }
 
 impl fmt::Display for CreateExternalTable {
+    /// Formats the statement as SQL that parses back to the same
+    /// [`CreateExternalTable`]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "CREATE EXTERNAL TABLE ")?;
-        if self.if_not_exists {
+        let Self {
+            name,
+            columns,
+            file_type,
+            has_header,
+            delimiter,
+            location,
+            table_partition_cols,
+            order_exprs,
+            if_not_exists,
+            file_compression_type,
+            unbounded,
+            options,
+            constraints,
+            schema_location,
+        } = self;
+
+        write!(f, "CREATE ")?;
+        if *unbounded {
+            write!(f, "UNBOUNDED ")?;
+        }
+        write!(f, "EXTERNAL TABLE ")?;
+        if *if_not_exists {
             write!(f, "IF NOT EXISTS ")?;
         }
-        write!(f, "{} ", self.name)?;
-        write!(f, "STORED AS {} ", self.file_type)?;
-        write!(f, "LOCATION {} ", self.location)
+        write!(f, "{name}")?;
+        if !columns.is_empty() || !constraints.is_empty() {
+            let elements: Vec<_> = columns
+                .iter()
+                .map(ToString::to_string)
+                .chain(constraints.iter().map(ToString::to_string))
+                .collect();
+            write!(f, " ({})", elements.join(", "))?;
+        }
+        write!(f, " STORED AS {file_type}")?;
+        if *has_header {
+            write!(f, " WITH HEADER ROW")?;
+        }
+        if *delimiter != ',' {
+            let delimiter = Value::SingleQuotedString(delimiter.to_string());
+            write!(f, " DELIMITER {delimiter}")?;
+        }
+        if file_compression_type.is_compressed() {
+            write!(f, " COMPRESSION TYPE {}", file_compression_type.to_string())?;
+        }
+        if !table_partition_cols.is_empty() {
+            write!(f, " PARTITIONED BY ({})", table_partition_cols.join(", "))?;
+        }
+        for ordering in order_exprs {
+            let exprs: Vec<_> = ordering.iter().map(ToString::to_string).collect();
+            write!(f, " WITH ORDER ({})", exprs.join(", "))?;
+        }
+        if !options.is_empty() {
+            // print them in sorted order
+            let mut options: Vec<_> = options
+                .iter()
+                .map(|(k, v)| {
+                    let k = Value::SingleQuotedString(k.clone());
+                    let v = Value::SingleQuotedString(v.clone());
+                    format!("{k} {v}")
+                })
+                .collect();
+            options.sort();
+            write!(f, " OPTIONS ({})", options.join(", "))?;
+        }
+        if let Some(schema_location) = schema_location {
+            let schema_location = Value::SingleQuotedString(schema_location.clone());
+            write!(f, " SCHEMA {schema_location}")?;
+        }
+        let location = Value::SingleQuotedString(location.clone());
+        write!(f, " LOCATION {location}")
     }
 }
 
@@ -434,12 +508,18 @@ impl<'a> DFParser<'a> {
     pub fn parse_explain(&mut self) -> Result<Statement, ParserError> {
         let analyze = self.parser.parse_keyword(Keyword::ANALYZE);
         let verbose = self.parser.parse_keyword(Keyword::VERBOSE);
+        let format = if self.parser.parse_keyword(Keyword::FORMAT) {
+            Some(self.parser.parse_analyze_format()?)
+        } else {
+            None
+        };
         let statement = self.parse_statement()?;
 
         Ok(Statement::Explain(ExplainStatement {
             statement: Box::new(statement),
             analyze,
             verbose,
+            format,
         }))
     }
 
@@ -621,6 +701,7 @@ impl<'a> DFParser<'a> {
             table_partition_cols: Option<Vec<String>>,
             order_exprs: Vec<LexOrdering>,
             options: Option<HashMap<String, String>>,
+            schema_location: Option<String>,
         }
         let mut builder = Builder::default();
 
@@ -642,6 +723,7 @@ impl<'a> DFParser<'a> {
                 Keyword::COMPRESSION,
                 Keyword::PARTITIONED,
                 Keyword::OPTIONS,
+                Keyword::SCHEMA,
             ]) {
                 match keyword {
                     Keyword::STORED => {
@@ -685,6 +767,11 @@ impl<'a> DFParser<'a> {
                         ensure_not_set(&builder.options, "OPTIONS")?;
                         builder.options = Some(self.parse_string_options()?);
                     }
+                    Keyword::SCHEMA => {
+                        ensure_not_set(&builder.schema_location, "SCHEMA")?;
+                        builder.schema_location =
+                            Some(self.parser.parse_literal_string()?);
+                    }
                     _ => {
                         unreachable!()
                     }
@@ -729,6 +816,7 @@ impl<'a> DFParser<'a> {
             unbounded,
             options: builder.options.unwrap_or(HashMap::new()),
             constraints,
+            schema_location: builder.schema_location,
         };
         Ok(Statement::CreateExternalTable(create))
     }
@@ -881,6 +969,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -900,6 +989,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -920,6 +1010,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -940,6 +1031,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -960,6 +1052,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -983,6 +1076,7 @@ mod tests {
                 unbounded: false,
                 options: HashMap::new(),
                 constraints: vec![],
+                schema_location: None,
             });
             expect_parse_ok(sql, expected)?;
         }
@@ -1011,10 +1105,31 @@ mod tests {
                 unbounded: false,
                 options: HashMap::new(),
                 constraints: vec![],
+                schema_location: None,
             });
             expect_parse_ok(sql, expected)?;
         }
 
+        // positive case: schema read from a file
+        let sql = "CREATE EXTERNAL TABLE t STORED AS CSV SCHEMA 'foo.schema.json' LOCATION 'foo.csv'";
+        let expected = Statement::CreateExternalTable(CreateExternalTable {
+            name: "t".into(),
+            columns: vec![],
+            file_type: "CSV".to_string(),
+            has_header: false,
+            delimiter: ',',
+            location: "foo.csv".into(),
+            table_partition_cols: vec![],
+            order_exprs: vec![],
+            if_not_exists: false,
+            file_compression_type: UNCOMPRESSED,
+            unbounded: false,
+            options: HashMap::new(),
+            constraints: vec![],
+            schema_location: Some("foo.schema.json".into()),
+        });
+        expect_parse_ok(sql, expected)?;
+
         // positive case: it is ok for parquet files not to have columns specified
         let sql = "CREATE EXTERNAL TABLE t STORED AS PARQUET LOCATION 'foo.parquet'";
         let expected = Statement::CreateExternalTable(CreateExternalTable {
@@ -1031,6 +1146,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -1050,6 +1166,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -1069,6 +1186,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -1089,6 +1207,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -1114,6 +1233,7 @@ mod tests {
             unbounded: false,
             options: HashMap::from([("k1".into(), "v1".into())]),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -1137,6 +1257,7 @@ mod tests {
                 ("k2".into(), "v2".into()),
             ]),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -1183,6 +1304,7 @@ mod tests {
                 unbounded: false,
                 options: HashMap::new(),
                 constraints: vec![],
+                schema_location: None,
             });
             expect_parse_ok(sql, expected)?;
         }
@@ -1224,6 +1346,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -1261,6 +1384,7 @@ mod tests {
             unbounded: false,
             options: HashMap::new(),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -1310,6 +1434,7 @@ mod tests {
                 ("TRUNCATE".into(), "NO".into()),
             ]),
             constraints: vec![],
+            schema_location: None,
         });
         expect_parse_ok(sql, expected)?;
 
@@ -1318,6 +1443,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn create_external_table_display() {
+        verified_stmt("CREATE EXTERNAL TABLE t STORED AS CSV LOCATION 'foo.csv'");
+        verified_stmt(
+            "CREATE UNBOUNDED EXTERNAL TABLE IF NOT EXISTS t (c1 INT, c2 VARCHAR, PRIMARY KEY (c1)) \
+             STORED AS CSV WITH HEADER ROW DELIMITER '|' COMPRESSION TYPE GZIP \
+             PARTITIONED BY (p1, p2) WITH ORDER (c1 ASC, c2 DESC NULLS LAST) \
+             OPTIONS ('k1' 'v1', 'k2' 'it''s') LOCATION 'foo.csv.gz'",
+        );
+        verified_stmt(
+            "CREATE EXTERNAL TABLE t STORED AS PARQUET SCHEMA 'foo.arrow' LOCATION 'foo.parquet'",
+        );
+        one_statement_parses_to(
+            "CREATE EXTERNAL TABLE t(c1 int) LOCATION 'foo.csv' STORED AS CSV DELIMITER ','",
+            "CREATE EXTERNAL TABLE t (c1 INT) STORED AS CSV LOCATION 'foo.csv'",
+        );
+    }
+
     #[test]
     fn copy_to_table_to_table() -> Result<(), ParserError> {
         // positive case
@@ -1351,6 +1494,7 @@ mod tests {
             let expected = Statement::Explain(ExplainStatement {
                 analyze,
                 verbose,
+                format: None,
                 statement: Box::new(expected_copy),
             });
             assert_eq!(verified_stmt(sql), expected);
@@ -1358,6 +1502,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn explain_analyze_format() -> Result<(), ParserError> {
+        let sql = "EXPLAIN ANALYZE FORMAT JSON COPY foo TO bar";
+        let expected = Statement::Explain(ExplainStatement {
+            analyze: true,
+            verbose: false,
+            format: Some(AnalyzeFormat::JSON),
+            statement: Box::new(Statement::CopyTo(CopyToStatement {
+                source: object_name("foo"),
+                target: "bar".to_string(),
+                options: vec![],
+            })),
+        });
+        assert_eq!(verified_stmt(sql), expected);
+        Ok(())
+    }
+
     #[test]
     fn copy_to_query_to_table() -> Result<(), ParserError> {
         let statement = verified_stmt("SELECT 1");
@@ -19,14 +19,17 @@ use std::sync::Arc;
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
 
+use datafusion_common::tree_node::{TreeNode, VisitRecursion};
 use datafusion_common::{
     not_impl_err, plan_err, sql_err, Constraints, DataFusionError, Result, ScalarValue,
+    TableReference,
 };
 use datafusion_expr::{
     CreateMemoryTable, DdlStatement, Distinct, Expr, LogicalPlan, LogicalPlanBuilder,
 };
 use sqlparser::ast::{
-    Expr as SQLExpr, Offset as SQLOffset, OrderByExpr, Query, SetExpr, Value,
+    Expr as SQLExpr, Ident, Offset as SQLOffset, OrderByExpr, Query, SetExpr,
+    SetOperator, SetQuantifier, Value,
 };
 
 use sqlparser::parser::ParserError::ParserError;
@@ -52,11 +55,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         let set_expr = query.body;
         if let Some(with) = query.with {
             // Process CTEs from top to bottom
-            // do not allow self-references
-            if with.recursive {
-                return not_impl_err!("Recursive CTEs are not supported");
-            }
-
+            // only recursive CTEs can reference themselves
             for cte in with.cte_tables {
                 // A `WITH` block can't use the same name more than once
                 let cte_name = self.normalizer.normalize(cte.alias.name.clone());
@@ -65,14 +64,31 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                         "WITH query name {cte_name:?} specified more than once"
                     )));
                 }
-                // create logical plan & pass backreferencing CTEs
-                // CTE expr don't need extend outer_query_schema
-                let logical_plan =
-                    self.query_to_plan(*cte.query, &mut planner_context.clone())?;
+                let recursive_plan = if with.recursive {
+                    self.recursive_cte_to_plan(
+                        &cte_name,
+                        &cte.alias.columns,
+                        &cte.query,
+                        planner_context,
+                    )?
+                } else {
+                    None
+                };
+                let logical_plan = match recursive_plan {
+                    Some(plan) => LogicalPlanBuilder::from(plan)
+                        .alias(cte_name.clone())?
+                        .build()?,
+                    None => {
+                        // create logical plan & pass backreferencing CTEs
+                        // CTE expr don't need extend outer_query_schema
+                        let logical_plan =
+                            self.query_to_plan(*cte.query, &mut planner_context.clone())?;
 
-                // Each `WITH` block can change the column names in the last
-                // projection (e.g. "WITH table(t1, t2) AS SELECT 1, 2").
-                let logical_plan = self.apply_table_alias(logical_plan, cte.alias)?;
+                        // Each `WITH` block can change the column names in the last
+                        // projection (e.g. "WITH table(t1, t2) AS SELECT 1, 2").
+                        self.apply_table_alias(logical_plan, cte.alias)?
+                    }
+                };
 
                 planner_context.insert_cte(cte_name, logical_plan);
             }
@@ -99,6 +115,87 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         Ok(plan)
     }
 
+    /// Generate a logical plan from the CTE `cte_name` of a `WITH RECURSIVE`
+    /// clause, whose columns are named `columns`, or `None` if it does not
+    /// reference itself
+    fn recursive_cte_to_plan(
+        &self,
+        cte_name: &str,
+        columns: &[Ident],
+        query: &Query,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Option<LogicalPlan>> {
+        // A recursive CTE is the union of a static term and of a recursive
+        // term referencing the CTE
+        let SetExpr::SetOperation {
+            op: SetOperator::Union,
+            left,
+            right,
+            set_quantifier,
+        } = query.body.as_ref()
+        else {
+            return Ok(None);
+        };
+        if query.with.is_some()
+            || !query.order_by.is_empty()
+            || query.limit.is_some()
+            || query.offset.is_some()
+            || query.fetch.is_some()
+        {
+            return Ok(None);
+        }
+        let is_distinct = match set_quantifier {
+            SetQuantifier::All => false,
+            SetQuantifier::Distinct | SetQuantifier::None => true,
+            SetQuantifier::ByName
+            | SetQuantifier::AllByName
+            | SetQuantifier::DistinctByName => {
+                return not_impl_err!("UNION BY NAME is not supported in recursive CTEs");
+            }
+        };
+
+        let static_plan =
+            self.set_expr_to_plan(left.as_ref().clone(), &mut planner_context.clone())?;
+        // The recursive term reads the columns of the CTE by their aliases
+        let static_plan = self.apply_expr_alias(static_plan, columns.to_vec())?;
+
+        // The recursive term reads the rows of the previous iteration from a
+        // work table with the schema of the static term
+        let work_table_source = self.context_provider.create_cte_work_table(
+            cte_name,
+            Arc::new(static_plan.schema().as_ref().into()),
+        )?;
+        let work_table = LogicalPlanBuilder::scan(
+            TableReference::bare(cte_name.to_owned()),
+            work_table_source,
+            None,
+        )?
+        .build()?;
+        let mut recursive_context = planner_context.clone();
+        recursive_context.insert_cte(cte_name, work_table);
+        let recursive_plan =
+            self.set_expr_to_plan(right.as_ref().clone(), &mut recursive_context)?;
+
+        let mut is_recursive = false;
+        recursive_plan.apply(&mut |plan| {
+            if let LogicalPlan::TableScan(scan) = plan {
+                if scan.table_name.table() == cte_name {
+                    is_recursive = true;
+                    return Ok(VisitRecursion::Stop);
+                }
+            }
+            Ok(VisitRecursion::Continue)
+        })?;
+        if !is_recursive {
+            return Ok(None);
+        }
+
+        LogicalPlanBuilder::from(static_plan)
+            .to_recursive_query(cte_name.to_owned(), recursive_plan, is_distinct)?
+            .build()
+            .map(Some)
+    }
+
     /// Wrap a plan in a limit
     fn limit(
         &self,
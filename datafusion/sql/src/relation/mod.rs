@@ -98,6 +98,21 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 self.plan_table_with_joins(*table_with_joins, planner_context)?,
                 alias,
             ),
+            TableFactor::UNNEST {
+                alias,
+                array_exprs,
+                with_offset,
+                with_offset_alias,
+            } => {
+                // the table alias does not name the offset column
+                return self.plan_unnest_relation(
+                    array_exprs,
+                    alias,
+                    with_offset,
+                    with_offset_alias,
+                    planner_context,
+                );
+            }
             // @todo Support TableFactory::TableFunction?
             _ => {
                 return not_impl_err!(
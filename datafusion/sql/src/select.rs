@@ -15,17 +15,18 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
 use crate::utils::{
-    check_columns_satisfy_exprs, extract_aliases, rebase_expr, resolve_aliases_to_exprs,
-    resolve_columns, resolve_positions_to_exprs,
+    check_columns_satisfy_exprs, extract_aliases, find_subquery_outer_columns,
+    rebase_expr, resolve_aliases_to_exprs, resolve_columns, resolve_positions_to_exprs,
 };
 
-use datafusion_common::Column;
+use arrow_schema::DataType;
 use datafusion_common::{not_impl_err, plan_err, DataFusionError, Result};
+use datafusion_common::{DFField, DFSchema, TableReference};
 use datafusion_expr::expr::Alias;
 use datafusion_expr::expr_rewriter::{
     normalize_col, normalize_col_with_schemas_and_ambiguity_check,
@@ -36,11 +37,12 @@ use datafusion_expr::utils::{
     find_aggregate_exprs, find_window_exprs,
 };
 use datafusion_expr::{
-    Expr, Filter, GroupingSet, LogicalPlan, LogicalPlanBuilder, Partitioning,
+    Expr, ExprSchemable, Filter, GroupingSet, LogicalPlan, LogicalPlanBuilder,
+    Partitioning, PlannedReplaceSelectItem, WildcardOptions,
 };
 use sqlparser::ast::{
-    Distinct, Expr as SQLExpr, GroupByExpr, ReplaceSelectItem, WildcardAdditionalOptions,
-    WindowType,
+    Distinct, Expr as SQLExpr, GroupByExpr, ObjectName, ReplaceSelectElement,
+    WildcardAdditionalOptions, WindowType,
 };
 use sqlparser::ast::{NamedWindowDefinition, Select, SelectItem, TableWithJoins};
 
@@ -79,10 +81,14 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         check_conflicting_windows(&select.named_window)?;
         match_window_definitions(&mut select.projection, &select.named_window)?;
 
+        // unnest the lists of an `unnest` call in the SELECT expressions
+        let (base_plan, projection) =
+            self.plan_select_unnest(base_plan, select.projection, planner_context)?;
+
         // process the SELECT expressions, with wildcards expanded.
         let select_exprs = self.prepare_select_exprs(
             &base_plan,
-            select.projection,
+            projection,
             empty_from,
             planner_context,
         )?;
@@ -338,6 +344,11 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
     /// Returns the `Expr`'s corresponding to a SQL query's SELECT expressions.
     ///
     /// Wildcards are expanded into the concrete list of columns.
+    ///
+    /// A SELECT expression may refer to the alias of an earlier SELECT
+    /// expression (a "lateral column alias"), as in `SELECT a + 1 AS x, x * 2`.
+    /// Such references are replaced by the aliased expression. Columns of the
+    /// input take precedence over aliases with the same name.
     fn prepare_select_exprs(
         &self,
         plan: &LogicalPlan,
@@ -345,14 +356,58 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         empty_from: bool,
         planner_context: &mut PlannerContext,
     ) -> Result<Vec<Expr>> {
-        projection
-            .into_iter()
-            .map(|expr| self.sql_select_to_rex(expr, plan, empty_from, planner_context))
-            .flat_map(|result| match result {
-                Ok(vec) => vec.into_iter().map(Ok).collect(),
-                Err(err) => vec![Err(err)],
+        let mut select_exprs = vec![];
+        let mut lateral_aliases = HashMap::new();
+        for item in projection {
+            let exprs = self.sql_select_to_rex(
+                item,
+                plan,
+                empty_from,
+                &lateral_aliases,
+                planner_context,
+            )?;
+            for expr in &exprs {
+                if let Expr::Alias(Alias { expr, name, .. }) = expr {
+                    if plan.schema().fields_with_unqualified_name(name).is_empty() {
+                        lateral_aliases.insert(name.clone(), *expr.clone());
+                    }
+                }
+            }
+            select_exprs.extend(exprs);
+        }
+        Ok(select_exprs)
+    }
+
+    /// Generate a relational expression from a SQL expression of the SELECT
+    /// list, resolving references to the `lateral_aliases` of earlier SELECT
+    /// expressions.
+    ///
+    /// Unqualified names are resolved in order against the columns of `plan`,
+    /// the lateral aliases, and finally the columns of any outer query.
+    fn sql_select_item_to_expr(
+        &self,
+        sql: SQLExpr,
+        plan: &LogicalPlan,
+        lateral_aliases: &HashMap<String, Expr>,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        if lateral_aliases.is_empty() {
+            return self.sql_to_expr(sql, plan.schema(), planner_context);
+        }
+        let alias_fields = lateral_aliases
+            .iter()
+            .map(|(name, expr)| {
+                Ok(DFField::new_unqualified(
+                    name,
+                    expr.get_type(plan.schema())?,
+                    expr.nullable(plan.schema())?,
+                ))
             })
-            .collect::<Result<Vec<Expr>>>()
+            .collect::<Result<Vec<_>>>()?;
+        let mut schema = plan.schema().as_ref().clone();
+        schema.merge(&DFSchema::new_with_metadata(alias_fields, HashMap::new())?);
+        let expr = self.sql_to_expr(sql, &schema, planner_context)?;
+        resolve_aliases_to_exprs(&expr, lateral_aliases)
     }
 
     /// Generate a relational expression from a select SQL expression
@@ -361,11 +416,17 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         sql: SelectItem,
         plan: &LogicalPlan,
         empty_from: bool,
+        lateral_aliases: &HashMap<String, Expr>,
         planner_context: &mut PlannerContext,
     ) -> Result<Vec<Expr>> {
         match sql {
             SelectItem::UnnamedExpr(expr) => {
-                let expr = self.sql_to_expr(expr, plan.schema(), planner_context)?;
+                let expr = self.sql_select_item_to_expr(
+                    expr,
+                    plan,
+                    lateral_aliases,
+                    planner_context,
+                )?;
                 let col = normalize_col_with_schemas_and_ambiguity_check(
                     expr,
                     &[&[plan.schema()]],
@@ -374,8 +435,12 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 Ok(vec![col])
             }
             SelectItem::ExprWithAlias { expr, alias } => {
-                let select_expr =
-                    self.sql_to_expr(expr, plan.schema(), planner_context)?;
+                let select_expr = self.sql_select_item_to_expr(
+                    expr,
+                    plan,
+                    lateral_aliases,
+                    planner_context,
+                )?;
                 let col = normalize_col_with_schemas_and_ambiguity_check(
                     select_expr,
                     &[&[plan.schema()]],
@@ -390,107 +455,136 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 Ok(vec![expr])
             }
             SelectItem::Wildcard(options) => {
-                Self::check_wildcard_options(&options)?;
-
                 if empty_from {
                     return plan_err!("SELECT * with no tables specified is not valid");
                 }
+                let options = self.plan_wildcard_options(
+                    plan,
+                    empty_from,
+                    planner_context,
+                    options,
+                )?;
                 // do not expand from outer schema
-                let expanded_exprs =
-                    expand_wildcard(plan.schema().as_ref(), plan, Some(&options))?;
-                // If there is a REPLACE statement, replace that column with the given
-                // replace expression. Column name remains the same.
-                if let Some(replace) = options.opt_replace {
-                    self.replace_columns(
-                        plan,
-                        empty_from,
-                        planner_context,
-                        expanded_exprs,
-                        replace,
-                    )
-                } else {
-                    Ok(expanded_exprs)
-                }
+                expand_wildcard(plan.schema().as_ref(), plan, Some(&options))
             }
             SelectItem::QualifiedWildcard(ref object_name, options) => {
-                Self::check_wildcard_options(&options)?;
+                if let Some(exprs) = self.plan_struct_wildcard(
+                    object_name,
+                    plan,
+                    planner_context,
+                    &options,
+                )? {
+                    return Ok(exprs);
+                }
                 let qualifier = format!("{object_name}");
+                let options = self.plan_wildcard_options(
+                    plan,
+                    empty_from,
+                    planner_context,
+                    options,
+                )?;
                 // do not expand from outer schema
-                let expanded_exprs = expand_qualified_wildcard(
+                expand_qualified_wildcard(
                     &qualifier,
                     plan.schema().as_ref(),
                     Some(&options),
-                )?;
-                // If there is a REPLACE statement, replace that column with the given
-                // replace expression. Column name remains the same.
-                if let Some(replace) = options.opt_replace {
-                    self.replace_columns(
-                        plan,
-                        empty_from,
-                        planner_context,
-                        expanded_exprs,
-                        replace,
-                    )
-                } else {
-                    Ok(expanded_exprs)
-                }
+                )
             }
         }
     }
 
-    fn check_wildcard_options(options: &WildcardAdditionalOptions) -> Result<()> {
-        let WildcardAdditionalOptions {
-            // opt_exclude is handled
-            opt_exclude: _opt_exclude,
-            opt_except: _opt_except,
-            opt_rename,
-            opt_replace: _opt_replace,
-        } = options;
+    /// Expands `s.*` into one column per field when `s` refers to a struct
+    /// typed expression instead of a relation, e.g. `SELECT s.* FROM t`.
+    ///
+    /// Each field is accessed from the struct, so a struct computed by a
+    /// function in a subquery, as in
+    /// `SELECT u.* FROM (SELECT parse_ua(ua) AS u FROM logs)`,
+    /// is evaluated once rather than once per field.
+    ///
+    /// Returns `None` if `object_name` does not refer to a struct.
+    fn plan_struct_wildcard(
+        &self,
+        object_name: &ObjectName,
+        plan: &LogicalPlan,
+        planner_context: &mut PlannerContext,
+        options: &WildcardAdditionalOptions,
+    ) -> Result<Option<Vec<Expr>>> {
+        let schema = plan.schema();
+        // relations take precedence over struct columns of the same name
+        let qualifier = object_name.to_string();
+        let qualifier = TableReference::from(qualifier.as_str());
+        if !schema.fields_with_qualified(&qualifier).is_empty() {
+            return Ok(None);
+        }
 
-        if opt_rename.is_some() {
-            Err(DataFusionError::NotImplemented(
-                "wildcard * with RENAME not supported ".to_string(),
-            ))
-        } else {
-            Ok(())
+        let sql_expr = match object_name.0.as_slice() {
+            [ident] => SQLExpr::Identifier(ident.clone()),
+            idents => SQLExpr::CompoundIdentifier(idents.to_vec()),
+        };
+        let Ok(expr) = self.sql_to_expr(sql_expr, schema, planner_context) else {
+            return Ok(None);
+        };
+        let Ok(DataType::Struct(fields)) = expr.get_type(schema) else {
+            return Ok(None);
+        };
+        if options != &WildcardAdditionalOptions::default() {
+            return not_impl_err!(
+                "Wildcard options are not supported when expanding struct {object_name}"
+            );
         }
+
+        Ok(Some(
+            fields
+                .iter()
+                .map(|field| expr.clone().field(field.name()).alias(field.name()))
+                .collect(),
+        ))
     }
 
-    /// If there is a REPLACE statement in the projected expression in the form of
-    /// "REPLACE (some_column_within_an_expr AS some_column)", this function replaces
-    /// that column with the given replace expression. Column name remains the same.
-    /// Multiple REPLACEs are also possible with comma separations.
-    fn replace_columns(
+    /// Plans the additional options of a wildcard, such as
+    /// `SELECT * EXCLUDE (c1) RENAME (c3 AS x) REPLACE (c2 + 1 AS c2)`.
+    /// The expressions of a `REPLACE` are planned against `plan`.
+    fn plan_wildcard_options(
         &self,
         plan: &LogicalPlan,
         empty_from: bool,
         planner_context: &mut PlannerContext,
-        mut exprs: Vec<Expr>,
-        replace: ReplaceSelectItem,
-    ) -> Result<Vec<Expr>> {
-        for expr in exprs.iter_mut() {
-            if let Expr::Column(Column { name, .. }) = expr {
-                if let Some(item) = replace
-                    .items
-                    .iter()
-                    .find(|item| item.column_name.value == *name)
-                {
-                    let new_expr = self.sql_select_to_rex(
+        options: WildcardAdditionalOptions,
+    ) -> Result<WildcardOptions> {
+        let WildcardAdditionalOptions {
+            opt_exclude,
+            opt_except,
+            opt_rename,
+            opt_replace,
+        } = options;
+        let replace = opt_replace
+            .map(|replace| {
+                let mut items = vec![];
+                let mut planned_expressions = vec![];
+                for item in replace.items {
+                    let expr = self.sql_select_to_rex(
                         SelectItem::UnnamedExpr(item.expr.clone()),
                         plan,
                         empty_from,
+                        &HashMap::new(),
                         planner_context,
                     )?[0]
                         .clone();
-                    *expr = Expr::Alias(Alias {
-                        expr: Box::new(new_expr),
-                        relation: None,
-                        name: name.clone(),
-                    });
+                    items.push(ReplaceSelectElement::clone(&item));
+                    planned_expressions.push(expr);
                 }
-            }
-        }
-        Ok(exprs)
+                Ok(PlannedReplaceSelectItem {
+                    items,
+                    planned_expressions,
+                }) as Result<_>
+            })
+            .transpose()?;
+        Ok(WildcardOptions {
+            exclude: opt_exclude,
+            except: opt_except,
+            replace,
+            rename: opt_rename,
+        })
     }
 
     /// Wrap a plan in a projection
@@ -606,6 +700,13 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 &[having_expr_post_aggr.clone()],
                 "HAVING clause references non-aggregate values",
             )?;
+            // subqueries in the HAVING clause are evaluated against the
+            // output of the aggregate, so their outer references must be too
+            check_columns_satisfy_exprs(
+                &column_exprs_post_aggr,
+                &find_subquery_outer_columns(&having_expr_post_aggr),
+                "HAVING clause subquery references non-aggregate values",
+            )?;
 
             Some(having_expr_post_aggr)
         } else {
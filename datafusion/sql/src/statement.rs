@@ -29,7 +29,6 @@ use crate::utils::normalize_ident;
 
 use arrow_schema::DataType;
 use datafusion_common::file_options::StatementOptions;
-use datafusion_common::parsers::CompressionTypeVariant;
 use datafusion_common::{
     not_impl_err, plan_datafusion_err, plan_err, schema_err, unqualified_field_not_found,
     Column, Constraints, DFField, DFSchema, DFSchemaRef, DataFusionError,
@@ -42,7 +41,7 @@ use datafusion_expr::logical_plan::builder::project;
 use datafusion_expr::logical_plan::DdlStatement;
 use datafusion_expr::utils::expr_to_columns;
 use datafusion_expr::{
-    cast, col, Analyze, CreateCatalog, CreateCatalogSchema,
+    cast, col, Analyze, AnalyzeFormat, CreateCatalog, CreateCatalogSchema,
     CreateExternalTable as PlanCreateExternalTable, CreateMemoryTable, CreateView,
     DescribeTable, DmlStatement, DropCatalogSchema, DropTable, DropView, EmptyRelation,
     Explain, ExprSchemable, Filter, LogicalPlan, LogicalPlanBuilder, PlanType, Prepare,
@@ -141,8 +140,9 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             DFStatement::Explain(ExplainStatement {
                 verbose,
                 analyze,
+                format,
                 statement,
-            }) => self.explain_to_plan(verbose, analyze, *statement),
+            }) => self.explain_to_plan(verbose, analyze, format, *statement),
         }
     }
 
@@ -174,16 +174,27 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 describe_alias: true, // only parse 'DESCRIBE table_name' and not 'EXPLAIN table_name'
                 table_name,
             } => self.describe_table_to_plan(table_name),
+            // 'DESCRIBE <query>' plans the query to describe its output schema
+            Statement::Explain {
+                verbose: false,
+                statement,
+                analyze: false,
+                describe_alias: true,
+                ..
+            } => self.describe_query_to_plan(*statement, planner_context),
             Statement::Explain {
                 verbose,
                 statement,
                 analyze,
-                format: _,
+                format,
                 describe_alias: _,
                 ..
-            } => {
-                self.explain_to_plan(verbose, analyze, DFStatement::Statement(statement))
-            }
+            } => self.explain_to_plan(
+                verbose,
+                analyze,
+                format,
+                DFStatement::Statement(statement),
+            ),
             Statement::Query(query) => self.query_to_plan(*query, planner_context),
             Statement::ShowVariable { variable } => self.show_variable_to_plan(&variable),
             Statement::SetVariable {
@@ -654,6 +665,24 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         }))
     }
 
+    fn describe_query_to_plan(
+        &self,
+        statement: Statement,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let plan =
+            self.sql_statement_to_plan_with_context_impl(statement, planner_context)?;
+
+        let schema = Arc::new(plan.schema().as_ref().into());
+
+        let output_schema = DFSchema::try_from(LogicalPlan::describe_schema()).unwrap();
+
+        Ok(LogicalPlan::DescribeTable(DescribeTable {
+            schema,
+            output_schema: Arc::new(output_schema),
+        }))
+    }
+
     fn copy_to_plan(&self, statement: CopyToStatement) -> Result<LogicalPlan> {
         // determine if source is table or query and handle accordingly
         let copy_source = statement.source;
@@ -716,18 +745,9 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         let mut all_results = vec![];
         for expr in order_exprs {
             // Convert each OrderByExpr to a SortExpr:
+            // The columns of the SortExprs are checked by the LogicalPlanBuilder
             let expr_vec =
                 self.order_by_to_sort_expr(&expr, schema, planner_context, true)?;
-            // Verify that columns of all SortExprs exist in the schema:
-            for expr in expr_vec.iter() {
-                for column in expr.to_columns()?.iter() {
-                    if !schema.has_column(column) {
-                        // Return an error if any column is not in the schema:
-                        return plan_err!("Column {column} is not in schema");
-                    }
-                }
-            }
-            // If all SortExprs are valid, return them as an expression vector
             all_results.push(expr_vec)
         }
         Ok(all_results)
@@ -753,21 +773,20 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             unbounded,
             options,
             constraints,
+            schema_location,
         } = statement;
 
+        if schema_location.is_some() && !columns.is_empty() {
+            return plan_err!(
+                "CREATE EXTERNAL TABLE cannot have both column definitions and a SCHEMA clause"
+            );
+        }
+
         // Merge inline constraints and existing constraints
         let mut all_constraints = constraints;
         let inline_constraints = calc_inline_constraints_from_columns(&columns);
         all_constraints.extend(inline_constraints);
 
-        if (file_type == "PARQUET" || file_type == "AVRO" || file_type == "ARROW")
-            && file_compression_type != CompressionTypeVariant::UNCOMPRESSED
-        {
-            plan_err!(
-                "File compression type cannot be set for PARQUET, AVRO, or ARROW files."
-            )?;
-        }
-
         let mut planner_context = PlannerContext::new();
 
         let column_defaults = self
@@ -785,25 +804,25 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         let name = OwnedTableReference::bare(name);
         let constraints =
             Constraints::new_from_table_constraints(&all_constraints, &df_schema)?;
-        Ok(LogicalPlan::Ddl(DdlStatement::CreateExternalTable(
-            PlanCreateExternalTable {
-                schema: df_schema,
-                name,
-                location,
-                file_type,
-                has_header,
-                delimiter,
-                table_partition_cols,
-                if_not_exists,
-                definition,
-                file_compression_type,
-                order_exprs: ordered_exprs,
-                unbounded,
-                options,
-                constraints,
-                column_defaults,
-            },
-        )))
+        LogicalPlanBuilder::create_external_table(PlanCreateExternalTable {
+            schema: df_schema,
+            name,
+            location,
+            file_type,
+            has_header,
+            delimiter,
+            table_partition_cols,
+            if_not_exists,
+            definition,
+            file_compression_type,
+            order_exprs: ordered_exprs,
+            unbounded,
+            options,
+            constraints,
+            column_defaults,
+            schema_location,
+        })?
+        .build()
     }
 
     /// Generate a plan for EXPLAIN ... that will print out a plan
@@ -814,8 +833,19 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         &self,
         verbose: bool,
         analyze: bool,
+        format: Option<ast::AnalyzeFormat>,
         statement: DFStatement,
     ) -> Result<LogicalPlan> {
+        let format = match format {
+            None | Some(ast::AnalyzeFormat::TEXT) => AnalyzeFormat::Text,
+            Some(ast::AnalyzeFormat::JSON) if analyze => AnalyzeFormat::Json,
+            Some(ast::AnalyzeFormat::JSON) => {
+                return not_impl_err!(
+                    "EXPLAIN FORMAT JSON is only supported with ANALYZE"
+                );
+            }
+            Some(format) => return not_impl_err!("Unsupported EXPLAIN format {format}"),
+        };
         let plan = self.statement_to_plan(statement)?;
         if matches!(plan, LogicalPlan::Explain(_)) {
             return plan_err!("Nested EXPLAINs are not supported");
@@ -827,6 +857,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         if analyze {
             Ok(LogicalPlan::Analyze(Analyze {
                 verbose,
+                format,
                 input: plan,
                 schema,
             }))
@@ -887,10 +918,6 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         variable: &ObjectName,
         value: Vec<sqlparser::ast::Expr>,
     ) -> Result<LogicalPlan> {
-        if local {
-            return not_impl_err!("LOCAL is not supported");
-        }
-
         if hivevar {
             return not_impl_err!("HIVEVAR is not supported");
         }
@@ -939,6 +966,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         let statement = PlanStatement::SetVariable(SetVariable {
             variable: variable_lower,
             value: value_string,
+            local,
             schema: DFSchemaRef::new(DFSchema::empty()),
         });
 
@@ -0,0 +1,218 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+
+use arrow_schema::DataType;
+use datafusion_common::{
+    not_impl_err, plan_err, Column, DFSchema, DataFusionError, Result, UnnestOptions,
+};
+use datafusion_expr::{lit, Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder};
+use sqlparser::ast::{
+    Expr as SQLExpr, Function, FunctionArg, FunctionArgExpr, Ident, SelectItem,
+    TableAlias,
+};
+
+/// Name of the column of `UNNEST` in a `FROM` clause, unless aliased
+const UNNEST_COLUMN: &str = "unnest";
+
+impl<'a, S: ContextProvider> SqlToRel<'a, S> {
+    /// Plans `UNNEST(<list>) [AS <alias>] [WITH OFFSET [AS <name>]]` in a
+    /// `FROM` clause, producing a row per value of `<list>`, and with
+    /// `WITH OFFSET` a column with the 0-based position of the value in the
+    /// list. The column aliases of `<alias>` do not cover that column.
+    ///
+    /// The values of a struct are unnested into a column per field instead.
+    pub(crate) fn plan_unnest_relation(
+        &self,
+        array_exprs: Vec<SQLExpr>,
+        alias: Option<TableAlias>,
+        with_offset: bool,
+        with_offset_alias: Option<Ident>,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let [array_expr] = <[SQLExpr; 1]>::try_from(array_exprs).map_err(|_| {
+            DataFusionError::NotImplemented(
+                "UNNEST of more than one list is not supported".to_string(),
+            )
+        })?;
+        let schema = DFSchema::empty();
+        let expr = self.sql_expr_to_logical_expr(array_expr, &schema, planner_context)?;
+        check_unnest_type(&expr.get_type(&schema)?)?;
+
+        let mut options = UnnestOptions::new().with_preserve_nulls(false);
+        let offset = with_offset.then(|| {
+            with_offset_alias
+                .map(|alias| self.normalizer.normalize(alias))
+                .unwrap_or_else(|| "offset".to_string())
+        });
+        if let Some(offset) = &offset {
+            options = options.with_ordinality(offset);
+        }
+        let plan = LogicalPlanBuilder::empty(true)
+            .project(vec![expr.alias(UNNEST_COLUMN)])?
+            .unnest_column_with_options(UNNEST_COLUMN, options)?
+            .build()?;
+        if offset.is_none() {
+            return match alias {
+                Some(alias) => self.apply_table_alias(plan, alias),
+                None => Ok(plan),
+            };
+        }
+
+        // The ordinality column, counting from 1, comes last
+        let (ordinality, values) = plan.schema().fields().split_last().unwrap();
+        let (name, columns) = match alias {
+            Some(alias) => (Some(alias.name), alias.columns),
+            None => (None, vec![]),
+        };
+        let mut exprs = values
+            .iter()
+            .map(|f| Expr::Column(f.qualified_column()))
+            .collect::<Vec<_>>();
+        if !columns.is_empty() {
+            if columns.len() != exprs.len() {
+                return plan_err!(
+                    "Source table contains {} columns but only {} names given as column alias",
+                    exprs.len(),
+                    columns.len()
+                );
+            }
+            exprs = exprs
+                .into_iter()
+                .zip(columns)
+                .map(|(expr, column)| expr.alias(self.normalizer.normalize(column)))
+                .collect();
+        }
+        exprs.push(
+            (Expr::Column(ordinality.qualified_column()) - lit(1_i64))
+                .alias(ordinality.name()),
+        );
+        let builder = LogicalPlanBuilder::from(plan).project(exprs)?;
+        match name {
+            Some(name) => builder.alias(self.normalizer.normalize(name))?.build(),
+            None => builder.build(),
+        }
+    }
+
+    /// Plans the `unnest(<list>)` call of a select list, such as
+    /// `SELECT id, unnest(list) FROM t`, which produces a row per value of
+    /// `list`, repeating the other columns of its row.
+    ///
+    /// Returns `plan` with the lists unnested, and `projection` referring
+    /// to the unnested column instead of the call.
+    pub(crate) fn plan_select_unnest(
+        &self,
+        plan: LogicalPlan,
+        mut projection: Vec<SelectItem>,
+        planner_context: &mut PlannerContext,
+    ) -> Result<(LogicalPlan, Vec<SelectItem>)> {
+        let mut unnest = None;
+        for (idx, item) in projection.iter().enumerate() {
+            let (expr, alias) = match item {
+                SelectItem::UnnamedExpr(expr) => (expr, None),
+                SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias)),
+                _ => continue,
+            };
+            let Some(arg) = unnest_argument(expr)? else {
+                continue;
+            };
+            if unnest.is_some() {
+                return not_impl_err!(
+                    "More than one unnest() in a select list is not supported"
+                );
+            }
+            unnest = Some((idx, arg.clone(), alias.cloned()));
+        }
+        let Some((idx, arg, alias)) = unnest else {
+            return Ok((plan, projection));
+        };
+
+        let arg = self.sql_expr_to_logical_expr(arg, plan.schema(), planner_context)?;
+        let name = match alias {
+            Some(alias) => self.normalizer.normalize(alias),
+            None => format!("unnest({})", arg.display_name()?),
+        };
+        // The columns replacing the call
+        let columns = match arg.get_type(plan.schema())? {
+            DataType::Struct(fields) => {
+                fields.iter().map(|f| f.name().to_string()).collect()
+            }
+            data_type => {
+                check_unnest_type(&data_type)?;
+                vec![name.clone()]
+            }
+        };
+
+        let mut exprs = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| Expr::Column(f.qualified_column()))
+            .collect::<Vec<_>>();
+        exprs.push(arg.alias(&name));
+        let plan = LogicalPlanBuilder::from(plan)
+            .project(exprs)?
+            .unnest_column_with_options(
+                Column::from_name(name),
+                UnnestOptions::new().with_preserve_nulls(false),
+            )?
+            .build()?;
+
+        let items = columns.into_iter().map(|column| {
+            let ident = Ident::with_quote('"', column);
+            SelectItem::ExprWithAlias {
+                expr: SQLExpr::Identifier(ident.clone()),
+                alias: ident,
+            }
+        });
+        projection.splice(idx..=idx, items);
+        Ok((plan, projection))
+    }
+}
+
+/// Returns the argument of `expr` if it is a call to `unnest`
+fn unnest_argument(expr: &SQLExpr) -> Result<Option<&SQLExpr>> {
+    let SQLExpr::Function(Function {
+        name, args, over, ..
+    }) = expr
+    else {
+        return Ok(None);
+    };
+    if over.is_some()
+        || name.0.len() != 1
+        || !name.0[0].value.eq_ignore_ascii_case("unnest")
+    {
+        return Ok(None);
+    }
+    match args.as_slice() {
+        [FunctionArg::Unnamed(FunctionArgExpr::Expr(arg))] => Ok(Some(arg)),
+        _ => plan_err!("unnest() takes a single list or struct argument"),
+    }
+}
+
+fn check_unnest_type(data_type: &DataType) -> Result<()> {
+    match data_type {
+        DataType::List(_)
+        | DataType::LargeList(_)
+        | DataType::FixedSizeList(_, _)
+        | DataType::Struct(_) => Ok(()),
+        other => {
+            plan_err!("unnest() can only be applied to a list or a struct, not {other}")
+        }
+    }
+}
@@ -25,9 +25,9 @@ use sqlparser::ast::Ident;
 
 use datafusion_common::{exec_err, internal_err, plan_err};
 use datafusion_common::{DataFusionError, Result, ScalarValue};
-use datafusion_expr::expr::{Alias, GroupingSet, WindowFunction};
+use datafusion_expr::expr::{Alias, Exists, GroupingSet, InSubquery, WindowFunction};
 use datafusion_expr::expr_vec_fmt;
-use datafusion_expr::utils::{expr_as_column_expr, find_column_exprs};
+use datafusion_expr::utils::{expr_as_column_expr, find_column_exprs, inspect_expr_pre};
 use datafusion_expr::{Expr, LogicalPlan};
 use std::collections::HashMap;
 
@@ -128,6 +128,29 @@ fn check_column_satisfies_expr(
     Ok(())
 }
 
+/// Returns the outer columns referenced by the subqueries in `expr`, as
+/// [`Expr::Column`]s.
+pub(crate) fn find_subquery_outer_columns(expr: &Expr) -> Vec<Expr> {
+    let mut columns = vec![];
+    inspect_expr_pre(expr, |expr| {
+        let subquery = match expr {
+            Expr::ScalarSubquery(subquery)
+            | Expr::Exists(Exists { subquery, .. })
+            | Expr::InSubquery(InSubquery { subquery, .. }) => subquery,
+            _ => return Ok(()),
+        };
+        for outer_ref in &subquery.outer_ref_columns {
+            if let Expr::OuterReferenceColumn(_, column) = outer_ref {
+                columns.push(Expr::Column(column.clone()));
+            }
+        }
+        Ok(()) as Result<()>
+    })
+    // As the closure always returns Ok, this "can't" error
+    .expect("Unexpected error");
+    columns
+}
+
 /// Returns mapping of each alias (`String`) to the expression (`Expr`) it is
 /// aliasing.
 pub(crate) fn extract_aliases(exprs: &[Expr]) -> HashMap<String, Expr> {
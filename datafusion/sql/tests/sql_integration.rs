@@ -587,6 +587,39 @@ fn select_filter_cannot_use_alias() {
     assert_field_not_found(err, "x");
 }
 
+#[test]
+fn select_lateral_column_alias() {
+    let sql = "SELECT age + 1 AS x, x * 2 AS y, y > 10 AS z FROM person";
+    let expected = "Projection: person.age + Int64(1) AS x, (person.age + Int64(1)) * Int64(2) AS y, (person.age + Int64(1)) * Int64(2) > Int64(10) AS z\
+                        \n  TableScan: person";
+    quick_test(sql, expected);
+}
+
+#[test]
+fn select_lateral_column_alias_prefers_input_column() {
+    let sql = "SELECT age * 2 AS age, age + 1 AS y FROM person";
+    let expected = "Projection: person.age * Int64(2) AS age, person.age + Int64(1) AS y\
+                        \n  TableScan: person";
+    quick_test(sql, expected);
+}
+
+#[test]
+fn select_lateral_column_alias_must_be_defined_earlier() {
+    let sql = "SELECT x * 2 AS y, age + 1 AS x FROM person";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_field_not_found(err, "x");
+}
+
+#[test]
+fn select_aggregate_with_group_by_and_having_using_expression_alias() {
+    let sql = "SELECT age + 1 AS s, COUNT(*) FROM person GROUP BY s HAVING s > 10";
+    let expected = "Projection: person.age + Int64(1) AS s, COUNT(*)\
+                        \n  Filter: person.age + Int64(1) > Int64(10)\
+                        \n    Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[COUNT(*)]]\
+                        \n      TableScan: person";
+    quick_test(sql, expected);
+}
+
 #[test]
 fn select_neg_filter() {
     let sql = "SELECT id, first_name, last_name \
@@ -1396,9 +1429,50 @@ fn recursive_ctes() {
               select n + 1 FROM numbers WHERE N < 10
         )
         select * from numbers;";
+    let expected = "Projection: numbers.n\
+        \n  SubqueryAlias: numbers\
+        \n    RecursiveQuery: name=numbers, is_distinct=false\
+        \n      Projection: Int64(1) AS n\
+        \n        EmptyRelation\
+        \n      Projection: numbers.n + Int64(1)\
+        \n        Filter: numbers.n < Int64(10)\
+        \n          TableScan: numbers";
+    quick_test(sql, expected);
+}
+
+#[test]
+fn recursive_ctes_column_alias() {
+    let sql = "
+        WITH RECURSIVE numbers(n) AS (
+              select 1 as a
+            UNION
+              select n + 1 FROM numbers WHERE n < 10
+        )
+        select n from numbers;";
+    let expected = "Projection: numbers.n\
+        \n  SubqueryAlias: numbers\
+        \n    RecursiveQuery: name=numbers, is_distinct=true\
+        \n      Projection: a AS n\
+        \n        Projection: Int64(1) AS a\
+        \n          EmptyRelation\
+        \n      Projection: numbers.n + Int64(1)\
+        \n        Filter: numbers.n < Int64(10)\
+        \n          TableScan: numbers";
+    quick_test(sql, expected);
+}
+
+#[test]
+fn recursive_ctes_column_count_mismatch() {
+    let sql = "
+        WITH RECURSIVE numbers AS (
+              select 1 as n
+            UNION ALL
+              select n + 1, n FROM numbers
+        )
+        select * from numbers;";
     let err = logical_plan(sql).expect_err("query should have failed");
     assert_eq!(
-        "This feature is not implemented: Recursive CTEs are not supported",
+        "Error during planning: Non-recursive term and recursive term must have the same number of columns (1 != 2)",
         err.strip_backtrace()
     );
 }
@@ -2786,6 +2860,14 @@ impl ContextProvider for MockContextProvider {
         }
     }
 
+    fn create_cte_work_table(
+        &self,
+        _name: &str,
+        schema: SchemaRef,
+    ) -> Result<Arc<dyn TableSource>> {
+        Ok(Arc::new(EmptyTable::new(schema)))
+    }
+
     fn get_function_meta(&self, _name: &str) -> Option<Arc<ScalarUDF>> {
         None
     }
@@ -2915,6 +2997,73 @@ fn exists_subquery() {
     quick_test(sql, expected);
 }
 
+#[test]
+fn row_value_in_subquery() {
+    let sql = "SELECT id FROM person p WHERE (p.first_name, p.last_name) IN \
+            (SELECT first_name, last_name FROM person)";
+
+    let expected = "Projection: p.id\
+        \n  Filter: EXISTS (<subquery>)\
+        \n    Subquery:\
+        \n      Filter: outer_ref(p.first_name) = person.first_name AND outer_ref(p.last_name) = person.last_name\
+        \n        Projection: person.first_name, person.last_name\
+        \n          TableScan: person\
+        \n    SubqueryAlias: p\
+        \n      TableScan: person";
+    quick_test(sql, expected);
+}
+
+#[test]
+fn row_value_not_in_subquery() {
+    let sql = "SELECT id FROM person p WHERE (p.first_name, p.last_name) NOT IN \
+            (SELECT first_name, last_name FROM person)";
+
+    let expected = "Projection: p.id\
+        \n  Filter: NOT EXISTS (<subquery>)\
+        \n    Subquery:\
+        \n      Filter: (outer_ref(p.first_name) = person.first_name OR outer_ref(p.first_name) IS NULL OR person.first_name IS NULL) AND (outer_ref(p.last_name) = person.last_name OR outer_ref(p.last_name) IS NULL OR person.last_name IS NULL)\
+        \n        Projection: person.first_name, person.last_name\
+        \n          TableScan: person\
+        \n    SubqueryAlias: p\
+        \n      TableScan: person";
+    quick_test(sql, expected);
+}
+
+#[test]
+fn row_value_in_subquery_column_count_mismatch() {
+    let sql = "SELECT id FROM person WHERE (first_name, last_name) IN \
+            (SELECT first_name FROM person)";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_eq!(
+        "Error during planning: IN subquery should return 2 columns to match the row value, but found 1",
+        err.strip_backtrace()
+    );
+}
+
+#[test]
+fn row_value_comparison() {
+    let sql = "SELECT id FROM person WHERE (age, id) <= (21, 100)";
+    let expected = "Projection: person.id\
+        \n  Filter: person.age < Int64(21) OR person.age = Int64(21) AND person.id <= Int64(100)\
+        \n    TableScan: person";
+    quick_test(sql, expected);
+
+    let sql = "SELECT id FROM person WHERE (age, id) <> (21, 100)";
+    let expected = "Projection: person.id\
+        \n  Filter: person.age != Int64(21) OR person.id != Int64(100)\
+        \n    TableScan: person";
+    quick_test(sql, expected);
+}
+
+#[test]
+fn row_value_in_list() {
+    let sql = "SELECT id FROM person WHERE (age, id) IN ((21, 1), (22, 2))";
+    let expected = "Projection: person.id\
+        \n  Filter: person.age = Int64(21) AND person.id = Int64(1) OR person.age = Int64(22) AND person.id = Int64(2)\
+        \n    TableScan: person";
+    quick_test(sql, expected);
+}
+
 #[test]
 fn exists_subquery_schema_outer_schema_overlap() {
     // both the outer query and the schema select from unaliased "person"